@@ -0,0 +1,131 @@
+//! The `forge cache` command.
+//!
+//! Inspects and clears the per-file discovery cache `forge survey` uses to
+//! avoid parsing content it has parsed before:
+//! - `stats`: Number and size of cached files, per parser
+//! - `clear`: Delete every cached file
+
+use crate::config::ForgeConfig;
+use crate::output;
+use forge_survey::{CacheStats, DiscoveryCache};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// What to do with the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheAction {
+    Stats,
+    Clear,
+}
+
+/// Options for the cache command.
+#[derive(Debug)]
+pub struct CacheOptions {
+    /// Path to the configuration file
+    pub config: Option<String>,
+    /// What to do with the cache
+    pub action: CacheAction,
+}
+
+/// Errors that can occur during the cache command.
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("Failed to load configuration: {0}")]
+    ConfigError(String),
+
+    #[error("Failed to read cache {0}: {1}")]
+    IoError(PathBuf, std::io::Error),
+}
+
+/// Run the cache command.
+pub fn run_cache(options: CacheOptions) -> Result<(), CacheError> {
+    let cache_path = if let Some(config_path) = &options.config {
+        ForgeConfig::load_from_path(Path::new(config_path))
+            .map_err(|e| CacheError::ConfigError(e.to_string()))?
+            .output
+            .discovery_cache_path
+    } else {
+        // Without a usable forge.yaml, use the default location
+        ForgeConfig::load_default()
+            .map(|config| config.output.discovery_cache_path)
+            .unwrap_or_else(|_| PathBuf::from(".forge/cache"))
+    };
+    let cache = DiscoveryCache::new(&cache_path);
+
+    match options.action {
+        CacheAction::Stats => {
+            let stats = cache
+                .stats()
+                .map_err(|e| CacheError::IoError(cache_path.clone(), e))?;
+            print!("{}", format_stats(&cache_path, &stats));
+        }
+        CacheAction::Clear => {
+            let removed = cache
+                .clear()
+                .map_err(|e| CacheError::IoError(cache_path.clone(), e))?;
+            output::success(&format!(
+                "Removed {} cached file(s) ({}) from {}",
+                removed.entries,
+                format_size(removed.bytes),
+                cache_path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Describe the cache contents, one parser per line.
+fn format_stats(cache_path: &Path, stats: &CacheStats) -> String {
+    let mut out = format!(
+        "Discovery cache: {}\n  {} file(s), {}\n",
+        cache_path.display(),
+        stats.entries,
+        format_size(stats.bytes)
+    );
+    for (parser, entries) in &stats.parsers {
+        out.push_str(&format!("  {}: {}\n", parser, entries));
+    }
+    out
+}
+
+/// A byte count in the largest unit that keeps it at or above 1.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn test_format_stats() {
+        let stats = CacheStats {
+            entries: 3,
+            bytes: 2048,
+            parsers: BTreeMap::from([("javascript".to_string(), 2), ("python".to_string(), 1)]),
+        };
+        assert_eq!(
+            format_stats(Path::new(".forge/cache"), &stats),
+            "Discovery cache: .forge/cache\n  3 file(s), 2.0 KB\n  javascript: 2\n  python: 1\n"
+        );
+    }
+}
@@ -120,6 +120,10 @@ output:
   # Supports ~ for home directory
   cache_path: "~/.forge/repos"
 
+  # Where to cache the discoveries parsed from each file, so unchanged
+  # content is not parsed again (see `forge cache`)
+  discovery_cache_path: ".forge/cache"
+
 # ===============================================================================
 # LLM CONFIGURATION (for business context interview)
 # ===============================================================================
@@ -5,11 +5,17 @@
 //! - `forge init` - Initialize a new `forge.yaml` configuration file
 //! - `forge survey` - Survey repositories and build the knowledge graph
 //! - `forge map` - Serialize the knowledge graph to various formats
+//! - `forge cache` - Inspect or clear the per-file discovery cache
+//! - `forge repos` - Show which repositories a survey covers
 
+pub mod cache;
 pub mod init;
 pub mod map;
+pub mod repos;
 pub mod survey;
 
+pub use cache::{CacheAction, CacheOptions, run_cache};
 pub use init::{InitOptions, run_init};
 pub use map::{MapOptions, run_map};
+pub use repos::{ReposAction, ReposOptions, run_repos};
 pub use survey::{SurveyOptions, run_survey};
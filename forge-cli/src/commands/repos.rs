@@ -0,0 +1,122 @@
+//! The `forge repos` command.
+//!
+//! Shows the repositories forge.yaml lists without surveying them:
+//! - `list`: Every discovered repository, whether `forge survey` covers it,
+//!   and the exclude pattern or filter that decided it
+
+use crate::commands::survey::{RepoSources, SurveyError, select_repos};
+use crate::config::ForgeConfig;
+use crate::output;
+use forge_survey::{RepoInfo, Selection};
+use std::path::Path;
+
+/// What to do with the repositories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReposAction {
+    List,
+}
+
+/// Options for the repos command.
+#[derive(Debug)]
+pub struct ReposOptions {
+    /// Path to the configuration file
+    pub config: Option<String>,
+    /// What to do with the repositories
+    pub action: ReposAction,
+}
+
+/// Run the repos command.
+pub async fn run_repos(options: ReposOptions) -> Result<(), SurveyError> {
+    let config = if let Some(config_path) = &options.config {
+        output::verbose(&format!("Loading configuration from: {}", config_path));
+        ForgeConfig::load_from_path(Path::new(config_path))?
+    } else {
+        output::verbose("Loading configuration from: forge.yaml");
+        ForgeConfig::load_default()?
+    };
+
+    match options.action {
+        ReposAction::List => {
+            let sources = RepoSources::from_config(&config)?;
+            let selections = select_repos(&config, &sources).await?;
+            print!("{}", format_selections(&selections));
+        }
+    }
+    Ok(())
+}
+
+/// Describe which repositories are surveyed and why, one per line.
+fn format_selections(selections: &[(RepoInfo, Selection)]) -> String {
+    let included = selections.iter().filter(|(_, s)| s.included).count();
+    let mut out = format!(
+        "{} repositories: {} included, {} excluded\n",
+        selections.len(),
+        included,
+        selections.len() - included
+    );
+
+    let width = selections
+        .iter()
+        .map(|(repo, _)| repo.full_name.len())
+        .max()
+        .unwrap_or(0);
+    for (repo, selection) in selections {
+        let status = if selection.included {
+            "included"
+        } else {
+            "excluded"
+        };
+        out.push_str(&format!(
+            "  {}  {:<width$}  {:<9}  {}\n",
+            status,
+            repo.full_name,
+            repo.source.to_string(),
+            selection.reason,
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_survey::SourceKind;
+
+    fn repo(full_name: &str, source: SourceKind) -> RepoInfo {
+        RepoInfo {
+            full_name: full_name.to_string(),
+            name: full_name.rsplit('/').next().unwrap().to_string(),
+            owner: "acme".to_string(),
+            clone_url: String::new(),
+            default_branch: "main".to_string(),
+            language: None,
+            archived: false,
+            fork: false,
+            topics: vec![],
+            pushed_at: None,
+            visibility: None,
+            source,
+        }
+    }
+
+    #[test]
+    fn test_format_selections() {
+        let selections = vec![
+            (
+                repo("acme/billing", SourceKind::GitHub),
+                Selection::included("topic 'payments'"),
+            ),
+            (
+                repo("acme/platform/old-gateway", SourceKind::GitLab),
+                Selection::excluded("archived"),
+            ),
+        ];
+
+        assert_eq!(
+            format_selections(&selections),
+            "2 repositories: 1 included, 1 excluded\n\
+             \x20 included  acme/billing               GitHub     topic 'payments'\n\
+             \x20 excluded  acme/platform/old-gateway  GitLab     archived\n"
+        );
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! This command surveys repositories and builds a knowledge graph by:
 //! 1. Loading configuration from forge.yaml
-//! 2. Discovering repositories from GitHub org, explicit repos, or local paths
+//! 2. Discovering repositories from GitHub, GitLab, Bitbucket or Gitea, explicit repos, or
+//!    local paths
 //! 3. Cloning/updating repositories to local cache
 //! 4. Automatically detecting languages and selecting appropriate parsers
-//! 5. Parsing code with language-specific parsers (JavaScript/TypeScript, Python, Terraform)
+//! 5. Parsing code with language-specific parsers (JavaScript/TypeScript, Python, Terraform,
+//!    CloudFormation/SAM, docker-compose)
 //! 6. Building a knowledge graph from discoveries
 //! 7. Saving the graph to the configured output path
 //!
@@ -27,6 +29,15 @@
 //! # Exclude specific languages
 //! forge survey --exclude-lang "terraform,python"
 //!
+//! # Enrich resources from Terraform state
+//! forge survey --tfstate "infra/terraform.tfstate"
+//!
+//! # Parse with 8 threads
+//! forge survey --jobs 8
+//!
+//! # Parse every file again, ignoring cached discoveries
+//! forge survey --no-cache
+//!
 //! # Enable verbose output (global flag)
 //! forge -v survey
 //!
@@ -34,17 +45,34 @@
 //! forge -q survey
 //! ```
 
-use crate::config::{CloneMethod, ConfigError, ForgeConfig};
+use crate::config::{
+    ActivityConfig, CloneMethod, ConfigError, ForgeConfig, OwnershipConfig, PackageConfig,
+    PluginConfig, RepoConfig, RepoCriteriaConfig, RepoFiltersConfig, RepoVisibility, RuleConfig,
+    RuleKind,
+};
 use crate::output;
 use crate::progress::SurveyProgress;
-use forge_graph::ForgeGraph;
+use chrono::Utc;
+use forge_graph::{ForgeGraph, GitActivity};
 use forge_llm::{LLMConfig, create_and_verify_provider, run_interactive_interview};
 use forge_survey::{
-    ChangeDetector, CloneMethod as SurveyCloneMethod, CouplingAnalyzer, GitHubClient, GraphBuilder,
-    RepoCache, RepoInfo, SurveyState, detect_languages, get_current_commit, parser::ParserRegistry,
+    ActivityOptions, BitbucketClient, ChangeDetector, CloneMethod as SurveyCloneMethod,
+    CouplingAnalyzer, DetectedLanguages, DetectionRule, DiscoveryCache, GitHubClient, GitLabClient,
+    GiteaClient, GraphBuilder, HINTS_FILE, HintsError, InferredOwner, OwnershipOptions,
+    PackageOverride, ParseFailure, ParserError, PluginSpec, RepoCache, RepoCriteria, RepoFilter,
+    RepoHints, RepoInfo, RepoParse, RepoSource, RuleKind as SurveyRuleKind, RulePattern, Selection,
+    ServiceDiscovery, SourceKind, SurveyState, TerraformState, Visibility, WorkerPool,
+    WorkspacePackage, detect_packages, get_current_commit, git_activity, infer_owners,
+    parser::ParserRegistry,
 };
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
+use tokio::sync::Semaphore;
 
 /// Errors that can occur during the survey process.
 #[derive(Debug, Error)]
@@ -57,6 +85,10 @@ pub enum SurveyError {
     #[error("GitHub error: {0}")]
     GitHubError(#[from] forge_survey::GitHubError),
 
+    /// Repository source or clone error.
+    #[error("{0}")]
+    SourceError(#[from] forge_survey::SourceError),
+
     /// Parser error.
     #[error("Parser error: {0}")]
     ParserError(#[from] forge_survey::ParserError),
@@ -75,7 +107,7 @@ pub enum SurveyError {
 
     /// No repositories configured.
     #[error(
-        "No repositories to survey. Configure github_org, github_repos, or local_paths in forge.yaml"
+        "No repositories to survey. Configure GitHub, GitLab, Bitbucket or Gitea repos, or local_paths, in forge.yaml"
     )]
     NoRepositories,
 
@@ -90,6 +122,18 @@ pub enum SurveyError {
     /// Change detection error.
     #[error("Change detection error: {0}")]
     ChangeError(#[from] forge_survey::ChangeError),
+
+    /// Terraform state could not be read.
+    #[error("Terraform state error in {0}: {1}")]
+    TerraformStateError(PathBuf, forge_survey::TerraformStateError),
+
+    /// The parser worker pool could not be started.
+    #[error("Worker pool error: {0}")]
+    WorkerPoolError(String),
+
+    /// A parser task panicked or was cancelled.
+    #[error("Parser task failed: {0}")]
+    TaskError(#[from] tokio::task::JoinError),
 }
 
 /// Options for the `forge survey` command.
@@ -107,6 +151,12 @@ pub struct SurveyOptions {
     pub business_context: bool,
     /// Only re-parse changed files (M7 feature).
     pub incremental: bool,
+    /// Additional Terraform state files (comma-separated).
+    pub tfstate: Option<String>,
+    /// Parser threads; overrides `survey.parallelism` (0 = one per CPU).
+    pub jobs: Option<usize>,
+    /// Parse every file, ignoring the discovery cache.
+    pub no_cache: bool,
 }
 
 /// Run the `forge survey` command.
@@ -144,6 +194,16 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
         config.languages.exclude.extend(langs);
     }
 
+    if let Some(tfstate) = &options.tfstate {
+        config.survey.terraform_state.extend(
+            tfstate
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from),
+        );
+    }
+
     output::verbose(&format!(
         "Output graph path: {}",
         config.output.graph_path.display()
@@ -154,7 +214,8 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
     ));
 
     // Collect repositories to survey
-    let repos = collect_repos(&config, &options).await?;
+    let sources = RepoSources::from_config(&config)?;
+    let repos = collect_repos(&config, &options, &sources).await?;
 
     if repos.is_empty() {
         return Err(SurveyError::NoRepositories);
@@ -220,6 +281,7 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
     } else {
         GraphBuilder::new()
     };
+    builder.set_service_hosts(config.survey.service_hosts.clone());
 
     // Create change detector for incremental mode
     let change_detector = survey_state
@@ -227,30 +289,47 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
         .map(|state| ChangeDetector::new(state.clone()));
 
     // Create parser registry once
-    let registry = ParserRegistry::new().map_err(SurveyError::ParserError)?;
+    let rules: Vec<DetectionRule> = config.survey.rules.iter().map(convert_rule).collect();
+    let mut registry = ParserRegistry::with_rules(&rules).map_err(SurveyError::ParserError)?;
+    for plugin in &config.survey.plugins {
+        registry.register_plugin(convert_plugin(plugin));
+    }
+    let registry = Arc::new(registry);
 
-    // Setup repository cache for GitHub repos
-    let cache = RepoCache::new(
-        config.output.cache_path.clone(),
-        convert_clone_method(config.github.clone_method),
-    );
+    // Setup repository cache for remote repos
+    let cache =
+        RepoCache::new(config.output.cache_path.clone()).with_history_days(history_days(&config));
+
+    // Bounded pool that all parsing runs on
+    let jobs = options.jobs.or(config.survey.parallelism).unwrap_or(0);
+    let pool =
+        Arc::new(WorkerPool::new(jobs).map_err(|e| SurveyError::WorkerPoolError(e.to_string()))?);
+    output::verbose(&format!("Parsing with {} worker thread(s)", pool.jobs()));
+
+    // Discoveries of files parsed by earlier surveys
+    let discovery_cache = Arc::new(if options.no_cache {
+        DiscoveryCache::disabled()
+    } else {
+        output::verbose(&format!(
+            "Discovery cache: {}",
+            config.output.discovery_cache_path.display()
+        ));
+        DiscoveryCache::new(&config.output.discovery_cache_path)
+    });
 
     // Survey each repository
     let mut success_count = 0;
     let mut error_count = 0;
     let mut skipped_count = 0;
     let mut repos_surveyed: Vec<(String, String, usize, Vec<String>, bool)> = Vec::new();
+    let mut parse_failures: Vec<ParseFailure> = Vec::new();
 
+    // For incremental mode, decide which repositories need surveying
+    let mut to_survey = Vec::new();
     for (i, repo) in repos.iter().enumerate() {
-        // Start repo in progress bar
-        if let Some(ref mut p) = progress {
-            p.start_repo(&repo.full_name);
-        }
-
-        // For incremental mode, check if we need to survey this repo
         if let Some(ref detector) = change_detector {
             // Get the local path first to check changes
-            let local_path = if repo.owner == "local" {
+            let local_path = if repo.source == SourceKind::Local {
                 PathBuf::from(&repo.full_name)
             } else {
                 cache.repo_path(repo)
@@ -261,7 +340,10 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
                 match detector.detect_changes(&repo.full_name, &local_path).await {
                     Ok(changes) if !changes.needs_full_survey && !changes.has_changes() => {
                         skipped_count += 1;
-                        if output::is_verbose() {
+                        if let Some(ref mut p) = progress {
+                            p.start_repo(&repo.full_name);
+                            p.finish_repo();
+                        } else {
                             println!(
                                 "[{}/{}] Skipping {} (no changes)",
                                 i + 1,
@@ -319,14 +401,47 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
                 }
             }
         }
+        to_survey.push((i, repo));
+    }
+
+    // Clone and parse up to one repository per worker at a time. They are
+    // merged into the graph in the order they were listed, so the graph is
+    // the same however parsing is scheduled.
+    let clone_permits = Semaphore::new(config.github.clone_concurrency.max(1));
+    let mut scans = stream::iter(&to_survey)
+        .map(|(_, repo)| {
+            scan_repository(
+                repo,
+                &cache,
+                &sources,
+                &config,
+                &registry,
+                &pool,
+                &discovery_cache,
+                &clone_permits,
+            )
+        })
+        .buffered(pool.jobs());
+
+    for (i, repo) in &to_survey {
+        let Some(scan) = scans.next().await else {
+            break;
+        };
+
+        // Start repo in progress bar
+        if let Some(ref mut p) = progress {
+            p.start_repo(&repo.full_name);
+        }
 
         if !options.incremental || change_detector.is_none() {
             println!("[{}/{}] Surveying: {}", i + 1, repos.len(), repo.full_name);
         }
 
-        match survey_repository(repo, &cache, &mut builder, &config, &options, &registry).await {
-            Ok(survey_info) => {
+        match scan {
+            Ok(scan) => {
                 success_count += 1;
+                let survey_info =
+                    merge_repository(repo, scan, &mut builder, &config, &mut parse_failures);
                 if let Some(ref mut p) = progress {
                     p.finish_repo();
                 } else {
@@ -342,7 +457,7 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
                     output::error(&format!("Error surveying {}: {}", repo.full_name, e));
                 }
                 // Record failed survey
-                let local_path = if repo.owner == "local" {
+                let local_path = if repo.source == SourceKind::Local {
                     PathBuf::from(&repo.full_name)
                 } else {
                     cache.repo_path(repo)
@@ -372,6 +487,24 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
             success_count, error_count
         );
     }
+    if !options.no_cache && (discovery_cache.hits() > 0 || output::is_verbose()) {
+        println!(
+            "Discovery cache: {} file(s) reused, {} parsed",
+            discovery_cache.hits(),
+            discovery_cache.misses()
+        );
+    }
+    if !parse_failures.is_empty() {
+        output::warning(&format!(
+            "{} file(s) could not be parsed and were skipped{}",
+            parse_failures.len(),
+            if output::is_verbose() {
+                ""
+            } else {
+                " (run with -v to list them)"
+            }
+        ));
+    }
 
     // Build graph
     let mut graph = builder.build();
@@ -381,6 +514,11 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
         graph.edge_count()
     );
 
+    // Enrich resources with their identities from Terraform state
+    if !config.survey.terraform_state.is_empty() {
+        apply_terraform_state(&config, &mut graph)?;
+    }
+
     // Run coupling analysis (M4-T4)
     if output::is_verbose() {
         println!("Running coupling analysis...");
@@ -522,10 +660,126 @@ pub async fn run_survey(options: SurveyOptions) -> Result<(), SurveyError> {
     Ok(())
 }
 
+/// The hosting services repositories are discovered and cloned from, one
+/// client each.
+pub(crate) struct RepoSources {
+    sources: Vec<Box<dyn RepoSource>>,
+}
+
+impl RepoSources {
+    /// Create a client for each service forge.yaml lists repositories from.
+    ///
+    /// GitHub needs a token, so its client exists whenever the token is set
+    /// (repos given with `--repos` are on GitHub). The other services reach
+    /// public repositories without one.
+    pub(crate) fn from_config(config: &ForgeConfig) -> Result<Self, SurveyError> {
+        let mut sources: Vec<Box<dyn RepoSource>> = Vec::new();
+        let used = |kind| {
+            source_selections(&config.repos)
+                .iter()
+                .any(|s| s.kind == kind && !s.is_empty())
+        };
+
+        if let Ok(token) = config.github_token() {
+            sources.push(Box::new(GitHubClient::new(
+                &token,
+                config.github.api_url.as_deref(),
+                convert_clone_method(config.github.clone_method),
+            )?));
+        }
+        if used(SourceKind::GitLab) {
+            sources.push(Box::new(GitLabClient::new(
+                &config.gitlab.url,
+                config.gitlab_token().as_deref(),
+                convert_clone_method(config.gitlab.clone_method),
+            )?));
+        }
+        if used(SourceKind::Bitbucket) {
+            sources.push(Box::new(BitbucketClient::new(
+                &config.bitbucket.api_url,
+                config.bitbucket.username.as_deref(),
+                config.bitbucket_token().as_deref(),
+                convert_clone_method(config.bitbucket.clone_method),
+            )?));
+        }
+        // The URL is validated to be set when Gitea repos are listed
+        if let (true, Some(url)) = (used(SourceKind::Gitea), &config.gitea.url) {
+            sources.push(Box::new(GiteaClient::new(
+                url,
+                config.gitea_token().as_deref(),
+                convert_clone_method(config.gitea.clone_method),
+            )?));
+        }
+
+        Ok(Self { sources })
+    }
+
+    /// The client of a service, if one was created.
+    fn get(&self, kind: SourceKind) -> Option<&dyn RepoSource> {
+        self.sources
+            .iter()
+            .find(|source| source.kind() == kind)
+            .map(|source| source.as_ref())
+    }
+}
+
+/// What forge.yaml lists from one hosting service.
+struct SourceSelection<'a> {
+    kind: SourceKind,
+    /// Scopes to discover repositories from, with what the service calls them
+    scopes: Vec<(&'static str, &'a str)>,
+    /// Repositories named explicitly
+    repos: &'a [String],
+}
+
+impl SourceSelection<'_> {
+    fn is_empty(&self) -> bool {
+        self.scopes.is_empty() && self.repos.is_empty()
+    }
+}
+
+/// The scopes and repositories forge.yaml lists from each hosting service.
+fn source_selections(repos: &RepoConfig) -> Vec<SourceSelection<'_>> {
+    fn scopes<'a>(name: &'static str, scopes: &'a [String]) -> Vec<(&'static str, &'a str)> {
+        scopes.iter().map(|scope| (name, scope.as_str())).collect()
+    }
+    vec![
+        SourceSelection {
+            kind: SourceKind::GitHub,
+            scopes: repos
+                .github_org
+                .iter()
+                .map(|org| ("org", org.as_str()))
+                .collect(),
+            repos: &repos.github_repos,
+        },
+        SourceSelection {
+            kind: SourceKind::GitLab,
+            scopes: scopes("group", &repos.gitlab_groups),
+            repos: &repos.gitlab_projects,
+        },
+        SourceSelection {
+            kind: SourceKind::Bitbucket,
+            scopes: [
+                scopes("workspace", &repos.bitbucket_workspaces),
+                scopes("project", &repos.bitbucket_projects),
+            ]
+            .concat(),
+            repos: &repos.bitbucket_repos,
+        },
+        SourceSelection {
+            kind: SourceKind::Gitea,
+            scopes: scopes("org", &repos.gitea_orgs),
+            repos: &repos.gitea_repos,
+        },
+    ]
+}
+
 /// Collect repositories to survey based on configuration and CLI overrides.
 async fn collect_repos(
     config: &ForgeConfig,
     options: &SurveyOptions,
+    sources: &RepoSources,
 ) -> Result<Vec<RepoInfo>, SurveyError> {
     let mut repos = Vec::new();
 
@@ -545,66 +799,95 @@ async fn collect_repos(
         return Ok(repos);
     }
 
-    // Collect from GitHub org
-    if let Some(org) = &config.repos.github_org {
-        if output::is_verbose() {
-            println!("Discovering repositories from GitHub org: {}", org);
+    for (repo, selection) in select_repos(config, sources).await? {
+        if selection.included {
+            repos.push(repo);
+        } else if output::is_verbose() {
+            println!("  Excluding {} ({})", repo.full_name, selection.reason);
         }
+    }
 
-        // Need GitHub token for API access
-        let token = config
-            .github_token()
-            .map_err(|_| SurveyError::NoGitHubToken(config.github.token_env.clone()))?;
+    Ok(repos)
+}
 
-        let client = GitHubClient::new(
-            &token,
-            config.github.api_url.as_deref(),
-            convert_clone_method(config.github.clone_method),
-        )?;
+/// Discover the repositories forge.yaml lists and decide which are surveyed.
+///
+/// Repositories from hosting services go through the `exclude` patterns and
+/// the `filters`; local paths are always surveyed.
+pub(crate) async fn select_repos(
+    config: &ForgeConfig,
+    sources: &RepoSources,
+) -> Result<Vec<(RepoInfo, Selection)>, SurveyError> {
+    let filter = convert_filters(&config.repos.filters)?;
+    let now = Utc::now();
+    // Names are checked first, so excluded repos named explicitly aren't fetched
+    let exclude_by_name = |name: &str| match config.exclude_pattern(name) {
+        Some(pattern) => Some(Selection::excluded(format!(
+            "matches exclude pattern '{}'",
+            pattern
+        ))),
+        None => filter.exclude_by_name(name),
+    };
+    let select =
+        |repo: &RepoInfo| exclude_by_name(&repo.name).unwrap_or_else(|| filter.select(repo, now));
+    let mut repos = Vec::new();
 
-        let org_repos = client.list_org_repos(org).await?;
-        if output::is_verbose() {
-            println!("  Found {} repositories in org", org_repos.len());
+    for selection in source_selections(&config.repos) {
+        if selection.is_empty() {
+            continue;
         }
+        // Only GitHub's client is missing when it's needed: it needs a token
+        let Some(source) = sources.get(selection.kind) else {
+            return Err(SurveyError::NoGitHubToken(config.github.token_env.clone()));
+        };
 
-        for repo in org_repos {
-            if !config.is_excluded(&repo.name) {
-                repos.push(repo);
-            } else if output::is_verbose() {
-                println!("  Excluding {} (matches exclude pattern)", repo.name);
+        // Collect from the source's orgs, groups and workspaces
+        for (scope_name, scope) in &selection.scopes {
+            if output::is_verbose() {
+                println!(
+                    "Discovering repositories from {} {}: {}",
+                    selection.kind, scope_name, scope
+                );
             }
-        }
-    }
 
-    // Collect from explicit GitHub repos
-    if !config.repos.github_repos.is_empty() {
-        if output::is_verbose() {
-            println!(
-                "Fetching {} explicit GitHub repositories",
-                config.repos.github_repos.len()
-            );
-        }
+            let scope_repos = source.list_repos(scope).await?;
+            if output::is_verbose() {
+                println!(
+                    "  Found {} repositories in {}",
+                    scope_repos.len(),
+                    scope_name
+                );
+            }
 
-        // Need GitHub token for API access
-        let token = config
-            .github_token()
-            .map_err(|_| SurveyError::NoGitHubToken(config.github.token_env.clone()))?;
+            for repo in scope_repos {
+                let selection = select(&repo);
+                repos.push((repo, selection));
+            }
+        }
 
-        let client = GitHubClient::new(
-            &token,
-            config.github.api_url.as_deref(),
-            convert_clone_method(config.github.clone_method),
-        )?;
+        // Collect explicit repos
+        if !selection.repos.is_empty() {
+            if output::is_verbose() {
+                println!(
+                    "Fetching {} explicit {} repositories",
+                    selection.repos.len(),
+                    selection.kind
+                );
+            }
 
-        for repo_str in &config.repos.github_repos {
-            let (owner, name) = parse_owner_repo(repo_str)?;
-            if !config.is_excluded(name) {
-                match client.get_repo(owner, name).await {
-                    Ok(repo) => repos.push(repo),
+            for repo_str in selection.repos {
+                let name = repo_str.rsplit('/').next().unwrap_or(repo_str);
+                if let Some(excluded) = exclude_by_name(name) {
+                    repos.push((unfetched_repo(selection.kind, repo_str), excluded));
+                    continue;
+                }
+                match source.get_repo(repo_str).await {
+                    Ok(repo) => {
+                        let selection = select(&repo);
+                        repos.push((repo, selection));
+                    }
                     Err(e) => println!("  Warning: Failed to fetch {}: {}", repo_str, e),
                 }
-            } else if output::is_verbose() {
-                println!("  Excluding {} (matches exclude pattern)", name);
             }
         }
     }
@@ -621,7 +904,7 @@ async fn collect_repos(
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
 
-        repos.push(RepoInfo {
+        let repo = RepoInfo {
             full_name: local_path.to_string_lossy().to_string(),
             name: name.to_string(),
             owner: "local".to_string(),
@@ -631,53 +914,255 @@ async fn collect_repos(
             archived: false,
             fork: false,
             topics: vec![],
-        });
+            pushed_at: None,
+            visibility: None,
+            source: SourceKind::Local,
+        };
+        repos.push((repo, Selection::included("local path")));
     }
 
     Ok(repos)
 }
 
-/// Survey info returned from survey_repository.
+/// Survey info returned from merge_repository.
 /// (repo_name, commit_sha, discovery_count, detected_languages, success)
 type SurveyInfo = (String, String, usize, Vec<String>, bool);
 
-/// Survey a single repository.
-/// Returns info for incremental state tracking: (repo_name, sha, discovery_count, languages, success)
-async fn survey_repository(
+/// A repository cloned and parsed, ready to merge into the graph.
+struct RepoScan {
+    local_path: PathBuf,
+    commit_sha: String,
+    detected: DetectedLanguages,
+    /// The repository's own service and the config file declaring it, or
+    /// `None` when no parser applies to the repository
+    service: Option<(ServiceDiscovery, Option<&'static str>)>,
+    packages: Vec<WorkspacePackage>,
+    /// Each parser's file extensions and what it found
+    parses: Vec<(String, Result<RepoParse, ParserError>)>,
+    /// What the repository declares in `.forge.yaml` and annotations
+    hints: Result<RepoHints, HintsError>,
+    /// Inferred owners by service directory, relative to the repository
+    owners: BTreeMap<PathBuf, InferredOwner>,
+    /// Git activity by service directory, relative to the repository
+    activity: BTreeMap<PathBuf, GitActivity>,
+}
+
+/// Clone (or update) and parse a single repository.
+///
+/// Parsing runs on the worker pool rather than the async runtime, so several
+/// repositories are cloned and parsed at once. Nothing is added to the
+/// graph here; see [`merge_repository`].
+#[allow(clippy::too_many_arguments)]
+async fn scan_repository(
     repo: &RepoInfo,
     cache: &RepoCache,
-    builder: &mut GraphBuilder,
+    sources: &RepoSources,
     config: &ForgeConfig,
-    _options: &SurveyOptions,
-    registry: &ParserRegistry,
-) -> Result<SurveyInfo, SurveyError> {
+    registry: &Arc<ParserRegistry>,
+    pool: &Arc<WorkerPool>,
+    discovery_cache: &Arc<DiscoveryCache>,
+    clone_permits: &Semaphore,
+) -> Result<RepoScan, SurveyError> {
     // Determine local path
-    let local_path = if repo.owner == "local" {
+    let local_path = if repo.source == SourceKind::Local {
         // Local repository - use the full_name as the path
         PathBuf::from(&repo.full_name)
     } else {
-        // GitHub repository - clone/update to cache
-        if output::is_verbose() {
-            println!("  Cloning/updating repository...");
-        }
-        let token = config.github_token().ok();
-        cache.ensure_repo(repo, token.as_deref()).await?
+        // Remote repository - clone/update to cache
+        let _permit = clone_permits
+            .acquire()
+            .await
+            .expect("clone semaphore is never closed");
+        let credentials = sources
+            .get(repo.source)
+            .and_then(|source| source.clone_credentials());
+        cache.ensure_repo(repo, credentials.as_ref()).await?
     };
 
-    if output::is_verbose() {
-        println!("  Local path: {}", local_path.display());
-    }
-
     // Get commit SHA for tracking (use get_current_commit for both local and remote)
     let commit_sha = get_current_commit(&local_path)
         .await
         .unwrap_or_else(|_| "unknown".to_string());
 
+    let overrides: Vec<PackageOverride> = config
+        .package_overrides(&repo.full_name)
+        .into_iter()
+        .map(convert_package_override)
+        .collect();
+    let exclude = config.languages.exclude.clone();
+    let ownership = convert_ownership(&config.survey.ownership);
+    let activity = convert_activity(&config.survey.activity);
+    let registry = Arc::clone(registry);
+    let pool = Arc::clone(pool);
+    let discovery_cache = Arc::clone(discovery_cache);
+    let repo = repo.clone();
+
+    let scan = tokio::task::spawn_blocking(move || {
+        // Detect monorepo packages, each surveyed as a service of its own
+        let packages = detect_packages(&registry, &local_path, &overrides);
+
+        // Detect languages in the repository and its packages
+        let mut detected = registry.detect_languages(&local_path);
+        for package in &packages {
+            for language in registry
+                .detect_languages(&local_path.join(&package.path))
+                .iter()
+            {
+                detected.add(language.clone());
+            }
+        }
+
+        // Get parsers for detected languages, respecting exclusions
+        let parsers = registry.get_for_languages(&detected, &exclude);
+        if parsers.is_empty() {
+            return RepoScan {
+                local_path,
+                commit_sha,
+                detected,
+                service: None,
+                packages,
+                parses: Vec::new(),
+                hints: Ok(RepoHints::default()),
+                owners: BTreeMap::new(),
+                activity: BTreeMap::new(),
+            };
+        }
+
+        let service = repo_service(&registry, &local_path, &repo, &detected);
+        let parses = parsers
+            .iter()
+            .map(|parser| parser.supported_extensions().join("/"))
+            .zip(pool.parse_repo(&parsers, &local_path, &discovery_cache))
+            .collect();
+        let hints = pool.install(|| RepoHints::load(&local_path));
+        let package_dirs: Vec<PathBuf> = packages.iter().map(|p| p.path.clone()).collect();
+        let owners = ownership.map_or_else(BTreeMap::new, |options| {
+            infer_owners(&local_path, &package_dirs, &options)
+        });
+        let activity = activity.map_or_else(BTreeMap::new, |options| {
+            git_activity(&local_path, &package_dirs, &options)
+        });
+
+        RepoScan {
+            local_path,
+            commit_sha,
+            detected,
+            service: Some(service),
+            packages,
+            parses,
+            hints,
+            owners,
+            activity,
+        }
+    })
+    .await?;
+
+    Ok(scan)
+}
+
+/// The service a repository declares in its package.json or Python config,
+/// or else one named after the repository.
+fn repo_service(
+    registry: &ParserRegistry,
+    local_path: &Path,
+    repo: &RepoInfo,
+    detected: &DetectedLanguages,
+) -> (ServiceDiscovery, Option<&'static str>) {
+    // Try JavaScript/TypeScript (package.json)
+    if local_path.join("package.json").exists() {
+        if let Some(js_parser) = registry.get("javascript") {
+            // Use downcast to call parse_package_json on JavaScriptParser
+            if let Some(js_parser) = js_parser
+                .as_ref()
+                .as_any()
+                .downcast_ref::<forge_survey::parser::javascript::JavaScriptParser>(
+            ) {
+                if let Some(service) = js_parser.parse_package_json(local_path) {
+                    return (service, Some("package.json"));
+                }
+            }
+        }
+    }
+
+    // Try Python (pyproject.toml, setup.py, requirements.txt)
+    let python_configs = ["pyproject.toml", "setup.py", "requirements.txt"];
+    if python_configs.iter().any(|f| local_path.join(f).exists()) {
+        if let Some(py_parser) = registry.get("python") {
+            if let Some(py_parser) = py_parser
+                .as_ref()
+                .as_any()
+                .downcast_ref::<forge_survey::parser::python::PythonParser>()
+            {
+                if let Some(service) = py_parser.parse_project_config(local_path) {
+                    return (service, Some("Python config"));
+                }
+            }
+        }
+    }
+
+    // Create a minimal service discovery from the repo name
+    let service = ServiceDiscovery {
+        name: repo.name.clone(),
+        language: detected
+            .primary()
+            .map(|l| l.name.clone())
+            .unwrap_or_else(|| "unknown".to_string()),
+        framework: None,
+        entry_point: "unknown".to_string(),
+        source_file: repo.full_name.clone(),
+        source_line: 0,
+        deployment_metadata: None,
+    };
+    (service, None)
+}
+
+/// Add a scanned repository to the graph.
+///
+/// Files that failed to parse are appended to `failures`.
+/// Returns info for incremental state tracking: (repo_name, sha, discovery_count, languages, success)
+fn merge_repository(
+    repo: &RepoInfo,
+    scan: RepoScan,
+    builder: &mut GraphBuilder,
+    config: &ForgeConfig,
+    failures: &mut Vec<ParseFailure>,
+) -> SurveyInfo {
+    let RepoScan {
+        local_path,
+        commit_sha,
+        detected,
+        service,
+        packages,
+        parses,
+        hints,
+        owners,
+        activity,
+    } = scan;
+
+    if output::is_verbose() {
+        println!("  Local path: {}", local_path.display());
+    }
+
     // Set repository context in builder
     builder.set_repo_context(&repo.full_name, Some(&commit_sha));
 
-    // Detect languages in the repository
-    let detected = detect_languages(&local_path);
+    // Inject environment context from forge.yaml (including local_only environments,
+    // whose topology typically comes from docker-compose files)
+    match config.resolve_environment(&repo.full_name) {
+        Some(env) => {
+            if output::is_verbose() {
+                let scope = if env.local_only == Some(true) {
+                    " (local only)"
+                } else {
+                    ""
+                };
+                println!("  Environment: {}{}", env.name, scope);
+            }
+            builder.set_environment(&env.name, env.aws_account_id.as_deref());
+        }
+        None => builder.clear_environment(),
+    }
+
     let detected_languages: Vec<String> = detected.iter().map(|l| l.name.clone()).collect();
 
     if output::is_verbose() {
@@ -692,10 +1177,7 @@ async fn survey_repository(
         }
     }
 
-    // Get parsers for detected languages, respecting exclusions
-    let parsers = registry.get_for_languages(&detected, &config.languages.exclude);
-
-    if parsers.is_empty() {
+    let Some((mut service, mut service_config)) = service else {
         if output::is_verbose() {
             if !config.languages.exclude.is_empty() {
                 println!(
@@ -706,123 +1188,154 @@ async fn survey_repository(
                 println!("  No parsers available for detected languages");
             }
         }
-        return Ok((
+        return (
             repo.full_name.clone(),
             commit_sha,
             0,
             detected_languages,
             true,
-        ));
-    }
-
-    if output::is_verbose() {
-        println!("  Using {} parser(s)", parsers.len());
-    }
+        );
+    };
 
-    // Detect service metadata from applicable config files
-    // Try JavaScript/TypeScript (package.json)
-    let package_json_path = local_path.join("package.json");
-    let mut service_id = None;
-    if package_json_path.exists() {
-        if let Some(js_parser) = registry.get("javascript") {
-            // Use downcast to call parse_package_json on JavaScriptParser
-            if let Some(js_parser) = js_parser
-                .as_ref()
-                .as_any()
-                .downcast_ref::<forge_survey::parser::javascript::JavaScriptParser>(
-            ) {
-                if let Some(service) = js_parser.parse_package_json(&local_path) {
-                    if output::is_verbose() {
-                        println!("  Found service: {} (from package.json)", service.name);
-                    }
-                    service_id = Some(builder.add_service(service));
-                }
-            }
-        }
+    let hints = hints.unwrap_or_else(|e| {
+        println!("    Warning: {}", e);
+        failures.push(ParseFailure {
+            path: local_path.join(HINTS_FILE),
+            error: e.to_string(),
+        });
+        RepoHints::default()
+    });
+    if let Some(name) = &hints.service_name {
+        service.name = name.clone();
+        service_config = Some(HINTS_FILE);
     }
 
-    // Try Python (pyproject.toml, setup.py, requirements.txt)
-    if service_id.is_none() {
-        let python_configs = ["pyproject.toml", "setup.py", "requirements.txt"];
-        let has_python_config = python_configs.iter().any(|f| local_path.join(f).exists());
-
-        if has_python_config {
-            if let Some(py_parser) = registry.get("python") {
-                if let Some(py_parser) = py_parser
-                    .as_ref()
-                    .as_any()
-                    .downcast_ref::<forge_survey::parser::python::PythonParser>(
-                ) {
-                    if let Some(service) = py_parser.parse_project_config(&local_path) {
-                        if output::is_verbose() {
-                            println!("  Found service: {} (from Python config)", service.name);
-                        }
-                        service_id = Some(builder.add_service(service));
-                    }
-                }
-            }
+    if output::is_verbose() {
+        println!("  Using {} parser(s)", parses.len());
+        match service_config {
+            Some(file) => println!("  Found service: {} (from {})", service.name, file),
+            None => println!("  No service metadata found - using repository name"),
         }
     }
+    let service_id = builder.add_service(service);
 
-    // If no service was detected from config files, use repo name
-    if service_id.is_none() {
+    let mut package_services = Vec::new();
+    for package in packages {
         if output::is_verbose() {
-            println!("  No service metadata found - using repository name");
+            println!(
+                "  Found service: {} (from {} {})",
+                package.service.name,
+                package.layout,
+                package.path.display()
+            );
         }
-        // Create a minimal service discovery from the repo name
-        let service = forge_survey::ServiceDiscovery {
-            name: repo.name.clone(),
-            language: detected
-                .iter()
-                .next()
-                .map(|l| l.name.clone())
-                .unwrap_or_else(|| "unknown".to_string()),
-            framework: None,
-            entry_point: "unknown".to_string(),
-            source_file: repo.full_name.clone(),
-            source_line: 0,
-            deployment_metadata: None,
-        };
-        service_id = Some(builder.add_service(service));
+        let package_id = builder.add_service(package.service);
+        package_services.push((local_path.join(&package.path), package_id));
     }
 
-    let service_id = service_id.expect("service_id should be set at this point");
-
-    // Run each parser and collect discoveries
-    let mut total_discoveries = 0usize;
-    for parser in &parsers {
+    // Collect each parser's discoveries. They are processed together so
+    // names the code reads from environment variables can be joined with
+    // the IaC of the same repository.
+    let mut discoveries = Vec::new();
+    for (extensions, parse) in parses {
         if output::is_verbose() {
-            let extensions = parser.supported_extensions();
-            println!("  Parsing {} files...", extensions.join("/"));
+            println!("  Parsing {} files...", extensions);
         }
 
-        match parser.parse_repo(&local_path) {
-            Ok(discoveries) => {
-                let count = discoveries.len();
+        match parse {
+            Ok(parse) => {
                 if output::is_verbose() {
-                    println!("    Found {} code discoveries", count);
+                    println!("    Found {} code discoveries", parse.discoveries.len());
+                    for failure in &parse.failures {
+                        println!(
+                            "    Failed to parse {}: {}",
+                            failure.path.display(),
+                            failure.error
+                        );
+                    }
                 }
-                total_discoveries += count;
-                builder.process_discoveries(discoveries, &service_id);
+                discoveries.extend(parse.discoveries);
+                failures.extend(parse.failures);
             }
             Err(e) => {
                 // Log warning and continue with other parsers
-                println!(
-                    "    Warning: Parser failed for {}: {}",
-                    parser.supported_extensions().join("/"),
-                    e
-                );
+                println!("    Warning: Parser failed for {}: {}", extensions, e);
+                failures.push(ParseFailure {
+                    path: local_path.clone(),
+                    error: e.to_string(),
+                });
             }
         }
     }
 
-    Ok((
+    hints.retain_discoveries(&local_path, &mut discoveries);
+    if output::is_verbose() && !hints.declarations.is_empty() {
+        println!("  Found {} declared edge(s)", hints.declarations.len());
+    }
+
+    let total_discoveries = discoveries.len();
+    builder.process_package_discoveries(discoveries, &package_services, &service_id);
+    builder.add_repo_hints(&hints, &package_services, &service_id);
+    for (dir, owner) in owners {
+        if output::is_verbose() {
+            println!(
+                "  Inferred owner {} from {}",
+                owner.owner,
+                owner.provenance.source.description()
+            );
+        }
+        let owner_id = package_services
+            .iter()
+            .find(|(root, _)| *root == local_path.join(&dir))
+            .map_or(&service_id, |(_, id)| id);
+        builder.set_inferred_owner(owner_id, owner);
+    }
+    for (dir, activity) in activity {
+        let activity_id = package_services
+            .iter()
+            .find(|(root, _)| *root == local_path.join(&dir))
+            .map_or(&service_id, |(_, id)| id);
+        builder.set_git_activity(activity_id, &activity);
+    }
+
+    (
         repo.full_name.clone(),
         commit_sha,
         total_discoveries,
         detected_languages,
         true,
-    ))
+    )
+}
+
+/// Load the configured Terraform state files and apply them to the graph.
+fn apply_terraform_state(config: &ForgeConfig, graph: &mut ForgeGraph) -> Result<(), SurveyError> {
+    let mut state = TerraformState::default();
+    for path in &config.survey.terraform_state {
+        output::verbose(&format!("Loading Terraform state: {}", path.display()));
+        let loaded = TerraformState::load(path)
+            .map_err(|e| SurveyError::TerraformStateError(path.clone(), e))?;
+        state.merge(loaded);
+    }
+
+    let result = state.apply_to_graph(graph, &config.account_environments());
+    println!(
+        "Terraform state: {} resources enriched, {} unused",
+        result.enriched.len(),
+        result.unused.len()
+    );
+    for node_id in &result.unused {
+        output::warning(&format!(
+            "{} is in Terraform state but no code accesses it",
+            node_id.name()
+        ));
+    }
+    if output::is_verbose() {
+        for address in &result.unmatched {
+            println!("  No graph node for Terraform resource {}", address);
+        }
+    }
+
+    Ok(())
 }
 
 /// Parse a "owner/repo" string into owner and repo parts.
@@ -848,9 +1361,142 @@ fn parse_repo_string(repo_str: &str) -> Result<RepoInfo, SurveyError> {
         archived: false,
         fork: false,
         topics: vec![],
+        pushed_at: None,
+        visibility: None,
+        source: SourceKind::GitHub,
     })
 }
 
+/// A repo named explicitly but excluded before its metadata was fetched.
+fn unfetched_repo(kind: SourceKind, full_name: &str) -> RepoInfo {
+    let (owner, name) = full_name.rsplit_once('/').unwrap_or(("", full_name));
+    RepoInfo {
+        full_name: full_name.to_string(),
+        name: name.to_string(),
+        owner: owner.to_string(),
+        clone_url: String::new(),
+        default_branch: "main".to_string(),
+        language: None,
+        archived: false,
+        fork: false,
+        topics: vec![],
+        pushed_at: None,
+        visibility: None,
+        source: kind,
+    }
+}
+
+/// Convert the CLI repository filters to a forge-survey RepoFilter.
+fn convert_filters(filters: &RepoFiltersConfig) -> Result<RepoFilter, SurveyError> {
+    Ok(RepoFilter {
+        include: convert_criteria("include", &filters.include)?,
+        exclude: convert_criteria("exclude", &filters.exclude)?,
+    })
+}
+
+fn convert_criteria(
+    name: &str,
+    criteria: &RepoCriteriaConfig,
+) -> Result<RepoCriteria, SurveyError> {
+    let name_regex = criteria
+        .name_regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .map_err(|e| {
+            ConfigError::ValidationError(format!(
+                "Invalid repos.filters.{}.name_regex: {}",
+                name, e
+            ))
+        })?;
+    Ok(RepoCriteria {
+        topics: criteria.topics.clone(),
+        languages: criteria.languages.clone(),
+        visibility: criteria
+            .visibility
+            .iter()
+            .map(|visibility| match visibility {
+                RepoVisibility::Public => Visibility::Public,
+                RepoVisibility::Internal => Visibility::Internal,
+                RepoVisibility::Private => Visibility::Private,
+            })
+            .collect(),
+        archived: criteria.archived,
+        fork: criteria.fork,
+        pushed_within_days: criteria.pushed_within_days,
+        name_regex,
+    })
+}
+
+/// Convert the CLI ownership settings to forge-survey OwnershipOptions,
+/// or `None` when inference is disabled.
+fn convert_ownership(ownership: &OwnershipConfig) -> Option<OwnershipOptions> {
+    ownership.enabled.then_some(OwnershipOptions {
+        history_days: ownership.history_days,
+        max_committers: ownership.max_committers,
+    })
+}
+
+/// Convert the CLI activity settings to forge-survey ActivityOptions, or
+/// `None` when activity isn't measured.
+fn convert_activity(activity: &ActivityConfig) -> Option<ActivityOptions> {
+    activity.enabled.then_some(ActivityOptions {
+        window_days: activity.window_days,
+    })
+}
+
+/// Days of history cloned repositories keep: the longest window ownership
+/// inference or activity metrics read, or `None` when neither runs.
+fn history_days(config: &ForgeConfig) -> Option<u32> {
+    let ownership = convert_ownership(&config.survey.ownership).map(|o| o.history_days);
+    let activity = convert_activity(&config.survey.activity).map(|a| a.window_days);
+    ownership.max(activity)
+}
+
+/// Convert a CLI detection rule to a forge-survey DetectionRule.
+fn convert_rule(rule: &RuleConfig) -> DetectionRule {
+    DetectionRule {
+        name: rule.name.clone(),
+        languages: rule.languages.clone(),
+        pattern: match (&rule.call, &rule.query) {
+            (_, Some(query)) => RulePattern::Query(query.clone()),
+            (call, None) => RulePattern::Call(call.clone().unwrap_or_default()),
+        },
+        kind: match rule.kind {
+            RuleKind::Database => SurveyRuleKind::Database,
+            RuleKind::Queue => SurveyRuleKind::Queue,
+            RuleKind::ApiCall => SurveyRuleKind::ApiCall,
+            RuleKind::CloudResource => SurveyRuleKind::CloudResource,
+        },
+        resource_type: rule.resource_type.clone(),
+        name_capture: rule.name_capture.clone(),
+        operation_capture: rule.operation_capture.clone(),
+        operation: rule.operation.clone(),
+        operations: rule.operations.clone(),
+    }
+}
+
+/// Convert a CLI parser plugin to a forge-survey PluginSpec.
+fn convert_plugin(plugin: &PluginConfig) -> PluginSpec {
+    PluginSpec {
+        name: plugin.name.clone(),
+        command: PathBuf::from(&plugin.command),
+        args: plugin.args.clone(),
+        extensions: plugin.extensions.clone(),
+        timeout: Duration::from_secs(plugin.timeout_secs),
+        version: plugin.version,
+    }
+}
+
+/// Convert a CLI package override to a forge-survey PackageOverride.
+fn convert_package_override(package: &PackageConfig) -> PackageOverride {
+    PackageOverride {
+        path: package.path.clone(),
+        name: package.name.clone(),
+        exclude: package.exclude,
+    }
+}
+
 /// Convert CLI CloneMethod to forge-survey CloneMethod.
 fn convert_clone_method(method: CloneMethod) -> SurveyCloneMethod {
     match method {
@@ -883,6 +1529,135 @@ mod tests {
         assert_eq!(repo.owner, "owner");
         assert_eq!(repo.name, "repo");
         assert_eq!(repo.clone_url, "https://github.com/owner/repo");
+        assert_eq!(repo.source, SourceKind::GitHub);
+    }
+
+    #[test]
+    fn test_source_selections() {
+        let repos = RepoConfig {
+            github_org: Some("acme".to_string()),
+            bitbucket_workspaces: vec!["acme".to_string()],
+            bitbucket_projects: vec!["acme/PLAT".to_string()],
+            gitea_repos: vec!["infra/terraform".to_string()],
+            ..Default::default()
+        };
+        let selections = source_selections(&repos);
+
+        let github = &selections[0];
+        assert_eq!(github.kind, SourceKind::GitHub);
+        assert_eq!(github.scopes, vec![("org", "acme")]);
+        assert!(selections[1].is_empty());
+        let bitbucket = &selections[2];
+        assert_eq!(
+            bitbucket.scopes,
+            vec![("workspace", "acme"), ("project", "acme/PLAT")]
+        );
+        let gitea = &selections[3];
+        assert!(gitea.scopes.is_empty());
+        assert_eq!(gitea.repos, ["infra/terraform".to_string()]);
+    }
+
+    #[test]
+    fn test_convert_filters() {
+        let filters = RepoFiltersConfig {
+            include: RepoCriteriaConfig {
+                visibility: vec![RepoVisibility::Public, RepoVisibility::Internal],
+                ..Default::default()
+            },
+            exclude: RepoCriteriaConfig {
+                name_regex: Some("-legacy$".to_string()),
+                ..Default::default()
+            },
+        };
+        let filter = convert_filters(&filters).unwrap();
+        assert_eq!(
+            filter.include.visibility,
+            vec![Visibility::Public, Visibility::Internal]
+        );
+
+        let mut repo = parse_repo_string("acme/billing-legacy").unwrap();
+        assert_eq!(
+            filter.select(&repo, Utc::now()),
+            Selection::excluded("unknown visibility")
+        );
+        repo.visibility = Some(Visibility::Internal);
+        assert_eq!(
+            filter.select(&repo, Utc::now()),
+            Selection::excluded("name matches /-legacy$/")
+        );
+
+        let invalid = RepoFiltersConfig {
+            exclude: RepoCriteriaConfig {
+                name_regex: Some("(".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            convert_filters(&invalid),
+            Err(SurveyError::ConfigError(ConfigError::ValidationError(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_select_repos_keeps_local_paths() {
+        let config: ForgeConfig = serde_yaml::from_str(
+            r#"
+repos:
+  local_paths: ["../billing-legacy"]
+  exclude: ["*-legacy"]
+  filters:
+    include:
+      archived: true
+"#,
+        )
+        .unwrap();
+        let sources = RepoSources::from_config(&config).unwrap();
+
+        let selections = select_repos(&config, &sources).await.unwrap();
+        assert_eq!(selections.len(), 1);
+        assert_eq!(selections[0].0.name, "billing-legacy");
+        assert_eq!(selections[0].1, Selection::included("local path"));
+    }
+
+    #[tokio::test]
+    async fn test_select_repos_excludes_named_repos_without_fetching() {
+        // Nothing listens on the instance, so fetching a repo would fail
+        let config: ForgeConfig = serde_yaml::from_str(
+            r#"
+repos:
+  gitlab_projects: ["acme/platform/billing-legacy", "acme/sandbox-api"]
+  exclude: ["*-legacy"]
+  filters:
+    exclude:
+      name_regex: "^sandbox-"
+gitlab:
+  url: "http://127.0.0.1:1"
+"#,
+        )
+        .unwrap();
+        let sources = RepoSources::from_config(&config).unwrap();
+
+        let selections = select_repos(&config, &sources).await.unwrap();
+        let selections: Vec<_> = selections
+            .iter()
+            .map(|(repo, selection)| (repo.full_name.as_str(), repo.source, selection))
+            .collect();
+        assert_eq!(
+            selections,
+            vec![
+                (
+                    "acme/platform/billing-legacy",
+                    SourceKind::GitLab,
+                    &Selection::excluded("matches exclude pattern '*-legacy'")
+                ),
+                (
+                    "acme/sandbox-api",
+                    SourceKind::GitLab,
+                    &Selection::excluded("name matches /^sandbox-/")
+                ),
+            ]
+        );
     }
 
     #[test]
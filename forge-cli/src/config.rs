@@ -21,6 +21,7 @@
 //! - `FORGE_TOKEN_BUDGET`: Override the token budget
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -62,6 +63,18 @@ pub struct ForgeConfig {
     #[serde(default)]
     pub github: GitHubConfig,
 
+    /// GitLab-specific settings.
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+
+    /// Bitbucket-specific settings.
+    #[serde(default)]
+    pub bitbucket: BitbucketConfig,
+
+    /// Gitea-specific settings.
+    #[serde(default)]
+    pub gitea: GiteaConfig,
+
     /// Language detection settings.
     #[serde(default)]
     pub languages: LanguageConfig,
@@ -70,6 +83,10 @@ pub struct ForgeConfig {
     #[serde(default)]
     pub output: OutputConfig,
 
+    /// Survey settings.
+    #[serde(default)]
+    pub survey: SurveyConfig,
+
     /// LLM provider configuration (for business context).
     #[serde(default)]
     pub llm: LLMConfig,
@@ -102,7 +119,8 @@ fn default_staleness_days() -> u32 {
 /// Repository source configuration.
 ///
 /// Defines where Forge should discover repositories. Multiple sources can be
-/// combined (GitHub org, explicit repos, and local paths).
+/// combined (GitHub, GitLab, Bitbucket and Gitea scopes, explicit repos, and
+/// local paths).
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RepoConfig {
     /// GitHub organization name (discovers all repos).
@@ -113,6 +131,36 @@ pub struct RepoConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub github_repos: Vec<String>,
 
+    /// GitLab groups to discover projects from, subgroups included.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gitlab_groups: Vec<String>,
+
+    /// Explicit list of GitLab projects (`group/project` or
+    /// `group/subgroup/project` format).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gitlab_projects: Vec<String>,
+
+    /// Bitbucket workspaces to discover repos from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bitbucket_workspaces: Vec<String>,
+
+    /// Bitbucket projects to discover repos from (`workspace/PROJECT_KEY`
+    /// format).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bitbucket_projects: Vec<String>,
+
+    /// Explicit list of Bitbucket repos (`workspace/repo` format).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bitbucket_repos: Vec<String>,
+
+    /// Gitea organizations to discover repos from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gitea_orgs: Vec<String>,
+
+    /// Explicit list of Gitea repos (`owner/repo` format).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gitea_repos: Vec<String>,
+
     /// Local filesystem paths to repositories.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub local_paths: Vec<PathBuf>,
@@ -120,6 +168,70 @@ pub struct RepoConfig {
     /// Glob patterns to exclude repos by name.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub exclude: Vec<String>,
+
+    /// Include and exclude filters on the metadata of repos from hosting
+    /// services.
+    #[serde(default)]
+    pub filters: RepoFiltersConfig,
+}
+
+/// Repository selection filters.
+///
+/// A repo is surveyed when it meets every `include` criterion and no
+/// `exclude` criterion. Local paths are always surveyed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoFiltersConfig {
+    /// Criteria a repo must all meet.
+    #[serde(default)]
+    pub include: RepoCriteriaConfig,
+
+    /// Criteria any of which excludes a repo.
+    #[serde(default)]
+    pub exclude: RepoCriteriaConfig,
+}
+
+/// Criteria on repository metadata. Unset criteria are ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoCriteriaConfig {
+    /// Topics, any of which matches (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+
+    /// Primary languages, any of which matches (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+
+    /// Visibilities, any of which matches.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visibility: Vec<RepoVisibility>,
+
+    /// Whether the repo is archived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+
+    /// Whether the repo is a fork.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fork: Option<bool>,
+
+    /// Pushed to in the last N days.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pushed_within_days: Option<u32>,
+
+    /// Regular expression the repo name matches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_regex: Option<String>,
+}
+
+/// Who can see a repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoVisibility {
+    /// Anyone.
+    Public,
+    /// Members of the enterprise or instance.
+    Internal,
+    /// Only those granted access.
+    Private,
 }
 
 /// GitHub-specific configuration.
@@ -157,6 +269,109 @@ fn default_token_env() -> String {
     "GITHUB_TOKEN".to_string()
 }
 
+/// GitLab-specific configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabConfig {
+    /// GitLab instance URL (for self-hosted GitLab).
+    #[serde(default = "default_gitlab_url")]
+    pub url: String,
+
+    /// Environment variable name containing the GitLab token.
+    #[serde(default = "default_gitlab_token_env")]
+    pub token_env: String,
+
+    /// Clone method: `https` or `ssh`.
+    #[serde(default = "default_clone_method")]
+    pub clone_method: CloneMethod,
+}
+
+impl Default for GitLabConfig {
+    fn default() -> Self {
+        Self {
+            url: default_gitlab_url(),
+            token_env: default_gitlab_token_env(),
+            clone_method: default_clone_method(),
+        }
+    }
+}
+
+fn default_gitlab_url() -> String {
+    "https://gitlab.com".to_string()
+}
+
+fn default_gitlab_token_env() -> String {
+    "GITLAB_TOKEN".to_string()
+}
+
+/// Bitbucket-specific configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitbucketConfig {
+    /// Bitbucket API base URL.
+    #[serde(default = "default_bitbucket_api_url")]
+    pub api_url: String,
+
+    /// Environment variable name containing the access token or app password.
+    #[serde(default = "default_bitbucket_token_env")]
+    pub token_env: String,
+
+    /// User name of an app password (unset for access tokens).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Clone method: `https` or `ssh`.
+    #[serde(default = "default_clone_method")]
+    pub clone_method: CloneMethod,
+}
+
+impl Default for BitbucketConfig {
+    fn default() -> Self {
+        Self {
+            api_url: default_bitbucket_api_url(),
+            token_env: default_bitbucket_token_env(),
+            username: None,
+            clone_method: default_clone_method(),
+        }
+    }
+}
+
+fn default_bitbucket_api_url() -> String {
+    "https://api.bitbucket.org/2.0".to_string()
+}
+
+fn default_bitbucket_token_env() -> String {
+    "BITBUCKET_TOKEN".to_string()
+}
+
+/// Gitea-specific configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiteaConfig {
+    /// Gitea instance URL (required to survey Gitea repos).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Environment variable name containing the Gitea token.
+    #[serde(default = "default_gitea_token_env")]
+    pub token_env: String,
+
+    /// Clone method: `https` or `ssh`.
+    #[serde(default = "default_clone_method")]
+    pub clone_method: CloneMethod,
+}
+
+impl Default for GiteaConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            token_env: default_gitea_token_env(),
+            clone_method: default_clone_method(),
+        }
+    }
+}
+
+fn default_gitea_token_env() -> String {
+    "GITEA_TOKEN".to_string()
+}
+
 fn default_clone_method() -> CloneMethod {
     CloneMethod::Https
 }
@@ -197,6 +412,10 @@ pub struct OutputConfig {
     /// Path to cache cloned repositories.
     #[serde(default = "default_cache_path")]
     pub cache_path: PathBuf,
+
+    /// Path to cache the discoveries parsed from each file.
+    #[serde(default = "default_discovery_cache_path")]
+    pub discovery_cache_path: PathBuf,
 }
 
 impl Default for OutputConfig {
@@ -204,10 +423,237 @@ impl Default for OutputConfig {
         Self {
             graph_path: default_graph_path(),
             cache_path: default_cache_path(),
+            discovery_cache_path: default_discovery_cache_path(),
         }
     }
 }
 
+/// Survey configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SurveyConfig {
+    /// Terraform state files (`terraform.tfstate` or `terraform show -json`
+    /// output) used to enrich resources with their ARN, region and account.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terraform_state: Vec<PathBuf>,
+
+    /// Hostnames services are reached at, mapped to the service name, for
+    /// resolving HTTP calls to absolute URLs (`api.example.com: orders-api`).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub service_hosts: HashMap<String, String>,
+
+    /// Per-directory overrides of monorepo package detection.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageConfig>,
+
+    /// Parser threads, shared by all repositories; one per CPU when unset
+    /// or 0. `--jobs` overrides it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<usize>,
+
+    /// External executables that parse languages without a built-in parser.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<PluginConfig>,
+
+    /// Detection rules for in-house libraries, applied by the JavaScript
+    /// and Python parsers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleConfig>,
+
+    /// How service owners are inferred from CODEOWNERS and git history.
+    #[serde(default)]
+    pub ownership: OwnershipConfig,
+
+    /// Git activity metrics measured for each service.
+    #[serde(default)]
+    pub activity: ActivityConfig,
+}
+
+/// Owner inference settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipConfig {
+    /// Infer owners for services without a declared one.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Days of git history read when no CODEOWNERS rule applies.
+    #[serde(default = "default_history_days")]
+    pub history_days: u32,
+
+    /// Most frequent committers recorded as the owner.
+    #[serde(default = "default_max_committers")]
+    pub max_committers: usize,
+}
+
+impl Default for OwnershipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            history_days: default_history_days(),
+            max_committers: default_max_committers(),
+        }
+    }
+}
+
+/// Git activity settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityConfig {
+    /// Measure commits, authors and churn of each service.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Days of git history the metrics cover.
+    #[serde(default = "default_activity_window_days")]
+    pub window_days: u32,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_days: default_activity_window_days(),
+        }
+    }
+}
+
+fn default_activity_window_days() -> u32 {
+    90
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_history_days() -> u32 {
+    180
+}
+
+fn default_max_committers() -> usize {
+    3
+}
+
+/// A detection rule: a call pattern or tree-sitter query mapped to a
+/// discovery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleConfig {
+    /// Rule name, recorded as the detection method.
+    pub name: String,
+
+    /// Languages the rule applies to: javascript, typescript, python.
+    /// All of them when empty; required for queries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+
+    /// Call pattern such as `acme.table($name).$op()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call: Option<String>,
+
+    /// Tree-sitter query, instead of a call pattern.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    /// Kind of discovery a match produces.
+    pub kind: RuleKind,
+
+    /// Database, queue or resource type (e.g., "dynamodb", "sns").
+    /// Required except for API calls.
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+
+    /// Capture holding the resource name, or the API call target.
+    #[serde(default = "default_name_capture")]
+    pub name_capture: String,
+
+    /// Capture holding the operation.
+    #[serde(default = "default_operation_capture")]
+    pub operation_capture: String,
+
+    /// Operation used when nothing is captured (e.g., "write", "publish").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+
+    /// Captured operations mapped to operations (e.g., `put: write`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub operations: BTreeMap<String, String>,
+}
+
+/// Kind of discovery a detection rule produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    /// A database access; operations: read, write, read_write.
+    Database,
+    /// A queue or topic operation; operations: publish, subscribe.
+    Queue,
+    /// An HTTP call; the operation is the method.
+    ApiCall,
+    /// A cloud resource usage.
+    CloudResource,
+}
+
+fn default_name_capture() -> String {
+    "name".to_string()
+}
+
+fn default_operation_capture() -> String {
+    "op".to_string()
+}
+
+/// A parser plugin: an executable that receives files as JSON on stdin and
+/// answers with their discoveries on stdout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    /// Language name the plugin is detected as and excluded by
+    /// (e.g., "elixir"). Replaces the built-in parser of the same name.
+    pub name: String,
+
+    /// Executable to run, looked up on `PATH` unless it is a path.
+    pub command: String,
+
+    /// Arguments passed to the executable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// File extensions the plugin parses (e.g., ["ex", "exs"]).
+    pub extensions: Vec<String>,
+
+    /// Seconds one run of the plugin may take.
+    #[serde(default = "default_plugin_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Version of the plugin's output; bump it to discard cached results.
+    #[serde(default = "default_plugin_version")]
+    pub version: u32,
+}
+
+fn default_plugin_timeout_secs() -> u64 {
+    60
+}
+
+fn default_plugin_version() -> u32 {
+    1
+}
+
+/// Override of package detection for one directory of a monorepo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageConfig {
+    /// Repository patterns the override applies to; all repositories when
+    /// empty. Supports glob patterns (e.g., "my-org/platform").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repos: Vec<String>,
+
+    /// Directory, relative to the repository root.
+    pub path: PathBuf,
+
+    /// Service name for the package in this directory. Declares a package
+    /// when no workspace layout detects one here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// If true, no directory at or below `path` is a package.
+    #[serde(default)]
+    pub exclude: bool,
+}
+
 fn default_graph_path() -> PathBuf {
     PathBuf::from(".forge/graph.json")
 }
@@ -216,6 +662,10 @@ fn default_cache_path() -> PathBuf {
     PathBuf::from("~/.forge/repos")
 }
 
+fn default_discovery_cache_path() -> PathBuf {
+    PathBuf::from(".forge/cache")
+}
+
 /// LLM provider configuration.
 ///
 /// Used for business context interviews (triggered by `--business-context` flag).
@@ -302,10 +752,15 @@ impl ForgeConfig {
                 github_repos: vec![],
                 local_paths: paths,
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: default_token_budget(),
             staleness_days: default_staleness_days(),
@@ -332,6 +787,11 @@ impl ForgeConfig {
             self.output.cache_path = PathBuf::from(path);
         }
 
+        // Override discovery cache path
+        if let Ok(path) = env::var("FORGE_OUTPUT_DISCOVERY_CACHE_PATH") {
+            self.output.discovery_cache_path = PathBuf::from(path);
+        }
+
         // Override token budget
         if let Ok(budget) = env::var("FORGE_TOKEN_BUDGET") {
             if let Ok(n) = budget.parse() {
@@ -382,6 +842,25 @@ impl ForgeConfig {
             self.output.graph_path = home.clone();
         }
 
+        // Expand discovery_cache_path if it uses ~
+        if let Some(rest) = self
+            .output
+            .discovery_cache_path
+            .to_str()
+            .and_then(|s| s.strip_prefix("~/"))
+        {
+            self.output.discovery_cache_path = home.join(rest);
+        } else if self.output.discovery_cache_path.to_str() == Some("~") {
+            self.output.discovery_cache_path = home.clone();
+        }
+
+        // Expand parser plugin commands
+        for plugin in &mut self.survey.plugins {
+            if let Some(rest) = plugin.command.strip_prefix("~/") {
+                plugin.command = home.join(rest).to_string_lossy().into_owned();
+            }
+        }
+
         // Expand local_paths
         let expanded_paths: Vec<PathBuf> = self
             .repos
@@ -399,18 +878,39 @@ impl ForgeConfig {
             .collect();
         self.repos.local_paths = expanded_paths;
 
+        // Expand terraform_state paths
+        for path in &mut self.survey.terraform_state {
+            if let Some(rest) = path.to_str().and_then(|s| s.strip_prefix("~/")) {
+                *path = home.join(rest);
+            }
+        }
+
         Ok(())
     }
 
     /// Validate the configuration.
     fn validate(&self) -> Result<(), ConfigError> {
         // Must have at least one repo source
-        if self.repos.github_org.is_none()
-            && self.repos.github_repos.is_empty()
-            && self.repos.local_paths.is_empty()
+        let repos = &self.repos;
+        if repos.github_org.is_none()
+            && [
+                &repos.github_repos,
+                &repos.gitlab_groups,
+                &repos.gitlab_projects,
+                &repos.bitbucket_workspaces,
+                &repos.bitbucket_projects,
+                &repos.bitbucket_repos,
+                &repos.gitea_orgs,
+                &repos.gitea_repos,
+            ]
+            .iter()
+            .all(|list| list.is_empty())
+            && repos.local_paths.is_empty()
         {
             return Err(ConfigError::ValidationError(
-                "No repository sources configured. Set github_org, github_repos, or local_paths"
+                "No repository sources configured. Set github_org, github_repos, gitlab_groups, \
+                 gitlab_projects, bitbucket_workspaces, bitbucket_projects, bitbucket_repos, \
+                 gitea_orgs, gitea_repos, or local_paths"
                     .into(),
             ));
         }
@@ -432,6 +932,61 @@ impl ForgeConfig {
             }
         }
 
+        // Validate the other sources' names (GitLab projects may be nested)
+        for project in &self.repos.gitlab_projects {
+            if project.split('/').count() < 2 || project.split('/').any(str::is_empty) {
+                return Err(ConfigError::ValidationError(format!(
+                    "Invalid GitLab project '{}'. Expected 'group/project' or 'group/subgroup/project'",
+                    project
+                )));
+            }
+        }
+        let two_part = [
+            (&self.repos.bitbucket_projects, "workspace/PROJECT_KEY"),
+            (&self.repos.bitbucket_repos, "workspace/repo"),
+            (&self.repos.gitea_repos, "owner/repo"),
+        ];
+        let is_two_part = |name: &str| {
+            let parts: Vec<&str> = name.split('/').collect();
+            parts.len() == 2 && parts.iter().all(|p| !p.is_empty())
+        };
+        for (names, expected) in two_part {
+            for name in names {
+                if !is_two_part(name) {
+                    return Err(ConfigError::ValidationError(format!(
+                        "Invalid repo format '{}'. Expected '{}'",
+                        name, expected
+                    )));
+                }
+            }
+        }
+        if self.gitea.url.is_none()
+            && !(self.repos.gitea_orgs.is_empty() && self.repos.gitea_repos.is_empty())
+        {
+            return Err(ConfigError::ValidationError(
+                "gitea.url is required to survey gitea_orgs or gitea_repos".into(),
+            ));
+        }
+
+        // Validate repository filters
+        let filters = &self.repos.filters;
+        for (name, criteria) in [("include", &filters.include), ("exclude", &filters.exclude)] {
+            if criteria.pushed_within_days == Some(0) {
+                return Err(ConfigError::ValidationError(format!(
+                    "repos.filters.{}.pushed_within_days must be above 0",
+                    name
+                )));
+            }
+            if let Some(pattern) = &criteria.name_regex {
+                regex::Regex::new(pattern).map_err(|e| {
+                    ConfigError::ValidationError(format!(
+                        "Invalid repos.filters.{}.name_regex '{}': {}",
+                        name, pattern, e
+                    ))
+                })?;
+            }
+        }
+
         // Validate LLM provider
         let valid_providers = ["claude", "gemini", "codex"];
         if !valid_providers.contains(&self.llm.provider.as_str()) {
@@ -442,6 +997,53 @@ impl ForgeConfig {
             )));
         }
 
+        // Validate parser plugins
+        for plugin in &self.survey.plugins {
+            if plugin.name.trim().is_empty() || plugin.command.trim().is_empty() {
+                return Err(ConfigError::ValidationError(
+                    "Every parser plugin needs a name and a command".into(),
+                ));
+            }
+            if plugin.extensions.is_empty() {
+                return Err(ConfigError::ValidationError(format!(
+                    "Parser plugin '{}' has no extensions",
+                    plugin.name
+                )));
+            }
+            if plugin.timeout_secs == 0 {
+                return Err(ConfigError::ValidationError(format!(
+                    "Parser plugin '{}' needs a timeout_secs above 0",
+                    plugin.name
+                )));
+            }
+        }
+
+        // Validate detection rules (patterns are checked when parsers load them)
+        for rule in &self.survey.rules {
+            if rule.call.is_some() == rule.query.is_some() {
+                return Err(ConfigError::ValidationError(format!(
+                    "Detection rule '{}' needs either a call pattern or a query",
+                    rule.name
+                )));
+            }
+        }
+
+        // Validate owner inference
+        let ownership = &self.survey.ownership;
+        if ownership.enabled && (ownership.history_days == 0 || ownership.max_committers == 0) {
+            return Err(ConfigError::ValidationError(
+                "survey.ownership needs history_days and max_committers above 0".into(),
+            ));
+        }
+
+        // Validate activity metrics
+        let activity = &self.survey.activity;
+        if activity.enabled && activity.window_days == 0 {
+            return Err(ConfigError::ValidationError(
+                "survey.activity needs a window_days above 0".into(),
+            ));
+        }
+
         // Validate clone method
         // (Already validated by serde deserialization)
 
@@ -459,16 +1061,37 @@ impl ForgeConfig {
         env::var(&self.github.token_env).is_ok()
     }
 
+    /// Get the GitLab token, if its environment variable is set.
+    pub fn gitlab_token(&self) -> Option<String> {
+        env::var(&self.gitlab.token_env).ok()
+    }
+
+    /// Get the Bitbucket token or app password, if its environment variable
+    /// is set.
+    pub fn bitbucket_token(&self) -> Option<String> {
+        env::var(&self.bitbucket.token_env).ok()
+    }
+
+    /// Get the Gitea token, if its environment variable is set.
+    pub fn gitea_token(&self) -> Option<String> {
+        env::var(&self.gitea.token_env).ok()
+    }
+
     /// Check if a repo name should be excluded based on configured patterns.
     pub fn is_excluded(&self, repo_name: &str) -> bool {
-        for pattern in &self.repos.exclude {
-            if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-                if glob_pattern.matches(repo_name) {
-                    return true;
-                }
-            }
-        }
-        false
+        self.exclude_pattern(repo_name).is_some()
+    }
+
+    /// The first configured exclude pattern a repo name matches.
+    pub fn exclude_pattern(&self, repo_name: &str) -> Option<&str> {
+        self.repos
+            .exclude
+            .iter()
+            .find(|pattern| {
+                glob::Pattern::new(pattern)
+                    .is_ok_and(|glob_pattern| glob_pattern.matches(repo_name))
+            })
+            .map(String::as_str)
     }
 
     /// Check if a language should be excluded from parsing.
@@ -511,6 +1134,20 @@ impl ForgeConfig {
         None
     }
 
+    /// The package overrides that apply to a repository.
+    pub fn package_overrides(&self, repo_name: &str) -> Vec<&PackageConfig> {
+        self.survey
+            .packages
+            .iter()
+            .filter(|package| {
+                package.repos.is_empty()
+                    || package.repos.iter().any(|pattern| {
+                        glob::Pattern::new(pattern).is_ok_and(|p| p.matches(repo_name))
+                    })
+            })
+            .collect()
+    }
+
     /// Get the AWS account ID for a given repository name.
     ///
     /// Returns None if the repo doesn't match any environment or if the
@@ -527,6 +1164,19 @@ impl ForgeConfig {
         self.resolve_environment(repo_name)
             .map(|env| env.name.as_str())
     }
+
+    /// Map each configured AWS account ID to its environment name.
+    pub fn account_environments(&self) -> HashMap<String, String> {
+        self.environments
+            .iter()
+            .flatten()
+            .filter_map(|env| {
+                env.aws_account_id
+                    .as_ref()
+                    .map(|account| (account.clone(), env.name.clone()))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -689,10 +1339,15 @@ repos:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -717,10 +1372,15 @@ repos:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -742,10 +1402,15 @@ repos:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec!["*-deprecated".to_string(), "fork-*".to_string()],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -755,6 +1420,7 @@ repos:
         assert!(config.is_excluded("old-service-deprecated"));
         assert!(config.is_excluded("fork-some-repo"));
         assert!(!config.is_excluded("main-service"));
+        assert_eq!(config.exclude_pattern("fork-some-repo"), Some("fork-*"));
     }
 
     #[test]
@@ -765,12 +1431,17 @@ repos:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig {
                 exclude: vec!["terraform".to_string(), "Python".to_string()],
             },
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -898,6 +1569,350 @@ environments:
         assert_eq!(dev.name, "development");
         assert!(dev.aws_account_id.is_none());
         assert_eq!(dev.local_only, Some(true));
+
+        let accounts = config.account_environments();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts["987654321098"], "staging");
+    }
+
+    #[test]
+    fn test_load_config_with_terraform_state() {
+        let yaml = r#"
+repos:
+  local_paths:
+    - ./services
+survey:
+  terraform_state:
+    - infra/terraform.tfstate
+    - infra/prod.json
+"#;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, yaml).unwrap();
+
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(
+            config.survey.terraform_state,
+            vec![
+                PathBuf::from("infra/terraform.tfstate"),
+                PathBuf::from("infra/prod.json")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_service_hosts() {
+        let yaml = r#"
+repos:
+  local_paths:
+    - ./services
+survey:
+  service_hosts:
+    orders.example.com: orders-api
+    Billing.Internal: billing
+"#;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, yaml).unwrap();
+
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(config.survey.service_hosts.len(), 2);
+        assert_eq!(
+            config.survey.service_hosts["orders.example.com"],
+            "orders-api"
+        );
+        assert!(config.survey.terraform_state.is_empty());
+    }
+
+    #[test]
+    fn test_load_config_with_package_overrides() {
+        let yaml = r#"
+repos:
+  local_paths:
+    - ./platform
+survey:
+  packages:
+    - path: services/billing
+      name: billing-api
+    - repos: ["acme/platform"]
+      path: tools
+      exclude: true
+"#;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, yaml).unwrap();
+
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(config.survey.packages.len(), 2);
+        assert_eq!(
+            config.survey.packages[0].name.as_deref(),
+            Some("billing-api")
+        );
+        assert!(!config.survey.packages[0].exclude);
+
+        let platform = config.package_overrides("acme/platform");
+        assert_eq!(platform.len(), 2);
+        assert!(platform[1].exclude);
+        let other = config.package_overrides("acme/web");
+        assert_eq!(other.len(), 1);
+        assert_eq!(other[0].path, PathBuf::from("services/billing"));
+    }
+
+    #[test]
+    fn test_load_config_with_parallelism() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  parallelism: 8\n",
+        )
+        .unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(config.survey.parallelism, Some(8));
+
+        std::fs::write(&path, "repos:\n  local_paths: [./app]\n").unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(config.survey.parallelism, None);
+    }
+
+    #[test]
+    fn test_load_config_with_plugins() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(
+            &path,
+            r#"
+repos:
+  local_paths: [./app]
+survey:
+  plugins:
+    - name: elixir
+      command: forge-elixir
+      args: ["--json"]
+      extensions: ["ex", "exs"]
+"#,
+        )
+        .unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        let plugin = &config.survey.plugins[0];
+        assert_eq!(plugin.name, "elixir");
+        assert_eq!(plugin.args, vec!["--json"]);
+        assert_eq!(plugin.extensions, vec!["ex", "exs"]);
+        assert_eq!(plugin.timeout_secs, 60);
+        assert_eq!(plugin.version, 1);
+
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  plugins:\n    - name: elixir\n      command: forge-elixir\n      extensions: []\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("no extensions"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_config_with_rules() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(
+            &path,
+            r#"
+repos:
+  local_paths: [./app]
+survey:
+  rules:
+    - name: acme-db
+      languages: [javascript]
+      call: "acme.table($name).$op()"
+      kind: database
+      type: dynamodb
+      operations:
+        put: write
+        get: read
+"#,
+        )
+        .unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        let rule = &config.survey.rules[0];
+        assert_eq!(rule.kind, RuleKind::Database);
+        assert_eq!(rule.resource_type.as_deref(), Some("dynamodb"));
+        assert_eq!(rule.name_capture, "name");
+        assert_eq!(rule.operation_capture, "op");
+        assert_eq!(rule.operations["put"], "write");
+
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  rules:\n    - name: acme-db\n      kind: database\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(
+            err.to_string().contains("call pattern or a query"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_ownership() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, "repos:\n  local_paths: [./app]\n").unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert!(config.survey.ownership.enabled);
+        assert_eq!(config.survey.ownership.history_days, 180);
+        assert_eq!(config.survey.ownership.max_committers, 3);
+
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  ownership:\n    history_days: 0\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("history_days"), "{}", err);
+
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  ownership:\n    enabled: false\n    history_days: 0\n",
+        )
+        .unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert!(!config.survey.ownership.enabled);
+    }
+
+    #[test]
+    fn test_load_config_with_activity() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, "repos:\n  local_paths: [./app]\n").unwrap();
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert!(config.survey.activity.enabled);
+        assert_eq!(config.survey.activity.window_days, 90);
+
+        std::fs::write(
+            &path,
+            "repos:\n  local_paths: [./app]\nsurvey:\n  activity:\n    window_days: 0\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("window_days"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_config_with_other_sources() {
+        let yaml = r#"
+repos:
+  gitlab_groups: [acme/platform]
+  gitlab_projects: [acme/platform/finance/ledger]
+  bitbucket_workspaces: [acme]
+  bitbucket_projects: [acme/PLAT]
+  bitbucket_repos: [acme/billing]
+  gitea_orgs: [infra]
+  gitea_repos: [infra/terraform]
+gitlab:
+  url: "https://gitlab.acme.com"
+bitbucket:
+  username: jdoe
+  clone_method: ssh
+gitea:
+  url: "https://git.acme.com"
+  token_env: ACME_GITEA_TOKEN
+"#;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, yaml).unwrap();
+
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        assert_eq!(config.repos.gitlab_groups, vec!["acme/platform"]);
+        assert_eq!(config.repos.bitbucket_projects, vec!["acme/PLAT"]);
+        assert_eq!(config.gitlab.url, "https://gitlab.acme.com");
+        assert_eq!(config.gitlab.token_env, "GITLAB_TOKEN");
+        assert_eq!(config.bitbucket.api_url, "https://api.bitbucket.org/2.0");
+        assert_eq!(config.bitbucket.username.as_deref(), Some("jdoe"));
+        assert_eq!(config.bitbucket.clone_method, CloneMethod::Ssh);
+        assert_eq!(config.gitea.token_env, "ACME_GITEA_TOKEN");
+    }
+
+    #[test]
+    fn test_load_config_with_filters() {
+        let yaml = r#"
+repos:
+  github_org: acme
+  filters:
+    include:
+      topics: [payments]
+      visibility: [private, internal]
+      pushed_within_days: 180
+    exclude:
+      archived: true
+      fork: true
+      languages: [HCL]
+      name_regex: "-(legacy|sandbox)$"
+"#;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        std::fs::write(&path, yaml).unwrap();
+
+        let config = ForgeConfig::load_from_path(&path).unwrap();
+        let filters = &config.repos.filters;
+        assert_eq!(filters.include.topics, vec!["payments"]);
+        assert_eq!(
+            filters.include.visibility,
+            vec![RepoVisibility::Private, RepoVisibility::Internal]
+        );
+        assert_eq!(filters.include.pushed_within_days, Some(180));
+        assert_eq!(filters.include.archived, None);
+        assert_eq!(filters.exclude.archived, Some(true));
+        assert_eq!(
+            filters.exclude.name_regex.as_deref(),
+            Some("-(legacy|sandbox)$")
+        );
+
+        std::fs::write(
+            &path,
+            "repos:\n  github_org: acme\n  filters:\n    exclude:\n      name_regex: \"(\"\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(
+            err.to_string().contains("repos.filters.exclude.name_regex"),
+            "{}",
+            err
+        );
+
+        std::fs::write(
+            &path,
+            "repos:\n  github_org: acme\n  filters:\n    include:\n      pushed_within_days: 0\n",
+        )
+        .unwrap();
+        let err = ForgeConfig::load_from_path(&path).unwrap_err();
+        assert!(err.to_string().contains("pushed_within_days"), "{}", err);
+    }
+
+    #[test]
+    fn test_config_validation_other_sources() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("forge.yaml");
+        let cases = [
+            (
+                "repos:\n  gitlab_projects: [ledger]\n",
+                "Invalid GitLab project",
+            ),
+            (
+                "repos:\n  bitbucket_projects: [acme/PLAT/x]\n",
+                "Expected 'workspace/PROJECT_KEY'",
+            ),
+            (
+                "repos:\n  gitea_repos: [/terraform]\n",
+                "Expected 'owner/repo'",
+            ),
+            ("repos:\n  gitea_orgs: [infra]\n", "gitea.url is required"),
+        ];
+        for (yaml, message) in cases {
+            std::fs::write(&path, yaml).unwrap();
+            let err = ForgeConfig::load_from_path(&path).unwrap_err();
+            assert!(err.to_string().contains(message), "{}", err);
+        }
     }
 
     #[test]
@@ -908,10 +1923,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -936,10 +1956,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -973,10 +1998,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -1015,10 +2045,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -1043,10 +2078,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -1066,10 +2106,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -1110,10 +2155,15 @@ environments:
                 github_repos: vec![],
                 local_paths: vec![],
                 exclude: vec![],
+                ..Default::default()
             },
             github: GitHubConfig::default(),
+            gitlab: GitLabConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitea: GiteaConfig::default(),
             languages: LanguageConfig::default(),
             output: OutputConfig::default(),
+            survey: SurveyConfig::default(),
             llm: LLMConfig::default(),
             token_budget: 8000,
             staleness_days: default_staleness_days(),
@@ -792,6 +792,7 @@ mod tests {
         let context = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![
                 "Rate limit is 1000 req/sec".to_string(),
@@ -815,6 +816,7 @@ mod tests {
         let context = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Email validation is required before writes".to_string()],
             notes: Default::default(),
@@ -835,6 +837,7 @@ mod tests {
         let context = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Never bypass the cache layer".to_string()],
             notes: Default::default(),
@@ -856,6 +859,7 @@ mod tests {
         let context = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Always use transactions for multi-table updates".to_string()],
             notes: Default::default(),
@@ -891,6 +895,7 @@ mod tests {
         let auth_context = BusinessContext {
             purpose: Some("Token validation".to_string()),
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: Default::default(),
@@ -1003,6 +1008,7 @@ mod tests {
         let auth_context = BusinessContext {
             purpose: Some("Token validation".to_string()),
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: Default::default(),
@@ -1035,6 +1041,7 @@ mod tests {
         let main_context = BusinessContext {
             purpose: Some("Main API service".to_string()),
             owner: Some("Platform Team".to_string()),
+            owner_provenance: None,
             history: None,
             gotchas: vec![
                 "Rate limit is 1000 req/sec".to_string(),
@@ -9,6 +9,8 @@
 //! - `forge init` - Initialize a new configuration file
 //! - `forge survey` - Survey repositories and build the knowledge graph
 //! - `forge map` - Serialize the knowledge graph to various formats
+//! - `forge cache` - Inspect or clear the per-file discovery cache
+//! - `forge repos` - Show which repositories a survey covers
 //!
 //! # Usage
 //!
@@ -93,6 +95,18 @@ enum Commands {
         /// Only re-parse changed files
         #[arg(long)]
         incremental: bool,
+
+        /// Terraform state files to enrich resources from (comma-separated)
+        #[arg(long)]
+        tfstate: Option<String>,
+
+        /// Parser threads (default: survey.parallelism, or one per CPU)
+        #[arg(long, short)]
+        jobs: Option<usize>,
+
+        /// Parse every file, ignoring the discovery cache
+        #[arg(long)]
+        no_cache: bool,
     },
 
     /// Serialize the knowledge graph to various formats
@@ -125,6 +139,41 @@ enum Commands {
         #[arg(long, short)]
         output: Option<String>,
     },
+
+    /// Inspect or clear the per-file discovery cache
+    Cache {
+        /// Path to the configuration file
+        #[arg(long, short, global = true)]
+        config: Option<String>,
+
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+
+    /// Show which repositories a survey covers
+    Repos {
+        /// Path to the configuration file
+        #[arg(long, short, global = true)]
+        config: Option<String>,
+
+        #[command(subcommand)]
+        action: ReposCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show the number and size of cached files
+    Stats,
+
+    /// Delete every cached file
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum ReposCommand {
+    /// List the repositories forge.yaml lists, and why each is or isn't surveyed
+    List,
 }
 
 fn main() {
@@ -147,6 +196,9 @@ fn main() {
             exclude_lang,
             business_context,
             incremental,
+            tfstate,
+            jobs,
+            no_cache,
         } => {
             let options = commands::SurveyOptions {
                 config,
@@ -155,6 +207,9 @@ fn main() {
                 exclude_lang,
                 business_context,
                 incremental,
+                tfstate,
+                jobs,
+                no_cache,
             };
             // Survey is async, so we need a tokio runtime
             match tokio::runtime::Runtime::new() {
@@ -184,6 +239,31 @@ fn main() {
             };
             commands::run_map(options).map_err(|e| e.to_string())
         }
+        Commands::Cache { config, action } => {
+            let options = commands::CacheOptions {
+                config,
+                action: match action {
+                    CacheCommand::Stats => commands::CacheAction::Stats,
+                    CacheCommand::Clear => commands::CacheAction::Clear,
+                },
+            };
+            commands::run_cache(options).map_err(|e| e.to_string())
+        }
+        Commands::Repos { config, action } => {
+            let options = commands::ReposOptions {
+                config,
+                action: match action {
+                    ReposCommand::List => commands::ReposAction::List,
+                },
+            };
+            // Discovery is async, so we need a tokio runtime
+            match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime
+                    .block_on(commands::run_repos(options))
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(format!("Failed to create tokio runtime: {}", e)),
+            }
+        }
     };
 
     if let Err(e) = result {
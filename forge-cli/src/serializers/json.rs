@@ -639,6 +639,7 @@ mod tests {
         service.business_context = Some(forge_graph::BusinessContext {
             purpose: Some("Handles authentication".to_string()),
             owner: Some("Platform Team".to_string()),
+            owner_provenance: None,
             history: Some("Migrated in 2023".to_string()),
             gotchas: vec!["Rate limited".to_string()],
             notes: Default::default(),
@@ -797,6 +798,7 @@ mod tests {
             .business_context(forge_graph::BusinessContext {
                 purpose: Some("Token validation".to_string()),
                 owner: None,
+                owner_provenance: None,
                 history: None,
                 gotchas: vec![],
                 notes: Default::default(),
@@ -884,6 +886,7 @@ mod tests {
             .business_context(forge_graph::BusinessContext {
                 purpose: Some("API service".to_string()),
                 owner: Some("Platform Team".to_string()),
+                owner_provenance: None,
                 history: None,
                 gotchas: vec![
                     "Rate limit is 1000 req/sec".to_string(),
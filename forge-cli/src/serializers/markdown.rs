@@ -31,7 +31,9 @@
 //! | users-table | Reads, Writes | src/db/users.ts:15 |
 //! ```
 
-use forge_graph::{EdgeType, ExtractedSubgraph, ForgeGraph, Node, NodeType, ScoredNode};
+use forge_graph::{
+    EdgeType, ExtractedSubgraph, ForgeGraph, GitActivity, Node, NodeType, ScoredNode,
+};
 use std::fmt::Write;
 
 /// Detail level for markdown output.
@@ -232,6 +234,27 @@ impl MarkdownSerializer {
             writeln!(output, "**Repository**: {}\n", repo_url).unwrap();
         }
 
+        // Git activity if measured
+        if let Some(activity) = GitActivity::from_attributes(&node.attributes) {
+            write!(
+                output,
+                "**Activity** (last {} days): {} commits by {} authors, {} lines changed in {} files",
+                activity.window_days,
+                activity.commits,
+                activity.authors,
+                activity.lines_changed,
+                activity.files_changed
+            )
+            .unwrap();
+            if let Some(date) = activity.last_commit {
+                write!(output, " | **Last commit**: {}", date.format("%Y-%m-%d")).unwrap();
+            }
+            if let Some(date) = activity.first_commit {
+                write!(output, " | **First commit**: {}", date.format("%Y-%m-%d")).unwrap();
+            }
+            writeln!(output, "\n").unwrap();
+        }
+
         // Staleness indicator
         if self.staleness_days > 0 && node.metadata.is_stale(self.staleness_days) {
             let age_desc = node.metadata.staleness_description();
@@ -250,7 +273,16 @@ impl MarkdownSerializer {
                     writeln!(output, "**Purpose**: {}\n", purpose).unwrap();
                 }
                 if let Some(owner) = &ctx.owner {
-                    writeln!(output, "**Owner**: {}\n", owner).unwrap();
+                    match &ctx.owner_provenance {
+                        Some(provenance) => writeln!(
+                            output,
+                            "**Owner**: {} (inferred from {})\n",
+                            owner,
+                            provenance.source.description()
+                        )
+                        .unwrap(),
+                        None => writeln!(output, "**Owner**: {}\n", owner).unwrap(),
+                    }
                 }
                 if let Some(history) = &ctx.history {
                     writeln!(output, "**History**: {}\n", history).unwrap();
@@ -1188,6 +1220,7 @@ mod tests {
         service.business_context = Some(forge_graph::BusinessContext {
             purpose: Some("Handles authentication and authorization".to_string()),
             owner: Some("Platform Team".to_string()),
+            owner_provenance: None,
             history: Some("Migrated from monolith in 2023".to_string()),
             gotchas: vec!["Rate limited to 100 req/s".to_string()],
             notes: Default::default(),
@@ -1205,6 +1238,53 @@ mod tests {
         assert!(output.contains("Rate limited to 100 req/s"));
     }
 
+    #[test]
+    fn test_service_with_git_activity() {
+        let mut graph = ForgeGraph::new();
+
+        let mut service = create_test_service("ns", "auth-api", "Auth API");
+        let activity = GitActivity {
+            window_days: 90,
+            commits: 42,
+            authors: 1,
+            lines_changed: 1200,
+            files_changed: 35,
+            last_commit: Some("2026-10-01T12:00:00Z".parse().unwrap()),
+            first_commit: None,
+        };
+        service.attributes.extend(activity.to_attributes());
+        graph.add_node(service).unwrap();
+
+        let output = MarkdownSerializer::new().serialize_graph(&graph);
+
+        assert!(output.contains(
+            "**Activity** (last 90 days): 42 commits by 1 authors, 1200 lines changed in 35 files | **Last commit**: 2026-10-01\n"
+        ));
+        assert!(!output.contains("**First commit**"));
+    }
+
+    #[test]
+    fn test_service_with_inferred_owner() {
+        let mut graph = ForgeGraph::new();
+
+        let mut service = create_test_service("ns", "auth-api", "Auth API");
+        service.business_context = Some(forge_graph::BusinessContext {
+            owner: Some("@acme/identity".to_string()),
+            owner_provenance: Some(forge_graph::OwnerProvenance {
+                source: forge_graph::OwnerSource::Codeowners,
+                evidence: vec![],
+            }),
+            ..Default::default()
+        });
+
+        graph.add_node(service).unwrap();
+
+        let serializer = MarkdownSerializer::new().with_business_context(true);
+        let output = serializer.serialize_graph(&graph);
+
+        assert!(output.contains("**Owner**: @acme/identity (inferred from CODEOWNERS)"));
+    }
+
     #[test]
     fn test_service_without_business_context() {
         let mut graph = ForgeGraph::new();
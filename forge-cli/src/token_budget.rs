@@ -847,13 +847,13 @@ mod tests {
         // A typical sentence should be 10-20 tokens
         let sentence = "The quick brown fox jumps over the lazy dog.";
         let count = counter.count(sentence);
-        assert!(count >= 8 && count <= 15, "Sentence token count: {}", count);
+        assert!((8..=15).contains(&count), "Sentence token count: {}", count);
 
         // Code-like content
         let code = "function getData() { return fetch('/api/data'); }";
         let code_count = counter.count(code);
         assert!(
-            code_count >= 10 && code_count <= 25,
+            (10..=25).contains(&code_count),
             "Code token count: {}",
             code_count
         );
@@ -1,7 +1,7 @@
 //! Edge types and structures for the knowledge graph.
 
 use crate::error::EdgeError;
-use crate::node::{NodeId, NodeType};
+use crate::node::{DiscoverySource, NodeId, NodeType};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -114,6 +114,14 @@ pub struct EdgeMetadata {
     /// Whether this edge was manually confirmed
     #[serde(default)]
     pub confirmed: bool,
+
+    /// Where the edge came from, when it was declared rather than inferred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery_source: Option<DiscoverySource>,
+
+    /// Labels describing the edge's provenance (e.g., "repo-declared")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 impl EdgeMetadata {
@@ -68,8 +68,8 @@ pub use edge::{Edge, EdgeMetadata, EdgeType};
 pub use error::{EdgeError, GraphError};
 pub use graph::{ForgeGraph, GraphMetadata, GraphSnapshot};
 pub use node::{
-    AttributeValue, BusinessContext, DiscoverySource, Node, NodeBuilder, NodeBuilderError, NodeId,
-    NodeIdError, NodeMetadata, NodeType,
+    AttributeValue, BusinessContext, DiscoverySource, GitActivity, Node, NodeBuilder,
+    NodeBuilderError, NodeId, NodeIdError, NodeMetadata, NodeType, OwnerProvenance, OwnerSource,
 };
 pub use query::{ExtractedSubgraph, ScoredNode, SubgraphConfig, TraversalDirection};
 
@@ -345,6 +345,7 @@ mod integration_tests {
         let business_ctx = BusinessContext {
             purpose: Some("Handles user authentication".to_string()),
             owner: Some("Platform Team".to_string()),
+            owner_provenance: None,
             history: Some("Migrated from monolith in 2023".to_string()),
             gotchas: vec!["Rate limited to 100 req/s".to_string()],
             notes: Default::default(),
@@ -225,6 +225,11 @@ pub struct BusinessContext {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owner: Option<String>,
 
+    /// How the owner was inferred during survey; `None` when it was
+    /// answered in the interview or declared by the repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_provenance: Option<OwnerProvenance>,
+
     /// Historical context / why was it built this way?
     #[serde(skip_serializing_if = "Option::is_none")]
     pub history: Option<String>,
@@ -259,6 +264,7 @@ impl BusinessContext {
 
         if self.owner.is_none() {
             self.owner = other.owner.clone();
+            self.owner_provenance = other.owner_provenance.clone();
         }
 
         if self.history.is_none() {
@@ -279,6 +285,119 @@ impl BusinessContext {
             }
         }
     }
+
+    /// Whether the owner was inferred rather than confirmed.
+    pub fn has_inferred_owner(&self) -> bool {
+        self.owner.is_some() && self.owner_provenance.is_some()
+    }
+}
+
+/// Where an inferred owner came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnerSource {
+    /// CODEOWNERS rules covering the component's files
+    Codeowners,
+    /// The component's most frequent recent committers
+    GitHistory,
+}
+
+impl OwnerSource {
+    /// Get a human-readable description of the source.
+    pub fn description(&self) -> &'static str {
+        match self {
+            OwnerSource::Codeowners => "CODEOWNERS",
+            OwnerSource::GitHistory => "git history",
+        }
+    }
+}
+
+/// How an owner was inferred during survey.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnerProvenance {
+    /// What the owner was inferred from
+    pub source: OwnerSource,
+
+    /// What the inference rests on (e.g., "CODEOWNERS:12 /billing/",
+    /// "42 commits by Jane Doe")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<String>,
+}
+
+/// Git activity of a component over a recent window.
+///
+/// Stored as node attributes prefixed `git_`, so it survives serialization
+/// without a schema change. Dates are RFC 3339 strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitActivity {
+    /// Days of history the counts cover
+    pub window_days: u32,
+
+    /// Non-merge commits in the window
+    pub commits: u64,
+
+    /// Distinct human authors in the window (the bus factor)
+    pub authors: u64,
+
+    /// Lines added plus lines deleted in the window
+    pub lines_changed: u64,
+
+    /// Distinct files changed in the window
+    pub files_changed: u64,
+
+    /// Date of the latest commit, in or before the window
+    pub last_commit: Option<DateTime<Utc>>,
+
+    /// Date of the first commit; `None` when history is truncated before it
+    pub first_commit: Option<DateTime<Utc>>,
+}
+
+impl GitActivity {
+    /// The node attributes holding this activity.
+    pub fn to_attributes(&self) -> Vec<(String, AttributeValue)> {
+        let count = |n: u64| AttributeValue::Integer(i64::try_from(n).unwrap_or(i64::MAX));
+        let mut attributes = vec![
+            (
+                "git_window_days".to_string(),
+                count(self.window_days.into()),
+            ),
+            ("git_commits".to_string(), count(self.commits)),
+            ("git_authors".to_string(), count(self.authors)),
+            ("git_lines_changed".to_string(), count(self.lines_changed)),
+            ("git_files_changed".to_string(), count(self.files_changed)),
+        ];
+        if let Some(date) = self.last_commit {
+            attributes.push(("git_last_commit".to_string(), date.to_rfc3339().into()));
+        }
+        if let Some(date) = self.first_commit {
+            attributes.push(("git_first_commit".to_string(), date.to_rfc3339().into()));
+        }
+        attributes
+    }
+
+    /// Read the activity from node attributes, or `None` when the node has
+    /// none.
+    pub fn from_attributes(attributes: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let count = |key: &str| match attributes.get(key) {
+            Some(AttributeValue::Integer(n)) => u64::try_from(*n).ok(),
+            _ => None,
+        };
+        let date = |key: &str| match attributes.get(key) {
+            Some(AttributeValue::String(s)) => DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|d| d.with_timezone(&Utc)),
+            _ => None,
+        };
+        Some(Self {
+            window_days: u32::try_from(count("git_window_days")?).ok()?,
+            commits: count("git_commits")?,
+            authors: count("git_authors").unwrap_or_default(),
+            lines_changed: count("git_lines_changed").unwrap_or_default(),
+            files_changed: count("git_files_changed").unwrap_or_default(),
+            last_commit: date("git_last_commit"),
+            first_commit: date("git_first_commit"),
+        })
+    }
 }
 
 /// Where a node was discovered from.
@@ -662,8 +781,8 @@ mod tests {
             let b: AttributeValue = true.into();
             assert_eq!(b, AttributeValue::Boolean(true));
 
-            let f: AttributeValue = 3.14f64.into();
-            assert_eq!(f, AttributeValue::Float(3.14));
+            let f: AttributeValue = 2.5f64.into();
+            assert_eq!(f, AttributeValue::Float(2.5));
         }
     }
 
@@ -683,9 +802,11 @@ mod tests {
 
         #[test]
         fn test_is_stale_old_node() {
-            let mut metadata = NodeMetadata::default();
-            // Set updated_at to 10 days ago
-            metadata.updated_at = Utc::now() - Duration::days(10);
+            let metadata = NodeMetadata {
+                // Set updated_at to 10 days ago
+                updated_at: Utc::now() - Duration::days(10),
+                ..Default::default()
+            };
             assert!(
                 metadata.is_stale(7),
                 "Node updated 10 days ago should be stale (threshold: 7)"
@@ -694,9 +815,11 @@ mod tests {
 
         #[test]
         fn test_is_stale_boundary() {
-            let mut metadata = NodeMetadata::default();
-            // Set updated_at to exactly 7 days ago (minus 1 second to avoid timing issues)
-            metadata.updated_at = Utc::now() - Duration::days(7) + Duration::seconds(1);
+            let mut metadata = NodeMetadata {
+                // Set updated_at to exactly 7 days ago (minus 1 second to avoid timing issues)
+                updated_at: Utc::now() - Duration::days(7) + Duration::seconds(1),
+                ..Default::default()
+            };
             // Should not be stale if just under threshold
             assert!(
                 !metadata.is_stale(7),
@@ -716,50 +839,64 @@ mod tests {
 
         #[test]
         fn test_staleness_description_yesterday() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(1);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(1),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated yesterday");
         }
 
         #[test]
         fn test_staleness_description_days() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(5);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(5),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated 5 days ago");
         }
 
         #[test]
         fn test_staleness_description_one_week() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(7);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(7),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated 1 week ago");
         }
 
         #[test]
         fn test_staleness_description_weeks() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(14);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(14),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated 2 weeks ago");
         }
 
         #[test]
         fn test_staleness_description_one_month() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(30);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(30),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated 1 month ago");
         }
 
         #[test]
         fn test_staleness_description_months() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(60);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(60),
+                ..Default::default()
+            };
             assert_eq!(metadata.staleness_description(), "Updated 2 months ago");
         }
 
         #[test]
         fn test_age_days() {
-            let mut metadata = NodeMetadata::default();
-            metadata.updated_at = Utc::now() - Duration::days(10);
+            let metadata = NodeMetadata {
+                updated_at: Utc::now() - Duration::days(10),
+                ..Default::default()
+            };
             assert_eq!(metadata.age_days(), 10);
         }
 
@@ -769,4 +906,23 @@ mod tests {
             assert_eq!(metadata.age_days(), 0);
         }
     }
+
+    #[test]
+    fn test_git_activity_attributes_round_trip() {
+        let activity = GitActivity {
+            window_days: 90,
+            commits: 42,
+            authors: 2,
+            lines_changed: 1200,
+            files_changed: 35,
+            last_commit: Some("2026-10-01T12:00:00Z".parse().unwrap()),
+            first_commit: None,
+        };
+        let attributes: HashMap<String, AttributeValue> =
+            activity.to_attributes().into_iter().collect();
+        assert_eq!(attributes["git_commits"], AttributeValue::Integer(42));
+        assert!(!attributes.contains_key("git_first_commit"));
+        assert_eq!(GitActivity::from_attributes(&attributes), Some(activity));
+        assert_eq!(GitActivity::from_attributes(&HashMap::new()), None);
+    }
 }
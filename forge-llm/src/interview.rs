@@ -31,7 +31,7 @@
 //! }
 //! ```
 
-use forge_graph::{EdgeType, ForgeGraph, Node, NodeId, NodeType};
+use forge_graph::{EdgeType, ForgeGraph, GitActivity, Node, NodeId, NodeType};
 use std::collections::HashMap;
 
 /// Score representing the need for business context on a node.
@@ -80,6 +80,14 @@ pub enum GapReason {
     /// No owner documented
     MissingOwner,
 
+    /// Owner inferred by the survey but never confirmed
+    UnconfirmedOwner {
+        /// The inferred owner
+        owner: String,
+        /// Where it was inferred from
+        source: String,
+    },
+
     /// High connectivity (central to architecture)
     HighCentrality {
         /// Total number of edges (incoming + outgoing)
@@ -103,6 +111,16 @@ pub enum GapReason {
         /// Signals indicating complexity
         complexity_signals: Vec<String>,
     },
+
+    /// Changing fast, but known to few people (low bus factor)
+    ConcentratedChurn {
+        /// Lines changed in the activity window
+        lines_changed: u64,
+        /// Distinct authors in the activity window
+        authors: u64,
+        /// Days the activity window covers
+        window_days: u32,
+    },
 }
 
 impl GapReason {
@@ -111,6 +129,9 @@ impl GapReason {
         match self {
             GapReason::MissingPurpose => "No business purpose documented".to_string(),
             GapReason::MissingOwner => "No owner documented".to_string(),
+            GapReason::UnconfirmedOwner { owner, source } => {
+                format!("Owner '{}' inferred from {} is unconfirmed", owner, source)
+            }
             GapReason::HighCentrality { edge_count } => {
                 format!("High centrality with {} connections", edge_count)
             }
@@ -129,6 +150,16 @@ impl GapReason {
                     complexity_signals.join(", ")
                 )
             }
+            GapReason::ConcentratedChurn {
+                lines_changed,
+                authors,
+                window_days,
+            } => {
+                format!(
+                    "{} lines changed by {} author(s) in the last {} days",
+                    lines_changed, authors, window_days
+                )
+            }
         }
     }
 }
@@ -148,6 +179,9 @@ pub struct GapAnalysisConfig {
     /// Score contribution for missing owner
     pub missing_owner_score: f64,
 
+    /// Score contribution for an inferred, unconfirmed owner
+    pub inferred_owner_score: f64,
+
     /// Maximum score contribution from centrality
     pub max_centrality_score: f64,
 
@@ -159,6 +193,16 @@ pub struct GapAnalysisConfig {
 
     /// Score contribution for complex service without gotchas
     pub complex_without_gotchas_score: f64,
+
+    /// Minimum lines changed in the activity window to consider a service
+    /// "high churn"
+    pub high_churn_threshold: u64,
+
+    /// Maximum distinct authors for high churn to count as concentrated
+    pub few_authors_threshold: u64,
+
+    /// Score contribution for high churn by few authors
+    pub concentrated_churn_score: f64,
 }
 
 impl Default for GapAnalysisConfig {
@@ -168,10 +212,14 @@ impl Default for GapAnalysisConfig {
             complexity_threshold: 3,
             missing_purpose_score: 0.3,
             missing_owner_score: 0.2,
+            inferred_owner_score: 0.1,
             max_centrality_score: 0.2,
             implicit_coupling_score: 0.15,
             shared_resource_score: 0.25,
             complex_without_gotchas_score: 0.1,
+            high_churn_threshold: 500,
+            few_authors_threshold: 1,
+            concentrated_churn_score: 0.15,
         }
     }
 }
@@ -266,6 +314,23 @@ fn analyze_service_gaps(
 
     if !has_owner {
         gap_score.add_reason(GapReason::MissingOwner, config.missing_owner_score);
+    } else if let Some(bc) = service
+        .business_context
+        .as_ref()
+        .filter(|bc| bc.has_inferred_owner())
+    {
+        // An inferred owner only partly fills the gap until confirmed
+        gap_score.add_reason(
+            GapReason::UnconfirmedOwner {
+                owner: bc.owner.clone().unwrap_or_default(),
+                source: bc
+                    .owner_provenance
+                    .as_ref()
+                    .map(|p| p.source.description().to_string())
+                    .unwrap_or_default(),
+            },
+            config.inferred_owner_score,
+        );
     }
 
     // Check centrality (edge count)
@@ -295,6 +360,23 @@ fn analyze_service_gaps(
         );
     }
 
+    // Check for heavy recent change known to few people
+    if let Some(activity) = GitActivity::from_attributes(&service.attributes) {
+        if activity.lines_changed >= config.high_churn_threshold
+            && activity.authors > 0
+            && activity.authors <= config.few_authors_threshold
+        {
+            gap_score.add_reason(
+                GapReason::ConcentratedChurn {
+                    lines_changed: activity.lines_changed,
+                    authors: activity.authors,
+                    window_days: activity.window_days,
+                },
+                config.concentrated_churn_score,
+            );
+        }
+    }
+
     // Check for gotchas in complex services
     let has_gotchas = service
         .business_context
@@ -518,6 +600,9 @@ pub fn generate_questions(
             GapReason::MissingOwner => {
                 questions.push(generate_owner_question(node));
             }
+            GapReason::UnconfirmedOwner { owner, source } => {
+                questions.push(generate_owner_confirmation_question(node, owner, source));
+            }
             GapReason::HighCentrality { edge_count } => {
                 questions.push(generate_centrality_question(node, *edge_count, graph));
             }
@@ -530,6 +615,18 @@ pub fn generate_questions(
             GapReason::ComplexWithoutGotchas { .. } => {
                 questions.push(generate_gotcha_question(node, graph));
             }
+            GapReason::ConcentratedChurn {
+                lines_changed,
+                authors,
+                window_days,
+            } => {
+                questions.push(generate_churn_question(
+                    node,
+                    *lines_changed,
+                    *authors,
+                    *window_days,
+                ));
+            }
         }
     }
 
@@ -555,7 +652,7 @@ pub fn generate_all_questions(graph: &ForgeGraph) -> Vec<InterviewQuestion> {
         .collect();
 
     // Sort by priority (highest first)
-    all_questions.sort_by(|a, b| b.priority.cmp(&a.priority));
+    all_questions.sort_by_key(|q| std::cmp::Reverse(q.priority));
 
     all_questions
 }
@@ -609,6 +706,32 @@ fn generate_owner_question(node: &Node) -> InterviewQuestion {
     )
 }
 
+/// Generate a question confirming an owner the survey inferred.
+fn generate_owner_confirmation_question(
+    node: &Node,
+    owner: &str,
+    source: &str,
+) -> InterviewQuestion {
+    let evidence = node
+        .business_context
+        .as_ref()
+        .and_then(|bc| bc.owner_provenance.as_ref())
+        .map(|p| p.evidence.join("; "))
+        .filter(|e| !e.is_empty())
+        .unwrap_or_else(|| format!("Inferred from {}.", source));
+
+    InterviewQuestion::new(
+        node.id.clone(),
+        format!(
+            "'{}' appears to be owned by {} (inferred from {}). Who owns it? (Repeat the owner to confirm)",
+            node.display_name, owner, source
+        ),
+        AnnotationType::Owner,
+        5, // Lower than a missing owner - a likely answer exists
+        evidence,
+    )
+}
+
 /// Generate a question about why a central service is so connected.
 fn generate_centrality_question(
     node: &Node,
@@ -726,6 +849,29 @@ fn generate_gotcha_question(node: &Node, graph: &ForgeGraph) -> InterviewQuestio
     )
 }
 
+/// Generate a question capturing what only a service's few recent authors
+/// know.
+fn generate_churn_question(
+    node: &Node,
+    lines_changed: u64,
+    authors: u64,
+    window_days: u32,
+) -> InterviewQuestion {
+    InterviewQuestion::new(
+        node.id.clone(),
+        format!(
+            "'{}' is changing quickly but few people work on it. What should someone else know before changing it?",
+            node.display_name
+        ),
+        AnnotationType::Gotcha,
+        6, // Above general gotchas - the knowledge is concentrated
+        format!(
+            "{} lines changed by {} author(s) in the last {} days.",
+            lines_changed, authors, window_days
+        ),
+    )
+}
+
 // ============================================================================
 // Interview Flow (M6-T8)
 // ============================================================================
@@ -861,6 +1007,7 @@ impl InterviewSession {
                         }
                         AnnotationType::Owner => {
                             bc.owner = Some(update.value.clone());
+                            bc.owner_provenance = None;
                         }
                         AnnotationType::History => {
                             bc.history = Some(update.value.clone());
@@ -1155,6 +1302,7 @@ mod tests {
     use super::*;
     use forge_graph::{
         BusinessContext, DiscoverySource, Edge, Node, NodeBuilder, NodeId, NodeType,
+        OwnerProvenance, OwnerSource,
     };
 
     fn create_test_service(namespace: &str, name: &str, display: &str) -> Node {
@@ -1223,6 +1371,121 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_inferred_owner_partly_fills_gap() {
+        let mut graph = ForgeGraph::new();
+
+        let mut node = create_test_service("ns", "svc", "Test Service");
+        node.business_context = Some(BusinessContext {
+            owner: Some("@acme/payments".to_string()),
+            owner_provenance: Some(OwnerProvenance {
+                source: OwnerSource::Codeowners,
+                evidence: vec!["CODEOWNERS:3 /svc/ @acme/payments".to_string()],
+            }),
+            ..Default::default()
+        });
+        graph.add_node(node).unwrap();
+
+        let gaps = analyze_gaps(&graph);
+        let reasons = &gaps[0].reasons;
+        assert!(!reasons.iter().any(|r| matches!(r, GapReason::MissingOwner)));
+        assert!(reasons.contains(&GapReason::UnconfirmedOwner {
+            owner: "@acme/payments".to_string(),
+            source: "CODEOWNERS".to_string(),
+        }));
+
+        let node_id = NodeId::new(NodeType::Service, "ns", "svc").unwrap();
+        let questions = generate_questions(graph.get_node(&node_id).unwrap(), &graph, &gaps[0]);
+        let question = questions
+            .iter()
+            .find(|q| q.annotation_type == AnnotationType::Owner)
+            .unwrap();
+        assert!(question.question.contains("@acme/payments"));
+        assert!(question.context.contains("CODEOWNERS:3"));
+
+        // Confirming the owner drops the provenance
+        let mut session = InterviewSession::new(&graph);
+        while let Some(q) = session.current_question() {
+            if q.annotation_type == AnnotationType::Owner {
+                session.submit_answer("@acme/payments");
+            } else {
+                session.skip();
+            }
+        }
+        session.apply_to_graph(&mut graph);
+        let bc = graph
+            .get_node(&node_id)
+            .unwrap()
+            .business_context
+            .as_ref()
+            .unwrap();
+        assert_eq!(bc.owner.as_deref(), Some("@acme/payments"));
+        assert!(bc.owner_provenance.is_none());
+        let gaps = analyze_gaps(&graph);
+        assert!(
+            !gaps[0]
+                .reasons
+                .iter()
+                .any(|r| matches!(r, GapReason::UnconfirmedOwner { .. }))
+        );
+    }
+
+    #[test]
+    fn test_detect_concentrated_churn() {
+        let mut graph = ForgeGraph::new();
+
+        let mut busy = create_test_service("ns", "busy", "Busy Service");
+        let activity = GitActivity {
+            window_days: 90,
+            commits: 30,
+            authors: 1,
+            lines_changed: 2000,
+            files_changed: 20,
+            ..Default::default()
+        };
+        busy.attributes.extend(activity.to_attributes());
+        graph.add_node(busy).unwrap();
+
+        let mut shared = create_test_service("ns", "shared", "Shared Service");
+        let activity = GitActivity {
+            authors: 4,
+            ..activity
+        };
+        shared.attributes.extend(activity.to_attributes());
+        graph.add_node(shared).unwrap();
+
+        let gaps = analyze_gaps(&graph);
+        let reasons = |name: &str| {
+            let id = NodeId::new(NodeType::Service, "ns", name).unwrap();
+            gaps.iter()
+                .find(|g| g.node_id == id)
+                .unwrap()
+                .reasons
+                .clone()
+        };
+        assert!(reasons("busy").contains(&GapReason::ConcentratedChurn {
+            lines_changed: 2000,
+            authors: 1,
+            window_days: 90,
+        }));
+        assert!(
+            !reasons("shared")
+                .iter()
+                .any(|r| matches!(r, GapReason::ConcentratedChurn { .. }))
+        );
+        // The busy service is asked about first
+        assert_eq!(gaps[0].node_id.name(), "busy");
+
+        let id = NodeId::new(NodeType::Service, "ns", "busy").unwrap();
+        let questions = generate_questions(graph.get_node(&id).unwrap(), &graph, &gaps[0]);
+        assert!(
+            questions
+                .iter()
+                .any(|q| q.annotation_type == AnnotationType::Gotcha
+                    && q.context.contains("2000 lines changed by 1 author(s)"))
+        );
+    }
+
     #[test]
     fn test_no_gap_when_fully_annotated() {
         let mut graph = ForgeGraph::new();
@@ -1231,6 +1494,7 @@ mod tests {
         node.business_context = Some(BusinessContext {
             purpose: Some("Handles authentication".to_string()),
             owner: Some("Auth Team".to_string()),
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Rate limited".to_string()],
             notes: Default::default(),
@@ -1472,6 +1736,7 @@ mod tests {
         complex.business_context = Some(BusinessContext {
             purpose: Some("Does complex things".to_string()),
             owner: Some("Team X".to_string()),
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Watch out for X".to_string()],
             notes: Default::default(),
@@ -1520,6 +1785,7 @@ mod tests {
         low_gap.business_context = Some(BusinessContext {
             purpose: Some("Has a purpose".to_string()),
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: Default::default(),
@@ -1865,6 +2131,7 @@ mod tests {
         node.business_context = Some(BusinessContext {
             purpose: Some("Handles user authentication".to_string()),
             owner: Some("Auth Team".to_string()),
+            owner_provenance: None,
             history: Some("Built in 2020".to_string()),
             gotchas: vec!["Rate limited to 100 req/s".to_string()],
             notes: Default::default(),
@@ -2103,11 +2370,11 @@ mod tests {
         let bc = updated_node.business_context.as_ref().unwrap();
 
         // Verify annotations were applied
-        if bc.purpose.is_some() {
-            assert_eq!(bc.purpose.as_ref().unwrap(), "Handles authentication");
+        if let Some(purpose) = &bc.purpose {
+            assert_eq!(purpose, "Handles authentication");
         }
-        if bc.owner.is_some() {
-            assert_eq!(bc.owner.as_ref().unwrap(), "Auth Team");
+        if let Some(owner) = &bc.owner {
+            assert_eq!(owner, "Auth Team");
         }
     }
 
@@ -2131,6 +2398,7 @@ mod tests {
         let mut bc1 = BusinessContext {
             purpose: Some("Original purpose".to_string()),
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Gotcha 1".to_string()],
             notes: Default::default(),
@@ -2139,8 +2407,9 @@ mod tests {
         let bc2 = BusinessContext {
             purpose: Some("New purpose".to_string()), // Should NOT overwrite
             owner: Some("New Team".to_string()),      // Should be added
+            owner_provenance: None,
             history: Some("History info".to_string()), // Should be added
-            gotchas: vec!["Gotcha 2".to_string()],    // Should be merged
+            gotchas: vec!["Gotcha 2".to_string()],     // Should be merged
             notes: Default::default(),
         };
 
@@ -2165,6 +2434,7 @@ mod tests {
         let mut bc1 = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Same gotcha".to_string()],
             notes: Default::default(),
@@ -2173,6 +2443,7 @@ mod tests {
         let bc2 = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Same gotcha".to_string(), "Different gotcha".to_string()],
             notes: Default::default(),
@@ -2191,6 +2462,7 @@ mod tests {
         let mut bc1 = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: [("key1".to_string(), "value1".to_string())]
@@ -2201,6 +2473,7 @@ mod tests {
         let bc2 = BusinessContext {
             purpose: None,
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: [
@@ -2228,6 +2501,7 @@ mod tests {
         existing_node.business_context = Some(BusinessContext {
             purpose: Some("Original purpose".to_string()),
             owner: Some("Original Team".to_string()),
+            owner_provenance: None,
             history: None,
             gotchas: vec!["Original gotcha".to_string()],
             notes: Default::default(),
@@ -2261,6 +2535,7 @@ mod tests {
         existing_node.business_context = Some(BusinessContext {
             purpose: Some("Purpose A".to_string()),
             owner: None,
+            owner_provenance: None,
             history: None,
             gotchas: vec![],
             notes: Default::default(),
@@ -0,0 +1,305 @@
+//! Git activity metrics of each service.
+//!
+//! Over a recent window of `git log` this module counts each service's
+//! commits, distinct authors, and changed lines and files. It also finds
+//! the dates of the service's latest and first commits. Together these show
+//! which services are hot, abandoned, or known to a single person.
+//!
+//! Merge commits are not counted, and bots are not counted as authors. A
+//! service's directory excludes the packages nested in it, as for
+//! ownership.
+//!
+//! Cloned repositories only keep recent history. The first commit date is
+//! left out when the history is cut off before it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use forge_survey::activity::{ActivityOptions, git_activity};
+//!
+//! let activity = git_activity(&repo_path, &[], &ActivityOptions::default());
+//! if let Some(root) = activity.get(Path::new("")) {
+//!     println!("{} commits by {} authors", root.commits, root.authors);
+//! }
+//! ```
+
+use crate::ownership::service_pathspecs;
+use chrono::{DateTime, Utc};
+use forge_graph::GitActivity;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How activity is measured.
+#[derive(Debug, Clone)]
+pub struct ActivityOptions {
+    /// Days of git history the counts cover.
+    pub window_days: u32,
+}
+
+impl Default for ActivityOptions {
+    fn default() -> Self {
+        Self { window_days: 90 }
+    }
+}
+
+/// Measure the git activity of a repository's services.
+///
+/// `package_dirs` are the directories of the repository's packages,
+/// relative to it. Activity is keyed by the directory of its service, the
+/// repository's own service being the empty path. Nothing is returned when
+/// the repository isn't a git repository.
+pub fn git_activity(
+    repo_path: &Path,
+    package_dirs: &[PathBuf],
+    options: &ActivityOptions,
+) -> BTreeMap<PathBuf, GitActivity> {
+    let Some(shallow) = shallow_commits(repo_path) else {
+        return BTreeMap::new();
+    };
+
+    std::iter::once(PathBuf::new())
+        .chain(package_dirs.iter().cloned())
+        .filter_map(|dir| {
+            let activity = service_activity(repo_path, &dir, package_dirs, options, &shallow)?;
+            Some((dir, activity))
+        })
+        .collect()
+}
+
+/// The activity of one service's directory, or `None` when it has no
+/// commits at all.
+fn service_activity(
+    repo_path: &Path,
+    dir: &Path,
+    package_dirs: &[PathBuf],
+    options: &ActivityOptions,
+    shallow: &HashSet<String>,
+) -> Option<GitActivity> {
+    let log = |args: &[&str]| git_log(repo_path, args, dir, package_dirs);
+
+    // Latest commit, which may predate the window
+    let last = log(&["-1", "--format=%H %aI"])?;
+    let (_, last_commit) = parse_commit_line(last.lines().next()?)?;
+
+    let mut activity = GitActivity {
+        window_days: options.window_days,
+        last_commit: Some(last_commit),
+        ..Default::default()
+    };
+
+    // Commits, authors and churn in the window. A shallow clone's boundary
+    // commits have no parent, so their diff is the whole tree; they count
+    // as commits but not as churn.
+    let since = format!("--since={} days ago", options.window_days);
+    let window = log(&[&since, "--format=%x00%H %aN", "--numstat"])?;
+    let mut authors = HashSet::new();
+    let mut files = HashSet::new();
+    let mut boundary = false;
+    for line in window.lines() {
+        if let Some(commit) = line.strip_prefix('\0') {
+            let (hash, author) = commit.split_once(' ').unwrap_or((commit, ""));
+            boundary = shallow.contains(hash);
+            activity.commits += 1;
+            if !author.ends_with("[bot]") {
+                authors.insert(author.to_string());
+            }
+            continue;
+        }
+        if boundary {
+            continue;
+        }
+        // "<added>\t<deleted>\t<path>", with "-" counts for binary files
+        let mut fields = line.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        activity.lines_changed += added.parse::<u64>().unwrap_or(0);
+        activity.lines_changed += deleted.parse::<u64>().unwrap_or(0);
+        files.insert(path.to_string());
+    }
+    activity.authors = authors.len() as u64;
+    activity.files_changed = files.len() as u64;
+
+    // First commit, unless the history was cut off at it
+    let history = log(&["--reverse", "--format=%H %aI"])?;
+    activity.first_commit = history
+        .lines()
+        .next()
+        .and_then(parse_commit_line)
+        .filter(|(hash, _)| !shallow.contains(hash))
+        .map(|(_, date)| date);
+
+    Some(activity)
+}
+
+/// Run `git log --no-merges` over a service's directory, returning its
+/// output or `None` when git fails.
+fn git_log(
+    repo_path: &Path,
+    args: &[&str],
+    dir: &Path,
+    package_dirs: &[PathBuf],
+) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["log", "--no-merges"])
+        .args(args)
+        .arg("--")
+        .args(service_pathspecs(dir, package_dirs))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        tracing::debug!(
+            "git log failed in {}: {}",
+            repo_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse a "<hash> <ISO 8601 date>" line.
+fn parse_commit_line(line: &str) -> Option<(String, DateTime<Utc>)> {
+    let (hash, date) = line.split_once(' ')?;
+    let date = DateTime::parse_from_rfc3339(date.trim()).ok()?;
+    Some((hash.to_string(), date.with_timezone(&Utc)))
+}
+
+/// The commits a shallow clone's history is cut off at (empty for a full
+/// clone), or `None` when the path isn't a git repository.
+fn shallow_commits(repo_path: &Path) -> Option<HashSet<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args([
+            "rev-parse",
+            "--path-format=absolute",
+            "--git-path",
+            "shallow",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    Some(content.lines().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.email=dev@example.com",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    fn commit(dir: &Path, author: &str, file: &str, content: &str) {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+        git(dir, &["add", "."]);
+        git(
+            dir,
+            &[
+                "-c",
+                &format!("user.name={}", author),
+                "commit",
+                "-qm",
+                file,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_git_activity_per_service() {
+        let dir = TempDir::new().unwrap();
+        git(dir.path(), &["init", "-q"]);
+        commit(dir.path(), "Jane", "a.js", "one\ntwo\n");
+        commit(dir.path(), "Sam", "a.js", "one\n");
+        commit(dir.path(), "renovate[bot]", "b.js", "x\n");
+        commit(dir.path(), "Alex", "pkg/c.js", "1\n2\n3\n");
+
+        let activity = git_activity(
+            dir.path(),
+            &[PathBuf::from("pkg")],
+            &ActivityOptions::default(),
+        );
+
+        let root = &activity[Path::new("")];
+        assert_eq!(root.window_days, 90);
+        assert_eq!(root.commits, 3);
+        assert_eq!(root.authors, 2);
+        // 2 added, then 1 deleted, then 1 added
+        assert_eq!(root.lines_changed, 4);
+        assert_eq!(root.files_changed, 2);
+        assert!(root.first_commit.is_some());
+        assert!(root.first_commit <= root.last_commit);
+
+        let pkg = &activity[Path::new("pkg")];
+        assert_eq!((pkg.commits, pkg.authors, pkg.lines_changed), (1, 1, 3));
+    }
+
+    #[test]
+    fn test_git_activity_shallow_clone() {
+        let origin = TempDir::new().unwrap();
+        git(origin.path(), &["init", "-q"]);
+        commit(origin.path(), "Jane", "a.js", "one\n");
+        commit(origin.path(), "Sam", "a.js", "two\n");
+
+        let clone = TempDir::new().unwrap();
+        let url = format!("file://{}", origin.path().display());
+        git(clone.path(), &["clone", "-q", "--depth=1", &url, "repo"]);
+
+        let activity = git_activity(&clone.path().join("repo"), &[], &ActivityOptions::default());
+        let root = &activity[Path::new("")];
+        assert_eq!(root.commits, 1);
+        assert!(root.last_commit.is_some());
+        assert_eq!(root.first_commit, None);
+    }
+
+    #[test]
+    fn test_git_activity_shallow_clone_churn() {
+        let origin = TempDir::new().unwrap();
+        git(origin.path(), &["init", "-q"]);
+        commit(origin.path(), "Jane", "a.js", &"line\n".repeat(100));
+        commit(origin.path(), "Sam", "b.js", "x\ny\n");
+        commit(origin.path(), "Alex", "b.js", "x\n");
+
+        let clone = TempDir::new().unwrap();
+        let url = format!("file://{}", origin.path().display());
+        git(clone.path(), &["clone", "-q", "--depth=2", &url, "repo"]);
+
+        let activity = git_activity(&clone.path().join("repo"), &[], &ActivityOptions::default());
+        let root = &activity[Path::new("")];
+        assert_eq!((root.commits, root.authors), (2, 2));
+        // Only Alex's deletion; Sam's boundary commit would add the whole tree
+        assert_eq!(root.lines_changed, 1);
+        assert_eq!(root.files_changed, 1);
+    }
+
+    #[test]
+    fn test_git_activity_outside_git() {
+        let dir = TempDir::new().unwrap();
+        assert!(git_activity(dir.path(), &[], &ActivityOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,531 @@
+//! Content-addressed cache of per-file discoveries.
+//!
+//! Parsing is by far the most expensive part of a survey, and most files
+//! are unchanged between surveys. The cache stores the discoveries a parser
+//! made in a file, keyed by a hash of:
+//!
+//! - the file's content and name (the extension decides the grammar)
+//! - the parser's name and [version](crate::Parser::version)
+//! - the Forge version
+//!
+//! The file's directory is not part of the key, so the same content is
+//! parsed once however many branches, repositories or vendored copies it
+//! appears in. Parsers that derive names from the directories holding a
+//! file key their entries by those directory names too (see
+//! [`ParserCache::keyed_by_directories`]). Discoveries served from the
+//! cache are pointed at the file being surveyed.
+//!
+//! Unlike `--incremental` (see [`crate::incremental`]), the cache needs no
+//! previous survey state: it works after a force push, on a new checkout, or
+//! when only some repositories changed.
+//!
+//! # Layout
+//!
+//! Each entry is a JSON file at `<dir>/<parser>/<hash[..2]>/<hash>.json`.
+//! Entries are written atomically, so parallel surveys can share a cache.
+//! Unreadable entries are treated as missing and overwritten.
+
+use crate::parser::{Discovery, ParserError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Per-file discovery cache stored in a directory (usually `.forge/cache`).
+#[derive(Debug, Default)]
+pub struct DiscoveryCache {
+    /// `None` when caching is disabled
+    dir: Option<PathBuf>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A cached parse of one file.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// The file the discoveries were made in
+    path: String,
+    discoveries: Vec<Discovery>,
+}
+
+/// What the cache directory holds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    /// Number of cached files
+    pub entries: usize,
+    /// Total size of the entries on disk
+    pub bytes: u64,
+    /// Number of cached files per parser
+    pub parsers: BTreeMap<String, usize>,
+}
+
+impl DiscoveryCache {
+    /// A cache stored in `dir`, which is created when the first entry is
+    /// written.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A cache that stores nothing; every file is parsed.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// The cache directory, or `None` when caching is disabled.
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// The cache as seen by one version of one parser.
+    pub fn for_parser<'a>(&'a self, parser: &'a str, version: u32) -> ParserCache<'a> {
+        ParserCache {
+            cache: self,
+            parser,
+            version,
+            directories: 0,
+        }
+    }
+
+    /// Files whose discoveries were served from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Files that had to be parsed so far.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Count the entries in the cache directory.
+    pub fn stats(&self) -> std::io::Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        let Some(dir) = self.dir.as_deref() else {
+            return Ok(stats);
+        };
+        for file in cache_files(dir)?.into_iter().filter(|file| file.entry) {
+            stats.entries += 1;
+            stats.bytes += std::fs::metadata(&file.path)?.len();
+            *stats.parsers.entry(file.parser).or_default() += 1;
+        }
+        Ok(stats)
+    }
+
+    /// Delete every entry, returning what was deleted.
+    ///
+    /// Only files laid out like entries (and temporary files left by
+    /// interrupted writes) are deleted, then the parser and shard
+    /// directories they leave empty. Anything else in the directory is
+    /// kept, so pointing the cache at a directory holding other data is
+    /// harmless.
+    pub fn clear(&self) -> std::io::Result<CacheStats> {
+        let stats = self.stats()?;
+        let Some(dir) = self.dir.as_deref() else {
+            return Ok(stats);
+        };
+
+        let mut shards = BTreeSet::new();
+        for file in cache_files(dir)? {
+            std::fs::remove_file(&file.path)?;
+            if let Some(shard) = file.path.parent() {
+                shards.insert(shard.to_path_buf());
+            }
+        }
+        let parsers: BTreeSet<PathBuf> = shards
+            .iter()
+            .filter_map(|shard| shard.parent().map(Path::to_path_buf))
+            .collect();
+        for empty_dir in shards.iter().chain(&parsers) {
+            if std::fs::read_dir(empty_dir)?.next().is_none() {
+                std::fs::remove_dir(empty_dir)?;
+            }
+        }
+        Ok(stats)
+    }
+}
+
+/// A file the cache wrote.
+struct CacheFile {
+    /// Name of the parser directory it is in
+    parser: String,
+    path: PathBuf,
+    /// Whether it is an entry rather than a leftover temporary file
+    entry: bool,
+}
+
+/// The files in a cache directory laid out like entries
+/// (`<parser>/<hash[..2]>/<hash>.json`) or their temporary files.
+fn cache_files(dir: &Path) -> std::io::Result<Vec<CacheFile>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for parser_dir in std::fs::read_dir(dir)? {
+        let parser_dir = parser_dir?;
+        if !parser_dir.file_type()?.is_dir() {
+            continue;
+        }
+        let parser = parser_dir.file_name().to_string_lossy().into_owned();
+        for shard in std::fs::read_dir(parser_dir.path())? {
+            let shard = shard?;
+            let shard_name = shard.file_name().to_string_lossy().into_owned();
+            if !shard.file_type()?.is_dir() || shard_name.len() != 2 || !is_hash(&shard_name) {
+                continue;
+            }
+            for file in std::fs::read_dir(shard.path())? {
+                let file = file?;
+                let name = file.file_name().to_string_lossy().into_owned();
+                let Some((hash, rest)) = name.split_once('.') else {
+                    continue;
+                };
+                if !file.file_type()?.is_file()
+                    || hash.len() != 64
+                    || !is_hash(hash)
+                    || !hash.starts_with(&shard_name)
+                {
+                    continue;
+                }
+                let entry = rest == "json";
+                if entry || rest.ends_with(".tmp") {
+                    files.push(CacheFile {
+                        parser: parser.clone(),
+                        path: file.path(),
+                        entry,
+                    });
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Whether a name is made of the lowercase hex digits of an entry hash.
+fn is_hash(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+/// The cache as seen by one version of one parser (see
+/// [`DiscoveryCache::for_parser`]).
+#[derive(Debug, Clone, Copy)]
+pub struct ParserCache<'a> {
+    cache: &'a DiscoveryCache,
+    parser: &'a str,
+    version: u32,
+    /// How many of the directories holding a file are part of its key
+    directories: usize,
+}
+
+impl ParserCache<'_> {
+    /// Also key entries by the names of the `levels` innermost directories
+    /// holding each file, for parsers whose discoveries depend on them.
+    pub fn keyed_by_directories(self, levels: usize) -> Self {
+        Self {
+            directories: levels,
+            ..self
+        }
+    }
+
+    /// The discoveries cached for `content`, or else those `parse` makes,
+    /// which are then cached.
+    ///
+    /// Parse errors are not cached, so failing files are retried on every
+    /// survey.
+    pub fn get_or_parse(
+        &self,
+        path: &Path,
+        content: &str,
+        parse: impl FnOnce() -> Result<Vec<Discovery>, ParserError>,
+    ) -> Result<Vec<Discovery>, ParserError> {
+        if let Some(discoveries) = self.get(path, content) {
+            return Ok(discoveries);
+        }
+        let discoveries = parse()?;
+        self.put(path, content, &discoveries);
+        Ok(discoveries)
+    }
+
+    /// The discoveries cached for a file with this name (and directories,
+    /// see [`keyed_by_directories`](Self::keyed_by_directories)) and
+    /// content, pointed at `path`.
+    pub fn get(&self, path: &Path, content: &str) -> Option<Vec<Discovery>> {
+        let entry_path = self.entry_path(path, content)?;
+        let Some(entry) = std::fs::read(&entry_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheEntry>(&bytes).ok())
+        else {
+            self.cache.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+
+        self.cache.hits.fetch_add(1, Ordering::Relaxed);
+        let source_file = path.to_string_lossy();
+        let mut discoveries = entry.discoveries;
+        for discovery in &mut discoveries {
+            if discovery.source_file() == entry.path {
+                *discovery.source_file_mut() = source_file.to_string();
+            }
+        }
+        Some(discoveries)
+    }
+
+    /// Cache the discoveries made in a file with this name (and directories)
+    /// and content.
+    pub fn put(&self, path: &Path, content: &str, discoveries: &[Discovery]) {
+        let Some(entry_path) = self.entry_path(path, content) else {
+            return;
+        };
+        let entry = CacheEntry {
+            path: path.to_string_lossy().into_owned(),
+            discoveries: discoveries.to_vec(),
+        };
+        if let Err(e) = write_entry(&entry_path, &entry) {
+            tracing::debug!("Failed to cache {}: {}", entry_path.display(), e);
+        }
+    }
+
+    /// Where the entry for a file with this name (and directories) and
+    /// content is stored.
+    fn entry_path(&self, path: &Path, content: &str) -> Option<PathBuf> {
+        let dir = self.cache.dir.as_deref()?;
+        let file_name = path.file_name().map(|n| n.to_string_lossy());
+        let directories: Vec<_> = path
+            .ancestors()
+            .skip(1)
+            .take(self.directories)
+            .map(|dir| dir.file_name().unwrap_or_default().to_string_lossy())
+            .collect();
+
+        let mut hasher = Sha256::new();
+        for part in [
+            env!("CARGO_PKG_VERSION"),
+            self.parser,
+            &self.version.to_string(),
+            file_name.as_deref().unwrap_or(""),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        for directory in &directories {
+            hasher.update(directory.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(content.as_bytes());
+        let hash: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        Some(
+            dir.join(sanitize(self.parser))
+                .join(&hash[..2])
+                .join(format!("{}.json", hash)),
+        )
+    }
+}
+
+/// Write an entry to a temporary file and move it into place, so readers
+/// never see a partial entry.
+fn write_entry(entry_path: &Path, entry: &CacheEntry) -> std::io::Result<()> {
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+
+    if let Some(parent) = entry_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp = entry_path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&temp, serde_json::to_vec(entry)?)?;
+    std::fs::rename(&temp, entry_path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp);
+    })
+}
+
+/// A parser name usable as a directory name.
+fn sanitize(parser: &str) -> String {
+    parser
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{DatabaseAccessDiscovery, DatabaseOperation};
+    use tempfile::tempdir;
+
+    fn table_access(source_file: &str, table: &str) -> Discovery {
+        Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+            db_type: "dynamodb".to_string(),
+            table_name: Some(table.to_string()),
+            connection: None,
+            operation: DatabaseOperation::Read,
+            detection_method: "test".to_string(),
+            source_file: source_file.to_string(),
+            source_line: 3,
+            deployment_metadata: None,
+        })
+    }
+
+    /// A parser that reads the table name from the file content.
+    fn parse(path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        Ok(vec![table_access(&path.to_string_lossy(), content)])
+    }
+
+    #[test]
+    fn test_cache_hit_for_same_content_elsewhere() {
+        let dir = tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path().join("cache"));
+        let parser = cache.for_parser("javascript", 1);
+
+        let first = Path::new("/repos/a/src/db.js");
+        let found = parser
+            .get_or_parse(first, "orders", || parse(first, "orders"))
+            .unwrap();
+        assert_eq!(found, vec![table_access("/repos/a/src/db.js", "orders")]);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+
+        // A vendored copy in another repository
+        let copy = Path::new("/repos/b/vendor/db.js");
+        let found = parser
+            .get_or_parse(copy, "orders", || panic!("should be cached"))
+            .unwrap();
+        assert_eq!(found, vec![table_access("/repos/b/vendor/db.js", "orders")]);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
+
+    #[test]
+    fn test_cache_miss_on_changed_key() {
+        let dir = tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path());
+        let path = Path::new("db.js");
+        cache
+            .for_parser("javascript", 1)
+            .get_or_parse(path, "orders", || parse(path, "orders"))
+            .unwrap();
+
+        // Different content, file name, parser and parser version
+        let ts = Path::new("db.ts");
+        for (parser, version, path, content) in [
+            ("javascript", 1, path, "users"),
+            ("javascript", 1, ts, "orders"),
+            ("python", 1, path, "orders"),
+            ("javascript", 2, path, "orders"),
+        ] {
+            cache
+                .for_parser(parser, version)
+                .get_or_parse(path, content, || parse(path, content))
+                .unwrap();
+        }
+        assert_eq!((cache.hits(), cache.misses()), (0, 5));
+    }
+
+    #[test]
+    fn test_cache_skips_errors_and_corrupt_entries() {
+        let dir = tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path());
+        let parser = cache.for_parser("javascript", 1);
+        let path = Path::new("db.js");
+
+        let failed = parser.get_or_parse(path, "orders", || {
+            Err(ParserError::ParseFailed {
+                path: "db.js".to_string(),
+            })
+        });
+        assert!(failed.is_err());
+        assert_eq!(cache.stats().unwrap().entries, 0);
+
+        parser
+            .get_or_parse(path, "orders", || parse(path, "orders"))
+            .unwrap();
+        let entry = parser.entry_path(path, "orders").unwrap();
+        std::fs::write(&entry, "{").unwrap();
+        let found = parser
+            .get_or_parse(path, "orders", || parse(path, "orders"))
+            .unwrap();
+        assert_eq!(found, vec![table_access("db.js", "orders")]);
+        assert_eq!(cache.hits(), 0);
+    }
+
+    #[test]
+    fn test_cache_stats_and_clear() {
+        let dir = tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path().join("cache"));
+        assert_eq!(cache.stats().unwrap(), CacheStats::default());
+
+        for (parser, content) in [("javascript", "a"), ("javascript", "b"), ("python", "c")] {
+            let path = Path::new("file");
+            cache
+                .for_parser(parser, 1)
+                .get_or_parse(path, content, || parse(path, content))
+                .unwrap();
+        }
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.entries, 3);
+        assert!(stats.bytes > 0);
+        assert_eq!(
+            stats.parsers.into_iter().collect::<Vec<_>>(),
+            vec![("javascript".to_string(), 2), ("python".to_string(), 1)]
+        );
+
+        assert_eq!(cache.clear().unwrap().entries, 3);
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+
+    #[test]
+    fn test_clear_keeps_other_files() {
+        let dir = tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path());
+        let path = Path::new("db.js");
+        cache
+            .for_parser("javascript", 1)
+            .get_or_parse(path, "orders", || parse(path, "orders"))
+            .unwrap();
+
+        // Data that isn't the cache's, some of it inside its directories
+        std::fs::write(dir.path().join("notes.txt"), "keep").unwrap();
+        std::fs::create_dir_all(dir.path().join("src/ab")).unwrap();
+        std::fs::write(dir.path().join("src/ab/main.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("javascript/README.md"), "keep").unwrap();
+
+        assert_eq!(cache.stats().unwrap().entries, 1);
+        assert_eq!(cache.clear().unwrap().entries, 1);
+
+        assert!(dir.path().join("notes.txt").exists());
+        assert!(dir.path().join("src/ab/main.json").exists());
+        assert!(dir.path().join("javascript/README.md").exists());
+        let shards: Vec<_> = std::fs::read_dir(dir.path().join("javascript"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(shards, vec!["README.md"]);
+    }
+
+    #[test]
+    fn test_disabled_cache_always_parses() {
+        let cache = DiscoveryCache::disabled();
+        let path = Path::new("db.js");
+        for _ in 0..2 {
+            cache
+                .for_parser("javascript", 1)
+                .get_or_parse(path, "orders", || parse(path, "orders"))
+                .unwrap();
+        }
+        assert_eq!((cache.hits(), cache.misses()), (0, 0));
+        assert_eq!(cache.stats().unwrap().entries, 0);
+    }
+}
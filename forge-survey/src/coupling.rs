@@ -9,7 +9,7 @@
 //!
 //! The coupling analyzer performs the following steps:
 //! 1. **Build Access Map**: Scan the graph to track which services access which resources
-//! 2. **Infer Ownership**: Determine resource ownership from Terraform, naming, or writes
+//! 2. **Infer Ownership**: Determine resource ownership from Terraform, IaC grants, naming, or writes
 //! 3. **Detect Couplings**: Find services sharing resources without explicit contracts
 //! 4. **Generate Edges**: Create READS_SHARED, WRITES_SHARED, and IMPLICITLY_COUPLED edges
 //!
@@ -24,6 +24,7 @@
 //! result.apply_to_graph(&mut graph)?;
 //! ```
 
+use crate::parser::cdk::CDK_GRANT_DETECTION_METHOD;
 use forge_graph::{Edge, EdgeMetadata, EdgeType, ForgeGraph, GraphError, NodeId, NodeType};
 use std::collections::{HashMap, HashSet};
 
@@ -298,6 +299,10 @@ pub enum OwnershipReason {
     /// Only this service writes to the resource
     ExclusiveWriter,
 
+    /// Only this service was granted write access in infrastructure code
+    /// (e.g., CDK `grantWriteData`)
+    InfrastructureGrant { evidence: String },
+
     /// Manually specified ownership
     Manual,
 
@@ -602,10 +607,11 @@ impl<'a> CouplingAnalyzer<'a> {
 
     /// Infer ownership of resources.
     ///
-    /// Uses three strategies in order of confidence:
+    /// Uses four strategies in order of confidence:
     /// 1. Terraform definition (0.9 confidence)
-    /// 2. Naming convention (0.7 confidence)
-    /// 3. Exclusive writer (0.6 confidence)
+    /// 2. Exclusive write grant in infrastructure code (0.8 confidence)
+    /// 3. Naming convention (0.7 confidence)
+    /// 4. Exclusive writer (0.6 confidence)
     fn infer_ownership(&self) -> Vec<OwnershipAssignment> {
         let mut assignments = Vec::new();
 
@@ -644,7 +650,21 @@ impl<'a> CouplingAnalyzer<'a> {
             }
         }
 
-        // Strategy 2: Naming convention
+        // Strategy 2: Write access granted in infrastructure code
+        // Granting a single function write access is a strong sign it owns the resource
+        let grants = self.write_grants(resource_id);
+        if let [(owner, evidence)] = grants.as_slice() {
+            return Some(OwnershipAssignment {
+                resource: resource_id.clone(),
+                owner: owner.clone(),
+                reason: OwnershipReason::InfrastructureGrant {
+                    evidence: evidence.clone(),
+                },
+                confidence: 0.8,
+            });
+        }
+
+        // Strategy 3: Naming convention
         // If resource name contains a service name, that service owns it
         let resource_name = &resource.display_name;
         for service in self.graph.nodes_by_type(NodeType::Service) {
@@ -662,7 +682,7 @@ impl<'a> CouplingAnalyzer<'a> {
             }
         }
 
-        // Strategy 3: Exclusive writer
+        // Strategy 4: Exclusive writer
         let writers = self.access_map.get_writers(resource_id);
         if writers.len() == 1 {
             return Some(OwnershipAssignment {
@@ -676,6 +696,31 @@ impl<'a> CouplingAnalyzer<'a> {
         None
     }
 
+    /// Services granted write access to a resource in infrastructure code,
+    /// with the evidence for the grant.
+    fn write_grants(&self, resource_id: &NodeId) -> Vec<(NodeId, String)> {
+        let mut grants: Vec<(NodeId, String)> = Vec::new();
+
+        for edge in self.graph.edges_to(resource_id) {
+            if !matches!(edge.edge_type, EdgeType::Writes | EdgeType::Publishes) {
+                continue;
+            }
+            let is_grant = edge
+                .metadata
+                .reason
+                .as_deref()
+                .is_some_and(|r| r.ends_with(CDK_GRANT_DETECTION_METHOD));
+            if is_grant && !grants.iter().any(|(owner, _)| owner == &edge.source) {
+                grants.push((
+                    edge.source.clone(),
+                    edge.metadata.evidence.first().cloned().unwrap_or_default(),
+                ));
+            }
+        }
+
+        grants
+    }
+
     /// Find a service in the same repository as a Terraform file.
     fn find_service_in_same_repo(&self, tf_file: &str) -> Option<NodeId> {
         // Extract repo name from file path
@@ -1392,6 +1437,56 @@ mod tests {
             ));
         }
 
+        #[test]
+        fn test_infer_ownership_from_write_grant() {
+            let mut graph = ForgeGraph::new();
+
+            graph
+                .add_node(create_service("orders-handler", "ns"))
+                .unwrap();
+            graph.add_node(create_service("reporting", "ns")).unwrap();
+            graph.add_node(create_database("orders", "ns")).unwrap();
+            let table = NodeId::new(NodeType::Database, "ns", "orders").unwrap();
+
+            // Write granted in CDK to orders-handler
+            let mut grant = Edge::new(
+                NodeId::new(NodeType::Service, "ns", "orders-handler").unwrap(),
+                table.clone(),
+                EdgeType::Writes,
+            )
+            .unwrap();
+            grant.metadata.reason = Some("Declared in cdk-grant".to_string());
+            grant.metadata.evidence = vec!["lib/orders-stack.ts:24".to_string()];
+            graph.add_edge(grant).unwrap();
+
+            // reporting also writes, so exclusive-writer inference would not apply
+            graph
+                .add_edge(
+                    Edge::new(
+                        NodeId::new(NodeType::Service, "ns", "reporting").unwrap(),
+                        table.clone(),
+                        EdgeType::Writes,
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+
+            let mut analyzer = CouplingAnalyzer::new(&graph);
+            let result = analyzer.analyze();
+
+            let ownership = result
+                .ownership_assignments
+                .iter()
+                .find(|a| a.resource == table)
+                .expect("grant should assign ownership");
+            assert_eq!(ownership.owner.name(), "orders-handler");
+            assert_eq!(ownership.confidence, 0.8);
+            assert!(matches!(
+                &ownership.reason,
+                OwnershipReason::InfrastructureGrant { evidence } if evidence == "lib/orders-stack.ts:24"
+            ));
+        }
+
         #[test]
         fn test_generate_shared_access_edges() {
             let mut graph = ForgeGraph::new();
@@ -22,8 +22,15 @@
 //! - **TypeScript**: `.ts`, `.tsx` or `package.json` with TypeScript dependencies
 //! - **Python**: `.py` or `requirements.txt`, `pyproject.toml`, `setup.py`, `setup.cfg`, `Pipfile`
 //! - **Terraform**: `.tf`, `.tfvars`
-
-use std::collections::{HashMap, HashSet};
+//! - **CloudFormation/SAM**: `template.yaml` and similar with CloudFormation markers
+//! - **docker-compose**: `docker-compose.yml`, `compose.yaml` and variants
+//! - **serverless**: `serverless.yml`, `serverless.ts` and other Serverless Framework configs
+//! - **openapi**: `openapi.yaml`, `swagger.json` and similar API documents
+//! - **protobuf**: any `.proto` file, `buf.yaml`, or `.go` sources (scanned by the
+//!   Protocol Buffers parser for generated gRPC clients)
+//! - **Go**: `.go` or `go.mod` (no parser of its own)
+
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -72,15 +79,16 @@ pub enum DetectionMethod {
 #[derive(Debug, Clone, Default)]
 pub struct DetectedLanguages {
     /// Map from language name to detection info.
-    /// Using a map ensures uniqueness by language name.
-    languages: HashMap<String, DetectedLanguage>,
+    /// Using a map ensures uniqueness by language name; ordering it by name
+    /// keeps parser selection, and so the survey, deterministic.
+    languages: BTreeMap<String, DetectedLanguage>,
 }
 
 impl DetectedLanguages {
     /// Creates a new empty DetectedLanguages instance.
     pub fn new() -> Self {
         Self {
-            languages: HashMap::new(),
+            languages: BTreeMap::new(),
         }
     }
 
@@ -125,10 +133,19 @@ impl DetectedLanguages {
         self.languages.get(language)
     }
 
-    /// Returns an iterator over the detected languages.
+    /// Returns an iterator over the detected languages, ordered by name.
     pub fn iter(&self) -> impl Iterator<Item = &DetectedLanguage> {
         self.languages.values()
     }
+
+    /// The language detected with the highest confidence; the first by
+    /// name among equally confident ones.
+    pub fn primary(&self) -> Option<&DetectedLanguage> {
+        self.languages
+            .values()
+            .rev()
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
 }
 
 /// Main entry point for language detection.
@@ -191,8 +208,88 @@ pub fn detect_languages(repo_path: &Path) -> DetectedLanguages {
 /// Each detection has a confidence score of 0.7.
 pub fn scan_file_extensions(repo_path: &Path) -> Vec<DetectedLanguage> {
     let mut extension_counts: HashMap<&str, usize> = HashMap::new();
+    let mut has_protobuf_sources = false;
+
+    for_each_scanned_extension(repo_path, |ext| {
+        // Count known extensions
+        match ext {
+            // JavaScript extensions
+            "js" | "jsx" | "mjs" | "cjs" => {
+                *extension_counts.entry("javascript").or_insert(0) += 1;
+            }
+            // TypeScript extensions
+            "ts" | "tsx" => {
+                *extension_counts.entry("typescript").or_insert(0) += 1;
+            }
+            // Python extensions
+            "py" => {
+                *extension_counts.entry("python").or_insert(0) += 1;
+            }
+            // Terraform extensions
+            "tf" | "tfvars" => {
+                *extension_counts.entry("terraform").or_insert(0) += 1;
+            }
+            // Protocol Buffers extensions
+            "proto" => {
+                has_protobuf_sources = true;
+            }
+            // Go extensions, also scanned for gRPC usage by the Protocol Buffers parser
+            "go" => {
+                has_protobuf_sources = true;
+                *extension_counts.entry("go").or_insert(0) += 1;
+            }
+            _ => {}
+        }
+    });
+
+    // Convert counts to detections if threshold met
+    let mut detected: Vec<DetectedLanguage> = extension_counts
+        .into_iter()
+        .filter(|(_, count)| *count >= FILE_THRESHOLD)
+        .map(|(lang, _)| DetectedLanguage {
+            name: lang.to_string(),
+            confidence: EXTENSION_CONFIDENCE,
+            detection_method: DetectionMethod::FileExtension,
+        })
+        .collect();
 
-    // Scan each directory
+    // A single contract or gRPC client is worth parsing, so no threshold applies
+    if has_protobuf_sources {
+        detected.push(DetectedLanguage {
+            name: "protobuf".to_string(),
+            confidence: EXTENSION_CONFIDENCE,
+            detection_method: DetectionMethod::FileExtension,
+        });
+    }
+    detected
+}
+
+/// Detects a language by its file extensions, scanning the same directories
+/// as [`scan_file_extensions`] and requiring the same number of files.
+///
+/// Used for languages handled by parser plugins, whose extensions are
+/// configured rather than known in advance.
+pub fn scan_language_extensions(
+    repo_path: &Path,
+    language: &str,
+    extensions: &[&str],
+) -> Option<DetectedLanguage> {
+    let mut count = 0;
+    for_each_scanned_extension(repo_path, |ext| {
+        if extensions.contains(&ext) {
+            count += 1;
+        }
+    });
+    (count >= FILE_THRESHOLD).then(|| DetectedLanguage {
+        name: language.to_string(),
+        confidence: EXTENSION_CONFIDENCE,
+        detection_method: DetectionMethod::FileExtension,
+    })
+}
+
+/// Calls `f` with the extension of each file in the repo root and common
+/// source directories, up to `MAX_DEPTH` deep.
+fn for_each_scanned_extension(repo_path: &Path, mut f: impl FnMut(&str)) {
     for dir in SCAN_DIRECTORIES {
         let scan_path = if dir.is_empty() {
             repo_path.to_path_buf()
@@ -217,40 +314,10 @@ pub fn scan_file_extensions(repo_path: &Path) -> Vec<DetectedLanguage> {
             }
 
             if let Some(ext) = entry.path().extension().and_then(|e| e.to_str()) {
-                // Count known extensions
-                match ext {
-                    // JavaScript extensions
-                    "js" | "jsx" | "mjs" | "cjs" => {
-                        *extension_counts.entry("javascript").or_insert(0) += 1;
-                    }
-                    // TypeScript extensions
-                    "ts" | "tsx" => {
-                        *extension_counts.entry("typescript").or_insert(0) += 1;
-                    }
-                    // Python extensions
-                    "py" => {
-                        *extension_counts.entry("python").or_insert(0) += 1;
-                    }
-                    // Terraform extensions
-                    "tf" | "tfvars" => {
-                        *extension_counts.entry("terraform").or_insert(0) += 1;
-                    }
-                    _ => {}
-                }
+                f(ext);
             }
         }
     }
-
-    // Convert counts to detections if threshold met
-    extension_counts
-        .into_iter()
-        .filter(|(_, count)| *count >= FILE_THRESHOLD)
-        .map(|(lang, _)| DetectedLanguage {
-            name: lang.to_string(),
-            confidence: EXTENSION_CONFIDENCE,
-            detection_method: DetectionMethod::FileExtension,
-        })
-        .collect()
 }
 
 /// Checks for language-specific configuration files.
@@ -265,6 +332,13 @@ pub fn scan_file_extensions(repo_path: &Path) -> Vec<DetectedLanguage> {
 /// - **TypeScript**: `package.json` with "typescript" or "ts-" prefixed dependencies
 /// - **Python**: `requirements.txt`, `pyproject.toml`, `setup.py`, `setup.cfg`, `Pipfile`
 /// - **Terraform**: `.tf` files (no specific config, relies on extension scanning)
+/// - **docker-compose**: `docker-compose.yml`, `docker-compose.yaml`, `compose.yml`, `compose.yaml`
+/// - **serverless**: `serverless.yml`, `serverless.yaml`, `serverless.json`, `serverless.ts`, `serverless.js`
+/// - **openapi**: `openapi.*`, `swagger.*` or `api.*` (`.yaml`, `.yml`, `.json`) in the root or
+///   a conventional spec directory (`api/`, `docs/`, `spec/`, `openapi/`, ...)
+/// - **protobuf**: `buf.yaml`, `buf.work.yaml`, or any `.proto` file in the root or a
+///   `proto/`, `protos/`, `protobuf/` or `api/` directory
+/// - **Go**: `go.mod`
 ///
 /// # Arguments
 ///
@@ -366,9 +440,126 @@ pub fn check_config_files(repo_path: &Path) -> Vec<DetectedLanguage> {
         }
     }
 
+    // Check for docker-compose files (local topology)
+    let compose_files = [
+        "docker-compose.yml",
+        "docker-compose.yaml",
+        "compose.yml",
+        "compose.yaml",
+    ];
+
+    if compose_files.iter().any(|f| repo_path.join(f).exists()) {
+        detected.push(DetectedLanguage {
+            name: "docker-compose".to_string(),
+            confidence: CONFIG_CONFIDENCE,
+            detection_method: DetectionMethod::ConfigFile,
+        });
+    }
+
+    // Check for Serverless Framework configuration
+    let serverless_configs = [
+        "serverless.yml",
+        "serverless.yaml",
+        "serverless.json",
+        "serverless.ts",
+        "serverless.js",
+    ];
+
+    if serverless_configs
+        .iter()
+        .any(|f| repo_path.join(f).exists())
+    {
+        detected.push(DetectedLanguage {
+            name: "serverless".to_string(),
+            confidence: CONFIG_CONFIDENCE,
+            detection_method: DetectionMethod::ConfigFile,
+        });
+    }
+
+    // Check for OpenAPI/Swagger documents
+    if has_openapi_document(repo_path) {
+        detected.push(DetectedLanguage {
+            name: "openapi".to_string(),
+            confidence: CONFIG_CONFIDENCE,
+            detection_method: DetectionMethod::ConfigFile,
+        });
+    }
+
+    // Check for Protocol Buffers definitions
+    if repo_path.join("buf.yaml").exists()
+        || repo_path.join("buf.work.yaml").exists()
+        || has_proto_files(repo_path)
+    {
+        detected.push(DetectedLanguage {
+            name: "protobuf".to_string(),
+            confidence: CONFIG_CONFIDENCE,
+            detection_method: DetectionMethod::ConfigFile,
+        });
+    }
+
+    // Check for Go modules
+    if repo_path.join("go.mod").exists() {
+        detected.push(DetectedLanguage {
+            name: "go".to_string(),
+            confidence: CONFIG_CONFIDENCE,
+            detection_method: DetectionMethod::ConfigFile,
+        });
+    }
+
     detected
 }
 
+/// Checks for `.proto` files in the root or conventional proto directories.
+///
+/// A single `.proto` file is enough to describe a service contract, so this
+/// does not apply the extension-count threshold.
+fn has_proto_files(repo_path: &Path) -> bool {
+    // Buf-style layouts mirror the package name (proto/acme/users/v1/users.proto)
+    const PROTO_MAX_DEPTH: usize = 6;
+
+    for dir in ["", "proto", "protos", "protobuf", "api"] {
+        let depth = if dir.is_empty() { 1 } else { PROTO_MAX_DEPTH };
+        let found = WalkDir::new(repo_path.join(dir))
+            .max_depth(depth)
+            .into_iter()
+            .filter_entry(|e| !is_ignored_dir(e.file_name().to_str().unwrap_or("")))
+            .filter_map(|e| e.ok())
+            .any(|e| {
+                e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext == "proto")
+            });
+        if found {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Checks for an OpenAPI/Swagger document in conventional locations.
+///
+/// The file content must declare an `openapi` or `swagger` version so that
+/// unrelated `api.json` files are not mistaken for API documents.
+fn has_openapi_document(repo_path: &Path) -> bool {
+    let dirs = ["", "api", "docs", "spec", "specs", "openapi", "swagger"];
+    let stems = ["openapi", "swagger", "api"];
+    let extensions = ["yaml", "yml", "json"];
+
+    for dir in &dirs {
+        for stem in &stems {
+            for ext in &extensions {
+                let path = repo_path.join(dir).join(format!("{}.{}", stem, ext));
+                if let Ok(content) = fs::read_to_string(&path) {
+                    if content.contains("openapi") || content.contains("swagger") {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    false
+}
+
 /// Checks if a directory should be ignored during scanning.
 ///
 /// Skips common directories that don't contain relevant source code
@@ -554,6 +745,23 @@ mod tests {
         assert!(names.contains("python"));
     }
 
+    #[test]
+    fn test_detected_languages_order_and_primary() {
+        let mut detected = DetectedLanguages::new();
+        assert!(detected.primary().is_none());
+        for (name, confidence) in [("terraform", 0.95), ("python", 0.7), ("javascript", 0.95)] {
+            detected.add(DetectedLanguage {
+                name: name.to_string(),
+                confidence,
+                detection_method: DetectionMethod::ConfigFile,
+            });
+        }
+
+        let names: Vec<_> = detected.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["javascript", "python", "terraform"]);
+        assert_eq!(detected.primary().unwrap().name, "javascript");
+    }
+
     // ==================== scan_file_extensions Tests ====================
 
     #[test]
@@ -923,6 +1131,82 @@ mod tests {
         assert!(names.contains("python"));
     }
 
+    #[test]
+    fn test_config_docker_compose() {
+        let temp_dir = create_test_repo(&["compose.yaml"]);
+
+        let detected = check_config_files(temp_dir.path());
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].name, "docker-compose");
+        assert_eq!(detected[0].detection_method, DetectionMethod::ConfigFile);
+    }
+
+    #[test]
+    fn test_config_serverless() {
+        let temp_dir = create_test_repo(&["serverless.ts"]);
+
+        let detected = check_config_files(temp_dir.path());
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].name, "serverless");
+        assert_eq!(detected[0].detection_method, DetectionMethod::ConfigFile);
+    }
+
+    #[test]
+    fn test_config_openapi() {
+        let temp_dir = TempDir::new().unwrap();
+        create_file_with_content(
+            temp_dir.path(),
+            "docs/openapi.yaml",
+            "openapi: 3.0.0\npaths: {}\n",
+        );
+        // Not an API document
+        create_file_with_content(temp_dir.path(), "api.json", r#"{"name": "x"}"#);
+
+        let detected = check_config_files(temp_dir.path());
+
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].name, "openapi");
+        assert_eq!(detected[0].detection_method, DetectionMethod::ConfigFile);
+    }
+
+    #[test]
+    fn test_extension_protobuf_sources() {
+        // One Go file is enough to scan for gRPC clients
+        let temp_dir = create_test_repo(&["cmd/main.go"]);
+        let detected = scan_file_extensions(temp_dir.path());
+        let names: Vec<_> = detected.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["protobuf"]);
+
+        let temp_dir = create_test_repo(&["schema/users.proto"]);
+        let detected = scan_file_extensions(temp_dir.path());
+        assert_eq!(detected.len(), 1);
+        assert_eq!(detected[0].name, "protobuf");
+        assert_eq!(detected[0].detection_method, DetectionMethod::FileExtension);
+    }
+
+    #[test]
+    fn test_config_protobuf_and_go() {
+        let temp_dir = create_test_repo(&["proto/acme/users/v1/users.proto", "go.mod"]);
+
+        let detected = check_config_files(temp_dir.path());
+
+        let names: HashSet<_> = detected.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains("protobuf"));
+        assert!(names.contains("go"));
+    }
+
+    #[test]
+    fn test_config_openapi_requires_version_marker() {
+        let temp_dir = create_test_repo(&["api.json"]);
+
+        let detected = check_config_files(temp_dir.path());
+
+        assert!(detected.is_empty());
+    }
+
     // ==================== detect_languages Integration Tests ====================
 
     #[test]
@@ -0,0 +1,257 @@
+//! Gitea API client for repository discovery.
+//!
+//! Lists the repositories of an organization and fetches single
+//! repositories through the REST API (v1) of a Gitea or Forgejo instance.
+//! Pages are requested until a short page is returned.
+
+use crate::source::{
+    ApiClient, CloneCredentials, CloneMethod, RepoInfo, RepoSource, SourceError, SourceKind,
+    Visibility, split_full_name,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use reqwest::header::{AUTHORIZATION, HeaderMap};
+use serde::Deserialize;
+
+/// Page size of Gitea, whose default maximum is 50
+const GITEA_PAGE_SIZE: usize = 50;
+
+/// A repository as returned by the Gitea API
+#[derive(Debug, Deserialize)]
+struct Repository {
+    name: String,
+    full_name: String,
+    owner: Owner,
+    clone_url: String,
+    ssh_url: String,
+    #[serde(default)]
+    default_branch: String,
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    topics: Option<Vec<String>>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    internal: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    login: String,
+}
+
+/// Gitea API client
+///
+/// Lists the repositories of organizations and fetches repositories by
+/// `owner/repo` on a Gitea or Forgejo instance.
+pub struct GiteaClient {
+    api: ApiClient,
+    base: Url,
+    token: Option<String>,
+    clone_method: CloneMethod,
+}
+
+impl GiteaClient {
+    /// Create a new Gitea client
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Instance URL, e.g. `https://git.acme.com`
+    /// * `token` - Access token; public repositories only without one
+    /// * `clone_method` - Whether to use HTTPS or SSH for cloning
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL or token is invalid
+    pub fn new(
+        url: &str,
+        token: Option<&str>,
+        clone_method: CloneMethod,
+    ) -> Result<Self, SourceError> {
+        let kind = SourceKind::Gitea;
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            let value = format!("token {}", token);
+            headers.insert(AUTHORIZATION, ApiClient::secret_header(kind, &value)?);
+        }
+        let base = ApiClient::endpoint(&ApiClient::base_url(kind, url)?, &["api", "v1"]);
+
+        Ok(Self {
+            api: ApiClient::new(kind, headers)?,
+            base,
+            token: token.map(str::to_string),
+            clone_method,
+        })
+    }
+
+    /// List all repositories of an organization
+    pub async fn list_org_repos(&self, org: &str) -> Result<Vec<RepoInfo>, SourceError> {
+        let mut repos = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let mut url = ApiClient::endpoint(&self.base, &["orgs", org, "repos"]);
+            url.query_pairs_mut()
+                .append_pair("limit", &GITEA_PAGE_SIZE.to_string())
+                .append_pair("page", &page.to_string());
+
+            let (page_repos, _): (Vec<Repository>, _) = self.api.get_json(url, org).await?;
+            let count = page_repos.len();
+            repos.extend(page_repos.into_iter().map(|r| self.repo_to_info(r)));
+
+            if count < GITEA_PAGE_SIZE {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(repos)
+    }
+
+    /// Get a repository by `owner/repo`
+    pub async fn get_owner_repo(&self, full_name: &str) -> Result<RepoInfo, SourceError> {
+        let (owner, name) = split_full_name(SourceKind::Gitea, full_name, 2..=2, "'owner/repo'")?;
+        let url = ApiClient::endpoint(&self.base, &["repos", owner, name]);
+        let (repo, _) = self.api.get_json(url, full_name).await?;
+        Ok(self.repo_to_info(repo))
+    }
+
+    /// Convert a Gitea repository to our RepoInfo
+    fn repo_to_info(&self, repo: Repository) -> RepoInfo {
+        RepoInfo {
+            full_name: repo.full_name,
+            name: repo.name,
+            owner: repo.owner.login,
+            clone_url: match self.clone_method {
+                CloneMethod::Https => repo.clone_url,
+                CloneMethod::Ssh => repo.ssh_url,
+            },
+            default_branch: Some(repo.default_branch)
+                .filter(|b| !b.is_empty())
+                .unwrap_or_else(|| "main".to_string()),
+            language: Some(repo.language).filter(|l| !l.is_empty()),
+            archived: repo.archived,
+            fork: repo.fork,
+            topics: repo.topics.unwrap_or_default(),
+            pushed_at: repo.updated_at,
+            visibility: Some(match (repo.private, repo.internal) {
+                (true, _) => Visibility::Private,
+                (false, true) => Visibility::Internal,
+                (false, false) => Visibility::Public,
+            }),
+            source: SourceKind::Gitea,
+        }
+    }
+}
+
+#[async_trait]
+impl RepoSource for GiteaClient {
+    fn kind(&self) -> SourceKind {
+        SourceKind::Gitea
+    }
+
+    async fn list_repos(&self, scope: &str) -> Result<Vec<RepoInfo>, SourceError> {
+        self.list_org_repos(scope).await
+    }
+
+    async fn get_repo(&self, full_name: &str) -> Result<RepoInfo, SourceError> {
+        self.get_owner_repo(full_name).await
+    }
+
+    fn clone_credentials(&self) -> Option<CloneCredentials> {
+        // Gitea accepts the token as the user name
+        self.token.as_ref().map(|token| CloneCredentials {
+            username: None,
+            token: token.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::test_server::serve;
+
+    fn repository(name: &str) -> String {
+        format!(
+            r#"{{"name": "{name}", "full_name": "acme/{name}", "owner": {{"login": "acme"}},
+                "clone_url": "https://git.acme.com/acme/{name}.git",
+                "ssh_url": "git@git.acme.com:acme/{name}.git",
+                "default_branch": "main", "language": "Go", "archived": false,
+                "fork": false, "topics": ["infra"], "private": true,
+                "updated_at": "2026-01-05T12:00:00+01:00"}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_list_org_repos_stops_at_short_page() {
+        let (base, requests) = serve(|target, request| {
+            if !request.contains("authorization: token secret") {
+                return (401, vec![], "{}".to_string());
+            }
+            let body = if target.ends_with("&page=1") {
+                let repos: Vec<String> = (0..GITEA_PAGE_SIZE)
+                    .map(|i| repository(&format!("svc-{:02}", i)))
+                    .collect();
+                format!("[{}]", repos.join(","))
+            } else {
+                format!("[{}]", repository("zeta"))
+            };
+            (200, vec![], body)
+        })
+        .await;
+
+        let client = GiteaClient::new(&base, Some("secret"), CloneMethod::Https).unwrap();
+        let repos = client.list_repos("acme").await.unwrap();
+
+        assert_eq!(repos.len(), GITEA_PAGE_SIZE + 1);
+        assert_eq!(repos[0].full_name, "acme/svc-00");
+        assert_eq!(repos[0].owner, "acme");
+        assert_eq!(repos[0].clone_url, "https://git.acme.com/acme/svc-00.git");
+        assert_eq!(repos[0].language.as_deref(), Some("Go"));
+        assert_eq!(repos[0].topics, vec!["infra".to_string()]);
+        assert_eq!(repos[0].source, SourceKind::Gitea);
+        assert_eq!(repos[0].visibility, Some(Visibility::Private));
+        assert_eq!(
+            repos[0].pushed_at.unwrap().to_rfc3339(),
+            "2026-01-05T11:00:00+00:00"
+        );
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            *requests,
+            vec![
+                "/api/v1/orgs/acme/repos?limit=50&page=1".to_string(),
+                "/api/v1/orgs/acme/repos?limit=50&page=2".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_repo_and_credentials() {
+        let (base, requests) = serve(|_, _| (200, vec![], repository("billing"))).await;
+
+        let client = GiteaClient::new(&base, Some("secret"), CloneMethod::Https).unwrap();
+        let repo = client.get_repo("acme/billing").await.unwrap();
+        assert_eq!(repo.name, "billing");
+        assert_eq!(requests.lock().unwrap()[0], "/api/v1/repos/acme/billing");
+
+        let credentials = client.clone_credentials().unwrap();
+        assert_eq!(
+            credentials.authenticate(&repo.clone_url),
+            "https://secret@git.acme.com/acme/billing.git"
+        );
+        assert!(matches!(
+            client.get_repo("acme/platform/billing").await,
+            Err(SourceError::InvalidRepoFormat { .. })
+        ));
+    }
+}
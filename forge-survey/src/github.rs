@@ -1,17 +1,19 @@
-//! GitHub API client for repository discovery and cloning.
+//! GitHub API client for repository discovery.
 //!
 //! This module provides functionality for:
 //! - Listing repositories in a GitHub organization
 //! - Fetching information about specific repositories
-//! - Cloning and caching repositories locally
 //!
-//! The client uses the `octocrab` library for GitHub API interactions
-//! and shells out to `git` for clone/pull operations.
-
+//! The client uses the `octocrab` library for GitHub API interactions.
+//! Cloning is done by [`RepoCache`](crate::source::RepoCache), shared by
+//! every repository source.
+
+use crate::source::{
+    CloneCredentials, CloneMethod, RepoInfo, RepoSource, SourceError, SourceKind, Visibility,
+};
+use async_trait::async_trait;
 use octocrab::Octocrab;
-use std::path::{Path, PathBuf};
 use thiserror::Error;
-use tokio::process::Command;
 
 /// Errors that can occur during GitHub operations
 #[derive(Debug, Error)]
@@ -20,24 +22,6 @@ pub enum GitHubError {
     #[error("GitHub API error: {0}")]
     ApiError(#[from] octocrab::Error),
 
-    /// Git clone operation failed
-    #[error("Git clone failed for {repo}: {message}")]
-    CloneFailed {
-        /// Repository that failed to clone
-        repo: String,
-        /// Error message
-        message: String,
-    },
-
-    /// Git pull operation failed
-    #[error("Git pull failed for {repo}: {message}")]
-    PullFailed {
-        /// Repository that failed to pull
-        repo: String,
-        /// Error message
-        message: String,
-    },
-
     /// Repository format is invalid
     #[error("Invalid repository format: {0}. Expected 'owner/repo'")]
     InvalidRepoFormat(String),
@@ -63,53 +47,13 @@ pub enum GitHubError {
     ClientBuildError(String),
 }
 
-/// Information about a repository discovered from GitHub
-#[derive(Debug, Clone)]
-pub struct RepoInfo {
-    /// Full name: "owner/repo"
-    pub full_name: String,
-
-    /// Just the repo name
-    pub name: String,
-
-    /// Owner/organization
-    pub owner: String,
-
-    /// Clone URL (https or ssh depending on configuration)
-    pub clone_url: String,
-
-    /// Default branch name
-    pub default_branch: String,
-
-    /// Primary language (as detected by GitHub)
-    pub language: Option<String>,
-
-    /// Whether the repository is archived
-    pub archived: bool,
-
-    /// Whether the repository is a fork
-    pub fork: bool,
-
-    /// Topics/tags associated with the repository
-    pub topics: Vec<String>,
-}
-
-/// Git clone method to use
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum CloneMethod {
-    /// Clone via HTTPS (default)
-    #[default]
-    Https,
-    /// Clone via SSH
-    Ssh,
-}
-
 /// GitHub API client wrapper
 ///
 /// Provides methods for interacting with the GitHub API to discover
 /// and fetch information about repositories.
 pub struct GitHubClient {
     client: Octocrab,
+    token: String,
     clone_method: CloneMethod,
 }
 
@@ -144,6 +88,7 @@ impl GitHubClient {
 
         Ok(Self {
             client,
+            token: token.to_string(),
             clone_method,
         })
     }
@@ -229,6 +174,20 @@ impl GitHubClient {
             archived: repo.archived.unwrap_or(false),
             fork: repo.fork.unwrap_or(false),
             topics: repo.topics.clone().unwrap_or_default(),
+            pushed_at: repo.pushed_at,
+            // Older servers only say whether a repository is private
+            visibility: repo
+                .visibility
+                .as_deref()
+                .and_then(Visibility::parse)
+                .or(repo.private.map(|private| {
+                    if private {
+                        Visibility::Private
+                    } else {
+                        Visibility::Public
+                    }
+                })),
+            source: SourceKind::GitHub,
         }
     }
 
@@ -245,221 +204,26 @@ impl GitHubClient {
     }
 }
 
-/// Manages local repository cache for cloned repositories
-///
-/// Handles cloning new repositories and updating existing ones.
-/// Repositories are organized by owner/name in the cache directory.
-pub struct RepoCache {
-    cache_dir: PathBuf,
-    clone_method: CloneMethod,
-}
-
-impl RepoCache {
-    /// Create a new repository cache manager
-    ///
-    /// # Arguments
-    ///
-    /// * `cache_dir` - Directory where repositories will be cloned
-    /// * `clone_method` - Whether to use HTTPS or SSH for cloning
-    pub fn new(cache_dir: PathBuf, clone_method: CloneMethod) -> Self {
-        Self {
-            cache_dir,
-            clone_method,
-        }
+#[async_trait]
+impl RepoSource for GitHubClient {
+    fn kind(&self) -> SourceKind {
+        SourceKind::GitHub
     }
 
-    /// Get the local path where a repository would be stored
-    ///
-    /// # Arguments
-    ///
-    /// * `repo` - Repository information
-    ///
-    /// # Returns
-    ///
-    /// The path where the repository is/would be cloned
-    pub fn repo_path(&self, repo: &RepoInfo) -> PathBuf {
-        self.cache_dir.join(&repo.owner).join(&repo.name)
+    async fn list_repos(&self, scope: &str) -> Result<Vec<RepoInfo>, SourceError> {
+        Ok(self.list_org_repos(scope).await?)
     }
 
-    /// Ensure a repository is cloned and up to date
-    ///
-    /// If the repository doesn't exist locally, it will be cloned.
-    /// If it exists, it will be updated with the latest changes.
-    ///
-    /// # Arguments
-    ///
-    /// * `repo` - Repository information
-    /// * `token` - Optional GitHub token for HTTPS authentication
-    ///
-    /// # Returns
-    ///
-    /// The local path to the repository
-    pub async fn ensure_repo(
-        &self,
-        repo: &RepoInfo,
-        token: Option<&str>,
-    ) -> Result<PathBuf, GitHubError> {
-        let local_path = self.repo_path(repo);
-
-        if local_path.exists() {
-            // Pull latest changes
-            self.pull_repo(&local_path, repo).await?;
-        } else {
-            // Clone the repository
-            self.clone_repo(repo, &local_path, token).await?;
-        }
-
-        Ok(local_path)
-    }
-
-    /// Clone a repository to a local path
-    async fn clone_repo(
-        &self,
-        repo: &RepoInfo,
-        local_path: &Path,
-        token: Option<&str>,
-    ) -> Result<(), GitHubError> {
-        // Create parent directories
-        if let Some(parent) = local_path.parent() {
-            tokio::fs::create_dir_all(parent)
-                .await
-                .map_err(|e| GitHubError::CloneFailed {
-                    repo: repo.full_name.clone(),
-                    message: format!("Failed to create directory: {}", e),
-                })?;
-        }
-
-        // Build clone URL with token for HTTPS authentication
-        let clone_url = match (self.clone_method, token) {
-            (CloneMethod::Https, Some(token)) => {
-                // Insert token into URL: https://TOKEN@github.com/owner/repo.git
-                repo.clone_url
-                    .replace("https://", &format!("https://{}@", token))
-            }
-            _ => repo.clone_url.clone(),
-        };
-
-        tracing::info!("Cloning {} to {}", repo.full_name, local_path.display());
-
-        // Execute git clone with shallow clone for speed
-        let output = Command::new("git")
-            .args([
-                "clone",
-                "--depth",
-                "1", // Shallow clone for speed
-                "--single-branch",
-                "--branch",
-                &repo.default_branch,
-                &clone_url,
-                local_path.to_str().unwrap_or_default(),
-            ])
-            .output()
-            .await
-            .map_err(|e| GitHubError::CloneFailed {
-                repo: repo.full_name.clone(),
-                message: e.to_string(),
-            })?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(GitHubError::CloneFailed {
-                repo: repo.full_name.clone(),
-                message: stderr.to_string(),
-            });
-        }
-
-        tracing::info!("Successfully cloned {}", repo.full_name);
-        Ok(())
+    async fn get_repo(&self, full_name: &str) -> Result<RepoInfo, SourceError> {
+        let (owner, name) = parse_repo_string(full_name)?;
+        Ok(GitHubClient::get_repo(self, owner, name).await?)
     }
 
-    /// Pull latest changes for an existing repository
-    async fn pull_repo(&self, local_path: &Path, repo: &RepoInfo) -> Result<(), GitHubError> {
-        tracing::debug!("Pulling updates for {}", repo.full_name);
-
-        let output = Command::new("git")
-            .args(["pull", "--ff-only"])
-            .current_dir(local_path)
-            .output()
-            .await
-            .map_err(|e| GitHubError::PullFailed {
-                repo: repo.full_name.clone(),
-                message: e.to_string(),
-            })?;
-
-        if !output.status.success() {
-            // Pull failed, might be dirty or diverged - try to reset to origin
-            tracing::warn!(
-                "Pull failed for {}, attempting reset to origin",
-                repo.full_name
-            );
-
-            let _ = Command::new("git")
-                .args(["fetch", "origin"])
-                .current_dir(local_path)
-                .output()
-                .await;
-
-            let reset_output = Command::new("git")
-                .args([
-                    "reset",
-                    "--hard",
-                    &format!("origin/{}", repo.default_branch),
-                ])
-                .current_dir(local_path)
-                .output()
-                .await;
-
-            if reset_output.is_err() {
-                tracing::warn!(
-                    "Failed to reset {} to origin, continuing with existing state",
-                    repo.full_name
-                );
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Get the current commit SHA of a repository
-    ///
-    /// # Arguments
-    ///
-    /// * `local_path` - Path to the local repository
-    ///
-    /// # Returns
-    ///
-    /// The commit SHA if it can be determined, None otherwise
-    pub async fn get_commit_sha(&self, local_path: &Path) -> Option<String> {
-        let output = Command::new("git")
-            .args(["rev-parse", "HEAD"])
-            .current_dir(local_path)
-            .output()
-            .await
-            .ok()?;
-
-        if output.status.success() {
-            Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
-        } else {
-            None
-        }
-    }
-
-    /// Check if a repository exists in the cache
-    ///
-    /// # Arguments
-    ///
-    /// * `repo` - Repository information
-    ///
-    /// # Returns
-    ///
-    /// True if the repository exists locally
-    pub fn repo_exists(&self, repo: &RepoInfo) -> bool {
-        self.repo_path(repo).exists()
-    }
-
-    /// Get the cache directory path
-    pub fn cache_dir(&self) -> &Path {
-        &self.cache_dir
+    fn clone_credentials(&self) -> Option<CloneCredentials> {
+        Some(CloneCredentials {
+            username: None,
+            token: self.token.clone(),
+        })
     }
 }
 
@@ -510,6 +274,9 @@ pub fn create_repo_info_minimal(owner: &str, name: &str, clone_method: CloneMeth
         archived: false,
         fork: false,
         topics: vec![],
+        pushed_at: None,
+        visibility: None,
+        source: SourceKind::GitHub,
     }
 }
 
@@ -553,18 +320,4 @@ mod tests {
         let info = create_repo_info_minimal("owner", "repo", CloneMethod::Ssh);
         assert_eq!(info.clone_url, "git@github.com:owner/repo.git");
     }
-
-    #[test]
-    fn test_repo_cache_path() {
-        let cache = RepoCache::new(PathBuf::from("/tmp/forge/repos"), CloneMethod::Https);
-        let repo = create_repo_info_minimal("myorg", "myrepo", CloneMethod::Https);
-        let path = cache.repo_path(&repo);
-        assert_eq!(path, PathBuf::from("/tmp/forge/repos/myorg/myrepo"));
-    }
-
-    #[test]
-    fn test_clone_method_default() {
-        let method = CloneMethod::default();
-        assert_eq!(method, CloneMethod::Https);
-    }
 }
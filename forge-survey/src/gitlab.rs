@@ -0,0 +1,340 @@
+//! GitLab API client for repository discovery.
+//!
+//! Lists the projects of a group, subgroups included, and fetches single
+//! projects through the REST API (v4) of GitLab.com or a self-hosted
+//! instance. Pages are followed through the `X-Next-Page` header.
+//!
+//! A project's full name is its path with namespace
+//! (`group/subgroup/project`); its owner is the namespace path. Project
+//! listings carry no language, so each project's languages are requested
+//! separately, a few at a time.
+
+use crate::source::{
+    ApiClient, CloneCredentials, CloneMethod, PAGE_SIZE, RepoInfo, RepoSource, SourceError,
+    SourceKind, Visibility, split_full_name,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
+use reqwest::Url;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// GitLab.com, used when no instance URL is configured
+pub const GITLAB_URL: &str = "https://gitlab.com";
+
+/// Language requests in flight at once while listing a group
+const LANGUAGE_REQUESTS: usize = 8;
+
+/// A project as returned by the GitLab API
+#[derive(Debug, Deserialize)]
+struct Project {
+    id: u64,
+    path: String,
+    path_with_namespace: String,
+    namespace: Namespace,
+    default_branch: Option<String>,
+    http_url_to_repo: String,
+    ssh_url_to_repo: String,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    forked_from_project: Option<serde_json::Value>,
+    #[serde(default)]
+    topics: Vec<String>,
+    /// Topics before GitLab 14.5
+    #[serde(default)]
+    tag_list: Vec<String>,
+    #[serde(default)]
+    last_activity_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    visibility: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Namespace {
+    full_path: String,
+}
+
+/// GitLab API client
+///
+/// Lists the projects of groups and fetches projects by path on GitLab.com
+/// or a self-hosted instance.
+pub struct GitLabClient {
+    api: ApiClient,
+    base: Url,
+    token: Option<String>,
+    clone_method: CloneMethod,
+}
+
+impl GitLabClient {
+    /// Create a new GitLab client
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Instance URL, e.g. `https://gitlab.acme.com` ([`GITLAB_URL`] for GitLab.com)
+    /// * `token` - Personal, group or project access token; public projects only without one
+    /// * `clone_method` - Whether to use HTTPS or SSH for cloning
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL or token is invalid
+    pub fn new(
+        url: &str,
+        token: Option<&str>,
+        clone_method: CloneMethod,
+    ) -> Result<Self, SourceError> {
+        let kind = SourceKind::GitLab;
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers.insert("private-token", ApiClient::secret_header(kind, token)?);
+        }
+        let base = ApiClient::endpoint(&ApiClient::base_url(kind, url)?, &["api", "v4"]);
+
+        Ok(Self {
+            api: ApiClient::new(kind, headers)?,
+            base,
+            token: token.map(str::to_string),
+            clone_method,
+        })
+    }
+
+    /// List all projects of a group and its subgroups
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The group's full path, e.g. `acme/platform`
+    pub async fn list_group_projects(&self, group: &str) -> Result<Vec<RepoInfo>, SourceError> {
+        let mut repos = Vec::new();
+        let mut page = "1".to_string();
+
+        loop {
+            let mut url = ApiClient::endpoint(&self.base, &["groups", group, "projects"]);
+            url.query_pairs_mut()
+                .append_pair("include_subgroups", "true")
+                .append_pair("order_by", "path")
+                .append_pair("sort", "asc")
+                .append_pair("per_page", &PAGE_SIZE.to_string())
+                .append_pair("page", &page);
+
+            let (projects, headers): (Vec<Project>, _) = self.api.get_json(url, group).await?;
+            let count = projects.len();
+            let page_repos: Vec<RepoInfo> = stream::iter(projects)
+                .map(|p| self.project_to_info(p))
+                .buffered(LANGUAGE_REQUESTS)
+                .collect()
+                .await;
+            repos.extend(page_repos);
+
+            // The next page is empty on the last page
+            match headers
+                .get("x-next-page")
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| !v.is_empty())
+            {
+                Some(next) => page = next.to_string(),
+                None => break,
+            }
+            if count == 0 {
+                break;
+            }
+        }
+
+        Ok(repos)
+    }
+
+    /// Get a project by its full path, e.g. `acme/platform/billing`
+    pub async fn get_project(&self, path: &str) -> Result<RepoInfo, SourceError> {
+        split_full_name(
+            SourceKind::GitLab,
+            path,
+            2..=usize::MAX,
+            "'group/project' or 'group/subgroup/project'",
+        )?;
+        let url = ApiClient::endpoint(&self.base, &["projects", path]);
+        let (project, _) = self.api.get_json(url, path).await?;
+        Ok(self.project_to_info(project).await)
+    }
+
+    /// The language most of a project's code is in
+    ///
+    /// A failed request leaves the language unknown rather than failing
+    /// discovery.
+    async fn main_language(&self, project: &Project) -> Option<String> {
+        let id = project.id.to_string();
+        let url = ApiClient::endpoint(&self.base, &["projects", &id, "languages"]);
+        let languages: BTreeMap<String, f64> =
+            match self.api.get_json(url, &project.path_with_namespace).await {
+                Ok((languages, _)) => languages,
+                Err(e) => {
+                    tracing::debug!("No languages for {}: {}", project.path_with_namespace, e);
+                    return None;
+                }
+            };
+        // Ties go to the first language by name
+        languages
+            .into_iter()
+            .max_by(|(a_name, a), (b_name, b)| a.total_cmp(b).then_with(|| b_name.cmp(a_name)))
+            .map(|(language, _)| language)
+    }
+
+    /// Convert a GitLab project to our RepoInfo
+    async fn project_to_info(&self, project: Project) -> RepoInfo {
+        let language = self.main_language(&project).await;
+        let topics = if project.topics.is_empty() {
+            project.tag_list
+        } else {
+            project.topics
+        };
+        RepoInfo {
+            full_name: project.path_with_namespace,
+            name: project.path,
+            owner: project.namespace.full_path,
+            clone_url: match self.clone_method {
+                CloneMethod::Https => project.http_url_to_repo,
+                CloneMethod::Ssh => project.ssh_url_to_repo,
+            },
+            default_branch: project.default_branch.unwrap_or_else(|| "main".to_string()),
+            language,
+            archived: project.archived,
+            fork: project.forked_from_project.is_some(),
+            topics,
+            pushed_at: project.last_activity_at,
+            visibility: project.visibility.as_deref().and_then(Visibility::parse),
+            source: SourceKind::GitLab,
+        }
+    }
+}
+
+#[async_trait]
+impl RepoSource for GitLabClient {
+    fn kind(&self) -> SourceKind {
+        SourceKind::GitLab
+    }
+
+    async fn list_repos(&self, scope: &str) -> Result<Vec<RepoInfo>, SourceError> {
+        self.list_group_projects(scope).await
+    }
+
+    async fn get_repo(&self, full_name: &str) -> Result<RepoInfo, SourceError> {
+        self.get_project(full_name).await
+    }
+
+    fn clone_credentials(&self) -> Option<CloneCredentials> {
+        // Any user name is accepted with an access token
+        self.token.as_ref().map(|token| CloneCredentials {
+            username: Some("oauth2".to_string()),
+            token: token.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::test_server::serve;
+
+    fn project(id: u64, path: &str, namespace: &str) -> String {
+        format!(
+            r#"{{"id": {id}, "path": "{path}", "path_with_namespace": "{namespace}/{path}",
+                "namespace": {{"full_path": "{namespace}"}}, "default_branch": "develop",
+                "http_url_to_repo": "https://gitlab.acme.com/{namespace}/{path}.git",
+                "ssh_url_to_repo": "git@gitlab.acme.com:{namespace}/{path}.git",
+                "archived": false, "topics": ["payments"],
+                "last_activity_at": "2026-03-01T10:00:00.000Z", "visibility": "internal"}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_list_group_projects_follows_pages() {
+        let (base, requests) = serve(|target, request| {
+            if !request.contains("private-token: secret") {
+                return (401, vec![], "{}".to_string());
+            }
+            match target {
+                "/api/v4/projects/1/languages" => {
+                    let body = r#"{"Go": 61.5, "Shell": 38.5}"#;
+                    return (200, vec![], body.to_string());
+                }
+                "/api/v4/projects/2/languages" => return (403, vec![], "{}".to_string()),
+                _ => {}
+            }
+            let (next, body) = if target.ends_with("&page=1") {
+                ("2", format!("[{}]", project(1, "billing", "acme/platform")))
+            } else {
+                (
+                    "",
+                    format!("[{}]", project(2, "ledger", "acme/platform/finance")),
+                )
+            };
+            (
+                200,
+                vec![("x-next-page".to_string(), next.to_string())],
+                body,
+            )
+        })
+        .await;
+
+        let client = GitLabClient::new(&base, Some("secret"), CloneMethod::Ssh).unwrap();
+        let repos = client.list_repos("acme/platform").await.unwrap();
+
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[0].full_name, "acme/platform/billing");
+        assert_eq!(repos[0].owner, "acme/platform");
+        assert_eq!(repos[0].name, "billing");
+        assert_eq!(repos[0].default_branch, "develop");
+        assert_eq!(
+            repos[0].clone_url,
+            "git@gitlab.acme.com:acme/platform/billing.git"
+        );
+        assert_eq!(repos[0].topics, vec!["payments".to_string()]);
+        assert_eq!(repos[0].language.as_deref(), Some("Go"));
+        assert_eq!(
+            repos[0].pushed_at.unwrap().to_rfc3339(),
+            "2026-03-01T10:00:00+00:00"
+        );
+        assert_eq!(repos[0].visibility, Some(Visibility::Internal));
+        assert_eq!(repos[0].source, SourceKind::GitLab);
+        assert_eq!(repos[1].full_name, "acme/platform/finance/ledger");
+        assert_eq!(repos[1].language, None);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            *requests,
+            vec![
+                "/api/v4/groups/acme%2Fplatform/projects?include_subgroups=true&order_by=path&sort=asc&per_page=100&page=1".to_string(),
+                "/api/v4/projects/1/languages".to_string(),
+                "/api/v4/groups/acme%2Fplatform/projects?include_subgroups=true&order_by=path&sort=asc&per_page=100&page=2".to_string(),
+                "/api/v4/projects/2/languages".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_project_errors() {
+        let (base, _) = serve(|target, _| {
+            if target.contains("missing") {
+                (404, vec![], "{}".to_string())
+            } else {
+                (401, vec![], "{}".to_string())
+            }
+        })
+        .await;
+        let client = GitLabClient::new(&base, None, CloneMethod::Https).unwrap();
+
+        assert!(matches!(
+            client.get_repo("acme/missing").await,
+            Err(SourceError::NotFound { .. })
+        ));
+        assert!(matches!(
+            client.get_repo("acme/private").await,
+            Err(SourceError::AuthFailed(SourceKind::GitLab))
+        ));
+        assert!(matches!(
+            client.get_repo("billing").await,
+            Err(SourceError::InvalidRepoFormat { .. })
+        ));
+        assert!(client.clone_credentials().is_none());
+    }
+}
@@ -0,0 +1,529 @@
+//! Repository-declared hints.
+//!
+//! Some relationships can't be found by reading code: calls routed through a
+//! service mesh, tables owned by convention, and so on. Repositories can
+//! declare them in a `.forge.yaml` file at their root:
+//!
+//! ```yaml
+//! service: billing-api          # overrides the detected service name
+//! context:
+//!   purpose: Charges customers for orders
+//!   owner: payments-team
+//! calls: [ledger-api]
+//! owns: [database:invoices]     # a type prefix creates the node if needed
+//! ignore: ["scripts/**"]        # discoveries in these files are dropped
+//! ```
+//!
+//! or in `forge:` comment annotations anywhere in the code:
+//!
+//! ```text
+//! // forge:calls billing-api
+//! # forge:owns orders-table
+//! const legacy = new DynamoDB(); // forge:ignore
+//! ```
+//!
+//! `forge:ignore` drops the discoveries made on its line and the next one.
+//! The other annotations (`calls`, `reads`, `writes`, `publishes`,
+//! `subscribes`, `uses`, `owns`) declare an edge from the service of the
+//! package containing the file.
+//!
+//! [`GraphBuilder::add_repo_hints`](crate::GraphBuilder::add_repo_hints)
+//! merges hints with the discovered graph. Declared edges and nodes are
+//! recorded as [`DiscoverySource::Manual`](forge_graph::DiscoverySource::Manual);
+//! edges are tagged [`REPO_DECLARED`], and nodes list what was declared
+//! about them (`name`, `business_context`, `node`) in a `repo_declared`
+//! attribute.
+
+use crate::parser::{Discovery, is_ignored_dir, parse_in_parallel, repo_files};
+use forge_graph::{BusinessContext, EdgeType, NodeType};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::path::Path;
+use thiserror::Error;
+
+/// Name of the hints file, at the root of a repository.
+pub const HINTS_FILE: &str = ".forge.yaml";
+
+/// Tag of edges declared by a repository.
+pub const REPO_DECLARED: &str = "repo-declared";
+
+/// Extensions of the files scanned for `forge:` annotations.
+const ANNOTATED_EXTENSIONS: &[&str] = &[
+    "js", "jsx", "ts", "tsx", "mjs", "cjs", "py", "tf", "go", "java", "kt", "rs", "rb", "cs",
+    "proto", "sql", "sh", "yaml", "yml",
+];
+
+/// Errors that can occur while reading a repository's hints.
+#[derive(Debug, Error)]
+pub enum HintsError {
+    /// IO error reading the hints file
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The hints file is not valid YAML, or has unknown fields
+    #[error("Invalid {HINTS_FILE}: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    /// A declared target names an unknown or unsupported node type
+    #[error("Invalid target '{target}' in {HINTS_FILE}: {reason}")]
+    InvalidTarget { target: String, reason: String },
+
+    /// An ignore entry is not a valid glob pattern
+    #[error("Invalid ignore pattern '{pattern}' in {HINTS_FILE}: {reason}")]
+    InvalidPattern { pattern: String, reason: String },
+}
+
+/// How a declaring service relates to a declared target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintRelation {
+    Calls,
+    Reads,
+    Writes,
+    Publishes,
+    Subscribes,
+    Uses,
+    Owns,
+}
+
+impl HintRelation {
+    /// The relation named by an annotation verb or hints file key.
+    pub fn from_verb(verb: &str) -> Option<Self> {
+        match verb {
+            "calls" => Some(Self::Calls),
+            "reads" => Some(Self::Reads),
+            "writes" => Some(Self::Writes),
+            "publishes" => Some(Self::Publishes),
+            "subscribes" => Some(Self::Subscribes),
+            "uses" => Some(Self::Uses),
+            "owns" => Some(Self::Owns),
+            _ => None,
+        }
+    }
+
+    /// The verb naming the relation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Calls => "calls",
+            Self::Reads => "reads",
+            Self::Writes => "writes",
+            Self::Publishes => "publishes",
+            Self::Subscribes => "subscribes",
+            Self::Uses => "uses",
+            Self::Owns => "owns",
+        }
+    }
+
+    /// The edge recording the relation to a target of the given type.
+    ///
+    /// Reading from a queue is consuming it and writing to one publishes,
+    /// as for relationships declared in configuration.
+    pub fn edge_type(&self, target: NodeType) -> EdgeType {
+        match (self, target) {
+            (Self::Reads, NodeType::Queue) => EdgeType::Subscribes,
+            (Self::Writes, NodeType::Queue) => EdgeType::Publishes,
+            (Self::Calls, _) => EdgeType::Calls,
+            (Self::Reads, _) => EdgeType::Reads,
+            (Self::Writes, _) => EdgeType::Writes,
+            (Self::Publishes, _) => EdgeType::Publishes,
+            (Self::Subscribes, _) => EdgeType::Subscribes,
+            (Self::Uses, _) => EdgeType::Uses,
+            (Self::Owns, _) => EdgeType::Owns,
+        }
+    }
+}
+
+/// An edge a repository declares from one of its services.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HintDeclaration {
+    pub relation: HintRelation,
+
+    /// Name of the target service or resource.
+    pub target: String,
+
+    /// Type of the target, from a `type:` prefix (e.g., `database:orders`).
+    /// Targets with a type are created when no node has their name; others
+    /// must already be in the graph.
+    pub target_type: Option<NodeType>,
+
+    /// File declaring the edge (the hints file or an annotated source file).
+    pub source_file: String,
+
+    /// Line number of the declaration.
+    pub source_line: u32,
+}
+
+/// Everything a repository declares about itself.
+#[derive(Debug, Clone, Default)]
+pub struct RepoHints {
+    /// Name of the repository's service, overriding the detected one.
+    pub service_name: Option<String>,
+
+    /// Business context of the repository's service.
+    pub context: Option<BusinessContext>,
+
+    /// Declared edges, from the hints file first and then annotations in
+    /// file order.
+    pub declarations: Vec<HintDeclaration>,
+
+    /// Files whose discoveries are dropped, relative to the repository.
+    ignore: Vec<glob::Pattern>,
+
+    /// Lines whose discoveries are dropped: (source file, line).
+    ignored_lines: BTreeSet<(String, u32)>,
+}
+
+/// The hints file as written.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct HintsFile {
+    service: Option<String>,
+    context: Option<BusinessContext>,
+    calls: Vec<String>,
+    reads: Vec<String>,
+    writes: Vec<String>,
+    publishes: Vec<String>,
+    subscribes: Vec<String>,
+    uses: Vec<String>,
+    owns: Vec<String>,
+    ignore: Vec<String>,
+}
+
+/// A `forge:` annotation found in a source file.
+enum Annotation {
+    Declaration(HintDeclaration),
+    /// `forge:ignore` at the given line
+    Ignore(u32),
+}
+
+impl RepoHints {
+    /// Read the hints file and annotations of a repository.
+    ///
+    /// A repository without a hints file only has its annotations.
+    /// Annotations that can't be understood are skipped with a warning,
+    /// rather than failing the survey over a comment. Source files are read
+    /// in parallel on the current worker pool.
+    pub fn load(repo_path: &Path) -> Result<Self, HintsError> {
+        let mut hints = Self::default();
+
+        let hints_path = repo_path.join(HINTS_FILE);
+        if hints_path.is_file() {
+            let content = std::fs::read_to_string(&hints_path)?;
+            hints.read_hints_file(&hints_path.to_string_lossy(), &content)?;
+        }
+
+        let files = repo_files(repo_path, is_ignored_dir, |path| {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            ANNOTATED_EXTENSIONS.contains(&ext) && path != hints_path
+        });
+        let (annotated, _) = parse_in_parallel(&files, |path, content| {
+            Ok(scan_annotations(&path.to_string_lossy(), content))
+        });
+        for (path, annotations) in annotated {
+            for annotation in annotations {
+                match annotation {
+                    Annotation::Declaration(declaration) => {
+                        hints.declarations.push(declaration);
+                    }
+                    Annotation::Ignore(line) => {
+                        let file = path.to_string_lossy().to_string();
+                        hints.ignored_lines.insert((file.clone(), line));
+                        hints.ignored_lines.insert((file, line + 1));
+                    }
+                }
+            }
+        }
+
+        Ok(hints)
+    }
+
+    /// Add the declarations of a hints file.
+    fn read_hints_file(&mut self, path: &str, content: &str) -> Result<(), HintsError> {
+        let file: HintsFile = serde_yaml::from_str(content)?;
+        self.service_name = file.service;
+        self.context = file.context;
+
+        let lists = [
+            (HintRelation::Calls, file.calls),
+            (HintRelation::Reads, file.reads),
+            (HintRelation::Writes, file.writes),
+            (HintRelation::Publishes, file.publishes),
+            (HintRelation::Subscribes, file.subscribes),
+            (HintRelation::Uses, file.uses),
+            (HintRelation::Owns, file.owns),
+        ];
+        for (relation, targets) in lists {
+            for target in targets {
+                let (target_type, name) = parse_target(&target)?;
+                self.declarations.push(HintDeclaration {
+                    relation,
+                    target: name,
+                    target_type,
+                    source_file: path.to_string(),
+                    source_line: line_of(content, &target),
+                });
+            }
+        }
+
+        for pattern in file.ignore {
+            let compiled =
+                glob::Pattern::new(&pattern).map_err(|e| HintsError::InvalidPattern {
+                    pattern: pattern.clone(),
+                    reason: e.to_string(),
+                })?;
+            self.ignore.push(compiled);
+        }
+        Ok(())
+    }
+
+    /// Whether the repository declares nothing.
+    pub fn is_empty(&self) -> bool {
+        self.service_name.is_none()
+            && self.context.is_none()
+            && self.declarations.is_empty()
+            && self.ignore.is_empty()
+            && self.ignored_lines.is_empty()
+    }
+
+    /// Whether a discovery made in the repository is ignored, by an
+    /// `ignore` pattern of the hints file or a `forge:ignore` annotation.
+    pub fn ignores(&self, repo_path: &Path, discovery: &Discovery) -> bool {
+        let file = discovery.source_file();
+        if self
+            .ignored_lines
+            .contains(&(file.to_string(), discovery.source_line()))
+        {
+            return true;
+        }
+        let relative = Path::new(file)
+            .strip_prefix(repo_path)
+            .unwrap_or(Path::new(file));
+        self.ignore
+            .iter()
+            .any(|pattern| pattern.matches_path(relative))
+    }
+
+    /// Drop the discoveries the repository ignores.
+    pub fn retain_discoveries(&self, repo_path: &Path, discoveries: &mut Vec<Discovery>) {
+        if self.ignore.is_empty() && self.ignored_lines.is_empty() {
+            return;
+        }
+        discoveries.retain(|discovery| !self.ignores(repo_path, discovery));
+    }
+}
+
+/// Split a declared target into its optional type prefix and name.
+fn parse_target(target: &str) -> Result<(Option<NodeType>, String), HintsError> {
+    let invalid = |reason: &str| HintsError::InvalidTarget {
+        target: target.to_string(),
+        reason: reason.to_string(),
+    };
+    let (node_type, name) = match target.split_once(':') {
+        Some((prefix, name)) => {
+            let node_type = prefix
+                .parse::<NodeType>()
+                .map_err(|_| invalid("unknown type prefix"))?;
+            if node_type == NodeType::Api {
+                return Err(invalid("API endpoints can't be declared"));
+            }
+            (Some(node_type), name)
+        }
+        None => (None, target),
+    };
+    let name = name.trim();
+    if name.is_empty() || name.contains(':') {
+        return Err(invalid("expected a name, optionally prefixed with a type"));
+    }
+    Ok((node_type, name.to_string()))
+}
+
+/// The 1-based line of the first occurrence of `text`, or 1.
+fn line_of(content: &str, text: &str) -> u32 {
+    content
+        .lines()
+        .position(|line| line.contains(text))
+        .map_or(1, |index| index as u32 + 1)
+}
+
+/// Find the `forge:` annotations of a source file.
+fn scan_annotations(path: &str, content: &str) -> Vec<Annotation> {
+    if !content.contains("forge:") {
+        return Vec::new();
+    }
+    let mut annotations = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index as u32 + 1;
+        let Some((verb, target)) = parse_annotation(line) else {
+            continue;
+        };
+        if verb == "ignore" {
+            annotations.push(Annotation::Ignore(line_number));
+            continue;
+        }
+        let Some(relation) = HintRelation::from_verb(verb) else {
+            tracing::debug!(
+                "Unknown annotation forge:{} at {}:{}",
+                verb,
+                path,
+                line_number
+            );
+            continue;
+        };
+        let parsed = target
+            .ok_or_else(|| HintsError::InvalidTarget {
+                target: String::new(),
+                reason: "missing target".to_string(),
+            })
+            .and_then(parse_target);
+        match parsed {
+            Ok((target_type, target)) => {
+                annotations.push(Annotation::Declaration(HintDeclaration {
+                    relation,
+                    target,
+                    target_type,
+                    source_file: path.to_string(),
+                    source_line: line_number,
+                }));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping annotation forge:{} at {}:{}: {}",
+                    verb,
+                    path,
+                    line_number,
+                    e
+                );
+            }
+        }
+    }
+    annotations
+}
+
+/// The verb and target of a `forge:` annotation in a line comment.
+///
+/// The annotation must follow a comment marker (`//`, `#`, `--`, `/*`, or
+/// the `*` of a block comment line).
+fn parse_annotation(line: &str) -> Option<(&str, Option<&str>)> {
+    let start = line.find("forge:")?;
+    let before = line[..start].trim_end();
+    if !["//", "#", "--", "/*", "*"]
+        .iter()
+        .any(|marker| before.ends_with(marker))
+    {
+        return None;
+    }
+    let mut words = line[start + "forge:".len()..]
+        .split_whitespace()
+        .map(|word| word.trim_end_matches("*/"))
+        .filter(|word| !word.is_empty());
+    let verb = words.next()?;
+    Some((verb, words.next()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ApiEndpointDiscovery;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_hints_file_and_annotations() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(HINTS_FILE),
+            "service: billing-api\n\
+             context:\n  owner: payments-team\n\
+             calls: [ledger-api]\n\
+             owns:\n  - database:invoices\n\
+             ignore: [\"scripts/**\"]\n",
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/app.ts"),
+            "// forge:publishes queue:billing-events\n\
+             const url = 'forge:calls nothing';\n\
+             const db = new DynamoDB(); // forge:ignore\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("worker.py"), "# forge:reads orders-table\n").unwrap();
+
+        let hints = RepoHints::load(dir.path()).unwrap();
+        assert_eq!(hints.service_name.as_deref(), Some("billing-api"));
+        assert_eq!(
+            hints.context.as_ref().and_then(|c| c.owner.as_deref()),
+            Some("payments-team")
+        );
+
+        let declared: Vec<_> = hints
+            .declarations
+            .iter()
+            .map(|d| (d.relation, d.target.as_str(), d.target_type, d.source_line))
+            .collect();
+        assert_eq!(
+            declared,
+            vec![
+                (HintRelation::Calls, "ledger-api", None, 4),
+                (HintRelation::Owns, "invoices", Some(NodeType::Database), 6),
+                (
+                    HintRelation::Publishes,
+                    "billing-events",
+                    Some(NodeType::Queue),
+                    1
+                ),
+                (HintRelation::Reads, "orders-table", None, 1),
+            ]
+        );
+
+        let source = dir.path().join("src/app.ts").to_string_lossy().to_string();
+        let discovery = |source_file: String, source_line: u32| {
+            Discovery::ApiEndpoint(ApiEndpointDiscovery {
+                source_file,
+                source_line,
+                ..Default::default()
+            })
+        };
+        let script = dir.path().join("scripts/seed.ts");
+        let mut discoveries = vec![
+            discovery(source.clone(), 2),
+            discovery(source.clone(), 3),
+            discovery(source.clone(), 4),
+            discovery(script.to_string_lossy().to_string(), 1),
+        ];
+        hints.retain_discoveries(dir.path(), &mut discoveries);
+        let kept: Vec<u32> = discoveries.iter().map(|d| d.source_line()).collect();
+        assert_eq!(kept, vec![2]);
+    }
+
+    #[test]
+    fn test_invalid_hints_file() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(HINTS_FILE), "owns: [table:orders]\n").unwrap();
+        assert!(matches!(
+            RepoHints::load(dir.path()),
+            Err(HintsError::InvalidTarget { .. })
+        ));
+
+        std::fs::write(dir.path().join(HINTS_FILE), "owner: payments\n").unwrap();
+        assert!(matches!(
+            RepoHints::load(dir.path()),
+            Err(HintsError::Yaml(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_annotation() {
+        assert_eq!(
+            parse_annotation("  // forge:calls billing-api"),
+            Some(("calls", Some("billing-api")))
+        );
+        assert_eq!(
+            parse_annotation("/* forge:owns database:orders */"),
+            Some(("owns", Some("database:orders")))
+        );
+        assert_eq!(
+            parse_annotation("x = 1  # forge:ignore"),
+            Some(("ignore", None))
+        );
+        assert_eq!(parse_annotation("url = 'forge:calls x'"), None);
+    }
+}
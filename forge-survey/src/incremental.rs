@@ -416,12 +416,13 @@ async fn get_git_diff(
 
 /// Check if a file should be parsed based on its extension.
 ///
-/// Returns `true` for JavaScript, TypeScript, Python, and Terraform files.
+/// Returns `true` for JavaScript, TypeScript, Prisma schema, Python, and
+/// Terraform files.
 pub fn is_parseable_file(path: &Path) -> bool {
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
     matches!(
         ext.to_lowercase().as_str(),
-        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "py" | "tf"
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "prisma" | "py" | "tf"
     )
 }
 
@@ -529,6 +530,7 @@ mod tests {
         assert!(is_parseable_file(Path::new("component.jsx")));
         assert!(is_parseable_file(Path::new("module.mjs")));
         assert!(is_parseable_file(Path::new("module.cjs")));
+        assert!(is_parseable_file(Path::new("schema.prisma")));
 
         // Python
         assert!(is_parseable_file(Path::new("main.py")));
@@ -7,7 +7,7 @@
 //!
 //! The forge-survey crate is responsible for:
 //!
-//! - **GitHub Integration**: Discovering and cloning repositories from GitHub organizations
+//! - **Repository Sources**: Discovering and cloning repositories from GitHub, GitLab, Bitbucket and Gitea
 //! - **Code Parsing**: Analyzing source code using tree-sitter AST parsing
 //! - **Discovery**: Detecting services, APIs, databases, and their relationships
 //! - **Coupling Detection**: Finding implicit coupling between services via shared resources
@@ -24,41 +24,84 @@
 //!
 //! # Modules
 //!
-//! - [`github`]: GitHub API client and repository caching
+//! - [`source`]: Repository sources and the cache repositories are cloned to
+//! - [`github`]: GitHub API client
+//! - [`gitlab`]: GitLab API client (groups and subgroups)
+//! - [`bitbucket`]: Bitbucket Cloud API client (workspaces and projects)
+//! - [`gitea`]: Gitea API client
+//! - [`selection`]: Include and exclude filters choosing which repositories are surveyed
+//! - [`cache`]: Content-addressed cache of per-file discoveries
 //! - [`parser`]: Language-specific code parsers and discovery types
 //! - [`graph_builder`]: Converts parser discoveries into a knowledge graph
+//! - [`hints`]: Edges and context repositories declare in `.forge.yaml` and annotations
+//! - [`pool`]: Bounded worker pool that parsing runs on
 //! - [`coupling`]: Implicit coupling detection and resource access tracking
 //! - [`incremental`]: Incremental survey support for efficient re-surveys
+//! - [`ownership`]: Service owners inferred from CODEOWNERS and git history
+//! - [`activity`]: Git activity metrics of each service
+//! - [`terraform_state`]: Terraform state ingestion for resource identities
+//! - [`workspace`]: Monorepo package detection (one service per package)
 
+pub mod activity;
+pub mod bitbucket;
+pub mod cache;
 pub mod coupling;
 pub mod detection;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 pub mod graph_builder;
+pub mod hints;
 pub mod incremental;
+pub mod ownership;
 pub mod parser;
+pub mod pool;
+pub mod selection;
+pub mod source;
+pub mod terraform_state;
+pub mod workspace;
 
-use forge_graph::{ForgeGraph, GraphError};
-use std::collections::HashSet;
-use std::path::PathBuf;
+use forge_graph::{ForgeGraph, GitActivity, GraphError};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+pub use activity::{ActivityOptions, git_activity};
+pub use bitbucket::BitbucketClient;
+pub use cache::{CacheStats, DiscoveryCache, ParserCache};
 pub use coupling::{
     AccessEvidence, AccessType, CouplingAnalysisResult, CouplingAnalyzer, CouplingRisk,
     ImplicitCoupling, OwnershipAssignment, OwnershipReason, ResourceAccessMap, SharedAccess,
 };
 pub use detection::{DetectedLanguage, DetectedLanguages, DetectionMethod, detect_languages};
-pub use github::{CloneMethod, GitHubClient, GitHubError, RepoCache, RepoInfo};
+pub use gitea::GiteaClient;
+pub use github::{GitHubClient, GitHubError};
+pub use gitlab::GitLabClient;
 pub use graph_builder::GraphBuilder;
+pub use hints::{HINTS_FILE, HintDeclaration, HintRelation, HintsError, REPO_DECLARED, RepoHints};
 pub use incremental::{
     ChangeDetector, ChangeError, ChangeResult, RepoState, StateError, SurveyState,
     get_current_commit, is_parseable_file,
 };
 // Re-export commonly used parser types for convenience
+pub use ownership::{CodeOwners, InferredOwner, OwnershipOptions, infer_owners};
 pub use parser::{
-    ApiCallDiscovery, CloudResourceDiscovery, DatabaseAccessDiscovery, DatabaseOperation,
-    Discovery, ImportDiscovery, Parser, ParserError, ParserRegistry, QueueOperationDiscovery,
-    QueueOperationType, ServiceDiscovery,
+    ApiCallDiscovery, ApiEndpointDiscovery, CloudResourceDiscovery, ComponentKind,
+    DatabaseAccessDiscovery, DatabaseOperation, DetectionRule, Discovery,
+    EnvironmentVariableDiscovery, ImportDiscovery, ParseFailure, Parser, ParserError,
+    ParserRegistry, PluginParser, PluginSpec, QueueOperationDiscovery, QueueOperationType,
+    RelationshipDiscovery, RelationshipType, RepoParse, RuleKind, RulePattern, ServiceDiscovery,
+    StackExportDiscovery, StackImportDiscovery,
 };
+pub use pool::WorkerPool;
+pub use selection::{RepoCriteria, RepoFilter, Selection};
+pub use source::{
+    CloneCredentials, CloneMethod, RepoCache, RepoInfo, RepoSource, SourceError, SourceKind,
+    Visibility,
+};
+pub use terraform_state::{StateImportResult, StateResource, TerraformState, TerraformStateError};
+pub use workspace::{PackageOverride, WorkspaceLayout, WorkspacePackage, detect_packages};
 
 #[derive(Debug, Error)]
 pub enum SurveyError {
@@ -77,6 +120,9 @@ pub enum SurveyError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("Worker pool error: {0}")]
+    WorkerPoolError(#[from] rayon::ThreadPoolBuildError),
+
     #[error("Invalid repository format: {0}. Expected 'owner/repo' or local path")]
     InvalidRepoFormat(String),
 
@@ -90,75 +136,223 @@ pub struct SurveyConfig {
     pub exclusions: HashSet<String>,
     pub cache_path: Option<PathBuf>,
     pub github_token: Option<String>,
+    /// Per-directory overrides of package detection, applied to every source.
+    pub packages: Vec<PackageOverride>,
+    /// Parser threads; 0 uses one per CPU.
+    pub jobs: usize,
+    /// Directory of the per-file discovery cache; no caching when `None`.
+    pub discovery_cache: Option<PathBuf>,
+    /// Parser plugins, registered alongside the built-in parsers.
+    pub plugins: Vec<PluginSpec>,
+    /// Detection rules applied by the JavaScript and Python parsers.
+    pub rules: Vec<DetectionRule>,
+    /// How service owners are inferred; not inferred when `None`.
+    pub ownership: Option<OwnershipOptions>,
+    /// How service git activity is measured; not measured when `None`.
+    pub activity: Option<ActivityOptions>,
+}
+
+/// What a survey produced.
+#[derive(Debug)]
+pub struct SurveyOutcome {
+    pub graph: ForgeGraph,
+    /// Files skipped because they failed to parse, in source order.
+    pub failures: Vec<ParseFailure>,
 }
 
 pub async fn survey(config: SurveyConfig) -> Result<ForgeGraph, SurveyError> {
-    let mut builder = GraphBuilder::new();
-    let registry = ParserRegistry::new()?;
+    Ok(survey_with_failures(config).await?.graph)
+}
 
-    for source in &config.sources {
-        tracing::info!("Surveying: {}", source.display());
+/// Survey the sources, also reporting the files that failed to parse.
+///
+/// Sources, and the files inside each, are parsed in parallel on a pool of
+/// `config.jobs` threads. The results are then merged into the graph one
+/// source at a time, in the order the sources are listed, so the graph
+/// doesn't depend on which source finishes parsing first.
+pub async fn survey_with_failures(config: SurveyConfig) -> Result<SurveyOutcome, SurveyError> {
+    let mut registry = ParserRegistry::with_rules(&config.rules)?;
+    for plugin in &config.plugins {
+        registry.register_plugin(plugin.clone());
+    }
+    let pool = WorkerPool::new(config.jobs)?;
+    let cache = config
+        .discovery_cache
+        .as_ref()
+        .map_or_else(DiscoveryCache::disabled, DiscoveryCache::new);
 
-        let detected_langs = detect_languages(source);
-        let exclusions: Vec<String> = config.exclusions.iter().cloned().collect();
-        let parsers = registry.get_for_languages(&detected_langs, &exclusions);
+    let scans: Vec<Option<SourceScan>> = pool.install(|| {
+        config
+            .sources
+            .par_iter()
+            .map(|source| scan_source(&registry, &pool, &cache, source, &config))
+            .collect()
+    });
 
-        if parsers.is_empty() {
-            tracing::warn!("No applicable parsers found for {}", source.display());
+    let mut builder = GraphBuilder::new();
+    let mut failures = Vec::new();
+    for (source, scan) in config.sources.iter().zip(scans) {
+        let Some(scan) = scan else {
             continue;
+        };
+        let service_id = builder.add_service(scan.service);
+        let package_services: Vec<_> = scan
+            .packages
+            .into_iter()
+            .map(|package| {
+                (
+                    source.join(&package.path),
+                    builder.add_service(package.service),
+                )
+            })
+            .collect();
+        builder.process_package_discoveries(scan.discoveries, &package_services, &service_id);
+        builder.add_repo_hints(&scan.hints, &package_services, &service_id);
+        for (dir, owner) in scan.owners {
+            let owner_id = package_services
+                .iter()
+                .find(|(root, _)| *root == source.join(&dir))
+                .map_or(&service_id, |(_, id)| id);
+            builder.set_inferred_owner(owner_id, owner);
         }
+        for (dir, activity) in scan.activity {
+            let activity_id = package_services
+                .iter()
+                .find(|(root, _)| *root == source.join(&dir))
+                .map_or(&service_id, |(_, id)| id);
+            builder.set_git_activity(activity_id, &activity);
+        }
+        failures.extend(scan.failures);
+    }
+
+    Ok(SurveyOutcome {
+        graph: builder.build(),
+        failures,
+    })
+}
 
-        let service_name = source
+/// What parsing one source produced, before it is merged into the graph.
+struct SourceScan {
+    /// The source's own service
+    service: ServiceDiscovery,
+    packages: Vec<WorkspacePackage>,
+    discoveries: Vec<Discovery>,
+    hints: RepoHints,
+    /// Inferred owners by service directory, relative to the source
+    owners: BTreeMap<PathBuf, InferredOwner>,
+    /// Git activity by service directory, relative to the source
+    activity: BTreeMap<PathBuf, GitActivity>,
+    failures: Vec<ParseFailure>,
+}
+
+/// Detect the languages and packages of a source and run its parsers.
+///
+/// Returns `None` when no parser applies to the source.
+fn scan_source(
+    registry: &ParserRegistry,
+    pool: &WorkerPool,
+    cache: &DiscoveryCache,
+    source: &Path,
+    config: &SurveyConfig,
+) -> Option<SourceScan> {
+    tracing::info!("Surveying: {}", source.display());
+
+    let packages = detect_packages(registry, source, &config.packages);
+    let mut detected_langs = registry.detect_languages(source);
+    for package in &packages {
+        for language in registry
+            .detect_languages(&source.join(&package.path))
+            .iter()
+        {
+            detected_langs.add(language.clone());
+        }
+    }
+    let exclusions: Vec<String> = config.exclusions.iter().cloned().collect();
+    let parsers = registry.get_for_languages(&detected_langs, &exclusions);
+
+    if parsers.is_empty() {
+        tracing::warn!("No applicable parsers found for {}", source.display());
+        return None;
+    }
+
+    let mut service = manifest_service(registry, source).unwrap_or_else(|| ServiceDiscovery {
+        name: source
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown_service")
-            .to_string();
-
-        let mut service_id = None;
-
-        if let Some(parser) = registry.get("javascript") {
-            if let Some(js_parser) = parser
-                .as_ref()
-                .as_any()
-                .downcast_ref::<parser::javascript::JavaScriptParser>()
-            {
-                if let Some(service) = js_parser.parse_package_json(source) {
-                    service_id = Some(builder.add_service(service));
-                }
-            }
-        }
+            .to_string(),
+        language: detected_langs
+            .primary()
+            .map(|l| l.name.clone())
+            .unwrap_or_default(),
+        ..Default::default()
+    });
 
-        if service_id.is_none() {
-            if let Some(parser) = registry.get("python") {
-                if let Some(py_parser) = parser
-                    .as_ref()
-                    .as_any()
-                    .downcast_ref::<parser::python::PythonParser>()
-                {
-                    if let Some(service) = py_parser.parse_project_config(source) {
-                        service_id = Some(builder.add_service(service));
-                    }
-                }
+    let mut discoveries = Vec::new();
+    let mut failures = Vec::new();
+    for result in pool.parse_repo(&parsers, source, cache) {
+        match result {
+            Ok(parse) => {
+                discoveries.extend(parse.discoveries);
+                failures.extend(parse.failures);
             }
+            Err(e) => failures.push(ParseFailure {
+                path: source.to_path_buf(),
+                error: e.to_string(),
+            }),
         }
+    }
 
-        let service_id = service_id.unwrap_or_else(|| {
-            builder.add_service(ServiceDiscovery {
-                name: service_name,
-                language: detected_langs
-                    .iter()
-                    .next()
-                    .map(|l| l.name.clone())
-                    .unwrap_or_default(),
-                ..Default::default()
-            })
+    let hints = RepoHints::load(source).unwrap_or_else(|e| {
+        failures.push(ParseFailure {
+            path: source.join(HINTS_FILE),
+            error: e.to_string(),
         });
-
-        for parser in parsers {
-            let discoveries = parser.parse_repo(source)?;
-            builder.process_discoveries(discoveries, &service_id);
-        }
+        RepoHints::default()
+    });
+    hints.retain_discoveries(source, &mut discoveries);
+    if let Some(name) = &hints.service_name {
+        service.name = name.clone();
     }
 
-    Ok(builder.build())
+    let package_dirs: Vec<PathBuf> = packages.iter().map(|p| p.path.clone()).collect();
+    let owners = config
+        .ownership
+        .as_ref()
+        .map_or_else(BTreeMap::new, |options| {
+            infer_owners(source, &package_dirs, options)
+        });
+    let activity = config
+        .activity
+        .as_ref()
+        .map_or_else(BTreeMap::new, |options| {
+            git_activity(source, &package_dirs, options)
+        });
+
+    Some(SourceScan {
+        service,
+        packages,
+        discoveries,
+        hints,
+        owners,
+        activity,
+        failures,
+    })
+}
+
+/// The service declared by a source's package.json or Python project config.
+fn manifest_service(registry: &ParserRegistry, source: &Path) -> Option<ServiceDiscovery> {
+    let js_parser = registry.get("javascript")?;
+    if let Some(service) = js_parser
+        .as_any()
+        .downcast_ref::<parser::javascript::JavaScriptParser>()
+        .and_then(|parser| parser.parse_package_json(source))
+    {
+        return Some(service);
+    }
+    registry
+        .get("python")?
+        .as_any()
+        .downcast_ref::<parser::python::PythonParser>()
+        .and_then(|parser| parser.parse_project_config(source))
 }
@@ -0,0 +1,475 @@
+//! Service ownership inferred from CODEOWNERS and git history.
+//!
+//! The owner of a service is usually already recorded in its repository.
+//! This module reads it from the first CODEOWNERS file found in
+//! `.github/`, the repository root or `docs/` (GitHub's lookup order): each
+//! file of a service is owned by the last rule matching it, and the owners
+//! of the most files own the service.
+//!
+//! Services no CODEOWNERS rule covers, including every service of a
+//! repository without CODEOWNERS, fall back to their most frequent
+//! committers over a recent window of `git log`.
+//!
+//! Inferred owners carry an [`OwnerProvenance`], so the interview can ask
+//! to confirm them instead of asking who the owner is.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use forge_survey::ownership::{OwnershipOptions, infer_owners};
+//!
+//! let owners = infer_owners(&repo_path, &[PathBuf::from("services/billing")], &OwnershipOptions::default());
+//! if let Some(owner) = owners.get(Path::new("services/billing")) {
+//!     println!("billing is owned by {}", owner.owner);
+//! }
+//! ```
+
+use crate::parser::{is_ignored_dir, repo_files};
+use forge_graph::{OwnerProvenance, OwnerSource};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Where CODEOWNERS files are looked for, in order.
+pub const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// How ownership is inferred.
+#[derive(Debug, Clone)]
+pub struct OwnershipOptions {
+    /// Days of git history considered when falling back to committers.
+    pub history_days: u32,
+
+    /// Most frequent committers named as owners.
+    pub max_committers: usize,
+}
+
+impl Default for OwnershipOptions {
+    fn default() -> Self {
+        Self {
+            history_days: 180,
+            max_committers: 3,
+        }
+    }
+}
+
+/// An owner inferred for a service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredOwner {
+    /// Owners, comma-separated (e.g., "@acme/payments, @jane").
+    pub owner: String,
+
+    /// What the owner was inferred from.
+    pub provenance: OwnerProvenance,
+}
+
+/// A rule of a CODEOWNERS file.
+#[derive(Debug, Clone)]
+pub struct CodeOwnersRule {
+    /// The pattern as written.
+    pub pattern: String,
+
+    /// Owners of the files matching the pattern; empty for files left
+    /// without an owner.
+    pub owners: Vec<String>,
+
+    /// Line of the rule in the file.
+    pub line: u32,
+
+    matchers: Vec<glob::Pattern>,
+}
+
+/// A parsed CODEOWNERS file.
+#[derive(Debug, Clone)]
+pub struct CodeOwners {
+    /// Path of the file, relative to the repository.
+    pub file: String,
+
+    pub rules: Vec<CodeOwnersRule>,
+}
+
+impl CodeOwners {
+    /// Load the repository's CODEOWNERS file, if it has one.
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        CODEOWNERS_LOCATIONS.iter().find_map(|location| {
+            let content = std::fs::read_to_string(repo_path.join(location)).ok()?;
+            Some(Self::parse(location, &content))
+        })
+    }
+
+    /// Parse CODEOWNERS content. Rules with invalid patterns are skipped.
+    pub fn parse(file: &str, content: &str) -> Self {
+        let mut rules = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let Some(pattern) = words.next() else {
+                continue;
+            };
+            let owners: Vec<String> = words
+                .take_while(|word| !word.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            let matchers: Result<Vec<_>, _> = codeowners_globs(pattern)
+                .iter()
+                .map(|glob| glob::Pattern::new(glob))
+                .collect();
+            match matchers {
+                Ok(matchers) => rules.push(CodeOwnersRule {
+                    pattern: pattern.to_string(),
+                    owners,
+                    line: index as u32 + 1,
+                    matchers,
+                }),
+                Err(e) => {
+                    tracing::debug!("Skipping CODEOWNERS pattern {}: {}", pattern, e);
+                }
+            }
+        }
+        Self {
+            file: file.to_string(),
+            rules,
+        }
+    }
+
+    /// The rule deciding who owns a file (the last one matching it).
+    ///
+    /// `path` is relative to the repository.
+    pub fn rule_for(&self, path: &Path) -> Option<&CodeOwnersRule> {
+        self.rule_index(path).map(|index| &self.rules[index])
+    }
+
+    /// Index of the rule deciding who owns a file.
+    fn rule_index(&self, path: &Path) -> Option<usize> {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        self.rules.iter().rposition(|rule| {
+            rule.matchers
+                .iter()
+                .any(|matcher| matcher.matches_path_with(path, options))
+        })
+    }
+}
+
+/// Translate a CODEOWNERS (gitignore-style) pattern to globs matching the
+/// files it covers.
+///
+/// Patterns with a slash other than a trailing one are relative to the
+/// repository root; others match at any depth. A pattern naming a
+/// directory covers everything below it.
+fn codeowners_globs(pattern: &str) -> Vec<String> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let mut glob = trimmed.trim_start_matches('/').to_string();
+    if !trimmed.contains('/') && !glob.starts_with("**") {
+        glob = format!("**/{}", glob);
+    }
+    if glob.ends_with("**") {
+        vec![glob]
+    } else if directory {
+        vec![format!("{}/**", glob)]
+    } else {
+        // A name can be a file or a directory
+        vec![format!("{}/**", glob), glob]
+    }
+}
+
+/// Infer the owners of a repository's services.
+///
+/// `package_dirs` are the directories of the repository's packages,
+/// relative to it. Owners are keyed by the directory of their service, the
+/// repository's own service being the empty path. A file belongs to the
+/// innermost package containing it.
+pub fn infer_owners(
+    repo_path: &Path,
+    package_dirs: &[PathBuf],
+    options: &OwnershipOptions,
+) -> BTreeMap<PathBuf, InferredOwner> {
+    let mut owners = match CodeOwners::load(repo_path) {
+        Some(codeowners) => codeowners_owners(repo_path, package_dirs, &codeowners),
+        None => BTreeMap::new(),
+    };
+
+    let services = std::iter::once(PathBuf::new()).chain(package_dirs.iter().cloned());
+    for dir in services {
+        if owners.contains_key(&dir) {
+            continue;
+        }
+        if let Some(owner) = committer_owners(repo_path, &dir, package_dirs, options) {
+            owners.insert(dir, owner);
+        }
+    }
+    owners
+}
+
+/// Owners of each service according to CODEOWNERS.
+fn codeowners_owners(
+    repo_path: &Path,
+    package_dirs: &[PathBuf],
+    codeowners: &CodeOwners,
+) -> BTreeMap<PathBuf, InferredOwner> {
+    // Files of each service, counted by the index of the rule owning them
+    let mut counts: BTreeMap<PathBuf, (usize, BTreeMap<usize, usize>)> = BTreeMap::new();
+    for path in repo_files(repo_path, is_ignored_dir, |_| true) {
+        let Ok(relative) = path.strip_prefix(repo_path) else {
+            continue;
+        };
+        let dir = package_dirs
+            .iter()
+            .filter(|dir| relative.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+            .cloned()
+            .unwrap_or_default();
+        let (files, rules) = counts.entry(dir).or_default();
+        *files += 1;
+        if let Some(index) = codeowners.rule_index(relative) {
+            *rules.entry(index).or_default() += 1;
+        }
+    }
+
+    let mut owners = BTreeMap::new();
+    for (dir, (files, rules)) in counts {
+        let Some((index, count)) = rules
+            .into_iter()
+            .filter(|(index, _)| !codeowners.rules[*index].owners.is_empty())
+            .max_by_key(|&(index, count)| (count, index))
+        else {
+            continue;
+        };
+        let rule = &codeowners.rules[index];
+        owners.insert(
+            dir,
+            InferredOwner {
+                owner: rule.owners.join(", "),
+                provenance: OwnerProvenance {
+                    source: OwnerSource::Codeowners,
+                    evidence: vec![
+                        format!("{}:{} {}", codeowners.file, rule.line, rule.pattern),
+                        format!("{} of {} files", count, files),
+                    ],
+                },
+            },
+        );
+    }
+    owners
+}
+
+/// `git` pathspecs selecting a service's directory, without the packages
+/// nested in it: those are services of their own.
+pub(crate) fn service_pathspecs(dir: &Path, package_dirs: &[PathBuf]) -> Vec<OsString> {
+    let mut pathspecs = vec![if dir.as_os_str().is_empty() {
+        OsString::from(".")
+    } else {
+        dir.as_os_str().to_owned()
+    }];
+    for package in package_dirs {
+        if package != dir && package.starts_with(dir) {
+            pathspecs.push(format!(":(exclude){}", package.display()).into());
+        }
+    }
+    pathspecs
+}
+
+/// The most frequent recent committers to a service's directory.
+///
+/// Merge commits and bots are not counted. Returns `None` when the
+/// repository has no history in the window, or isn't a git repository.
+fn committer_owners(
+    repo_path: &Path,
+    dir: &Path,
+    package_dirs: &[PathBuf],
+    options: &OwnershipOptions,
+) -> Option<InferredOwner> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo_path)
+        .arg("log")
+        .arg("--no-merges")
+        .arg(format!("--since={} days ago", options.history_days))
+        .arg("--format=%aN")
+        .arg("--")
+        .args(service_pathspecs(dir, package_dirs));
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        tracing::debug!(
+            "git log failed in {}: {}",
+            repo_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+
+    let mut commits: HashMap<String, usize> = HashMap::new();
+    for author in String::from_utf8_lossy(&output.stdout).lines() {
+        let author = author.trim();
+        if author.is_empty() || author.ends_with("[bot]") {
+            continue;
+        }
+        *commits.entry(author.to_string()).or_default() += 1;
+    }
+
+    let mut ranked: Vec<(String, usize)> = commits.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(options.max_committers.max(1));
+    if ranked.is_empty() {
+        return None;
+    }
+
+    Some(InferredOwner {
+        owner: ranked
+            .iter()
+            .map(|(author, _)| author.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        provenance: OwnerProvenance {
+            source: OwnerSource::GitHistory,
+            evidence: ranked
+                .iter()
+                .map(|(author, count)| {
+                    format!(
+                        "{} commits by {} in the last {} days",
+                        count, author, options.history_days
+                    )
+                })
+                .collect(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_codeowners_last_matching_rule_wins() {
+        let codeowners = CodeOwners::parse(
+            "CODEOWNERS",
+            "# Default owners\n\
+             *       @acme/platform\n\
+             *.py    @acme/data  # python\n\
+             /services/billing/ @acme/payments @jane\n\
+             docs                @acme/writers\n\
+             /services/billing/generated/\n",
+        );
+        let owners = |path: &str| {
+            codeowners
+                .rule_for(Path::new(path))
+                .map(|rule| rule.owners.join(" "))
+        };
+        assert_eq!(owners("README.md").as_deref(), Some("@acme/platform"));
+        assert_eq!(owners("jobs/etl/run.py").as_deref(), Some("@acme/data"));
+        assert_eq!(
+            owners("services/billing/src/app.py").as_deref(),
+            Some("@acme/payments @jane")
+        );
+        assert_eq!(
+            owners("api/docs/index.md").as_deref(),
+            Some("@acme/writers")
+        );
+        assert_eq!(
+            owners("services/billing/generated/x.ts").as_deref(),
+            Some("")
+        );
+        assert_eq!(codeowners.rule_for(Path::new("README.md")).unwrap().line, 2);
+    }
+
+    #[test]
+    fn test_infer_owners_from_codeowners() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            ".github/CODEOWNERS",
+            "* @acme/platform\n/services/billing/ @acme/payments\n/services/billing/README.md @jane\n",
+        );
+        write(dir.path(), "index.js", "");
+        write(dir.path(), "services/billing/src/app.js", "");
+        write(dir.path(), "services/billing/src/db.js", "");
+        write(dir.path(), "services/billing/README.md", "");
+
+        let owners = infer_owners(
+            dir.path(),
+            &[PathBuf::from("services/billing")],
+            &OwnershipOptions::default(),
+        );
+
+        let root = &owners[Path::new("")];
+        assert_eq!(root.owner, "@acme/platform");
+        let billing = &owners[Path::new("services/billing")];
+        assert_eq!(billing.owner, "@acme/payments");
+        assert_eq!(billing.provenance.source, OwnerSource::Codeowners);
+        assert_eq!(
+            billing.provenance.evidence,
+            vec![
+                ".github/CODEOWNERS:2 /services/billing/".to_string(),
+                "2 of 3 files".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infer_owners_from_git_history() {
+        let dir = TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args([
+                    "-c",
+                    "user.email=dev@example.com",
+                    "-c",
+                    "commit.gpgsign=false",
+                ])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(status.status.success(), "{:?}", status);
+        };
+        git(&["init", "-q"]);
+        for (author, file) in [
+            ("Jane", "a.js"),
+            ("Sam", "b.js"),
+            ("Jane", "c.js"),
+            ("dependabot[bot]", "d.js"),
+            ("Alex", "pkg/e.js"),
+        ] {
+            write(dir.path(), file, author);
+            git(&["add", "."]);
+            git(&[
+                "-c",
+                &format!("user.name={}", author),
+                "commit",
+                "-qm",
+                file,
+            ]);
+        }
+
+        let options = OwnershipOptions {
+            max_committers: 2,
+            ..Default::default()
+        };
+        let owners = infer_owners(dir.path(), &[PathBuf::from("pkg")], &options);
+
+        let root = &owners[Path::new("")];
+        assert_eq!(root.owner, "Jane, Sam");
+        assert_eq!(root.provenance.source, OwnerSource::GitHistory);
+        assert_eq!(
+            root.provenance.evidence[0],
+            "2 commits by Jane in the last 180 days"
+        );
+        assert_eq!(owners[Path::new("pkg")].owner, "Alex");
+    }
+}
@@ -0,0 +1,608 @@
+//! AWS CDK construct recognition shared by the JavaScript/TypeScript and Python parsers.
+//!
+//! The language parsers find the syntax (imports, `new dynamodb.Table(...)`,
+//! `table.grantReadData(fn)`, `fn.addEventSource(...)`) in their own ASTs and
+//! hand it to a [`CdkCollector`], which maps constructs onto discoveries:
+//!
+//! - `dynamodb.Table` / `TableV2` -> `DatabaseAccessDiscovery`
+//! - `sqs.Queue`, `sns.Topic` -> `QueueOperationDiscovery`
+//! - `s3.Bucket` -> `CloudResourceDiscovery`
+//! - `lambda.Function` (and `NodejsFunction`, `PythonFunction`, ...) -> `ServiceDiscovery`
+//!
+//! Grants and event sources become relationships from the function to the
+//! resource. Grants use the [`CDK_GRANT_DETECTION_METHOD`] detection method so
+//! the coupling analyzer can treat write grants as ownership hints.
+//!
+//! All discoveries carry a `deployment_method` of "cdk".
+
+use super::traits::*;
+use std::collections::HashMap;
+
+/// Detection method for constructs and event sources.
+pub(crate) const CDK_DETECTION_METHOD: &str = "cdk";
+
+/// Detection method for relationships created from `grant*` calls.
+pub(crate) const CDK_GRANT_DETECTION_METHOD: &str = "cdk-grant";
+
+/// Kinds of CDK constructs Forge understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CdkConstructKind {
+    Table,
+    Queue,
+    Topic,
+    Bucket,
+    Function,
+}
+
+impl CdkConstructKind {
+    fn component_kind(&self) -> ComponentKind {
+        match self {
+            CdkConstructKind::Table => ComponentKind::Database,
+            CdkConstructKind::Queue | CdkConstructKind::Topic => ComponentKind::Queue,
+            CdkConstructKind::Bucket => ComponentKind::CloudResource,
+            CdkConstructKind::Function => ComponentKind::Service,
+        }
+    }
+
+    /// Property holding the physical name (normalized, see [`normalize_name`]).
+    fn name_property(&self) -> &'static str {
+        match self {
+            CdkConstructKind::Table => "tablename",
+            CdkConstructKind::Queue => "queuename",
+            CdkConstructKind::Topic => "topicname",
+            CdkConstructKind::Bucket => "bucketname",
+            CdkConstructKind::Function => "functionname",
+        }
+    }
+}
+
+/// Check if an import path refers to the CDK (v2 `aws-cdk-lib`, v1 `@aws-cdk/*`
+/// or Python `aws_cdk`).
+pub(crate) fn is_cdk_module(module: &str) -> bool {
+    module == "aws-cdk-lib"
+        || module.starts_with("aws-cdk-lib/")
+        || module.starts_with("@aws-cdk/")
+        || module == "aws_cdk"
+        || module.starts_with("aws_cdk.")
+}
+
+/// The AWS service a CDK module or module alias refers to.
+///
+/// `aws-cdk-lib/aws-dynamodb`, `@aws-cdk/aws-dynamodb`, `aws_dynamodb` and
+/// `aws_cdk.aws_dynamodb` all map to `dynamodb`. Returns `None` for the root
+/// module.
+pub(crate) fn cdk_service(module: &str) -> Option<String> {
+    if module == "aws-cdk-lib" || module == "aws_cdk" {
+        return None;
+    }
+    let last = module.rsplit(['/', '.']).next().unwrap_or(module);
+    let service = last
+        .strip_prefix("aws-")
+        .or_else(|| last.strip_prefix("aws_"))?;
+    Some(service.replace('-', "_"))
+}
+
+/// Resolve a construct class within a CDK service module.
+pub(crate) fn construct_kind(service: &str, class: &str) -> Option<CdkConstructKind> {
+    match (service, class) {
+        ("dynamodb", "Table" | "TableV2") => Some(CdkConstructKind::Table),
+        ("sqs", "Queue") => Some(CdkConstructKind::Queue),
+        ("sns", "Topic") => Some(CdkConstructKind::Topic),
+        ("s3", "Bucket") => Some(CdkConstructKind::Bucket),
+        (service, class) if service.starts_with("lambda") && class.ends_with("Function") => {
+            Some(CdkConstructKind::Function)
+        }
+        _ => None,
+    }
+}
+
+/// Resolve an event source class (`aws-lambda-event-sources`) to the kind of
+/// construct it wraps.
+pub(crate) fn event_source_kind(class: &str) -> Option<CdkConstructKind> {
+    match class {
+        "SqsEventSource" => Some(CdkConstructKind::Queue),
+        "SnsEventSource" => Some(CdkConstructKind::Topic),
+        "DynamoEventSource" => Some(CdkConstructKind::Table),
+        "S3EventSource" | "S3EventSourceV2" => Some(CdkConstructKind::Bucket),
+        _ => None,
+    }
+}
+
+/// Relationships implied by a grant method on a construct.
+///
+/// Accepts both `grantReadData` and `grant_read_data`.
+fn grant_relationships(kind: CdkConstructKind, method: &str) -> &'static [RelationshipType] {
+    use RelationshipType::*;
+
+    match (kind, normalize_name(method).as_str()) {
+        (CdkConstructKind::Table, "grantreaddata" | "grantstreamread") => &[Reads],
+        (CdkConstructKind::Table, "grantwritedata") => &[Writes],
+        (CdkConstructKind::Table, "grantreadwritedata" | "grantfullaccess") => &[Reads, Writes],
+        (CdkConstructKind::Queue, "grantsendmessages") => &[Publishes],
+        (CdkConstructKind::Queue, "grantconsumemessages" | "grantpurge") => &[Subscribes],
+        (CdkConstructKind::Topic, "grantpublish") => &[Publishes],
+        (CdkConstructKind::Topic, "grantsubscribe") => &[Subscribes],
+        // Buckets are cloud resources, which only support USES edges
+        (
+            CdkConstructKind::Bucket,
+            "grantread" | "grantwrite" | "grantreadwrite" | "grantput" | "grantdelete",
+        ) => &[Uses],
+        _ => &[],
+    }
+}
+
+/// Normalize a property or method name so camelCase and snake_case compare
+/// equal (`tableName`, `table_name` -> `tablename`).
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// CDK names imported into a file, used to resolve constructor expressions.
+#[derive(Debug, Default)]
+pub(crate) struct CdkImports {
+    /// Module aliases (`dynamodb`, `cdk`) to their AWS service (`None` for
+    /// the root module).
+    aliases: HashMap<String, Option<String>>,
+    /// Directly imported classes (`Table`) to `(service, class)`.
+    classes: HashMap<String, (String, String)>,
+}
+
+impl CdkImports {
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty() && self.classes.is_empty()
+    }
+
+    /// Record a whole-module import (`import * as dynamodb from '...'`,
+    /// `import aws_cdk.aws_sqs as sqs`).
+    pub fn add_module(&mut self, alias: &str, module: &str) {
+        if is_cdk_module(module) {
+            self.aliases.insert(alias.to_string(), cdk_service(module));
+        }
+    }
+
+    /// Record a named import (`import { Table } from '...'`,
+    /// `from aws_cdk import aws_sqs as sqs`).
+    pub fn add_named(&mut self, local: &str, imported: &str, module: &str) {
+        if !is_cdk_module(module) {
+            return;
+        }
+        match cdk_service(module) {
+            Some(service) => {
+                self.classes
+                    .insert(local.to_string(), (service, imported.to_string()));
+            }
+            // Service modules re-exported from the root (`aws_sqs`)
+            None if imported.starts_with("aws_") => {
+                self.aliases
+                    .insert(local.to_string(), cdk_service(imported));
+            }
+            None => {}
+        }
+    }
+
+    /// Resolve a constructor expression (`dynamodb.Table`,
+    /// `cdk.aws_dynamodb.Table`, `Table`) to `(service, class)`.
+    pub fn resolve(&self, callee: &str) -> Option<(String, String)> {
+        let segments: Vec<&str> = callee.split('.').map(str::trim).collect();
+        match segments.as_slice() {
+            [class] => self.classes.get(*class).cloned(),
+            [first, middle @ .., class] => {
+                let alias = self.aliases.get(*first)?;
+                let service = middle
+                    .iter()
+                    .rev()
+                    .find(|s| s.starts_with("aws_"))
+                    .and_then(|s| cdk_service(s))
+                    .or_else(|| alias.clone())?;
+                Some((service, class.to_string()))
+            }
+            [] => None,
+        }
+    }
+}
+
+/// A construct instantiation found by a language parser.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConstructCall<'a> {
+    /// Variable or attribute the construct is assigned to (`table`, `this.table`).
+    pub binding: Option<&'a str>,
+    /// Construct id (second constructor argument).
+    pub construct_id: Option<String>,
+    /// Properties keyed by normalized name. String literals are unquoted;
+    /// other values hold their source text.
+    pub props: HashMap<String, String>,
+    pub line: u32,
+}
+
+/// A construct recorded by the collector.
+#[derive(Debug, Clone)]
+struct Construct {
+    kind: CdkConstructKind,
+    name: String,
+}
+
+/// A grant or event source waiting for all constructs to be known.
+#[derive(Debug, Clone)]
+struct PendingAccess {
+    function: String,
+    resource: String,
+    /// Grant method, or `None` for event sources.
+    grant: Option<String>,
+    /// Event source kind (for event sources only).
+    source_kind: Option<CdkConstructKind>,
+    line: u32,
+}
+
+/// Collects CDK constructs, grants and event sources for a single file.
+pub(crate) struct CdkCollector {
+    source_file: String,
+    /// Constructs by binding.
+    constructs: HashMap<String, Construct>,
+    pending: Vec<PendingAccess>,
+    discoveries: Vec<Discovery>,
+}
+
+impl CdkCollector {
+    pub fn new(source_file: String) -> Self {
+        Self {
+            source_file,
+            constructs: HashMap::new(),
+            pending: Vec::new(),
+            discoveries: Vec::new(),
+        }
+    }
+
+    /// Record a construct instantiation.
+    pub fn add_construct(&mut self, kind: CdkConstructKind, call: ConstructCall) {
+        let Some(name) = call
+            .props
+            .get(kind.name_property())
+            .filter(|n| is_literal_name(n))
+            .cloned()
+            .or(call.construct_id)
+        else {
+            return;
+        };
+
+        if let Some(binding) = call.binding {
+            self.constructs.insert(
+                binding.to_string(),
+                Construct {
+                    kind,
+                    name: name.clone(),
+                },
+            );
+        }
+
+        let metadata = DeploymentMetadata {
+            deployment_method: CDK_DETECTION_METHOD.to_string(),
+            ..Default::default()
+        };
+
+        let discovery = match kind {
+            CdkConstructKind::Table => Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+                db_type: "dynamodb".to_string(),
+                table_name: Some(name),
+                connection: None,
+                operation: DatabaseOperation::Unknown, // Construct defines table, not operations
+                detection_method: CDK_DETECTION_METHOD.to_string(),
+                source_file: self.source_file.clone(),
+                source_line: call.line,
+                deployment_metadata: Some(metadata),
+            }),
+            CdkConstructKind::Queue | CdkConstructKind::Topic => {
+                Discovery::QueueOperation(QueueOperationDiscovery {
+                    queue_type: if kind == CdkConstructKind::Queue {
+                        "sqs"
+                    } else {
+                        "sns"
+                    }
+                    .to_string(),
+                    queue_name: Some(name),
+                    operation: QueueOperationType::Unknown,
+                    source_file: self.source_file.clone(),
+                    source_line: call.line,
+                    deployment_metadata: Some(metadata),
+                })
+            }
+            CdkConstructKind::Bucket => Discovery::CloudResourceUsage(CloudResourceDiscovery {
+                resource_type: "s3".to_string(),
+                resource_name: Some(name),
+                source_file: self.source_file.clone(),
+                source_line: call.line,
+                deployment_metadata: Some(metadata),
+            }),
+            CdkConstructKind::Function => Discovery::Service(ServiceDiscovery {
+                name,
+                language: runtime_language(call.props.get("runtime").map(String::as_str)),
+                framework: Some("aws-lambda".to_string()),
+                entry_point: call
+                    .props
+                    .get("handler")
+                    .or_else(|| call.props.get("entry"))
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                source_file: self.source_file.clone(),
+                source_line: call.line,
+                deployment_metadata: Some(metadata),
+            }),
+        };
+
+        self.discoveries.push(discovery);
+    }
+
+    /// Record a `grant*` call (`resource.grantWriteData(function)`).
+    pub fn add_grant(&mut self, resource: &str, method: &str, function: &str, line: u32) {
+        self.pending.push(PendingAccess {
+            function: function.to_string(),
+            resource: resource.to_string(),
+            grant: Some(method.to_string()),
+            source_kind: None,
+            line,
+        });
+    }
+
+    /// Record an event source (`function.addEventSource(new SqsEventSource(queue))`).
+    pub fn add_event_source(
+        &mut self,
+        function: &str,
+        source_class: &str,
+        resource: &str,
+        line: u32,
+    ) {
+        let Some(source_kind) = event_source_kind(source_class) else {
+            return;
+        };
+        self.pending.push(PendingAccess {
+            function: function.to_string(),
+            resource: resource.to_string(),
+            grant: None,
+            source_kind: Some(source_kind),
+            line,
+        });
+    }
+
+    /// Resolve grants and event sources and return all discoveries.
+    ///
+    /// Grants and event sources are only resolved when both the function and
+    /// the resource were created in the same file.
+    pub fn into_discoveries(mut self) -> Vec<Discovery> {
+        for access in std::mem::take(&mut self.pending) {
+            let (Some(function), Some(resource)) = (
+                self.constructs.get(&access.function),
+                self.constructs.get(&access.resource),
+            ) else {
+                continue;
+            };
+            if function.kind != CdkConstructKind::Function {
+                continue;
+            }
+
+            let (relationships, detection_method): (&[RelationshipType], &str) =
+                match (&access.grant, access.source_kind) {
+                    (Some(method), _) => (
+                        grant_relationships(resource.kind, method),
+                        CDK_GRANT_DETECTION_METHOD,
+                    ),
+                    (None, Some(kind)) if kind == resource.kind => (
+                        match kind {
+                            CdkConstructKind::Table => &[RelationshipType::Reads],
+                            CdkConstructKind::Bucket => &[RelationshipType::Uses],
+                            _ => &[RelationshipType::Subscribes],
+                        },
+                        CDK_DETECTION_METHOD,
+                    ),
+                    _ => (&[], CDK_DETECTION_METHOD),
+                };
+
+            for relationship in relationships {
+                self.discoveries
+                    .push(Discovery::Relationship(RelationshipDiscovery {
+                        source_service: function.name.clone(),
+                        target: resource.name.clone(),
+                        target_kind: resource.kind.component_kind(),
+                        relationship: *relationship,
+                        detection_method: detection_method.to_string(),
+                        source_file: self.source_file.clone(),
+                        source_line: access.line,
+                    }));
+            }
+        }
+
+        self.discoveries
+    }
+}
+
+/// Whether a property value is a usable literal name (not an expression).
+fn is_literal_name(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Infer the function language from a `Runtime` expression
+/// (`lambda.Runtime.NODEJS_20_X`, `_lambda.Runtime.PYTHON_3_12`).
+fn runtime_language(runtime: Option<&str>) -> String {
+    let Some(runtime) = runtime else {
+        return "unknown".to_string();
+    };
+    let constant = runtime.rsplit('.').next().unwrap_or(runtime).to_lowercase();
+
+    match constant.as_str() {
+        r if r.starts_with("python") => "python",
+        r if r.starts_with("nodejs") => "javascript",
+        r if r.starts_with("java") => "java",
+        r if r.starts_with("go") => "go",
+        r if r.starts_with("ruby") => "ruby",
+        r if r.starts_with("dotnet") => "csharp",
+        r if r.starts_with("provided") => "custom",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cdk_service_from_module() {
+        assert_eq!(
+            cdk_service("aws-cdk-lib/aws-dynamodb").as_deref(),
+            Some("dynamodb")
+        );
+        assert_eq!(cdk_service("@aws-cdk/aws-sqs").as_deref(), Some("sqs"));
+        assert_eq!(cdk_service("aws_sns").as_deref(), Some("sns"));
+        assert_eq!(
+            cdk_service("aws_cdk.aws_lambda_event_sources").as_deref(),
+            Some("lambda_event_sources")
+        );
+        assert_eq!(
+            cdk_service("aws-cdk-lib/aws-lambda-nodejs").as_deref(),
+            Some("lambda_nodejs")
+        );
+        assert_eq!(cdk_service("aws-cdk-lib"), None);
+    }
+
+    #[test]
+    fn test_cdk_imports_resolve() {
+        let mut imports = CdkImports::default();
+        imports.add_module("cdk", "aws-cdk-lib");
+        imports.add_module("dynamodb", "aws-cdk-lib/aws-dynamodb");
+        imports.add_module("fs", "fs");
+        imports.add_named("sqs", "aws_sqs", "aws-cdk-lib");
+        imports.add_named("Stack", "Stack", "aws-cdk-lib");
+        imports.add_named("Bucket", "Bucket", "aws_cdk.aws_s3");
+
+        let resolved = |callee: &str| imports.resolve(callee);
+        assert_eq!(
+            resolved("dynamodb.Table"),
+            Some(("dynamodb".to_string(), "Table".to_string()))
+        );
+        assert_eq!(
+            resolved("cdk.aws_lambda.Function"),
+            Some(("lambda".to_string(), "Function".to_string()))
+        );
+        assert_eq!(
+            resolved("sqs.Queue"),
+            Some(("sqs".to_string(), "Queue".to_string()))
+        );
+        assert_eq!(
+            resolved("Bucket"),
+            Some(("s3".to_string(), "Bucket".to_string()))
+        );
+        assert_eq!(resolved("Stack"), None);
+        assert_eq!(resolved("fs.Stats"), None);
+    }
+
+    #[test]
+    fn test_construct_kind() {
+        assert_eq!(
+            construct_kind("dynamodb", "Table"),
+            Some(CdkConstructKind::Table)
+        );
+        assert_eq!(
+            construct_kind("lambda_nodejs", "NodejsFunction"),
+            Some(CdkConstructKind::Function)
+        );
+        assert_eq!(construct_kind("dynamodb", "Attribute"), None);
+        assert_eq!(construct_kind("sqs", "Topic"), None);
+    }
+
+    #[test]
+    fn test_grant_and_event_source_resolution() {
+        let mut collector = CdkCollector::new("lib/stack.ts".to_string());
+        collector.add_construct(
+            CdkConstructKind::Table,
+            ConstructCall {
+                binding: Some("this.table"),
+                construct_id: Some("Orders".to_string()),
+                props: HashMap::from([("tablename".to_string(), "orders".to_string())]),
+                line: 3,
+            },
+        );
+        collector.add_construct(
+            CdkConstructKind::Queue,
+            ConstructCall {
+                binding: Some("queue"),
+                construct_id: Some("OrderEvents".to_string()),
+                line: 4,
+                ..Default::default()
+            },
+        );
+        collector.add_construct(
+            CdkConstructKind::Function,
+            ConstructCall {
+                binding: Some("handler"),
+                construct_id: Some("OrdersHandler".to_string()),
+                props: HashMap::from([
+                    (
+                        "runtime".to_string(),
+                        "lambda.Runtime.PYTHON_3_12".to_string(),
+                    ),
+                    ("handler".to_string(), "app.handler".to_string()),
+                ]),
+                line: 5,
+            },
+        );
+        collector.add_grant("this.table", "grantReadWriteData", "handler", 9);
+        collector.add_event_source("handler", "SqsEventSource", "queue", 10);
+        // Unknown grantee is ignored
+        collector.add_grant("this.table", "grantReadData", "props.other", 11);
+
+        let discoveries = collector.into_discoveries();
+
+        let function = discoveries
+            .iter()
+            .find_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(function.name, "OrdersHandler");
+        assert_eq!(function.language, "python");
+        assert_eq!(function.entry_point, "app.handler");
+
+        let relationships: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(relationships.len(), 3);
+        assert!(relationships.iter().any(|r| r.target == "orders"
+            && r.relationship == RelationshipType::Writes
+            && r.detection_method == CDK_GRANT_DETECTION_METHOD));
+        assert!(relationships.iter().any(|r| r.target == "OrderEvents"
+            && r.relationship == RelationshipType::Subscribes
+            && r.detection_method == CDK_DETECTION_METHOD));
+    }
+
+    #[test]
+    fn test_non_literal_name_falls_back_to_construct_id() {
+        let mut collector = CdkCollector::new("app.py".to_string());
+        collector.add_construct(
+            CdkConstructKind::Bucket,
+            ConstructCall {
+                binding: None,
+                construct_id: Some("Uploads".to_string()),
+                props: HashMap::from([(
+                    "bucketname".to_string(),
+                    "f\"{prefix}-uploads\"".to_string(),
+                )]),
+                line: 1,
+            },
+        );
+
+        let discoveries = collector.into_discoveries();
+        assert!(matches!(
+            &discoveries[0],
+            Discovery::CloudResourceUsage(r) if r.resource_name.as_deref() == Some("Uploads")
+        ));
+    }
+}
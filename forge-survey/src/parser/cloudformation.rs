@@ -8,11 +8,21 @@
 //! - Template Transform field (SAM vs CloudFormation detection)
 //! - Parameters section (environment detection)
 //! - Resource properties (names, configurations)
-
+//!
+//! Intrinsic functions are resolved before resources are extracted (see
+//! [`TemplateScope`]). Functions referencing resources become relationships,
+//! local nested stacks (`AWS::CloudFormation::Stack`) are evaluated with the
+//! parameters their parent passes, and `Outputs` exports and
+//! `Fn::ImportValue` consumers are reported so the graph builder can link
+//! stacks across repositories. Function environment variables are reported
+//! so names the code reads from them can be resolved.
+
+use super::cloudformation_eval::{Binding, Exports, Reference, TemplateScope, scalar_string};
 use super::traits::*;
+use crate::cache::DiscoveryCache;
 use std::any::Any;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 
 /// Parser for CloudFormation and SAM YAML/JSON templates.
 pub struct CloudFormationParser {}
@@ -34,6 +44,34 @@ impl CloudFormationParser {
             .map_err(|e| ParserError::TreeSitterError(format!("JSON parse error: {}", e)))
     }
 
+    /// Parse a file as a template, or `None` if it is not one.
+    fn load_template(
+        &self,
+        path: &Path,
+        content: &str,
+    ) -> Result<Option<serde_yaml::Value>, ParserError> {
+        // Determine file format
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+
+        // Parse based on extension
+        let template: serde_yaml::Value = if ext == "json" {
+            // Parse JSON and convert to YAML Value for uniform processing
+            let json_value = self.parse_json(content)?;
+            serde_json::from_value(json_value).map_err(|e| {
+                ParserError::TreeSitterError(format!("JSON to YAML conversion error: {}", e))
+            })?
+        } else {
+            self.parse_yaml(content)?
+        };
+
+        // Check if this is a valid CloudFormation/SAM template
+        if !self.is_template(&template) && !self.is_template_filename(path) {
+            return Ok(None);
+        }
+
+        Ok(Some(template))
+    }
+
     /// Check if this is a valid CloudFormation/SAM template.
     ///
     /// Valid templates must have:
@@ -57,6 +95,62 @@ impl CloudFormationParser {
         false
     }
 
+    /// The code directories of the SAM functions in a template, with the
+    /// service each function is reported as.
+    ///
+    /// `CodeUri` is read from the function or from `Globals.Function`, and
+    /// resolved against the template's directory. S3 locations are skipped.
+    pub fn function_code_dirs(
+        &self,
+        path: &Path,
+        content: &str,
+    ) -> Vec<(ServiceDiscovery, PathBuf)> {
+        let Ok(Some(template)) = self.load_template(path, content) else {
+            return Vec::new();
+        };
+        if !self.is_sam_template(&template) {
+            return Vec::new();
+        }
+
+        let stack_name = self.extract_stack_name(&template, path);
+        let exports = Exports::new();
+        let scope = TemplateScope::new(&template, &HashMap::new(), stack_name.as_deref(), &exports);
+        let evaluated = scope.evaluate_template(&template);
+        let environment = self.extract_environment_from_parameters(&evaluated);
+        let global_code_uri = evaluated
+            .get("Globals")
+            .and_then(|g| g.get("Function"))
+            .and_then(|f| f.get("CodeUri"));
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        section_entries(&evaluated, "Resources")
+            .filter(|(_, resource)| {
+                resource.get("Type").and_then(|t| t.as_str()) == Some("AWS::Serverless::Function")
+            })
+            .filter_map(|(logical_id, resource)| {
+                let code_uri = resource
+                    .get("Properties")
+                    .and_then(|p| p.get("CodeUri"))
+                    .or(global_code_uri)?
+                    .as_str()?;
+                if code_uri.contains("://") {
+                    return None;
+                }
+                let Some(Discovery::Service(service)) = self.parse_serverless_function(
+                    logical_id,
+                    resource,
+                    path,
+                    "sam",
+                    environment.as_deref(),
+                    stack_name.as_deref(),
+                ) else {
+                    return None;
+                };
+                Some((service, normalize_path(&dir.join(code_uri))))
+            })
+            .collect()
+    }
+
     /// Determine if template is SAM or raw CloudFormation.
     ///
     /// SAM templates have a Transform field containing "AWS::Serverless-*".
@@ -80,18 +174,188 @@ impl CloudFormationParser {
         false
     }
 
-    /// Extract resources from a parsed template.
-    fn extract_resources(&self, template: &serde_yaml::Value, path: &Path) -> Vec<Discovery> {
-        let mut discoveries = Vec::new();
-
+    /// Evaluate a template and extract its resources and relationships.
+    ///
+    /// Intrinsic functions are resolved first (see [`TemplateScope`]).
+    /// Nested stacks whose template is in `templates` are evaluated with the
+    /// parameters passed to them, up to [`MAX_STACK_DEPTH`] levels deep, and
+    /// `exports` supplies the values of `Fn::ImportValue`.
+    #[allow(clippy::too_many_arguments)]
+    fn evaluate_stack(
+        &self,
+        template: &serde_yaml::Value,
+        path: &Path,
+        inputs: &HashMap<String, Binding>,
+        stack_name: Option<String>,
+        templates: &BTreeMap<PathBuf, serde_yaml::Value>,
+        exports: &Exports,
+        depth: usize,
+    ) -> StackEvaluation {
         let is_sam = self.is_sam_template(template);
         let deployment_method = if is_sam { "sam" } else { "cloudformation" };
 
+        // Extract stack name from metadata or filename
+        let stack_name = stack_name.or_else(|| self.extract_stack_name(template, path));
+
+        let mut scope = TemplateScope::new(template, inputs, stack_name.as_deref(), exports);
+        let mut evaluation = StackEvaluation::default();
+
+        // Nested stacks first, so their outputs can be referenced
+        for (logical_id, location, parameters) in nested_stacks(template) {
+            let Some(child_path) = nested_template_path(path, location) else {
+                continue;
+            };
+            let Some(child) = templates.get(&child_path) else {
+                continue;
+            };
+            if depth >= MAX_STACK_DEPTH {
+                tracing::warn!(
+                    "Not following nested stack {} in {}: nested too deep",
+                    logical_id,
+                    path.display()
+                );
+                continue;
+            }
+            let inputs: HashMap<String, Binding> = parameters
+                .into_iter()
+                .flatten()
+                .filter_map(|(name, value)| Some((name.as_str()?.to_string(), scope.bind(value))))
+                .collect();
+            let child_stack = stack_name
+                .as_ref()
+                .map(|parent| format!("{}-{}", parent, logical_id));
+            let nested = self.evaluate_stack(
+                child,
+                &child_path,
+                &inputs,
+                child_stack,
+                templates,
+                exports,
+                depth + 1,
+            );
+            scope.add_stack_outputs(logical_id, nested.outputs);
+            evaluation.discoveries.extend(nested.discoveries);
+            evaluation.exports.extend(nested.exports);
+        }
+
+        let evaluated = scope.evaluate_template(template);
+
         // Extract environment from Parameters
-        let environment = self.extract_environment_from_parameters(template);
+        let environment = self.extract_environment_from_parameters(&evaluated);
 
-        // Extract stack name from metadata or filename
-        let stack_name = self.extract_stack_name(template, path);
+        evaluation
+            .discoveries
+            .extend(self.extract_template_resources(
+                &evaluated,
+                path,
+                deployment_method,
+                environment.as_deref(),
+                stack_name.as_deref(),
+            ));
+
+        let references = function_references(template, &scope);
+        let source_file = path.to_string_lossy().to_string();
+        for (function, reference, relationship) in &references {
+            evaluation.discoveries.push(match reference {
+                Reference::Resource(resource) => {
+                    if &resource.name == function {
+                        continue;
+                    }
+                    Discovery::Relationship(RelationshipDiscovery {
+                        source_service: function.clone(),
+                        target: resource.name.clone(),
+                        target_kind: resource.kind,
+                        relationship: *relationship,
+                        detection_method: deployment_method.to_string(),
+                        source_file: source_file.clone(),
+                        source_line: 1,
+                    })
+                }
+                Reference::Import(export_name) => Discovery::StackImport(StackImportDiscovery {
+                    source_service: function.clone(),
+                    export_name: export_name.clone(),
+                    relationship: *relationship,
+                    source_file: source_file.clone(),
+                    source_line: 1,
+                }),
+            });
+        }
+
+        for (function, name, value) in function_environment(template, &scope) {
+            evaluation.discoveries.push(Discovery::EnvironmentVariable(
+                EnvironmentVariableDiscovery {
+                    service: function,
+                    name,
+                    value,
+                    source_file: source_file.clone(),
+                    source_line: 1,
+                },
+            ));
+        }
+
+        // Outputs, and the exports other stacks can import
+        for (name, output) in section_entries(template, "Outputs") {
+            if !scope.is_enabled(output) {
+                continue;
+            }
+            let Some(value) = output.get("Value") else {
+                continue;
+            };
+            let binding = scope.bind(value);
+            let export_name = output
+                .get("Export")
+                .and_then(|e| e.get("Name"))
+                .and_then(|n| scope.evaluate(n))
+                .and_then(|n| scalar_string(&n));
+
+            if let (Some(export_name), Some(resource)) = (&export_name, &binding.resource) {
+                let owners = references
+                    .iter()
+                    .filter(|(function, reference, _)| {
+                        *reference == Reference::Resource(resource.clone())
+                            && function != &resource.name
+                    })
+                    .map(|(function, _, _)| function.clone())
+                    .fold(Vec::new(), |mut owners, function| {
+                        if !owners.contains(&function) {
+                            owners.push(function);
+                        }
+                        owners
+                    });
+                evaluation
+                    .discoveries
+                    .push(Discovery::StackExport(StackExportDiscovery {
+                        export_name: export_name.clone(),
+                        target: resource.name.clone(),
+                        target_kind: resource.kind,
+                        owners,
+                        source_file: source_file.clone(),
+                        source_line: 1,
+                    }));
+            }
+            if let Some(export_name) = export_name {
+                evaluation.exports.insert(export_name, binding.clone());
+            }
+            evaluation.outputs.insert(name.to_string(), binding);
+        }
+
+        evaluation
+    }
+
+    /// Extract resources from the `Resources` section of a template.
+    ///
+    /// Deployment context is supplied by the caller, so templates embedded in
+    /// other formats (e.g., the `resources` block of serverless.yml) can reuse
+    /// the resource handling with their own deployment method and stage.
+    pub(crate) fn extract_template_resources(
+        &self,
+        template: &serde_yaml::Value,
+        path: &Path,
+        deployment_method: &str,
+        environment: Option<&str>,
+        stack_name: Option<&str>,
+    ) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
 
         // Process Resources section
         if let Some(resources) = template.get("Resources") {
@@ -103,8 +367,8 @@ impl CloudFormationParser {
                             resource,
                             path,
                             deployment_method,
-                            environment.as_deref(),
-                            stack_name.as_deref(),
+                            environment,
+                            stack_name,
                         ) {
                             discoveries.push(discovery);
                         }
@@ -312,6 +576,7 @@ impl CloudFormationParser {
         Some(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
             db_type: "dynamodb".to_string(),
             table_name: Some(table_name),
+            connection: None,
             operation: DatabaseOperation::Unknown, // Template defines table, not operations
             detection_method: deployment_method.to_string(),
             source_file: path.to_string_lossy().to_string(),
@@ -545,7 +810,7 @@ impl CloudFormationParser {
     }
 
     /// Infer programming language from AWS Lambda runtime.
-    fn infer_language_from_runtime(&self, runtime: Option<&str>) -> String {
+    pub(crate) fn infer_language_from_runtime(&self, runtime: Option<&str>) -> String {
         match runtime {
             Some(r) if r.starts_with("python") => "python".to_string(),
             Some(r) if r.starts_with("nodejs") => "javascript".to_string(),
@@ -572,6 +837,7 @@ impl CloudFormationParser {
             environment: environment.map(|s| s.to_string()),
             stack_name: stack_name.map(|s| s.to_string()),
             tags: HashMap::new(),
+            resource_attributes: HashMap::new(),
         }
     }
 
@@ -595,86 +861,380 @@ impl Parser for CloudFormationParser {
         self
     }
 
+    fn name(&self) -> &str {
+        "cloudformation"
+    }
+
     fn supported_extensions(&self) -> &[&str] {
         // CloudFormation/SAM templates use YAML or JSON
         &["yaml", "yml", "json"]
     }
 
     fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
-        // Determine file format
-        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-
-        // Parse based on extension
-        let template: serde_yaml::Value = if ext == "json" {
-            // Parse JSON and convert to YAML Value for uniform processing
-            let json_value = self.parse_json(content)?;
-            serde_json::from_value(json_value).map_err(|e| {
-                ParserError::TreeSitterError(format!("JSON to YAML conversion error: {}", e))
-            })?
-        } else {
-            self.parse_yaml(content)?
-        };
-
-        // Check if this is a valid CloudFormation/SAM template
-        if !self.is_template(&template) && !self.is_template_filename(path) {
+        let Some(template) = self.load_template(path, content)? else {
             // Not a CloudFormation template, return empty (no error)
             return Ok(Vec::new());
-        }
+        };
 
-        // Extract resources
-        Ok(self.extract_resources(&template, path))
+        // A single file has no other templates for nested stacks or imports
+        Ok(self
+            .evaluate_stack(
+                &template,
+                path,
+                &HashMap::new(),
+                None,
+                &BTreeMap::new(),
+                &Exports::new(),
+                0,
+            )
+            .discoveries)
     }
 
     /// Custom repository parsing to filter for CloudFormation/SAM templates.
     ///
     /// Unlike other parsers that process all files with matching extensions,
     /// this parser only processes files that are valid CloudFormation/SAM templates.
-    fn parse_repo(&self, repo_path: &Path) -> Result<Vec<Discovery>, ParserError> {
-        let mut all_discoveries = Vec::new();
+    ///
+    /// All templates are loaded before any is evaluated: templates used as
+    /// nested stacks are evaluated through their parent, with the parameters
+    /// it passes, and the exports of every stack are collected first so
+    /// `Fn::ImportValue` resolves regardless of file order. Templates are
+    /// therefore not cached per file.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        _cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
         let extensions = self.supported_extensions();
-
-        for entry in walkdir::WalkDir::new(repo_path)
-            .follow_links(true)
+        let files = repo_files(repo_path, is_ignored_cloudformation_dir, |path| {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            extensions.contains(&ext)
+        });
+        let (loaded, failures) =
+            parse_in_parallel(&files, |path, content| self.load_template(path, content));
+        for failure in failures {
+            // Most YAML/JSON files are not templates; don't report them
+            tracing::debug!(
+                "Failed to parse {}: {}",
+                failure.path.display(),
+                failure.error
+            );
+        }
+        let templates: BTreeMap<_, _> = loaded
             .into_iter()
-            .filter_entry(|e| !is_ignored_cloudformation_dir(e.file_name().to_str().unwrap_or("")))
-        {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(e) => {
-                    tracing::debug!("Failed to read directory entry: {}", e);
-                    continue;
-                }
+            .filter_map(|(path, template)| Some((normalize_path(&path), template?)))
+            .collect();
+
+        let nested: HashSet<PathBuf> = templates
+            .iter()
+            .flat_map(|(path, template)| {
+                nested_stacks(template)
+                    .into_iter()
+                    .filter_map(|(_, location, _)| nested_template_path(path, location))
+            })
+            .collect();
+        let roots: Vec<_> = templates
+            .iter()
+            .filter(|(path, _)| !nested.contains(*path))
+            .collect();
+
+        let evaluate = |template, path: &Path, exports: &Exports| {
+            self.evaluate_stack(
+                template,
+                path,
+                &HashMap::new(),
+                None,
+                &templates,
+                exports,
+                0,
+            )
+        };
+
+        let mut exports = Exports::new();
+        for (path, template) in &roots {
+            exports.extend(evaluate(template, path, &Exports::new()).exports);
+        }
+
+        let mut all_discoveries = Vec::new();
+        for (path, template) in &roots {
+            all_discoveries.extend(evaluate(template, path, &exports).discoveries);
+        }
+
+        Ok(RepoParse {
+            discoveries: all_discoveries,
+            failures: Vec::new(),
+        })
+    }
+}
+
+/// Nested stacks are followed at most this many levels deep.
+const MAX_STACK_DEPTH: usize = 8;
+
+/// What evaluating one stack (and its nested stacks) produces.
+#[derive(Default)]
+struct StackEvaluation {
+    discoveries: Vec<Discovery>,
+    /// Output values by output name
+    outputs: HashMap<String, Binding>,
+    /// Exported values by export name, including those of nested stacks
+    exports: Exports,
+}
+
+/// The entries of a top-level template section, by name.
+fn section_entries<'t>(
+    template: &'t serde_yaml::Value,
+    name: &str,
+) -> impl Iterator<Item = (&'t str, &'t serde_yaml::Value)> {
+    template
+        .get(name)
+        .and_then(|s| s.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some((key.as_str()?, value)))
+}
+
+/// Nested stacks declared by a template, as (logical id, template
+/// location, parameters).
+///
+/// Covers `AWS::CloudFormation::Stack` (`TemplateURL`) and
+/// `AWS::Serverless::Application` (`Location`) with a literal location.
+fn nested_stacks(template: &serde_yaml::Value) -> Vec<(&str, &str, Option<&serde_yaml::Mapping>)> {
+    section_entries(template, "Resources")
+        .filter_map(|(logical_id, resource)| {
+            let location_property = match resource.get("Type")?.as_str()? {
+                "AWS::CloudFormation::Stack" => "TemplateURL",
+                "AWS::Serverless::Application" => "Location",
+                _ => return None,
             };
+            let properties = resource.get("Properties")?;
+            let location = properties.get(location_property)?.as_str()?;
+            let parameters = properties.get("Parameters").and_then(|p| p.as_mapping());
+            Some((logical_id, location, parameters))
+        })
+        .collect()
+}
 
-            if !entry.file_type().is_file() {
-                continue;
-            }
+/// The local file a nested stack location refers to, relative to the
+/// parent template. `None` for S3 and HTTP URLs.
+fn nested_template_path(parent: &Path, location: &str) -> Option<PathBuf> {
+    if location.contains("://") {
+        return None;
+    }
+    let dir = parent.parent().unwrap_or(Path::new(""));
+    Some(normalize_path(&dir.join(location)))
+}
 
-            let path = entry.path();
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+/// Remove `.` and `..` components without touching the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
 
-            if !extensions.contains(&ext) {
-                continue;
+/// What the functions of a template refer to, as (function name,
+/// reference, relationship).
+///
+/// How a function uses a resource is taken from where the reference
+/// appears: event sources subscribe to (or read) it, SAM policy templates
+/// read or write it, and anything else (environment variables, IAM
+/// statements) is a plain dependency. `AWS::Lambda::EventSourceMapping`
+/// resources subscribe the function they name to their event source.
+fn function_references(
+    template: &serde_yaml::Value,
+    scope: &TemplateScope,
+) -> Vec<(String, Reference, RelationshipType)> {
+    let mut references: Vec<(String, Reference, RelationshipType)> = Vec::new();
+    let mut add = |function: &str, found: Vec<Reference>, relationship: RelationshipType| {
+        for reference in found {
+            let entry = (function.to_string(), reference, relationship);
+            if !references.contains(&entry) {
+                references.push(entry);
             }
+        }
+    };
+
+    for (logical_id, resource) in section_entries(template, "Resources") {
+        if !scope.is_enabled(resource) {
+            continue;
+        }
+        let Some(properties) = resource.get("Properties").and_then(|p| p.as_mapping()) else {
+            continue;
+        };
 
-            let content = match std::fs::read_to_string(path) {
-                Ok(c) => c,
-                Err(e) => {
-                    tracing::debug!("Failed to read file {}: {}", path.display(), e);
+        match resource.get("Type").and_then(|t| t.as_str()) {
+            Some("AWS::Serverless::Function" | "AWS::Lambda::Function") => {
+                let Some(function) = scope.resource(logical_id) else {
                     continue;
+                };
+                for (key, value) in properties {
+                    match key.as_str() {
+                        Some("Events") => {
+                            for event in value.as_mapping().into_iter().flat_map(|e| e.values()) {
+                                let relationship = event_relationship(
+                                    event.get("Type").and_then(|t| t.as_str()).unwrap_or(""),
+                                );
+                                if let Some(event_properties) = event.get("Properties") {
+                                    add(
+                                        &function.name,
+                                        scope.references(event_properties),
+                                        relationship,
+                                    );
+                                }
+                            }
+                        }
+                        Some("Policies") => {
+                            let policies = match value.as_sequence() {
+                                Some(policies) => policies.iter().collect(),
+                                None => vec![value],
+                            };
+                            for policy in policies {
+                                let relationship = policy
+                                    .as_mapping()
+                                    .filter(|p| p.len() == 1)
+                                    .and_then(|p| p.keys().next())
+                                    .and_then(|name| name.as_str())
+                                    .map(policy_relationship)
+                                    .unwrap_or(RelationshipType::DependsOn);
+                                add(&function.name, scope.references(policy), relationship);
+                            }
+                        }
+                        _ => add(
+                            &function.name,
+                            scope.references(value),
+                            RelationshipType::DependsOn,
+                        ),
+                    }
                 }
-            };
-
-            match self.parse_file(path, &content) {
-                Ok(discoveries) => all_discoveries.extend(discoveries),
-                Err(e) => {
-                    // Log but continue - don't fail entire survey for one file
-                    tracing::debug!("Failed to parse {}: {}", path.display(), e);
+            }
+            Some("AWS::Lambda::EventSourceMapping") => {
+                let functions = properties
+                    .get("FunctionName")
+                    .map(|f| scope.references(f))
+                    .unwrap_or_default();
+                let sources = properties
+                    .get("EventSourceArn")
+                    .map(|s| scope.references(s))
+                    .unwrap_or_default();
+                for function in functions {
+                    if let Reference::Resource(function) = function {
+                        add(
+                            &function.name,
+                            sources.clone(),
+                            RelationshipType::Subscribes,
+                        );
+                    }
                 }
             }
+            _ => {}
+        }
+    }
+
+    // A specific relationship makes a plain dependency on the same target redundant
+    let specific: Vec<(String, Reference)> = references
+        .iter()
+        .filter(|(_, _, relationship)| *relationship != RelationshipType::DependsOn)
+        .map(|(function, reference, _)| (function.clone(), reference.clone()))
+        .collect();
+    references.retain(|(function, reference, relationship)| {
+        *relationship != RelationshipType::DependsOn
+            || !specific.contains(&(function.clone(), reference.clone()))
+    });
+    references
+}
+
+/// Environment variables of the functions in a template, as (function, name,
+/// value).
+///
+/// Variables referring to a resource that becomes a graph node take the
+/// resource's name, so `!Ref OrdersQueue` gives the queue name rather than
+/// its URL. SAM functions inherit `Globals.Function.Environment`.
+fn function_environment(
+    template: &serde_yaml::Value,
+    scope: &TemplateScope,
+) -> Vec<(String, String, String)> {
+    let globals = environment_variables(template.get("Globals").and_then(|g| g.get("Function")));
+
+    let mut environment = Vec::new();
+    for (logical_id, resource) in section_entries(template, "Resources") {
+        if !scope.is_enabled(resource) {
+            continue;
+        }
+        let inherited = match resource.get("Type").and_then(|t| t.as_str()) {
+            Some("AWS::Serverless::Function") => globals.clone(),
+            Some("AWS::Lambda::Function") => Vec::new(),
+            _ => continue,
+        };
+        let Some(function) = scope.resource(logical_id) else {
+            continue;
+        };
+        let own = environment_variables(resource.get("Properties"));
+        let inherited: Vec<_> = inherited
+            .into_iter()
+            .filter(|(name, _)| !own.iter().any(|(own_name, _)| own_name == name))
+            .collect();
+        for (name, value) in inherited.into_iter().chain(own) {
+            let binding = scope.bind(value);
+            let value = match binding.resource {
+                Some(resource) => Some(resource.name),
+                None => binding.value.as_ref().and_then(scalar_string),
+            };
+            if let Some(value) = value {
+                environment.push((function.name.clone(), name.to_string(), value));
+            }
+        }
+    }
+    environment
+}
+
+/// The `Environment.Variables` of function properties, by name.
+fn environment_variables(
+    properties: Option<&serde_yaml::Value>,
+) -> Vec<(&str, &serde_yaml::Value)> {
+    properties
+        .and_then(|p| p.get("Environment"))
+        .and_then(|e| e.get("Variables"))
+        .and_then(|v| v.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| Some((name.as_str()?, value)))
+        .collect()
+}
+
+/// How a function uses the resources of a SAM event source, by event type.
+fn event_relationship(event_type: &str) -> RelationshipType {
+    match event_type {
+        "SQS" | "SNS" | "Kinesis" | "MQ" | "MSK" | "SelfManagedKafka" => {
+            RelationshipType::Subscribes
         }
+        "DynamoDB" => RelationshipType::Reads,
+        "S3" | "Api" | "HttpApi" => RelationshipType::Uses,
+        _ => RelationshipType::DependsOn,
+    }
+}
 
-        Ok(all_discoveries)
+/// How a function uses the resources of a SAM policy template, by name
+/// (`DynamoDBCrudPolicy`, `SQSSendMessagePolicy`, ...).
+fn policy_relationship(policy: &str) -> RelationshipType {
+    if policy.starts_with("S3") {
+        RelationshipType::Uses
+    } else if policy.contains("Poller") {
+        RelationshipType::Subscribes
+    } else if policy.contains("Send") || policy.contains("Publish") {
+        RelationshipType::Publishes
+    } else if policy.contains("Crud") || policy.contains("Write") {
+        RelationshipType::Writes
+    } else if policy.contains("Read") {
+        RelationshipType::Reads
+    } else {
+        RelationshipType::DependsOn
     }
 }
 
@@ -828,6 +1388,57 @@ Resources:
         assert_eq!(services[0].language, "javascript");
     }
 
+    #[test]
+    fn test_function_code_dirs() {
+        let parser = CloudFormationParser::new().unwrap();
+        let content = r#"
+Transform: AWS::Serverless-2016-10-31
+Parameters:
+  Stage:
+    Type: String
+    Default: prod
+Globals:
+  Function:
+    CodeUri: shared/
+Resources:
+  Orders:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: !Sub '${Stage}-orders'
+      CodeUri: ../functions/orders
+      Runtime: nodejs20.x
+  Billing:
+    Type: AWS::Serverless::Function
+    Properties:
+      Runtime: python3.12
+  Archived:
+    Type: AWS::Serverless::Function
+    Properties:
+      CodeUri: s3://artifacts/archived.zip
+"#;
+
+        let dirs: Vec<_> = parser
+            .function_code_dirs(Path::new("repo/infra/template.yaml"), content)
+            .into_iter()
+            .map(|(service, dir)| (service.name, service.language, dir))
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                (
+                    "prod-orders".to_string(),
+                    "javascript".to_string(),
+                    PathBuf::from("repo/functions/orders")
+                ),
+                (
+                    "Billing".to_string(),
+                    "python".to_string(),
+                    PathBuf::from("repo/infra/shared")
+                ),
+            ]
+        );
+    }
+
     // ==================== CloudFormation Resource Tests ====================
 
     #[test]
@@ -1211,11 +1822,8 @@ Resources:
             .collect();
 
         assert_eq!(databases.len(), 1);
-        // The !Sub template string should be extracted
-        assert_eq!(
-            databases[0].table_name,
-            Some("${Environment}-users".to_string())
-        );
+        // The !Sub template is resolved against the parameter default
+        assert_eq!(databases[0].table_name, Some("prod-users".to_string()));
     }
 
     #[test]
@@ -1286,4 +1894,346 @@ Resources:
         assert!(!parser.is_template_filename(Path::new("config.yaml")));
         assert!(!parser.is_template_filename(Path::new("app.json")));
     }
+
+    // ==================== Template Evaluation Tests ====================
+
+    fn relationships(discoveries: &[Discovery]) -> Vec<(&str, &str, RelationshipType)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => {
+                    Some((r.source_service.as_str(), r.target.as_str(), r.relationship))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_intrinsics_resolved_with_mappings_and_conditions() {
+        let parser = CloudFormationParser::new().unwrap();
+        let content = r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Transform: AWS::Serverless-2016-10-31
+Parameters:
+  Stage:
+    Type: String
+    Default: prod
+Mappings:
+  Prefixes:
+    prod:
+      Table: live
+Conditions:
+  IsProd: !Equals [!Ref Stage, prod]
+  IsDev: !Not [!Condition IsProd]
+Resources:
+  Orders:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: !Join ['-', [!FindInMap [Prefixes, !Ref Stage, Table], orders]]
+  DebugQueue:
+    Type: AWS::SQS::Queue
+    Condition: IsDev
+  Jobs:
+    Type: AWS::SQS::Queue
+    Properties:
+      QueueName: !If [IsProd, !Sub '${AWS::StackName}-jobs', dev-jobs]
+  Worker:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: !Sub '${Stage}-worker'
+      Runtime: python3.12
+      Handler: app.handler
+      Environment:
+        Variables:
+          TABLE: !Ref Orders
+      Policies:
+        - DynamoDBCrudPolicy:
+            TableName: !Ref Orders
+        - SQSPollerPolicy:
+            QueueName: !GetAtt Jobs.QueueName
+      Events:
+        Work:
+          Type: SQS
+          Properties:
+            Queue: !GetAtt Jobs.Arn
+"#;
+
+        let discoveries = parser
+            .parse_file(Path::new("infra/shop.yaml"), content)
+            .unwrap();
+
+        let names: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s.name.clone()),
+                Discovery::DatabaseAccess(db) => db.table_name.clone(),
+                Discovery::QueueOperation(q) => q.queue_name.clone(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["live-orders", "shop-jobs", "prod-worker"]);
+
+        // The environment variable is covered by the more specific policy
+        assert_eq!(
+            relationships(&discoveries),
+            vec![
+                ("prod-worker", "live-orders", RelationshipType::Writes),
+                ("prod-worker", "shop-jobs", RelationshipType::Subscribes),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_environment_variables() {
+        let parser = CloudFormationParser::new().unwrap();
+        let content = r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Transform: AWS::Serverless-2016-10-31
+Parameters:
+  Stage:
+    Type: String
+    Default: prod
+Globals:
+  Function:
+    Environment:
+      Variables:
+        STAGE: !Ref Stage
+        USERS_TABLE: legacy-users
+Resources:
+  Users:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: !Sub '${Stage}-users'
+  Jobs:
+    Type: AWS::SQS::Queue
+  Api:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: api
+      Runtime: python3.12
+      Environment:
+        Variables:
+          USERS_TABLE: !Ref Users
+          JOBS_QUEUE_URL: !Ref Jobs
+          SECRET: !Ref SomethingUnknown
+"#;
+
+        let discoveries = parser
+            .parse_file(Path::new("template.yaml"), content)
+            .unwrap();
+        let variables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::EnvironmentVariable(v) => {
+                    Some((v.service.as_str(), v.name.as_str(), v.value.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            variables,
+            vec![
+                ("api", "STAGE", "prod"),
+                ("api", "USERS_TABLE", "prod-users"),
+                ("api", "JOBS_QUEUE_URL", "Jobs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exports_and_imports() {
+        let parser = CloudFormationParser::new().unwrap();
+        let content = r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Resources:
+  Orders:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: !Sub '${AWS::StackName}-orders'
+  Writer:
+    Type: AWS::Lambda::Function
+    Properties:
+      FunctionName: writer
+      Runtime: nodejs20.x
+      Environment:
+        Variables:
+          TABLE: !Ref Orders
+          EVENTS: !ImportValue shared-EventsTopic
+  Mapping:
+    Type: AWS::Lambda::EventSourceMapping
+    Properties:
+      FunctionName: !Ref Writer
+      EventSourceArn: !ImportValue shared-JobsQueueArn
+Outputs:
+  OrdersTable:
+    Value: !Ref Orders
+    Export:
+      Name: !Sub '${AWS::StackName}-OrdersTable'
+"#;
+
+        let discoveries = parser
+            .parse_file(Path::new("orders.yaml"), content)
+            .unwrap();
+
+        let exports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::StackExport(e) => Some(e),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(exports.len(), 1);
+        assert_eq!(exports[0].export_name, "orders-OrdersTable");
+        assert_eq!(exports[0].target, "orders-orders");
+        assert_eq!(exports[0].target_kind, ComponentKind::Database);
+        assert_eq!(exports[0].owners, vec!["writer"]);
+
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::StackImport(i) => Some((
+                    i.source_service.as_str(),
+                    i.export_name.as_str(),
+                    i.relationship,
+                )),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("writer", "shared-EventsTopic", RelationshipType::DependsOn),
+                (
+                    "writer",
+                    "shared-JobsQueueArn",
+                    RelationshipType::Subscribes
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_repo_follows_nested_stacks_and_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+
+        write(
+            "template.yaml",
+            r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Transform: AWS::Serverless-2016-10-31
+Parameters:
+  Stage:
+    Type: String
+    Default: prod
+Resources:
+  Storage:
+    Type: AWS::CloudFormation::Stack
+    Properties:
+      TemplateURL: ./stacks/storage.yaml
+      Parameters:
+        Stage: !Ref Stage
+  Api:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: !Sub '${Stage}-api'
+      Runtime: python3.12
+      Environment:
+        Variables:
+          TABLE: !GetAtt Storage.Outputs.TableName
+          REPORTS: !ImportValue reports-Bucket
+"#,
+        );
+        write(
+            "stacks/storage.yaml",
+            r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Parameters:
+  Stage:
+    Type: String
+    Default: dev
+Resources:
+  Table:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: !Sub '${Stage}-users'
+Outputs:
+  TableName:
+    Value: !Ref Table
+"#,
+        );
+        // Declared after the importing template, in file order
+        write(
+            "reports/reports.yaml",
+            r#"
+AWSTemplateFormatVersion: '2010-09-09'
+Resources:
+  Bucket:
+    Type: AWS::S3::Bucket
+    Properties:
+      BucketName: acme-reports
+Outputs:
+  Bucket:
+    Value: !Ref Bucket
+    Export:
+      Name: reports-Bucket
+"#,
+        );
+
+        let parser = CloudFormationParser::new().unwrap();
+        let discoveries = parser.parse_repo(dir.path()).unwrap().discoveries;
+
+        // The nested stack is only evaluated through its parent
+        let tables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].table_name.as_deref(), Some("prod-users"));
+        assert_eq!(
+            tables[0]
+                .deployment_metadata
+                .as_ref()
+                .and_then(|m| m.stack_name.as_deref()),
+            Some("template-Storage")
+        );
+
+        assert_eq!(
+            relationships(&discoveries),
+            vec![("prod-api", "prod-users", RelationshipType::DependsOn)]
+        );
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::StackImport(i) if i.export_name == "reports-Bucket"
+        )));
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::StackExport(e) if e.export_name == "reports-Bucket" && e.target == "acme-reports"
+        )));
+    }
+
+    #[test]
+    fn test_nested_template_path() {
+        let parent = Path::new("/repo/infra/template.yaml");
+        assert_eq!(
+            nested_template_path(parent, "./stacks/db.yaml"),
+            Some(PathBuf::from("/repo/infra/stacks/db.yaml"))
+        );
+        assert_eq!(
+            nested_template_path(parent, "../shared/db.yaml"),
+            Some(PathBuf::from("/repo/shared/db.yaml"))
+        );
+        assert_eq!(
+            nested_template_path(parent, "https://s3.amazonaws.com/b/db.yaml"),
+            None
+        );
+    }
 }
@@ -0,0 +1,912 @@
+//! Static evaluation of CloudFormation intrinsic functions.
+//!
+//! Resource names in templates are usually built from parameters
+//! (`!Sub '${Environment}-orders'`). A [`TemplateScope`] holds everything
+//! about one stack that is known without deploying it:
+//!
+//! - `Ref` to parameters (defaults, or the values a parent stack passes in),
+//!   resources and the pseudo parameters `AWS::StackName`, `AWS::Partition`,
+//!   `AWS::URLSuffix` and `AWS::NoValue`
+//! - `Fn::GetAtt` on resources and on nested stack outputs
+//!   (`!GetAtt Storage.Outputs.TableName`)
+//! - `Fn::Sub`, `Fn::Join`, `Fn::Select`, `Fn::Split`, `Fn::FindInMap`,
+//!   `Fn::If`, `Fn::Base64` and the condition functions
+//! - `Fn::ImportValue` of values exported by other stacks in the survey
+//!
+//! References to resources evaluate to the resource's physical name, the
+//! name its graph node is created under, whatever attribute is asked for:
+//! ARNs and URLs only exist once the stack is deployed. Anything else
+//! (`AWS::Region`, `Fn::GetAZs`, unknown exports) is left as it is.
+
+use super::traits::ComponentKind;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+/// Intrinsic functions that have a YAML short form (`!Ref`, `!Sub`, ...).
+const SHORT_FORM_TAGS: &[&str] = &[
+    "Ref",
+    "Condition",
+    "Base64",
+    "Cidr",
+    "FindInMap",
+    "GetAtt",
+    "GetAZs",
+    "ImportValue",
+    "Join",
+    "Select",
+    "Split",
+    "Sub",
+    "Transform",
+    "If",
+    "Equals",
+    "And",
+    "Or",
+    "Not",
+];
+
+/// A template resource that becomes a graph node.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StackResource {
+    pub(crate) name: String,
+    pub(crate) kind: ComponentKind,
+}
+
+/// A value known to a stack (a parameter, output or export), with the
+/// resource it refers to, if any.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Binding {
+    pub(crate) value: Option<Value>,
+    pub(crate) resource: Option<StackResource>,
+}
+
+/// Exported values by export name.
+pub(crate) type Exports = HashMap<String, Binding>;
+
+/// Something a template value refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Reference {
+    /// A resource of this stack, or of a nested stack through its outputs.
+    Resource(StackResource),
+    /// A value exported by another stack (`Fn::ImportValue`), by export name.
+    Import(String),
+}
+
+/// Resource types that become graph nodes, with the property holding their
+/// physical name.
+pub(crate) fn resource_component(resource_type: &str) -> Option<(ComponentKind, &'static str)> {
+    match resource_type {
+        "AWS::Serverless::Function" | "AWS::Lambda::Function" => {
+            Some((ComponentKind::Service, "FunctionName"))
+        }
+        "AWS::DynamoDB::Table" => Some((ComponentKind::Database, "TableName")),
+        "AWS::SQS::Queue" => Some((ComponentKind::Queue, "QueueName")),
+        "AWS::SNS::Topic" => Some((ComponentKind::Queue, "TopicName")),
+        "AWS::S3::Bucket" => Some((ComponentKind::CloudResource, "BucketName")),
+        "AWS::Serverless::Api" => Some((ComponentKind::CloudResource, "Name")),
+        _ => None,
+    }
+}
+
+/// The intrinsic function a value calls, as (function name, argument).
+///
+/// Both the long form (`{ "Fn::Sub": ... }`) and the YAML short form
+/// (`!Sub ...`) give `("Fn::Sub", ...)`.
+pub(crate) fn intrinsic(value: &Value) -> Option<(String, &Value)> {
+    match value {
+        Value::Tagged(tagged) => {
+            let tag = SHORT_FORM_TAGS.iter().find(|t| tagged.tag == **t)?;
+            let name = match *tag {
+                "Ref" | "Condition" => tag.to_string(),
+                other => format!("Fn::{}", other),
+            };
+            Some((name, &tagged.value))
+        }
+        Value::Mapping(map) if map.len() == 1 => {
+            let (key, arg) = map.iter().next()?;
+            let key = key.as_str()?;
+            (key == "Ref" || key == "Condition" || key.starts_with("Fn::"))
+                .then(|| (key.to_string(), arg))
+        }
+        _ => None,
+    }
+}
+
+/// Known values of one stack.
+pub(crate) struct TemplateScope<'a> {
+    stack_name: Option<String>,
+    parameters: HashMap<String, Binding>,
+    mappings: Mapping,
+    conditions: HashMap<String, bool>,
+    /// Resources that become graph nodes, by logical id
+    resources: HashMap<String, StackResource>,
+    /// Outputs of nested stacks, by the stack's logical id
+    stack_outputs: HashMap<String, HashMap<String, Binding>>,
+    exports: &'a Exports,
+}
+
+impl<'a> TemplateScope<'a> {
+    /// Build the scope of a template.
+    ///
+    /// `inputs` (the `Parameters` a parent stack passes) override the
+    /// defaults of the parameters the template declares.
+    pub(crate) fn new(
+        template: &Value,
+        inputs: &HashMap<String, Binding>,
+        stack_name: Option<&str>,
+        exports: &'a Exports,
+    ) -> Self {
+        let mut scope = Self {
+            stack_name: stack_name.map(|s| s.to_string()),
+            parameters: HashMap::new(),
+            mappings: template
+                .get("Mappings")
+                .and_then(|m| m.as_mapping())
+                .cloned()
+                .unwrap_or_default(),
+            conditions: HashMap::new(),
+            resources: HashMap::new(),
+            stack_outputs: HashMap::new(),
+            exports,
+        };
+
+        for (name, declaration) in section(template, "Parameters") {
+            let binding = match inputs.get(name) {
+                Some(input) => input.clone(),
+                None => match declaration.get("Default") {
+                    Some(default) => Binding {
+                        value: Some(default.clone()),
+                        resource: None,
+                    },
+                    None => continue,
+                },
+            };
+            let is_list = declaration
+                .get("Type")
+                .and_then(|t| t.as_str())
+                .is_some_and(|t| t == "CommaDelimitedList" || t.starts_with("List<"));
+            scope
+                .parameters
+                .insert(name.to_string(), list_parameter(binding, is_list));
+        }
+
+        scope.resolve_conditions(template);
+
+        for (logical_id, resource) in section(template, "Resources") {
+            if !scope.is_enabled(resource) {
+                continue;
+            }
+            let Some((kind, name_property)) = resource
+                .get("Type")
+                .and_then(|t| t.as_str())
+                .and_then(resource_component)
+            else {
+                continue;
+            };
+            let name = resource
+                .get("Properties")
+                .and_then(|p| p.get(name_property))
+                .and_then(|n| scope.evaluate(n))
+                .and_then(|n| scalar_string(&n))
+                .unwrap_or_else(|| logical_id.to_string());
+            scope
+                .resources
+                .insert(logical_id.to_string(), StackResource { name, kind });
+        }
+
+        scope
+    }
+
+    /// Make the outputs of a nested stack available as
+    /// `!GetAtt <logical id>.Outputs.<name>`.
+    pub(crate) fn add_stack_outputs(
+        &mut self,
+        logical_id: &str,
+        outputs: HashMap<String, Binding>,
+    ) {
+        self.stack_outputs.insert(logical_id.to_string(), outputs);
+    }
+
+    /// The resource a logical id names, if it becomes a graph node.
+    pub(crate) fn resource(&self, logical_id: &str) -> Option<&StackResource> {
+        self.resources.get(logical_id)
+    }
+
+    /// Whether a resource or output is created, given its `Condition`.
+    ///
+    /// Conditions that cannot be evaluated count as true.
+    pub(crate) fn is_enabled(&self, declaration: &Value) -> bool {
+        declaration
+            .get("Condition")
+            .and_then(|c| c.as_str())
+            .and_then(|c| self.conditions.get(c))
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Copy a template with every intrinsic that can be evaluated replaced
+    /// by its value.
+    ///
+    /// Disabled resources are dropped, properties set to `AWS::NoValue` are
+    /// removed, and parameter defaults are replaced by the values in effect.
+    pub(crate) fn evaluate_template(&self, template: &Value) -> Value {
+        let Some(map) = template.as_mapping() else {
+            return template.clone();
+        };
+
+        let mut evaluated = Mapping::new();
+        for (key, value) in map {
+            let value = match key.as_str() {
+                Some("Parameters") => self.effective_parameters(value),
+                Some("Resources") => Value::Mapping(
+                    section(template, "Resources")
+                        .filter(|(_, resource)| self.is_enabled(resource))
+                        .map(|(id, resource)| {
+                            (Value::from(id), self.evaluate_resource(id, resource))
+                        })
+                        .collect(),
+                ),
+                _ => value.clone(),
+            };
+            evaluated.insert(key.clone(), value);
+        }
+        Value::Mapping(evaluated)
+    }
+
+    /// Evaluate a resource declaration.
+    ///
+    /// The name property of resources that become graph nodes is set to the
+    /// name references resolve to, so nodes and edges agree on it even when
+    /// the declared name could not be evaluated.
+    fn evaluate_resource(&self, logical_id: &str, resource: &Value) -> Value {
+        let mut evaluated = self.evaluate_tree(resource);
+        let name_property = resource
+            .get("Type")
+            .and_then(|t| t.as_str())
+            .and_then(resource_component)
+            .map(|(_, property)| property);
+        let properties = evaluated
+            .get_mut("Properties")
+            .and_then(|p| p.as_mapping_mut());
+        if let (Some(resource), Some(property), Some(properties)) =
+            (self.resources.get(logical_id), name_property, properties)
+        {
+            properties.insert(Value::from(property), Value::String(resource.name.clone()));
+        }
+        evaluated
+    }
+
+    /// Evaluate a value, or `None` if any part of it cannot be evaluated.
+    pub(crate) fn evaluate(&self, value: &Value) -> Option<Value> {
+        if let Some((function, arg)) = intrinsic(value) {
+            return self.call(&function, arg);
+        }
+
+        match value {
+            Value::Sequence(items) => items
+                .iter()
+                .map(|item| self.evaluate(item))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Sequence),
+            Value::Mapping(map) => map
+                .iter()
+                .map(|(k, v)| Some((k.clone(), self.evaluate(v)?)))
+                .collect::<Option<Mapping>>()
+                .map(Value::Mapping),
+            Value::Tagged(tagged) => self.evaluate(&tagged.value),
+            other => Some(other.clone()),
+        }
+    }
+
+    /// Evaluate a value as far as possible, leaving the intrinsics that
+    /// cannot be evaluated as they are.
+    pub(crate) fn evaluate_tree(&self, value: &Value) -> Value {
+        if intrinsic(value).is_some() {
+            return self.evaluate(value).unwrap_or_else(|| value.clone());
+        }
+
+        match value {
+            Value::Sequence(items) => Value::Sequence(
+                items
+                    .iter()
+                    .filter(|item| !self.is_no_value(item))
+                    .map(|item| self.evaluate_tree(item))
+                    .collect(),
+            ),
+            Value::Mapping(map) => Value::Mapping(
+                map.iter()
+                    .filter(|(_, v)| !self.is_no_value(v))
+                    .map(|(k, v)| (k.clone(), self.evaluate_tree(v)))
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Evaluate a value, keeping the resource it refers to.
+    ///
+    /// Used for parameters passed to nested stacks and for outputs, so that
+    /// references can be followed from one stack to another.
+    pub(crate) fn bind(&self, value: &Value) -> Binding {
+        let resolved = match intrinsic(value) {
+            Some((function, arg)) if function == "Ref" => arg.as_str().and_then(|s| self.lookup(s)),
+            Some((function, arg)) if function == "Fn::GetAtt" => self.attribute(arg),
+            Some((function, arg)) if function == "Fn::ImportValue" => self
+                .evaluate(arg)
+                .and_then(|name| scalar_string(&name))
+                .and_then(|name| self.exports.get(&name).cloned()),
+            _ => None,
+        };
+
+        resolved.unwrap_or_else(|| Binding {
+            value: self.evaluate(value),
+            resource: None,
+        })
+    }
+
+    /// Everything a value refers to: resources (directly, through
+    /// parameters, nested stack outputs or `Fn::Sub` variables) and imports.
+    pub(crate) fn references(&self, value: &Value) -> Vec<Reference> {
+        let mut references = Vec::new();
+        self.collect_references(value, &mut references);
+        references
+    }
+
+    fn collect_references(&self, value: &Value, references: &mut Vec<Reference>) {
+        match intrinsic(value) {
+            Some((function, arg)) => match function.as_str() {
+                "Ref" => {
+                    let binding = arg.as_str().and_then(|s| self.lookup(s));
+                    push_resource(references, binding);
+                }
+                "Fn::GetAtt" => push_resource(references, self.attribute(arg)),
+                "Fn::ImportValue" => {
+                    if let Some(name) = self.evaluate(arg).and_then(|n| scalar_string(&n)) {
+                        let reference = Reference::Import(name);
+                        if !references.contains(&reference) {
+                            references.push(reference);
+                        }
+                    }
+                }
+                "Fn::Sub" => {
+                    let (template, variables) = sub_arguments(arg);
+                    for name in template.map(sub_variables).unwrap_or_default() {
+                        if variables.is_some_and(|v| v.contains_key(name)) {
+                            continue;
+                        }
+                        push_resource(references, self.variable(name));
+                    }
+                    for value in variables.into_iter().flat_map(|v| v.values()) {
+                        self.collect_references(value, references);
+                    }
+                }
+                _ => self.collect_references(arg, references),
+            },
+            None => match value {
+                Value::Sequence(items) => {
+                    for item in items {
+                        self.collect_references(item, references);
+                    }
+                }
+                Value::Mapping(map) => {
+                    for item in map.values() {
+                        self.collect_references(item, references);
+                    }
+                }
+                Value::Tagged(tagged) => self.collect_references(&tagged.value, references),
+                _ => {}
+            },
+        }
+    }
+
+    /// Whether a value is `AWS::NoValue`, possibly chosen by `Fn::If`.
+    fn is_no_value(&self, value: &Value) -> bool {
+        intrinsic(value).is_some() && self.evaluate(value) == Some(Value::Null)
+    }
+
+    fn call(&self, function: &str, arg: &Value) -> Option<Value> {
+        match function {
+            "Ref" => self.lookup(arg.as_str()?)?.value,
+            "Fn::GetAtt" => self.attribute(arg)?.value,
+            "Fn::Sub" => {
+                let (template, variables) = sub_arguments(arg);
+                let substituted = substitute(template?, |name| {
+                    match variables.and_then(|v| v.get(name)) {
+                        Some(value) => self.evaluate(value).and_then(|v| scalar_string(&v)),
+                        None => self.variable(name)?.value.and_then(|v| scalar_string(&v)),
+                    }
+                })?;
+                Some(Value::String(substituted))
+            }
+            "Fn::Join" => {
+                let [delimiter, items] = arguments::<2>(arg)?;
+                let delimiter = delimiter.as_str()?;
+                let items = self.evaluate(items)?;
+                let parts = items
+                    .as_sequence()?
+                    .iter()
+                    .map(scalar_string)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Value::String(parts.join(delimiter)))
+            }
+            "Fn::Select" => {
+                let [index, items] = arguments::<2>(arg)?;
+                let index: usize = scalar_string(&self.evaluate(index)?)?.parse().ok()?;
+                self.evaluate(items)?.as_sequence()?.get(index).cloned()
+            }
+            "Fn::Split" => {
+                let [delimiter, source] = arguments::<2>(arg)?;
+                let delimiter = delimiter.as_str()?;
+                let source = scalar_string(&self.evaluate(source)?)?;
+                Some(Value::Sequence(
+                    source
+                        .split(delimiter)
+                        .map(|s| Value::String(s.to_string()))
+                        .collect(),
+                ))
+            }
+            "Fn::FindInMap" => {
+                let [map, top, second] = arguments::<3>(arg)?;
+                let mut value = self.mappings.get(scalar_string(&self.evaluate(map)?)?)?;
+                for key in [top, second] {
+                    value = value.get(scalar_string(&self.evaluate(key)?)?)?;
+                }
+                Some(value.clone())
+            }
+            "Fn::If" => {
+                let [condition, if_true, if_false] = arguments::<3>(arg)?;
+                let chosen = if *self.conditions.get(condition.as_str()?)? {
+                    if_true
+                } else {
+                    if_false
+                };
+                self.evaluate(chosen)
+            }
+            "Fn::ImportValue" => {
+                let name = scalar_string(&self.evaluate(arg)?)?;
+                self.exports.get(&name)?.value.clone()
+            }
+            "Fn::Base64" => self.evaluate(arg),
+            "Fn::Equals" | "Fn::And" | "Fn::Or" | "Fn::Not" | "Condition" => {
+                self.condition(function, arg).map(Value::Bool)
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluate a condition function, or `None` if it depends on unknowns.
+    fn condition(&self, function: &str, arg: &Value) -> Option<bool> {
+        let operand = |value: &Value| match intrinsic(value) {
+            Some((function, arg)) => self.condition(&function, arg),
+            None => value.as_bool(),
+        };
+
+        match function {
+            "Condition" => self.conditions.get(arg.as_str()?).copied(),
+            "Fn::Equals" => {
+                let [left, right] = arguments::<2>(arg)?;
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                Some(scalar_string(&left)? == scalar_string(&right)?)
+            }
+            "Fn::Not" => {
+                let [operand_value] = arguments::<1>(arg)?;
+                operand(operand_value).map(|b| !b)
+            }
+            "Fn::And" | "Fn::Or" => {
+                let want = function == "Fn::Or";
+                let mut unknown = false;
+                for item in arg.as_sequence()? {
+                    match operand(item) {
+                        Some(b) if b == want => return Some(want),
+                        Some(_) => {}
+                        None => unknown = true,
+                    }
+                }
+                (!unknown).then_some(!want)
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluate the `Conditions` section, in dependency order.
+    fn resolve_conditions(&mut self, template: &Value) {
+        let declared: Vec<(&str, &Value)> = section(template, "Conditions").collect();
+        loop {
+            let mut progress = false;
+            for (name, expression) in &declared {
+                if self.conditions.contains_key(*name) {
+                    continue;
+                }
+                let value = intrinsic(expression)
+                    .and_then(|(function, arg)| self.condition(&function, arg));
+                if let Some(value) = value {
+                    self.conditions.insert(name.to_string(), value);
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+    }
+
+    /// What `Ref <name>` refers to.
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        if let Some(parameter) = self.parameters.get(name) {
+            return Some(parameter.clone());
+        }
+        if let Some(resource) = self.resources.get(name) {
+            return Some(Binding {
+                value: Some(Value::String(resource.name.clone())),
+                resource: Some(resource.clone()),
+            });
+        }
+
+        let value = match name {
+            "AWS::StackName" => Value::String(self.stack_name.clone()?),
+            "AWS::Partition" => Value::String("aws".to_string()),
+            "AWS::URLSuffix" => Value::String("amazonaws.com".to_string()),
+            "AWS::NoValue" => Value::Null,
+            _ => return None,
+        };
+        Some(Binding {
+            value: Some(value),
+            resource: None,
+        })
+    }
+
+    /// What `Fn::GetAtt` refers to, in the `[Resource, Attribute]` or
+    /// `Resource.Attribute` form.
+    fn attribute(&self, arg: &Value) -> Option<Binding> {
+        let (logical_id, attribute) = match arg {
+            Value::Sequence(items) => (
+                items.first()?.as_str()?.to_string(),
+                scalar_string(&self.evaluate(items.get(1)?)?)?,
+            ),
+            other => {
+                let (id, attribute) = other.as_str()?.split_once('.')?;
+                (id.to_string(), attribute.to_string())
+            }
+        };
+
+        if let Some(outputs) = self.stack_outputs.get(&logical_id) {
+            return outputs.get(attribute.strip_prefix("Outputs.")?).cloned();
+        }
+
+        let resource = self.resources.get(&logical_id)?;
+        Some(Binding {
+            value: Some(Value::String(resource.name.clone())),
+            resource: Some(resource.clone()),
+        })
+    }
+
+    /// What a `${name}` variable of `Fn::Sub` refers to.
+    fn variable(&self, name: &str) -> Option<Binding> {
+        if name.contains('.') && !self.parameters.contains_key(name) {
+            self.attribute(&Value::String(name.to_string()))
+        } else {
+            self.lookup(name)
+        }
+    }
+
+    /// Parameter declarations with `Default` set to the value in effect.
+    fn effective_parameters(&self, declarations: &Value) -> Value {
+        let Some(map) = declarations.as_mapping() else {
+            return declarations.clone();
+        };
+
+        let mut effective = map.clone();
+        for (name, declaration) in effective.iter_mut() {
+            let value = name
+                .as_str()
+                .and_then(|n| self.parameters.get(n))
+                .and_then(|b| b.value.as_ref())
+                .and_then(scalar_string);
+            if let (Some(value), Some(declaration)) = (value, declaration.as_mapping_mut()) {
+                declaration.insert(Value::from("Default"), Value::String(value));
+            }
+        }
+        Value::Mapping(effective)
+    }
+}
+
+/// The entries of a top-level template section, by name.
+fn section<'t>(template: &'t Value, name: &str) -> impl Iterator<Item = (&'t str, &'t Value)> {
+    template
+        .get(name)
+        .and_then(|s| s.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| Some((key.as_str()?, value)))
+}
+
+/// Record the resource a binding refers to, once.
+fn push_resource(references: &mut Vec<Reference>, binding: Option<Binding>) {
+    if let Some(resource) = binding.and_then(|b| b.resource) {
+        let reference = Reference::Resource(resource);
+        if !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+}
+
+/// Split a comma-delimited string value of a list parameter.
+fn list_parameter(mut binding: Binding, is_list: bool) -> Binding {
+    if let (true, Some(Value::String(s))) = (is_list, &binding.value) {
+        binding.value = Some(Value::Sequence(
+            s.split(',')
+                .map(|item| Value::String(item.trim().to_string()))
+                .collect(),
+        ));
+    }
+    binding
+}
+
+/// A scalar as a string.
+pub(crate) fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Tagged(tagged) => scalar_string(&tagged.value),
+        _ => None,
+    }
+}
+
+/// A fixed number of function arguments.
+fn arguments<const N: usize>(arg: &Value) -> Option<[&Value; N]> {
+    let items = arg.as_sequence()?;
+    if items.len() != N {
+        return None;
+    }
+    let mut arguments = [&Value::Null; N];
+    for (slot, item) in arguments.iter_mut().zip(items) {
+        *slot = item;
+    }
+    Some(arguments)
+}
+
+/// The template string and variable map of `Fn::Sub`, in either form.
+fn sub_arguments(arg: &Value) -> (Option<&str>, Option<&Mapping>) {
+    match arg {
+        Value::Sequence(items) => (
+            items.first().and_then(|t| t.as_str()),
+            items.get(1).and_then(|v| v.as_mapping()),
+        ),
+        other => (other.as_str(), None),
+    }
+}
+
+/// The `${name}` variables of a `Fn::Sub` template, skipping `${!literal}`.
+fn sub_variables(template: &str) -> Vec<&str> {
+    let mut variables = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        if !after.starts_with('!') {
+            variables.push(after[..end].trim());
+        }
+        rest = &after[end + 1..];
+    }
+    variables
+}
+
+/// Substitute the variables of a `Fn::Sub` template, or `None` if any
+/// variable cannot be resolved.
+fn substitute(template: &str, mut resolve: impl FnMut(&str) -> Option<String>) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            result.push_str(&rest[start..]);
+            return Some(result);
+        };
+        match after.strip_prefix('!') {
+            Some(literal) => {
+                result.push_str("${");
+                result.push_str(&literal[..end - 1]);
+                result.push('}');
+            }
+            None => result.push_str(&resolve(after[..end].trim())?),
+        }
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(content: &str) -> Value {
+        serde_yaml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_parameters_mappings_and_conditions() {
+        let template = template(
+            r#"
+Parameters:
+  Environment:
+    Type: String
+    Default: staging
+  Subnets:
+    Type: CommaDelimitedList
+    Default: "subnet-a, subnet-b"
+Mappings:
+  Sizes:
+    prod:
+      Capacity: 20
+    staging:
+      Capacity: 5
+Conditions:
+  IsProd: !Equals [!Ref Environment, prod]
+  NotProd: !Not [!Condition IsProd]
+Resources:
+  Orders:
+    Type: AWS::DynamoDB::Table
+    Properties:
+      TableName: !Sub '${Environment}-orders-${AWS::StackName}'
+"#,
+        );
+        let exports = Exports::new();
+        let scope = TemplateScope::new(&template, &HashMap::new(), Some("shop"), &exports);
+
+        let eval = |yaml: &str| scope.evaluate(&serde_yaml::from_str(yaml).unwrap());
+        assert_eq!(
+            eval("!Ref Orders"),
+            Some(Value::from("staging-orders-shop"))
+        );
+        assert_eq!(
+            eval("!FindInMap [Sizes, !Ref Environment, Capacity]"),
+            Some(Value::from(5))
+        );
+        assert_eq!(eval("!If [IsProd, big, small]"), Some(Value::from("small")));
+        assert_eq!(eval("!Condition NotProd"), Some(Value::Bool(true)));
+        assert_eq!(
+            eval("!Select [1, !Ref Subnets]"),
+            Some(Value::from("subnet-b"))
+        );
+        assert_eq!(
+            eval("{ 'Fn::Join': ['-', [a, !Select [0, !Split [',', 'b,c']]]] }"),
+            Some(Value::from("a-b"))
+        );
+        assert_eq!(
+            eval("!Sub ['${Name}-${!Literal}', { Name: !GetAtt Orders.Arn }]"),
+            Some(Value::from("staging-orders-shop-${Literal}"))
+        );
+        assert_eq!(eval("!Sub '${AWS::Region}-orders'"), None);
+        assert_eq!(eval("!ImportValue shared-table"), None);
+    }
+
+    #[test]
+    fn test_inputs_override_defaults_and_no_value() {
+        let template = template(
+            r#"
+Parameters:
+  Environment:
+    Type: String
+    Default: dev
+Conditions:
+  HasDlq: !Equals [!Ref Environment, prod]
+Resources:
+  Dlq:
+    Type: AWS::SQS::Queue
+    Condition: HasDlq
+  Queue:
+    Type: AWS::SQS::Queue
+    Properties:
+      QueueName: !Sub '${Environment}-jobs'
+      RedrivePolicy: !If [HasDlq, { deadLetterTargetArn: !GetAtt Dlq.Arn }, !Ref AWS::NoValue]
+"#,
+        );
+        let exports = Exports::new();
+
+        let dev = TemplateScope::new(&template, &HashMap::new(), None, &exports);
+        let evaluated = dev.evaluate_template(&template);
+        let resources = evaluated.get("Resources").unwrap();
+        assert!(resources.get("Dlq").is_none());
+        let properties = resources.get("Queue").unwrap().get("Properties").unwrap();
+        assert_eq!(properties.get("QueueName"), Some(&Value::from("dev-jobs")));
+        assert!(properties.get("RedrivePolicy").is_none());
+
+        let inputs = HashMap::from([(
+            "Environment".to_string(),
+            Binding {
+                value: Some(Value::from("prod")),
+                resource: None,
+            },
+        )]);
+        let prod = TemplateScope::new(&template, &inputs, None, &exports);
+        assert_eq!(prod.resource("Dlq").unwrap().name, "Dlq");
+        let evaluated = prod.evaluate_template(&template);
+        assert_eq!(
+            evaluated
+                .get("Parameters")
+                .and_then(|p| p.get("Environment"))
+                .and_then(|e| e.get("Default")),
+            Some(&Value::from("prod"))
+        );
+    }
+
+    #[test]
+    fn test_references_follow_parameters_outputs_and_imports() {
+        let template = template(
+            r#"
+Parameters:
+  TableName:
+    Type: String
+Resources:
+  Topic:
+    Type: AWS::SNS::Topic
+"#,
+        );
+        let table = StackResource {
+            name: "orders".to_string(),
+            kind: ComponentKind::Database,
+        };
+        let inputs = HashMap::from([(
+            "TableName".to_string(),
+            Binding {
+                value: Some(Value::from("orders")),
+                resource: Some(table.clone()),
+            },
+        )]);
+        let exports = Exports::from([(
+            "shared-bucket".to_string(),
+            Binding {
+                value: Some(Value::from("assets")),
+                resource: None,
+            },
+        )]);
+        let mut scope = TemplateScope::new(&template, &inputs, None, &exports);
+        let queue = StackResource {
+            name: "jobs".to_string(),
+            kind: ComponentKind::Queue,
+        };
+        scope.add_stack_outputs(
+            "Messaging",
+            HashMap::from([(
+                "QueueName".to_string(),
+                Binding {
+                    value: Some(Value::from("jobs")),
+                    resource: Some(queue.clone()),
+                },
+            )]),
+        );
+
+        let value: Value = serde_yaml::from_str(
+            r#"
+Variables:
+  TABLE: !Ref TableName
+  TOPIC: !Sub 'arn:aws:sns:${AWS::Region}:${AWS::AccountId}:${Topic.TopicName}'
+  QUEUE: !GetAtt Messaging.Outputs.QueueName
+  BUCKET: !ImportValue shared-bucket
+"#,
+        )
+        .unwrap();
+        let references = scope.references(&value);
+        assert_eq!(
+            references,
+            vec![
+                Reference::Resource(table),
+                Reference::Resource(StackResource {
+                    name: "Topic".to_string(),
+                    kind: ComponentKind::Queue,
+                }),
+                Reference::Resource(queue),
+                Reference::Import("shared-bucket".to_string()),
+            ]
+        );
+        assert_eq!(
+            scope.evaluate(value.get("Variables").unwrap().get("BUCKET").unwrap()),
+            Some(Value::from("assets"))
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Intra-file constant and environment-variable propagation.
+//!
+//! Resource names are rarely written inline: a table or queue name is bound
+//! to a constant or read from an environment variable once, and the
+//! identifier is passed to the SDK. The language parsers record those
+//! bindings in a [`Bindings`] table before looking for resource accesses, so
+//! the identifier can be replaced by the name it holds.
+//!
+//! | Binding | JavaScript | Python |
+//! |---------|------------|--------|
+//! | Literal | `const TABLE = 'users'` | `TABLE = 'users'` |
+//! | Environment variable | `process.env.USERS_TABLE`, `process.env['USERS_TABLE']`, `const { USERS_TABLE } = process.env` | `os.environ['USERS_TABLE']`, `os.environ.get('USERS_TABLE')`, `os.getenv('USERS_TABLE')` |
+//! | Default | `process.env.USERS_TABLE \|\| 'users'`, `?? 'users'` | `os.getenv('USERS_TABLE', 'users')`, `... or 'users'` |
+//! | Alias | `const TABLE = OTHER` | `TABLE = OTHER` |
+//!
+//! The analysis is flow-insensitive: an identifier assigned different values
+//! anywhere in the file is not resolved.
+//!
+//! A name read from an environment variable is only known once deployed, so
+//! it is reported as an environment reference (`env:USERS_TABLE`). The graph
+//! builder replaces references with the value the IaC of the same repository
+//! (SAM, CloudFormation, serverless.yml, docker-compose) assigns to the
+//! variable, falling back to the default in code, then to the variable name.
+
+use std::collections::HashMap;
+
+/// Prefix of environment references.
+const ENV_PREFIX: &str = "env:";
+
+/// What an identifier is bound to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BoundValue {
+    /// A string literal.
+    Literal(String),
+    /// An environment variable, with the default used when it is unset.
+    Env {
+        variable: String,
+        default: Option<String>,
+    },
+}
+
+impl BoundValue {
+    /// The resource name the value stands for: the literal, or an
+    /// environment reference.
+    pub(crate) fn into_name(self) -> String {
+        match self {
+            Self::Literal(value) => value,
+            Self::Env { variable, default } => env_reference(&variable, default.as_deref()),
+        }
+    }
+}
+
+/// Values bound to identifiers in one file.
+#[derive(Debug, Default)]
+pub(crate) struct Bindings {
+    /// `None` marks identifiers bound to different values.
+    values: HashMap<String, Option<BoundValue>>,
+}
+
+impl Bindings {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an assignment. An identifier assigned two different values
+    /// becomes unresolved.
+    pub(crate) fn bind(&mut self, name: &str, value: BoundValue) {
+        self.values
+            .entry(name.to_string())
+            .and_modify(|bound| {
+                if bound.as_ref() != Some(&value) {
+                    *bound = None;
+                }
+            })
+            .or_insert(Some(value));
+    }
+
+    /// The value an identifier is bound to, if it has exactly one.
+    pub(crate) fn get(&self, name: &str) -> Option<&BoundValue> {
+        self.values.get(name).and_then(|value| value.as_ref())
+    }
+}
+
+/// An environment reference: `env:USERS_TABLE`, or `env:USERS_TABLE:users`
+/// with a default.
+pub(crate) fn env_reference(variable: &str, default: Option<&str>) -> String {
+    match default {
+        Some(default) => format!("{}{}:{}", ENV_PREFIX, variable, default),
+        None => format!("{}{}", ENV_PREFIX, variable),
+    }
+}
+
+/// Split an environment reference into the variable and its default.
+pub(crate) fn parse_env_reference(name: &str) -> Option<(&str, Option<&str>)> {
+    let reference = name.strip_prefix(ENV_PREFIX)?;
+    match reference.split_once(':') {
+        Some((variable, default)) => Some((variable, Some(default))),
+        None => Some((reference, None)),
+    }
+}
+
+/// The resource name in a configured value: the last segment of a queue URL
+/// (`https://sqs.../123456789012/orders`) or ARN
+/// (`arn:aws:dynamodb:...:table/users`), or the value itself.
+pub(crate) fn resource_name(value: &str) -> &str {
+    let value = value.trim_end_matches('/');
+    let value = if value.starts_with("arn:") {
+        value.rsplit(':').next().unwrap_or(value)
+    } else if value.contains("://") {
+        value
+    } else {
+        return value;
+    };
+    value.rsplit('/').next().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflicting_bindings_are_unresolved() {
+        let mut bindings = Bindings::new();
+        bindings.bind("TABLE", BoundValue::Literal("users".to_string()));
+        bindings.bind("TABLE", BoundValue::Literal("users".to_string()));
+        bindings.bind("QUEUE", BoundValue::Literal("jobs".to_string()));
+        bindings.bind("QUEUE", BoundValue::Literal("other".to_string()));
+
+        assert_eq!(
+            bindings.get("TABLE"),
+            Some(&BoundValue::Literal("users".to_string()))
+        );
+        assert_eq!(bindings.get("QUEUE"), None);
+        assert_eq!(bindings.get("MISSING"), None);
+    }
+
+    #[test]
+    fn test_env_references_and_resource_names() {
+        let reference = BoundValue::Env {
+            variable: "QUEUE_URL".to_string(),
+            default: Some("https://sqs.local/000/jobs".to_string()),
+        }
+        .into_name();
+        assert_eq!(
+            parse_env_reference(&reference),
+            Some(("QUEUE_URL", Some("https://sqs.local/000/jobs")))
+        );
+        assert_eq!(
+            parse_env_reference("env:USERS_TABLE"),
+            Some(("USERS_TABLE", None))
+        );
+        assert_eq!(parse_env_reference("users"), None);
+
+        assert_eq!(
+            resource_name("https://sqs.us-east-1.amazonaws.com/123456789012/orders"),
+            "orders"
+        );
+        assert_eq!(
+            resource_name("arn:aws:dynamodb:us-east-1:123456789012:table/users"),
+            "users"
+        );
+        assert_eq!(resource_name("arn:aws:sns:us-east-1:123:events"), "events");
+        assert_eq!(resource_name("users-table"), "users-table");
+    }
+}
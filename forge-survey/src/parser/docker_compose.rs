@@ -0,0 +1,980 @@
+//! Docker Compose parser for detecting local service topology.
+//!
+//! Detects:
+//! - One service per compose service (`services.<name>`)
+//! - Databases from well-known images (postgres, mysql, mongo, redis, ...)
+//! - Message brokers from well-known images (rabbitmq, kafka, nats, ...)
+//! - AWS emulators (localstack, minio) as cloud resources
+//!
+//! Extracts relationships from:
+//! - `depends_on` (list and map forms)
+//! - `links` (`service` and `service:alias` forms)
+//! - Connection URLs and `*_HOST` values in `environment`
+//!
+//! Database DSN variables in `environment` are reported as environment
+//! variables, naming the compose database when the DSN points at one, so
+//! code reading the variable reaches that database.
+//!
+//! All discoveries carry a `deployment_method` of "docker-compose". Compose
+//! files usually describe local development setups, so no environment is set
+//! here; the forge.yaml environment (typically a `local_only` one) applies.
+
+use super::sql::is_dsn_variable;
+use super::traits::*;
+use crate::cache::DiscoveryCache;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// What a compose service represents, based on its image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageKind {
+    /// A database (value is the db_type).
+    Database(&'static str),
+    /// A queue or message broker (value is the queue_type).
+    Queue(&'static str),
+    /// A cloud resource emulator (value is the resource_type).
+    CloudResource(&'static str),
+    /// An application service.
+    Service,
+}
+
+impl ImageKind {
+    fn component_kind(&self) -> ComponentKind {
+        match self {
+            ImageKind::Database(_) => ComponentKind::Database,
+            ImageKind::Queue(_) => ComponentKind::Queue,
+            ImageKind::CloudResource(_) => ComponentKind::CloudResource,
+            ImageKind::Service => ComponentKind::Service,
+        }
+    }
+}
+
+/// A compose service after classification.
+struct ComposeService<'a> {
+    /// Key under `services`.
+    key: &'a str,
+    /// Qualified component name used for graph nodes.
+    name: String,
+    kind: ImageKind,
+    definition: &'a serde_yaml::Value,
+    line: u32,
+}
+
+/// Parser for docker-compose files.
+pub struct DockerComposeParser {}
+
+impl DockerComposeParser {
+    pub fn new() -> Result<Self, ParserError> {
+        Ok(Self {})
+    }
+
+    fn parse_yaml(&self, content: &str) -> Result<serde_yaml::Value, ParserError> {
+        serde_yaml::from_str(content)
+            .map_err(|e| ParserError::TreeSitterError(format!("YAML parse error: {}", e)))
+    }
+
+    /// Check if a file is a compose file by name.
+    ///
+    /// Matches `docker-compose.yml`, `compose.yaml` and override/variant files
+    /// such as `docker-compose.override.yml` or `compose.dev.yaml`.
+    pub fn is_compose_filename(path: &Path) -> bool {
+        let filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        if !(filename.ends_with(".yml") || filename.ends_with(".yaml")) {
+            return false;
+        }
+
+        filename.starts_with("docker-compose.") || filename.starts_with("compose.")
+    }
+
+    /// Extract all discoveries from a parsed compose file.
+    fn extract(&self, compose: &serde_yaml::Value, path: &Path, content: &str) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+
+        let Some(services_map) = compose.get("services").and_then(|s| s.as_mapping()) else {
+            return discoveries;
+        };
+
+        let project = self.project_name(compose, path);
+        let source_file = path.to_string_lossy().to_string();
+
+        // Classify every service first so relationships can be resolved by key.
+        // BTreeMap keeps output order deterministic.
+        let mut services: BTreeMap<&str, ComposeService> = BTreeMap::new();
+        for (key, definition) in services_map {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+            let kind = definition
+                .get("image")
+                .and_then(|i| i.as_str())
+                .map(classify_image)
+                .unwrap_or(ImageKind::Service);
+
+            services.insert(
+                key,
+                ComposeService {
+                    key,
+                    name: qualify_name(project.as_deref(), key),
+                    kind,
+                    definition,
+                    line: find_service_line(content, key),
+                },
+            );
+        }
+
+        // Emit component discoveries
+        for service in services.values() {
+            let metadata = self.build_deployment_metadata(service.definition, project.as_deref());
+            let discovery = match service.kind {
+                ImageKind::Database(db_type) => {
+                    Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+                        db_type: db_type.to_string(),
+                        table_name: Some(service.name.clone()),
+                        connection: None,
+                        operation: DatabaseOperation::Unknown,
+                        detection_method: "docker-compose".to_string(),
+                        source_file: source_file.clone(),
+                        source_line: service.line,
+                        deployment_metadata: Some(metadata),
+                    })
+                }
+                ImageKind::Queue(queue_type) => {
+                    Discovery::QueueOperation(QueueOperationDiscovery {
+                        queue_type: queue_type.to_string(),
+                        queue_name: Some(service.name.clone()),
+                        operation: QueueOperationType::Unknown,
+                        source_file: source_file.clone(),
+                        source_line: service.line,
+                        deployment_metadata: Some(metadata),
+                    })
+                }
+                ImageKind::CloudResource(resource_type) => {
+                    Discovery::CloudResourceUsage(CloudResourceDiscovery {
+                        resource_type: resource_type.to_string(),
+                        resource_name: Some(service.name.clone()),
+                        source_file: source_file.clone(),
+                        source_line: service.line,
+                        deployment_metadata: Some(metadata),
+                    })
+                }
+                ImageKind::Service => Discovery::Service(ServiceDiscovery {
+                    name: service.name.clone(),
+                    language: self.infer_language(service.definition),
+                    framework: None,
+                    entry_point: self.entry_point(service.definition),
+                    source_file: source_file.clone(),
+                    source_line: service.line,
+                    deployment_metadata: Some(metadata),
+                }),
+            };
+            discoveries.push(discovery);
+        }
+
+        // Emit relationships. Only application services originate relationships;
+        // a database depending on another container is not interesting here.
+        for service in services.values() {
+            if service.kind != ImageKind::Service {
+                continue;
+            }
+
+            let mut targets: Vec<&str> = Vec::new();
+            targets.extend(self.depends_on(service.definition));
+            targets.extend(self.links(service.definition));
+            for host in self.environment_hosts(service.definition) {
+                if let Some((key, _)) = services.get_key_value(host.as_str()) {
+                    targets.push(key);
+                }
+            }
+
+            targets.sort_unstable();
+            targets.dedup();
+
+            for (key, value) in self.environment(service.definition) {
+                if !is_dsn_variable(&key) {
+                    continue;
+                }
+                let database = host_from_url(&value)
+                    .and_then(|host| services.get(host.as_str()))
+                    .filter(|target| matches!(target.kind, ImageKind::Database(_)));
+                discoveries.push(Discovery::EnvironmentVariable(
+                    EnvironmentVariableDiscovery {
+                        service: service.name.clone(),
+                        name: key,
+                        value: database.map_or(value, |target| target.name.clone()),
+                        source_file: source_file.clone(),
+                        source_line: service.line,
+                    },
+                ));
+            }
+
+            for target_key in targets {
+                let Some(target) = services.get(target_key) else {
+                    continue;
+                };
+                if target.key == service.key {
+                    continue;
+                }
+                discoveries.push(Discovery::Relationship(RelationshipDiscovery {
+                    source_service: service.name.clone(),
+                    target: target.name.clone(),
+                    target_kind: target.kind.component_kind(),
+                    relationship: RelationshipType::DependsOn,
+                    detection_method: "docker-compose".to_string(),
+                    source_file: source_file.clone(),
+                    source_line: service.line,
+                }));
+            }
+        }
+
+        discoveries
+    }
+
+    /// Determine the compose project name.
+    ///
+    /// Uses the top-level `name` field if present, otherwise the directory
+    /// containing the compose file (matching docker compose's own default).
+    fn project_name(&self, compose: &serde_yaml::Value, path: &Path) -> Option<String> {
+        if let Some(name) = compose.get("name").and_then(|n| n.as_str()) {
+            return Some(name.to_string());
+        }
+
+        path.parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.to_string())
+    }
+
+    /// Collect service keys from `depends_on` (list or map form).
+    fn depends_on<'a>(&self, definition: &'a serde_yaml::Value) -> Vec<&'a str> {
+        match definition.get("depends_on") {
+            Some(serde_yaml::Value::Sequence(seq)) => {
+                seq.iter().filter_map(|v| v.as_str()).collect()
+            }
+            Some(serde_yaml::Value::Mapping(map)) => {
+                map.keys().filter_map(|k| k.as_str()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Collect service keys from `links` (`service` or `service:alias`).
+    fn links<'a>(&self, definition: &'a serde_yaml::Value) -> Vec<&'a str> {
+        definition
+            .get("links")
+            .and_then(|l| l.as_sequence())
+            .map(|seq| {
+                seq.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.split(':').next().unwrap_or(s))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Collect environment variables as key/value pairs.
+    ///
+    /// Handles both the map form and the `KEY=value` list form.
+    fn environment(&self, definition: &serde_yaml::Value) -> Vec<(String, String)> {
+        match definition.get("environment") {
+            Some(serde_yaml::Value::Mapping(map)) => map
+                .iter()
+                .filter_map(|(k, v)| Some((k.as_str()?.to_string(), scalar_to_string(v)?)))
+                .collect(),
+            Some(serde_yaml::Value::Sequence(seq)) => seq
+                .iter()
+                .filter_map(|v| v.as_str())
+                .filter_map(|entry| {
+                    let (k, v) = entry.split_once('=')?;
+                    Some((k.to_string(), v.to_string()))
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Extract host names referenced by environment variables.
+    ///
+    /// Recognizes connection URLs (`postgres://user:pw@db:5432/app`,
+    /// `http://orders:8080`) and host-style variables (`DB_HOST=db`,
+    /// `CACHE_ADDR=redis:6379`).
+    fn environment_hosts(&self, definition: &serde_yaml::Value) -> Vec<String> {
+        let mut hosts = Vec::new();
+
+        for (key, value) in self.environment(definition) {
+            if let Some(host) = host_from_url(&value) {
+                hosts.push(host);
+                continue;
+            }
+
+            let key_upper = key.to_uppercase();
+            let host_like = ["_HOST", "_HOSTNAME", "_ADDR", "_ADDRESS", "_SERVER"]
+                .iter()
+                .any(|suffix| key_upper.ends_with(suffix));
+            if host_like {
+                let host = value.split(':').next().unwrap_or("").trim();
+                if !host.is_empty() {
+                    hosts.push(host.to_string());
+                }
+            }
+        }
+
+        hosts
+    }
+
+    /// Infer a language from the service image (e.g., `node:20` → javascript).
+    fn infer_language(&self, definition: &serde_yaml::Value) -> String {
+        let image = definition
+            .get("image")
+            .and_then(|i| i.as_str())
+            .map(image_base_name)
+            .unwrap_or_default();
+
+        match image.as_str() {
+            "node" => "javascript",
+            "python" => "python",
+            "golang" => "go",
+            "openjdk" | "eclipse-temurin" | "amazoncorretto" => "java",
+            "ruby" => "ruby",
+            "php" => "php",
+            _ => "unknown",
+        }
+        .to_string()
+    }
+
+    /// Entry point: the build context if the service is built locally,
+    /// otherwise the image reference.
+    fn entry_point(&self, definition: &serde_yaml::Value) -> String {
+        match definition.get("build") {
+            Some(serde_yaml::Value::String(context)) => return context.clone(),
+            Some(build) => {
+                if let Some(context) = build.get("context").and_then(|c| c.as_str()) {
+                    return context.to_string();
+                }
+            }
+            None => {}
+        }
+
+        definition
+            .get("image")
+            .and_then(|i| i.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    /// Build deployment metadata, using compose `labels` as tags.
+    fn build_deployment_metadata(
+        &self,
+        definition: &serde_yaml::Value,
+        project: Option<&str>,
+    ) -> DeploymentMetadata {
+        let mut tags = HashMap::new();
+        match definition.get("labels") {
+            Some(serde_yaml::Value::Mapping(map)) => {
+                for (k, v) in map {
+                    if let (Some(k), Some(v)) = (k.as_str(), scalar_to_string(v)) {
+                        tags.insert(k.to_string(), v);
+                    }
+                }
+            }
+            Some(serde_yaml::Value::Sequence(seq)) => {
+                for entry in seq.iter().filter_map(|v| v.as_str()) {
+                    if let Some((k, v)) = entry.split_once('=') {
+                        tags.insert(k.to_string(), v.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        DeploymentMetadata {
+            deployment_method: "docker-compose".to_string(),
+            terraform_workspace: None,
+            environment: None,
+            stack_name: project.map(|s| s.to_string()),
+            tags,
+            resource_attributes: HashMap::new(),
+        }
+    }
+}
+
+/// Classify a compose image reference into a component kind.
+fn classify_image(image: &str) -> ImageKind {
+    let repository = image_repository(image);
+    let base = image_base_name(image);
+
+    match repository.as_str() {
+        "amazon/dynamodb-local" => return ImageKind::Database("dynamodb"),
+        "softwaremill/elasticmq" | "softwaremill/elasticmq-native" => {
+            return ImageKind::Queue("sqs");
+        }
+        "localstack/localstack" | "localstack/localstack-pro" => {
+            return ImageKind::CloudResource("localstack");
+        }
+        _ => {}
+    }
+
+    match base.as_str() {
+        "postgres" | "postgresql" | "postgis" | "timescaledb" => ImageKind::Database("postgresql"),
+        "mysql" | "mysql-server" => ImageKind::Database("mysql"),
+        "mariadb" => ImageKind::Database("mariadb"),
+        "mongo" | "mongodb" => ImageKind::Database("mongodb"),
+        "redis" | "redis-stack" | "valkey" | "keydb" => ImageKind::Database("redis"),
+        "memcached" => ImageKind::Database("memcached"),
+        "cassandra" | "scylla" => ImageKind::Database("cassandra"),
+        "elasticsearch" | "opensearch" => ImageKind::Database("elasticsearch"),
+        "dynamodb-local" => ImageKind::Database("dynamodb"),
+        "mssql-server" => ImageKind::Database("sqlserver"),
+        "rabbitmq" => ImageKind::Queue("rabbitmq"),
+        "kafka" | "cp-kafka" | "cp-server" | "redpanda" => ImageKind::Queue("kafka"),
+        "nats" => ImageKind::Queue("nats"),
+        "activemq" | "activemq-artemis" => ImageKind::Queue("activemq"),
+        "elasticmq" | "elasticmq-native" => ImageKind::Queue("sqs"),
+        "localstack" => ImageKind::CloudResource("localstack"),
+        "minio" => ImageKind::CloudResource("s3"),
+        _ => ImageKind::Service,
+    }
+}
+
+/// Strip registry host, tag and digest from an image reference.
+///
+/// `docker.io/library/postgres:15` → `postgres`,
+/// `ghcr.io/acme/api:1.0` → `acme/api`.
+fn image_repository(image: &str) -> String {
+    let without_digest = image.split('@').next().unwrap_or(image);
+
+    let mut parts: Vec<&str> = without_digest.split('/').collect();
+    // A first segment with a dot or port is a registry host
+    if parts.len() > 1 && (parts[0].contains('.') || parts[0].contains(':')) {
+        parts.remove(0);
+    }
+    if parts.first() == Some(&"library") && parts.len() > 1 {
+        parts.remove(0);
+    }
+
+    let joined = parts.join("/");
+    // Strip the tag from the last segment only
+    match joined.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => repo.to_lowercase(),
+        _ => joined.to_lowercase(),
+    }
+}
+
+/// The last path segment of an image repository (`bitnami/redis:7` → `redis`).
+fn image_base_name(image: &str) -> String {
+    let repository = image_repository(image);
+    repository
+        .rsplit('/')
+        .next()
+        .unwrap_or(&repository)
+        .to_string()
+}
+
+/// Prefix a compose service key with the project name.
+///
+/// Compose keys like `db` or `api` are only unique within one file, so they are
+/// qualified to avoid merging unrelated containers across repositories. Keys
+/// already qualified (`shop` or `shop-api` in project `shop`) are kept.
+fn qualify_name(project: Option<&str>, key: &str) -> String {
+    match project {
+        Some(project) if !is_qualified(project, key) => format!("{}-{}", project, key),
+        _ => key.to_string(),
+    }
+}
+
+/// Whether a service key already starts with the project name as a word.
+fn is_qualified(project: &str, key: &str) -> bool {
+    key.strip_prefix(project)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '_']))
+}
+
+/// Extract the host from a URL-style value (`scheme://[user@]host[:port]/...`).
+fn host_from_url(value: &str) -> Option<String> {
+    let (_, rest) = value.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit('@').next().unwrap_or(authority);
+    let host = host_port.split(':').next().unwrap_or("");
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_string())
+    }
+}
+
+/// Convert a scalar YAML value to a string.
+fn scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Find the 1-based line on which a service is declared.
+///
+/// Best-effort: looks for `key:` (optionally quoted) at the indentation of the
+/// first service inside the top-level `services:` block, so keys of the same
+/// name under `depends_on`, `networks` or `volumes` are skipped. Returns 1 if
+/// not found.
+fn find_service_line(content: &str, key: &str) -> u32 {
+    let needles = [
+        format!("{}:", key),
+        format!("\"{}\":", key),
+        format!("'{}':", key),
+    ];
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let is_blank = |line: &str| {
+        let trimmed = line.trim_start();
+        trimmed.is_empty() || trimmed.starts_with('#')
+    };
+
+    let mut lines = content.lines().enumerate();
+    if !lines
+        .by_ref()
+        .any(|(_, line)| indent(line) == 0 && line.starts_with("services:"))
+    {
+        return 1;
+    }
+
+    let mut service_indent = None;
+    for (i, line) in lines {
+        if is_blank(line) {
+            continue;
+        }
+        let line_indent = indent(line);
+        if line_indent == 0 {
+            break;
+        }
+        let service_indent = *service_indent.get_or_insert(line_indent);
+        if line_indent == service_indent
+            && needles
+                .iter()
+                .any(|needle| line.trim_start().starts_with(needle.as_str()))
+        {
+            return i as u32 + 1;
+        }
+    }
+    1
+}
+
+impl Parser for DockerComposeParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "docker-compose"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["yml", "yaml"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        if !Self::is_compose_filename(path) {
+            return Ok(Vec::new());
+        }
+
+        let compose = self.parse_yaml(content)?;
+        Ok(self.extract(&compose, path, content))
+    }
+
+    /// Custom repository parsing that only visits compose files.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let files = repo_files(repo_path, is_ignored_compose_dir, |path| {
+            Self::is_compose_filename(path)
+        });
+        // Without a `name`, the project is named after the directory
+        let cache = cache
+            .for_parser(self.name(), self.version())
+            .keyed_by_directories(1);
+        Ok(RepoParse::parse_files(&files, |path, content| {
+            cache.get_or_parse(path, content, || self.parse_file(path, content))
+        }))
+    }
+}
+
+/// Directories to skip during compose file scanning.
+fn is_ignored_compose_dir(name: &str) -> bool {
+    matches!(
+        name,
+        "node_modules"
+            | ".git"
+            | "target"
+            | "dist"
+            | "build"
+            | "__pycache__"
+            | "venv"
+            | ".venv"
+            | ".terraform"
+            | ".idea"
+            | ".vscode"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(path: &str, content: &str) -> Vec<Discovery> {
+        let parser = DockerComposeParser::new().unwrap();
+        parser.parse_file(Path::new(path), content).unwrap()
+    }
+
+    const SHOP_COMPOSE: &str = r#"
+services:
+  api:
+    build: ./api
+    depends_on:
+      - db
+      - cache
+    environment:
+      DATABASE_URL: postgres://shop:secret@db:5432/shop
+      ORDERS_URL: http://orders:8080/v1
+      QUEUE_HOST: broker
+  orders:
+    image: node:20-alpine
+    depends_on:
+      broker:
+        condition: service_healthy
+    labels:
+      team: checkout
+  db:
+    image: postgres:15-alpine
+  cache:
+    image: bitnami/redis:7.2
+  broker:
+    image: rabbitmq:3-management
+  aws:
+    image: localstack/localstack:3
+"#;
+
+    #[test]
+    fn test_is_compose_filename() {
+        assert!(DockerComposeParser::is_compose_filename(Path::new(
+            "docker-compose.yml"
+        )));
+        assert!(DockerComposeParser::is_compose_filename(Path::new(
+            "repo/compose.yaml"
+        )));
+        assert!(DockerComposeParser::is_compose_filename(Path::new(
+            "docker-compose.override.yml"
+        )));
+        assert!(!DockerComposeParser::is_compose_filename(Path::new(
+            "template.yaml"
+        )));
+        assert!(!DockerComposeParser::is_compose_filename(Path::new(
+            "docker-compose.json"
+        )));
+    }
+
+    #[test]
+    fn test_classify_image() {
+        assert_eq!(
+            classify_image("postgres:15"),
+            ImageKind::Database("postgresql")
+        );
+        assert_eq!(
+            classify_image("docker.io/library/postgres@sha256:abc"),
+            ImageKind::Database("postgresql")
+        );
+        assert_eq!(
+            classify_image("bitnami/redis:7"),
+            ImageKind::Database("redis")
+        );
+        assert_eq!(
+            classify_image("amazon/dynamodb-local"),
+            ImageKind::Database("dynamodb")
+        );
+        assert_eq!(
+            classify_image("confluentinc/cp-kafka:7.5.0"),
+            ImageKind::Queue("kafka")
+        );
+        assert_eq!(
+            classify_image("localhost:5000/localstack/localstack"),
+            ImageKind::CloudResource("localstack")
+        );
+        assert_eq!(classify_image("ghcr.io/acme/api:1.0"), ImageKind::Service);
+    }
+
+    #[test]
+    fn test_services_and_datastores() {
+        let discoveries = parse("docker-compose.yml", SHOP_COMPOSE);
+
+        let services: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(services.len(), 2);
+        let api = services.iter().find(|s| s.name == "api").unwrap();
+        assert_eq!(api.entry_point, "./api");
+        assert_eq!(api.source_line, 3);
+        let orders = services.iter().find(|s| s.name == "orders").unwrap();
+        assert_eq!(orders.language, "javascript");
+        let metadata = orders.deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "docker-compose");
+        assert_eq!(metadata.tags.get("team"), Some(&"checkout".to_string()));
+
+        let databases: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(databases.len(), 2);
+        assert!(
+            databases
+                .iter()
+                .any(|d| d.db_type == "postgresql" && d.table_name.as_deref() == Some("db"))
+        );
+        assert!(databases.iter().any(|d| d.db_type == "redis"));
+
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::QueueOperation(q) if q.queue_type == "rabbitmq"
+        )));
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::CloudResourceUsage(r) if r.resource_type == "localstack"
+        )));
+    }
+
+    #[test]
+    fn test_relationships_from_depends_on_and_environment() {
+        let discoveries = parse("docker-compose.yml", SHOP_COMPOSE);
+
+        let relationships: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+
+        let api_targets: Vec<_> = relationships
+            .iter()
+            .filter(|r| r.source_service == "api")
+            .map(|r| (r.target.as_str(), r.target_kind))
+            .collect();
+        assert_eq!(
+            api_targets,
+            vec![
+                ("broker", ComponentKind::Queue),
+                ("cache", ComponentKind::Database),
+                ("db", ComponentKind::Database),
+                ("orders", ComponentKind::Service),
+            ]
+        );
+
+        // Map-form depends_on
+        assert!(
+            relationships
+                .iter()
+                .any(|r| r.source_service == "orders" && r.target == "broker")
+        );
+        assert!(
+            relationships
+                .iter()
+                .all(|r| r.detection_method == "docker-compose")
+        );
+    }
+
+    #[test]
+    fn test_dsn_variables_name_compose_database() {
+        let discoveries = parse("docker-compose.yml", SHOP_COMPOSE);
+
+        let variables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::EnvironmentVariable(v) => {
+                    Some((v.service.as_str(), v.name.as_str(), v.value.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(variables, vec![("api", "DATABASE_URL", "db")]);
+    }
+
+    #[test]
+    fn test_cached_project_named_after_directory() {
+        let repo = tempfile::tempdir().unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache = DiscoveryCache::new(cache_dir.path());
+        for project in ["shop", "billing"] {
+            std::fs::create_dir(repo.path().join(project)).unwrap();
+            std::fs::write(
+                repo.path().join(project).join("docker-compose.yml"),
+                SHOP_COMPOSE,
+            )
+            .unwrap();
+        }
+
+        let parser = DockerComposeParser::new().unwrap();
+        let stacks = || -> Vec<String> {
+            let mut stacks: Vec<String> = parser
+                .parse_repo_cached(repo.path(), &cache)
+                .unwrap()
+                .discoveries
+                .iter()
+                .filter_map(|d| match d {
+                    Discovery::DatabaseAccess(db) => {
+                        db.deployment_metadata.as_ref()?.stack_name.clone()
+                    }
+                    _ => None,
+                })
+                .collect();
+            stacks.dedup();
+            stacks
+        };
+
+        assert_eq!(stacks(), vec!["billing", "shop"]);
+        // Served from the cache the second time, still per directory
+        assert_eq!(stacks(), vec!["billing", "shop"]);
+        assert_eq!(cache.hits(), 2);
+    }
+
+    #[test]
+    fn test_links_and_list_environment() {
+        let content = r#"
+services:
+  web:
+    image: python:3.12
+    links:
+      - "search:es"
+    environment:
+      - MONGO_URL=mongodb://mongo:27017/app
+      - DEBUG=1
+  search:
+    image: elasticsearch:8.11.0
+  mongo:
+    image: mongo:7
+"#;
+        let discoveries = parse("compose.yaml", content);
+
+        let targets: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some(r.target.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(targets, vec!["mongo", "search"]);
+    }
+
+    #[test]
+    fn test_project_name_qualifies_components() {
+        let content = r#"
+name: shop
+services:
+  shop-api:
+    build: .
+    depends_on: [db]
+  db:
+    image: mysql:8
+"#;
+        let discoveries = parse("repo/docker-compose.yml", content);
+
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::Service(s) if s.name == "shop-api"
+        )));
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::DatabaseAccess(db) if db.table_name.as_deref() == Some("shop-db")
+                && db.deployment_metadata.as_ref().unwrap().stack_name.as_deref() == Some("shop")
+        )));
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::Relationship(r) if r.source_service == "shop-api" && r.target == "shop-db"
+        )));
+    }
+
+    #[test]
+    fn test_project_prefix_needs_word_boundary() {
+        assert_eq!(qualify_name(Some("app"), "application"), "app-application");
+        assert_eq!(qualify_name(Some("app"), "app"), "app");
+        assert_eq!(qualify_name(Some("app"), "app-web"), "app-web");
+        assert_eq!(qualify_name(Some("app"), "app_worker"), "app_worker");
+        assert_eq!(qualify_name(None, "application"), "application");
+    }
+
+    #[test]
+    fn test_service_line_skips_nested_keys() {
+        let content = r#"
+volumes:
+  db:
+services:
+  api:
+    build: .
+    depends_on:
+      db:
+        condition: service_healthy
+    networks:
+      db:
+  "db":
+    image: postgres:16
+    volumes:
+      - db:/var/lib/postgresql/data
+networks:
+  db:
+"#;
+        assert_eq!(find_service_line(content, "api"), 5);
+        assert_eq!(find_service_line(content, "db"), 12);
+        assert_eq!(find_service_line(content, "cache"), 1);
+    }
+
+    #[test]
+    fn test_directory_used_as_project_name() {
+        let content = r#"
+services:
+  db:
+    image: postgres
+"#;
+        let discoveries = parse("checkout/docker-compose.yml", content);
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::DatabaseAccess(db) if db.table_name.as_deref() == Some("checkout-db")
+        )));
+    }
+
+    #[test]
+    fn test_host_from_url() {
+        assert_eq!(
+            host_from_url("postgres://u:p@db:5432/app"),
+            Some("db".to_string())
+        );
+        assert_eq!(
+            host_from_url("http://orders/v1?x=1"),
+            Some("orders".to_string())
+        );
+        assert_eq!(host_from_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_non_compose_file_ignored() {
+        let discoveries = parse("config.yaml", "services:\n  db:\n    image: postgres\n");
+        assert!(discoveries.is_empty());
+    }
+
+    #[test]
+    fn test_compose_without_services() {
+        let discoveries = parse("docker-compose.yml", "version: '3'\n");
+        assert!(discoveries.is_empty());
+    }
+}
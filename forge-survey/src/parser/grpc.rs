@@ -0,0 +1,184 @@
+//! gRPC generated-code recognition shared by the language parsers.
+//!
+//! gRPC services are declared in `.proto` files, which the protobuf parser
+//! turns into `Api` nodes (one per rpc, method [`GRPC_METHOD`], path
+//! `/{package}.{Service}/{Method}`). Code refers to those services through
+//! generated classes whose names follow fixed conventions:
+//!
+//! | Language | Client | Server |
+//! |----------|--------|--------|
+//! | JavaScript | `new UserServiceClient(addr)`, `new pkg.UserService(addr, creds)` | `server.addService(pkg.UserService.service, impl)` |
+//! | Python | `UserServiceStub(channel)` | `add_UserServiceServicer_to_server(impl, server)`, `class X(UserServiceServicer)` |
+//! | Go | `NewUserServiceClient(conn)` | `RegisterUserServiceServer(s, impl)` |
+//!
+//! The language parsers find these in their own syntax trees (Go is scanned
+//! by the protobuf parser, as there is no Go parser) and hand them to a
+//! [`GrpcCollector`], which also records the methods invoked on each client.
+
+use super::traits::*;
+
+/// HTTP method recorded on `Api` nodes for gRPC methods.
+pub(crate) const GRPC_METHOD: &str = "GRPC";
+
+/// The gRPC service a generated class name refers to, given its suffix.
+///
+/// `service_from_class("UserServiceClient", "Client")` → `Some("UserService")`.
+pub(crate) fn service_from_class<'a>(class: &'a str, suffix: &str) -> Option<&'a str> {
+    let class = class.rsplit('.').next().unwrap_or(class);
+    class
+        .strip_suffix(suffix)
+        .filter(|service| !service.is_empty())
+        .filter(|service| {
+            service
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_uppercase())
+        })
+}
+
+/// The gRPC service name of a fully-qualified gRPC path.
+///
+/// `/acme.users.v1.UserService/GetUser` → `UserService`.
+pub(crate) fn service_from_path(path: &str) -> Option<&str> {
+    let qualified = path.trim_start_matches('/').split('/').next()?;
+    qualified.rsplit('.').next().filter(|s| !s.is_empty())
+}
+
+/// A client or server usage found in one file.
+struct Usage {
+    service: String,
+    role: GrpcRole,
+    binding: Option<String>,
+    line: u32,
+}
+
+/// Collects gRPC usages in one file and turns them into discoveries.
+pub(crate) struct GrpcCollector {
+    source_file: String,
+    detection_method: String,
+    usages: Vec<Usage>,
+    /// (receiver, method) pairs for every method call in the file
+    calls: Vec<(String, String)>,
+}
+
+impl GrpcCollector {
+    pub(crate) fn new(source_file: String, detection_method: &str) -> Self {
+        Self {
+            source_file,
+            detection_method: detection_method.to_string(),
+            usages: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Record a client construction or server registration.
+    ///
+    /// `binding` is the variable the client is assigned to, used to find the
+    /// RPC methods called on it.
+    pub(crate) fn add_usage(
+        &mut self,
+        role: GrpcRole,
+        service: &str,
+        binding: Option<&str>,
+        line: u32,
+    ) {
+        self.usages.push(Usage {
+            service: service.to_string(),
+            role,
+            binding: binding.map(|b| b.to_string()),
+            line,
+        });
+    }
+
+    /// Record a method call (`client.getUser(...)`).
+    pub(crate) fn add_call(&mut self, receiver: &str, method: &str) {
+        self.calls.push((receiver.to_string(), method.to_string()));
+    }
+
+    /// Build one discovery per service and role, merging repeated usages.
+    pub(crate) fn into_discoveries(self) -> Vec<Discovery> {
+        let mut merged: Vec<GrpcUsageDiscovery> = Vec::new();
+
+        for usage in &self.usages {
+            let methods: Vec<String> = match &usage.binding {
+                Some(binding) if usage.role == GrpcRole::Client => self
+                    .calls
+                    .iter()
+                    .filter(|(receiver, _)| receiver == binding)
+                    .map(|(_, method)| method.clone())
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            let existing = merged
+                .iter_mut()
+                .find(|d| d.service == usage.service && d.role == usage.role);
+            let discovery = match existing {
+                Some(discovery) => discovery,
+                None => {
+                    merged.push(GrpcUsageDiscovery {
+                        service: usage.service.clone(),
+                        role: usage.role,
+                        methods: Vec::new(),
+                        detection_method: self.detection_method.clone(),
+                        source_file: self.source_file.clone(),
+                        source_line: usage.line,
+                    });
+                    merged.last_mut().expect("just pushed")
+                }
+            };
+            for method in methods {
+                if !discovery.methods.contains(&method) {
+                    discovery.methods.push(method);
+                }
+            }
+        }
+
+        merged.into_iter().map(Discovery::GrpcUsage).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_from_class() {
+        assert_eq!(
+            service_from_class("UserServiceClient", "Client"),
+            Some("UserService")
+        );
+        assert_eq!(
+            service_from_class("users_pb2_grpc.UserServiceStub", "Stub"),
+            Some("UserService")
+        );
+        assert_eq!(service_from_class("Client", "Client"), None);
+        assert_eq!(service_from_class("httpClient", "Client"), None);
+        assert_eq!(
+            service_from_path("/acme.users.v1.UserService/GetUser"),
+            Some("UserService")
+        );
+        assert_eq!(service_from_path("/Greeter/SayHello"), Some("Greeter"));
+    }
+
+    #[test]
+    fn test_collector_merges_usages_and_methods() {
+        let mut collector = GrpcCollector::new("client.js".to_string(), "grpc-js");
+        collector.add_usage(GrpcRole::Client, "UserService", Some("users"), 3);
+        collector.add_usage(GrpcRole::Client, "UserService", Some("admin"), 4);
+        collector.add_call("users", "getUser");
+        collector.add_call("admin", "deleteUser");
+        collector.add_call("users", "getUser");
+        collector.add_call("other", "listOrders");
+
+        let discoveries = collector.into_discoveries();
+        assert_eq!(discoveries.len(), 1);
+        let Discovery::GrpcUsage(usage) = &discoveries[0] else {
+            panic!("expected gRPC usage");
+        };
+        assert_eq!(usage.service, "UserService");
+        assert_eq!(usage.role, GrpcRole::Client);
+        assert_eq!(usage.methods, vec!["getUser", "deleteUser"]);
+        assert_eq!(usage.source_line, 3);
+    }
+}
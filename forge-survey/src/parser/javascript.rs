@@ -5,17 +5,34 @@
 //! - CommonJS requires (`require('Y')`)
 //! - AWS SDK v2 and v3 usage
 //! - DynamoDB operations (get, put, update, delete, query, scan)
+//! - Table names and queue URLs held in constants or environment variables
 //! - HTTP client usage (axios, fetch)
+//! - AWS CDK constructs, grants and event sources
+//! - Generated gRPC clients and server registrations
+//! - Database access through Prisma, TypeORM, Sequelize, Knex, Mongoose and
+//!   SQL drivers such as `pg`, plus Prisma schema models
+//! - HTTP routes (Express, Fastify, NestJS controllers)
+//! - Message brokers (kafkajs, amqplib, Redis streams, BullMQ)
 //! - Service metadata from package.json
 //!
 //! The parser is deterministic - it uses only AST analysis with no LLM calls.
 
+use super::cdk::{CdkCollector, CdkImports, ConstructCall, construct_kind, normalize_name};
+use super::dataflow::{Bindings, BoundValue, resource_name};
+use super::grpc::{GrpcCollector, service_from_class};
+use super::messaging::{
+    BULLMQ, KAFKA, MessagingCollector, RABBITMQ, REDIS_STREAM, redis_stream_operation,
+};
+use super::routes::{HTTP_METHODS, RouteCollector, join_route};
+use super::rules::{DetectionRule, RuleSet};
+use super::sql::{SqlCollector, db_type_from_text, looks_like_sql, pluralize, snake_case};
 use super::traits::{
     ApiCallDiscovery, CloudResourceDiscovery, DatabaseAccessDiscovery, DatabaseOperation,
-    Discovery, ImportDiscovery, Parser, ParserError, QueueOperationDiscovery, QueueOperationType,
-    ServiceDiscovery,
+    Discovery, GrpcRole, ImportDiscovery, Parser, ParserError, QueueOperationDiscovery,
+    QueueOperationType, ServiceDiscovery,
 };
 use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::{Language, Node, Parser as TSParser, Query, QueryCursor};
@@ -29,6 +46,10 @@ use tree_sitter::{Language, Node, Parser as TSParser, Query, QueryCursor};
 /// - DynamoDB operations
 pub struct JavaScriptParser {
     language: Language,
+    typescript: Language,
+    tsx: Language,
+    /// User-defined detection rules
+    rules: RuleSet,
 }
 
 impl JavaScriptParser {
@@ -37,15 +58,48 @@ impl JavaScriptParser {
     /// # Errors
     /// Returns an error if tree-sitter initialization fails.
     pub fn new() -> Result<Self, ParserError> {
+        Self::with_rules(&[])
+    }
+
+    /// Create a JavaScript parser that also applies user-defined detection
+    /// rules.
+    ///
+    /// # Errors
+    /// Returns an error if tree-sitter initialization fails or a rule is
+    /// invalid.
+    pub fn with_rules(rules: &[DetectionRule]) -> Result<Self, ParserError> {
         let language = tree_sitter_javascript::LANGUAGE.into();
+        let typescript = tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into();
+        let tsx = tree_sitter_typescript::LANGUAGE_TSX.into();
 
-        // Verify the language is valid by trying to set it on a parser
+        // Verify the languages are valid by trying to set them on a parser
         let mut parser = TSParser::new();
-        parser
-            .set_language(&language)
-            .map_err(|e| ParserError::TreeSitterError(format!("Failed to set language: {}", e)))?;
+        for lang in [&language, &typescript, &tsx] {
+            parser.set_language(lang).map_err(|e| {
+                ParserError::TreeSitterError(format!("Failed to set language: {}", e))
+            })?;
+        }
+
+        let rules = RuleSet::javascript(rules, &[&language, &typescript, &tsx])?;
+
+        Ok(Self {
+            language,
+            typescript,
+            tsx,
+            rules,
+        })
+    }
 
-        Ok(Self { language })
+    /// Select the grammar for a file.
+    ///
+    /// TypeScript files need the TypeScript grammar: type annotations would
+    /// otherwise turn whole statements into error nodes.
+    fn language_for(&self, path: &Path) -> &Language {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ts") => &self.typescript,
+            Some("tsx") => &self.tsx,
+            _ => &self.language,
+        }
     }
 
     /// Parse package.json to extract service metadata.
@@ -133,7 +187,7 @@ impl JavaScriptParser {
 
         // Query for ES6 imports: import X from 'Y'
         let import_query = match Query::new(
-            &self.language,
+            &tree.language(),
             r#"
             (import_statement
               source: (string) @source)
@@ -168,7 +222,7 @@ impl JavaScriptParser {
 
         // Query for CommonJS requires: require('Y')
         let require_query = match Query::new(
-            &self.language,
+            &tree.language(),
             r#"
             (call_expression
               function: (identifier) @func
@@ -413,11 +467,13 @@ impl JavaScriptParser {
         ];
 
         // Walk the AST looking for method calls
+        let bindings = collect_js_bindings(tree.root_node(), content);
         self.walk_for_method_calls(
             tree.root_node(),
             content,
             path,
             &dynamodb_methods,
+            &bindings,
             &mut discoveries,
         );
 
@@ -431,6 +487,7 @@ impl JavaScriptParser {
         content: &str,
         path: &Path,
         methods: &[(&str, DatabaseOperation)],
+        bindings: &Bindings,
         discoveries: &mut Vec<Discovery>,
     ) {
         if node.kind() == "call_expression" {
@@ -448,13 +505,14 @@ impl JavaScriptParser {
                                 for (method, operation) in methods {
                                     if method_name == *method {
                                         // Try to extract table name from arguments
-                                        let table_name =
-                                            self.extract_table_name_from_call(&node, content);
+                                        let table_name = self
+                                            .extract_table_name_from_call(&node, content, bindings);
 
                                         discoveries.push(Discovery::DatabaseAccess(
                                             DatabaseAccessDiscovery {
                                                 db_type: "dynamodb".to_string(),
                                                 table_name,
+                                                connection: None,
                                                 operation: *operation,
                                                 detection_method: "method-call".to_string(),
                                                 source_file: path.to_string_lossy().to_string(),
@@ -475,7 +533,7 @@ impl JavaScriptParser {
         // Recursively walk children
         for i in 0..node.named_child_count() {
             if let Some(child) = node.named_child(i) {
-                self.walk_for_method_calls(child, content, path, methods, discoveries);
+                self.walk_for_method_calls(child, content, path, methods, bindings, discoveries);
             }
         }
     }
@@ -523,14 +581,19 @@ impl JavaScriptParser {
     }
 
     /// Try to extract table name from a DynamoDB call.
-    fn extract_table_name_from_call(&self, call_node: &Node, content: &str) -> Option<String> {
+    fn extract_table_name_from_call(
+        &self,
+        call_node: &Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         // Look for TableName in the arguments
         if let Some(args) = call_node.child_by_field_name("arguments") {
             for i in 0..args.named_child_count() {
                 if let Some(arg) = args.named_child(i) {
                     // Check if it's an object with TableName property
                     if arg.kind() == "object" {
-                        return self.find_table_name_in_object(arg, content);
+                        return self.find_table_name_in_object(arg, content, bindings);
                     }
                 }
             }
@@ -539,7 +602,15 @@ impl JavaScriptParser {
     }
 
     /// Find TableName property in an object literal.
-    fn find_table_name_in_object(&self, obj_node: Node, content: &str) -> Option<String> {
+    ///
+    /// Identifiers bound to a constant or environment variable in the file
+    /// resolve to the name they hold.
+    fn find_table_name_in_object(
+        &self,
+        obj_node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         for i in 0..obj_node.named_child_count() {
             if let Some(child) = obj_node.named_child(i) {
                 if child.kind() == "pair" {
@@ -547,6 +618,9 @@ impl JavaScriptParser {
                         let key_text = key.utf8_text(content.as_bytes()).unwrap_or("");
                         if key_text == "TableName" {
                             if let Some(value) = child.child_by_field_name("value") {
+                                if let Some(bound) = js_bound_value(value, content, bindings) {
+                                    return Some(bound.into_name());
+                                }
                                 let value_text = value.utf8_text(content.as_bytes()).unwrap_or("");
                                 return Some(
                                     value_text
@@ -607,12 +681,14 @@ impl JavaScriptParser {
         ];
 
         // Walk AST looking for `new XxxCommand(...)` patterns
+        let bindings = collect_js_bindings(tree.root_node(), content);
         self.walk_for_sdk_v3_commands(
             tree.root_node(),
             content,
             path,
             &dynamodb_commands,
             &sqs_commands,
+            &bindings,
             &mut discoveries,
         );
 
@@ -620,6 +696,7 @@ impl JavaScriptParser {
     }
 
     /// Walk AST looking for AWS SDK v3 command instantiations.
+    #[allow(clippy::too_many_arguments)]
     fn walk_for_sdk_v3_commands(
         &self,
         node: Node,
@@ -627,6 +704,7 @@ impl JavaScriptParser {
         path: &Path,
         dynamodb_commands: &[(&str, DatabaseOperation)],
         sqs_commands: &[(&str, super::traits::QueueOperationType)],
+        bindings: &Bindings,
         discoveries: &mut Vec<Discovery>,
     ) {
         // Look for `new XxxCommand(...)` pattern
@@ -638,11 +716,13 @@ impl JavaScriptParser {
                 for (command_name, operation) in dynamodb_commands {
                     if constructor_name == *command_name {
                         // Try to extract table name from constructor arguments
-                        let table_name = self.extract_table_name_from_new_expr(&node, content);
+                        let table_name =
+                            self.extract_table_name_from_new_expr(&node, content, bindings);
 
                         discoveries.push(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
                             db_type: "dynamodb".to_string(),
                             table_name,
+                            connection: None,
                             operation: *operation,
                             detection_method: "aws-sdk-v3-command".to_string(),
                             source_file: path.to_string_lossy().to_string(),
@@ -657,7 +737,8 @@ impl JavaScriptParser {
                 for (command_name, operation) in sqs_commands {
                     if constructor_name == *command_name {
                         // Try to extract queue name from QueueUrl
-                        let queue_name = self.extract_queue_name_from_new_expr(&node, content);
+                        let queue_name =
+                            self.extract_queue_name_from_new_expr(&node, content, bindings);
 
                         discoveries.push(Discovery::QueueOperation(QueueOperationDiscovery {
                             queue_type: "sqs".to_string(),
@@ -682,6 +763,7 @@ impl JavaScriptParser {
                     path,
                     dynamodb_commands,
                     sqs_commands,
+                    bindings,
                     discoveries,
                 );
             }
@@ -689,14 +771,19 @@ impl JavaScriptParser {
     }
 
     /// Extract table name from a `new XxxCommand({ TableName: '...', ... })` expression.
-    fn extract_table_name_from_new_expr(&self, new_expr: &Node, content: &str) -> Option<String> {
+    fn extract_table_name_from_new_expr(
+        &self,
+        new_expr: &Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         // Look for arguments of the new expression
         if let Some(args) = new_expr.child_by_field_name("arguments") {
             for i in 0..args.named_child_count() {
                 if let Some(arg) = args.named_child(i) {
                     // Check if it's an object with TableName property
                     if arg.kind() == "object" {
-                        return self.find_table_name_in_object(arg, content);
+                        return self.find_table_name_in_object(arg, content, bindings);
                     }
                 }
             }
@@ -705,14 +792,19 @@ impl JavaScriptParser {
     }
 
     /// Extract queue name from a `new SendMessageCommand({ QueueUrl: '...', ... })` expression.
-    fn extract_queue_name_from_new_expr(&self, new_expr: &Node, content: &str) -> Option<String> {
+    fn extract_queue_name_from_new_expr(
+        &self,
+        new_expr: &Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         // Look for arguments of the new expression
         if let Some(args) = new_expr.child_by_field_name("arguments") {
             for i in 0..args.named_child_count() {
                 if let Some(arg) = args.named_child(i) {
                     // Check if it's an object with QueueUrl property
                     if arg.kind() == "object" {
-                        return self.find_queue_url_in_object(arg, content);
+                        return self.find_queue_url_in_object(arg, content, bindings);
                     }
                 }
             }
@@ -721,7 +813,15 @@ impl JavaScriptParser {
     }
 
     /// Find QueueUrl property in an object literal and extract queue name.
-    fn find_queue_url_in_object(&self, obj_node: Node, content: &str) -> Option<String> {
+    ///
+    /// Identifiers bound to a constant or environment variable in the file
+    /// resolve to the URL they hold.
+    fn find_queue_url_in_object(
+        &self,
+        obj_node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         for i in 0..obj_node.named_child_count() {
             if let Some(child) = obj_node.named_child(i) {
                 if child.kind() == "pair" {
@@ -729,6 +829,13 @@ impl JavaScriptParser {
                         let key_text = key.utf8_text(content.as_bytes()).unwrap_or("");
                         if key_text == "QueueUrl" {
                             if let Some(value) = child.child_by_field_name("value") {
+                                match js_bound_value(value, content, bindings) {
+                                    Some(BoundValue::Literal(url)) => {
+                                        return Some(resource_name(&url).to_string());
+                                    }
+                                    Some(env) => return Some(env.into_name()),
+                                    None => {}
+                                }
                                 let value_text = value.utf8_text(content.as_bytes()).unwrap_or("");
                                 let url =
                                     value_text.trim_matches(|c| c == '"' || c == '\'' || c == '`');
@@ -860,216 +967,2374 @@ impl JavaScriptParser {
         }
         None
     }
-}
 
-impl Parser for JavaScriptParser {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
+    /// Detect AWS CDK constructs, grants and event sources.
+    ///
+    /// Only files importing the CDK are considered. See [`super::cdk`] for
+    /// how constructs map onto discoveries.
+    fn detect_cdk_constructs(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let root = tree.root_node();
 
-    fn supported_extensions(&self) -> &[&str] {
-        &["js", "jsx", "ts", "tsx", "mjs", "cjs"]
-    }
+        let mut imports = CdkImports::default();
+        self.collect_cdk_imports(root, content, &mut imports);
+        if imports.is_empty() {
+            return Vec::new();
+        }
 
-    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
-        // Create a new parser instance for thread safety (tree-sitter parsers are not thread-safe)
-        let mut parser = TSParser::new();
-        parser
-            .set_language(&self.language)
-            .map_err(|e| ParserError::TreeSitterError(format!("Failed to set language: {}", e)))?;
+        let mut collector = CdkCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_cdk(root, content, &imports, &mut collector);
+        collector.into_discoveries()
+    }
 
-        // Parse the file
-        let tree = parser
-            .parse(content, None)
-            .ok_or_else(|| ParserError::ParseFailed {
-                path: path.to_string_lossy().to_string(),
-            })?;
+    /// Collect CDK module aliases and class imports (ES6 and CommonJS).
+    fn collect_cdk_imports(&self, node: Node, content: &str, imports: &mut CdkImports) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        match node.kind() {
+            "import_statement" => {
+                let Some(source) = node.child_by_field_name("source") else {
+                    return;
+                };
+                let module = text(source).trim_matches(|c| c == '"' || c == '\'' || c == '`');
+
+                let mut cursor = node.walk();
+                for clause in node.named_children(&mut cursor) {
+                    if clause.kind() != "import_clause" {
+                        continue;
+                    }
+                    let mut clause_cursor = clause.walk();
+                    for child in clause.named_children(&mut clause_cursor) {
+                        match child.kind() {
+                            // import * as dynamodb from 'aws-cdk-lib/aws-dynamodb'
+                            "namespace_import" => {
+                                if let Some(alias) = child.named_child(0) {
+                                    imports.add_module(text(alias), module);
+                                }
+                            }
+                            // import { Table, aws_sqs as sqs } from '...'
+                            "named_imports" => {
+                                let mut spec_cursor = child.walk();
+                                for spec in child.named_children(&mut spec_cursor) {
+                                    let Some(name) = spec.child_by_field_name("name") else {
+                                        continue;
+                                    };
+                                    let local = spec.child_by_field_name("alias").unwrap_or(name);
+                                    imports.add_named(text(local), text(name), module);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                return;
+            }
+            // const sqs = require('aws-cdk-lib/aws-sqs')
+            "variable_declarator" => {
+                if let (Some(name), Some(value)) = (
+                    node.child_by_field_name("name"),
+                    node.child_by_field_name("value"),
+                ) {
+                    if name.kind() == "identifier"
+                        && value.kind() == "call_expression"
+                        && value
+                            .child_by_field_name("function")
+                            .is_some_and(|f| text(f) == "require")
+                    {
+                        if let Some(module) = value
+                            .child_by_field_name("arguments")
+                            .and_then(|args| args.named_child(0))
+                            .filter(|arg| arg.kind() == "string")
+                        {
+                            let module =
+                                text(module).trim_matches(|c| c == '"' || c == '\'' || c == '`');
+                            imports.add_module(text(name), module);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
 
-        let mut discoveries = Vec::new();
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_cdk_imports(child, content, imports);
+            }
+        }
+    }
 
-        // Run all detectors
-        discoveries.extend(self.detect_imports(&tree, content, path));
-        discoveries.extend(self.detect_aws_sdk(&tree, content, path));
-        discoveries.extend(self.detect_dynamodb_operations(&tree, content, path));
-        discoveries.extend(self.detect_aws_sdk_v3_commands(&tree, content, path));
-        discoveries.extend(self.detect_http_calls(&tree, content, path));
+    /// Walk the AST looking for construct instantiations, grants and event sources.
+    fn walk_for_cdk(
+        &self,
+        node: Node,
+        content: &str,
+        imports: &CdkImports,
+        collector: &mut CdkCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            // new dynamodb.Table(this, 'Orders', { tableName: 'orders' })
+            "new_expression" => {
+                let kind = node
+                    .child_by_field_name("constructor")
+                    .and_then(|c| imports.resolve(text(c)))
+                    .and_then(|(service, class)| construct_kind(&service, &class));
+
+                if let Some(kind) = kind {
+                    let mut call = ConstructCall {
+                        binding: self.construct_binding(node, content),
+                        line,
+                        ..Default::default()
+                    };
+                    if let Some(args) = node.child_by_field_name("arguments") {
+                        if let Some(id) = args.named_child(1).filter(|a| a.kind() == "string") {
+                            call.construct_id = Some(unquote_js(text(id)));
+                        }
+                        if let Some(props) = args.named_child(2).filter(|a| a.kind() == "object") {
+                            call.props = self.object_props(props, content);
+                        }
+                    }
+                    collector.add_construct(kind, call);
+                }
+            }
+            // table.grantWriteData(fn) / fn.addEventSource(new SqsEventSource(queue))
+            "call_expression" => {
+                if let Some(function) = node
+                    .child_by_field_name("function")
+                    .filter(|f| f.kind() == "member_expression")
+                {
+                    let receiver = function.child_by_field_name("object").map(text);
+                    let method = function.child_by_field_name("property").map(text);
+                    let first_arg = node
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0));
+
+                    if let (Some(receiver), Some(method), Some(arg)) = (receiver, method, first_arg)
+                    {
+                        if method.starts_with("grant") {
+                            collector.add_grant(receiver, method, text(arg), line);
+                        } else if method == "addEventSource" && arg.kind() == "new_expression" {
+                            let class = arg
+                                .child_by_field_name("constructor")
+                                .map(|c| text(c).rsplit('.').next().unwrap_or(""));
+                            let source = arg
+                                .child_by_field_name("arguments")
+                                .and_then(|args| args.named_child(0));
+                            if let (Some(class), Some(source)) = (class, source) {
+                                collector.add_event_source(receiver, class, text(source), line);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
 
-        Ok(discoveries)
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_cdk(child, content, imports, collector);
+            }
+        }
     }
-}
 
-impl Default for JavaScriptParser {
-    fn default() -> Self {
-        Self::new().expect("Failed to create default JavaScriptParser")
+    /// The variable or property a construct is assigned to
+    /// (`const table = new ...`, `this.table = new ...`).
+    fn construct_binding<'a>(&self, new_expr: Node, content: &'a str) -> Option<&'a str> {
+        let parent = new_expr.parent()?;
+        let target = match parent.kind() {
+            "variable_declarator" => parent.child_by_field_name("name")?,
+            "assignment_expression" => parent.child_by_field_name("left")?,
+            _ => return None,
+        };
+        target.utf8_text(content.as_bytes()).ok()
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    fn create_parser() -> JavaScriptParser {
-        JavaScriptParser::new().expect("Failed to create parser")
+    /// Properties of an object literal keyed by normalized name.
+    ///
+    /// String literals are unquoted; template literals with substitutions and
+    /// other expressions keep their source text.
+    fn object_props(&self, object: Node, content: &str) -> HashMap<String, String> {
+        let mut props = HashMap::new();
+        for i in 0..object.named_child_count() {
+            let Some(pair) = object.named_child(i).filter(|p| p.kind() == "pair") else {
+                continue;
+            };
+            let (Some(key), Some(value)) = (
+                pair.child_by_field_name("key"),
+                pair.child_by_field_name("value"),
+            ) else {
+                continue;
+            };
+
+            let key = unquote_js(key.utf8_text(content.as_bytes()).unwrap_or(""));
+            let raw = value.utf8_text(content.as_bytes()).unwrap_or("");
+            let is_literal = value.kind() == "string"
+                || (value.kind() == "template_string" && !raw.contains("${"));
+            let value = if is_literal {
+                unquote_js(raw)
+            } else {
+                raw.to_string()
+            };
+            props.insert(normalize_name(&key), value);
+        }
+        props
     }
 
-    #[test]
-    fn test_detect_es6_imports() {
-        let parser = create_parser();
-        let content = r#"
-import express from 'express';
-import { DynamoDB } from '@aws-sdk/client-dynamodb';
-import axios from 'axios';
-"#;
-
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+    /// Detect generated gRPC clients and server registrations.
+    ///
+    /// Clients are only recognized when constructed from a generated module
+    /// or a runtime-loaded package, so SDK clients such as `S3Client` are not
+    /// mistaken for gRPC services.
+    fn detect_grpc_usage(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        if !content.contains("grpc") {
+            return Vec::new();
+        }
 
-        let imports: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::Import(i) => Some(i),
-                _ => None,
-            })
-            .collect();
+        let root = tree.root_node();
+        let mut bindings = GrpcBindings::default();
+        self.collect_grpc_bindings(root, content, &mut bindings);
 
-        assert!(
-            imports.len() >= 3,
-            "Expected at least 3 imports, got {}",
-            imports.len()
-        );
-        assert!(
-            imports.iter().any(|i| i.module == "express"),
-            "Should detect express import"
-        );
-        assert!(
-            imports
-                .iter()
-                .any(|i| i.module == "@aws-sdk/client-dynamodb"),
-            "Should detect AWS SDK import"
-        );
-        assert!(
-            imports.iter().any(|i| i.module == "axios"),
-            "Should detect axios import"
-        );
+        let mut collector = GrpcCollector::new(path.to_string_lossy().to_string(), "grpc-js");
+        self.walk_for_grpc(root, content, &bindings, &mut collector);
+        collector.into_discoveries()
     }
 
-    #[test]
-    fn test_detect_commonjs_requires() {
-        let parser = create_parser();
-        let content = r#"
-const express = require('express');
-const AWS = require('aws-sdk');
-"#;
+    /// Collect names imported from generated gRPC modules and runtime-loaded packages.
+    fn collect_grpc_bindings(&self, node: Node, content: &str, bindings: &mut GrpcBindings) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        match node.kind() {
+            // import { UserServiceClient } from './gen/users_grpc_web_pb'
+            "import_statement" => {
+                let is_generated = node
+                    .child_by_field_name("source")
+                    .is_some_and(|s| is_generated_grpc_module(&unquote_js(text(s))));
+                if is_generated {
+                    self.collect_bound_names(node, content, &mut bindings.generated);
+                }
+                return;
+            }
+            "variable_declarator" => {
+                if let (Some(name), Some(value)) = (
+                    node.child_by_field_name("name"),
+                    node.child_by_field_name("value"),
+                ) {
+                    if let Some(function) = value
+                        .child_by_field_name("function")
+                        .filter(|_| value.kind() == "call_expression")
+                    {
+                        let first_arg = value
+                            .child_by_field_name("arguments")
+                            .and_then(|args| args.named_child(0));
+                        let function = text(function);
+
+                        // const services = require('./users_grpc_pb')
+                        if function == "require"
+                            && first_arg.is_some_and(|a| {
+                                a.kind() == "string"
+                                    && is_generated_grpc_module(&unquote_js(text(a)))
+                            })
+                        {
+                            self.collect_bound_names(name, content, &mut bindings.generated);
+                        }
+                        // const proto = grpc.loadPackageDefinition(definition)
+                        if function.ends_with("loadPackageDefinition") {
+                            self.collect_bound_names(name, content, &mut bindings.loaded);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_grpc_bindings(child, content, bindings);
+            }
+        }
+    }
 
-        let imports: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::Import(i) => Some(i),
-                _ => None,
-            })
-            .collect();
+    /// Collect every identifier bound by an import clause or binding pattern.
+    fn collect_bound_names(&self, node: Node, content: &str, names: &mut HashSet<String>) {
+        match node.kind() {
+            // Skip the module source string and the original name in `{ A as B }`
+            "string" => return,
+            "import_specifier" => {
+                let local = node
+                    .child_by_field_name("alias")
+                    .or_else(|| node.child_by_field_name("name"));
+                if let Some(local) = local {
+                    names.insert(
+                        local
+                            .utf8_text(content.as_bytes())
+                            .unwrap_or("")
+                            .to_string(),
+                    );
+                }
+                return;
+            }
+            "identifier" | "shorthand_property_identifier_pattern" => {
+                names.insert(node.utf8_text(content.as_bytes()).unwrap_or("").to_string());
+                return;
+            }
+            _ => {}
+        }
 
-        assert!(
-            imports.iter().any(|i| i.module == "express"),
-            "Should detect express require"
-        );
-        assert!(
-            imports.iter().any(|i| i.module == "aws-sdk"),
-            "Should detect aws-sdk require"
-        );
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                // In `{ key: value }` patterns only the value is bound
+                if node.kind() == "pair_pattern" && child.kind() != "identifier" {
+                    continue;
+                }
+                self.collect_bound_names(child, content, names);
+            }
+        }
     }
 
-    #[test]
-    fn test_detect_relative_imports() {
-        let parser = create_parser();
-        let content = r#"
-import { helper } from './utils/helper';
-import config from '../config';
-import data from 'data-package';
-"#;
+    /// Walk the AST looking for client constructions, `addService` calls and
+    /// method calls on clients.
+    fn walk_for_grpc(
+        &self,
+        node: Node,
+        content: &str,
+        bindings: &GrpcBindings,
+        collector: &mut GrpcCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            // new UserServiceClient(addr) / new proto.acme.UserService(addr, creds)
+            "new_expression" => {
+                if let Some(constructor) = node.child_by_field_name("constructor") {
+                    let constructor = text(constructor);
+                    let root = constructor.split('.').next().unwrap_or("");
+                    let class = constructor.rsplit('.').next().unwrap_or("");
+
+                    let service = if bindings.generated.contains(root) {
+                        service_from_class(class, "Client")
+                    } else if bindings.loaded.contains(root) && constructor.contains('.') {
+                        Some(class).filter(|c| c.starts_with(|ch: char| ch.is_ascii_uppercase()))
+                    } else {
+                        None
+                    };
+
+                    if let Some(service) = service {
+                        let binding = self.construct_binding(node, content);
+                        collector.add_usage(GrpcRole::Client, service, binding, line);
+                    }
+                }
+            }
+            "call_expression" => {
+                if let Some(function) = node
+                    .child_by_field_name("function")
+                    .filter(|f| f.kind() == "member_expression")
+                {
+                    let receiver = function.child_by_field_name("object").map(text);
+                    let method = function.child_by_field_name("property").map(text);
+
+                    if let (Some(receiver), Some(method)) = (receiver, method) {
+                        // server.addService(proto.UserService.service, impl)
+                        // server.addService(services.UserServiceService, impl)
+                        if method == "addService" {
+                            let service = node
+                                .child_by_field_name("arguments")
+                                .and_then(|args| args.named_child(0))
+                                .map(text)
+                                .and_then(|arg| match arg.strip_suffix(".service") {
+                                    Some(definition) => definition.rsplit('.').next(),
+                                    None => service_from_class(arg, "Service"),
+                                });
+                            if let Some(service) = service {
+                                collector.add_usage(GrpcRole::Server, service, None, line);
+                            }
+                        } else {
+                            collector.add_call(receiver, method);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_grpc(child, content, bindings, collector);
+            }
+        }
+    }
 
-        let imports: Vec<_> = discoveries
+    /// Detect SQL and MongoDB access: Prisma, TypeORM, Sequelize, Knex,
+    /// Mongoose and SQL executed with `pg` (or another driver).
+    ///
+    /// Tables come from entity decorators, model definitions, Knex table
+    /// builders and SQL string literals; Prisma accesses are named after the
+    /// model. DSN environment variables read in the file identify the
+    /// connection; see [`super::sql`].
+    fn detect_database_access(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let root = tree.root_node();
+        let mut bindings = OrmBindings::default();
+        self.collect_orm_bindings(root, content, &mut bindings);
+        if bindings.libraries.is_empty() && !content.contains("prisma") {
+            return Vec::new();
+        }
+
+        let db_type = bindings
+            .libraries
             .iter()
-            .filter_map(|d| match d {
-                Discovery::Import(i) => Some(i),
-                _ => None,
+            .find_map(|library| match *library {
+                "pg" => Some("postgresql"),
+                library => db_type_from_text(library),
             })
-            .collect();
-
-        let relative: Vec<_> = imports.iter().filter(|i| i.is_relative).collect();
-        let non_relative: Vec<_> = imports.iter().filter(|i| !i.is_relative).collect();
+            .or_else(|| db_type_from_text(content))
+            .unwrap_or("sql");
+        let mut collector = SqlCollector::new(path.to_string_lossy().to_string(), db_type);
+        let mut models: Vec<&OrmModel> = bindings.models.values().collect();
+        models.sort_by_key(|model| (model.line, &model.table));
+        for model in models {
+            collector.add_access(
+                &model.table,
+                DatabaseOperation::Unknown,
+                &format!("{}.model", model.orm),
+                model.line,
+            );
+        }
 
-        assert_eq!(relative.len(), 2, "Should have 2 relative imports");
-        assert_eq!(non_relative.len(), 1, "Should have 1 non-relative import");
+        self.walk_for_database_access(root, content, &bindings, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Collect ORM libraries, models, repositories and Knex instances.
+    fn collect_orm_bindings(&self, node: Node, content: &str, bindings: &mut OrmBindings) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            "import_statement" => {
+                if let Some(library) = node
+                    .child_by_field_name("source")
+                    .and_then(|s| orm_library(&unquote_js(text(s))))
+                {
+                    bindings.libraries.insert(library);
+                }
+            }
+            // @Entity('users') class User {}
+            "class_declaration" => {
+                if let Some(name) = node.child_by_field_name("name").map(text) {
+                    if let Some(table) = self.typeorm_entity_table(node, name, content) {
+                        bindings.models.insert(
+                            name.to_string(),
+                            OrmModel {
+                                table,
+                                orm: "typeorm",
+                                line,
+                            },
+                        );
+                    }
+                }
+            }
+            "call_expression" => {
+                if let (Some(function), Some(args)) = (
+                    node.child_by_field_name("function"),
+                    node.child_by_field_name("arguments"),
+                ) {
+                    let callee = text(function);
+                    let first_arg = args.named_child(0);
+                    let first_string = first_arg
+                        .filter(|a| a.kind() == "string")
+                        .map(|a| unquote_js(text(a)));
+
+                    if callee == "require" {
+                        if let Some(library) = first_string.as_deref().and_then(orm_library) {
+                            bindings.libraries.insert(library);
+                        }
+                    }
+
+                    // User.init(attributes, { sequelize, tableName: 'users' })
+                    if function.kind() == "member_expression"
+                        && callee.ends_with(".init")
+                        && bindings.libraries.contains("sequelize")
+                    {
+                        let model = function.child_by_field_name("object").map(text);
+                        let options = args.named_child(1).map(|o| self.object_props(o, content));
+                        if let (Some(model), Some(options)) = (model, options) {
+                            bindings.models.insert(
+                                model.to_string(),
+                                OrmModel {
+                                    table: sequelize_table(model, &options),
+                                    orm: "sequelize",
+                                    line,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+            "variable_declarator" => {
+                if let (Some(name), Some(value)) = (
+                    node.child_by_field_name("name").map(text),
+                    node.child_by_field_name("value"),
+                ) {
+                    self.collect_orm_declarator(name, value, content, bindings, line);
+                }
+            }
+            // constructor(@InjectRepository(User) private users: Repository<User>)
+            "required_parameter" | "optional_parameter" | "public_field_definition" => {
+                let name = node
+                    .child_by_field_name("pattern")
+                    .or_else(|| node.child_by_field_name("name"))
+                    .map(text);
+                let class = node.child_by_field_name("type").and_then(|t| {
+                    let t = text(t).trim_start_matches(':').trim();
+                    let (wrapper, class) = t.strip_suffix('>')?.split_once('<')?;
+                    Some((wrapper, class.trim()))
+                });
+                if let (Some(name), Some((wrapper, class))) = (name, class) {
+                    let orm = match wrapper {
+                        "Repository" => Some("typeorm"),
+                        "Model" if bindings.libraries.contains("mongoose") => Some("mongoose"),
+                        _ => None,
+                    };
+                    if let Some(orm) = orm {
+                        bindings
+                            .repositories
+                            .insert(name.to_string(), (class.to_string(), orm));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_orm_bindings(child, content, bindings);
+            }
+        }
+    }
+
+    /// Record what a `const name = value` declaration binds: a model, a
+    /// repository or a Knex instance.
+    fn collect_orm_declarator(
+        &self,
+        name: &str,
+        value: Node,
+        content: &str,
+        bindings: &mut OrmBindings,
+        line: u32,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        if value.kind() != "call_expression" {
+            return;
+        }
+        let (Some(function), Some(args)) = (
+            value.child_by_field_name("function"),
+            value.child_by_field_name("arguments"),
+        ) else {
+            return;
+        };
+        let callee = text(function);
+        let first_arg = args.named_child(0);
+        let model_name = first_arg
+            .filter(|a| a.kind() == "string")
+            .map(|a| unquote_js(text(a)));
+
+        // const db = knex({ client: 'pg' }) / require('knex')(config)
+        if matches!(callee, "knex" | "Knex")
+            || (function.kind() == "call_expression"
+                && function
+                    .child_by_field_name("arguments")
+                    .and_then(|a| a.named_child(0))
+                    .is_some_and(|a| unquote_js(text(a)) == "knex"))
+        {
+            bindings.knex.insert(name.to_string());
+        // const User = sequelize.define('User', attributes, { tableName: 'users' })
+        } else if callee.ends_with(".define") && bindings.libraries.contains("sequelize") {
+            if let Some(model_name) = model_name {
+                let options = args
+                    .named_child(2)
+                    .map(|o| self.object_props(o, content))
+                    .unwrap_or_default();
+                bindings.models.insert(
+                    name.to_string(),
+                    OrmModel {
+                        table: sequelize_table(&model_name, &options),
+                        orm: "sequelize",
+                        line,
+                    },
+                );
+            }
+        // const User = mongoose.model('User', userSchema, 'people')
+        } else if (callee == "model" || callee.ends_with(".model"))
+            && bindings.libraries.contains("mongoose")
+        {
+            if let Some(model_name) = model_name {
+                let table = args
+                    .named_child(2)
+                    .filter(|a| a.kind() == "string")
+                    .map(|a| unquote_js(text(a)))
+                    .unwrap_or_else(|| pluralize(&model_name).to_lowercase());
+                bindings.models.insert(
+                    name.to_string(),
+                    OrmModel {
+                        table,
+                        orm: "mongoose",
+                        line,
+                    },
+                );
+            }
+        // const users = dataSource.getRepository(User)
+        } else if callee.ends_with("getRepository") {
+            if let Some(entity) = first_arg.filter(|a| a.kind() == "identifier") {
+                bindings
+                    .repositories
+                    .insert(name.to_string(), (text(entity).to_string(), "typeorm"));
+            }
+        }
+    }
+
+    /// The table of a TypeORM entity class, if it has an `@Entity` decorator.
+    fn typeorm_entity_table(&self, class: Node, name: &str, content: &str) -> Option<String> {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        let entity = class_decorator(class, "Entity", content)?;
+
+        // @Entity('users') or @Entity({ name: 'users' })
+        let argument = entity
+            .child_by_field_name("arguments")
+            .and_then(|args| args.named_child(0));
+        let table = argument.and_then(|arg| match arg.kind() {
+            "string" => Some(unquote_js(text(arg))),
+            "object" => self.object_props(arg, content).remove("name"),
+            _ => None,
+        });
+        Some(table.unwrap_or_else(|| snake_case(name)))
+    }
+
+    /// Walk the AST looking for DSN variables, ORM calls, Knex builders and
+    /// SQL statements.
+    fn walk_for_database_access(
+        &self,
+        node: Node,
+        content: &str,
+        bindings: &OrmBindings,
+        collector: &mut SqlCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            // process.env.DATABASE_URL
+            "member_expression" => {
+                if let (Some(object), Some(property)) = (
+                    node.child_by_field_name("object"),
+                    node.child_by_field_name("property"),
+                ) {
+                    if text(object) == "process.env" {
+                        collector.add_env_variable(text(property), "process.env", line);
+                    }
+                }
+            }
+            // process.env["DATABASE_URL"]
+            "subscript_expression" => {
+                if let (Some(object), Some(index)) = (
+                    node.child_by_field_name("object"),
+                    node.child_by_field_name("index"),
+                ) {
+                    if text(object) == "process.env" && index.kind() == "string" {
+                        collector.add_env_variable(&unquote_js(text(index)), "process.env", line);
+                    }
+                }
+            }
+            "call_expression" => self.check_database_call(node, content, bindings, collector),
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_database_access(child, content, bindings, collector);
+            }
+        }
+    }
+
+    /// Record the database access a call makes, if any.
+    fn check_database_call(
+        &self,
+        node: Node,
+        content: &str,
+        bindings: &OrmBindings,
+        collector: &mut SqlCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+        let (Some(function), Some(args)) = (
+            node.child_by_field_name("function"),
+            node.child_by_field_name("arguments"),
+        ) else {
+            return;
+        };
+        let first_arg = if args.kind() == "template_string" {
+            Some(args)
+        } else {
+            args.named_child(0)
+        };
+        let first_string = first_arg
+            .filter(|a| matches!(a.kind(), "string" | "template_string"))
+            .map(|a| unquote_js(text(a)));
+
+        // knex('users').where(...).update(...)
+        if function.kind() == "identifier" {
+            if bindings.knex.contains(text(function)) {
+                if let Some(table) = first_string {
+                    let operation = knex_chain_operation(node, content);
+                    collector.add_access(&table, operation, "knex", line);
+                }
+            // sql`SELECT ...` (postgres.js)
+            } else if text(function) == "sql" && args.kind() == "template_string" {
+                let sql = unquote_js(text(args));
+                if looks_like_sql(&sql) {
+                    collector.add_statement(&sql, "postgres.sql", line);
+                }
+            }
+            return;
+        }
+
+        let (Some(object), Some(method)) = (
+            function.child_by_field_name("object"),
+            function.child_by_field_name("property").map(text),
+        ) else {
+            return;
+        };
+        let receiver = text(object);
+        let receiver_name = receiver.strip_prefix("this.").unwrap_or(receiver);
+
+        // prisma.orderItem.findMany(...)
+        if object.kind() == "member_expression"
+            && object
+                .child_by_field_name("object")
+                .is_some_and(|client| text(client).ends_with("prisma"))
+        {
+            let accessor = object
+                .child_by_field_name("property")
+                .map(text)
+                .unwrap_or("");
+            if !accessor.starts_with('$') {
+                if let Some(operation) = orm_operation("prisma", method) {
+                    collector.add_access(
+                        &pascal_case(accessor),
+                        operation,
+                        &format!("prisma.{}", method),
+                        line,
+                    );
+                }
+                return;
+            }
+        }
+
+        // users.find(), this.users.save(), getRepository(User).delete()
+        let repository = bindings
+            .repositories
+            .get(receiver_name)
+            .cloned()
+            .or_else(|| {
+                (object.kind() == "call_expression" && receiver.contains("getRepository("))
+                    .then(|| {
+                        object
+                            .child_by_field_name("arguments")
+                            .and_then(|a| a.named_child(0))
+                            .map(|entity| (text(entity).to_string(), "typeorm"))
+                    })
+                    .flatten()
+            });
+        // manager.find(User, ...)
+        let managed_entity = (receiver.to_ascii_lowercase().ends_with("manager"))
+            .then(|| first_arg.filter(|a| a.kind() == "identifier").map(text))
+            .flatten()
+            .map(|entity| (entity.to_string(), "typeorm"));
+        if let Some((class, orm)) = repository.or(managed_entity) {
+            if let Some(operation) = orm_operation(orm, method) {
+                let table = bindings
+                    .models
+                    .get(&class)
+                    .map(|m| m.table.clone())
+                    .unwrap_or_else(|| match orm {
+                        "mongoose" => pluralize(&class).to_lowercase(),
+                        _ => snake_case(&class),
+                    });
+                collector.add_access(&table, operation, &format!("{}.{}", orm, method), line);
+                return;
+            }
+        }
+
+        // User.findAll() (Sequelize), User.find() (Mongoose)
+        if let Some(model) = bindings.models.get(receiver) {
+            if let Some(operation) = orm_operation(model.orm, method) {
+                collector.add_access(
+                    &model.table,
+                    operation,
+                    &format!("{}.{}", model.orm, method),
+                    line,
+                );
+            }
+            return;
+        }
+
+        let chain_root = member_chain_root(function, content);
+        if chain_root.is_some_and(|root| bindings.knex.contains(root)) {
+            match (method, first_string.clone()) {
+                // knex.select('id').from('users'), knex.insert(row).into('users')
+                ("from" | "into" | "table", Some(table)) => {
+                    let operation = match knex_chain_operation(node, content) {
+                        DatabaseOperation::Read if method == "into" => DatabaseOperation::Write,
+                        operation => operation,
+                    };
+                    collector.add_access(&table, operation, "knex", line);
+                }
+                // knex.schema.createTable('users', ...)
+                ("createTable" | "alterTable", Some(table)) => {
+                    collector.add_access(&table, DatabaseOperation::Unknown, "knex.schema", line);
+                }
+                _ => {}
+            }
+        }
+
+        // pool.query('SELECT ...'), prisma.$queryRaw`SELECT ...`
+        if JS_SQL_METHODS.contains(&method) {
+            if let Some(sql) = first_string.filter(|sql| looks_like_sql(sql)) {
+                let driver = if receiver.ends_with("prisma") {
+                    "prisma"
+                } else {
+                    bindings.libraries.iter().next().copied().unwrap_or("sql")
+                };
+                collector.add_statement(&sql, &format!("{}.{}", driver, method), line);
+            }
+        }
+    }
+
+    /// Detect message broker usage: kafkajs, amqplib, Redis streams and
+    /// BullMQ. See [`super::messaging`].
+    fn detect_messaging(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let brokers = JsBrokers {
+            kafka: content.contains("kafkajs"),
+            amqp: content.contains("amqplib") || content.contains("amqp-connection-manager"),
+            redis: content.contains("redis"),
+            bullmq: content.contains("bullmq"),
+        };
+        if !(brokers.kafka || brokers.amqp || brokers.redis || brokers.bullmq) {
+            return Vec::new();
+        }
+
+        let mut collector = MessagingCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_messaging(tree.root_node(), content, &brokers, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Walk the AST looking for broker client calls and BullMQ queues.
+    fn walk_for_messaging(
+        &self,
+        node: Node,
+        content: &str,
+        brokers: &JsBrokers,
+        collector: &mut MessagingCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+        let arguments: Vec<Node> = node
+            .child_by_field_name("arguments")
+            .map(|args| {
+                let mut cursor = args.walk();
+                args.named_children(&mut cursor).collect()
+            })
+            .unwrap_or_default();
+        let string = |index: usize| {
+            arguments
+                .get(index)
+                .and_then(|a| literal_js_string(*a, content))
+        };
+
+        match node.kind() {
+            // new Queue('emails'), new Worker('emails', processor)
+            "new_expression" if brokers.bullmq => {
+                let constructor = node.child_by_field_name("constructor").map(text);
+                let operation = match constructor {
+                    Some("Queue") => Some(QueueOperationType::Publish),
+                    Some("Worker") => Some(QueueOperationType::Subscribe),
+                    _ => None,
+                };
+                if let (Some(operation), Some(queue)) = (operation, string(0)) {
+                    collector.add(BULLMQ, &queue, operation, line);
+                }
+            }
+            "call_expression" => {
+                let method = node
+                    .child_by_field_name("function")
+                    .filter(|f| f.kind() == "member_expression")
+                    .and_then(|f| f.child_by_field_name("property"))
+                    .map(text)
+                    .unwrap_or("");
+                let options = arguments.first().filter(|a| a.kind() == "object");
+
+                match method {
+                    // producer.send({ topic: 'orders', messages })
+                    "send" if brokers.kafka => {
+                        if let Some(topic) =
+                            options.and_then(|o| literal_js_prop(*o, "topic", content))
+                        {
+                            collector.add(KAFKA, &topic, QueueOperationType::Publish, line);
+                        }
+                    }
+                    // consumer.subscribe({ topic: 'orders' }) / { topics: ['a', 'b'] }
+                    "subscribe" if brokers.kafka => {
+                        if let Some(options) = options {
+                            let topics = literal_js_prop(*options, "topic", content)
+                                .into_iter()
+                                .chain(
+                                    js_prop(*options, "topics", content)
+                                        .filter(|t| t.kind() == "array")
+                                        .map(|t| {
+                                            let mut cursor = t.walk();
+                                            t.named_children(&mut cursor)
+                                                .filter_map(|i| literal_js_string(i, content))
+                                                .collect::<Vec<_>>()
+                                        })
+                                        .unwrap_or_default(),
+                                );
+                            for topic in topics {
+                                collector.add(KAFKA, &topic, QueueOperationType::Subscribe, line);
+                            }
+                        }
+                    }
+                    // channel.publish('events', 'user.created', body)
+                    "publish" if brokers.amqp => {
+                        if let Some(exchange) = string(0) {
+                            collector.add_rabbitmq_publish(&exchange, string(1).as_deref(), line);
+                        }
+                    }
+                    // channel.sendToQueue('emails', body)
+                    "sendToQueue" if brokers.amqp => {
+                        if let Some(queue) = string(0) {
+                            collector.add(RABBITMQ, &queue, QueueOperationType::Publish, line);
+                        }
+                    }
+                    // channel.consume('emails', handler)
+                    "consume" if brokers.amqp => {
+                        if let Some(queue) = string(0) {
+                            collector.add(RABBITMQ, &queue, QueueOperationType::Subscribe, line);
+                        }
+                    }
+                    // channel.bindQueue('audit', 'events', '#')
+                    "bindQueue" if brokers.amqp => {
+                        if let Some(exchange) = string(1) {
+                            collector.add(RABBITMQ, &exchange, QueueOperationType::Subscribe, line);
+                        }
+                    }
+                    _ if brokers.redis => match redis_stream_operation(method) {
+                        // redis.xadd('orders', '*', ...) / client.xAdd('orders', '*', fields)
+                        Some(QueueOperationType::Publish) => {
+                            if let Some(stream) = string(0) {
+                                collector.add(
+                                    REDIS_STREAM,
+                                    &stream,
+                                    QueueOperationType::Publish,
+                                    line,
+                                );
+                            }
+                        }
+                        Some(operation) => {
+                            for stream in redis_read_streams(&arguments, content) {
+                                collector.add(REDIS_STREAM, &stream, operation, line);
+                            }
+                        }
+                        None => {}
+                    },
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_messaging(child, content, brokers, collector);
+            }
+        }
+    }
+
+    /// Detect HTTP routes declared with Express, Fastify or NestJS.
+    /// See [`super::routes`].
+    fn detect_routes(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let framework = if content.contains("fastify") {
+            Some("fastify")
+        } else if content.contains("express") {
+            Some("express")
+        } else {
+            None
+        };
+        let nestjs = content.contains("@nestjs/common");
+        if framework.is_none() && !nestjs {
+            return Vec::new();
+        }
+
+        let root = tree.root_node();
+        // Route modules often receive the app or router as a parameter
+        let mut routers: HashSet<String> = ["app", "router", "server", "fastify"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        self.collect_routers(root, content, &mut routers);
+
+        let mut collector = RouteCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_routes(root, content, framework, nestjs, &routers, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Collect variables holding an Express app or router or a Fastify
+    /// instance.
+    fn collect_routers(&self, node: Node, content: &str, routers: &mut HashSet<String>) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        if node.kind() == "variable_declarator" {
+            let name = node.child_by_field_name("name").map(text);
+            let function = node
+                .child_by_field_name("value")
+                .filter(|v| v.kind() == "call_expression")
+                .and_then(|v| v.child_by_field_name("function"));
+            if let (Some(name), Some(function)) = (name, function) {
+                // express(), express.Router(), Router(), Fastify(), require('fastify')()
+                let is_router = matches!(
+                    text(function),
+                    "express" | "express.Router" | "Router" | "fastify" | "Fastify"
+                ) || (function.kind() == "call_expression"
+                    && function
+                        .child_by_field_name("arguments")
+                        .and_then(|a| a.named_child(0))
+                        .and_then(|a| literal_js_string(a, content))
+                        .is_some_and(|module| module == "express" || module == "fastify"));
+                if is_router {
+                    routers.insert(name.to_string());
+                }
+            }
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_routers(child, content, routers);
+            }
+        }
+    }
+
+    /// Walk the AST looking for route registrations and NestJS controllers.
+    fn walk_for_routes(
+        &self,
+        node: Node,
+        content: &str,
+        framework: Option<&str>,
+        nestjs: bool,
+        routers: &HashSet<String>,
+        collector: &mut RouteCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            "call_expression" if framework.is_some() => {
+                let framework = framework.unwrap_or_default();
+                let function = node
+                    .child_by_field_name("function")
+                    .filter(|f| f.kind() == "member_expression");
+                let object = function.and_then(|f| f.child_by_field_name("object"));
+                let method = function
+                    .and_then(|f| f.child_by_field_name("property"))
+                    .map(text)
+                    .unwrap_or("");
+                let arguments: Vec<Node> = node
+                    .child_by_field_name("arguments")
+                    .map(|args| {
+                        let mut cursor = args.walk();
+                        args.named_children(&mut cursor).collect()
+                    })
+                    .unwrap_or_default();
+                let is_router = |n: Node| {
+                    let name = text(n);
+                    routers.contains(name.strip_prefix("this.").unwrap_or(name))
+                };
+                let http_method = match method {
+                    "all" => Some("ANY"),
+                    method if HTTP_METHODS.contains(&method) => Some(method),
+                    _ => None,
+                };
+
+                match (object, http_method) {
+                    // app.get('/users/:id', handler); a single argument is
+                    // Express's settings getter (`app.get('env')`)
+                    (Some(object), Some(http_method)) if is_router(object) => {
+                        let route = arguments
+                            .first()
+                            .and_then(|a| literal_js_string(*a, content));
+                        if let Some(route) = route.filter(|r| r.starts_with('/')) {
+                            if arguments.len() > 1 {
+                                collector.add(http_method, &route, framework, line);
+                            }
+                        }
+                    }
+                    // router.route('/users/:id').get(show).put(update)
+                    (Some(object), Some(http_method)) if object.kind() == "call_expression" => {
+                        let mut chain = object;
+                        let route = loop {
+                            let Some(inner) = chain
+                                .child_by_field_name("function")
+                                .filter(|f| f.kind() == "member_expression")
+                            else {
+                                break None;
+                            };
+                            let (Some(receiver), Some(property)) = (
+                                inner.child_by_field_name("object"),
+                                inner.child_by_field_name("property"),
+                            ) else {
+                                break None;
+                            };
+                            if text(property) == "route" && is_router(receiver) {
+                                break chain
+                                    .child_by_field_name("arguments")
+                                    .and_then(|a| a.named_child(0))
+                                    .and_then(|a| literal_js_string(a, content));
+                            }
+                            if receiver.kind() != "call_expression" {
+                                break None;
+                            }
+                            chain = receiver;
+                        };
+                        if let Some(route) = route {
+                            collector.add(http_method, &route, framework, line);
+                        }
+                    }
+                    // fastify.route({ method: ['GET', 'HEAD'], url: '/users/:id', handler })
+                    (Some(object), None) if method == "route" && is_router(object) => {
+                        if let Some(options) = arguments.first().filter(|a| a.kind() == "object") {
+                            let route = literal_js_prop(*options, "url", content)
+                                .or_else(|| literal_js_prop(*options, "path", content));
+                            let methods: Vec<String> = js_prop(*options, "method", content)
+                                .map(|m| match m.kind() {
+                                    "array" => {
+                                        let mut cursor = m.walk();
+                                        m.named_children(&mut cursor)
+                                            .filter_map(|i| literal_js_string(i, content))
+                                            .collect()
+                                    }
+                                    _ => literal_js_string(m, content).into_iter().collect(),
+                                })
+                                .unwrap_or_default();
+                            if let Some(route) = route {
+                                for method in methods {
+                                    collector.add(&method, &route, framework, line);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // @Controller('users') class UsersController { @Get(':id') find() {} }
+            "class_declaration" if nestjs => {
+                if let Some(controller) = class_decorator(node, "Controller", content) {
+                    let argument = controller
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0));
+                    let prefix = argument
+                        .and_then(|arg| match arg.kind() {
+                            "object" => literal_js_prop(arg, "path", content),
+                            _ => literal_js_string(arg, content),
+                        })
+                        .unwrap_or_default();
+                    if let Some(body) = node.child_by_field_name("body") {
+                        self.collect_nest_routes(body, &prefix, content, collector);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_routes(child, content, framework, nestjs, routers, collector);
+            }
+        }
+    }
+
+    /// Record the routes of a NestJS controller's handler methods.
+    fn collect_nest_routes(
+        &self,
+        body: Node,
+        prefix: &str,
+        content: &str,
+        collector: &mut RouteCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        // Method decorators precede the method in the class body
+        let mut decorators = Vec::new();
+        let mut cursor = body.walk();
+        for member in body.named_children(&mut cursor) {
+            if member.kind() == "decorator" {
+                decorators.push(member);
+                continue;
+            }
+            if member.kind() == "method_definition" {
+                let mut inner = member.walk();
+                decorators.extend(
+                    member
+                        .children(&mut inner)
+                        .filter(|c| c.kind() == "decorator"),
+                );
+                for expression in decorators.iter().filter_map(|d| d.named_child(0)) {
+                    let callee = expression
+                        .child_by_field_name("function")
+                        .unwrap_or(expression);
+                    let method = match text(callee) {
+                        "Get" | "Post" | "Put" | "Patch" | "Delete" | "Head" | "Options" => {
+                            text(callee)
+                        }
+                        "All" => "ANY",
+                        _ => continue,
+                    };
+                    let route = expression
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .and_then(|arg| literal_js_string(arg, content))
+                        .unwrap_or_default();
+                    let line = expression.start_position().row as u32 + 1;
+                    collector.add(method, &join_route(prefix, &route), "nestjs", line);
+                }
+            }
+            decorators.clear();
+        }
+    }
+}
+
+/// The expression of a class decorator (`@Entity('users')`) by name.
+///
+/// Decorators belong to the export statement when the class is exported.
+fn class_decorator<'t>(class: Node<'t>, name: &str, content: &str) -> Option<Node<'t>> {
+    let holders = [
+        Some(class),
+        class.parent().filter(|p| p.kind() == "export_statement"),
+    ];
+    holders.into_iter().flatten().find_map(|holder| {
+        let mut cursor = holder.walk();
+        holder
+            .children(&mut cursor)
+            .filter(|c| c.kind() == "decorator")
+            .filter_map(|decorator| decorator.named_child(0))
+            .find(|expression| {
+                let callee = expression
+                    .child_by_field_name("function")
+                    .unwrap_or(*expression);
+                callee.utf8_text(content.as_bytes()).ok() == Some(name)
+            })
+    })
+}
+
+/// Message brokers whose client libraries a JavaScript file mentions.
+struct JsBrokers {
+    kafka: bool,
+    amqp: bool,
+    redis: bool,
+    bullmq: bool,
+}
+
+/// Value of a string literal (or a template without substitutions).
+fn literal_js_string(node: Node, content: &str) -> Option<String> {
+    let raw = node.utf8_text(content.as_bytes()).ok()?;
+    let literal =
+        node.kind() == "string" || (node.kind() == "template_string" && !raw.contains("${"));
+    literal.then(|| unquote_js(raw))
+}
+
+/// The value of a property in an object literal.
+fn js_prop<'t>(object: Node<'t>, key: &str, content: &str) -> Option<Node<'t>> {
+    let mut cursor = object.walk();
+    object
+        .named_children(&mut cursor)
+        .filter(|p| p.kind() == "pair")
+        .find(|pair| {
+            pair.child_by_field_name("key")
+                .and_then(|k| k.utf8_text(content.as_bytes()).ok())
+                .is_some_and(|k| unquote_js(k) == key)
+        })
+        .and_then(|pair| pair.child_by_field_name("value"))
+}
+
+/// Record the string constants and environment variables bound to
+/// identifiers (and `this.` fields) in a file. See [`super::dataflow`].
+fn collect_js_bindings(root: Node, content: &str) -> Bindings {
+    fn walk(node: Node, content: &str, bindings: &mut Bindings) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("").to_string();
+        let declaration = match node.kind() {
+            // const TABLE = ..., const { USERS_TABLE } = process.env
+            "variable_declarator" => node
+                .child_by_field_name("name")
+                .zip(node.child_by_field_name("value")),
+            // TABLE = ..., this.table = ...
+            "assignment_expression" => node
+                .child_by_field_name("left")
+                .zip(node.child_by_field_name("right")),
+            // class fields: private table = ...
+            "public_field_definition" | "field_definition" => node
+                .child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("property"))
+                .zip(node.child_by_field_name("value")),
+            _ => None,
+        };
+        if let Some((name, value)) = declaration {
+            match name.kind() {
+                "identifier" => {
+                    if let Some(bound) = js_bound_value(value, content, bindings) {
+                        bindings.bind(&text(name), bound);
+                    }
+                }
+                "member_expression" if text(name).starts_with("this.") => {
+                    if let Some(bound) = js_bound_value(value, content, bindings) {
+                        bindings.bind(&text(name), bound);
+                    }
+                }
+                "property_identifier" | "private_property_identifier" => {
+                    if let Some(bound) = js_bound_value(value, content, bindings) {
+                        bindings.bind(&format!("this.{}", text(name)), bound);
+                    }
+                }
+                "object_pattern" if is_js_env_object(value, content) => {
+                    let mut cursor = name.walk();
+                    for property in name.named_children(&mut cursor) {
+                        let (variable, local, default) = match property.kind() {
+                            "shorthand_property_identifier_pattern" => {
+                                (text(property), text(property), None)
+                            }
+                            // { USERS_TABLE: table }
+                            "pair_pattern" => match (
+                                property.child_by_field_name("key"),
+                                property.child_by_field_name("value"),
+                            ) {
+                                (Some(key), Some(local)) if local.kind() == "identifier" => {
+                                    (unquote_js(&text(key)), text(local), None)
+                                }
+                                _ => continue,
+                            },
+                            // { USERS_TABLE = 'users' }
+                            "object_assignment_pattern" => match (
+                                property.child_by_field_name("left"),
+                                property.child_by_field_name("right"),
+                            ) {
+                                (Some(left), Some(right)) => {
+                                    (text(left), text(left), literal_js_string(right, content))
+                                }
+                                _ => continue,
+                            },
+                            _ => continue,
+                        };
+                        bindings.bind(&local, BoundValue::Env { variable, default });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            walk(child, content, bindings);
+        }
+    }
+
+    let mut bindings = Bindings::new();
+    walk(root, content, &mut bindings);
+    bindings
+}
+
+/// The value an expression holds, if it is a string literal, an environment
+/// variable (with a `||`/`??` default) or an identifier bound to either.
+fn js_bound_value(node: Node, content: &str, bindings: &Bindings) -> Option<BoundValue> {
+    let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+    match node.kind() {
+        "string" | "template_string" => literal_js_string(node, content).map(BoundValue::Literal),
+        "identifier" => bindings.get(text(node)).cloned(),
+        "member_expression" | "subscript_expression" => {
+            let object = node.child_by_field_name("object")?;
+            if !is_js_env_object(object, content) {
+                return bindings.get(text(node)).cloned();
+            }
+            let variable = match node.kind() {
+                "member_expression" => text(node.child_by_field_name("property")?).to_string(),
+                _ => literal_js_string(node.child_by_field_name("index")?, content)?,
+            };
+            Some(BoundValue::Env {
+                variable,
+                default: None,
+            })
+        }
+        // process.env.USERS_TABLE || 'users'
+        "binary_expression" => {
+            let operator = text(node.child_by_field_name("operator")?);
+            if operator != "||" && operator != "??" {
+                return None;
+            }
+            let left = js_bound_value(node.child_by_field_name("left")?, content, bindings)?;
+            let default = node
+                .child_by_field_name("right")
+                .and_then(|right| literal_js_string(right, content));
+            match (left, default) {
+                (
+                    BoundValue::Env {
+                        variable,
+                        default: None,
+                    },
+                    Some(default),
+                ) => Some(BoundValue::Env {
+                    variable,
+                    default: Some(default),
+                }),
+                (left, _) => Some(left),
+            }
+        }
+        // (expr), expr as string, expr!
+        "parenthesized_expression"
+        | "as_expression"
+        | "non_null_expression"
+        | "satisfies_expression" => js_bound_value(node.named_child(0)?, content, bindings),
+        _ => None,
+    }
+}
+
+/// Whether an expression is `process.env` (or Vite's `import.meta.env`).
+fn is_js_env_object(node: Node, content: &str) -> bool {
+    matches!(
+        node.utf8_text(content.as_bytes()).unwrap_or(""),
+        "process.env" | "import.meta.env"
+    )
+}
+
+/// The string literal value of a property in an object literal.
+fn literal_js_prop(object: Node, key: &str, content: &str) -> Option<String> {
+    js_prop(object, key, content).and_then(|value| literal_js_string(value, content))
+}
+
+/// Streams read by `XREAD`/`XREADGROUP`: the argument after `'STREAMS'`
+/// (ioredis), or the `key` of each stream object (node-redis).
+fn redis_read_streams(arguments: &[Node], content: &str) -> Vec<String> {
+    let mut streams = Vec::new();
+    let mut after_streams = false;
+    for argument in arguments {
+        match argument.kind() {
+            "object" => streams.extend(literal_js_prop(*argument, "key", content)),
+            "array" => {
+                let mut cursor = argument.walk();
+                streams.extend(
+                    argument
+                        .named_children(&mut cursor)
+                        .filter(|item| item.kind() == "object")
+                        .filter_map(|item| literal_js_prop(item, "key", content)),
+                );
+            }
+            _ => {
+                let Some(value) = literal_js_string(*argument, content) else {
+                    continue;
+                };
+                if after_streams {
+                    streams.push(value);
+                    after_streams = false;
+                } else {
+                    after_streams = value.eq_ignore_ascii_case("streams");
+                }
+            }
+        }
+    }
+    streams
+}
+
+/// Driver and ORM methods that take a SQL string.
+const JS_SQL_METHODS: &[&str] = &[
+    "query",
+    "execute",
+    "raw",
+    "none",
+    "one",
+    "many",
+    "any",
+    "oneOrNone",
+    "manyOrNone",
+    "$queryRaw",
+    "$executeRaw",
+    "$queryRawUnsafe",
+    "$executeRawUnsafe",
+];
+
+/// Database libraries, by the name of the module imported.
+fn orm_library(module: &str) -> Option<&'static str> {
+    Some(match module {
+        "@prisma/client" => "prisma",
+        "typeorm" | "@nestjs/typeorm" => "typeorm",
+        "sequelize" | "sequelize-typescript" => "sequelize",
+        "knex" => "knex",
+        "mongoose" | "@nestjs/mongoose" => "mongoose",
+        "pg" | "pg-pool" | "pg-promise" => "pg",
+        "postgres" => "postgres",
+        "mysql" | "mysql2" | "mysql2/promise" => "mysql2",
+        "better-sqlite3" | "sqlite3" => "sqlite3",
+        _ => return None,
+    })
+}
+
+/// A model class (or variable) and its table or collection.
+struct OrmModel {
+    table: String,
+    orm: &'static str,
+    line: u32,
+}
+
+/// Database libraries and bindings found in a JavaScript file.
+#[derive(Default)]
+struct OrmBindings {
+    /// Database libraries imported, in a stable order
+    libraries: std::collections::BTreeSet<&'static str>,
+    /// Models declared in the file, by class or variable name
+    models: HashMap<String, OrmModel>,
+    /// Repository variables and the (model class, ORM) they access
+    repositories: HashMap<String, (String, &'static str)>,
+    /// Variables holding a Knex instance
+    knex: HashSet<String>,
+}
+
+/// How an ORM method accesses its table.
+fn orm_operation(orm: &str, method: &str) -> Option<DatabaseOperation> {
+    use DatabaseOperation::{Read, ReadWrite, Write};
+    Some(match (orm, method) {
+        (
+            "prisma",
+            "findMany" | "findFirst" | "findUnique" | "findFirstOrThrow" | "findUniqueOrThrow"
+            | "count" | "aggregate" | "groupBy",
+        ) => Read,
+        ("prisma", "create" | "createMany" | "delete" | "deleteMany") => Write,
+        ("prisma", "update" | "updateMany" | "upsert") => ReadWrite,
+        (
+            "typeorm",
+            "find" | "findOne" | "findBy" | "findOneBy" | "findAndCount" | "findOneOrFail"
+            | "findOneByOrFail" | "count" | "countBy" | "exists" | "existsBy"
+            | "createQueryBuilder" | "sum" | "average" | "minimum" | "maximum",
+        ) => Read,
+        ("typeorm", "insert" | "delete" | "remove" | "softDelete" | "softRemove" | "clear") => {
+            Write
+        }
+        (
+            "typeorm",
+            "save" | "update" | "upsert" | "increment" | "decrement" | "restore" | "recover",
+        ) => ReadWrite,
+        (
+            "sequelize",
+            "findAll" | "findOne" | "findByPk" | "findAndCountAll" | "count" | "max" | "min"
+            | "sum",
+        ) => Read,
+        ("sequelize", "create" | "bulkCreate" | "destroy" | "truncate") => Write,
+        (
+            "sequelize",
+            "update" | "upsert" | "findOrCreate" | "increment" | "decrement" | "restore",
+        ) => ReadWrite,
+        (
+            "mongoose",
+            "find"
+            | "findOne"
+            | "findById"
+            | "countDocuments"
+            | "estimatedDocumentCount"
+            | "aggregate"
+            | "exists"
+            | "distinct",
+        ) => Read,
+        (
+            "mongoose",
+            "create" | "insertMany" | "deleteOne" | "deleteMany" | "findByIdAndDelete"
+            | "findOneAndDelete",
+        ) => Write,
+        (
+            "mongoose",
+            "updateOne" | "updateMany" | "findOneAndUpdate" | "findByIdAndUpdate" | "replaceOne"
+            | "findOneAndReplace" | "bulkWrite",
+        ) => ReadWrite,
+        _ => return None,
+    })
+}
+
+/// The table of a Sequelize model: `tableName`, the model name with
+/// `freezeTableName`, or the pluralized model name.
+fn sequelize_table(model: &str, options: &HashMap<String, String>) -> String {
+    if let Some(table) = options.get("tablename") {
+        return table.clone();
+    }
+    if options.get("freezetablename").map(String::as_str) == Some("true") {
+        return model.to_string();
+    }
+    pluralize(model)
+}
+
+/// The identifier a member/call chain starts from: `knex.select('id').from`
+/// → `knex`.
+fn member_chain_root<'c>(mut node: Node, content: &'c str) -> Option<&'c str> {
+    loop {
+        node = match node.kind() {
+            "member_expression" => node.child_by_field_name("object")?,
+            "call_expression" => node.child_by_field_name("function")?,
+            "identifier" => return node.utf8_text(content.as_bytes()).ok(),
+            _ => return None,
+        };
+    }
+}
+
+/// How a Knex query builder chain accesses its table, from the methods
+/// called on it: `knex('users').where(...).update(...)` is a read-write.
+fn knex_chain_operation(call: Node, content: &str) -> DatabaseOperation {
+    // Climb to the outermost call of the chain
+    let mut outer = call;
+    while let Some(member) = outer.parent().filter(|p| p.kind() == "member_expression") {
+        match member.parent().filter(|p| p.kind() == "call_expression") {
+            Some(next) => outer = next,
+            None => break,
+        }
+    }
+
+    let mut methods = Vec::new();
+    let mut node = outer;
+    loop {
+        node = match node.kind() {
+            "call_expression" => match node.child_by_field_name("function") {
+                Some(function) => function,
+                None => break,
+            },
+            "member_expression" => {
+                if let Some(property) = node.child_by_field_name("property") {
+                    methods.push(property.utf8_text(content.as_bytes()).unwrap_or(""));
+                }
+                match node.child_by_field_name("object") {
+                    Some(object) => object,
+                    None => break,
+                }
+            }
+            _ => break,
+        };
+    }
+
+    if methods.iter().any(|m| {
+        matches!(
+            *m,
+            "update" | "increment" | "decrement" | "merge" | "upsert"
+        )
+    }) {
+        DatabaseOperation::ReadWrite
+    } else if methods
+        .iter()
+        .any(|m| matches!(*m, "insert" | "del" | "delete" | "truncate"))
+    {
+        DatabaseOperation::Write
+    } else {
+        DatabaseOperation::Read
+    }
+}
+
+/// Tables declared in a Prisma schema: one per `model`, with the connection
+/// from the datasource `url = env("DATABASE_URL")`.
+fn parse_prisma_schema(content: &str, path: &Path) -> Vec<Discovery> {
+    let mut db_type = "sql";
+    let mut connection = None;
+    let mut models = Vec::new();
+    let mut in_datasource = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index as u32 + 1;
+        let line = line.trim();
+        if line.starts_with("datasource ") {
+            in_datasource = true;
+        } else if line.starts_with('}') {
+            in_datasource = false;
+        } else if let Some(rest) = line.strip_prefix("model ") {
+            if let Some(name) = rest.split_whitespace().next() {
+                models.push((name.to_string(), line_number));
+            }
+        } else if in_datasource {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "provider" => {
+                    db_type = match value.trim_matches('"') {
+                        "postgresql" | "postgres" => "postgresql",
+                        "cockroachdb" => "cockroachdb",
+                        "mysql" => "mysql",
+                        "sqlite" => "sqlite",
+                        "sqlserver" => "sqlserver",
+                        "mongodb" => "mongodb",
+                        _ => "sql",
+                    };
+                }
+                "url" => {
+                    connection = value
+                        .strip_prefix("env(")
+                        .and_then(|v| v.strip_suffix(')'))
+                        .map(|v| (v.trim_matches('"').to_string(), line_number));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut collector = SqlCollector::new(path.to_string_lossy().to_string(), db_type);
+    if let Some((name, line)) = connection {
+        collector.add_connection(&name, "prisma.datasource", line);
+    }
+    for (model, line) in models {
+        collector.add_access(&model, DatabaseOperation::Unknown, "prisma.schema", line);
+    }
+    collector.into_discoveries()
+}
+
+/// `orderItem` → `OrderItem`, the Prisma model behind a client accessor.
+fn pascal_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Local names bound to generated gRPC code in a JavaScript file.
+#[derive(Default)]
+struct GrpcBindings {
+    /// Imports from generated modules (`users_grpc_pb`, `users_grpc_web_pb`)
+    generated: HashSet<String>,
+    /// Packages loaded at runtime (`grpc.loadPackageDefinition(...)`)
+    loaded: HashSet<String>,
+}
+
+/// Check if a module path refers to protoc-generated gRPC code
+/// (`users_grpc_pb`, `users_grpc_web_pb`, `users.grpc-client`).
+fn is_generated_grpc_module(module: &str) -> bool {
+    let file = module.rsplit('/').next().unwrap_or(module);
+    file.contains("_grpc_") || file.contains(".grpc")
+}
+
+/// Strip JavaScript string quotes.
+fn unquote_js(text: &str) -> String {
+    text.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+impl Parser for JavaScriptParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "javascript"
+    }
+
+    fn version(&self) -> u32 {
+        1u32.wrapping_add(self.rules.fingerprint())
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["js", "jsx", "ts", "tsx", "mjs", "cjs", "prisma"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        if path.extension().and_then(|e| e.to_str()) == Some("prisma") {
+            return Ok(parse_prisma_schema(content, path));
+        }
+
+        // Create a new parser instance for thread safety (tree-sitter parsers are not thread-safe)
+        let mut parser = TSParser::new();
+        parser
+            .set_language(self.language_for(path))
+            .map_err(|e| ParserError::TreeSitterError(format!("Failed to set language: {}", e)))?;
+
+        // Parse the file
+        let tree = parser
+            .parse(content, None)
+            .ok_or_else(|| ParserError::ParseFailed {
+                path: path.to_string_lossy().to_string(),
+            })?;
+
+        let mut discoveries = Vec::new();
+
+        // Run all detectors
+        discoveries.extend(self.detect_imports(&tree, content, path));
+        discoveries.extend(self.detect_aws_sdk(&tree, content, path));
+        discoveries.extend(self.detect_dynamodb_operations(&tree, content, path));
+        discoveries.extend(self.detect_aws_sdk_v3_commands(&tree, content, path));
+        discoveries.extend(self.detect_http_calls(&tree, content, path));
+        discoveries.extend(self.detect_cdk_constructs(&tree, content, path));
+        discoveries.extend(self.detect_grpc_usage(&tree, content, path));
+        discoveries.extend(self.detect_database_access(&tree, content, path));
+        discoveries.extend(self.detect_messaging(&tree, content, path));
+        discoveries.extend(self.detect_routes(&tree, content, path));
+
+        if !self.rules.is_empty() {
+            let bindings = collect_js_bindings(tree.root_node(), content);
+            discoveries.extend(self.rules.apply(&tree, content, path, &|node| {
+                js_bound_value(node, content, &bindings).map(BoundValue::into_name)
+            }));
+        }
+
+        Ok(discoveries)
+    }
+}
+
+impl Default for JavaScriptParser {
+    fn default() -> Self {
+        Self::new().expect("Failed to create default JavaScriptParser")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::RelationshipType;
+
+    fn create_parser() -> JavaScriptParser {
+        JavaScriptParser::new().expect("Failed to create parser")
+    }
+
+    #[test]
+    fn test_detect_es6_imports() {
+        let parser = create_parser();
+        let content = r#"
+import express from 'express';
+import { DynamoDB } from '@aws-sdk/client-dynamodb';
+import axios from 'axios';
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Import(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            imports.len() >= 3,
+            "Expected at least 3 imports, got {}",
+            imports.len()
+        );
+        assert!(
+            imports.iter().any(|i| i.module == "express"),
+            "Should detect express import"
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.module == "@aws-sdk/client-dynamodb"),
+            "Should detect AWS SDK import"
+        );
+        assert!(
+            imports.iter().any(|i| i.module == "axios"),
+            "Should detect axios import"
+        );
+    }
+
+    #[test]
+    fn test_detect_commonjs_requires() {
+        let parser = create_parser();
+        let content = r#"
+const express = require('express');
+const AWS = require('aws-sdk');
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Import(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            imports.iter().any(|i| i.module == "express"),
+            "Should detect express require"
+        );
+        assert!(
+            imports.iter().any(|i| i.module == "aws-sdk"),
+            "Should detect aws-sdk require"
+        );
+    }
+
+    #[test]
+    fn test_detect_relative_imports() {
+        let parser = create_parser();
+        let content = r#"
+import { helper } from './utils/helper';
+import config from '../config';
+import data from 'data-package';
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Import(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        let relative: Vec<_> = imports.iter().filter(|i| i.is_relative).collect();
+        let non_relative: Vec<_> = imports.iter().filter(|i| !i.is_relative).collect();
+
+        assert_eq!(relative.len(), 2, "Should have 2 relative imports");
+        assert_eq!(non_relative.len(), 1, "Should have 1 non-relative import");
+    }
+
+    #[test]
+    fn test_detect_aws_sdk_v3_dynamodb() {
+        let parser = create_parser();
+        // AWS SDK v3 imports - tests that imports are correctly detected
+        // Note: Resource discoveries (DynamoDB, SQS) require actual method calls,
+        // not just imports, to avoid creating "unknown" nodes that can't be deduplicated.
+        let content = r#"
+import { DynamoDBClient } from '@aws-sdk/client-dynamodb';
+import { S3Client } from '@aws-sdk/client-s3';
+import { SQSClient } from '@aws-sdk/client-sqs';
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        // AWS SDK imports should be detected
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Import(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.module == "@aws-sdk/client-dynamodb"),
+            "Should detect DynamoDB client import"
+        );
+        assert!(
+            imports.iter().any(|i| i.module == "@aws-sdk/client-s3"),
+            "Should detect S3 client import"
+        );
+        assert!(
+            imports.iter().any(|i| i.module == "@aws-sdk/client-sqs"),
+            "Should detect SQS client import"
+        );
+
+        // S3 still creates a CloudResourceUsage from import (unlike DynamoDB/SQS which need method calls)
+        let cloud_resources: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::CloudResourceUsage(c) => Some(c),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            cloud_resources.iter().any(|c| c.resource_type == "s3"),
+            "Should detect S3 usage"
+        );
     }
 
     #[test]
-    fn test_detect_aws_sdk_v3_dynamodb() {
+    fn test_detect_aws_sdk_v2() {
+        let parser = create_parser();
+        let content = r#"
+const AWS = require('aws-sdk');
+const dynamodb = new AWS.DynamoDB();
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        // Should detect the aws-sdk import
+        let imports: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Import(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            imports.iter().any(|i| i.module == "aws-sdk"),
+            "Should detect aws-sdk import"
+        );
+    }
+
+    #[test]
+    fn test_detect_dynamodb_operations() {
+        let parser = create_parser();
+        // Use DynamoDB-like variable name so detection recognizes this as DynamoDB
+        let content = r#"
+const result = await dynamoClient.get({ TableName: 'users', Key: { id } });
+await dynamoClient.put({ TableName: 'users', Item: user });
+const items = await dynamoClient.query({ TableName: 'orders' });
+await dynamoClient.delete({ TableName: 'users', Key: { id } });
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let db_ops: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        assert!(
+            db_ops
+                .iter()
+                .any(|d| d.operation == DatabaseOperation::Read),
+            "Should detect read operation"
+        );
+        assert!(
+            db_ops
+                .iter()
+                .any(|d| d.operation == DatabaseOperation::Write),
+            "Should detect write operation"
+        );
+    }
+
+    #[test]
+    fn test_detect_dynamodb_table_name() {
+        let parser = create_parser();
+        // Use DynamoDB-like variable name so detection recognizes this as DynamoDB
+        let content = r#"
+await docClient.get({ TableName: 'users-table', Key: { id: '123' } });
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let db_ops: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!db_ops.is_empty(), "Should detect database access");
+        assert!(
+            db_ops
+                .iter()
+                .any(|d| d.table_name.as_deref() == Some("users-table")),
+            "Should extract table name"
+        );
+    }
+
+    #[test]
+    fn test_detect_fetch_calls() {
+        let parser = create_parser();
+        let content = r#"
+const response = await fetch('https://api.example.com/users');
+const data = await fetch('/api/data');
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let api_calls: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::ApiCall(a) => Some(a),
+                _ => None,
+            })
+            .collect();
+
+        assert!(api_calls.len() >= 2, "Should detect both fetch calls");
+        assert!(
+            api_calls.iter().any(|a| a.detection_method == "fetch"),
+            "Should identify fetch as detection method"
+        );
+        assert!(
+            api_calls
+                .iter()
+                .any(|a| a.target.contains("api.example.com")),
+            "Should extract URL"
+        );
+    }
+
+    #[test]
+    fn test_detect_axios_calls() {
+        let parser = create_parser();
+        let content = r#"
+const users = await axios.get('https://api.example.com/users');
+await axios.post('/api/users', { name: 'John' });
+await axios.delete('/api/users/123');
+"#;
+
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        let api_calls: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::ApiCall(a) => Some(a),
+                _ => None,
+            })
+            .collect();
+
+        assert!(api_calls.len() >= 3, "Should detect all axios calls");
+        assert!(
+            api_calls
+                .iter()
+                .any(|a| a.method == Some("GET".to_string())),
+            "Should detect GET method"
+        );
+        assert!(
+            api_calls
+                .iter()
+                .any(|a| a.method == Some("POST".to_string())),
+            "Should detect POST method"
+        );
+        assert!(
+            api_calls
+                .iter()
+                .any(|a| a.method == Some("DELETE".to_string())),
+            "Should detect DELETE method"
+        );
+    }
+
+    #[test]
+    fn test_package_json_parsing() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let package_json = r#"
+{
+  "name": "user-service",
+  "main": "dist/index.js",
+  "dependencies": {
+    "express": "^4.18.0",
+    "@aws-sdk/client-dynamodb": "^3.0.0"
+  }
+}
+"#;
+        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+
+        let parser = create_parser();
+        let service = parser.parse_package_json(dir.path()).unwrap();
+
+        assert_eq!(service.name, "user-service");
+        assert_eq!(service.framework, Some("express".to_string()));
+        assert_eq!(service.entry_point, "dist/index.js");
+        assert_eq!(service.language, "javascript");
+    }
+
+    #[test]
+    fn test_package_json_typescript_detection() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let package_json = r#"
+{
+  "name": "ts-service",
+  "main": "dist/index.js",
+  "devDependencies": {
+    "typescript": "^5.0.0"
+  },
+  "dependencies": {
+    "fastify": "^4.0.0"
+  }
+}
+"#;
+        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+
+        let parser = create_parser();
+        let service = parser.parse_package_json(dir.path()).unwrap();
+
+        assert_eq!(service.name, "ts-service");
+        assert_eq!(service.framework, Some("fastify".to_string()));
+        assert_eq!(service.language, "typescript");
+    }
+
+    #[test]
+    fn test_package_json_missing() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let parser = create_parser();
+        let service = parser.parse_package_json(dir.path());
+
+        assert!(
+            service.is_none(),
+            "Should return None for missing package.json"
+        );
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let parser = create_parser();
+        let discoveries = parser.parse_file(Path::new("test.js"), "").unwrap();
+
+        assert!(
+            discoveries.is_empty(),
+            "Empty file should produce no discoveries"
+        );
+    }
+
+    #[test]
+    fn test_file_with_comments_only() {
+        let parser = create_parser();
+        let content = r#"
+// This is a comment
+/* Multi-line
+   comment */
+"#;
+        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+
+        assert!(
+            discoveries.is_empty(),
+            "File with only comments should produce no discoveries"
+        );
+    }
+
+    #[test]
+    fn test_mixed_imports_and_code() {
         let parser = create_parser();
-        // AWS SDK v3 imports - tests that imports are correctly detected
-        // Note: Resource discoveries (DynamoDB, SQS) require actual method calls,
-        // not just imports, to avoid creating "unknown" nodes that can't be deduplicated.
+        // Use AWS SDK v3 command pattern for DynamoDB detection
+        // Note: Variable name like 'client' doesn't trigger DynamoDB detection
+        // to avoid false positives (e.g., axios.get()). Use explicit command classes instead.
         let content = r#"
-import { DynamoDBClient } from '@aws-sdk/client-dynamodb';
-import { S3Client } from '@aws-sdk/client-s3';
-import { SQSClient } from '@aws-sdk/client-sqs';
+import express from 'express';
+import { DynamoDBClient, GetItemCommand } from '@aws-sdk/client-dynamodb';
+
+const app = express();
+const dynamoClient = new DynamoDBClient({});
+
+app.get('/users/:id', async (req, res) => {
+    const command = new GetItemCommand({ TableName: 'users', Key: { id: { S: req.params.id } } });
+    const result = await dynamoClient.send(command);
+    res.json(result.Item);
+});
+
+const data = await fetch('https://api.example.com/data');
 "#;
 
         let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
 
-        // AWS SDK imports should be detected
+        // Should have imports
         let imports: Vec<_> = discoveries
             .iter()
-            .filter_map(|d| match d {
-                Discovery::Import(i) => Some(i),
-                _ => None,
-            })
+            .filter(|d| matches!(d, Discovery::Import(_)))
             .collect();
+        assert!(imports.len() >= 2, "Should have at least 2 imports");
 
-        assert!(
-            imports
-                .iter()
-                .any(|i| i.module == "@aws-sdk/client-dynamodb"),
-            "Should detect DynamoDB client import"
-        );
-        assert!(
-            imports.iter().any(|i| i.module == "@aws-sdk/client-s3"),
-            "Should detect S3 client import"
-        );
-        assert!(
-            imports.iter().any(|i| i.module == "@aws-sdk/client-sqs"),
-            "Should detect SQS client import"
-        );
+        // Should have database access
+        let db_accesses: Vec<_> = discoveries
+            .iter()
+            .filter(|d| matches!(d, Discovery::DatabaseAccess(_)))
+            .collect();
+        assert!(!db_accesses.is_empty(), "Should have database access");
 
-        // S3 still creates a CloudResourceUsage from import (unlike DynamoDB/SQS which need method calls)
-        let cloud_resources: Vec<_> = discoveries
+        // Should have API call
+        let api_calls: Vec<_> = discoveries
             .iter()
-            .filter_map(|d| match d {
-                Discovery::CloudResourceUsage(c) => Some(c),
-                _ => None,
-            })
+            .filter(|d| matches!(d, Discovery::ApiCall(_)))
             .collect();
+        assert!(!api_calls.is_empty(), "Should have API call");
+    }
 
-        assert!(
-            cloud_resources.iter().any(|c| c.resource_type == "s3"),
-            "Should detect S3 usage"
-        );
+    #[test]
+    fn test_nestjs_framework_detection() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let package_json = r#"
+{
+  "name": "nest-service",
+  "dependencies": {
+    "@nestjs/core": "^10.0.0",
+    "express": "^4.18.0"
+  }
+}
+"#;
+        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+
+        let parser = create_parser();
+        let service = parser.parse_package_json(dir.path()).unwrap();
+
+        // NestJS should take precedence over express
+        assert_eq!(service.framework, Some("nestjs".to_string()));
     }
 
     #[test]
-    fn test_detect_aws_sdk_v2() {
+    fn test_nextjs_framework_detection() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let package_json = r#"
+{
+  "name": "next-app",
+  "dependencies": {
+    "next": "^14.0.0",
+    "react": "^18.0.0"
+  }
+}
+"#;
+        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+
+        let parser = create_parser();
+        let service = parser.parse_package_json(dir.path()).unwrap();
+
+        assert_eq!(service.framework, Some("next.js".to_string()));
+    }
+
+    #[test]
+    fn test_supported_extensions() {
+        let parser = create_parser();
+        let extensions = parser.supported_extensions();
+
+        assert!(extensions.contains(&"js"));
+        assert!(extensions.contains(&"jsx"));
+        assert!(extensions.contains(&"ts"));
+        assert!(extensions.contains(&"tsx"));
+        assert!(extensions.contains(&"mjs"));
+        assert!(extensions.contains(&"cjs"));
+        assert!(extensions.contains(&"prisma"));
+    }
+
+    #[test]
+    fn test_sns_detection() {
         let parser = create_parser();
+        // SNS import detection - actual publish operations would be needed
+        // to create QueueOperation discoveries with topic names.
         let content = r#"
-const AWS = require('aws-sdk');
-const dynamodb = new AWS.DynamoDB();
+import { SNSClient } from '@aws-sdk/client-sns';
 "#;
 
         let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
 
-        // Should detect the aws-sdk import
+        // Should detect the SNS client import
         let imports: Vec<_> = discoveries
             .iter()
             .filter_map(|d| match d {
@@ -1079,383 +3344,932 @@ const dynamodb = new AWS.DynamoDB();
             .collect();
 
         assert!(
-            imports.iter().any(|i| i.module == "aws-sdk"),
-            "Should detect aws-sdk import"
+            imports.iter().any(|i| i.module == "@aws-sdk/client-sns"),
+            "Should detect SNS client import"
         );
     }
 
     #[test]
-    fn test_detect_dynamodb_operations() {
+    fn test_lambda_detection() {
         let parser = create_parser();
-        // Use DynamoDB-like variable name so detection recognizes this as DynamoDB
         let content = r#"
-const result = await dynamoClient.get({ TableName: 'users', Key: { id } });
-await dynamoClient.put({ TableName: 'users', Item: user });
-const items = await dynamoClient.query({ TableName: 'orders' });
-await dynamoClient.delete({ TableName: 'users', Key: { id } });
+import { LambdaClient } from '@aws-sdk/client-lambda';
 "#;
 
         let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
 
-        let db_ops: Vec<_> = discoveries
+        let cloud_resources: Vec<_> = discoveries
             .iter()
             .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
+                Discovery::CloudResourceUsage(c) => Some(c),
                 _ => None,
             })
             .collect();
 
         assert!(
-            db_ops
-                .iter()
-                .any(|d| d.operation == DatabaseOperation::Read),
-            "Should detect read operation"
-        );
-        assert!(
-            db_ops
-                .iter()
-                .any(|d| d.operation == DatabaseOperation::Write),
-            "Should detect write operation"
+            cloud_resources.iter().any(|c| c.resource_type == "lambda"),
+            "Should detect Lambda usage"
         );
     }
 
     #[test]
-    fn test_detect_dynamodb_table_name() {
+    fn test_typescript_grammar_for_ts_files() {
         let parser = create_parser();
-        // Use DynamoDB-like variable name so detection recognizes this as DynamoDB
         let content = r#"
-await docClient.get({ TableName: 'users-table', Key: { id: '123' } });
+import { DynamoDBClient, GetItemCommand } from '@aws-sdk/client-dynamodb';
+
+export async function getUser(client: DynamoDBClient, id: string): Promise<void> {
+    const cmd: GetItemCommand = new GetItemCommand({ TableName: 'users', Key: { id } });
+    await client.send(cmd);
+}
 "#;
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+        let discoveries = parser.parse_file(Path::new("users.ts"), content).unwrap();
 
-        let db_ops: Vec<_> = discoveries
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::DatabaseAccess(db)
+                if db.table_name.as_deref() == Some("users")
+                    && db.operation == DatabaseOperation::Read
+        )));
+    }
+
+    // ==================== AWS CDK Tests ====================
+
+    const CDK_STACK: &str = r#"
+import * as cdk from 'aws-cdk-lib';
+import { Construct } from 'constructs';
+import * as dynamodb from 'aws-cdk-lib/aws-dynamodb';
+import * as lambda from 'aws-cdk-lib/aws-lambda';
+import { aws_sqs as sqs } from 'aws-cdk-lib';
+import { SqsEventSource } from 'aws-cdk-lib/aws-lambda-event-sources';
+
+export class OrdersStack extends cdk.Stack {
+  public readonly table: dynamodb.Table;
+
+  constructor(scope: Construct, id: string, props?: cdk.StackProps) {
+    super(scope, id, props);
+
+    this.table = new dynamodb.Table(this, 'OrdersTable', {
+      tableName: 'orders',
+      partitionKey: { name: 'pk', type: dynamodb.AttributeType.STRING },
+    });
+
+    const queue = new sqs.Queue(this, 'OrderEvents');
+    const bucket = new cdk.aws_s3.Bucket(this, 'Receipts', { bucketName: `receipts-${this.account}` });
+
+    const handler: lambda.Function = new lambda.Function(this, 'OrdersHandler', {
+      functionName: 'orders-handler',
+      runtime: lambda.Runtime.NODEJS_20_X,
+      handler: 'index.handler',
+      code: lambda.Code.fromAsset('dist'),
+    });
+
+    this.table.grantReadWriteData(handler);
+    queue.grantConsumeMessages(handler);
+    handler.addEventSource(new SqsEventSource(queue, { batchSize: 10 }));
+  }
+}
+"#;
+
+    #[test]
+    fn test_cdk_constructs() {
+        let parser = create_parser();
+        let discoveries = parser
+            .parse_file(Path::new("lib/orders-stack.ts"), CDK_STACK)
+            .unwrap();
+
+        let table = discoveries
             .iter()
-            .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
+            .find_map(|d| match d {
+                Discovery::DatabaseAccess(db) if db.detection_method == "cdk" => Some(db),
                 _ => None,
             })
-            .collect();
-
-        assert!(!db_ops.is_empty(), "Should detect database access");
-        assert!(
-            db_ops
-                .iter()
-                .any(|d| d.table_name.as_deref() == Some("users-table")),
-            "Should extract table name"
+            .expect("Should detect dynamodb.Table");
+        assert_eq!(table.table_name.as_deref(), Some("orders"));
+        assert_eq!(
+            table
+                .deployment_metadata
+                .as_ref()
+                .unwrap()
+                .deployment_method,
+            "cdk"
         );
+
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::QueueOperation(q)
+                if q.queue_type == "sqs" && q.queue_name.as_deref() == Some("OrderEvents")
+        )));
+
+        // Template literal name can't be evaluated, so the construct id is used
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::CloudResourceUsage(r)
+                if r.resource_type == "s3" && r.resource_name.as_deref() == Some("Receipts")
+        )));
+
+        let function = discoveries
+            .iter()
+            .find_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .expect("Should detect lambda.Function");
+        assert_eq!(function.name, "orders-handler");
+        assert_eq!(function.language, "javascript");
+        assert_eq!(function.entry_point, "index.handler");
     }
 
     #[test]
-    fn test_detect_fetch_calls() {
+    fn test_cdk_grants_and_event_sources() {
         let parser = create_parser();
-        let content = r#"
-const response = await fetch('https://api.example.com/users');
-const data = await fetch('/api/data');
-"#;
-
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+        let discoveries = parser
+            .parse_file(Path::new("lib/orders-stack.ts"), CDK_STACK)
+            .unwrap();
 
-        let api_calls: Vec<_> = discoveries
+        let relationships: Vec<_> = discoveries
             .iter()
             .filter_map(|d| match d {
-                Discovery::ApiCall(a) => Some(a),
+                Discovery::Relationship(r) => Some(r),
                 _ => None,
             })
             .collect();
 
-        assert!(api_calls.len() >= 2, "Should detect both fetch calls");
+        let table_access: Vec<_> = relationships
+            .iter()
+            .filter(|r| r.target == "orders")
+            .map(|r| r.relationship)
+            .collect();
+        assert!(table_access.contains(&RelationshipType::Reads));
+        assert!(table_access.contains(&RelationshipType::Writes));
         assert!(
-            api_calls.iter().any(|a| a.detection_method == "fetch"),
-            "Should identify fetch as detection method"
+            relationships
+                .iter()
+                .all(|r| r.source_service == "orders-handler")
         );
+
+        // Grant and event source both subscribe the handler to the queue
+        let queue_access: Vec<_> = relationships
+            .iter()
+            .filter(|r| r.target == "OrderEvents")
+            .collect();
+        assert_eq!(queue_access.len(), 2);
         assert!(
-            api_calls
+            queue_access
                 .iter()
-                .any(|a| a.target.contains("api.example.com")),
-            "Should extract URL"
+                .all(|r| r.relationship == RelationshipType::Subscribes)
         );
     }
 
     #[test]
-    fn test_detect_axios_calls() {
+    fn test_cdk_ignored_without_cdk_import() {
         let parser = create_parser();
         let content = r#"
-const users = await axios.get('https://api.example.com/users');
-await axios.post('/api/users', { name: 'John' });
-await axios.delete('/api/users/123');
+const fn = new Function('a', 'return a');
+const table = new Table(this, 'Orders');
 "#;
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+        let discoveries = parser.parse_file(Path::new("app.js"), content).unwrap();
+        assert!(discoveries.is_empty());
+    }
 
-        let api_calls: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::ApiCall(a) => Some(a),
-                _ => None,
-            })
-            .collect();
+    #[test]
+    fn test_grpc_clients_and_server() {
+        let parser = create_parser();
+        let content = r#"
+const grpc = require('@grpc/grpc-js');
+const protoLoader = require('@grpc/proto-loader');
+const { UserServiceClient } = require('./gen/users_grpc_pb');
+const { S3Client } = require('@aws-sdk/client-s3');
 
-        assert!(api_calls.len() >= 3, "Should detect all axios calls");
-        assert!(
-            api_calls
-                .iter()
-                .any(|a| a.method == Some("GET".to_string())),
-            "Should detect GET method"
-        );
-        assert!(
-            api_calls
-                .iter()
-                .any(|a| a.method == Some("POST".to_string())),
-            "Should detect POST method"
-        );
-        assert!(
-            api_calls
-                .iter()
-                .any(|a| a.method == Some("DELETE".to_string())),
-            "Should detect DELETE method"
-        );
+const users = new UserServiceClient('users:50051', grpc.credentials.createInsecure());
+users.getUser({ id: '1' }, (err, user) => {});
+
+const proto = grpc.loadPackageDefinition(protoLoader.loadSync('orders.proto'));
+const orders = new proto.acme.OrderService('orders:50051', grpc.credentials.createInsecure());
+orders.listOrders({}, () => {});
+
+const s3 = new S3Client({});
+
+const server = new grpc.Server();
+server.addService(proto.acme.BillingService.service, { charge });
+"#;
+
+        let discoveries = parser.parse_file(Path::new("client.js"), content).unwrap();
+        let usages: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::GrpcUsage(u) => Some(u),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(usages.len(), 3);
+        assert_eq!(usages[0].service, "UserService");
+        assert_eq!(usages[0].role, GrpcRole::Client);
+        assert_eq!(usages[0].methods, vec!["getUser"]);
+        assert_eq!(usages[0].detection_method, "grpc-js");
+        assert_eq!(usages[1].service, "OrderService");
+        assert_eq!(usages[1].methods, vec!["listOrders"]);
+        assert_eq!(usages[2].service, "BillingService");
+        assert_eq!(usages[2].role, GrpcRole::Server);
+    }
+
+    // ==================== Database Tests ====================
+
+    /// (table, connection, operation, detection_method) of each database access.
+    fn db_accesses(
+        discoveries: &[Discovery],
+    ) -> Vec<(Option<&str>, Option<&str>, DatabaseOperation, &str)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some((
+                    db.table_name.as_deref(),
+                    db.connection.as_deref(),
+                    db.operation,
+                    db.detection_method.as_str(),
+                )),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_package_json_parsing() {
-        let dir = tempfile::tempdir().unwrap();
+    fn test_prisma_schema_and_client() {
+        let parser = create_parser();
+        let schema = r#"
+datasource db {
+  provider = "postgresql"
+  url      = env("DATABASE_URL")
+}
 
-        let package_json = r#"
-{
-  "name": "user-service",
-  "main": "dist/index.js",
-  "dependencies": {
-    "express": "^4.18.0",
-    "@aws-sdk/client-dynamodb": "^3.0.0"
-  }
+model Order {
+  id    Int         @id
+  items OrderItem[]
+}
+
+model OrderItem {
+  id      Int   @id
+  orderId Int
 }
 "#;
-        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+        let discoveries = parser
+            .parse_file(Path::new("prisma/schema.prisma"), schema)
+            .unwrap();
+        let connection = Some("DATABASE_URL");
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (
+                    None,
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "prisma.datasource"
+                ),
+                (
+                    Some("Order"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "prisma.schema"
+                ),
+                (
+                    Some("OrderItem"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "prisma.schema"
+                ),
+            ]
+        );
+        let Discovery::DatabaseAccess(db) = &discoveries[0] else {
+            panic!("expected a database access");
+        };
+        assert_eq!(db.db_type, "postgresql");
 
-        let parser = create_parser();
-        let service = parser.parse_package_json(dir.path()).unwrap();
+        let client = r#"
+import { PrismaClient } from '@prisma/client';
+const prisma = new PrismaClient();
 
-        assert_eq!(service.name, "user-service");
-        assert_eq!(service.framework, Some("express".to_string()));
-        assert_eq!(service.entry_point, "dist/index.js");
-        assert_eq!(service.language, "javascript");
+export async function checkout(orderId) {
+  const items = await prisma.orderItem.findMany({ where: { orderId } });
+  await prisma.order.update({ where: { id: orderId }, data: { paid: true } });
+  return prisma.$queryRaw`SELECT * FROM invoices WHERE order_id = ${orderId}`;
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("checkout.ts"), client).unwrap();
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (
+                    Some("OrderItem"),
+                    None,
+                    DatabaseOperation::Read,
+                    "prisma.findMany"
+                ),
+                (
+                    Some("Order"),
+                    None,
+                    DatabaseOperation::ReadWrite,
+                    "prisma.update"
+                ),
+                (
+                    Some("invoices"),
+                    None,
+                    DatabaseOperation::Read,
+                    "prisma.$queryRaw"
+                ),
+            ]
+        );
     }
 
     #[test]
-    fn test_package_json_typescript_detection() {
-        let dir = tempfile::tempdir().unwrap();
+    fn test_typeorm_entities_and_repositories() {
+        let parser = create_parser();
+        let content = r#"
+import { Entity, PrimaryGeneratedColumn, Repository } from 'typeorm';
+import { InjectRepository } from '@nestjs/typeorm';
 
-        let package_json = r#"
-{
-  "name": "ts-service",
-  "main": "dist/index.js",
-  "devDependencies": {
-    "typescript": "^5.0.0"
-  },
-  "dependencies": {
-    "fastify": "^4.0.0"
+@Entity('customers')
+export class Customer {
+  @PrimaryGeneratedColumn()
+  id: number;
+}
+
+@Entity()
+export class OrderItem {
+  @PrimaryGeneratedColumn()
+  id: number;
+}
+
+export class CustomerService {
+  constructor(
+    @InjectRepository(Customer) private readonly customers: Repository<Customer>,
+  ) {}
+
+  find(id: number) {
+    return this.customers.findOneBy({ id });
+  }
+
+  async addItem(item: OrderItem) {
+    await this.dataSource.manager.save(OrderItem, item);
   }
 }
 "#;
-        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+        let discoveries = parser
+            .parse_file(Path::new("customers.ts"), content)
+            .unwrap();
+        let mut accesses = db_accesses(&discoveries);
+        accesses.sort_by_key(|a| (a.0, a.3));
+        assert_eq!(
+            accesses,
+            vec![
+                (
+                    Some("customers"),
+                    None,
+                    DatabaseOperation::Read,
+                    "typeorm.findOneBy"
+                ),
+                (
+                    Some("customers"),
+                    None,
+                    DatabaseOperation::Unknown,
+                    "typeorm.model"
+                ),
+                (
+                    Some("order_item"),
+                    None,
+                    DatabaseOperation::Unknown,
+                    "typeorm.model"
+                ),
+                (
+                    Some("order_item"),
+                    None,
+                    DatabaseOperation::ReadWrite,
+                    "typeorm.save"
+                ),
+            ]
+        );
+    }
 
+    #[test]
+    fn test_sequelize_and_mongoose_models() {
         let parser = create_parser();
-        let service = parser.parse_package_json(dir.path()).unwrap();
+        let sequelize = r#"
+const { Sequelize, DataTypes } = require('sequelize');
+const sequelize = new Sequelize(process.env.DATABASE_URL);
 
-        assert_eq!(service.name, "ts-service");
-        assert_eq!(service.framework, Some("fastify".to_string()));
-        assert_eq!(service.language, "typescript");
-    }
+const Category = sequelize.define('Category', { name: DataTypes.STRING });
+const Invoice = sequelize.define('Invoice', {}, { tableName: 'billing_invoices' });
 
-    #[test]
-    fn test_package_json_missing() {
-        let dir = tempfile::tempdir().unwrap();
+async function list() {
+  await Invoice.destroy({ where: { void: true } });
+  return Category.findAll();
+}
+"#;
+        let discoveries = parser
+            .parse_file(Path::new("models.js"), sequelize)
+            .unwrap();
+        let connection = Some("DATABASE_URL");
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "process.env"),
+                (
+                    Some("Categories"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "sequelize.model"
+                ),
+                (
+                    Some("billing_invoices"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "sequelize.model"
+                ),
+                (
+                    Some("billing_invoices"),
+                    connection,
+                    DatabaseOperation::Write,
+                    "sequelize.destroy"
+                ),
+                (
+                    Some("Categories"),
+                    connection,
+                    DatabaseOperation::Read,
+                    "sequelize.findAll"
+                ),
+            ]
+        );
 
-        let parser = create_parser();
-        let service = parser.parse_package_json(dir.path());
+        let mongoose = r#"
+const mongoose = require('mongoose');
+mongoose.connect(process.env.MONGODB_URI);
+
+const Story = mongoose.model('Story', new mongoose.Schema({ title: String }));
 
+async function publish(id) {
+  await Story.findByIdAndUpdate(id, { published: true });
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("story.js"), mongoose).unwrap();
+        let connection = Some("MONGODB_URI");
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "process.env"),
+                (
+                    Some("stories"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "mongoose.model"
+                ),
+                (
+                    Some("stories"),
+                    connection,
+                    DatabaseOperation::ReadWrite,
+                    "mongoose.findByIdAndUpdate"
+                ),
+            ]
+        );
         assert!(
-            service.is_none(),
-            "Should return None for missing package.json"
+            discoveries
+                .iter()
+                .any(|d| matches!(d, Discovery::DatabaseAccess(db) if db.db_type == "mongodb"))
         );
     }
 
     #[test]
-    fn test_empty_file() {
+    fn test_knex_builders_and_pg_queries() {
         let parser = create_parser();
-        let discoveries = parser.parse_file(Path::new("test.js"), "").unwrap();
+        let knex = r#"
+const db = require('knex')({ client: 'pg', connection: process.env.DATABASE_URL });
+
+async function archive(id) {
+  const order = await db('orders').where({ id }).first();
+  await db('orders').where({ id }).update({ archived: true });
+  await db.insert({ order_id: id }).into('order_archive');
+  return order;
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("archive.js"), knex).unwrap();
+        let connection = Some("DATABASE_URL");
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "process.env"),
+                (Some("orders"), connection, DatabaseOperation::Read, "knex"),
+                (
+                    Some("orders"),
+                    connection,
+                    DatabaseOperation::ReadWrite,
+                    "knex"
+                ),
+                (
+                    Some("order_archive"),
+                    connection,
+                    DatabaseOperation::Write,
+                    "knex"
+                ),
+            ]
+        );
 
-        assert!(
-            discoveries.is_empty(),
-            "Empty file should produce no discoveries"
+        let pg = r#"
+import { Pool } from 'pg';
+const pool = new Pool({ connectionString: process.env.ORDERS_DB_URL });
+
+export async function totals(customerId) {
+  const { rows } = await pool.query(
+    'SELECT o.id, SUM(i.price) FROM orders o JOIN order_items i ON i.order_id = o.id WHERE o.customer_id = $1',
+    [customerId],
+  );
+  await pool.query(`INSERT INTO audit_log (event) VALUES ('totals')`);
+  return rows;
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("totals.ts"), pg).unwrap();
+        let connection = Some("ORDERS_DB_URL");
+        assert_eq!(
+            db_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "process.env"),
+                (
+                    Some("orders"),
+                    connection,
+                    DatabaseOperation::Read,
+                    "pg.query"
+                ),
+                (
+                    Some("order_items"),
+                    connection,
+                    DatabaseOperation::Read,
+                    "pg.query"
+                ),
+                (
+                    Some("audit_log"),
+                    connection,
+                    DatabaseOperation::Write,
+                    "pg.query"
+                ),
+            ]
         );
+        let Discovery::DatabaseAccess(db) = &discoveries
+            .iter()
+            .find(|d| matches!(d, Discovery::DatabaseAccess(_)))
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(db.db_type, "postgresql");
+    }
+
+    // ==================== Messaging Tests ====================
+
+    /// (queue_type, queue_name, operation) of each queue operation.
+    fn queue_operations(discoveries: &[Discovery]) -> Vec<(&str, &str, QueueOperationType)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some((
+                    q.queue_type.as_str(),
+                    q.queue_name.as_deref().unwrap_or(""),
+                    q.operation,
+                )),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_file_with_comments_only() {
+    fn test_kafkajs_and_amqplib_clients() {
         let parser = create_parser();
         let content = r#"
-// This is a comment
-/* Multi-line
-   comment */
+const { Kafka } = require('kafkajs');
+const amqp = require('amqplib');
+
+const kafka = new Kafka({ brokers: ['kafka:9092'] });
+const producer = kafka.producer();
+const consumer = kafka.consumer({ groupId: 'billing' });
+
+async function run() {
+  await producer.send({ topic: 'orders', messages: [{ value: '{}' }] });
+  await consumer.subscribe({ topics: ['payments', 'refunds'] });
+
+  const channel = await (await amqp.connect(process.env.AMQP_URL)).createChannel();
+  channel.sendToQueue('emails', Buffer.from('hi'));
+  channel.publish('events', 'user.created', Buffer.from('{}'));
+  await channel.bindQueue('audit-log', 'events', '#');
+  channel.consume('audit-log', (msg) => channel.ack(msg));
+}
 "#;
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
 
-        assert!(
-            discoveries.is_empty(),
-            "File with only comments should produce no discoveries"
+        let discoveries = parser.parse_file(Path::new("worker.js"), content).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("kafka", "orders", QueueOperationType::Publish),
+                ("kafka", "payments", QueueOperationType::Subscribe),
+                ("kafka", "refunds", QueueOperationType::Subscribe),
+                ("rabbitmq", "emails", QueueOperationType::Publish),
+                ("rabbitmq", "events", QueueOperationType::Publish),
+                ("rabbitmq", "events", QueueOperationType::Subscribe),
+                ("rabbitmq", "audit-log", QueueOperationType::Subscribe),
+            ]
         );
     }
 
     #[test]
-    fn test_mixed_imports_and_code() {
+    fn test_redis_streams_and_bullmq_queues() {
         let parser = create_parser();
-        // Use AWS SDK v3 command pattern for DynamoDB detection
-        // Note: Variable name like 'client' doesn't trigger DynamoDB detection
-        // to avoid false positives (e.g., axios.get()). Use explicit command classes instead.
         let content = r#"
-import express from 'express';
-import { DynamoDBClient, GetItemCommand } from '@aws-sdk/client-dynamodb';
-
-const app = express();
-const dynamoClient = new DynamoDBClient({});
-
-app.get('/users/:id', async (req, res) => {
-    const command = new GetItemCommand({ TableName: 'users', Key: { id: { S: req.params.id } } });
-    const result = await dynamoClient.send(command);
-    res.json(result.Item);
+import Redis from 'ioredis';
+import { createClient } from 'redis';
+import { Queue, Worker } from 'bullmq';
+
+const redis = new Redis();
+const client = createClient();
+const emails = new Queue('emails');
+
+new Worker('thumbnails', async (job) => {
+  await redis.xadd('image-events', '*', 'id', job.id);
+  await redis.xreadgroup('GROUP', 'indexer', 'c1', 'STREAMS', 'image-events', '>');
+  await client.xReadGroup('search', 'c1', [{ key: 'search-events', id: '>' }]);
+  await emails.add('welcome', { to: job.data.email });
 });
-
-const data = await fetch('https://api.example.com/data');
 "#;
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
-
-        // Should have imports
-        let imports: Vec<_> = discoveries
-            .iter()
-            .filter(|d| matches!(d, Discovery::Import(_)))
-            .collect();
-        assert!(imports.len() >= 2, "Should have at least 2 imports");
+        let discoveries = parser.parse_file(Path::new("jobs.ts"), content).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("bullmq", "emails", QueueOperationType::Publish),
+                ("bullmq", "thumbnails", QueueOperationType::Subscribe),
+                ("redis-stream", "image-events", QueueOperationType::Publish),
+                (
+                    "redis-stream",
+                    "image-events",
+                    QueueOperationType::Subscribe
+                ),
+                (
+                    "redis-stream",
+                    "search-events",
+                    QueueOperationType::Subscribe
+                ),
+            ]
+        );
+    }
 
-        // Should have database access
-        let db_accesses: Vec<_> = discoveries
-            .iter()
-            .filter(|d| matches!(d, Discovery::DatabaseAccess(_)))
-            .collect();
-        assert!(!db_accesses.is_empty(), "Should have database access");
+    // ==================== HTTP Route Tests ====================
 
-        // Should have API call
-        let api_calls: Vec<_> = discoveries
+    /// (method, path, detection_method) of each endpoint.
+    fn endpoints(discoveries: &[Discovery]) -> Vec<(&str, &str, &str)> {
+        discoveries
             .iter()
-            .filter(|d| matches!(d, Discovery::ApiCall(_)))
-            .collect();
-        assert!(!api_calls.is_empty(), "Should have API call");
+            .filter_map(|d| match d {
+                Discovery::ApiEndpoint(e) => Some((
+                    e.method.as_str(),
+                    e.path.as_str(),
+                    e.detection_method.as_str(),
+                )),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_nestjs_framework_detection() {
-        let dir = tempfile::tempdir().unwrap();
+    fn test_express_and_fastify_routes() {
+        let parser = create_parser();
+        let express = r#"
+const express = require('express');
+const axios = require('axios');
+const app = express();
+const users = express.Router();
 
-        let package_json = r#"
-{
-  "name": "nest-service",
-  "dependencies": {
-    "@nestjs/core": "^10.0.0",
-    "express": "^4.18.0"
-  }
+app.set('env', 'production');
+const env = app.get('env');
+
+app.get('/health', (req, res) => res.send('ok'));
+users.post('/users/:id/avatar', upload);
+users.route('/users/:id').get(show).put(update);
+app.all('/legacy/*', proxy);
+
+async function show(req, res) {
+  res.json(await axios.get('/profiles/' + req.params.id));
 }
 "#;
-        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
-
-        let parser = create_parser();
-        let service = parser.parse_package_json(dir.path()).unwrap();
+        let discoveries = parser.parse_file(Path::new("app.js"), express).unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("GET", "/health", "express"),
+                ("POST", "/users/{id}/avatar", "express"),
+                ("PUT", "/users/{id}", "express"),
+                ("GET", "/users/{id}", "express"),
+                ("ANY", "/legacy/*", "express"),
+            ]
+        );
 
-        // NestJS should take precedence over express
-        assert_eq!(service.framework, Some("nestjs".to_string()));
+        let fastify = r#"
+export default async function routes(fastify) {
+  fastify.get('/orders/:orderId', getOrder);
+  fastify.route({ method: ['POST', 'PUT'], url: '/orders', handler: saveOrder });
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("routes.ts"), fastify).unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("GET", "/orders/{orderId}", "fastify"),
+                ("POST", "/orders", "fastify"),
+                ("PUT", "/orders", "fastify"),
+            ]
+        );
     }
 
     #[test]
-    fn test_nextjs_framework_detection() {
-        let dir = tempfile::tempdir().unwrap();
+    fn test_nestjs_controller_routes() {
+        let parser = create_parser();
+        let content = r#"
+import { Body, Controller, Delete, Get, HttpCode, Param, Post } from '@nestjs/common';
 
-        let package_json = r#"
-{
-  "name": "next-app",
-  "dependencies": {
-    "next": "^14.0.0",
-    "react": "^18.0.0"
-  }
-}
-"#;
-        std::fs::write(dir.path().join("package.json"), package_json).unwrap();
+@Controller('users')
+export class UsersController {
+  @Get()
+  findAll() {}
 
-        let parser = create_parser();
-        let service = parser.parse_package_json(dir.path()).unwrap();
+  @Get(':id')
+  findOne(@Param('id') id: string) {}
 
-        assert_eq!(service.framework, Some("next.js".to_string()));
+  @Post()
+  @HttpCode(201)
+  create(@Body() dto: CreateUserDto) {}
+
+  @Delete(':id/sessions')
+  logout(@Param('id') id: string) {}
+}
+"#;
+        let discoveries = parser
+            .parse_file(Path::new("users.controller.ts"), content)
+            .unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("GET", "/users", "nestjs"),
+                ("GET", "/users/{id}", "nestjs"),
+                ("POST", "/users", "nestjs"),
+                ("DELETE", "/users/{id}/sessions", "nestjs"),
+            ]
+        );
     }
 
+    // ==================== Constant and Environment Propagation Tests ====================
+
     #[test]
-    fn test_supported_extensions() {
+    fn test_resource_names_from_constants_and_environment() {
         let parser = create_parser();
-        let extensions = parser.supported_extensions();
+        let content = r#"
+const { DynamoDBClient, GetItemCommand } = require('@aws-sdk/client-dynamodb');
+const { SQSClient, SendMessageCommand } = require('@aws-sdk/client-sqs');
+const { ORDERS_TABLE, AUDIT_TABLE = 'audit' } = process.env;
+const USERS_TABLE = process.env.USERS_TABLE || 'users-local';
+const TABLE = USERS_TABLE;
+const QUEUE_URL = 'https://sqs.us-east-1.amazonaws.com/123456789012/jobs';
+
+class Repo {
+  constructor() {
+    this.tableName = process.env['SESSIONS_TABLE'];
+  }
+  load(id) {
+    return docClient.get({ TableName: this.tableName, Key: { id } });
+  }
+}
 
-        assert!(extensions.contains(&"js"));
-        assert!(extensions.contains(&"jsx"));
-        assert!(extensions.contains(&"ts"));
-        assert!(extensions.contains(&"tsx"));
-        assert!(extensions.contains(&"mjs"));
-        assert!(extensions.contains(&"cjs"));
+await docClient.get({ TableName: TABLE, Key: { id } });
+await docClient.put({ TableName: ORDERS_TABLE, Item: order });
+await docClient.put({ TableName: AUDIT_TABLE, Item: entry });
+await client.send(new GetItemCommand({ TableName: process.env.CARTS_TABLE as string, Key }));
+await sqs.send(new SendMessageCommand({ QueueUrl: QUEUE_URL, MessageBody: '{}' }));
+await sqs.send(new SendMessageCommand({ QueueUrl: process.env.EVENTS_QUEUE_URL!, MessageBody: '{}' }));
+"#;
+
+        let discoveries = parser.parse_file(Path::new("repo.ts"), content).unwrap();
+        let tables: Vec<_> = db_accesses(&discoveries)
+            .into_iter()
+            .filter_map(|(table, _, _, _)| table)
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                "env:SESSIONS_TABLE",
+                "env:USERS_TABLE:users-local",
+                "env:ORDERS_TABLE",
+                "env:AUDIT_TABLE:audit",
+                "env:CARTS_TABLE",
+            ]
+        );
+
+        let queues: Vec<_> = queue_operations(&discoveries)
+            .into_iter()
+            .map(|(_, name, _)| name)
+            .collect();
+        assert_eq!(queues, vec!["jobs", "env:EVENTS_QUEUE_URL"]);
     }
 
     #[test]
-    fn test_sns_detection() {
+    fn test_reassigned_constants_are_not_resolved() {
         let parser = create_parser();
-        // SNS import detection - actual publish operations would be needed
-        // to create QueueOperation discoveries with topic names.
         let content = r#"
-import { SNSClient } from '@aws-sdk/client-sns';
+let table = 'users';
+if (legacy) {
+  table = 'users-v1';
+}
+await docClient.get({ TableName: table, Key: { id } });
 "#;
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
-
-        // Should detect the SNS client import
-        let imports: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::Import(i) => Some(i),
-                _ => None,
-            })
+        let discoveries = parser.parse_file(Path::new("repo.js"), content).unwrap();
+        let tables: Vec<_> = db_accesses(&discoveries)
+            .into_iter()
+            .filter_map(|(table, _, _, _)| table)
             .collect();
+        assert_eq!(tables, vec!["table"]);
+    }
 
-        assert!(
-            imports.iter().any(|i| i.module == "@aws-sdk/client-sns"),
-            "Should detect SNS client import"
-        );
+    fn acme_rule(pattern: crate::parser::RulePattern) -> DetectionRule {
+        DetectionRule {
+            name: "acme-db".to_string(),
+            languages: vec!["javascript".to_string()],
+            pattern,
+            kind: crate::parser::RuleKind::Database,
+            resource_type: Some("dynamodb".to_string()),
+            name_capture: "name".to_string(),
+            operation_capture: "op".to_string(),
+            operation: None,
+            operations: [("put", "write"), ("get", "read")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
     }
 
     #[test]
-    fn test_lambda_detection() {
-        let parser = create_parser();
+    fn test_detection_rules_call_pattern() {
+        let parser = JavaScriptParser::with_rules(&[acme_rule(crate::parser::RulePattern::Call(
+            "acme.table($name).$op()".to_string(),
+        ))])
+        .unwrap();
         let content = r#"
-import { LambdaClient } from '@aws-sdk/client-lambda';
-"#;
+const acme = require('@acme/db-client');
+const USERS = process.env.USERS_TABLE;
 
-        let discoveries = parser.parse_file(Path::new("test.js"), content).unwrap();
+await acme.table('orders').put({ id });
+await acme.table(USERS).get(id);
+await acme.table('orders').scan();
+await other.table('orders').put({ id });
+"#;
 
-        let cloud_resources: Vec<_> = discoveries
+        let discoveries = parser.parse_file(Path::new("repo.ts"), content).unwrap();
+        let accesses: Vec<_> = discoveries
             .iter()
             .filter_map(|d| match d {
-                Discovery::CloudResourceUsage(c) => Some(c),
+                Discovery::DatabaseAccess(db) if db.detection_method == "acme-db" => Some((
+                    db.table_name.clone().unwrap_or_default(),
+                    db.operation,
+                    db.source_line,
+                )),
                 _ => None,
             })
             .collect();
-
-        assert!(
-            cloud_resources.iter().any(|c| c.resource_type == "lambda"),
-            "Should detect Lambda usage"
+        assert_eq!(
+            accesses,
+            vec![
+                ("orders".to_string(), DatabaseOperation::Write, 5),
+                ("env:USERS_TABLE".to_string(), DatabaseOperation::Read, 6),
+                ("orders".to_string(), DatabaseOperation::Unknown, 7),
+            ]
         );
     }
+
+    #[test]
+    fn test_detection_rules_query() {
+        let parser = JavaScriptParser::with_rules(&[acme_rule(crate::parser::RulePattern::Query(
+            r#"(call_expression
+                     function: (member_expression
+                       object: (identifier) @client
+                       property: (property_identifier) @op)
+                     arguments: (arguments . (string) @name)
+                     (#eq? @client "acmeDb"))"#
+                .to_string(),
+        ))])
+        .unwrap();
+        assert_ne!(parser.version(), JavaScriptParser::new().unwrap().version());
+
+        let content = "acmeDb.get('users', id);\notherDb.get('orders', id);\n";
+        let discoveries = parser.parse_file(Path::new("repo.js"), content).unwrap();
+        let tables: Vec<_> = db_accesses(&discoveries)
+            .into_iter()
+            .filter(|(_, _, _, method)| *method == "acme-db")
+            .filter_map(|(table, _, _, _)| table)
+            .collect();
+        assert_eq!(tables, vec!["users"]);
+    }
 }
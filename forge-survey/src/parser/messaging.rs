@@ -0,0 +1,174 @@
+//! Message broker recognition shared by the language parsers.
+//!
+//! Services coupled through a broker never call each other directly; the
+//! coupling is a topic, exchange, stream or task queue that one side
+//! publishes to and the other consumes from. The language parsers find the
+//! client calls below and hand them to a [`MessagingCollector`], which turns
+//! them into queue operations named after the topic, so producer and
+//! consumer meet at the same `Queue` node.
+//!
+//! | Broker | `queue_type` | JavaScript | Python |
+//! |--------|--------------|------------|--------|
+//! | Kafka | `kafka` | kafkajs `producer.send({ topic })`, `consumer.subscribe({ topic })` | confluent-kafka `produce(topic)`, kafka-python `send(topic)`, `KafkaConsumer(topic)`, `subscribe([topic])` |
+//! | RabbitMQ | `rabbitmq` | amqplib `publish(exchange)`, `sendToQueue(queue)`, `consume(queue)`, `bindQueue(queue, exchange)` | pika `basic_publish(exchange, routing_key)`, `basic_consume(queue)`, `queue_bind(queue, exchange)` |
+//! | Redis streams | `redis-stream` | `xadd`/`xAdd`, `xreadgroup`/`xReadGroup` | `xadd`, `xreadgroup` |
+//! | Celery | `celery` | | `@app.task`/`@shared_task` consume, `.delay()`/`.apply_async()`/`send_task(name)` publish |
+//! | BullMQ | `bullmq` | `new Queue(name)` publishes, `new Worker(name)` consumes | |
+//!
+//! RabbitMQ messages published to the default exchange (`''`) are routed to
+//! the queue named by the routing key, so that queue is recorded instead.
+//! Celery queues are named after the task (the last segment of a dotted
+//! task name), which is what producers and the worker have in common.
+
+use super::traits::*;
+
+/// Queue type of Kafka topics.
+pub(crate) const KAFKA: &str = "kafka";
+/// Queue type of RabbitMQ exchanges and queues.
+pub(crate) const RABBITMQ: &str = "rabbitmq";
+/// Queue type of Redis streams.
+pub(crate) const REDIS_STREAM: &str = "redis-stream";
+/// Queue type of Celery task queues.
+pub(crate) const CELERY: &str = "celery";
+/// Queue type of BullMQ queues.
+pub(crate) const BULLMQ: &str = "bullmq";
+
+/// Redis stream commands and how they use the stream.
+pub(crate) fn redis_stream_operation(command: &str) -> Option<QueueOperationType> {
+    match command.to_ascii_lowercase().as_str() {
+        "xadd" => Some(QueueOperationType::Publish),
+        "xread" | "xreadgroup" => Some(QueueOperationType::Subscribe),
+        _ => None,
+    }
+}
+
+/// The queue a Celery task name refers to: `orders.tasks.process_order` →
+/// `process_order`.
+pub(crate) fn celery_task_queue(task: &str) -> &str {
+    task.rsplit('.').next().unwrap_or(task)
+}
+
+/// Collects broker operations in a file and builds queue discoveries.
+pub(crate) struct MessagingCollector {
+    source_file: String,
+    operations: Vec<QueueOperationDiscovery>,
+}
+
+impl MessagingCollector {
+    pub(crate) fn new(source_file: String) -> Self {
+        Self {
+            source_file,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Record a publish or subscribe on a named topic, exchange or queue.
+    ///
+    /// Empty names (e.g. the RabbitMQ default exchange) and repeats of an
+    /// operation already recorded are ignored.
+    pub(crate) fn add(
+        &mut self,
+        queue_type: &str,
+        name: &str,
+        operation: QueueOperationType,
+        line: u32,
+    ) {
+        if name.is_empty() {
+            return;
+        }
+        let duplicate = self.operations.iter().any(|q| {
+            q.queue_type == queue_type
+                && q.queue_name.as_deref() == Some(name)
+                && q.operation == operation
+        });
+        if duplicate {
+            return;
+        }
+        self.operations.push(QueueOperationDiscovery {
+            queue_type: queue_type.to_string(),
+            queue_name: Some(name.to_string()),
+            operation,
+            source_file: self.source_file.clone(),
+            source_line: line,
+            deployment_metadata: None,
+        });
+    }
+
+    /// Record a RabbitMQ publish: to the exchange, or to the queue named by
+    /// the routing key when publishing to the default exchange.
+    pub(crate) fn add_rabbitmq_publish(
+        &mut self,
+        exchange: &str,
+        routing_key: Option<&str>,
+        line: u32,
+    ) {
+        let name = match (exchange, routing_key) {
+            ("", Some(queue)) => queue,
+            (exchange, _) => exchange,
+        };
+        self.add(RABBITMQ, name, QueueOperationType::Publish, line);
+    }
+
+    pub(crate) fn into_discoveries(self) -> Vec<Discovery> {
+        self.operations
+            .into_iter()
+            .map(Discovery::QueueOperation)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_deduplicates_and_routes_default_exchange() {
+        let mut collector = MessagingCollector::new("worker.py".to_string());
+        collector.add(KAFKA, "orders", QueueOperationType::Publish, 3);
+        collector.add(KAFKA, "orders", QueueOperationType::Publish, 9);
+        collector.add(KAFKA, "orders", QueueOperationType::Subscribe, 12);
+        collector.add_rabbitmq_publish("", Some("emails"), 15);
+        collector.add_rabbitmq_publish("events", Some("user.created"), 16);
+        collector.add_rabbitmq_publish("", None, 17);
+
+        let names: Vec<_> = collector
+            .into_discoveries()
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some((q.queue_name, q.operation, q.source_line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Some("orders".to_string()), QueueOperationType::Publish, 3),
+                (
+                    Some("orders".to_string()),
+                    QueueOperationType::Subscribe,
+                    12
+                ),
+                (Some("emails".to_string()), QueueOperationType::Publish, 15),
+                (Some("events".to_string()), QueueOperationType::Publish, 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redis_stream_commands_and_celery_names() {
+        assert_eq!(
+            redis_stream_operation("xAdd"),
+            Some(QueueOperationType::Publish)
+        );
+        assert_eq!(
+            redis_stream_operation("XREADGROUP"),
+            Some(QueueOperationType::Subscribe)
+        );
+        assert_eq!(redis_stream_operation("get"), None);
+        assert_eq!(
+            celery_task_queue("orders.tasks.process_order"),
+            "process_order"
+        );
+        assert_eq!(celery_task_queue("send_email"), "send_email");
+    }
+}
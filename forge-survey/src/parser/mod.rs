@@ -25,6 +25,12 @@
 //! - [`JavaScriptParser`] - JavaScript/TypeScript (Milestone 2)
 //! - [`PythonParser`] - Python (Milestone 3)
 //! - [`TerraformParser`] - Terraform/HCL (Milestone 3)
+//! - [`CloudFormationParser`] - CloudFormation/SAM templates
+//! - [`DockerComposeParser`] - docker-compose local topology
+//! - [`ServerlessParser`] - Serverless Framework configurations
+//! - [`OpenApiParser`] - OpenAPI 3.x / Swagger 2.0 API documents
+//! - [`ProtoParser`] - Protocol Buffers gRPC services (and gRPC usage in Go)
+//! - [`PluginParser`] - Any language, parsed by an external executable (see [`plugin`])
 //!
 //! # Adding a New Parser
 //!
@@ -35,30 +41,58 @@
 //! 3. Add the module to this file and export the parser
 //! 4. Register the parser in the parser registry (coming in M3)
 //!
+//! Languages can also be added without changing Forge, by declaring a
+//! [`plugin`] in `forge.yaml`, and the JavaScript and Python parsers taught
+//! an organization's own libraries with detection [`rules`].
+//!
 //! See the extension guide in `docs/extending-parsers.md` for detailed instructions.
 
+pub(crate) mod cdk;
 pub mod cloudformation;
+pub(crate) mod cloudformation_eval;
+pub(crate) mod dataflow;
+pub mod docker_compose;
+pub(crate) mod grpc;
 pub mod javascript;
+pub(crate) mod messaging;
+pub mod openapi;
+pub mod plugin;
+pub mod proto;
 pub mod python;
+pub(crate) mod routes;
+pub mod rules;
+pub mod serverless;
+pub(crate) mod sql;
 pub mod terraform;
+pub(crate) mod terraform_eval;
 mod traits;
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
-use crate::detection::DetectedLanguages;
+use crate::detection::{DetectedLanguages, detect_languages, scan_language_extensions};
 
 // Re-export all public types from traits
 pub use traits::{
-    ApiCallDiscovery, CloudResourceDiscovery, DatabaseAccessDiscovery, DatabaseOperation,
-    DeploymentMetadata, Discovery, ImportDiscovery, Parser, ParserError, QueueOperationDiscovery,
-    QueueOperationType, ServiceDiscovery,
+    ApiCallDiscovery, ApiEndpointDiscovery, CloudResourceDiscovery, ComponentKind,
+    DatabaseAccessDiscovery, DatabaseOperation, DeploymentMetadata, Discovery,
+    EnvironmentVariableDiscovery, GrpcRole, GrpcUsageDiscovery, ImportDiscovery, ParseFailure,
+    Parser, ParserError, QueueOperationDiscovery, QueueOperationType, RelationshipDiscovery,
+    RelationshipType, RepoParse, ServiceDiscovery, StackExportDiscovery, StackImportDiscovery,
 };
+pub(crate) use traits::{is_ignored_dir, parse_in_parallel, repo_files};
 
 // Re-export parsers
 pub use cloudformation::CloudFormationParser;
+pub use docker_compose::DockerComposeParser;
 pub use javascript::JavaScriptParser;
+pub use openapi::OpenApiParser;
+pub use plugin::{PluginParser, PluginSpec};
+pub use proto::ProtoParser;
 pub use python::PythonParser;
+pub use rules::{DetectionRule, RuleKind, RulePattern, RuleSet};
+pub use serverless::ServerlessParser;
 pub use terraform::TerraformParser;
 
 /// Registry for language parsers.
@@ -89,7 +123,7 @@ pub use terraform::TerraformParser;
 ///
 /// // Get a specific parser
 /// if let Some(parser) = registry.get("javascript") {
-///     let discoveries = parser.parse_repo(&repo_path)?;
+///     let discoveries = parser.parse_repo(&repo_path)?.discoveries;
 /// }
 ///
 /// // Get all parsers for detected languages
@@ -99,6 +133,10 @@ pub use terraform::TerraformParser;
 pub struct ParserRegistry {
     /// Map from lowercase language name to parser instance.
     parsers: HashMap<String, Arc<dyn Parser>>,
+
+    /// Registered plugins, in registration order, for detecting the
+    /// languages they parse.
+    plugins: Vec<Arc<PluginParser>>,
 }
 
 impl ParserRegistry {
@@ -109,6 +147,11 @@ impl ParserRegistry {
     /// - `typescript` -> `JavaScriptParser` (shared instance)
     /// - `python` -> `PythonParser`
     /// - `terraform` -> `TerraformParser`
+    /// - `cloudformation` / `sam` -> `CloudFormationParser` (shared instance)
+    /// - `docker-compose` -> `DockerComposeParser`
+    /// - `serverless` -> `ServerlessParser`
+    /// - `openapi` -> `OpenApiParser`
+    /// - `protobuf` -> `ProtoParser`
     ///
     /// # Errors
     ///
@@ -121,17 +164,28 @@ impl ParserRegistry {
     /// assert_eq!(registry.available_languages().len(), 4);
     /// ```
     pub fn new() -> Result<Self, ParserError> {
+        Self::with_rules(&[])
+    }
+
+    /// Creates a registry with all built-in parsers, the JavaScript and
+    /// Python parsers also applying the given detection rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the built-in parsers fail to initialize
+    /// or a rule is invalid.
+    pub fn with_rules(rules: &[DetectionRule]) -> Result<Self, ParserError> {
         let mut parsers: HashMap<String, Arc<dyn Parser>> = HashMap::new();
 
         // Create JavaScript parser (shared between JavaScript and TypeScript)
-        let js_parser: Arc<dyn Parser> = Arc::new(JavaScriptParser::new()?);
+        let js_parser: Arc<dyn Parser> = Arc::new(JavaScriptParser::with_rules(rules)?);
 
         // Register JavaScript and TypeScript with the same parser
         parsers.insert("javascript".to_string(), Arc::clone(&js_parser));
         parsers.insert("typescript".to_string(), js_parser);
 
         // Create and register Python parser
-        let python_parser: Arc<dyn Parser> = Arc::new(PythonParser::new()?);
+        let python_parser: Arc<dyn Parser> = Arc::new(PythonParser::with_rules(rules)?);
         parsers.insert("python".to_string(), python_parser);
 
         // Create and register Terraform parser
@@ -146,7 +200,70 @@ impl ParserRegistry {
         );
         parsers.insert("sam".to_string(), cloudformation_parser);
 
-        Ok(Self { parsers })
+        // Create and register docker-compose parser
+        let compose_parser: Arc<dyn Parser> = Arc::new(DockerComposeParser::new()?);
+        parsers.insert("docker-compose".to_string(), compose_parser);
+
+        // Create and register Serverless Framework parser
+        let serverless_parser: Arc<dyn Parser> = Arc::new(ServerlessParser::new()?);
+        parsers.insert("serverless".to_string(), serverless_parser);
+
+        // Create and register OpenAPI/Swagger parser
+        let openapi_parser: Arc<dyn Parser> = Arc::new(OpenApiParser::new()?);
+        parsers.insert("openapi".to_string(), openapi_parser);
+
+        // Create and register Protocol Buffers parser (also scans Go for gRPC usage)
+        let proto_parser: Arc<dyn Parser> = Arc::new(ProtoParser::new()?);
+        parsers.insert("protobuf".to_string(), proto_parser);
+
+        Ok(Self {
+            parsers,
+            plugins: Vec::new(),
+        })
+    }
+
+    /// Creates a registry with all built-in parsers and the given plugins.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the built-in parsers fail to initialize.
+    pub fn with_plugins(plugins: &[PluginSpec]) -> Result<Self, ParserError> {
+        let mut registry = Self::new()?;
+        for spec in plugins {
+            registry.register_plugin(spec.clone());
+        }
+        Ok(registry)
+    }
+
+    /// Registers a parser plugin under its name.
+    ///
+    /// A plugin named after a built-in language replaces its parser.
+    pub fn register_plugin(&mut self, spec: PluginSpec) {
+        let name = spec.name.to_lowercase();
+        let plugin = Arc::new(PluginParser::new(spec));
+        self.plugins
+            .retain(|existing| existing.name().to_lowercase() != name);
+        self.plugins.push(Arc::clone(&plugin));
+        self.parsers.insert(name, plugin);
+    }
+
+    /// Detects the languages used in a repository, including those parsed
+    /// by plugins.
+    ///
+    /// A plugin's language is detected by its extensions, like the built-in
+    /// languages are (see [`scan_language_extensions`]).
+    pub fn detect_languages(&self, repo_path: &Path) -> DetectedLanguages {
+        let mut detected = detect_languages(repo_path);
+        for plugin in &self.plugins {
+            if let Some(language) = scan_language_extensions(
+                repo_path,
+                &plugin.name().to_lowercase(),
+                plugin.supported_extensions(),
+            ) {
+                detected.add(language);
+            }
+        }
+        detected
     }
 
     /// Gets a parser by language name.
@@ -282,8 +399,10 @@ mod tests {
     fn test_registry_new_creates_all_parsers() {
         let registry = ParserRegistry::new().expect("Failed to create registry");
 
-        // Should have 6 language mappings (js, ts, python, terraform, cloudformation, sam)
-        assert_eq!(registry.parsers.len(), 6);
+        // Should have 10 language mappings
+        // (js, ts, python, terraform, cloudformation, sam, docker-compose, serverless, openapi,
+        // protobuf)
+        assert_eq!(registry.parsers.len(), 10);
 
         // All expected languages should be present
         assert!(registry.parsers.contains_key("javascript"));
@@ -292,6 +411,11 @@ mod tests {
         assert!(registry.parsers.contains_key("terraform"));
         assert!(registry.parsers.contains_key("cloudformation"));
         assert!(registry.parsers.contains_key("sam"));
+        assert!(registry.parsers.contains_key("docker-compose"));
+        assert!(registry.parsers.contains_key("serverless"));
+        assert!(registry.parsers.contains_key("openapi"));
+        assert!(registry.parsers.contains_key("protobuf"));
+        assert!(!registry.parsers.contains_key("go"));
     }
 
     #[test]
@@ -570,6 +694,51 @@ mod tests {
         assert_eq!(parsers.len(), 1);
     }
 
+    // ==================== Plugin Tests ====================
+
+    fn plugin_spec(name: &str) -> PluginSpec {
+        PluginSpec {
+            name: name.to_string(),
+            command: std::path::PathBuf::from("forge-plugin"),
+            args: Vec::new(),
+            extensions: vec!["ex".to_string(), ".exs".to_string()],
+            timeout: std::time::Duration::from_secs(60),
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn test_registry_plugin_detected_by_extension() {
+        let registry = ParserRegistry::with_plugins(&[plugin_spec("Elixir")])
+            .expect("Failed to create registry");
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for name in ["a.ex", "b.ex", "test.exs"] {
+            std::fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+
+        let detected = registry.detect_languages(temp_dir.path());
+        assert!(detected.contains("elixir"));
+
+        let parsers = registry.get_for_languages(&detected, &[]);
+        assert_eq!(parsers.len(), 1);
+        assert_eq!(parsers[0].name(), "Elixir");
+        assert_eq!(parsers[0].supported_extensions(), &["ex", "exs"]);
+
+        // Too few files to detect
+        std::fs::remove_file(temp_dir.path().join("b.ex")).unwrap();
+        assert!(registry.detect_languages(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_registry_plugin_replaces_builtin() {
+        let registry = ParserRegistry::with_plugins(&[plugin_spec("python")])
+            .expect("Failed to create registry");
+
+        let parser = registry.get("python").unwrap();
+        assert!(parser.as_any().downcast_ref::<PluginParser>().is_some());
+        assert_eq!(registry.available_languages().len(), 10);
+    }
+
     // ==================== ParserRegistry::available_languages() Tests ====================
 
     #[test]
@@ -578,13 +747,17 @@ mod tests {
 
         let languages = registry.available_languages();
 
-        assert_eq!(languages.len(), 6);
+        assert_eq!(languages.len(), 10);
         assert!(languages.contains(&"javascript"));
         assert!(languages.contains(&"typescript"));
         assert!(languages.contains(&"python"));
         assert!(languages.contains(&"terraform"));
         assert!(languages.contains(&"cloudformation"));
         assert!(languages.contains(&"sam"));
+        assert!(languages.contains(&"docker-compose"));
+        assert!(languages.contains(&"serverless"));
+        assert!(languages.contains(&"openapi"));
+        assert!(languages.contains(&"protobuf"));
     }
 
     #[test]
@@ -0,0 +1,640 @@
+//! OpenAPI / Swagger parser for API contract ingestion.
+//!
+//! Detects:
+//! - OpenAPI 3.x documents (`openapi: 3.0.3`)
+//! - Swagger 2.0 documents (`swagger: "2.0"`)
+//!
+//! Every operation under `paths` becomes an [`ApiEndpointDiscovery`] carrying
+//! the path, method, `operationId` and the schema names referenced by its
+//! request body and responses. Endpoints are owned by the service of the
+//! repository the document lives in; outbound API calls are matched against
+//! them when the graph is built.
+//!
+//! Path prefixes are applied from `basePath` (Swagger 2.0) or from the path
+//! component of the first `servers` URL (OpenAPI 3.x), so `/users/{id}`
+//! served under `https://api.example.com/v1` is recorded as `/v1/users/{id}`.
+
+use super::traits::*;
+use crate::cache::DiscoveryCache;
+use serde_yaml::Value;
+use std::any::Any;
+use std::path::Path;
+
+/// HTTP methods allowed as keys of an OpenAPI path item.
+const OPERATION_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Which specification a document follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpecVersion {
+    /// Swagger 2.0
+    Swagger2,
+    /// OpenAPI 3.x
+    OpenApi3,
+}
+
+impl SpecVersion {
+    fn detection_method(&self) -> &'static str {
+        match self {
+            SpecVersion::Swagger2 => "swagger",
+            SpecVersion::OpenApi3 => "openapi",
+        }
+    }
+}
+
+/// Parser for OpenAPI 3.x and Swagger 2.0 documents.
+pub struct OpenApiParser {}
+
+impl OpenApiParser {
+    pub fn new() -> Result<Self, ParserError> {
+        Ok(Self {})
+    }
+
+    fn parse_document(&self, content: &str) -> Result<Value, ParserError> {
+        // JSON is a subset of YAML, so one parser covers both formats
+        serde_yaml::from_str(content)
+            .map_err(|e| ParserError::TreeSitterError(format!("YAML parse error: {}", e)))
+    }
+
+    /// Cheap check for whether file content may be an API document.
+    ///
+    /// Used to avoid fully parsing every YAML/JSON file in a repository.
+    pub fn looks_like_spec(content: &str) -> bool {
+        content.contains("openapi") || content.contains("swagger")
+    }
+
+    /// Determine the spec version from the document root.
+    ///
+    /// Documents without a `paths` mapping are not considered API documents.
+    fn spec_version(document: &Value) -> Option<SpecVersion> {
+        document.get("paths")?.as_mapping()?;
+
+        if let Some(version) = document.get("openapi").and_then(scalar_to_string) {
+            if version.starts_with('3') {
+                return Some(SpecVersion::OpenApi3);
+            }
+        }
+        if let Some(version) = document.get("swagger").and_then(scalar_to_string) {
+            if version.starts_with('2') {
+                return Some(SpecVersion::Swagger2);
+            }
+        }
+        None
+    }
+
+    /// Extract one endpoint per operation in the document.
+    fn extract(&self, document: &Value, path: &Path, content: &str) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+
+        let Some(version) = Self::spec_version(document) else {
+            return discoveries;
+        };
+        let Some(paths) = document.get("paths").and_then(|p| p.as_mapping()) else {
+            return discoveries;
+        };
+
+        let prefix = self.path_prefix(document, version);
+        let source_file = path.to_string_lossy().to_string();
+
+        for (route, item) in paths {
+            let Some(route) = route.as_str() else {
+                continue;
+            };
+            let Some(item) = item.as_mapping() else {
+                continue;
+            };
+            let line = find_key_line(content, route);
+
+            for method in OPERATION_METHODS {
+                let Some(operation) = item.get(*method) else {
+                    continue;
+                };
+
+                let operation_id = operation
+                    .get("operationId")
+                    .and_then(|o| o.as_str())
+                    .map(|o| o.to_string());
+
+                discoveries.push(Discovery::ApiEndpoint(ApiEndpointDiscovery {
+                    path: join_paths(&prefix, route),
+                    method: method.to_uppercase(),
+                    service: None,
+                    operation_id,
+                    request_schemas: self.request_schemas(document, version, item, operation),
+                    response_schemas: self.response_schemas(document, version, operation),
+                    detection_method: version.detection_method().to_string(),
+                    source_file: source_file.clone(),
+                    source_line: line,
+                }));
+            }
+        }
+
+        discoveries
+    }
+
+    /// Path prefix applied to every route.
+    ///
+    /// Swagger 2.0 uses `basePath`; OpenAPI 3.x uses the path component of the
+    /// first server URL. Server variables (`{version}`) are kept as-is.
+    fn path_prefix(&self, document: &Value, version: SpecVersion) -> String {
+        let raw = match version {
+            SpecVersion::Swagger2 => document
+                .get("basePath")
+                .and_then(|b| b.as_str())
+                .unwrap_or("")
+                .to_string(),
+            SpecVersion::OpenApi3 => document
+                .get("servers")
+                .and_then(|s| s.as_sequence())
+                .and_then(|s| s.first())
+                .and_then(|s| s.get("url"))
+                .and_then(|u| u.as_str())
+                .map(url_path)
+                .unwrap_or_default(),
+        };
+
+        raw.trim_end_matches('/').to_string()
+    }
+
+    /// Schema names referenced by an operation's request body.
+    fn request_schemas(
+        &self,
+        document: &Value,
+        version: SpecVersion,
+        path_item: &serde_yaml::Mapping,
+        operation: &Value,
+    ) -> Vec<String> {
+        let mut schemas = Vec::new();
+
+        match version {
+            SpecVersion::OpenApi3 => {
+                if let Some(body) = operation.get("requestBody") {
+                    let body = resolve_ref(document, body);
+                    collect_content_schemas(body, &mut schemas);
+                }
+            }
+            SpecVersion::Swagger2 => {
+                // Body parameters may be declared on the path item or the operation
+                let path_params = path_item
+                    .get("parameters")
+                    .and_then(|p| p.as_sequence())
+                    .into_iter()
+                    .flatten();
+                let operation_params = operation
+                    .get("parameters")
+                    .and_then(|p| p.as_sequence())
+                    .into_iter()
+                    .flatten();
+
+                for param in path_params.chain(operation_params) {
+                    let param = resolve_ref(document, param);
+                    if param.get("in").and_then(|i| i.as_str()) == Some("body") {
+                        if let Some(schema) = param.get("schema") {
+                            collect_schema_refs(schema, &mut schemas);
+                        }
+                    }
+                }
+            }
+        }
+
+        dedup(schemas)
+    }
+
+    /// Schema names referenced by any of an operation's responses.
+    fn response_schemas(
+        &self,
+        document: &Value,
+        version: SpecVersion,
+        operation: &Value,
+    ) -> Vec<String> {
+        let mut schemas = Vec::new();
+
+        let Some(responses) = operation.get("responses").and_then(|r| r.as_mapping()) else {
+            return schemas;
+        };
+
+        for response in responses.values() {
+            let response = resolve_ref(document, response);
+            match version {
+                SpecVersion::OpenApi3 => collect_content_schemas(response, &mut schemas),
+                SpecVersion::Swagger2 => {
+                    if let Some(schema) = response.get("schema") {
+                        collect_schema_refs(schema, &mut schemas);
+                    }
+                }
+            }
+        }
+
+        dedup(schemas)
+    }
+}
+
+/// Collect schema references from an OpenAPI 3.x `content` map.
+fn collect_content_schemas(holder: &Value, out: &mut Vec<String>) {
+    let Some(content) = holder.get("content").and_then(|c| c.as_mapping()) else {
+        return;
+    };
+    for media in content.values() {
+        if let Some(schema) = media.get("schema") {
+            collect_schema_refs(schema, out);
+        }
+    }
+}
+
+/// Collect schema names from `$ref`s in a schema.
+///
+/// Follows arrays (`items`) and composition (`allOf`, `oneOf`, `anyOf`) but
+/// not object properties, so only the top-level types are recorded.
+fn collect_schema_refs(schema: &Value, out: &mut Vec<String>) {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        if let Some(name) = ref_name(reference) {
+            out.push(name.to_string());
+        }
+        return;
+    }
+
+    if let Some(items) = schema.get("items") {
+        collect_schema_refs(items, out);
+    }
+
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(variants) = schema.get(key).and_then(|v| v.as_sequence()) {
+            for variant in variants {
+                collect_schema_refs(variant, out);
+            }
+        }
+    }
+}
+
+/// Follow a local `$ref` (`#/components/requestBodies/NewUser`) if present.
+///
+/// Returns the value itself if it is not a reference or cannot be resolved.
+fn resolve_ref<'a>(document: &'a Value, value: &'a Value) -> &'a Value {
+    let Some(reference) = value.get("$ref").and_then(|r| r.as_str()) else {
+        return value;
+    };
+    let Some(pointer) = reference.strip_prefix("#/") else {
+        return value;
+    };
+
+    let mut current = document;
+    for segment in pointer.split('/') {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        match current.get(segment.as_str()) {
+            Some(next) => current = next,
+            None => return value,
+        }
+    }
+    current
+}
+
+/// The schema name a `$ref` points to (`#/components/schemas/User` → `User`).
+fn ref_name(reference: &str) -> Option<&str> {
+    reference.rsplit('/').next().filter(|name| !name.is_empty())
+}
+
+/// Join a path prefix and a route, avoiding doubled slashes.
+fn join_paths(prefix: &str, route: &str) -> String {
+    let route = if route.starts_with('/') {
+        route.to_string()
+    } else {
+        format!("/{}", route)
+    };
+
+    if prefix.is_empty() || prefix == "/" {
+        route
+    } else if prefix.starts_with('/') {
+        format!("{}{}", prefix, route)
+    } else {
+        format!("/{}{}", prefix, route)
+    }
+}
+
+/// Extract the path component of a server URL.
+///
+/// `https://api.example.com/v1` → `/v1`, `/api` → `/api`.
+fn url_path(url: &str) -> String {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => match rest.find('/') {
+            Some(idx) => &rest[idx..],
+            None => "",
+        },
+        None => url,
+    };
+
+    rest.split(['?', '#']).next().unwrap_or("").to_string()
+}
+
+/// Remove duplicates while preserving first-seen order.
+fn dedup(values: Vec<String>) -> Vec<String> {
+    let mut seen = Vec::with_capacity(values.len());
+    for value in values {
+        if !seen.contains(&value) {
+            seen.push(value);
+        }
+    }
+    seen
+}
+
+/// Convert a scalar YAML value to a string.
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Find the 1-based line on which a mapping key is declared.
+///
+/// Handles both YAML (`/users:`, `"/users":`) and JSON (`"/users": {`) keys.
+/// Returns 1 if the key cannot be found.
+fn find_key_line(content: &str, key: &str) -> u32 {
+    let plain = format!("{}:", key);
+    let double_quoted = format!("\"{}\":", key);
+    let single_quoted = format!("'{}':", key);
+
+    content
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            line.starts_with(&plain)
+                || line.starts_with(&double_quoted)
+                || line.starts_with(&single_quoted)
+        })
+        .map(|i| i as u32 + 1)
+        .unwrap_or(1)
+}
+
+impl Parser for OpenApiParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "openapi"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["yml", "yaml", "json"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        if !Self::looks_like_spec(content) {
+            return Ok(Vec::new());
+        }
+
+        let document = self.parse_document(content)?;
+        Ok(self.extract(&document, path, content))
+    }
+
+    /// Custom repository parsing that only visits YAML and JSON files.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let extensions = self.supported_extensions();
+        let files = repo_files(repo_path, is_ignored_openapi_dir, |path| {
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            extensions.contains(&extension)
+        });
+        let cache = cache.for_parser(self.name(), self.version());
+        let mut parsed = RepoParse::parse_files(&files, |path, content| {
+            cache.get_or_parse(path, content, || self.parse_file(path, content))
+        });
+
+        // Most YAML/JSON files are not API documents; don't report them
+        for failure in parsed.failures.drain(..) {
+            tracing::debug!(
+                "Failed to parse {}: {}",
+                failure.path.display(),
+                failure.error
+            );
+        }
+        Ok(parsed)
+    }
+}
+
+/// Directories to skip during API document scanning.
+fn is_ignored_openapi_dir(name: &str) -> bool {
+    matches!(
+        name,
+        "node_modules"
+            | ".git"
+            | "target"
+            | "dist"
+            | "build"
+            | "__pycache__"
+            | "venv"
+            | ".venv"
+            | ".terraform"
+            | ".serverless"
+            | ".aws-sam"
+            | "cdk.out"
+            | ".idea"
+            | ".vscode"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(path: &str, content: &str) -> Vec<ApiEndpointDiscovery> {
+        let parser = OpenApiParser::new().unwrap();
+        parser
+            .parse_file(Path::new(path), content)
+            .unwrap()
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovery::ApiEndpoint(e) => Some(e),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn find<'a>(
+        endpoints: &'a [ApiEndpointDiscovery],
+        method: &str,
+        path: &str,
+    ) -> &'a ApiEndpointDiscovery {
+        endpoints
+            .iter()
+            .find(|e| e.method == method && e.path == path)
+            .unwrap_or_else(|| panic!("missing {} {}", method, path))
+    }
+
+    const OPENAPI_3: &str = r##"
+openapi: 3.0.3
+info:
+  title: Users
+  version: "1.0"
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/User"
+    post:
+      operationId: createUser
+      requestBody:
+        $ref: "#/components/requestBodies/NewUser"
+      responses:
+        "201":
+          $ref: "#/components/responses/UserCreated"
+        "400":
+          description: bad request
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Error"
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema:
+          type: string
+    get:
+      operationId: getUser
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema:
+                oneOf:
+                  - $ref: "#/components/schemas/User"
+                  - $ref: "#/components/schemas/Admin"
+components:
+  requestBodies:
+    NewUser:
+      content:
+        application/json:
+          schema:
+            $ref: "#/components/schemas/NewUser"
+  responses:
+    UserCreated:
+      description: created
+      content:
+        application/json:
+          schema:
+            $ref: "#/components/schemas/User"
+"##;
+
+    const SWAGGER_2: &str = r##"{
+  "swagger": "2.0",
+  "info": { "title": "Orders", "version": "1.0" },
+  "basePath": "/api",
+  "paths": {
+    "/orders": {
+      "post": {
+        "operationId": "createOrder",
+        "parameters": [
+          { "name": "body", "in": "body", "schema": { "$ref": "#/definitions/NewOrder" } }
+        ],
+        "responses": {
+          "200": { "description": "ok", "schema": { "$ref": "#/definitions/Order" } }
+        }
+      }
+    },
+    "/orders/{orderId}": {
+      "delete": {
+        "responses": { "204": { "description": "deleted" } }
+      }
+    }
+  }
+}"##;
+
+    // ==================== OpenAPI 3 Tests ====================
+
+    #[test]
+    fn test_openapi3_operations() {
+        let endpoints = parse("api/openapi.yaml", OPENAPI_3);
+
+        assert_eq!(endpoints.len(), 3);
+        let list = find(&endpoints, "GET", "/v1/users");
+        assert_eq!(list.operation_id.as_deref(), Some("listUsers"));
+        assert_eq!(list.response_schemas, vec!["User"]);
+        assert!(list.request_schemas.is_empty());
+        assert_eq!(list.detection_method, "openapi");
+        assert_eq!(list.source_line, 9);
+        assert!(list.service.is_none());
+
+        let get = find(&endpoints, "GET", "/v1/users/{id}");
+        assert_eq!(get.operation_id.as_deref(), Some("getUser"));
+        assert_eq!(get.response_schemas, vec!["User", "Admin"]);
+    }
+
+    #[test]
+    fn test_openapi3_component_refs_are_followed() {
+        let endpoints = parse("api/openapi.yaml", OPENAPI_3);
+
+        let create = find(&endpoints, "POST", "/v1/users");
+        assert_eq!(create.request_schemas, vec!["NewUser"]);
+        assert_eq!(create.response_schemas, vec!["User", "Error"]);
+    }
+
+    // ==================== Swagger 2 Tests ====================
+
+    #[test]
+    fn test_swagger2_json() {
+        let endpoints = parse("swagger.json", SWAGGER_2);
+
+        assert_eq!(endpoints.len(), 2);
+        let create = find(&endpoints, "POST", "/api/orders");
+        assert_eq!(create.operation_id.as_deref(), Some("createOrder"));
+        assert_eq!(create.request_schemas, vec!["NewOrder"]);
+        assert_eq!(create.response_schemas, vec!["Order"]);
+        assert_eq!(create.detection_method, "swagger");
+        assert_eq!(create.source_line, 6);
+
+        let delete = find(&endpoints, "DELETE", "/api/orders/{orderId}");
+        assert!(delete.operation_id.is_none());
+        assert!(delete.response_schemas.is_empty());
+    }
+
+    // ==================== Filtering Tests ====================
+
+    #[test]
+    fn test_non_spec_files_ignored() {
+        assert!(parse("package.json", r#"{"name": "swagger-ui-demo"}"#).is_empty());
+        assert!(parse("config.yaml", "openapi: 3.0.0\ninfo: {}\n").is_empty());
+        assert!(parse("docker-compose.yml", "services:\n  api:\n    image: node\n").is_empty());
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(url_path("https://api.example.com/v1"), "/v1");
+        assert_eq!(url_path("https://api.example.com"), "");
+        assert_eq!(url_path("/api"), "/api");
+        assert_eq!(join_paths("", "/users"), "/users");
+        assert_eq!(join_paths("/v1", "/users"), "/v1/users");
+        assert_eq!(ref_name("#/components/schemas/User"), Some("User"));
+    }
+
+    #[test]
+    fn test_parse_repo_finds_nested_specs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/api")).unwrap();
+        std::fs::write(dir.path().join("docs/api/openapi.yaml"), OPENAPI_3).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.path().join("node_modules/pkg/swagger.json"), SWAGGER_2).unwrap();
+
+        let parser = OpenApiParser::new().unwrap();
+        let discoveries = parser.parse_repo(dir.path()).unwrap().discoveries;
+
+        assert_eq!(discoveries.len(), 3);
+    }
+}
@@ -0,0 +1,500 @@
+//! Out-of-process parser plugins.
+//!
+//! A plugin is an executable declared in `forge.yaml` that parses files for
+//! a language Forge has no built-in parser for. [`PluginParser`] adapts it
+//! to the [`Parser`] trait, so it is detected, cached and reported like any
+//! other parser.
+//!
+//! # Protocol
+//!
+//! Forge runs the plugin once per batch of up to [`BATCH_SIZE`] files,
+//! writes one JSON request to its stdin and closes it:
+//!
+//! ```json
+//! {
+//!   "protocol": 1,
+//!   "repository": "/path/to/repo",
+//!   "files": [{ "path": "/path/to/repo/lib/app.ex", "content": "..." }]
+//! }
+//! ```
+//!
+//! The plugin answers with one JSON document on stdout, holding a result
+//! for each file it was sent, under the path it was sent:
+//!
+//! ```json
+//! {
+//!   "files": [
+//!     { "path": "/path/to/repo/lib/app.ex", "discoveries": [{ "ApiCall": { ... } }] },
+//!     { "path": "/path/to/repo/lib/broken.ex", "error": "unexpected token" }
+//!   ]
+//! }
+//! ```
+//!
+//! Discoveries use the serialized form of [`Discovery`]; an empty
+//! `source_file` is set to the file's path. Files with an `error` are
+//! reported as parse failures. The plugin must exit with status 0 within
+//! its timeout, or the whole parser fails with a [`ParserError`]. Anything
+//! written to stderr is included in the error.
+
+use super::traits::{Discovery, ParseFailure, Parser, ParserError, RepoParse, repo_files};
+use crate::cache::DiscoveryCache;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Version of the request and response format.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Maximum number of files sent to one run of a plugin.
+pub const BATCH_SIZE: usize = 256;
+
+/// How often a running plugin is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A file's discoveries, or why the plugin couldn't parse it.
+type FileResult = Result<Vec<Discovery>, String>;
+
+/// A parser plugin as declared in configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginSpec {
+    /// Language name the plugin is registered and detected as (e.g. "elixir")
+    pub name: String,
+
+    /// Executable to run
+    pub command: PathBuf,
+
+    /// Arguments passed to the executable
+    pub args: Vec<String>,
+
+    /// File extensions the plugin parses, without the dot
+    pub extensions: Vec<String>,
+
+    /// How long one run may take before it is stopped
+    pub timeout: Duration,
+
+    /// Version of the discoveries the plugin makes; bump it to invalidate
+    /// cached discoveries after changing the plugin
+    pub version: u32,
+}
+
+/// A [`Parser`] that runs a plugin executable.
+#[derive(Debug)]
+pub struct PluginParser {
+    spec: PluginSpec,
+    /// `spec.extensions`, in the form [`Parser::supported_extensions`]
+    /// returns. Leaked because parsers live for the whole process.
+    extensions: &'static [&'static str],
+}
+
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    protocol: u32,
+    repository: &'a Path,
+    files: Vec<FileRequest<'a>>,
+}
+
+#[derive(Serialize)]
+struct FileRequest<'a> {
+    path: &'a Path,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct PluginResponse {
+    files: Vec<FileResponse>,
+}
+
+#[derive(Deserialize)]
+struct FileResponse {
+    path: PathBuf,
+    #[serde(default)]
+    discoveries: Vec<Discovery>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl PluginParser {
+    /// Creates an adapter for the plugin. The executable is not run until
+    /// files are parsed.
+    pub fn new(spec: PluginSpec) -> Self {
+        let extensions: Vec<&'static str> = spec
+            .extensions
+            .iter()
+            .map(|ext| &*Box::leak(ext.trim_start_matches('.').to_string().into_boxed_str()))
+            .collect();
+        Self {
+            spec,
+            extensions: Box::leak(extensions.into_boxed_slice()),
+        }
+    }
+
+    /// The plugin's configuration.
+    pub fn spec(&self) -> &PluginSpec {
+        &self.spec
+    }
+
+    /// Run the plugin on a batch of files, returning each file's result in
+    /// the order the files were given.
+    fn run(
+        &self,
+        repository: &Path,
+        files: &[(PathBuf, String)],
+    ) -> Result<Vec<FileResult>, ParserError> {
+        let request = PluginRequest {
+            protocol: PROTOCOL_VERSION,
+            repository,
+            files: files
+                .iter()
+                .map(|(path, content)| FileRequest { path, content })
+                .collect(),
+        };
+        let input = serde_json::to_vec(&request).map_err(|e| self.failed(e))?;
+        let output = self.execute(&input)?;
+
+        let response: PluginResponse = serde_json::from_slice(&output)
+            .map_err(|e| self.failed(format!("invalid response: {}", e)))?;
+        let mut results: HashMap<PathBuf, FileResponse> = HashMap::new();
+        for file in response.files {
+            results.insert(file.path.clone(), file);
+        }
+
+        Ok(files
+            .iter()
+            .map(|(path, _)| match results.remove(path) {
+                Some(FileResponse {
+                    error: Some(error), ..
+                }) => Err(error),
+                Some(FileResponse {
+                    mut discoveries, ..
+                }) => {
+                    let source_file = path.to_string_lossy();
+                    for discovery in &mut discoveries {
+                        if discovery.source_file().is_empty() {
+                            *discovery.source_file_mut() = source_file.to_string();
+                        }
+                    }
+                    Ok(discoveries)
+                }
+                None => Err("no result returned by plugin".to_string()),
+            })
+            .collect())
+    }
+
+    /// Run the executable with `input` on stdin, returning its stdout.
+    fn execute(&self, input: &[u8]) -> Result<Vec<u8>, ParserError> {
+        let mut child = Command::new(&self.spec.command)
+            .args(&self.spec.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                self.failed(format!(
+                    "could not start {}: {}",
+                    self.spec.command.display(),
+                    e
+                ))
+            })?;
+
+        // Write and read on threads of their own, so a plugin that fills
+        // its output pipe before reading all of its input can't deadlock.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = input.to_vec();
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let stdout_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            stdout.read_to_end(&mut buf).map(|_| buf)
+        });
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = String::new();
+            let _ = stderr.read_to_string(&mut buf);
+            buf
+        });
+
+        let deadline = Instant::now() + self.spec.timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(ParserError::PluginTimedOut {
+                        plugin: self.spec.name.clone(),
+                        seconds: self.spec.timeout.as_secs(),
+                    });
+                }
+                Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => return Err(self.failed(e)),
+            }
+        };
+
+        // A plugin may exit without reading its input; that is only an
+        // error if it also fails.
+        let written = writer.join().expect("stdin writer panicked");
+        let stdout = stdout_reader.join().expect("stdout reader panicked");
+        let stderr = stderr_reader.join().expect("stderr reader panicked");
+
+        if !status.success() {
+            let stderr = stderr.trim();
+            return Err(self.failed(if stderr.is_empty() {
+                format!("exited with {}", status)
+            } else {
+                format!("exited with {}: {}", status, stderr)
+            }));
+        }
+        if let Err(e) = written {
+            tracing::debug!("Plugin {} did not read its input: {}", self.spec.name, e);
+        }
+        stdout.map_err(|e| self.failed(e))
+    }
+
+    fn failed(&self, reason: impl ToString) -> ParserError {
+        ParserError::PluginFailed {
+            plugin: self.spec.name.clone(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Parser for PluginParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn version(&self) -> u32 {
+        self.spec.version
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        self.extensions
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        let repository = path.parent().unwrap_or(Path::new(""));
+        let files = [(path.to_path_buf(), content.to_string())];
+        self.run(repository, &files)?
+            .pop()
+            .expect("one result per file")
+            .map_err(|reason| self.failed(format!("{}: {}", path.display(), reason)))
+    }
+
+    /// Sends the files not in the cache to the plugin in batches. A batch
+    /// that fails fails the whole parser.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let files = repo_files(repo_path, super::is_ignored_dir, |path| {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            self.extensions.contains(&ext)
+        });
+        let cache = cache.for_parser(self.name(), self.version());
+
+        // Each readable file's discoveries, from the cache where possible
+        let mut parsed: Vec<(PathBuf, String, Option<FileResult>)> = Vec::new();
+        for path in files {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                tracing::debug!("Skipping unreadable file: {}", path.display());
+                continue;
+            };
+            let cached = cache.get(&path, &content).map(Ok);
+            parsed.push((path, content, cached));
+        }
+
+        let uncached: Vec<usize> = (0..parsed.len())
+            .filter(|&i| parsed[i].2.is_none())
+            .collect();
+        for batch in uncached.chunks(BATCH_SIZE) {
+            let files: Vec<(PathBuf, String)> = batch
+                .iter()
+                .map(|&i| (parsed[i].0.clone(), parsed[i].1.clone()))
+                .collect();
+            for (&i, result) in batch.iter().zip(self.run(repo_path, &files)?) {
+                if let Ok(discoveries) = &result {
+                    cache.put(&parsed[i].0, &parsed[i].1, discoveries);
+                }
+                parsed[i].2 = Some(result);
+            }
+        }
+
+        let mut parse = RepoParse::default();
+        for (path, _, result) in parsed {
+            match result.expect("every file was parsed") {
+                Ok(discoveries) => parse.discoveries.extend(discoveries),
+                Err(error) => parse.failures.push(ParseFailure { path, error }),
+            }
+        }
+        Ok(parse)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A plugin written as a shell script, reading the request with `cat`.
+    fn script_plugin(dir: &Path, script: &str, timeout: Duration) -> PluginParser {
+        let path = dir.join("plugin.sh");
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        PluginParser::new(PluginSpec {
+            name: "elixir".to_string(),
+            command: PathBuf::from("sh"),
+            args: vec![path.to_string_lossy().into_owned()],
+            extensions: vec!["ex".to_string()],
+            timeout,
+            version: 1,
+        })
+    }
+
+    #[test]
+    fn test_plugin_parses_repository() {
+        let plugin_dir = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let app = repo.path().join("lib/app.ex");
+        let broken = repo.path().join("lib/broken.ex");
+        fs::create_dir_all(app.parent().unwrap()).unwrap();
+        fs::write(&app, "defmodule App do end").unwrap();
+        fs::write(&broken, "defmodule").unwrap();
+        fs::write(repo.path().join("README.md"), "# App").unwrap();
+
+        let response = serde_json::json!({
+            "files": [
+                {
+                    "path": app,
+                    "discoveries": [{ "Import": {
+                        "module": "HTTPoison",
+                        "is_relative": false,
+                        "imported_items": [],
+                        "source_file": "",
+                        "source_line": 1
+                    }}]
+                },
+                { "path": broken, "error": "unexpected end of input" }
+            ]
+        });
+        let plugin = script_plugin(
+            plugin_dir.path(),
+            &format!("cat > /dev/null\necho '{}'", response),
+            Duration::from_secs(10),
+        );
+
+        let parse = plugin.parse_repo(repo.path()).unwrap();
+        assert_eq!(parse.discoveries.len(), 1);
+        assert_eq!(parse.discoveries[0].source_file(), app.to_string_lossy());
+        assert_eq!(
+            parse.failures,
+            vec![ParseFailure {
+                path: broken,
+                error: "unexpected end of input".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_plugin_receives_request() {
+        let plugin_dir = TempDir::new().unwrap();
+        let request_path = plugin_dir.path().join("request.json");
+        let plugin = script_plugin(
+            plugin_dir.path(),
+            &format!(
+                "cat > '{}'\necho '{{\"files\": []}}'",
+                request_path.display()
+            ),
+            Duration::from_secs(10),
+        );
+
+        let result = plugin.parse_file(Path::new("/repo/app.ex"), "defmodule App do end");
+        assert!(result.is_err(), "a file missing from the response fails");
+
+        let request: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&request_path).unwrap()).unwrap();
+        assert_eq!(request["protocol"], PROTOCOL_VERSION);
+        assert_eq!(request["files"][0]["path"], "/repo/app.ex");
+        assert_eq!(request["files"][0]["content"], "defmodule App do end");
+    }
+
+    #[test]
+    fn test_plugin_errors() {
+        let plugin_dir = TempDir::new().unwrap();
+        let content = "defmodule App do end";
+        let path = Path::new("/repo/app.ex");
+
+        let failing = script_plugin(
+            plugin_dir.path(),
+            "cat > /dev/null\necho 'no grammar' >&2\nexit 3",
+            Duration::from_secs(10),
+        );
+        match failing.parse_file(path, content) {
+            Err(ParserError::PluginFailed { plugin, reason }) => {
+                assert_eq!(plugin, "elixir");
+                assert!(reason.contains("no grammar"), "{}", reason);
+            }
+            other => panic!("expected PluginFailed, got {:?}", other),
+        }
+
+        let garbage = script_plugin(
+            plugin_dir.path(),
+            "cat > /dev/null\necho 'not json'",
+            Duration::from_secs(10),
+        );
+        assert!(matches!(
+            garbage.parse_file(path, content),
+            Err(ParserError::PluginFailed { .. })
+        ));
+
+        let slow = script_plugin(plugin_dir.path(), "sleep 5", Duration::from_millis(100));
+        assert!(matches!(
+            slow.parse_file(path, content),
+            Err(ParserError::PluginTimedOut { .. })
+        ));
+
+        let missing = PluginParser::new(PluginSpec {
+            command: PathBuf::from("/nonexistent/forge-plugin"),
+            ..slow.spec().clone()
+        });
+        assert!(matches!(
+            missing.parse_file(path, content),
+            Err(ParserError::PluginFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_plugin_results_are_cached() {
+        let plugin_dir = TempDir::new().unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let calls = plugin_dir.path().join("calls");
+        fs::write(repo.path().join("app.ex"), "defmodule App do end").unwrap();
+
+        let plugin = script_plugin(
+            plugin_dir.path(),
+            &format!(
+                "cat > /dev/null\necho run >> '{}'\necho '{{\"files\": [{{\"path\": \"{}\"}}]}}'",
+                calls.display(),
+                repo.path().join("app.ex").display()
+            ),
+            Duration::from_secs(10),
+        );
+
+        let cache = DiscoveryCache::new(cache_dir.path());
+        plugin.parse_repo_cached(repo.path(), &cache).unwrap();
+        let parse = plugin.parse_repo_cached(repo.path(), &cache).unwrap();
+
+        assert!(parse.failures.is_empty());
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(fs::read_to_string(&calls).unwrap().lines().count(), 1);
+    }
+}
@@ -0,0 +1,504 @@
+//! Protocol Buffers parser for gRPC service contracts.
+//!
+//! Detects:
+//! - `service` definitions in `.proto` files, with one API endpoint per `rpc`
+//! - Generated gRPC clients (`NewUserServiceClient`) and server registrations
+//!   (`RegisterUserServiceServer`) in Go sources
+//!
+//! Each rpc becomes an [`ApiEndpointDiscovery`] with method [`GRPC_METHOD`],
+//! the gRPC wire path (`/acme.users.v1.UserService/GetUser`) as its path, and
+//! the request and response message type names as its schemas, so contract
+//! changes can be traced to the services that use them.
+//!
+//! Go has no full parser in Forge, so Go sources are scanned here for the
+//! gRPC codegen naming conventions only. JavaScript and Python usage is
+//! detected by their own parsers (see [`super::grpc`]).
+
+use super::grpc::{GRPC_METHOD, GrpcCollector};
+use super::traits::*;
+use std::any::Any;
+use std::path::Path;
+
+/// A lexical token of a `.proto` file.
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    line: u32,
+}
+
+/// An rpc declared in a service definition.
+#[derive(Debug, Clone, PartialEq)]
+struct Rpc {
+    name: String,
+    request: String,
+    response: String,
+    line: u32,
+}
+
+/// Parser for `.proto` files (and generated gRPC usage in Go).
+pub struct ProtoParser {}
+
+impl ProtoParser {
+    pub fn new() -> Result<Self, ParserError> {
+        Ok(Self {})
+    }
+
+    /// Extract one endpoint per rpc in every service of a `.proto` file.
+    fn parse_proto(&self, path: &Path, content: &str) -> Vec<Discovery> {
+        let tokens = tokenize(content);
+        let source_file = path.to_string_lossy().to_string();
+
+        let package = tokens
+            .windows(2)
+            .find(|w| w[0].text == "package")
+            .map(|w| w[1].text.clone());
+
+        let mut discoveries = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if tokens[i].text == "service" && tokens.get(i + 2).is_some_and(|t| t.text == "{") {
+                let service = &tokens[i + 1].text;
+                let qualified = match &package {
+                    Some(package) => format!("{}.{}", package, service),
+                    None => service.clone(),
+                };
+
+                let (rpcs, end) = parse_service_body(&tokens, i + 3);
+                for rpc in rpcs {
+                    discoveries.push(Discovery::ApiEndpoint(ApiEndpointDiscovery {
+                        path: format!("/{}/{}", qualified, rpc.name),
+                        method: GRPC_METHOD.to_string(),
+                        service: None,
+                        operation_id: Some(rpc.name),
+                        request_schemas: vec![rpc.request],
+                        response_schemas: vec![rpc.response],
+                        detection_method: "protobuf".to_string(),
+                        source_file: source_file.clone(),
+                        source_line: rpc.line,
+                    }));
+                }
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+
+        discoveries
+    }
+
+    /// Scan Go source for generated gRPC clients and server registrations.
+    ///
+    /// Generated code (`*_grpc.pb.go`) is skipped: it declares the
+    /// `New...Client` and `Register...Server` functions rather than using them.
+    fn parse_go(&self, path: &Path, content: &str) -> Vec<Discovery> {
+        if !content.contains("google.golang.org/grpc") || is_generated_go(content) {
+            return Vec::new();
+        }
+
+        let mut collector = GrpcCollector::new(path.to_string_lossy().to_string(), "grpc-go");
+
+        for (index, line) in content.lines().enumerate() {
+            let code = line.split("//").next().unwrap_or("");
+            let line_number = index as u32 + 1;
+
+            for (callee, start) in go_calls(code) {
+                if code[..start].trim_end().ends_with("func") {
+                    continue;
+                }
+                let (receiver, function) = match callee.rsplit_once('.') {
+                    Some((receiver, function)) => (Some(receiver), function),
+                    None => (None, callee),
+                };
+
+                // client := pb.NewUserServiceClient(conn)
+                if let Some(service) = function
+                    .strip_prefix("New")
+                    .and_then(|f| f.strip_suffix("Client"))
+                    .filter(|s| !s.is_empty())
+                {
+                    let binding = go_assignment_target(&code[..start]);
+                    collector.add_usage(GrpcRole::Client, service, binding, line_number);
+                // pb.RegisterUserServiceServer(grpcServer, &server{})
+                } else if let Some(service) = function
+                    .strip_prefix("Register")
+                    .and_then(|f| f.strip_suffix("Server"))
+                    .filter(|s| !s.is_empty())
+                {
+                    collector.add_usage(GrpcRole::Server, service, None, line_number);
+                // client.GetUser(ctx, req)
+                } else if let Some(receiver) = receiver {
+                    collector.add_call(receiver, function);
+                }
+            }
+        }
+
+        collector.into_discoveries()
+    }
+}
+
+/// Split `.proto` source into tokens, dropping comments.
+///
+/// Identifiers keep their dots (`google.protobuf.Empty`); string literals are
+/// kept whole; every other non-space character is its own token.
+fn tokenize(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut text = String::from(c);
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    text.push(c);
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if c == quote && !escaped {
+                        break;
+                    }
+                    escaped = c == '\\' && !escaped;
+                }
+                tokens.push(Token { text, line });
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {
+                let mut text = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '.' {
+                        text.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token { text, line });
+            }
+            c => tokens.push(Token {
+                text: c.to_string(),
+                line,
+            }),
+        }
+    }
+
+    tokens
+}
+
+/// Parse rpcs from a service body starting just after its `{`.
+///
+/// Returns the rpcs and the index just past the closing `}`.
+fn parse_service_body(tokens: &[Token], start: usize) -> (Vec<Rpc>, usize) {
+    let mut rpcs = Vec::new();
+    let mut depth = 1;
+    let mut i = start;
+
+    while i < tokens.len() {
+        match tokens[i].text.as_str() {
+            "{" => depth += 1,
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return (rpcs, i + 1);
+                }
+            }
+            "rpc" if depth == 1 => {
+                if let Some((rpc, next)) = parse_rpc(tokens, i) {
+                    rpcs.push(rpc);
+                    i = next;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    (rpcs, i)
+}
+
+/// Parse `rpc Name ([stream] Request) returns ([stream] Response)` at `start`.
+///
+/// Returns the rpc and the index of the token after the response's `)`.
+fn parse_rpc(tokens: &[Token], start: usize) -> Option<(Rpc, usize)> {
+    let name = tokens.get(start + 1)?;
+    let (request, next) = parse_message_type(tokens, start + 2)?;
+    if tokens.get(next)?.text != "returns" {
+        return None;
+    }
+    let (response, next) = parse_message_type(tokens, next + 1)?;
+
+    Some((
+        Rpc {
+            name: name.text.clone(),
+            request,
+            response,
+            line: tokens[start].line,
+        },
+        next,
+    ))
+}
+
+/// Parse `( [stream] Type )` at `start`, returning the type name without a
+/// leading `.` and the index after the `)`.
+fn parse_message_type(tokens: &[Token], start: usize) -> Option<(String, usize)> {
+    if tokens.get(start)?.text != "(" {
+        return None;
+    }
+    let mut i = start + 1;
+    if tokens.get(i)?.text == "stream" && tokens.get(i + 1)?.text != ")" {
+        i += 1;
+    }
+    let name = tokens.get(i)?.text.trim_start_matches('.').to_string();
+    if tokens.get(i + 1)?.text != ")" {
+        return None;
+    }
+    Some((name, i + 2))
+}
+
+/// Find function calls in a line of Go code.
+///
+/// Returns each callee (`pb.NewUserServiceClient`, `client.GetUser`) with the
+/// byte offset where it starts.
+fn go_calls(code: &str) -> Vec<(&str, usize)> {
+    let mut calls = Vec::new();
+    let bytes = code.as_bytes();
+    let mut start = None;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        let is_ident = b.is_ascii_alphanumeric() || b == b'_' || b == b'.';
+        match (is_ident, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                if b == b'(' {
+                    let callee = code[s..i].trim_matches('.');
+                    if !callee.is_empty() {
+                        calls.push((callee, s));
+                    }
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    calls
+}
+
+/// Check for the standard Go "generated code" header.
+fn is_generated_go(content: &str) -> bool {
+    content
+        .lines()
+        .take_while(|line| !line.starts_with("package "))
+        .any(|line| line.starts_with("// Code generated") && line.contains("DO NOT EDIT"))
+}
+
+/// The variable assigned on a line of Go code, given the text before the call.
+///
+/// `client := ` → `client`, `s.users = ` → `s.users`, `var c = ` → `c`.
+fn go_assignment_target(prefix: &str) -> Option<&str> {
+    let (left, _) = prefix.split_once(":=").or_else(|| prefix.split_once('='))?;
+    let left = left.trim();
+    let left = left.strip_prefix("var ").unwrap_or(left);
+    let target = left.split(',').next()?.split_whitespace().next()?;
+    Some(target).filter(|t| !t.is_empty())
+}
+
+impl Parser for ProtoParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "proto"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["proto", "go"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("proto") => Ok(self.parse_proto(path, content)),
+            Some("go") => Ok(self.parse_go(path, content)),
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USERS_PROTO: &str = r#"
+syntax = "proto3";
+
+package acme.users.v1;
+
+import "google/protobuf/empty.proto";
+
+// User directory.
+service UserService {
+  // Fetch one user.
+  rpc GetUser(GetUserRequest) returns (User) {
+    option (google.api.http) = { get: "/v1/users/{id}" };
+  }
+  rpc ListUsers (ListUsersRequest) returns (stream User);
+  /* rpc Disabled(A) returns (B); */
+  rpc Ping(.google.protobuf.Empty) returns (google.protobuf.Empty);
+}
+
+message GetUserRequest { string id = 1; }
+message User { string id = 1; string name = 2; }
+"#;
+
+    fn endpoints(discoveries: Vec<Discovery>) -> Vec<ApiEndpointDiscovery> {
+        discoveries
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovery::ApiEndpoint(e) => Some(e),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // ==================== .proto Tests ====================
+
+    #[test]
+    fn test_rpcs_become_endpoints() {
+        let parser = ProtoParser::new().unwrap();
+        let discoveries = parser
+            .parse_file(Path::new("proto/users.proto"), USERS_PROTO)
+            .unwrap();
+        let endpoints = endpoints(discoveries);
+
+        assert_eq!(endpoints.len(), 3);
+
+        let get = &endpoints[0];
+        assert_eq!(get.path, "/acme.users.v1.UserService/GetUser");
+        assert_eq!(get.method, "GRPC");
+        assert_eq!(get.operation_id.as_deref(), Some("GetUser"));
+        assert_eq!(get.request_schemas, vec!["GetUserRequest"]);
+        assert_eq!(get.response_schemas, vec!["User"]);
+        assert_eq!(get.detection_method, "protobuf");
+        assert_eq!(get.source_line, 11);
+
+        let list = &endpoints[1];
+        assert_eq!(list.path, "/acme.users.v1.UserService/ListUsers");
+        assert_eq!(list.response_schemas, vec!["User"]);
+
+        let ping = &endpoints[2];
+        assert_eq!(ping.request_schemas, vec!["google.protobuf.Empty"]);
+    }
+
+    #[test]
+    fn test_proto_without_package() {
+        let parser = ProtoParser::new().unwrap();
+        let content = "service Greeter { rpc SayHello (HelloRequest) returns (HelloReply); }";
+        let endpoints = endpoints(
+            parser
+                .parse_file(Path::new("greeter.proto"), content)
+                .unwrap(),
+        );
+
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].path, "/Greeter/SayHello");
+    }
+
+    // ==================== Go Tests ====================
+
+    #[test]
+    fn test_go_client_and_server() {
+        let parser = ProtoParser::new().unwrap();
+        let content = r#"
+package main
+
+import (
+	"google.golang.org/grpc"
+	pb "github.com/acme/protos/users/v1"
+)
+
+func main() {
+	conn, _ := grpc.Dial("users:50051")
+	client := pb.NewUserServiceClient(conn)
+	user, err := client.GetUser(ctx, &pb.GetUserRequest{Id: "1"})
+	// client.DeleteUser(ctx, req)
+
+	s := grpc.NewServer()
+	pb.RegisterOrderServiceServer(s, &server{})
+}
+"#;
+        let discoveries = parser.parse_file(Path::new("main.go"), content).unwrap();
+        let usages: Vec<_> = discoveries
+            .into_iter()
+            .filter_map(|d| match d {
+                Discovery::GrpcUsage(u) => Some(u),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].service, "UserService");
+        assert_eq!(usages[0].role, GrpcRole::Client);
+        assert_eq!(usages[0].methods, vec!["GetUser"]);
+        assert_eq!(usages[0].source_line, 11);
+        assert_eq!(usages[1].service, "OrderService");
+        assert_eq!(usages[1].role, GrpcRole::Server);
+    }
+
+    #[test]
+    fn test_go_generated_code_ignored() {
+        let parser = ProtoParser::new().unwrap();
+        let content = r#"// Code generated by protoc-gen-go-grpc. DO NOT EDIT.
+
+package usersv1
+
+import grpc "google.golang.org/grpc"
+
+func NewUserServiceClient(cc grpc.ClientConnInterface) UserServiceClient {
+	return &userServiceClient{cc}
+}
+"#;
+        assert!(
+            parser
+                .parse_file(Path::new("users_grpc.pb.go"), content)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_go_without_grpc_import_ignored() {
+        let parser = ProtoParser::new().unwrap();
+        let content = "package main\n\nfunc main() { c := NewHTTPClient() }\n";
+        assert!(
+            parser
+                .parse_file(Path::new("main.go"), content)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -3,17 +3,35 @@
 //! This parser uses tree-sitter to analyze Python files and detect:
 //! - Import statements (`import X` and `from X import Y`)
 //! - boto3 client/resource patterns for AWS services (DynamoDB, S3, SQS, SNS, Lambda, EventBridge)
-//! - DynamoDB method calls (get_item, put_item, query, scan, etc.)
+//! - DynamoDB method calls (get_item, put_item, query, scan, etc.) and SQS
+//!   messages sent or received by queue URL
+//! - Table names and queue URLs held in constants or environment variables
 //! - HTTP client usage (requests, httpx)
+//! - AWS CDK constructs, grants and event sources
+//! - Generated gRPC stubs and servicer registrations
+//! - Relational database access (SQLAlchemy, Django ORM, psycopg, asyncpg)
+//! - HTTP routes (FastAPI, Flask, Django `urlpatterns`)
+//! - Message brokers (Kafka, RabbitMQ via pika, Redis streams, Celery tasks)
 //! - Service metadata from pyproject.toml, setup.py, requirements.txt
 //!
 //! The parser is deterministic - it uses only AST analysis with no LLM calls.
 
+use super::cdk::{CdkCollector, CdkImports, ConstructCall, construct_kind, normalize_name};
+use super::dataflow::{Bindings, BoundValue, resource_name};
+use super::grpc::{GrpcCollector, service_from_class};
+use super::messaging::{
+    CELERY, KAFKA, MessagingCollector, RABBITMQ, REDIS_STREAM, celery_task_queue,
+    redis_stream_operation,
+};
+use super::routes::{HTTP_METHODS, RouteCollector, join_route};
+use super::rules::{DetectionRule, RuleSet};
+use super::sql::{SqlCollector, db_type_from_text, looks_like_sql, snake_case};
 use super::traits::{
     ApiCallDiscovery, CloudResourceDiscovery, DatabaseAccessDiscovery, DatabaseOperation,
-    Discovery, ImportDiscovery, Parser, ParserError, QueueOperationDiscovery, QueueOperationType,
-    ServiceDiscovery,
+    Discovery, GrpcRole, ImportDiscovery, Parser, ParserError, QueueOperationDiscovery,
+    QueueOperationType, RepoParse, ServiceDiscovery, is_ignored_dir, repo_files,
 };
+use crate::cache::DiscoveryCache;
 use std::any::Any;
 use std::collections::HashMap;
 use std::path::Path;
@@ -29,6 +47,8 @@ use tree_sitter::{Language, Node, Parser as TSParser, Query, QueryCursor};
 /// - DynamoDB operations
 pub struct PythonParser {
     language: Language,
+    /// User-defined detection rules
+    rules: RuleSet,
 }
 
 impl PythonParser {
@@ -37,6 +57,16 @@ impl PythonParser {
     /// # Errors
     /// Returns an error if tree-sitter initialization fails.
     pub fn new() -> Result<Self, ParserError> {
+        Self::with_rules(&[])
+    }
+
+    /// Create a Python parser that also applies user-defined detection
+    /// rules.
+    ///
+    /// # Errors
+    /// Returns an error if tree-sitter initialization fails or a rule is
+    /// invalid.
+    pub fn with_rules(rules: &[DetectionRule]) -> Result<Self, ParserError> {
         let language = tree_sitter_python::LANGUAGE.into();
 
         // Verify the language is valid by trying to set it on a parser
@@ -45,7 +75,9 @@ impl PythonParser {
             .set_language(&language)
             .map_err(|e| ParserError::TreeSitterError(format!("Failed to set language: {}", e)))?;
 
-        Ok(Self { language })
+        let rules = RuleSet::python(rules, &language)?;
+
+        Ok(Self { language, rules })
     }
 
     /// Parse pyproject.toml, setup.py, or requirements.txt to extract service metadata.
@@ -412,12 +444,10 @@ impl PythonParser {
                                 }
                             }
                         }
-                        "dotted_name" => {
-                            // Skip the first dotted_name which is the module name
-                            if i > 0 {
-                                if let Ok(text) = child.utf8_text(content.as_bytes()) {
-                                    items.push(text.to_string());
-                                }
+                        // Skip the first dotted_name which is the module name
+                        "dotted_name" if i > 0 => {
+                            if let Ok(text) = child.utf8_text(content.as_bytes()) {
+                                items.push(text.to_string());
                             }
                         }
                         _ => {}
@@ -672,9 +702,15 @@ impl PythonParser {
     ) -> Vec<Discovery> {
         let mut discoveries = Vec::new();
 
+        // SQLAlchemy's `session.query()` shares DynamoDB's method names
+        if !content.contains("boto3") && SQL_LIBRARIES.iter().any(|l| content.contains(l)) {
+            return discoveries;
+        }
+
         // First pass: collect variable -> table name mappings
         // e.g., `table = dynamodb.Table('my-table')` maps "table" -> "my-table"
-        let table_mappings = self.collect_table_assignments(tree.root_node(), content);
+        let bindings = collect_py_bindings(tree.root_node(), content);
+        let table_mappings = self.collect_table_assignments(tree.root_node(), content, &bindings);
 
         self.walk_for_dynamodb_methods(
             tree.root_node(),
@@ -682,6 +718,7 @@ impl PythonParser {
             path,
             &mut discoveries,
             &table_mappings,
+            &bindings,
         );
 
         discoveries
@@ -689,9 +726,14 @@ impl PythonParser {
 
     /// Collect table variable assignments (e.g., `table = dynamodb.Table('name')`).
     /// Returns a mapping of variable name to table name.
-    fn collect_table_assignments(&self, node: Node, content: &str) -> HashMap<String, String> {
+    fn collect_table_assignments(
+        &self,
+        node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> HashMap<String, String> {
         let mut mappings = HashMap::new();
-        self.walk_for_table_assignments(node, content, &mut mappings);
+        self.walk_for_table_assignments(node, content, bindings, &mut mappings);
         mappings
     }
 
@@ -700,19 +742,20 @@ impl PythonParser {
         &self,
         node: Node,
         content: &str,
+        bindings: &Bindings,
         mappings: &mut HashMap<String, String>,
     ) {
         // Look for assignment patterns:
         // - expression_statement containing assignment (Python 3)
         // - assignment itself
         if node.kind() == "expression_statement" || node.kind() == "assignment" {
-            self.check_table_assignment(node, content, mappings);
+            self.check_table_assignment(node, content, bindings, mappings);
         }
 
         // Recursively walk children
         for i in 0..node.named_child_count() {
             if let Some(child) = node.named_child(i) {
-                self.walk_for_table_assignments(child, content, mappings);
+                self.walk_for_table_assignments(child, content, bindings, mappings);
             }
         }
     }
@@ -722,6 +765,7 @@ impl PythonParser {
         &self,
         node: Node,
         content: &str,
+        bindings: &Bindings,
         mappings: &mut HashMap<String, String>,
     ) {
         // Find the assignment node (may be direct or child of expression_statement)
@@ -745,7 +789,7 @@ impl PythonParser {
                     // Right should be a call expression
                     if right_node.kind() == "call" {
                         if let Some(table_name) =
-                            self.extract_table_name_from_call(right_node, content)
+                            self.extract_table_name_from_call(right_node, content, bindings)
                         {
                             mappings.insert(var_name.to_string(), table_name);
                         }
@@ -756,7 +800,15 @@ impl PythonParser {
     }
 
     /// Extract table name from a call like `dynamodb.Table('my-table')` or `resource.Table('my-table')`.
-    fn extract_table_name_from_call(&self, call_node: Node, content: &str) -> Option<String> {
+    ///
+    /// The name may also be a constant or environment variable bound in the
+    /// file (`dynamodb.Table(TABLE_NAME)`).
+    fn extract_table_name_from_call(
+        &self,
+        call_node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         // Check if this is a .Table() call
         if let Some(function_node) = call_node.child_by_field_name("function") {
             if function_node.kind() == "attribute" {
@@ -765,6 +817,11 @@ impl PythonParser {
                     if method == "Table" {
                         // Extract the first argument as the table name
                         if let Some(args_node) = call_node.child_by_field_name("arguments") {
+                            let bound = argument(args_node, 0, "name", content)
+                                .and_then(|arg| py_bound_value(arg, content, bindings));
+                            if let Some(bound) = bound {
+                                return Some(bound.into_name());
+                            }
                             return self.extract_first_string_arg(args_node, content);
                         }
                     }
@@ -782,6 +839,7 @@ impl PythonParser {
         path: &Path,
         discoveries: &mut Vec<Discovery>,
         table_mappings: &HashMap<String, String>,
+        bindings: &Bindings,
     ) {
         if node.kind() == "call" {
             if let Some(function_node) = node.child_by_field_name("function") {
@@ -809,7 +867,7 @@ impl PythonParser {
                                 let mut table_name = if let Some(args_node) =
                                     node.child_by_field_name("arguments")
                                 {
-                                    self.extract_table_name(args_node, content)
+                                    self.extract_table_name(args_node, content, bindings)
                                 } else {
                                     None
                                 };
@@ -833,6 +891,7 @@ impl PythonParser {
                                     DatabaseAccessDiscovery {
                                         db_type: "dynamodb".to_string(),
                                         table_name,
+                                        connection: None,
                                         operation: *operation,
                                         detection_method: format!("boto3.{}", method),
                                         source_file: path.to_string_lossy().to_string(),
@@ -851,13 +910,25 @@ impl PythonParser {
         // Recursively walk children
         for i in 0..node.named_child_count() {
             if let Some(child) = node.named_child(i) {
-                self.walk_for_dynamodb_methods(child, content, path, discoveries, table_mappings);
+                self.walk_for_dynamodb_methods(
+                    child,
+                    content,
+                    path,
+                    discoveries,
+                    table_mappings,
+                    bindings,
+                );
             }
         }
     }
 
     /// Extract table name from DynamoDB method call arguments.
-    fn extract_table_name(&self, args_node: Node, content: &str) -> Option<String> {
+    fn extract_table_name(
+        &self,
+        args_node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
         // Look for TableName='xxx' or TableName="xxx" in the arguments
         // This can be a keyword argument or part of a dict
         for i in 0..args_node.named_child_count() {
@@ -878,13 +949,20 @@ impl PythonParser {
                                                 .to_string(),
                                         );
                                     }
+                                    if let Some(bound) =
+                                        py_bound_value(value_node, content, bindings)
+                                    {
+                                        return Some(bound.into_name());
+                                    }
                                 }
                             }
                         }
                     }
                     "dictionary" => {
                         // Look for 'TableName': '...' in a dict
-                        if let Some(table_name) = self.find_table_name_in_dict(child, content) {
+                        if let Some(table_name) =
+                            self.find_table_name_in_dict(child, content, bindings)
+                        {
                             return Some(table_name);
                         }
                     }
@@ -892,36 +970,1342 @@ impl PythonParser {
                 }
             }
         }
-        None
+        None
+    }
+
+    /// Find TableName in a dictionary literal.
+    fn find_table_name_in_dict(
+        &self,
+        dict_node: Node,
+        content: &str,
+        bindings: &Bindings,
+    ) -> Option<String> {
+        for i in 0..dict_node.named_child_count() {
+            if let Some(child) = dict_node.named_child(i) {
+                if child.kind() == "pair" {
+                    if let Some(key_node) = child.child_by_field_name("key") {
+                        let key = key_node
+                            .utf8_text(content.as_bytes())
+                            .unwrap_or("")
+                            .trim_matches(|c| c == '"' || c == '\'');
+                        if key == "TableName" {
+                            if let Some(value_node) = child.child_by_field_name("value") {
+                                if value_node.kind() == "string" {
+                                    let value =
+                                        value_node.utf8_text(content.as_bytes()).unwrap_or("");
+                                    return Some(
+                                        value.trim_matches(|c| c == '"' || c == '\'').to_string(),
+                                    );
+                                }
+                                if let Some(bound) = py_bound_value(value_node, content, bindings) {
+                                    return Some(bound.into_name());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Detect SQS messages sent and received through a boto3 client
+    /// (`sqs.send_message(QueueUrl=QUEUE_URL, ...)`).
+    ///
+    /// Only calls whose queue URL resolves to a literal or an environment
+    /// variable are reported; the client itself is reported by
+    /// [`Self::detect_boto3_clients`].
+    fn detect_sqs_operations(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        if !content.contains("QueueUrl") {
+            return Vec::new();
+        }
+        let bindings = collect_py_bindings(tree.root_node(), content);
+        let mut discoveries = Vec::new();
+        self.walk_for_sqs_operations(tree.root_node(), content, path, &bindings, &mut discoveries);
+        discoveries
+    }
+
+    fn walk_for_sqs_operations(
+        &self,
+        node: Node,
+        content: &str,
+        path: &Path,
+        bindings: &Bindings,
+        discoveries: &mut Vec<Discovery>,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        if node.kind() == "call" {
+            let method = node
+                .child_by_field_name("function")
+                .filter(|f| f.kind() == "attribute")
+                .and_then(|f| f.child_by_field_name("attribute"))
+                .map(text);
+            let operation = match method {
+                Some("send_message" | "send_message_batch") => Some(QueueOperationType::Publish),
+                Some(
+                    "receive_message"
+                    | "delete_message"
+                    | "delete_message_batch"
+                    | "change_message_visibility",
+                ) => Some(QueueOperationType::Subscribe),
+                _ => None,
+            };
+            let queue_name = node
+                .child_by_field_name("arguments")
+                .and_then(|args| keyword_value(args, "QueueUrl", content))
+                .and_then(|url| py_bound_value(url, content, bindings))
+                .map(|url| match url {
+                    BoundValue::Literal(url) => resource_name(&url).to_string(),
+                    env => env.into_name(),
+                });
+            if let (Some(operation), Some(queue_name)) = (operation, queue_name) {
+                discoveries.push(Discovery::QueueOperation(QueueOperationDiscovery {
+                    queue_type: "sqs".to_string(),
+                    queue_name: Some(queue_name),
+                    operation,
+                    source_file: path.to_string_lossy().to_string(),
+                    source_line: node.start_position().row as u32 + 1,
+                    deployment_metadata: None,
+                }));
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.walk_for_sqs_operations(child, content, path, bindings, discoveries);
+        }
+    }
+
+    /// Detect AWS CDK constructs, grants and event sources.
+    ///
+    /// Only files importing `aws_cdk` are considered. See [`super::cdk`] for
+    /// how constructs map onto discoveries.
+    fn detect_cdk_constructs(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let root = tree.root_node();
+
+        let mut imports = CdkImports::default();
+        self.collect_cdk_imports(root, content, &mut imports);
+        if imports.is_empty() {
+            return Vec::new();
+        }
+
+        let mut collector = CdkCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_cdk(root, content, &imports, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Collect CDK module aliases and class imports.
+    fn collect_cdk_imports(&self, node: Node, content: &str, imports: &mut CdkImports) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        match node.kind() {
+            // import aws_cdk.aws_sqs as sqs
+            "import_statement" => {
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    match name.kind() {
+                        "aliased_import" => {
+                            if let (Some(module), Some(alias)) = (
+                                name.child_by_field_name("name"),
+                                name.child_by_field_name("alias"),
+                            ) {
+                                imports.add_module(text(alias), text(module));
+                            }
+                        }
+                        _ => imports.add_module(text(name), text(name)),
+                    }
+                }
+            }
+            // from aws_cdk import aws_dynamodb as dynamodb
+            // from aws_cdk.aws_lambda_event_sources import SqsEventSource
+            "import_from_statement" => {
+                let Some(module) = node.child_by_field_name("module_name") else {
+                    return;
+                };
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    match name.kind() {
+                        "aliased_import" => {
+                            if let (Some(imported), Some(alias)) = (
+                                name.child_by_field_name("name"),
+                                name.child_by_field_name("alias"),
+                            ) {
+                                imports.add_named(text(alias), text(imported), text(module));
+                            }
+                        }
+                        _ => imports.add_named(text(name), text(name), text(module)),
+                    }
+                }
+            }
+            _ => {
+                for i in 0..node.named_child_count() {
+                    if let Some(child) = node.named_child(i) {
+                        self.collect_cdk_imports(child, content, imports);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Walk the AST looking for construct instantiations, grants and event sources.
+    fn walk_for_cdk(
+        &self,
+        node: Node,
+        content: &str,
+        imports: &CdkImports,
+        collector: &mut CdkCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        if node.kind() == "call" {
+            if let (Some(function), Some(args)) = (
+                node.child_by_field_name("function"),
+                node.child_by_field_name("arguments"),
+            ) {
+                let positional: Vec<Node> = (0..args.named_child_count())
+                    .filter_map(|i| args.named_child(i))
+                    .filter(|a| a.kind() != "keyword_argument" && a.kind() != "comment")
+                    .collect();
+
+                // dynamodb.Table(self, "Orders", table_name="orders")
+                if let Some(kind) = imports
+                    .resolve(text(function))
+                    .and_then(|(service, class)| construct_kind(&service, &class))
+                {
+                    let binding = node
+                        .parent()
+                        .filter(|p| p.kind() == "assignment")
+                        .and_then(|p| p.child_by_field_name("left"))
+                        .map(text);
+                    let construct_id = positional
+                        .get(1)
+                        .filter(|a| a.kind() == "string")
+                        .map(|a| python_string_value(*a, content));
+
+                    collector.add_construct(
+                        kind,
+                        ConstructCall {
+                            binding,
+                            construct_id,
+                            props: self.keyword_props(args, content),
+                            line,
+                        },
+                    );
+                } else if function.kind() == "attribute" {
+                    // table.grant_write_data(fn) / fn.add_event_source(SqsEventSource(queue))
+                    let receiver = function.child_by_field_name("object").map(text);
+                    let method = function.child_by_field_name("attribute").map(text);
+
+                    if let (Some(receiver), Some(method), Some(arg)) =
+                        (receiver, method, positional.first())
+                    {
+                        if method.starts_with("grant") {
+                            collector.add_grant(receiver, method, text(*arg), line);
+                        } else if method == "add_event_source" && arg.kind() == "call" {
+                            let class = arg
+                                .child_by_field_name("function")
+                                .map(|f| text(f).rsplit('.').next().unwrap_or(""));
+                            let source = arg
+                                .child_by_field_name("arguments")
+                                .and_then(|a| a.named_child(0));
+                            if let (Some(class), Some(source)) = (class, source) {
+                                collector.add_event_source(receiver, class, text(source), line);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_cdk(child, content, imports, collector);
+            }
+        }
+    }
+
+    /// Keyword arguments keyed by normalized name.
+    ///
+    /// Plain string literals are unquoted; f-strings and other expressions
+    /// keep their source text.
+    fn keyword_props(&self, args: Node, content: &str) -> HashMap<String, String> {
+        let mut props = HashMap::new();
+        for i in 0..args.named_child_count() {
+            let Some(arg) = args
+                .named_child(i)
+                .filter(|a| a.kind() == "keyword_argument")
+            else {
+                continue;
+            };
+            let (Some(name), Some(value)) = (
+                arg.child_by_field_name("name"),
+                arg.child_by_field_name("value"),
+            ) else {
+                continue;
+            };
+
+            let value = if value.kind() == "string" {
+                python_string_value(value, content)
+            } else {
+                value
+                    .utf8_text(content.as_bytes())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            props.insert(
+                normalize_name(name.utf8_text(content.as_bytes()).unwrap_or("")),
+                value,
+            );
+        }
+        props
+    }
+
+    /// Detect generated gRPC stubs and servicer registrations.
+    ///
+    /// grpcio codegen names are distinctive (`UserServiceStub`,
+    /// `UserServiceServicer`, `add_UserServiceServicer_to_server`), so only
+    /// files mentioning grpc are checked and no import tracking is needed.
+    fn detect_grpc_usage(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        if !content.contains("grpc") {
+            return Vec::new();
+        }
+
+        let mut collector = GrpcCollector::new(path.to_string_lossy().to_string(), "grpcio");
+        self.walk_for_grpc(tree.root_node(), content, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Walk the AST looking for stubs, servicers and method calls on stubs.
+    fn walk_for_grpc(&self, node: Node, content: &str, collector: &mut GrpcCollector) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            "call" => {
+                if let Some(function) = node.child_by_field_name("function") {
+                    let name = text(function).rsplit('.').next().unwrap_or("");
+
+                    // users_pb2_grpc.UserServiceStub(channel)
+                    if let Some(service) = service_from_class(name, "Stub") {
+                        let binding = node
+                            .parent()
+                            .filter(|p| p.kind() == "assignment")
+                            .and_then(|p| p.child_by_field_name("left"))
+                            .map(text);
+                        collector.add_usage(GrpcRole::Client, service, binding, line);
+                    // add_UserServiceServicer_to_server(UserService(), server)
+                    } else if let Some(service) = name
+                        .strip_prefix("add_")
+                        .and_then(|n| n.strip_suffix("Servicer_to_server"))
+                        .filter(|n| !n.is_empty())
+                    {
+                        collector.add_usage(GrpcRole::Server, service, None, line);
+                    // stub.GetUser(request)
+                    } else if function.kind() == "attribute" {
+                        if let (Some(receiver), Some(method)) = (
+                            function.child_by_field_name("object"),
+                            function.child_by_field_name("attribute"),
+                        ) {
+                            collector.add_call(text(receiver), text(method));
+                        }
+                    }
+                }
+            }
+            // class UserService(users_pb2_grpc.UserServiceServicer)
+            "class_definition" => {
+                if let Some(bases) = node.child_by_field_name("superclasses") {
+                    for i in 0..bases.named_child_count() {
+                        let Some(base) = bases.named_child(i) else {
+                            continue;
+                        };
+                        if let Some(service) = service_from_class(text(base), "Servicer") {
+                            collector.add_usage(GrpcRole::Server, service, None, line);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_grpc(child, content, collector);
+            }
+        }
+    }
+
+    /// Detect relational database access: SQLAlchemy models and queries,
+    /// Django models and managers, and SQL executed with psycopg or asyncpg.
+    ///
+    /// Tables come from `__tablename__`, Django's `db_table` (or its
+    /// `{app}_{model}` default) and SQL string literals. DSN environment
+    /// variables read in the file identify the connection; see
+    /// [`super::sql`].
+    fn detect_sql_access(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        if !SQL_LIBRARIES
+            .iter()
+            .any(|library| content.contains(library))
+            && !content.contains(".objects.")
+        {
+            return Vec::new();
+        }
+
+        let db_type = db_type_from_text(content).unwrap_or("sql");
+        let driver = SQL_LIBRARIES
+            .iter()
+            .find(|library| content.contains(*library))
+            .copied()
+            .unwrap_or("sql");
+        let mut collector = SqlCollector::new(path.to_string_lossy().to_string(), db_type);
+
+        let mut models = HashMap::new();
+        self.collect_sql_models(tree.root_node(), content, path, &mut models);
+        let mut declared: Vec<&SqlModel> = models.values().filter(|m| m.declared).collect();
+        declared.sort_by_key(|model| (model.line, &model.table));
+        for model in declared {
+            collector.add_access(
+                &model.table,
+                DatabaseOperation::Unknown,
+                &format!("{}.model", model.orm),
+                model.line,
+            );
+        }
+
+        self.walk_for_sql(tree.root_node(), content, driver, &models, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Collect the ORM models declared in or imported into a file.
+    fn collect_sql_models(
+        &self,
+        node: Node,
+        content: &str,
+        path: &Path,
+        models: &mut HashMap<String, SqlModel>,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        match node.kind() {
+            "class_definition" => {
+                if let Some((class, model)) = self.sql_model(node, content, path) {
+                    models.insert(class, model);
+                }
+            }
+            // from orders.models import Order -> table "orders_order"
+            "import_from_statement" => {
+                let module = node.child_by_field_name("module_name");
+                let app = module.and_then(|module| match module.kind() {
+                    "relative_import" if text(module) == ".models" => django_app_label(path),
+                    "dotted_name" => text(module)
+                        .strip_suffix(".models")
+                        .map(|m| m.rsplit('.').next().unwrap_or(m).to_string()),
+                    _ => None,
+                });
+                if let Some(app) = app {
+                    for class in self.extract_imported_names(Some(node), content) {
+                        let table = format!("{}_{}", app, class.to_lowercase());
+                        models.entry(class).or_insert(SqlModel {
+                            table,
+                            orm: "django",
+                            declared: false,
+                            line: node.start_position().row as u32 + 1,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_sql_models(child, content, path, models);
+            }
+        }
+    }
+
+    /// The table of a SQLAlchemy or Django model class, if it is one.
+    fn sql_model(&self, class: Node, content: &str, path: &Path) -> Option<(String, SqlModel)> {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let name = text(class.child_by_field_name("name")?).to_string();
+        let body = class.child_by_field_name("body")?;
+        let bases: Vec<&str> = class
+            .child_by_field_name("superclasses")
+            .map(|bases| {
+                let mut cursor = bases.walk();
+                bases.named_children(&mut cursor).map(text).collect()
+            })
+            .unwrap_or_default();
+        let line = class.start_position().row as u32 + 1;
+
+        // class Order(Base): __tablename__ = "orders"
+        if let Some(table) = class_string_attribute(body, "__tablename__", content) {
+            return Some((
+                name,
+                SqlModel {
+                    table,
+                    orm: "sqlalchemy",
+                    declared: true,
+                    line,
+                },
+            ));
+        }
+
+        // Flask-SQLAlchemy names the table after the class: OrderItem -> order_item
+        if bases.contains(&"db.Model") {
+            let table = snake_case(&name);
+            return Some((
+                name,
+                SqlModel {
+                    table,
+                    orm: "sqlalchemy",
+                    declared: true,
+                    line,
+                },
+            ));
+        }
+
+        if bases
+            .iter()
+            .any(|b| *b == "models.Model" || b.ends_with(".models.Model"))
+        {
+            let mut cursor = body.walk();
+            let meta = body
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() == "class_definition")
+                .find(|c| c.child_by_field_name("name").map(text) == Some("Meta"))
+                .and_then(|meta| meta.child_by_field_name("body"));
+            let abstract_model = meta
+                .and_then(|meta| class_attribute(meta, "abstract", content))
+                .is_some_and(|value| text(value) == "True");
+            if abstract_model {
+                return None;
+            }
+            let table = meta
+                .and_then(|meta| class_string_attribute(meta, "db_table", content))
+                .or_else(|| {
+                    django_app_label(path).map(|app| format!("{}_{}", app, name.to_lowercase()))
+                })?;
+            return Some((
+                name,
+                SqlModel {
+                    table,
+                    orm: "django",
+                    declared: true,
+                    line,
+                },
+            ));
+        }
+
+        None
+    }
+
+    /// Walk the AST looking for DSN variables, ORM queries and SQL statements.
+    fn walk_for_sql(
+        &self,
+        node: Node,
+        content: &str,
+        driver: &str,
+        models: &HashMap<String, SqlModel>,
+        collector: &mut SqlCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            "call" => {
+                if let (Some(function), Some(args)) = (
+                    node.child_by_field_name("function"),
+                    node.child_by_field_name("arguments"),
+                ) {
+                    let callee = text(function);
+                    let method = callee.rsplit('.').next().unwrap_or(callee);
+                    let first_arg = args.named_child(0);
+                    let model = first_arg
+                        .filter(|arg| arg.kind() == "identifier")
+                        .and_then(|arg| models.get(text(arg)));
+
+                    // os.getenv("DATABASE_URL"), os.environ.get(...), env("...")
+                    if callee.ends_with("getenv")
+                        || callee.ends_with("environ.get")
+                        || matches!(callee, "env" | "config" | "env.str")
+                    {
+                        if let Some(name) = self.extract_first_string_arg(args, content) {
+                            collector.add_env_variable(&name, "os.environ", line);
+                        }
+                    // dj_database_url.config() and env.db() default to DATABASE_URL
+                    } else if callee.ends_with("dj_database_url.config")
+                        || matches!(callee, "env.db" | "env.db_url")
+                    {
+                        let name = keyword_string(args, "env", content)
+                            .or_else(|| self.extract_first_string_arg(args, content))
+                            .unwrap_or_else(|| "DATABASE_URL".to_string());
+                        collector.add_env_variable(&name, "dj_database_url", line);
+                    } else if let Some(model) = model.filter(|m| m.orm == "sqlalchemy") {
+                        // session.query(Order), select(Order), session.get(Order, id)
+                        let operation = match method {
+                            "query" | "select" | "get" => Some(DatabaseOperation::Read),
+                            "insert" | "delete" => Some(DatabaseOperation::Write),
+                            "update" => Some(DatabaseOperation::ReadWrite),
+                            _ => None,
+                        };
+                        if let Some(operation) = operation {
+                            collector.add_access(
+                                &model.table,
+                                operation,
+                                &format!("sqlalchemy.{}", method),
+                                line,
+                            );
+                        }
+                    } else if let Some(model) = function
+                        .child_by_field_name("object")
+                        .and_then(|object| manager_model(object, content))
+                        .and_then(|class| models.get(class))
+                    {
+                        // Order.objects.filter(...)
+                        if let Some(operation) = django_manager_operation(method) {
+                            collector.add_access(
+                                &model.table,
+                                operation,
+                                &format!("django.objects.{}", method),
+                                line,
+                            );
+                        }
+                    } else if SQL_EXECUTE_METHODS.contains(&method)
+                        && function.kind() == "attribute"
+                    {
+                        // cur.execute("SELECT ..."), conn.fetch("SELECT ...")
+                        if let Some(sql) = first_arg.and_then(|arg| sql_string(arg, content)) {
+                            if looks_like_sql(&sql) {
+                                collector.add_statement(
+                                    &sql,
+                                    &format!("{}.{}", driver, method),
+                                    line,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            // os.environ["DATABASE_URL"]
+            "subscript" => {
+                if let (Some(value), Some(key)) = (
+                    node.child_by_field_name("value"),
+                    node.child_by_field_name("subscript"),
+                ) {
+                    if text(value).ends_with("environ") && key.kind() == "string" {
+                        collector.add_env_variable(
+                            &python_string_value(key, content),
+                            "os.environ",
+                            line,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_sql(child, content, driver, models, collector);
+            }
+        }
+    }
+
+    /// Detect message broker usage: Kafka, RabbitMQ (pika), Redis streams
+    /// and Celery tasks. See [`super::messaging`].
+    fn detect_messaging(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let brokers = PythonBrokers {
+            kafka: content.contains("kafka"),
+            pika: content.contains("pika"),
+            redis: content.contains("redis"),
+            celery: content.contains("celery"),
+        };
+        let celery_calls = [".delay(", ".apply_async(", "send_task("]
+            .iter()
+            .any(|call| content.contains(call));
+        if !(brokers.kafka || brokers.pika || brokers.redis || brokers.celery || celery_calls) {
+            return Vec::new();
+        }
+
+        let mut collector = MessagingCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_messaging(tree.root_node(), content, &brokers, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Walk the AST looking for broker client calls and Celery tasks.
+    fn walk_for_messaging(
+        &self,
+        node: Node,
+        content: &str,
+        brokers: &PythonBrokers,
+        collector: &mut MessagingCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let line = node.start_position().row as u32 + 1;
+
+        match node.kind() {
+            // @app.task / @shared_task(name="orders.process_order")
+            "decorated_definition" if brokers.celery => {
+                let mut cursor = node.walk();
+                let task = node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "decorator")
+                    .filter_map(|d| d.named_child(0))
+                    .find_map(|expression| {
+                        let callee = match expression.kind() {
+                            "call" => expression.child_by_field_name("function")?,
+                            _ => expression,
+                        };
+                        let callee = text(callee);
+                        (callee == "shared_task" || callee.ends_with(".task"))
+                            .then(|| expression.child_by_field_name("arguments"))
+                    });
+                if let Some(arguments) = task {
+                    let name = arguments
+                        .and_then(|args| keyword_string(args, "name", content))
+                        .or_else(|| {
+                            node.child_by_field_name("definition")
+                                .and_then(|d| d.child_by_field_name("name"))
+                                .map(|n| text(n).to_string())
+                        });
+                    if let Some(name) = name {
+                        collector.add(
+                            CELERY,
+                            celery_task_queue(&name),
+                            QueueOperationType::Subscribe,
+                            line,
+                        );
+                    }
+                }
+            }
+            "call" => {
+                if let (Some(function), Some(args)) = (
+                    node.child_by_field_name("function"),
+                    node.child_by_field_name("arguments"),
+                ) {
+                    self.check_messaging_call(function, args, content, brokers, collector, line);
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_messaging(child, content, brokers, collector);
+            }
+        }
+    }
+
+    /// Record the broker operation a call makes, if any.
+    fn check_messaging_call(
+        &self,
+        function: Node,
+        args: Node,
+        content: &str,
+        brokers: &PythonBrokers,
+        collector: &mut MessagingCollector,
+        line: u32,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+        let string = |index: usize, keyword: &str| {
+            argument(args, index, keyword, content).and_then(|n| literal_string(n, content))
+        };
+        let callee = text(function);
+        let method = callee.rsplit('.').next().unwrap_or(callee);
+        let receiver = function
+            .child_by_field_name("object")
+            .filter(|_| function.kind() == "attribute");
+
+        match method {
+            // process_order.delay(order_id), tasks.process_order.apply_async(...)
+            "delay" | "apply_async" => {
+                if let Some(task) = receiver
+                    .filter(|r| matches!(r.kind(), "identifier" | "attribute"))
+                    .filter(|r| text(*r) != "self")
+                {
+                    let task = text(task);
+                    collector.add(
+                        CELERY,
+                        celery_task_queue(task),
+                        QueueOperationType::Publish,
+                        line,
+                    );
+                }
+            }
+            // app.send_task("orders.tasks.process_order")
+            "send_task" => {
+                if let Some(task) = string(0, "name") {
+                    collector.add(
+                        CELERY,
+                        celery_task_queue(&task),
+                        QueueOperationType::Publish,
+                        line,
+                    );
+                }
+            }
+            // producer.produce("orders", value), producer.send("orders", value)
+            "produce" | "send" | "send_and_wait" if brokers.kafka && receiver.is_some() => {
+                if let Some(topic) = string(0, "topic") {
+                    collector.add(KAFKA, &topic, QueueOperationType::Publish, line);
+                }
+            }
+            // consumer.subscribe(["orders"]), consumer.subscribe(topics=["orders"])
+            "subscribe" if brokers.kafka && receiver.is_some() => {
+                if let Some(topics) = argument(args, 0, "topics", content) {
+                    for topic in string_items(topics, content) {
+                        collector.add(KAFKA, &topic, QueueOperationType::Subscribe, line);
+                    }
+                }
+            }
+            // KafkaConsumer("orders", "payments", bootstrap_servers=...)
+            "KafkaConsumer" | "AIOKafkaConsumer" if brokers.kafka => {
+                let mut cursor = args.walk();
+                for topic in args
+                    .named_children(&mut cursor)
+                    .filter_map(|arg| literal_string(arg, content))
+                {
+                    collector.add(KAFKA, &topic, QueueOperationType::Subscribe, line);
+                }
+            }
+            // channel.basic_publish(exchange="", routing_key="emails", body=...)
+            "basic_publish" if brokers.pika => {
+                if let Some(exchange) = string(0, "exchange") {
+                    let routing_key = string(1, "routing_key");
+                    collector.add_rabbitmq_publish(&exchange, routing_key.as_deref(), line);
+                }
+            }
+            // channel.basic_consume(queue="emails", on_message_callback=handle)
+            "basic_consume" if brokers.pika => {
+                if let Some(queue) = string(0, "queue") {
+                    collector.add(RABBITMQ, &queue, QueueOperationType::Subscribe, line);
+                }
+            }
+            // channel.queue_bind(queue="audit", exchange="events")
+            "queue_bind" if brokers.pika => {
+                if let Some(exchange) = string(1, "exchange") {
+                    collector.add(RABBITMQ, &exchange, QueueOperationType::Subscribe, line);
+                }
+            }
+            // r.xadd("orders", fields), r.xreadgroup("group", "c1", {"orders": ">"})
+            _ if brokers.redis && receiver.is_some() => match redis_stream_operation(method) {
+                Some(QueueOperationType::Publish) => {
+                    if let Some(stream) = string(0, "name") {
+                        collector.add(REDIS_STREAM, &stream, QueueOperationType::Publish, line);
+                    }
+                }
+                Some(operation) => {
+                    let index = if method == "xreadgroup" { 2 } else { 0 };
+                    if let Some(streams) = argument(args, index, "streams", content)
+                        .filter(|s| s.kind() == "dictionary")
+                    {
+                        let mut cursor = streams.walk();
+                        for stream in streams
+                            .named_children(&mut cursor)
+                            .filter_map(|pair| pair.child_by_field_name("key"))
+                            .filter_map(|key| literal_string(key, content))
+                        {
+                            collector.add(REDIS_STREAM, &stream, operation, line);
+                        }
+                    }
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Detect HTTP routes declared with FastAPI, Flask or Django.
+    /// See [`super::routes`].
+    fn detect_routes(
+        &self,
+        tree: &tree_sitter::Tree,
+        content: &str,
+        path: &Path,
+    ) -> Vec<Discovery> {
+        let framework = if content.contains("fastapi") {
+            Some("fastapi")
+        } else if content.contains("flask") {
+            Some("flask")
+        } else {
+            None
+        };
+        let django = content.contains("urlpatterns");
+        if framework.is_none() && !django {
+            return Vec::new();
+        }
+
+        let root = tree.root_node();
+        // Route modules often use an app or router created elsewhere
+        let mut prefixes: HashMap<String, String> = ["app", "api", "router", "bp", "blueprint"]
+            .iter()
+            .map(|name| (name.to_string(), String::new()))
+            .collect();
+        self.collect_route_prefixes(root, content, &mut prefixes);
+
+        let mut collector = RouteCollector::new(path.to_string_lossy().to_string());
+        self.walk_for_routes(root, content, framework, django, &prefixes, &mut collector);
+        collector.into_discoveries()
+    }
+
+    /// Collect applications and routers with the path prefix they apply:
+    /// `APIRouter(prefix="/users")`, `Blueprint("users", __name__, url_prefix="/users")`.
+    fn collect_route_prefixes(
+        &self,
+        node: Node,
+        content: &str,
+        prefixes: &mut HashMap<String, String>,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        if node.kind() == "assignment" {
+            let name = node.child_by_field_name("left").map(text);
+            let call = node
+                .child_by_field_name("right")
+                .filter(|r| r.kind() == "call");
+            let constructor = call
+                .and_then(|c| c.child_by_field_name("function"))
+                .map(|f| {
+                    let callee = text(f);
+                    callee.rsplit('.').next().unwrap_or(callee)
+                });
+            let args = call.and_then(|c| c.child_by_field_name("arguments"));
+            if let (Some(name), Some(constructor), Some(args)) = (name, constructor, args) {
+                let prefix = match constructor {
+                    "FastAPI" | "Flask" => Some(String::new()),
+                    "APIRouter" => {
+                        Some(keyword_string(args, "prefix", content).unwrap_or_default())
+                    }
+                    "Blueprint" => {
+                        Some(keyword_string(args, "url_prefix", content).unwrap_or_default())
+                    }
+                    _ => None,
+                };
+                if let Some(prefix) = prefix {
+                    prefixes.insert(name.to_string(), prefix);
+                }
+            }
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.collect_route_prefixes(child, content, prefixes);
+            }
+        }
+    }
+
+    /// Walk the AST looking for route decorators and Django `urlpatterns`.
+    fn walk_for_routes(
+        &self,
+        node: Node,
+        content: &str,
+        framework: Option<&str>,
+        django: bool,
+        prefixes: &HashMap<String, String>,
+        collector: &mut RouteCollector,
+    ) {
+        let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+
+        match node.kind() {
+            // @app.get("/users/{id}"), @bp.route("/users", methods=["POST"])
+            "decorated_definition" if framework.is_some() => {
+                let framework = framework.unwrap_or_default();
+                let mut cursor = node.walk();
+                for decorator in node
+                    .named_children(&mut cursor)
+                    .filter(|c| c.kind() == "decorator")
+                {
+                    let Some(call) = decorator.named_child(0).filter(|e| e.kind() == "call") else {
+                        continue;
+                    };
+                    let (Some(function), Some(args)) = (
+                        call.child_by_field_name("function")
+                            .filter(|f| f.kind() == "attribute"),
+                        call.child_by_field_name("arguments"),
+                    ) else {
+                        continue;
+                    };
+                    let receiver = function.child_by_field_name("object").map(text);
+                    let Some(prefix) = receiver.and_then(|r| prefixes.get(r)) else {
+                        continue;
+                    };
+                    let method = function
+                        .child_by_field_name("attribute")
+                        .map(text)
+                        .unwrap_or("");
+                    let methods = match method {
+                        "route" | "api_route" => keyword_value(args, "methods", content)
+                            .map(|m| string_items(m, content))
+                            .unwrap_or_else(|| vec!["GET".to_string()]),
+                        method if HTTP_METHODS.contains(&method) => vec![method.to_string()],
+                        _ => continue,
+                    };
+                    let Some(route) = argument(args, 0, "path", content)
+                        .or_else(|| keyword_value(args, "rule", content))
+                        .and_then(|r| literal_string(r, content))
+                    else {
+                        continue;
+                    };
+                    let line = decorator.start_position().row as u32 + 1;
+                    for method in methods {
+                        collector.add(&method, &join_route(prefix, &route), framework, line);
+                    }
+                }
+            }
+            // urlpatterns = [path("users/<int:pk>/", views.detail), ...]
+            "assignment" | "augmented_assignment" if django => {
+                let is_urlpatterns = node
+                    .child_by_field_name("left")
+                    .is_some_and(|left| text(left) == "urlpatterns");
+                let patterns = node
+                    .child_by_field_name("right")
+                    .filter(|r| r.kind() == "list" && is_urlpatterns);
+                if let Some(patterns) = patterns {
+                    let mut cursor = patterns.walk();
+                    for pattern in patterns
+                        .named_children(&mut cursor)
+                        .filter(|p| p.kind() == "call")
+                    {
+                        let function = pattern.child_by_field_name("function").map(text);
+                        let args = pattern.child_by_field_name("arguments");
+                        let (Some("path" | "re_path" | "url"), Some(args)) = (function, args)
+                        else {
+                            continue;
+                        };
+                        // Prefixes of included URLconfs are not followed
+                        let includes = argument(args, 1, "view", content).is_some_and(|view| {
+                            view.kind() == "call"
+                                && view
+                                    .child_by_field_name("function")
+                                    .is_some_and(|f| text(f) == "include")
+                        });
+                        let route = argument(args, 0, "route", content)
+                            .and_then(|r| literal_string(r, content));
+                        if let (false, Some(route)) = (includes, route) {
+                            let line = pattern.start_position().row as u32 + 1;
+                            collector.add("ANY", &route, "django", line);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        for i in 0..node.named_child_count() {
+            if let Some(child) = node.named_child(i) {
+                self.walk_for_routes(child, content, framework, django, prefixes, collector);
+            }
+        }
+    }
+}
+
+/// Message brokers whose client libraries a Python file mentions.
+struct PythonBrokers {
+    kafka: bool,
+    pika: bool,
+    redis: bool,
+    celery: bool,
+}
+
+/// Libraries whose presence makes a file worth scanning for SQL access, in
+/// the order used to name the driver executing raw SQL.
+const SQL_LIBRARIES: &[&str] = &["asyncpg", "psycopg", "sqlalchemy", "django"];
+
+/// Driver and session methods that take a SQL string.
+const SQL_EXECUTE_METHODS: &[&str] = &["execute", "executemany", "fetch", "fetchrow", "fetchval"];
+
+/// An ORM model class and its table.
+struct SqlModel {
+    table: String,
+    orm: &'static str,
+    /// Declared in this file, rather than imported
+    declared: bool,
+    line: u32,
+}
+
+/// The value assigned to `name` in a class body.
+fn class_attribute<'t>(body: Node<'t>, name: &str, content: &str) -> Option<Node<'t>> {
+    let mut cursor = body.walk();
+    body.named_children(&mut cursor)
+        .filter_map(|statement| statement.named_child(0))
+        .filter(|assignment| assignment.kind() == "assignment")
+        .find(|assignment| {
+            assignment
+                .child_by_field_name("left")
+                .and_then(|left| left.utf8_text(content.as_bytes()).ok())
+                == Some(name)
+        })
+        .and_then(|assignment| assignment.child_by_field_name("right"))
+}
+
+/// The string assigned to `name` in a class body.
+fn class_string_attribute(body: Node, name: &str, content: &str) -> Option<String> {
+    class_attribute(body, name, content)
+        .filter(|value| value.kind() == "string")
+        .map(|value| python_string_value(value, content))
+}
+
+/// The string value of keyword argument `name`.
+fn keyword_string(args: Node, name: &str, content: &str) -> Option<String> {
+    keyword_value(args, name, content)
+        .filter(|value| value.kind() == "string")
+        .map(|value| python_string_value(value, content))
+}
+
+/// The SQL in a string literal, implicitly concatenated literals, or a
+/// SQLAlchemy `text("...")` call.
+fn sql_string(node: Node, content: &str) -> Option<String> {
+    match node.kind() {
+        "string" => Some(python_string_value(node, content)),
+        "concatenated_string" => {
+            let mut cursor = node.walk();
+            let parts: Vec<String> = node
+                .named_children(&mut cursor)
+                .map(|part| python_string_value(part, content))
+                .collect();
+            Some(parts.join(" "))
+        }
+        "call" => {
+            let function = node.child_by_field_name("function")?;
+            let callee = function.utf8_text(content.as_bytes()).ok()?;
+            if callee == "text" || callee.ends_with(".text") {
+                sql_string(
+                    node.child_by_field_name("arguments")?.named_child(0)?,
+                    content,
+                )
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The model class a Django manager call chain starts from.
+///
+/// `Order.objects.filter(...).first()` → `Order`.
+fn manager_model<'c>(mut node: Node, content: &'c str) -> Option<&'c str> {
+    loop {
+        match node.kind() {
+            "attribute" => {
+                let attribute = node.child_by_field_name("attribute")?;
+                let object = node.child_by_field_name("object")?;
+                if attribute.utf8_text(content.as_bytes()).ok()? == "objects" {
+                    return object
+                        .utf8_text(content.as_bytes())
+                        .ok()
+                        .filter(|_| object.kind() == "identifier");
+                }
+                node = object;
+            }
+            "call" => node = node.child_by_field_name("function")?,
+            _ => return None,
+        }
+    }
+}
+
+/// How a Django manager or queryset method accesses its table.
+fn django_manager_operation(method: &str) -> Option<DatabaseOperation> {
+    match method {
+        "get" | "filter" | "exclude" | "all" | "first" | "last" | "count" | "exists" | "values"
+        | "values_list" | "aggregate" | "annotate" | "in_bulk" | "iterator" | "latest"
+        | "earliest" | "raw" => Some(DatabaseOperation::Read),
+        "create" | "bulk_create" | "delete" => Some(DatabaseOperation::Write),
+        "update" | "bulk_update" | "get_or_create" | "update_or_create" => {
+            Some(DatabaseOperation::ReadWrite)
+        }
+        _ => None,
+    }
+}
+
+/// The Django app a file belongs to: the directory holding `models.py` (or
+/// the `models` package).
+fn django_app_label(path: &Path) -> Option<String> {
+    let mut dir = path.parent()?;
+    if dir.file_name().is_some_and(|name| name == "models") {
+        dir = dir.parent()?;
+    }
+    dir.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// The value of a keyword argument.
+fn keyword_value<'t>(args: Node<'t>, name: &str, content: &str) -> Option<Node<'t>> {
+    let mut cursor = args.walk();
+    args.named_children(&mut cursor)
+        .filter(|arg| arg.kind() == "keyword_argument")
+        .find(|arg| {
+            arg.child_by_field_name("name")
+                .and_then(|n| n.utf8_text(content.as_bytes()).ok())
+                == Some(name)
+        })
+        .and_then(|arg| arg.child_by_field_name("value"))
+}
+
+/// A call argument given by position or by keyword.
+fn argument<'t>(args: Node<'t>, index: usize, keyword: &str, content: &str) -> Option<Node<'t>> {
+    keyword_value(args, keyword, content).or_else(|| {
+        let mut cursor = args.walk();
+        args.named_children(&mut cursor)
+            .filter(|arg| arg.kind() != "keyword_argument")
+            .nth(index)
+    })
+}
+
+/// Value of a string literal without interpolation.
+fn literal_string(node: Node, content: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let interpolated = node
+        .named_children(&mut cursor)
+        .any(|c| c.kind() == "interpolation");
+    (node.kind() == "string" && !interpolated).then(|| python_string_value(node, content))
+}
+
+/// Record the string constants and environment variables bound to names
+/// (and `self.` attributes) in a file. See [`super::dataflow`].
+fn collect_py_bindings(root: Node, content: &str) -> Bindings {
+    fn walk(node: Node, content: &str, bindings: &mut Bindings) {
+        if node.kind() == "assignment" {
+            let left = node.child_by_field_name("left");
+            let right = node.child_by_field_name("right");
+            if let (Some(left), Some(right)) = (left, right) {
+                let name = left.utf8_text(content.as_bytes()).unwrap_or("");
+                let bindable = left.kind() == "identifier"
+                    || (left.kind() == "attribute" && name.starts_with("self."));
+                if bindable {
+                    if let Some(bound) = py_bound_value(right, content, bindings) {
+                        bindings.bind(name, bound);
+                    }
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            walk(child, content, bindings);
+        }
+    }
+
+    let mut bindings = Bindings::new();
+    walk(root, content, &mut bindings);
+    bindings
+}
+
+/// The value an expression holds, if it is a string literal, an environment
+/// variable (with a default) or a name bound to either.
+fn py_bound_value(node: Node, content: &str, bindings: &Bindings) -> Option<BoundValue> {
+    let text = |n: Node| n.utf8_text(content.as_bytes()).unwrap_or("");
+    let is_environ = |n: Node| matches!(text(n), "os.environ" | "environ");
+    match node.kind() {
+        "string" => literal_string(node, content).map(BoundValue::Literal),
+        "identifier" | "attribute" => bindings.get(text(node)).cloned(),
+        // os.environ['USERS_TABLE']
+        "subscript" => {
+            let value = node.child_by_field_name("value")?;
+            let index = node.child_by_field_name("subscript")?;
+            if !is_environ(value) {
+                return None;
+            }
+            Some(BoundValue::Env {
+                variable: literal_string(index, content)?,
+                default: None,
+            })
+        }
+        // os.environ.get('USERS_TABLE', 'users'), os.getenv('USERS_TABLE')
+        "call" => {
+            let function = text(node.child_by_field_name("function")?);
+            if !matches!(
+                function,
+                "os.environ.get" | "environ.get" | "os.getenv" | "getenv"
+            ) {
+                return None;
+            }
+            let args = node.child_by_field_name("arguments")?;
+            let variable =
+                argument(args, 0, "key", content).and_then(|arg| literal_string(arg, content))?;
+            let default =
+                argument(args, 1, "default", content).and_then(|arg| literal_string(arg, content));
+            Some(BoundValue::Env { variable, default })
+        }
+        // os.environ.get('USERS_TABLE') or 'users'
+        "boolean_operator" => {
+            if text(node.child_by_field_name("operator")?) != "or" {
+                return None;
+            }
+            let left = py_bound_value(node.child_by_field_name("left")?, content, bindings)?;
+            let default = node
+                .child_by_field_name("right")
+                .and_then(|right| literal_string(right, content));
+            match (left, default) {
+                (
+                    BoundValue::Env {
+                        variable,
+                        default: None,
+                    },
+                    Some(default),
+                ) => Some(BoundValue::Env {
+                    variable,
+                    default: Some(default),
+                }),
+                (left, _) => Some(left),
+            }
+        }
+        "parenthesized_expression" => py_bound_value(node.named_child(0)?, content, bindings),
+        _ => None,
     }
+}
 
-    /// Find TableName in a dictionary literal.
-    fn find_table_name_in_dict(&self, dict_node: Node, content: &str) -> Option<String> {
-        for i in 0..dict_node.named_child_count() {
-            if let Some(child) = dict_node.named_child(i) {
-                if child.kind() == "pair" {
-                    if let Some(key_node) = child.child_by_field_name("key") {
-                        let key = key_node
-                            .utf8_text(content.as_bytes())
-                            .unwrap_or("")
-                            .trim_matches(|c| c == '"' || c == '\'');
-                        if key == "TableName" {
-                            if let Some(value_node) = child.child_by_field_name("value") {
-                                if value_node.kind() == "string" {
-                                    let value =
-                                        value_node.utf8_text(content.as_bytes()).unwrap_or("");
-                                    return Some(
-                                        value.trim_matches(|c| c == '"' || c == '\'').to_string(),
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+/// String literals in a list or tuple, or a single string literal.
+fn string_items(node: Node, content: &str) -> Vec<String> {
+    match node.kind() {
+        "list" | "tuple" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .filter_map(|item| literal_string(item, content))
+                .collect()
         }
-        None
+        _ => literal_string(node, content).into_iter().collect(),
+    }
+}
+
+/// Value of a Python string literal, or its source text if it interpolates.
+fn python_string_value(node: Node, content: &str) -> String {
+    let text = node.utf8_text(content.as_bytes()).unwrap_or("");
+    let mut cursor = node.walk();
+    let interpolated = node
+        .named_children(&mut cursor)
+        .any(|c| c.kind() == "interpolation");
+    if interpolated {
+        return text.to_string();
     }
+    text.trim_start_matches(['r', 'b', 'u', 'R', 'B', 'U'])
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
 }
 
 impl Parser for PythonParser {
@@ -929,6 +2313,14 @@ impl Parser for PythonParser {
         self
     }
 
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn version(&self) -> u32 {
+        1u32.wrapping_add(self.rules.fingerprint())
+    }
+
     fn supported_extensions(&self) -> &[&str] {
         &["py"]
     }
@@ -954,9 +2346,40 @@ impl Parser for PythonParser {
         discoveries.extend(self.detect_boto3_clients(&tree, content, path));
         discoveries.extend(self.detect_http_clients(&tree, content, path));
         discoveries.extend(self.detect_dynamodb_methods(&tree, content, path));
+        discoveries.extend(self.detect_sqs_operations(&tree, content, path));
+        discoveries.extend(self.detect_cdk_constructs(&tree, content, path));
+        discoveries.extend(self.detect_grpc_usage(&tree, content, path));
+        discoveries.extend(self.detect_sql_access(&tree, content, path));
+        discoveries.extend(self.detect_messaging(&tree, content, path));
+        discoveries.extend(self.detect_routes(&tree, content, path));
+
+        if !self.rules.is_empty() {
+            let bindings = collect_py_bindings(tree.root_node(), content);
+            discoveries.extend(self.rules.apply(&tree, content, path, &|node| {
+                py_bound_value(node, content, &bindings).map(BoundValue::into_name)
+            }));
+        }
 
         Ok(discoveries)
     }
+
+    /// The default traversal, with cached files also keyed by the Django
+    /// app directory (see [`django_app_label`]) that names their tables.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let files = repo_files(repo_path, is_ignored_dir, |path| {
+            path.extension().is_some_and(|ext| ext == "py")
+        });
+        let cache = cache
+            .for_parser(self.name(), self.version())
+            .keyed_by_directories(2);
+        Ok(RepoParse::parse_files(&files, |path, content| {
+            cache.get_or_parse(path, content, || self.parse_file(path, content))
+        }))
+    }
 }
 
 impl Default for PythonParser {
@@ -968,6 +2391,7 @@ impl Default for PythonParser {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::RelationshipType;
 
     fn create_parser() -> PythonParser {
         PythonParser::new().expect("Failed to create parser")
@@ -2089,4 +3513,635 @@ requests.options('https://api.example.com/users')
                 .any(|a| a.method == Some("OPTIONS".to_string()))
         );
     }
+
+    // ==================== AWS CDK Tests ====================
+
+    #[test]
+    fn test_cdk_constructs_and_grants() {
+        let parser = create_parser();
+        let content = r#"
+from aws_cdk import Stack, aws_dynamodb as dynamodb, aws_lambda as _lambda
+import aws_cdk.aws_sns as sns
+from aws_cdk.aws_lambda_event_sources import DynamoEventSource
+from constructs import Construct
+
+
+class BillingStack(Stack):
+    def __init__(self, scope: Construct, construct_id: str, **kwargs) -> None:
+        super().__init__(scope, construct_id, **kwargs)
+
+        self.invoices = dynamodb.Table(
+            self,
+            "Invoices",
+            table_name="invoices",
+            partition_key=dynamodb.Attribute(name="id", type=dynamodb.AttributeType.STRING),
+        )
+        topic = sns.Topic(self, "InvoiceEvents", topic_name=f"invoices-{self.region}")
+
+        handler = _lambda.Function(
+            self,
+            "InvoiceHandler",
+            runtime=_lambda.Runtime.PYTHON_3_12,
+            handler="app.handler",
+            code=_lambda.Code.from_asset("src"),
+        )
+
+        self.invoices.grant_write_data(handler)
+        topic.grant_publish(handler)
+        handler.add_event_source(DynamoEventSource(self.invoices, starting_position=_lambda.StartingPosition.LATEST))
+"#;
+
+        let discoveries = parser
+            .parse_file(Path::new("billing/stack.py"), content)
+            .unwrap();
+
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::DatabaseAccess(db)
+                if db.detection_method == "cdk" && db.table_name.as_deref() == Some("invoices")
+        )));
+        // f-string name falls back to the construct id
+        assert!(discoveries.iter().any(|d| matches!(
+            d,
+            Discovery::QueueOperation(q)
+                if q.queue_type == "sns" && q.queue_name.as_deref() == Some("InvoiceEvents")
+        )));
+
+        let function = discoveries
+            .iter()
+            .find_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .expect("Should detect _lambda.Function");
+        assert_eq!(function.name, "InvoiceHandler");
+        assert_eq!(function.language, "python");
+
+        let relationships: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some((r.target.as_str(), r.relationship)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            relationships,
+            vec![
+                ("invoices", RelationshipType::Writes),
+                ("InvoiceEvents", RelationshipType::Publishes),
+                ("invoices", RelationshipType::Reads),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grpc_stub_and_servicer() {
+        let parser = create_parser();
+        let content = r#"
+import grpc
+from acme.users.v1 import users_pb2, users_pb2_grpc
+import orders_pb2_grpc
+
+
+class OrderService(orders_pb2_grpc.OrderServiceServicer):
+    def __init__(self):
+        channel = grpc.insecure_channel("users:50051")
+        self.users = users_pb2_grpc.UserServiceStub(channel)
+
+    def GetOrder(self, request, context):
+        user = self.users.GetUser(users_pb2.GetUserRequest(id=request.user_id))
+        return orders_pb2.Order(id=request.id)
+
+
+def serve():
+    server = grpc.server(futures.ThreadPoolExecutor())
+    orders_pb2_grpc.add_OrderServiceServicer_to_server(OrderService(), server)
+"#;
+
+        let discoveries = parser.parse_file(Path::new("server.py"), content).unwrap();
+        let usages: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::GrpcUsage(u) => Some(u),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].service, "OrderService");
+        assert_eq!(usages[0].role, GrpcRole::Server);
+        assert_eq!(usages[0].source_line, 7);
+        assert_eq!(usages[1].service, "UserService");
+        assert_eq!(usages[1].role, GrpcRole::Client);
+        assert_eq!(usages[1].methods, vec!["GetUser"]);
+        assert_eq!(usages[1].detection_method, "grpcio");
+    }
+
+    // ==================== SQL / ORM Tests ====================
+
+    /// (table, connection, operation, detection_method) of each database access.
+    fn sql_accesses(
+        discoveries: &[Discovery],
+    ) -> Vec<(Option<&str>, Option<&str>, DatabaseOperation, &str)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some((
+                    db.table_name.as_deref(),
+                    db.connection.as_deref(),
+                    db.operation,
+                    db.detection_method.as_str(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sqlalchemy_models_and_queries() {
+        let parser = create_parser();
+        let content = r#"
+import os
+from sqlalchemy import create_engine, select, update
+from sqlalchemy.orm import DeclarativeBase, Session
+
+engine = create_engine(os.environ["DATABASE_URL"])
+
+
+class Base(DeclarativeBase):
+    pass
+
+
+class Order(Base):
+    __tablename__ = "orders"
+
+
+def pending(session: Session):
+    session.query(Order).filter_by(status="pending").all()
+    session.execute(update(Order).values(status="sent"))
+"#;
+
+        let discoveries = parser.parse_file(Path::new("db.py"), content).unwrap();
+        let connection = Some("DATABASE_URL");
+        assert_eq!(
+            sql_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "os.environ"),
+                (
+                    Some("orders"),
+                    connection,
+                    DatabaseOperation::Unknown,
+                    "sqlalchemy.model"
+                ),
+                (
+                    Some("orders"),
+                    connection,
+                    DatabaseOperation::Read,
+                    "sqlalchemy.query"
+                ),
+                (
+                    Some("orders"),
+                    connection,
+                    DatabaseOperation::ReadWrite,
+                    "sqlalchemy.update"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_django_models_and_managers() {
+        let parser = create_parser();
+        let models = r#"
+from django.db import models
+
+
+class Order(models.Model):
+    total = models.IntegerField()
+
+
+class Invoice(models.Model):
+    class Meta:
+        db_table = "billing_invoices"
+
+
+class Timestamped(models.Model):
+    class Meta:
+        abstract = True
+"#;
+        let discoveries = parser
+            .parse_file(Path::new("shop/orders/models.py"), models)
+            .unwrap();
+        let mut tables: Vec<_> = sql_accesses(&discoveries)
+            .into_iter()
+            .map(|(table, ..)| table.unwrap())
+            .collect();
+        tables.sort();
+        assert_eq!(tables, vec!["billing_invoices", "orders_order"]);
+
+        let views = r#"
+from orders.models import Order
+
+
+def ship(order_id):
+    Order.objects.filter(id=order_id).update(status="shipped")
+    Order.objects.create(total=0)
+"#;
+        let discoveries = parser
+            .parse_file(Path::new("shop/shipping/views.py"), views)
+            .unwrap();
+        assert_eq!(
+            sql_accesses(&discoveries),
+            vec![
+                (
+                    Some("orders_order"),
+                    None,
+                    DatabaseOperation::ReadWrite,
+                    "django.objects.update"
+                ),
+                (
+                    Some("orders_order"),
+                    None,
+                    DatabaseOperation::Read,
+                    "django.objects.filter"
+                ),
+                (
+                    Some("orders_order"),
+                    None,
+                    DatabaseOperation::Write,
+                    "django.objects.create"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_sql_with_asyncpg() {
+        let parser = create_parser();
+        let content = r#"
+import os
+import asyncpg
+
+
+async def handler(user_id):
+    conn = await asyncpg.connect(os.getenv("USERS_DB_URL"))
+    user = await conn.fetchrow("SELECT * FROM users WHERE id = $1", user_id)
+    await conn.execute(
+        "INSERT INTO audit_log (user_id, action) "
+        "VALUES ($1, 'login')",
+        user_id,
+    )
+    await conn.execute("SET search_path TO app")
+"#;
+
+        let discoveries = parser.parse_file(Path::new("handler.py"), content).unwrap();
+        let connection = Some("USERS_DB_URL");
+        assert_eq!(
+            sql_accesses(&discoveries),
+            vec![
+                (None, connection, DatabaseOperation::Unknown, "os.environ"),
+                (
+                    Some("users"),
+                    connection,
+                    DatabaseOperation::Read,
+                    "asyncpg.fetchrow"
+                ),
+                (
+                    Some("audit_log"),
+                    connection,
+                    DatabaseOperation::Write,
+                    "asyncpg.execute"
+                ),
+            ]
+        );
+        let Discovery::DatabaseAccess(db) = &discoveries
+            .iter()
+            .find(|d| matches!(d, Discovery::DatabaseAccess(_)))
+            .unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(db.db_type, "postgresql");
+    }
+
+    // ==================== Messaging Tests ====================
+
+    /// (queue_type, queue_name, operation) of each queue operation.
+    fn queue_operations(discoveries: &[Discovery]) -> Vec<(&str, &str, QueueOperationType)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some((
+                    q.queue_type.as_str(),
+                    q.queue_name.as_deref().unwrap_or(""),
+                    q.operation,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_kafka_and_rabbitmq_clients() {
+        let parser = create_parser();
+        let content = r#"
+import pika
+from confluent_kafka import Consumer, Producer
+from kafka import KafkaConsumer
+
+producer = Producer({"bootstrap.servers": "kafka:9092"})
+producer.produce("orders", value=b"{}")
+
+consumer = Consumer({"group.id": "billing"})
+consumer.subscribe(["payments", "refunds"])
+audit = KafkaConsumer("audit", bootstrap_servers="kafka:9092")
+
+channel = pika.BlockingConnection().channel()
+channel.basic_publish(exchange="", routing_key="emails", body="hi")
+channel.basic_publish("events", "user.created", "{}")
+channel.queue_bind(queue="audit-log", exchange="events")
+channel.basic_consume(queue="audit-log", on_message_callback=print)
+"#;
+
+        let discoveries = parser.parse_file(Path::new("worker.py"), content).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("kafka", "orders", QueueOperationType::Publish),
+                ("kafka", "payments", QueueOperationType::Subscribe),
+                ("kafka", "refunds", QueueOperationType::Subscribe),
+                ("kafka", "audit", QueueOperationType::Subscribe),
+                ("rabbitmq", "emails", QueueOperationType::Publish),
+                ("rabbitmq", "events", QueueOperationType::Publish),
+                ("rabbitmq", "events", QueueOperationType::Subscribe),
+                ("rabbitmq", "audit-log", QueueOperationType::Subscribe),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redis_streams_and_celery_tasks() {
+        let parser = create_parser();
+        let worker = r#"
+import redis
+from celery import Celery, shared_task
+
+app = Celery("orders")
+r = redis.Redis()
+
+
+@app.task
+def process_order(order_id):
+    r.xadd("order-events", {"id": order_id})
+
+
+@shared_task(name="billing.tasks.charge")
+def charge_card(order_id):
+    r.xreadgroup("billing", "worker-1", {"order-events": ">"})
+"#;
+        let discoveries = parser.parse_file(Path::new("tasks.py"), worker).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("celery", "process_order", QueueOperationType::Subscribe),
+                ("redis-stream", "order-events", QueueOperationType::Publish),
+                ("celery", "charge", QueueOperationType::Subscribe),
+                (
+                    "redis-stream",
+                    "order-events",
+                    QueueOperationType::Subscribe
+                ),
+            ]
+        );
+
+        // Producers usually import the task rather than Celery itself
+        let view = r#"
+from orders.tasks import process_order
+
+
+def checkout(request, app):
+    process_order.delay(request.order_id)
+    app.send_task("billing.tasks.charge", args=[request.order_id])
+"#;
+        let discoveries = parser.parse_file(Path::new("views.py"), view).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("celery", "process_order", QueueOperationType::Publish),
+                ("celery", "charge", QueueOperationType::Publish),
+            ]
+        );
+    }
+
+    // ==================== HTTP Route Tests ====================
+
+    /// (method, path, detection_method) of each endpoint.
+    fn endpoints(discoveries: &[Discovery]) -> Vec<(&str, &str, &str)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::ApiEndpoint(e) => Some((
+                    e.method.as_str(),
+                    e.path.as_str(),
+                    e.detection_method.as_str(),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fastapi_and_flask_routes() {
+        let parser = create_parser();
+        let fastapi = r#"
+from fastapi import APIRouter, FastAPI
+
+app = FastAPI()
+router = APIRouter(prefix="/items")
+
+
+@app.get("/health")
+def health():
+    return {"ok": True}
+
+
+@router.get("/{item_id}")
+async def read_item(item_id: int):
+    ...
+
+
+@router.api_route("/{item_id}/stock", methods=["PUT", "PATCH"])
+async def stock(item_id: int):
+    ...
+"#;
+        let discoveries = parser.parse_file(Path::new("main.py"), fastapi).unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("GET", "/health", "fastapi"),
+                ("GET", "/items/{item_id}", "fastapi"),
+                ("PUT", "/items/{item_id}/stock", "fastapi"),
+                ("PATCH", "/items/{item_id}/stock", "fastapi"),
+            ]
+        );
+
+        let flask = r#"
+from flask import Blueprint, Flask
+
+app = Flask(__name__)
+accounts = Blueprint("accounts", __name__, url_prefix="/accounts")
+
+
+@app.route("/")
+def index():
+    return "ok"
+
+
+@accounts.route("/<int:account_id>", methods=["GET", "POST"])
+def account(account_id):
+    ...
+
+
+@accounts.delete("/<int:account_id>")
+def close(account_id):
+    ...
+"#;
+        let discoveries = parser.parse_file(Path::new("app.py"), flask).unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("GET", "/", "flask"),
+                ("GET", "/accounts/{account_id}", "flask"),
+                ("POST", "/accounts/{account_id}", "flask"),
+                ("DELETE", "/accounts/{account_id}", "flask"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_django_urlpatterns() {
+        let parser = create_parser();
+        let content = r#"
+from django.urls import include, path, re_path
+
+from . import views
+
+urlpatterns = [
+    path("orders/", views.order_list),
+    path("orders/<int:pk>/", views.order_detail, name="order-detail"),
+    re_path(r"^archive/(?P<year>[0-9]{4})/$", views.archive),
+    path("api/", include("api.urls")),
+]
+"#;
+        let discoveries = parser.parse_file(Path::new("urls.py"), content).unwrap();
+        assert_eq!(
+            endpoints(&discoveries),
+            vec![
+                ("ANY", "/orders", "django"),
+                ("ANY", "/orders/{pk}", "django"),
+                ("ANY", "/archive/{year}", "django"),
+            ]
+        );
+    }
+
+    // ==================== Constant and Environment Propagation Tests ====================
+
+    #[test]
+    fn test_resource_names_from_constants_and_environment() {
+        let parser = create_parser();
+        let content = r#"
+import os
+import boto3
+
+USERS_TABLE = os.environ["USERS_TABLE"]
+ORDERS_TABLE = os.getenv("ORDERS_TABLE", "orders-local")
+AUDIT_TABLE = os.environ.get("AUDIT_TABLE") or "audit"
+JOBS_QUEUE = "https://sqs.us-east-1.amazonaws.com/123456789012/jobs"
+
+dynamodb = boto3.resource("dynamodb")
+client = boto3.client("dynamodb")
+sqs = boto3.client("sqs")
+users = dynamodb.Table(USERS_TABLE)
+
+
+class Orders:
+    def __init__(self):
+        self.table_name = ORDERS_TABLE
+
+    def get(self, order_id):
+        return client.get_item(TableName=self.table_name, Key={"id": {"S": order_id}})
+
+
+def handler(event, context):
+    users.get_item(Key={"id": event["id"]})
+    client.put_item(TableName=AUDIT_TABLE, Item={})
+    client.query(TableName=os.environ["CARTS_TABLE"])
+    sqs.send_message(QueueUrl=JOBS_QUEUE, MessageBody="{}")
+    sqs.receive_message(QueueUrl=os.environ["EVENTS_QUEUE_URL"])
+"#;
+
+        let discoveries = parser.parse_file(Path::new("handler.py"), content).unwrap();
+        let tables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => db.table_name.as_deref(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tables,
+            vec![
+                "env:ORDERS_TABLE:orders-local",
+                "env:USERS_TABLE",
+                "env:AUDIT_TABLE:audit",
+                "env:CARTS_TABLE",
+            ]
+        );
+
+        let queues: Vec<_> = queue_operations(&discoveries)
+            .into_iter()
+            .filter(|(_, name, _)| !name.is_empty())
+            .collect();
+        assert_eq!(
+            queues,
+            vec![
+                ("sqs", "jobs", QueueOperationType::Publish),
+                ("sqs", "env:EVENTS_QUEUE_URL", QueueOperationType::Subscribe),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detection_rules_call_pattern() {
+        let rule = DetectionRule {
+            name: "acme-events".to_string(),
+            languages: vec!["python".to_string()],
+            pattern: crate::parser::RulePattern::Call("acme_events.publish($name)".to_string()),
+            kind: crate::parser::RuleKind::Queue,
+            resource_type: Some("sns".to_string()),
+            name_capture: "name".to_string(),
+            operation_capture: "op".to_string(),
+            operation: Some("publish".to_string()),
+            operations: Default::default(),
+        };
+        let parser = PythonParser::with_rules(&[rule]).unwrap();
+        let content = r#"
+import acme_events
+
+ORDERS_TOPIC = "orders-created"
+
+def notify(order):
+    acme_events.publish(ORDERS_TOPIC, order)
+    acme_events.publish(order.topic, order)
+"#;
+
+        let discoveries = parser.parse_file(Path::new("app.py"), content).unwrap();
+        assert_eq!(
+            queue_operations(&discoveries),
+            vec![
+                ("sns", "orders-created", QueueOperationType::Publish),
+                ("sns", "", QueueOperationType::Publish),
+            ]
+        );
+    }
 }
@@ -0,0 +1,139 @@
+//! HTTP route recognition shared by the language parsers.
+//!
+//! Web frameworks declare the routes a service exposes in code. The language
+//! parsers find the declarations below and hand them to a [`RouteCollector`],
+//! which normalises each path so that a route declared in code and the same
+//! route described in an OpenAPI document or `serverless.yml` become one
+//! `Api` node.
+//!
+//! | Framework | Declaration | Prefix |
+//! |-----------|-------------|--------|
+//! | Express | `app.get('/users/:id', h)`, `router.route('/users').post(h)` | |
+//! | Fastify | `fastify.get('/users/:id', h)`, `fastify.route({ method, url })` | |
+//! | NestJS | `@Get(':id')` methods | `@Controller('users')` |
+//! | FastAPI | `@app.get('/users/{id}')`, `@router.api_route(path, methods=[...])` | `APIRouter(prefix=...)` |
+//! | Flask | `@app.route('/users/<int:id>', methods=[...])`, `@bp.get(...)` | `Blueprint(url_prefix=...)` |
+//! | Django | `urlpatterns = [path('users/<int:pk>/', view), re_path(r'^...$', view)]` | |
+//!
+//! Prefixes applied in another file (`app.use('/api', router)`,
+//! `include_router`, Django `include()`) are not followed.
+
+use super::traits::*;
+
+/// HTTP methods routes can be declared for, in lower case.
+pub(crate) const HTTP_METHODS: &[&str] =
+    &["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Normalise a route path: a leading `/`, no trailing `/`, and `{param}`
+/// placeholders in place of Express `:param`, Flask and Django `<type:param>`
+/// and regex `(?P<param>...)` groups.
+pub(crate) fn normalize_route(path: &str) -> String {
+    let path = path.trim().trim_start_matches('^').trim_end_matches('$');
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(normalize_segment)
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Join a router prefix and a route path.
+pub(crate) fn join_route(prefix: &str, path: &str) -> String {
+    normalize_route(&format!("{}/{}", prefix, path))
+}
+
+fn normalize_segment(segment: &str) -> String {
+    // :id, :id? (Express optional parameter)
+    if let Some(name) = segment.strip_prefix(':') {
+        return format!("{{{}}}", name.trim_end_matches('?'));
+    }
+    // <int:id>, <id> (Flask, Django)
+    if let Some(inner) = segment.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+        let name = inner.rsplit(':').next().unwrap_or(inner);
+        return format!("{{{}}}", name);
+    }
+    // {id:path} (Starlette converters)
+    if let Some(inner) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        let name = inner.split(':').next().unwrap_or(inner);
+        return format!("{{{}}}", name);
+    }
+    // (?P<pk>[0-9]+) (Django regular expressions)
+    if let Some(rest) = segment.strip_prefix("(?P<") {
+        if let Some((name, _)) = rest.split_once('>') {
+            return format!("{{{}}}", name);
+        }
+    }
+    segment.to_string()
+}
+
+/// Collects the routes declared in a file and builds endpoint discoveries.
+pub(crate) struct RouteCollector {
+    source_file: String,
+    endpoints: Vec<ApiEndpointDiscovery>,
+}
+
+impl RouteCollector {
+    pub(crate) fn new(source_file: String) -> Self {
+        Self {
+            source_file,
+            endpoints: Vec::new(),
+        }
+    }
+
+    /// Record a route. `method` is upper-cased (`"ANY"` for routes that
+    /// accept every method) and `path` normalised; repeats are ignored.
+    pub(crate) fn add(&mut self, method: &str, path: &str, framework: &str, line: u32) {
+        let method = method.to_uppercase();
+        let path = normalize_route(path);
+        if self
+            .endpoints
+            .iter()
+            .any(|e| e.method == method && e.path == path)
+        {
+            return;
+        }
+        self.endpoints.push(ApiEndpointDiscovery {
+            path,
+            method,
+            detection_method: framework.to_string(),
+            source_file: self.source_file.clone(),
+            source_line: line,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn into_discoveries(self) -> Vec<Discovery> {
+        self.endpoints
+            .into_iter()
+            .map(Discovery::ApiEndpoint)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_route() {
+        assert_eq!(normalize_route("/users/:id"), "/users/{id}");
+        assert_eq!(normalize_route("/users/:id?/"), "/users/{id}");
+        assert_eq!(normalize_route("users/<int:pk>/"), "/users/{pk}");
+        assert_eq!(normalize_route("/files/<path:name>"), "/files/{name}");
+        assert_eq!(normalize_route("/items/{item_id}"), "/items/{item_id}");
+        assert_eq!(normalize_route("/static/{rest:path}"), "/static/{rest}");
+        assert_eq!(
+            normalize_route(r"^articles/(?P<year>[0-9]{4})/$"),
+            "/articles/{year}"
+        );
+        assert_eq!(normalize_route(""), "/");
+        assert_eq!(normalize_route("/"), "/");
+    }
+
+    #[test]
+    fn test_join_route() {
+        assert_eq!(join_route("users", ":id"), "/users/{id}");
+        assert_eq!(join_route("/api/v1/", "/orders"), "/api/v1/orders");
+        assert_eq!(join_route("/health", ""), "/health");
+    }
+}
@@ -0,0 +1,762 @@
+//! User-defined detection rules.
+//!
+//! The JavaScript and Python parsers only recognize the libraries they were
+//! written for. Rules declared in `forge.yaml` teach them an organization's
+//! own wrappers (`acme.table('users').put(item)`) by mapping a pattern to a
+//! database, queue, API call or cloud resource discovery.
+//!
+//! A rule's pattern is either a call pattern or a tree-sitter query.
+//!
+//! # Call patterns
+//!
+//! A call pattern is a chain of names separated by dots, each optionally
+//! called:
+//!
+//! | Pattern | Matches |
+//! |---------|---------|
+//! | `acme.table($name).put()` | `acme.table('users').put(item)` |
+//! | `acme.table($name).$op()` | `acme.table('users').get(key)`, capturing `get` as `op` |
+//! | `acme_events.publish($name)` | `acme_events.publish(ORDERS_TOPIC)` |
+//! | `db.$name.find()` | `db.orders.find({})` |
+//! | `client.send(_, $name)` | `client.send(message, 'audit')` |
+//!
+//! - A name is matched literally, or captured with `$capture`.
+//! - Arguments are matched by position: `$capture` captures one, `_`
+//!   accepts any. Arguments after the last one listed are ignored, so `()`
+//!   accepts any call.
+//! - A segment without parentheses only matches a property that isn't
+//!   called.
+//! - The whole chain must match, starting from a plain identifier (or
+//!   `this`).
+//!
+//! # Queries
+//!
+//! A query is matched as is against the file's syntax tree; its captures
+//! are named with `@capture`. See the tree-sitter query syntax.
+//!
+//! # Captured values
+//!
+//! A captured argument is resolved like the built-in detectors resolve
+//! names: string literals, constants and environment variables (see
+//! [`super::dataflow`]). A captured name is used as written. The `name`
+//! capture gives the resource name (or API call target) and the `op`
+//! capture the operation, both configurable.
+
+use super::traits::{
+    ApiCallDiscovery, CloudResourceDiscovery, DatabaseAccessDiscovery, DatabaseOperation,
+    Discovery, ParserError, QueueOperationDiscovery, QueueOperationType,
+};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Language, Node, Query, QueryCursor, Tree};
+
+/// Languages rules can be written for.
+pub const RULE_LANGUAGES: &[&str] = &["javascript", "typescript", "python"];
+
+/// A detection rule as declared in configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionRule {
+    /// Identifies the rule in errors; used as the detection method
+    pub name: String,
+
+    /// Languages the rule applies to ("javascript" also covers TypeScript);
+    /// all of them when empty
+    pub languages: Vec<String>,
+
+    /// What the rule matches
+    pub pattern: RulePattern,
+
+    /// The discovery a match produces
+    pub kind: RuleKind,
+
+    /// Database, queue or resource type (e.g. "dynamodb", "sns", "s3");
+    /// not used by API call rules
+    pub resource_type: Option<String>,
+
+    /// Capture giving the resource name, or the target of an API call
+    pub name_capture: String,
+
+    /// Capture giving the operation
+    pub operation_capture: String,
+
+    /// Operation when there is no operation capture, or its value isn't one
+    pub operation: Option<String>,
+
+    /// Captured operation values (e.g. `put`), mapped to operations
+    /// (e.g. `write`)
+    pub operations: BTreeMap<String, String>,
+}
+
+/// How a rule matches code.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RulePattern {
+    /// A call pattern such as `acme.table($name).put()`
+    Call(String),
+    /// A tree-sitter query
+    Query(String),
+}
+
+/// The kind of discovery a rule produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
+    /// [`Discovery::DatabaseAccess`]; operations: `read`, `write`,
+    /// `read_write`
+    Database,
+    /// [`Discovery::QueueOperation`]; operations: `publish`, `subscribe`
+    Queue,
+    /// [`Discovery::ApiCall`]; the operation is the HTTP method
+    ApiCall,
+    /// [`Discovery::CloudResourceUsage`]; no operation
+    CloudResource,
+}
+
+/// The rules that apply to one parser, ready to run.
+#[derive(Debug, Default)]
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+    syntax: Option<CallSyntax>,
+    fingerprint: u32,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    rule: DetectionRule,
+    /// The parsed call pattern, or `None` for queries
+    call: Option<Vec<Segment>>,
+    /// A query compiled for each grammar it is valid for
+    queries: Vec<(Language, Query)>,
+}
+
+/// One `name(args)` link of a call pattern.
+#[derive(Debug, PartialEq)]
+struct Segment {
+    name: Name,
+    /// `None` when the segment is not called
+    args: Option<Vec<Name>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Name {
+    Literal(String),
+    Capture(String),
+    Any,
+}
+
+/// Node kinds a call chain is made of in one language.
+#[derive(Debug, Clone, Copy)]
+struct CallSyntax {
+    call: &'static str,
+    member: &'static str,
+    property_field: &'static str,
+    roots: &'static [&'static str],
+}
+
+const JAVASCRIPT_SYNTAX: CallSyntax = CallSyntax {
+    call: "call_expression",
+    member: "member_expression",
+    property_field: "property",
+    roots: &["identifier", "this"],
+};
+
+const PYTHON_SYNTAX: CallSyntax = CallSyntax {
+    call: "call",
+    member: "attribute",
+    property_field: "attribute",
+    roots: &["identifier"],
+};
+
+/// A link of a call chain found in code.
+struct Link<'t> {
+    name: Node<'t>,
+    args: Option<Vec<Node<'t>>>,
+}
+
+/// What a match captured, by capture name.
+type Captures<'t> = BTreeMap<String, Capture<'t>>;
+
+#[derive(Clone, Copy)]
+enum Capture<'t> {
+    /// An argument or query capture, to be resolved
+    Node(Node<'t>),
+    /// A name in a call chain, used as written
+    Name(Node<'t>),
+}
+
+impl RuleSet {
+    /// The rules for the JavaScript parser, checked against the grammars it
+    /// parses with.
+    pub fn javascript(
+        rules: &[DetectionRule],
+        grammars: &[&Language],
+    ) -> Result<Self, ParserError> {
+        Self::compile(
+            rules,
+            &["javascript", "typescript"],
+            grammars,
+            JAVASCRIPT_SYNTAX,
+        )
+    }
+
+    /// The rules for the Python parser.
+    pub fn python(rules: &[DetectionRule], grammar: &Language) -> Result<Self, ParserError> {
+        Self::compile(rules, &["python"], &[grammar], PYTHON_SYNTAX)
+    }
+
+    fn compile(
+        rules: &[DetectionRule],
+        languages: &[&str],
+        grammars: &[&Language],
+        syntax: CallSyntax,
+    ) -> Result<Self, ParserError> {
+        let mut compiled = Vec::new();
+        for rule in rules {
+            validate(rule)?;
+            let applies = rule.languages.is_empty()
+                || rule
+                    .languages
+                    .iter()
+                    .any(|l| languages.contains(&l.to_lowercase().as_str()));
+            if !applies {
+                continue;
+            }
+
+            let mut queries = Vec::new();
+            let call = match &rule.pattern {
+                RulePattern::Call(pattern) => {
+                    Some(parse_call_pattern(pattern).map_err(|reason| invalid(rule, reason))?)
+                }
+                RulePattern::Query(query) => {
+                    // A query may use node kinds only some of the grammars
+                    // have (TypeScript types); it is skipped for the others
+                    let mut errors = Vec::new();
+                    for grammar in grammars {
+                        match Query::new(grammar, query) {
+                            Ok(compiled) => queries.push(((*grammar).clone(), compiled)),
+                            Err(e) => errors.push(e),
+                        }
+                    }
+                    if queries.is_empty() {
+                        return Err(invalid(rule, format!("invalid query: {}", errors[0])));
+                    }
+                    None
+                }
+            };
+            compiled.push(CompiledRule {
+                rule: rule.clone(),
+                call,
+                queries,
+            });
+        }
+
+        let fingerprint = if compiled.is_empty() {
+            0
+        } else {
+            let mut hasher = Sha256::new();
+            for rule in &compiled {
+                hasher.update(format!("{:?}", rule.rule).as_bytes());
+            }
+            let hash = hasher.finalize();
+            u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+        };
+
+        Ok(Self {
+            rules: compiled,
+            syntax: Some(syntax),
+            fingerprint,
+        })
+    }
+
+    /// Whether there are no rules to apply.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// A hash of the rules, 0 when there are none. Parsers add it to their
+    /// version, so discoveries cached under other rules aren't reused.
+    pub fn fingerprint(&self) -> u32 {
+        self.fingerprint
+    }
+
+    /// Apply the rules to a parsed file.
+    ///
+    /// `resolve` gives the name an argument expression holds, if it can be
+    /// determined.
+    pub(crate) fn apply(
+        &self,
+        tree: &Tree,
+        content: &str,
+        path: &Path,
+        resolve: &dyn Fn(Node) -> Option<String>,
+    ) -> Vec<Discovery> {
+        let Some(syntax) = self.syntax.filter(|_| !self.rules.is_empty()) else {
+            return Vec::new();
+        };
+        let mut discoveries = Vec::new();
+
+        // Call patterns, checked at every call and property access
+        let call_rules: Vec<(&CompiledRule, &[Segment])> = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.call.as_deref().map(|call| (rule, call)))
+            .collect();
+        if !call_rules.is_empty() {
+            walk(tree.root_node(), &mut |node| {
+                if node.kind() != syntax.call && node.kind() != syntax.member {
+                    return;
+                }
+                let Some(chain) = call_chain(node, &syntax) else {
+                    return;
+                };
+                for (rule, segments) in &call_rules {
+                    if let Some(captures) = match_chain(segments, &chain, content) {
+                        let line = node.start_position().row as u32 + 1;
+                        discoveries.extend(rule.discovery(&captures, line, content, path, resolve));
+                    }
+                }
+            });
+        }
+
+        // Queries, as compiled for the file's grammar
+        let language = tree.language();
+        for rule in self.rules.iter().filter(|rule| !rule.queries.is_empty()) {
+            let Some((_, query)) = rule.queries.iter().find(|(l, _)| *l == *language) else {
+                tracing::debug!(
+                    "Rule {} does not apply to {}",
+                    rule.rule.name,
+                    path.display()
+                );
+                continue;
+            };
+            let names = query.capture_names();
+            let mut cursor = QueryCursor::new();
+            let mut matches = cursor.matches(query, tree.root_node(), content.as_bytes());
+            while let Some(match_) = matches.next() {
+                let mut captures = Captures::new();
+                let mut line = u32::MAX;
+                for capture in match_.captures {
+                    captures.insert(
+                        names[capture.index as usize].to_string(),
+                        Capture::Node(capture.node),
+                    );
+                    line = line.min(capture.node.start_position().row as u32 + 1);
+                }
+                discoveries.extend(rule.discovery(&captures, line, content, path, resolve));
+            }
+        }
+
+        discoveries
+    }
+}
+
+impl CompiledRule {
+    /// The discovery a match makes, if it has what the kind needs.
+    fn discovery(
+        &self,
+        captures: &Captures,
+        line: u32,
+        content: &str,
+        path: &Path,
+        resolve: &dyn Fn(Node) -> Option<String>,
+    ) -> Option<Discovery> {
+        let rule = &self.rule;
+        let text = |node: Node| node.utf8_text(content.as_bytes()).unwrap_or("").to_string();
+        let value = |capture: &Capture| match *capture {
+            Capture::Name(node) => Some(text(node)),
+            // Leaves other than identifiers (property names, numbers) stand
+            // for themselves
+            Capture::Node(node) => resolve(node).or_else(|| {
+                (node.named_child_count() == 0 && node.kind() != "identifier").then(|| text(node))
+            }),
+        };
+        let name = captures.get(&rule.name_capture).and_then(value);
+        let operation = captures
+            .get(&rule.operation_capture)
+            .and_then(value)
+            .map(|op| rule.operations.get(&op).cloned().unwrap_or(op));
+        let resource_type = rule.resource_type.clone().unwrap_or_default();
+        let source_file = path.to_string_lossy().to_string();
+
+        Some(match rule.kind {
+            RuleKind::Database => Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+                db_type: resource_type,
+                table_name: name,
+                connection: None,
+                operation: operation
+                    .as_deref()
+                    .and_then(database_operation)
+                    .or_else(|| rule.operation.as_deref().and_then(database_operation))
+                    .unwrap_or(DatabaseOperation::Unknown),
+                detection_method: rule.name.clone(),
+                source_file,
+                source_line: line,
+                deployment_metadata: None,
+            }),
+            RuleKind::Queue => Discovery::QueueOperation(QueueOperationDiscovery {
+                queue_type: resource_type,
+                queue_name: name,
+                operation: operation
+                    .as_deref()
+                    .and_then(queue_operation)
+                    .or_else(|| rule.operation.as_deref().and_then(queue_operation))
+                    .unwrap_or(QueueOperationType::Unknown),
+                source_file,
+                source_line: line,
+                deployment_metadata: None,
+            }),
+            RuleKind::ApiCall => Discovery::ApiCall(ApiCallDiscovery {
+                target: name?,
+                method: operation
+                    .or_else(|| rule.operation.clone())
+                    .map(|method| method.to_uppercase()),
+                detection_method: rule.name.clone(),
+                source_file,
+                source_line: line,
+            }),
+            RuleKind::CloudResource => Discovery::CloudResourceUsage(CloudResourceDiscovery {
+                resource_type,
+                resource_name: name,
+                source_file,
+                source_line: line,
+                deployment_metadata: None,
+            }),
+        })
+    }
+}
+
+/// Check what doesn't depend on the language: the type and operations.
+fn validate(rule: &DetectionRule) -> Result<(), ParserError> {
+    for language in &rule.languages {
+        if !RULE_LANGUAGES.contains(&language.to_lowercase().as_str()) {
+            return Err(invalid(
+                rule,
+                format!(
+                    "unknown language '{}' (expected one of: {})",
+                    language,
+                    RULE_LANGUAGES.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if matches!(rule.pattern, RulePattern::Query(_)) && rule.languages.is_empty() {
+        return Err(invalid(
+            rule,
+            "query rules must list their languages, since queries depend on the grammar",
+        ));
+    }
+
+    let parse: fn(&str) -> bool = match rule.kind {
+        RuleKind::Database => |op| database_operation(op).is_some(),
+        RuleKind::Queue => |op| queue_operation(op).is_some(),
+        RuleKind::ApiCall => |_| true,
+        RuleKind::CloudResource => {
+            if rule.operation.is_some() || !rule.operations.is_empty() {
+                return Err(invalid(rule, "cloud resource rules have no operation"));
+            }
+            |_| true
+        }
+    };
+    if rule.kind != RuleKind::ApiCall && rule.resource_type.is_none() {
+        return Err(invalid(rule, "a type is required"));
+    }
+    for op in rule.operation.iter().chain(rule.operations.values()) {
+        if !parse(op) {
+            return Err(invalid(rule, format!("unknown operation '{}'", op)));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(rule: &DetectionRule, reason: impl Into<String>) -> ParserError {
+    ParserError::InvalidRule {
+        rule: rule.name.clone(),
+        reason: reason.into(),
+    }
+}
+
+fn database_operation(op: &str) -> Option<DatabaseOperation> {
+    match op.to_lowercase().as_str() {
+        "read" => Some(DatabaseOperation::Read),
+        "write" => Some(DatabaseOperation::Write),
+        "read_write" | "readwrite" => Some(DatabaseOperation::ReadWrite),
+        _ => None,
+    }
+}
+
+fn queue_operation(op: &str) -> Option<QueueOperationType> {
+    match op.to_lowercase().as_str() {
+        "publish" => Some(QueueOperationType::Publish),
+        "subscribe" => Some(QueueOperationType::Subscribe),
+        _ => None,
+    }
+}
+
+/// Parse a call pattern such as `acme.table($name).put()`.
+fn parse_call_pattern(pattern: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = pattern.trim();
+    loop {
+        let end = rest.find(['.', '(']).unwrap_or(rest.len());
+        let name = parse_name(rest[..end].trim())
+            .filter(|name| *name != Name::Any)
+            .ok_or_else(|| format!("invalid name '{}' in '{}'", rest[..end].trim(), pattern))?;
+        rest = rest[end..].trim_start();
+
+        let args = if let Some(after) = rest.strip_prefix('(') {
+            let close = after
+                .find(')')
+                .ok_or_else(|| format!("unclosed '(' in '{}'", pattern))?;
+            let inner = after[..close].trim();
+            rest = after[close + 1..].trim_start();
+            let args = if inner.is_empty() {
+                Vec::new()
+            } else {
+                inner
+                    .split(',')
+                    .map(|arg| {
+                        parse_name(arg.trim())
+                            .filter(|arg| !matches!(arg, Name::Literal(_)))
+                            .ok_or_else(|| {
+                                format!(
+                                    "invalid argument '{}' in '{}' (use $capture or _)",
+                                    arg.trim(),
+                                    pattern
+                                )
+                            })
+                    })
+                    .collect::<Result<_, _>>()?
+            };
+            Some(args)
+        } else {
+            None
+        };
+        segments.push(Segment { name, args });
+
+        if rest.is_empty() {
+            return Ok(segments);
+        }
+        rest = rest
+            .strip_prefix('.')
+            .ok_or_else(|| format!("unexpected '{}' in '{}'", rest, pattern))?
+            .trim_start();
+    }
+}
+
+/// `name`, `$capture` or `_`.
+fn parse_name(text: &str) -> Option<Name> {
+    let is_ident = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+    };
+    if text == "_" {
+        Some(Name::Any)
+    } else if let Some(capture) = text.strip_prefix('$') {
+        is_ident(capture).then(|| Name::Capture(capture.to_string()))
+    } else {
+        is_ident(text).then(|| Name::Literal(text.to_string()))
+    }
+}
+
+/// The chain of names and calls an expression is made of, from its root
+/// identifier; `None` if it isn't one.
+fn call_chain<'t>(node: Node<'t>, syntax: &CallSyntax) -> Option<Vec<Link<'t>>> {
+    if node.kind() == syntax.call {
+        let mut chain = call_chain(node.child_by_field_name("function")?, syntax)?;
+        let last = chain.last_mut()?;
+        if last.args.is_some() {
+            // A call of a call's result: f()()
+            return None;
+        }
+        let arguments = node.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        last.args = Some(
+            arguments
+                .named_children(&mut cursor)
+                .filter(|arg| arg.kind() != "comment")
+                .collect(),
+        );
+        Some(chain)
+    } else if node.kind() == syntax.member {
+        let mut chain = call_chain(node.child_by_field_name("object")?, syntax)?;
+        chain.push(Link {
+            name: node.child_by_field_name(syntax.property_field)?,
+            args: None,
+        });
+        Some(chain)
+    } else if syntax.roots.contains(&node.kind()) {
+        Some(vec![Link {
+            name: node,
+            args: None,
+        }])
+    } else {
+        None
+    }
+}
+
+/// Match a whole chain against a call pattern.
+fn match_chain<'t>(
+    segments: &[Segment],
+    chain: &[Link<'t>],
+    content: &str,
+) -> Option<Captures<'t>> {
+    if segments.len() != chain.len() {
+        return None;
+    }
+    let mut captures = Captures::new();
+    for (segment, link) in segments.iter().zip(chain) {
+        match &segment.name {
+            Name::Literal(name) => {
+                if link.name.utf8_text(content.as_bytes()).ok()? != name {
+                    return None;
+                }
+            }
+            Name::Capture(capture) => {
+                captures.insert(capture.clone(), Capture::Name(link.name));
+            }
+            Name::Any => {}
+        }
+        match (&segment.args, &link.args) {
+            (None, None) => {}
+            (Some(patterns), Some(args)) => {
+                if patterns.len() > args.len() {
+                    return None;
+                }
+                for (pattern, arg) in patterns.iter().zip(args) {
+                    if let Name::Capture(capture) = pattern {
+                        captures.insert(capture.clone(), Capture::Node(*arg));
+                    }
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(captures)
+}
+
+/// Call `f` with every node of the tree.
+fn walk<'t>(node: Node<'t>, f: &mut impl FnMut(Node<'t>)) {
+    f(node);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: RulePattern, kind: RuleKind) -> DetectionRule {
+        DetectionRule {
+            name: "acme".to_string(),
+            languages: Vec::new(),
+            pattern,
+            kind,
+            resource_type: Some("dynamodb".to_string()),
+            name_capture: "name".to_string(),
+            operation_capture: "op".to_string(),
+            operation: None,
+            operations: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_call_pattern() {
+        let segments = parse_call_pattern("acme.table($name).$op(_, $item)").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                Segment {
+                    name: Name::Literal("acme".to_string()),
+                    args: None,
+                },
+                Segment {
+                    name: Name::Literal("table".to_string()),
+                    args: Some(vec![Name::Capture("name".to_string())]),
+                },
+                Segment {
+                    name: Name::Capture("op".to_string()),
+                    args: Some(vec![Name::Any, Name::Capture("item".to_string())]),
+                },
+            ]
+        );
+
+        for bad in [
+            "",
+            "acme.",
+            "acme.table(",
+            "acme.table('users')",
+            "_.put()",
+            "a b",
+        ] {
+            assert!(
+                parse_call_pattern(bad).is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let grammar: Language = tree_sitter_python::LANGUAGE.into();
+
+        let mut bad_op = rule(
+            RulePattern::Call("db.get()".to_string()),
+            RuleKind::Database,
+        );
+        bad_op.operation = Some("publish".to_string());
+        let mut no_type = rule(RulePattern::Call("db.get()".to_string()), RuleKind::Queue);
+        no_type.resource_type = None;
+        let mut bad_language = rule(
+            RulePattern::Call("db.get()".to_string()),
+            RuleKind::Database,
+        );
+        bad_language.languages = vec!["cobol".to_string()];
+        let mut bad_query = rule(RulePattern::Query("(call".to_string()), RuleKind::Database);
+        bad_query.languages = vec!["python".to_string()];
+        let any_language_query = rule(
+            RulePattern::Query("(call) @name".to_string()),
+            RuleKind::Database,
+        );
+
+        for rule in [bad_op, no_type, bad_language, bad_query, any_language_query] {
+            assert!(
+                matches!(
+                    RuleSet::python(std::slice::from_ref(&rule), &grammar),
+                    Err(ParserError::InvalidRule { .. })
+                ),
+                "{:?} should be rejected",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn test_rules_filtered_by_language() {
+        let grammar: Language = tree_sitter_python::LANGUAGE.into();
+        let mut js_only = rule(
+            RulePattern::Call("db.get()".to_string()),
+            RuleKind::Database,
+        );
+        js_only.languages = vec!["TypeScript".to_string()];
+
+        let rules = RuleSet::python(&[js_only], &grammar).unwrap();
+        assert!(rules.is_empty());
+        assert_eq!(rules.fingerprint(), 0);
+
+        let all = RuleSet::python(
+            &[rule(
+                RulePattern::Call("db.get()".to_string()),
+                RuleKind::Database,
+            )],
+            &grammar,
+        )
+        .unwrap();
+        assert!(!all.is_empty());
+        assert_ne!(all.fingerprint(), 0);
+    }
+}
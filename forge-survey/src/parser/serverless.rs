@@ -0,0 +1,1780 @@
+//! Serverless Framework parser for detecting functions, event sources and resources.
+//!
+//! Handles `serverless.yml`/`serverless.yaml`/`serverless.json` as well as the
+//! object literal exported by `serverless.ts`/`serverless.js`.
+//!
+//! Detects:
+//! - One service per function under `functions`
+//! - API endpoints from `http` and `httpApi` events
+//! - Subscriptions from `sqs`, `sns`, `stream`, `eventBridge` and `s3` events
+//! - Schedules from `schedule` events (recorded as a tag on the function)
+//! - Environment variables of each function (`provider.environment` merged
+//!   with the function's own)
+//! - Resources declared in the `resources` block (via the CloudFormation parser)
+//!
+//! Extracts deployment metadata from:
+//! - `provider.stage` (the environment; defaults to "dev" like the framework)
+//! - `provider.region`, `provider.tags` and per-function `tags`
+//!
+//! Serverless variables (`${self:...}`, `${opt:stage, 'dev'}`, `${sls:stage}`)
+//! are resolved on a best-effort basis; anything that depends on the deploy
+//! environment (`${env:...}`, `${ssm:...}`) is left as-is.
+
+use super::cloudformation::CloudFormationParser;
+use super::traits::*;
+use crate::cache::DiscoveryCache;
+use std::any::Any;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Stage used when the configuration does not pin one.
+const DEFAULT_STAGE: &str = "dev";
+
+/// Maximum nesting depth when resolving variables that reference other variables.
+const MAX_VARIABLE_DEPTH: usize = 5;
+
+/// A resource declared in the `resources` block, keyed by logical id.
+#[derive(Debug, Clone, PartialEq)]
+struct DeclaredResource {
+    kind: ComponentKind,
+    /// Physical name as the CloudFormation parser names the node.
+    name: String,
+    /// CloudFormation type (e.g., "AWS::SQS::Queue").
+    resource_type: String,
+}
+
+/// Resolution context for a single serverless configuration.
+struct ServerlessContext<'a> {
+    config: &'a serde_yaml::Value,
+    service: String,
+    stage: String,
+    region: Option<String>,
+    /// Resources from the `resources` block, keyed by logical id.
+    resources: HashMap<String, DeclaredResource>,
+    source_file: String,
+}
+
+/// Parser for Serverless Framework configuration files.
+pub struct ServerlessParser {
+    cloudformation: CloudFormationParser,
+}
+
+impl ServerlessParser {
+    pub fn new() -> Result<Self, ParserError> {
+        Ok(Self {
+            cloudformation: CloudFormationParser::new()?,
+        })
+    }
+
+    /// Check if a file is a Serverless Framework config by name.
+    pub fn is_serverless_filename(path: &Path) -> bool {
+        let filename = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        matches!(
+            filename.as_str(),
+            "serverless.yml"
+                | "serverless.yaml"
+                | "serverless.json"
+                | "serverless.ts"
+                | "serverless.js"
+        )
+    }
+
+    /// Parse a configuration file into a YAML value regardless of its format.
+    fn parse_config(&self, path: &Path, content: &str) -> Result<serde_yaml::Value, ParserError> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+        match extension {
+            "ts" | "js" => read_config_literal(content).ok_or_else(|| {
+                ParserError::TreeSitterError(
+                    "Could not find serverless configuration object".to_string(),
+                )
+            }),
+            // JSON is valid YAML
+            _ => serde_yaml::from_str(content)
+                .map_err(|e| ParserError::TreeSitterError(format!("YAML parse error: {}", e))),
+        }
+    }
+
+    /// Extract all discoveries from a parsed serverless configuration.
+    fn extract(&self, config: &serde_yaml::Value, path: &Path, content: &str) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+
+        if !config.is_mapping() || config.get("service").is_none() {
+            return discoveries;
+        }
+
+        let mut ctx = ServerlessContext {
+            config,
+            service: String::new(),
+            stage: DEFAULT_STAGE.to_string(),
+            region: None,
+            resources: HashMap::new(),
+            source_file: path.to_string_lossy().to_string(),
+        };
+
+        // The stage is needed to resolve almost everything else, so it is
+        // resolved first without a known stage (falling back to defaults).
+        ctx.stage = config
+            .get("provider")
+            .and_then(|p| p.get("stage"))
+            .and_then(scalar_to_string)
+            .map(|raw| ctx.resolve_variables(&raw, false, 0))
+            .filter(|s| !s.is_empty() && !s.contains("${"))
+            .unwrap_or_else(|| DEFAULT_STAGE.to_string());
+        ctx.region = config
+            .get("provider")
+            .and_then(|p| p.get("region"))
+            .and_then(scalar_to_string)
+            .map(|raw| ctx.resolve_variables(&raw, true, 0))
+            .filter(|s| !s.contains("${"));
+        ctx.service = match config.get("service") {
+            Some(serde_yaml::Value::Mapping(map)) => map
+                .get("name")
+                .and_then(scalar_to_string)
+                .unwrap_or_default(),
+            Some(value) => scalar_to_string(value).unwrap_or_default(),
+            None => String::new(),
+        };
+        ctx.service = ctx.resolve_variables(&ctx.service, true, 0);
+        if ctx.service.is_empty() {
+            return discoveries;
+        }
+
+        // Resources are resolved up-front so events and environment variables
+        // can refer to them by logical id.
+        let templates = self.resource_templates(&ctx);
+        for template in &templates {
+            ctx.resources.extend(declared_resources(template));
+        }
+
+        let stack_name = format!("{}-{}", ctx.service, ctx.stage);
+        for template in &templates {
+            discoveries.extend(self.cloudformation.extract_template_resources(
+                template,
+                path,
+                "serverless",
+                Some(&ctx.stage),
+                Some(&stack_name),
+            ));
+        }
+
+        let provider_runtime = config
+            .get("provider")
+            .and_then(|p| p.get("runtime"))
+            .and_then(|r| r.as_str());
+        let provider_environment = config.get("provider").and_then(|p| p.get("environment"));
+
+        let Some(functions) = config.get("functions").and_then(|f| f.as_mapping()) else {
+            return discoveries;
+        };
+
+        for (key, function) in functions {
+            let Some(key) = key.as_str() else {
+                continue;
+            };
+
+            let function_name = function
+                .get("name")
+                .and_then(scalar_to_string)
+                .map(|name| ctx.resolve_variables(&name, true, 0))
+                .unwrap_or_else(|| format!("{}-{}-{}", ctx.service, ctx.stage, key));
+            let line = find_function_line(content, key);
+            let events = function
+                .get("events")
+                .and_then(|e| e.as_sequence())
+                .map(|e| e.as_slice())
+                .unwrap_or_default();
+
+            let runtime = function
+                .get("runtime")
+                .and_then(|r| r.as_str())
+                .or(provider_runtime);
+            let mut metadata = self.build_deployment_metadata(&ctx, Some(function));
+            let schedules: Vec<String> = events
+                .iter()
+                .filter_map(|event| event.get("schedule"))
+                .flat_map(schedule_expressions)
+                .collect();
+            if !schedules.is_empty() {
+                metadata
+                    .tags
+                    .insert("schedule".to_string(), schedules.join("; "));
+            }
+
+            discoveries.push(Discovery::Service(ServiceDiscovery {
+                name: function_name.clone(),
+                language: self
+                    .cloudformation
+                    .infer_language_from_runtime(runtime.or(Some("nodejs"))),
+                framework: Some("aws-lambda".to_string()),
+                entry_point: function
+                    .get("handler")
+                    .and_then(scalar_to_string)
+                    .unwrap_or_else(|| "unknown".to_string()),
+                source_file: ctx.source_file.clone(),
+                source_line: line,
+                deployment_metadata: Some(metadata),
+            }));
+
+            for event in events {
+                discoveries.extend(self.process_event(&ctx, &function_name, event, line));
+            }
+
+            // Environment variables, function values overriding the provider's
+            let mut environment = serde_yaml::Mapping::new();
+            for variables in [provider_environment, function.get("environment")]
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.as_mapping())
+            {
+                environment.extend(variables.clone());
+            }
+            discoveries.extend(self.environment_discoveries(
+                &ctx,
+                &function_name,
+                &environment,
+                line,
+            ));
+        }
+
+        discoveries
+    }
+
+    /// Collect CloudFormation templates from the `resources` block.
+    ///
+    /// `resources` is either a template fragment or a list of fragments.
+    /// Serverless variables are resolved so physical names match the
+    /// deployed resources.
+    fn resource_templates(&self, ctx: &ServerlessContext) -> Vec<serde_yaml::Value> {
+        let Some(resources) = ctx.config.get("resources") else {
+            return Vec::new();
+        };
+
+        let fragments: Vec<&serde_yaml::Value> = match resources {
+            serde_yaml::Value::Sequence(items) => items.iter().collect(),
+            other => vec![other],
+        };
+
+        fragments
+            .into_iter()
+            .filter(|fragment| fragment.get("Resources").is_some())
+            .map(|fragment| ctx.resolve_tree(fragment))
+            .collect()
+    }
+
+    /// Convert a single function event into discoveries.
+    fn process_event(
+        &self,
+        ctx: &ServerlessContext,
+        function_name: &str,
+        event: &serde_yaml::Value,
+        line: u32,
+    ) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+        let Some(map) = event.as_mapping() else {
+            return discoveries;
+        };
+
+        for (event_type, definition) in map {
+            let Some(event_type) = event_type.as_str() else {
+                continue;
+            };
+
+            match event_type {
+                "http" | "httpApi" => {
+                    if let Some((method, path)) = http_route(definition) {
+                        discoveries.push(Discovery::ApiEndpoint(ApiEndpointDiscovery {
+                            path: ctx.resolve_variables(&path, true, 0),
+                            method,
+                            service: Some(function_name.to_string()),
+                            detection_method: "serverless".to_string(),
+                            source_file: ctx.source_file.clone(),
+                            source_line: line,
+                            ..Default::default()
+                        }));
+                    }
+                }
+                "sqs" => {
+                    let arn = definition.get("arn").unwrap_or(definition);
+                    if let Some(queue) = ctx.resolve_resource(arn) {
+                        discoveries.push(self.queue_discovery(ctx, "sqs", &queue, line));
+                        discoveries.push(ctx.relationship(
+                            function_name,
+                            &queue,
+                            ComponentKind::Queue,
+                            RelationshipType::Subscribes,
+                            line,
+                        ));
+                    }
+                }
+                "sns" => {
+                    let topic = match definition {
+                        serde_yaml::Value::Mapping(_) => definition
+                            .get("arn")
+                            .and_then(|arn| ctx.resolve_resource(arn))
+                            .or_else(|| {
+                                definition
+                                    .get("topicName")
+                                    .and_then(scalar_to_string)
+                                    .map(|name| ctx.resolve_variables(&name, true, 0))
+                            }),
+                        other => ctx.resolve_resource(other),
+                    };
+                    if let Some(topic) = topic {
+                        discoveries.push(self.queue_discovery(ctx, "sns", &topic, line));
+                        discoveries.push(ctx.relationship(
+                            function_name,
+                            &topic,
+                            ComponentKind::Queue,
+                            RelationshipType::Subscribes,
+                            line,
+                        ));
+                    }
+                }
+                "stream" => {
+                    discoveries.extend(self.process_stream_event(
+                        ctx,
+                        function_name,
+                        definition,
+                        line,
+                    ));
+                }
+                "eventBridge" => {
+                    let bus = definition
+                        .get("eventBus")
+                        .and_then(|bus| ctx.resolve_resource(bus))
+                        .unwrap_or_else(|| "default".to_string());
+                    discoveries.push(self.queue_discovery(ctx, "eventbridge", &bus, line));
+                    discoveries.push(ctx.relationship(
+                        function_name,
+                        &bus,
+                        ComponentKind::Queue,
+                        RelationshipType::Subscribes,
+                        line,
+                    ));
+                }
+                "s3" => {
+                    let bucket = match definition {
+                        serde_yaml::Value::Mapping(_) => definition
+                            .get("bucket")
+                            .and_then(|b| ctx.resolve_resource(b)),
+                        other => ctx.resolve_resource(other),
+                    };
+                    if let Some(bucket) = bucket {
+                        discoveries.push(Discovery::CloudResourceUsage(CloudResourceDiscovery {
+                            resource_type: "s3".to_string(),
+                            resource_name: Some(bucket.clone()),
+                            source_file: ctx.source_file.clone(),
+                            source_line: line,
+                            deployment_metadata: Some(self.build_deployment_metadata(ctx, None)),
+                        }));
+                        discoveries.push(ctx.relationship(
+                            function_name,
+                            &bucket,
+                            ComponentKind::CloudResource,
+                            RelationshipType::Uses,
+                            line,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        discoveries
+    }
+
+    /// Handle `stream` events (DynamoDB streams and Kinesis).
+    fn process_stream_event(
+        &self,
+        ctx: &ServerlessContext,
+        function_name: &str,
+        definition: &serde_yaml::Value,
+        line: u32,
+    ) -> Vec<Discovery> {
+        let arn = definition.get("arn").unwrap_or(definition);
+        let explicit_type = definition.get("type").and_then(|t| t.as_str());
+
+        // Work out what the stream belongs to: a declared resource or an ARN.
+        let (is_dynamodb, name) =
+            if let Some(resource) = resource_reference(arn).and_then(|id| ctx.resources.get(&id)) {
+                (
+                    resource.resource_type == "AWS::DynamoDB::Table",
+                    resource.name.clone(),
+                )
+            } else if let Some(arn) = scalar_to_string(arn) {
+                let arn = ctx.resolve_variables(&arn, true, 0);
+                if let Some(table) = dynamodb_table_from_arn(&arn) {
+                    (true, table)
+                } else {
+                    (explicit_type == Some("dynamodb"), name_from_arn(&arn))
+                }
+            } else {
+                return Vec::new();
+            };
+
+        let mut discoveries = Vec::new();
+        if is_dynamodb {
+            discoveries.push(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+                db_type: "dynamodb".to_string(),
+                table_name: Some(name.clone()),
+                connection: None,
+                operation: DatabaseOperation::Read,
+                detection_method: "serverless".to_string(),
+                source_file: ctx.source_file.clone(),
+                source_line: line,
+                deployment_metadata: Some(self.build_deployment_metadata(ctx, None)),
+            }));
+            discoveries.push(ctx.relationship(
+                function_name,
+                &name,
+                ComponentKind::Database,
+                RelationshipType::Reads,
+                line,
+            ));
+        } else {
+            discoveries.push(self.queue_discovery(ctx, "kinesis", &name, line));
+            discoveries.push(ctx.relationship(
+                function_name,
+                &name,
+                ComponentKind::Queue,
+                RelationshipType::Subscribes,
+                line,
+            ));
+        }
+
+        discoveries
+    }
+
+    /// Environment variables of a function, and relationships to the declared
+    /// resources they reference.
+    ///
+    /// Matches `Ref`/`Fn::GetAtt` to a logical id as well as literal values
+    /// equal to a resource's physical name (e.g., a table name built from
+    /// `${self:service}-orders-${sls:stage}`). Variables referencing a
+    /// resource take its name as their value.
+    fn environment_discoveries(
+        &self,
+        ctx: &ServerlessContext,
+        function_name: &str,
+        variables: &serde_yaml::Mapping,
+        line: u32,
+    ) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+        for (name, value) in variables {
+            let literal = scalar_to_string(value).map(|l| ctx.resolve_variables(&l, true, 0));
+            let resource = if let Some(logical_id) = resource_reference(value) {
+                ctx.resources.get(&logical_id)
+            } else if let Some(literal) = &literal {
+                let mut matches: Vec<&DeclaredResource> = ctx
+                    .resources
+                    .values()
+                    .filter(|r| &r.name == literal)
+                    .collect();
+                matches.sort_by(|a, b| a.resource_type.cmp(&b.resource_type));
+                matches.first().copied()
+            } else {
+                None
+            };
+
+            let value = resource.map(|r| r.name.clone()).or(literal);
+            if let (Some(name), Some(value)) = (name.as_str(), value) {
+                discoveries.push(Discovery::EnvironmentVariable(
+                    EnvironmentVariableDiscovery {
+                        service: function_name.to_string(),
+                        name: name.to_string(),
+                        value,
+                        source_file: ctx.source_file.clone(),
+                        source_line: line,
+                    },
+                ));
+            }
+            if let Some(resource) = resource {
+                discoveries.push(ctx.relationship(
+                    function_name,
+                    &resource.name,
+                    resource.kind,
+                    RelationshipType::DependsOn,
+                    line,
+                ));
+            }
+        }
+
+        discoveries
+    }
+
+    fn queue_discovery(
+        &self,
+        ctx: &ServerlessContext,
+        queue_type: &str,
+        name: &str,
+        line: u32,
+    ) -> Discovery {
+        Discovery::QueueOperation(QueueOperationDiscovery {
+            queue_type: queue_type.to_string(),
+            queue_name: Some(name.to_string()),
+            operation: QueueOperationType::Subscribe,
+            source_file: ctx.source_file.clone(),
+            source_line: line,
+            deployment_metadata: Some(self.build_deployment_metadata(ctx, None)),
+        })
+    }
+
+    /// Build deployment metadata for the configuration, optionally merging
+    /// the tags of a single function.
+    fn build_deployment_metadata(
+        &self,
+        ctx: &ServerlessContext,
+        function: Option<&serde_yaml::Value>,
+    ) -> DeploymentMetadata {
+        let mut tags = HashMap::new();
+
+        let provider = ctx.config.get("provider");
+        let tag_sources = [
+            provider.and_then(|p| p.get("stackTags")),
+            provider.and_then(|p| p.get("tags")),
+            function.and_then(|f| f.get("tags")),
+        ];
+        for source in tag_sources.into_iter().flatten() {
+            if let Some(map) = source.as_mapping() {
+                for (key, value) in map {
+                    if let (Some(key), Some(value)) = (key.as_str(), scalar_to_string(value)) {
+                        tags.insert(key.to_string(), ctx.resolve_variables(&value, true, 0));
+                    }
+                }
+            }
+        }
+
+        if let Some(region) = &ctx.region {
+            tags.insert("region".to_string(), region.clone());
+        }
+
+        DeploymentMetadata {
+            deployment_method: "serverless".to_string(),
+            terraform_workspace: None,
+            environment: Some(ctx.stage.clone()),
+            stack_name: Some(format!("{}-{}", ctx.service, ctx.stage)),
+            tags,
+            resource_attributes: HashMap::new(),
+        }
+    }
+}
+
+impl ServerlessContext<'_> {
+    /// Resolve `${...}` variables in a string.
+    ///
+    /// When `stage_known` is false, stage lookups are treated as unresolved
+    /// so their fallbacks apply (used while determining the stage itself).
+    fn resolve_variables(&self, raw: &str, stage_known: bool, depth: usize) -> String {
+        if depth > MAX_VARIABLE_DEPTH || !raw.contains("${") {
+            return raw.to_string();
+        }
+
+        let mut result = String::new();
+        let mut rest = raw;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(len) = closing_brace(&rest[start + 2..]) else {
+                result.push_str(&rest[start..]);
+                return result;
+            };
+            let expression = &rest[start + 2..start + 2 + len];
+            match self.resolve_expression(expression, stage_known, depth) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + 3 + len]),
+            }
+            rest = &rest[start + 3 + len..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// Resolve a single variable expression (the part between `${` and `}`).
+    fn resolve_expression(
+        &self,
+        expression: &str,
+        stage_known: bool,
+        depth: usize,
+    ) -> Option<String> {
+        let (source, fallback) = split_fallback(expression);
+
+        let value = match source.trim() {
+            "opt:stage" | "sls:stage" | "self:provider.stage" | "provider.stage" => {
+                stage_known.then(|| self.stage.clone())
+            }
+            "opt:region" | "aws:region" | "self:provider.region" => self.region.clone(),
+            "self:service" => Some(self.service.clone()).filter(|s| !s.is_empty()),
+            source => source.strip_prefix("self:").and_then(|path| {
+                let value = lookup_path(self.config, path)?;
+                let value = scalar_to_string(value)?;
+                Some(self.resolve_variables(&value, stage_known, depth + 1))
+            }),
+        };
+
+        if let Some(value) = value.filter(|v| !v.contains("${")) {
+            return Some(value);
+        }
+
+        let fallback = fallback?.trim();
+        if let Some(literal) = unquote(fallback) {
+            return Some(literal.to_string());
+        }
+        if fallback.parse::<f64>().is_ok() {
+            return Some(fallback.to_string());
+        }
+        if let Some(inner) = fallback
+            .strip_prefix("${")
+            .and_then(|f| f.strip_suffix('}'))
+        {
+            return self.resolve_expression(inner, stage_known, depth + 1);
+        }
+        self.resolve_expression(fallback, stage_known, depth + 1)
+    }
+
+    /// Deep-copy a value with all string scalars variable-resolved.
+    fn resolve_tree(&self, value: &serde_yaml::Value) -> serde_yaml::Value {
+        match value {
+            serde_yaml::Value::String(s) => {
+                serde_yaml::Value::String(self.resolve_variables(s, true, 0))
+            }
+            serde_yaml::Value::Sequence(items) => {
+                serde_yaml::Value::Sequence(items.iter().map(|i| self.resolve_tree(i)).collect())
+            }
+            serde_yaml::Value::Mapping(map) => serde_yaml::Value::Mapping(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.resolve_tree(v)))
+                    .collect(),
+            ),
+            serde_yaml::Value::Tagged(tagged) => {
+                serde_yaml::Value::Tagged(Box::new(serde_yaml::value::TaggedValue {
+                    tag: tagged.tag.clone(),
+                    value: self.resolve_tree(&tagged.value),
+                }))
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Resolve an event source (ARN, name or intrinsic reference) to a
+    /// resource name.
+    ///
+    /// References to resources in the `resources` block resolve to the
+    /// physical name of the declared resource; other references resolve to
+    /// the logical id, matching how the CloudFormation parser names nodes.
+    fn resolve_resource(&self, value: &serde_yaml::Value) -> Option<String> {
+        if let Some(logical_id) = resource_reference(value) {
+            return Some(
+                self.resources
+                    .get(&logical_id)
+                    .map(|r| r.name.clone())
+                    .unwrap_or(logical_id),
+            );
+        }
+
+        let raw = scalar_to_string(value)?;
+        let resolved = self.resolve_variables(&raw, true, 0);
+        if resolved.is_empty() {
+            return None;
+        }
+        Some(name_from_arn(&resolved))
+    }
+
+    fn relationship(
+        &self,
+        function_name: &str,
+        target: &str,
+        target_kind: ComponentKind,
+        relationship: RelationshipType,
+        line: u32,
+    ) -> Discovery {
+        Discovery::Relationship(RelationshipDiscovery {
+            source_service: function_name.to_string(),
+            target: target.to_string(),
+            target_kind,
+            relationship,
+            detection_method: "serverless".to_string(),
+            source_file: self.source_file.clone(),
+            source_line: line,
+        })
+    }
+}
+
+/// Index the resources of a template fragment by logical id.
+fn declared_resources(template: &serde_yaml::Value) -> HashMap<String, DeclaredResource> {
+    let mut resources = HashMap::new();
+    let Some(map) = template.get("Resources").and_then(|r| r.as_mapping()) else {
+        return resources;
+    };
+
+    for (logical_id, resource) in map {
+        let (Some(logical_id), Some(resource_type)) = (
+            logical_id.as_str(),
+            resource.get("Type").and_then(|t| t.as_str()),
+        ) else {
+            continue;
+        };
+
+        let (kind, name_property) = match resource_type {
+            "AWS::DynamoDB::Table" => (ComponentKind::Database, "TableName"),
+            "AWS::SQS::Queue" => (ComponentKind::Queue, "QueueName"),
+            "AWS::SNS::Topic" => (ComponentKind::Queue, "TopicName"),
+            "AWS::S3::Bucket" => (ComponentKind::CloudResource, "BucketName"),
+            _ => continue,
+        };
+
+        let name = resource
+            .get("Properties")
+            .and_then(|p| p.get(name_property))
+            .and_then(|n| n.as_str())
+            .unwrap_or(logical_id)
+            .to_string();
+
+        resources.insert(
+            logical_id.to_string(),
+            DeclaredResource {
+                kind,
+                name,
+                resource_type: resource_type.to_string(),
+            },
+        );
+    }
+
+    resources
+}
+
+/// Extract the logical id from `Ref`/`Fn::GetAtt` references in either the
+/// long (`{ Ref: X }`) or short (`!Ref X`, `!GetAtt X.Arn`) form.
+fn resource_reference(value: &serde_yaml::Value) -> Option<String> {
+    if let serde_yaml::Value::Tagged(tagged) = value {
+        if tagged.tag == "Ref" {
+            return tagged.value.as_str().map(|s| s.to_string());
+        }
+        if tagged.tag == "GetAtt" {
+            return getatt_logical_id(&tagged.value);
+        }
+        return None;
+    }
+
+    let map = value.as_mapping()?;
+    if let Some(reference) = map.get("Ref") {
+        return reference.as_str().map(|s| s.to_string());
+    }
+    map.get("Fn::GetAtt").and_then(getatt_logical_id)
+}
+
+fn getatt_logical_id(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => s.split('.').next().map(|s| s.to_string()),
+        serde_yaml::Value::Sequence(items) => items
+            .first()
+            .and_then(|i| i.as_str())
+            .map(|s| s.to_string()),
+        _ => None,
+    }
+}
+
+/// Extract the method and path from an `http`/`httpApi` event.
+///
+/// Supports the string shorthand (`GET /users/{id}`, `'*'`) and the
+/// `{ method, path }` map form.
+fn http_route(definition: &serde_yaml::Value) -> Option<(String, String)> {
+    let (method, path) = match definition {
+        serde_yaml::Value::String(s) => {
+            let s = s.trim();
+            if s == "*" {
+                ("*".to_string(), "*".to_string())
+            } else {
+                let (method, path) = s.split_once(char::is_whitespace)?;
+                (method.to_string(), path.trim().to_string())
+            }
+        }
+        serde_yaml::Value::Mapping(_) => (
+            definition
+                .get("method")
+                .and_then(|m| m.as_str())
+                .unwrap_or("*")
+                .to_string(),
+            definition
+                .get("path")
+                .and_then(|p| p.as_str())
+                .unwrap_or("*")
+                .to_string(),
+        ),
+        _ => return None,
+    };
+
+    let method = match method.to_uppercase().as_str() {
+        "*" | "ANY" => "ANY".to_string(),
+        other => other.to_string(),
+    };
+    let path = if path.starts_with('/') {
+        path
+    } else {
+        format!("/{}", path)
+    };
+
+    Some((method, path))
+}
+
+/// Schedule expressions from a `schedule` event (string, `{ rate }` with a
+/// string or list of rates).
+fn schedule_expressions(definition: &serde_yaml::Value) -> Vec<String> {
+    let rate = match definition {
+        serde_yaml::Value::Mapping(_) => definition.get("rate"),
+        other => Some(other),
+    };
+
+    match rate {
+        Some(serde_yaml::Value::String(s)) => vec![s.clone()],
+        Some(serde_yaml::Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|i| i.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Extract a table name from a DynamoDB table or stream ARN.
+///
+/// `arn:aws:dynamodb:us-east-1:123:table/orders/stream/2024-01-01` -> `orders`
+fn dynamodb_table_from_arn(arn: &str) -> Option<String> {
+    if !arn.starts_with("arn:") || !arn.contains(":dynamodb:") {
+        return None;
+    }
+    // The resource part may itself contain ':' (stream labels are timestamps)
+    let resource = arn.splitn(6, ':').nth(5)?;
+    let table = resource.strip_prefix("table/")?;
+    table.split('/').next().map(|t| t.to_string())
+}
+
+/// Resource name from an ARN, or the value itself if it is not an ARN.
+///
+/// `arn:aws:sqs:us-east-1:123:orders` -> `orders`,
+/// `arn:aws:kinesis:us-east-1:123:stream/clicks` -> `clicks`,
+/// `arn:aws:events:us-east-1:123:event-bus/orders` -> `orders`.
+pub(crate) fn name_from_arn(value: &str) -> String {
+    if !value.starts_with("arn:") {
+        return value.to_string();
+    }
+    let resource = value.splitn(6, ':').nth(5).unwrap_or(value);
+    match resource.split_once('/') {
+        // `type/name[/...]`
+        Some((_, rest)) => rest.split('/').next().unwrap_or(rest).to_string(),
+        // `name` or `type:name`
+        None => resource.rsplit(':').next().unwrap_or(resource).to_string(),
+    }
+}
+
+/// Split a variable expression into its source and optional fallback at the
+/// first top-level comma.
+fn split_fallback(expression: &str) -> (&str, Option<&str>) {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for (i, c) in expression.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                return (&expression[..i], Some(&expression[i + 1..]));
+            }
+            _ => {}
+        }
+    }
+    (expression, None)
+}
+
+/// Length of the text up to the `}` closing an already-opened `${`.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unquote(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .or_else(|| value.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+}
+
+/// Look up a dotted path (`custom.tableName`) in the configuration.
+fn lookup_path<'a>(config: &'a serde_yaml::Value, path: &str) -> Option<&'a serde_yaml::Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(config, |value, segment| value.get(segment))
+}
+
+fn scalar_to_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Find the 1-based line of a function key, searching after `functions:`.
+fn find_function_line(content: &str, key: &str) -> u32 {
+    let start = content
+        .lines()
+        .position(|line| line.trim_start().starts_with("functions"))
+        .unwrap_or(0);
+    let candidates = [
+        format!("{}:", key),
+        format!("'{}':", key),
+        format!("\"{}\":", key),
+    ];
+
+    content
+        .lines()
+        .enumerate()
+        .skip(start)
+        .find(|(_, line)| {
+            let line = line.trim_start();
+            candidates.iter().any(|c| line.starts_with(c.as_str()))
+        })
+        .map(|(i, _)| i as u32 + 1)
+        .unwrap_or(1)
+}
+
+// ============================================================================
+// serverless.ts / serverless.js support
+// ============================================================================
+
+/// Read the configuration object exported by `serverless.ts`/`serverless.js`.
+///
+/// Scans for object literals assigned (`= {`), exported (`export default {`,
+/// `module.exports = {`) or passed as an argument, and returns the first one
+/// that looks like a serverless configuration. Values that are not plain
+/// literals (identifiers, calls, imports) become null.
+fn read_config_literal(content: &str) -> Option<serde_yaml::Value> {
+    let bytes = content.as_bytes();
+
+    for (start, _) in content.match_indices('{') {
+        let preceding = content[..start].trim_end();
+        if !(preceding.ends_with('=')
+            || preceding.ends_with("default")
+            || preceding.ends_with('(')
+            || preceding.ends_with("as const")
+            || preceding.ends_with("satisfies"))
+        {
+            continue;
+        }
+
+        let mut reader = LiteralReader {
+            src: bytes,
+            pos: start,
+        };
+        if let Some(value) = reader.read_value() {
+            if value.get("service").is_some()
+                && (value.get("functions").is_some() || value.get("provider").is_some())
+            {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Tolerant reader for JavaScript/TypeScript object literals.
+struct LiteralReader<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl LiteralReader<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'/') => {
+                    while let Some(c) = self.peek() {
+                        if c == b'\n' {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                Some(b'/') if self.src.get(self.pos + 1) == Some(&b'*') => {
+                    self.pos += 2;
+                    while self.pos < self.src.len()
+                        && !(self.src[self.pos] == b'*'
+                            && self.src.get(self.pos + 1) == Some(&b'/'))
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.src.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> Option<serde_yaml::Value> {
+        self.skip_trivia();
+        let value = match self.peek()? {
+            b'{' => self.read_object()?,
+            b'[' => self.read_array()?,
+            b'\'' | b'"' | b'`' => serde_yaml::Value::String(self.read_string()?),
+            c if c == b'-' || c.is_ascii_digit() => self.read_number(),
+            _ => self.read_expression(),
+        };
+        self.skip_expression_tail();
+        Some(value)
+    }
+
+    fn read_object(&mut self) -> Option<serde_yaml::Value> {
+        let mut map = serde_yaml::Mapping::new();
+        self.pos += 1; // '{'
+
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                b'}' => {
+                    self.pos += 1;
+                    return Some(serde_yaml::Value::Mapping(map));
+                }
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                b'.' => {
+                    // Spread (`...other`) - contents are unknown
+                    self.pos += 3;
+                    self.read_value()?;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let key = self.read_key()?;
+            self.skip_trivia();
+            if self.peek() == Some(b'?') {
+                self.pos += 1;
+                self.skip_trivia();
+            }
+            let value = match self.peek()? {
+                b':' => {
+                    self.pos += 1;
+                    self.read_value()?
+                }
+                // Shorthand property (`{ functions }`) refers to a binding
+                // defined elsewhere, which we don't evaluate.
+                _ => serde_yaml::Value::Null,
+            };
+            if let Some(key) = key {
+                map.insert(serde_yaml::Value::String(key), value);
+            }
+        }
+    }
+
+    /// Read an object key. Computed keys (`[name]`) yield `None`.
+    fn read_key(&mut self) -> Option<Option<String>> {
+        match self.peek()? {
+            b'\'' | b'"' | b'`' => Some(Some(self.read_string()?)),
+            b'[' => {
+                self.read_array()?;
+                Some(None)
+            }
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80 {
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                if self.pos == start {
+                    return None;
+                }
+                Some(Some(
+                    String::from_utf8_lossy(&self.src[start..self.pos]).to_string(),
+                ))
+            }
+        }
+    }
+
+    fn read_array(&mut self) -> Option<serde_yaml::Value> {
+        let mut items = Vec::new();
+        self.pos += 1; // '['
+
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                b']' => {
+                    self.pos += 1;
+                    return Some(serde_yaml::Value::Sequence(items));
+                }
+                b',' => self.pos += 1,
+                b'.' => {
+                    self.pos += 3;
+                    self.read_value()?;
+                }
+                _ => items.push(self.read_value()?),
+            }
+        }
+    }
+
+    /// Read a quoted or template string. Template substitutions are kept
+    /// verbatim, which matches Serverless variable syntax.
+    fn read_string(&mut self) -> Option<String> {
+        let quote = self.peek()?;
+        self.pos += 1;
+        let mut out = Vec::new();
+
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                b'\\' => {
+                    let escaped = self.peek()?;
+                    self.pos += 1;
+                    out.push(match escaped {
+                        b'n' => b'\n',
+                        b't' => b'\t',
+                        other => other,
+                    });
+                }
+                c if c == quote => return Some(String::from_utf8_lossy(&out).to_string()),
+                c => out.push(c),
+            }
+        }
+
+        None
+    }
+
+    fn read_number(&mut self) -> serde_yaml::Value {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_alphanumeric() || matches!(c, b'.' | b'-' | b'+' | b'_') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&self.src[start..self.pos]).replace('_', "");
+        if let Ok(n) = text.parse::<i64>() {
+            serde_yaml::Value::Number(n.into())
+        } else if let Ok(n) = text.parse::<f64>() {
+            serde_yaml::Value::Number(n.into())
+        } else {
+            serde_yaml::Value::String(text)
+        }
+    }
+
+    /// Read a non-literal expression (identifier, call, member access) and
+    /// map it to a YAML value. Only `true`, `false` and `null` are understood.
+    fn read_expression(&mut self) -> serde_yaml::Value {
+        let start = self.pos;
+        self.skip_until_delimiter();
+        match String::from_utf8_lossy(&self.src[start..self.pos]).trim() {
+            "true" => serde_yaml::Value::Bool(true),
+            "false" => serde_yaml::Value::Bool(false),
+            _ => serde_yaml::Value::Null,
+        }
+    }
+
+    /// Skip anything after a value up to the next `,`, `}` or `]` (e.g.,
+    /// `as const` or `|| 'default'`).
+    fn skip_expression_tail(&mut self) {
+        self.skip_trivia();
+        if !matches!(self.peek(), Some(b',' | b'}' | b']') | None) {
+            self.skip_until_delimiter();
+        }
+    }
+
+    /// Advance to the next `,`, `}` or `]` at nesting depth zero.
+    fn skip_until_delimiter(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                b'\'' | b'"' | b'`' => {
+                    if self.read_string().is_none() {
+                        return;
+                    }
+                    continue;
+                }
+                b'(' | b'{' | b'[' => depth += 1,
+                b')' | b'}' | b']' if depth > 0 => depth -= 1,
+                b',' | b'}' | b']' if depth == 0 => return,
+                b';' if depth == 0 => return,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+impl Parser for ServerlessParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "serverless"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["yml", "yaml", "json", "ts", "js"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        if !Self::is_serverless_filename(path) {
+            return Ok(Vec::new());
+        }
+
+        let config = self.parse_config(path, content)?;
+        Ok(self.extract(&config, path, content))
+    }
+
+    /// Custom repository parsing that only visits serverless config files.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let files = repo_files(repo_path, is_ignored_serverless_dir, |path| {
+            Self::is_serverless_filename(path)
+        });
+        let cache = cache.for_parser(self.name(), self.version());
+        Ok(RepoParse::parse_files(&files, |path, content| {
+            cache.get_or_parse(path, content, || self.parse_file(path, content))
+        }))
+    }
+}
+
+/// Directories to skip during serverless config scanning.
+fn is_ignored_serverless_dir(name: &str) -> bool {
+    matches!(
+        name,
+        "node_modules"
+            | ".git"
+            | ".serverless"
+            | "target"
+            | "dist"
+            | "build"
+            | "__pycache__"
+            | "venv"
+            | ".venv"
+            | ".terraform"
+            | ".idea"
+            | ".vscode"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(path: &str, content: &str) -> Vec<Discovery> {
+        let parser = ServerlessParser::new().unwrap();
+        parser.parse_file(Path::new(path), content).unwrap()
+    }
+
+    fn services(discoveries: &[Discovery]) -> Vec<&ServiceDiscovery> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn relationships(discoveries: &[Discovery]) -> Vec<&RelationshipDiscovery> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some(r),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn endpoints(discoveries: &[Discovery]) -> Vec<&ApiEndpointDiscovery> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::ApiEndpoint(e) => Some(e),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const ORDERS_SERVERLESS: &str = r#"
+service: orders
+
+provider:
+  name: aws
+  runtime: nodejs20.x
+  stage: ${opt:stage, 'staging'}
+  region: us-west-2
+  tags:
+    team: checkout
+  environment:
+    ORDERS_TABLE: ${self:service}-orders-${sls:stage}
+
+functions:
+  createOrder:
+    handler: src/create.handler
+    events:
+      - http:
+          method: post
+          path: orders
+      - httpApi: 'GET /orders/{id}'
+  processPayment:
+    handler: src/payments.handler
+    runtime: python3.12
+    events:
+      - sqs:
+          arn: !GetAtt PaymentsQueue.Arn
+      - sns: order-events
+  nightly:
+    name: orders-nightly-report
+    handler: src/report.handler
+    events:
+      - schedule: rate(1 day)
+      - stream:
+          type: dynamodb
+          arn: arn:aws:dynamodb:us-west-2:123456789012:table/audit/stream/2024-01-01T00:00:00.000
+
+resources:
+  Resources:
+    OrdersTable:
+      Type: AWS::DynamoDB::Table
+      Properties:
+        TableName: ${self:service}-orders-${sls:stage}
+    PaymentsQueue:
+      Type: AWS::SQS::Queue
+      Properties:
+        QueueName: payments-${sls:stage}
+"#;
+
+    // ==================== File Detection Tests ====================
+
+    #[test]
+    fn test_is_serverless_filename() {
+        assert!(ServerlessParser::is_serverless_filename(Path::new(
+            "serverless.yml"
+        )));
+        assert!(ServerlessParser::is_serverless_filename(Path::new(
+            "api/serverless.ts"
+        )));
+        assert!(!ServerlessParser::is_serverless_filename(Path::new(
+            "template.yaml"
+        )));
+        assert!(!ServerlessParser::is_serverless_filename(Path::new(
+            "serverless-config.yml"
+        )));
+    }
+
+    #[test]
+    fn test_non_serverless_file_ignored() {
+        assert!(parse("config.yml", ORDERS_SERVERLESS).is_empty());
+    }
+
+    // ==================== Function and Event Tests ====================
+
+    #[test]
+    fn test_functions_become_services() {
+        let discoveries = parse("serverless.yml", ORDERS_SERVERLESS);
+        let services = services(&discoveries);
+
+        assert_eq!(services.len(), 3);
+
+        let create = services
+            .iter()
+            .find(|s| s.name == "orders-staging-createOrder")
+            .expect("default function name should be service-stage-key");
+        assert_eq!(create.language, "javascript");
+        assert_eq!(create.framework.as_deref(), Some("aws-lambda"));
+        assert_eq!(create.entry_point, "src/create.handler");
+        assert!(create.source_line > 1);
+
+        let metadata = create.deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "serverless");
+        assert_eq!(metadata.environment.as_deref(), Some("staging"));
+        assert_eq!(metadata.stack_name.as_deref(), Some("orders-staging"));
+        assert_eq!(metadata.tags.get("team"), Some(&"checkout".to_string()));
+        assert_eq!(metadata.tags.get("region"), Some(&"us-west-2".to_string()));
+
+        let payments = services
+            .iter()
+            .find(|s| s.name == "orders-staging-processPayment")
+            .unwrap();
+        assert_eq!(payments.language, "python");
+
+        let nightly = services
+            .iter()
+            .find(|s| s.name == "orders-nightly-report")
+            .expect("explicit function name should be used");
+        assert_eq!(
+            nightly
+                .deployment_metadata
+                .as_ref()
+                .unwrap()
+                .tags
+                .get("schedule"),
+            Some(&"rate(1 day)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_http_events_become_endpoints() {
+        let discoveries = parse("serverless.yml", ORDERS_SERVERLESS);
+        let endpoints = endpoints(&discoveries);
+
+        assert_eq!(endpoints.len(), 2);
+        assert!(endpoints.iter().any(|e| e.method == "POST"
+            && e.path == "/orders"
+            && e.service.as_deref() == Some("orders-staging-createOrder")));
+        assert!(
+            endpoints
+                .iter()
+                .any(|e| e.method == "GET" && e.path == "/orders/{id}")
+        );
+    }
+
+    #[test]
+    fn test_event_sources_become_subscriptions() {
+        let discoveries = parse("serverless.yml", ORDERS_SERVERLESS);
+        let relationships = relationships(&discoveries);
+
+        // SQS via !GetAtt resolves to the declared queue's physical name
+        assert!(
+            relationships
+                .iter()
+                .any(|r| r.source_service == "orders-staging-processPayment"
+                    && r.target == "payments-staging"
+                    && r.target_kind == ComponentKind::Queue
+                    && r.relationship == RelationshipType::Subscribes)
+        );
+
+        // SNS topic by name
+        assert!(
+            relationships
+                .iter()
+                .any(|r| r.target == "order-events"
+                    && r.relationship == RelationshipType::Subscribes)
+        );
+
+        // DynamoDB stream reads the table named in the ARN
+        assert!(
+            relationships
+                .iter()
+                .any(|r| r.source_service == "orders-nightly-report"
+                    && r.target == "audit"
+                    && r.target_kind == ComponentKind::Database
+                    && r.relationship == RelationshipType::Reads)
+        );
+
+        let topic = discoveries.iter().find_map(|d| match d {
+            Discovery::QueueOperation(q) if q.queue_type == "sns" => Some(q),
+            _ => None,
+        });
+        assert_eq!(
+            topic.unwrap().operation,
+            QueueOperationType::Subscribe,
+            "event sources are consumed by the function"
+        );
+    }
+
+    #[test]
+    fn test_resources_and_environment() {
+        let discoveries = parse("serverless.yml", ORDERS_SERVERLESS);
+
+        // Resources block goes through the CloudFormation parser with
+        // variables resolved
+        let table = discoveries
+            .iter()
+            .find_map(|d| match d {
+                Discovery::DatabaseAccess(db) if db.detection_method == "serverless" => {
+                    db.table_name.clone()
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(table, "orders-orders-staging");
+
+        // Provider environment matching a declared table applies to every function
+        let depends_on: Vec<_> = relationships(&discoveries)
+            .into_iter()
+            .filter(|r| r.relationship == RelationshipType::DependsOn)
+            .collect();
+        assert_eq!(depends_on.len(), 3);
+        assert!(depends_on.iter().all(
+            |r| r.target == "orders-orders-staging" && r.target_kind == ComponentKind::Database
+        ));
+
+        let variables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::EnvironmentVariable(v) => Some((v.name.as_str(), v.value.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            variables,
+            vec![("ORDERS_TABLE", "orders-orders-staging"); 3]
+        );
+    }
+
+    #[test]
+    fn test_function_environment_overrides_provider() {
+        let content = r#"
+service: billing
+provider:
+  name: aws
+  environment:
+    STAGE: ${sls:stage}
+    QUEUE_URL: https://sqs.us-east-1.amazonaws.com/123/default
+functions:
+  charge:
+    handler: charge.handler
+    environment:
+      QUEUE_URL:
+        Ref: InvoicesQueue
+resources:
+  Resources:
+    InvoicesQueue:
+      Type: AWS::SQS::Queue
+      Properties:
+        QueueName: ${self:service}-invoices
+"#;
+        let discoveries = parse("serverless.yml", content);
+        let variables: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::EnvironmentVariable(v) => {
+                    Some((v.service.as_str(), v.name.as_str(), v.value.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            variables,
+            vec![
+                ("billing-dev-charge", "STAGE", "dev"),
+                ("billing-dev-charge", "QUEUE_URL", "billing-invoices"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_stage_and_service_map() {
+        let content = r#"
+service:
+  name: users
+provider:
+  name: aws
+functions:
+  hello:
+    handler: handler.hello
+    events:
+      - http: ANY {proxy+}
+"#;
+        let discoveries = parse("serverless.yaml", content);
+        let services = services(&discoveries);
+
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].name, "users-dev-hello");
+        assert_eq!(
+            services[0]
+                .deployment_metadata
+                .as_ref()
+                .unwrap()
+                .environment
+                .as_deref(),
+            Some("dev")
+        );
+
+        let endpoints = endpoints(&discoveries);
+        assert_eq!(endpoints[0].method, "ANY");
+        assert_eq!(endpoints[0].path, "/{proxy+}");
+    }
+
+    #[test]
+    fn test_missing_service_ignored() {
+        let content = "functions:\n  hello:\n    handler: handler.hello\n";
+        assert!(parse("serverless.yml", content).is_empty());
+    }
+
+    // ==================== Variable Resolution Tests ====================
+
+    #[test]
+    fn test_resolve_variables() {
+        let config: serde_yaml::Value = serde_yaml::from_str(
+            "custom:\n  tableName: ${self:service}-users\n  nested:\n    ttl: 30\n",
+        )
+        .unwrap();
+        let ctx = ServerlessContext {
+            config: &config,
+            service: "accounts".to_string(),
+            stage: "prod".to_string(),
+            region: None,
+            resources: HashMap::new(),
+            source_file: "serverless.yml".to_string(),
+        };
+
+        assert_eq!(
+            ctx.resolve_variables("${self:custom.tableName}-${sls:stage}", true, 0),
+            "accounts-users-prod"
+        );
+        assert_eq!(
+            ctx.resolve_variables("${self:custom.nested.ttl}", true, 0),
+            "30"
+        );
+        assert_eq!(
+            ctx.resolve_variables("${opt:region, 'eu-west-1'}", true, 0),
+            "eu-west-1"
+        );
+        assert_eq!(
+            ctx.resolve_variables("${opt:stage, ${self:custom.missing, 'qa'}}", false, 0),
+            "qa"
+        );
+        assert_eq!(
+            ctx.resolve_variables("${env:API_KEY}", true, 0),
+            "${env:API_KEY}"
+        );
+    }
+
+    #[test]
+    fn test_name_from_arn() {
+        assert_eq!(name_from_arn("arn:aws:sqs:us-east-1:123:orders"), "orders");
+        assert_eq!(
+            name_from_arn("arn:aws:kinesis:us-east-1:123:stream/clicks"),
+            "clicks"
+        );
+        assert_eq!(name_from_arn("plain-topic"), "plain-topic");
+        assert_eq!(
+            dynamodb_table_from_arn("arn:aws:dynamodb:us-east-1:123:table/users/stream/x"),
+            Some("users".to_string())
+        );
+    }
+
+    // ==================== serverless.ts Tests ====================
+
+    #[test]
+    fn test_typescript_config() {
+        let content = r#"
+import type { AWS } from '@serverless/typescript';
+import hello from '@functions/hello';
+
+const serverlessConfiguration: AWS = {
+  service: 'inventory',
+  frameworkVersion: '3',
+  plugins: ['serverless-esbuild'],
+  provider: {
+    name: 'aws',
+    runtime: 'nodejs18.x',
+    stage: "${opt:stage, 'prod'}",
+    environment: {
+      AWS_NODEJS_CONNECTION_REUSE_ENABLED: '1',
+    },
+  },
+  // Functions are defined inline and imported
+  functions: {
+    hello,
+    restock: {
+      handler: `src/restock.main`,
+      events: [
+        { httpApi: { method: 'put', path: '/stock/{sku}' } },
+        { sqs: { arn: { 'Fn::GetAtt': ['RestockQueue', 'Arn'] }, batchSize: 10 } },
+      ],
+    },
+  },
+  resources: {
+    Resources: {
+      RestockQueue: {
+        Type: 'AWS::SQS::Queue',
+        Properties: { QueueName: 'restock-${sls:stage}' },
+      },
+    },
+  },
+};
+
+module.exports = serverlessConfiguration;
+"#;
+        let discoveries = parse("serverless.ts", content);
+
+        let names: Vec<_> = services(&discoveries)
+            .iter()
+            .map(|s| s.name.clone())
+            .collect();
+        assert!(names.contains(&"inventory-prod-restock".to_string()));
+        // Shorthand properties can't be evaluated but still declare a function
+        assert!(names.contains(&"inventory-prod-hello".to_string()));
+
+        let endpoints = endpoints(&discoveries);
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].method, "PUT");
+        assert_eq!(endpoints[0].path, "/stock/{sku}");
+
+        assert!(
+            relationships(&discoveries)
+                .iter()
+                .any(|r| r.source_service == "inventory-prod-restock"
+                    && r.target == "restock-prod"
+                    && r.relationship == RelationshipType::Subscribes)
+        );
+    }
+
+    #[test]
+    fn test_read_config_literal_handles_syntax() {
+        let content = r#"
+export default {
+  service: "api", /* block comment */
+  provider: { name: 'aws', memorySize: 512, tracing: true, role: process.env.ROLE },
+  custom: { ...shared, list: [1, 'two', `three`,], },
+} satisfies AWS;
+"#;
+        let value = read_config_literal(content).unwrap();
+
+        assert_eq!(value.get("service").and_then(|v| v.as_str()), Some("api"));
+        let provider = value.get("provider").unwrap();
+        assert_eq!(
+            provider.get("memorySize").and_then(|v| v.as_i64()),
+            Some(512)
+        );
+        assert_eq!(
+            provider.get("tracing").and_then(|v| v.as_bool()),
+            Some(true)
+        );
+        assert!(provider.get("role").unwrap().is_null());
+        assert_eq!(
+            value
+                .get("custom")
+                .and_then(|c| c.get("list"))
+                .and_then(|l| l.as_sequence())
+                .map(|l| l.len()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_typescript_without_config_errors() {
+        let parser = ServerlessParser::new().unwrap();
+        let result = parser.parse_file(Path::new("serverless.ts"), "export const x = 1;");
+        assert!(result.is_err());
+    }
+}
@@ -6,19 +6,98 @@
 //! - aws_sns_topic resources
 //! - aws_s3_bucket resources
 //! - aws_lambda_function resources
+//! - RDS instances and Aurora clusters (cluster instances are folded into
+//!   their cluster) and ElastiCache caches, as databases
+//! - Kinesis streams, EventBridge buses and MSK clusters, as queues
+//! - EventBridge rules, API Gateway REST and HTTP APIs, Secrets Manager
+//!   secrets and SSM parameters, as cloud resources
+//! - API Gateway routes and methods, as API endpoints owned by the Lambda
+//!   function they integrate with
+//! - Step Functions state machines and ECS services, as services
+//!
+//! Relationship resources become edges between the resources they reference:
+//! - aws_sns_topic_subscription: a Lambda endpoint subscribes to the topic
+//! - aws_lambda_event_source_mapping: the function subscribes to the queue or
+//!   stream (or reads the DynamoDB table whose stream it consumes)
+//! - aws_cloudwatch_event_target: the target uses the rule and subscribes to
+//!   the rule's event bus
+//! - aws_s3_bucket_notification: notified functions use the bucket
+//!
+//! State machines call the Lambda functions their definition references.
+//! Graph edges always start at a service, so subscriptions of queues to
+//! topics or buses are not recorded.
+//!
+//! A Terraform module spans every `.tf` file in its directory, so references
+//! (`aws_sqs_queue.orders.arn`) are resolved per directory.
+//!
+//! Expressions are evaluated before extraction (see [`super::terraform_eval`]),
+//! so interpolated names such as `"${var.env}-orders"` resolve. Variables come
+//! from their defaults and from `.tfvars` files: `terraform.tfvars` and
+//! `*.auto.tfvars` always apply, and every other `.tfvars` file (next to the
+//! module or in an `env/`, `envs/`, `environments/` or `vars/` directory) is
+//! treated as one workspace, named after the file, for which the module is
+//! evaluated separately. Local `module` sources are followed, and the
+//! resources they define are reported with the caller, using the values the
+//! caller passes in; directories only used as modules are not reported on
+//! their own.
 //!
 //! Extracts deployment metadata from:
 //! - Resource tags (ManagedBy, Environment, terraform:workspace)
 //! - Backend configuration (workspace from S3 key path)
+//! - Typed resource settings (engine, instance class, shard count, ...)
 
+use super::serverless::name_from_arn;
+use super::terraform_eval::{ModuleScope, tfvars_values};
 use super::traits::*;
+use crate::cache::DiscoveryCache;
+use forge_graph::{AttributeValue, NodeType};
 use std::any::Any;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component as PathComponent, Path, PathBuf};
+
+/// How deep nested local modules are followed.
+const MAX_MODULE_DEPTH: usize = 8;
+
+/// Directories whose `.tfvars` files belong to the module in their parent.
+const VAR_FILE_DIRS: &[&str] = &["env", "envs", "environments", "vars"];
 
 /// Parser for Terraform HCL files
 pub struct TerraformParser {}
 
+/// A `resource` block of a Terraform module.
+struct TerraformResource<'a> {
+    resource_type: &'a str,
+    tf_name: &'a str,
+    block: &'a hcl::Block,
+    path: &'a Path,
+}
+
+impl TerraformResource<'_> {
+    /// The address other blocks use to reference this resource
+    /// (`aws_sqs_queue.orders`).
+    fn address(&self) -> String {
+        format!("{}.{}", self.resource_type, self.tf_name)
+    }
+
+    fn attribute(&self, key: &str) -> Option<&hcl::Expression> {
+        attribute_expr(self.block.body(), key)
+    }
+}
+
+/// The `.tf` and `.tfvars` files of one module directory.
+#[derive(Default)]
+struct ModuleFiles {
+    tf: Vec<(PathBuf, hcl::Body)>,
+    tfvars: Vec<(PathBuf, hcl::Body)>,
+}
+
+/// The graph component a Terraform resource was turned into.
+#[derive(Debug, Clone)]
+struct Component {
+    name: String,
+    kind: ComponentKind,
+}
+
 impl TerraformParser {
     pub fn new() -> Result<Self, ParserError> {
         Ok(Self {})
@@ -29,23 +108,191 @@ impl TerraformParser {
             .map_err(|e| ParserError::TreeSitterError(format!("HCL parse error: {}", e)))
     }
 
-    fn extract_resources(&self, body: &hcl::Body, path: &Path) -> Vec<Discovery> {
+    /// Evaluate one module instance and extract its discoveries, including
+    /// those of the local modules it calls.
+    ///
+    /// `inputs` are the variable values given to the module. `workspace`
+    /// overrides the workspace of the backend configuration; called modules
+    /// inherit the caller's. Returns the discoveries and the module's
+    /// evaluated outputs.
+    fn evaluate_module(
+        &self,
+        dir: &Path,
+        files: &[(PathBuf, hcl::Body)],
+        inputs: &hcl::Map<String, hcl::Value>,
+        workspace: Option<&str>,
+        modules: &BTreeMap<PathBuf, ModuleFiles>,
+        depth: usize,
+    ) -> (Vec<Discovery>, hcl::Map<String, hcl::Value>) {
+        // Extract terraform workspace from backend config (usually in its own file)
+        let workspace = workspace.map(|w| w.to_string()).or_else(|| {
+            files
+                .iter()
+                .find_map(|(_, body)| self.extract_backend_workspace(body))
+        });
+        let bodies: Vec<&hcl::Body> = files.iter().map(|(_, body)| body).collect();
+        let mut scope = ModuleScope::new(
+            &bodies,
+            inputs,
+            workspace.as_deref().unwrap_or("default"),
+            &dir.to_string_lossy(),
+        );
+
         let mut discoveries = Vec::new();
+        let module_blocks = bodies
+            .iter()
+            .flat_map(|body| body.blocks())
+            .filter(|block| block.identifier() == "module");
+        for block in module_blocks {
+            let Some(name) = block.labels().first().map(|l| l.as_str()) else {
+                continue;
+            };
+            let Some(child_dir) = self
+                .get_string_attribute(block.body(), "source")
+                .and_then(|source| local_module_dir(dir, &source))
+            else {
+                // Registry and remote modules are not available locally
+                continue;
+            };
+            if depth >= MAX_MODULE_DEPTH {
+                tracing::debug!("Not following module {} beyond depth {}", name, depth);
+                continue;
+            }
+
+            let child_files = match modules.get(&child_dir) {
+                Some(module) => module.tf.clone(),
+                None => self.load_module_dir(&child_dir),
+            };
+            let arguments = scope.module_arguments(block);
+            let (child_discoveries, outputs) = self.evaluate_module(
+                &child_dir,
+                &child_files,
+                &arguments,
+                workspace.as_deref(),
+                modules,
+                depth + 1,
+            );
+            discoveries.extend(child_discoveries);
+            scope.add_module_outputs(name, outputs);
+        }
+
+        let evaluated: Vec<(PathBuf, hcl::Body)> = files
+            .iter()
+            .map(|(path, body)| {
+                let mut body = body.clone();
+                scope.evaluate_in_place(&mut body);
+                (path.clone(), body)
+            })
+            .collect();
+        discoveries.extend(self.extract_module(&evaluated, workspace.as_deref()));
 
-        // Extract terraform workspace from backend config
-        let workspace = self.extract_backend_workspace(body);
+        (discoveries, scope.outputs(&bodies))
+    }
 
-        for block in body.blocks() {
-            let ident: &str = block.identifier();
-            if ident == "resource" {
-                if let Some(discovery) =
-                    self.process_resource_block(block, path, workspace.as_deref())
-                {
-                    discoveries.push(discovery);
+    /// Parse the `.tf` files of a module directory outside the surveyed tree.
+    fn load_module_dir(&self, dir: &Path) -> Vec<(PathBuf, hcl::Body)> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            tracing::debug!("Module directory {} not found", dir.display());
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("tf"))
+            .collect();
+        paths.sort();
+
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let content = std::fs::read_to_string(&path).ok()?;
+                match self.parse_hcl(&content) {
+                    Ok(body) => Some((path, body)),
+                    Err(e) => {
+                        tracing::warn!("Failed to parse {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// The variable values of each workspace a module is deployed to.
+    ///
+    /// `terraform.tfvars` and `*.auto.tfvars` apply to every workspace; each
+    /// other `.tfvars` file defines a workspace named after the file. Without
+    /// such files there is a single, unnamed workspace.
+    fn workspace_inputs(
+        &self,
+        tfvars: &[(PathBuf, hcl::Body)],
+    ) -> Vec<(Option<String>, hcl::Map<String, hcl::Value>)> {
+        let mut shared = hcl::Map::new();
+        let mut workspaces = Vec::new();
+        for (path, body) in tfvars {
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if file_name == "terraform.tfvars" || file_name.ends_with(".auto.tfvars") {
+                shared.extend(tfvars_values(body));
+            } else if let Some(workspace) = file_name.strip_suffix(".tfvars") {
+                workspaces.push((workspace.to_string(), tfvars_values(body)));
+            }
+        }
+
+        if workspaces.is_empty() {
+            return vec![(None, shared)];
+        }
+        workspaces
+            .into_iter()
+            .map(|(workspace, values)| {
+                let mut inputs = shared.clone();
+                inputs.extend(values);
+                (Some(workspace), inputs)
+            })
+            .collect()
+    }
+
+    /// Extract discoveries from the (evaluated) files of one module.
+    ///
+    /// Resources are processed first; relationship resources are processed
+    /// afterwards, resolving their references against the components found.
+    fn extract_module(
+        &self,
+        files: &[(PathBuf, hcl::Body)],
+        workspace: Option<&str>,
+    ) -> Vec<Discovery> {
+        let resources: Vec<TerraformResource> = files
+            .iter()
+            .flat_map(|(path, body)| {
+                body.blocks()
+                    .filter(|block| block.identifier() == "resource")
+                    .filter_map(move |block| {
+                        let labels = block.labels();
+                        if labels.len() < 2 {
+                            return None;
+                        }
+                        Some(TerraformResource {
+                            resource_type: labels[0].as_str(),
+                            tf_name: labels[1].as_str(),
+                            block,
+                            path,
+                        })
+                    })
+            })
+            .collect();
+
+        let mut discoveries = Vec::new();
+        let mut components = HashMap::new();
+        for resource in &resources {
+            if let Some(discovery) = self.process_resource_block(resource, &resources, workspace) {
+                if let Some(component) = component_of(&discovery) {
+                    components.insert(resource.address(), component);
                 }
+                discoveries.push(discovery);
             }
         }
 
+        for resource in &resources {
+            discoveries.extend(self.process_relationship_block(resource, &resources, &components));
+        }
+
         discoveries
     }
 
@@ -82,7 +329,6 @@ impl TerraformParser {
         }
         None
     }
-
     /// Parse workspace from S3 key path.
     ///
     /// Examples:
@@ -112,20 +358,16 @@ impl TerraformParser {
 
     fn process_resource_block(
         &self,
-        block: &hcl::Block,
-        path: &Path,
+        resource: &TerraformResource,
+        resources: &[TerraformResource],
         backend_workspace: Option<&str>,
     ) -> Option<Discovery> {
-        let labels = block.labels();
-
-        if labels.len() < 2 {
-            return None;
-        }
+        resource_node(resource.resource_type)?;
+        let block = resource.block;
+        let resource_name = resource.tf_name;
+        let path = resource.path;
 
-        let resource_type = labels[0].as_str();
-        let resource_name = labels[1].as_str();
-
-        match resource_type {
+        match resource.resource_type {
             "aws_dynamodb_table" => {
                 self.parse_dynamodb_table(block, resource_name, path, backend_workspace)
             }
@@ -135,10 +377,93 @@ impl TerraformParser {
             "aws_lambda_function" => {
                 self.parse_lambda_function(block, resource_name, path, backend_workspace)
             }
+            "aws_db_instance" => {
+                self.parse_db_instance(block, resource_name, path, backend_workspace)
+            }
+            "aws_rds_cluster" => {
+                self.parse_rds_cluster(block, resource_name, path, backend_workspace, resources)
+            }
+            "aws_elasticache_cluster"
+            | "aws_elasticache_replication_group"
+            | "aws_elasticache_serverless_cache" => self.parse_elasticache(
+                resource.resource_type,
+                block,
+                resource_name,
+                path,
+                backend_workspace,
+            ),
+            "aws_kinesis_stream" => {
+                self.parse_kinesis_stream(block, resource_name, path, backend_workspace)
+            }
+            "aws_cloudwatch_event_bus" => {
+                self.parse_event_bus(block, resource_name, path, backend_workspace)
+            }
+            "aws_msk_cluster" | "aws_msk_serverless_cluster" => self.parse_msk_cluster(
+                resource.resource_type,
+                block,
+                resource_name,
+                path,
+                backend_workspace,
+            ),
+            "aws_cloudwatch_event_rule" => {
+                self.parse_event_rule(block, resource_name, path, backend_workspace)
+            }
+            "aws_api_gateway_rest_api" | "aws_apigatewayv2_api" => self.parse_api_gateway(
+                resource.resource_type,
+                block,
+                resource_name,
+                path,
+                backend_workspace,
+            ),
+            "aws_secretsmanager_secret" => {
+                self.parse_secret(block, resource_name, path, backend_workspace)
+            }
+            "aws_ssm_parameter" => {
+                self.parse_ssm_parameter(block, resource_name, path, backend_workspace)
+            }
+            "aws_sfn_state_machine" => {
+                self.parse_state_machine(block, resource_name, path, backend_workspace)
+            }
+            "aws_ecs_service" => {
+                self.parse_ecs_service(block, resource_name, path, backend_workspace, resources)
+            }
             _ => None,
         }
     }
 
+    /// Process resources that connect other resources.
+    fn process_relationship_block(
+        &self,
+        resource: &TerraformResource,
+        resources: &[TerraformResource],
+        components: &HashMap<String, Component>,
+    ) -> Vec<Discovery> {
+        match resource.resource_type {
+            "aws_sns_topic_subscription" => self
+                .parse_topic_subscription(resource, components)
+                .into_iter()
+                .collect(),
+            "aws_lambda_event_source_mapping" => self
+                .parse_event_source_mapping(resource, components)
+                .into_iter()
+                .collect(),
+            "aws_cloudwatch_event_target" => {
+                self.parse_event_target(resource, resources, components)
+            }
+            "aws_s3_bucket_notification" => self.parse_bucket_notification(resource, components),
+            "aws_sfn_state_machine" => self.parse_state_machine_references(resource, components),
+            "aws_apigatewayv2_route" => self
+                .parse_apigatewayv2_route(resource, resources, components)
+                .into_iter()
+                .collect(),
+            "aws_api_gateway_method" => self
+                .parse_api_gateway_method(resource, resources, components)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     /// Extract tags from a resource block.
     ///
     /// Handles both simple tags attribute and tags_all.
@@ -221,6 +546,7 @@ impl TerraformParser {
             environment,
             stack_name: None, // Not applicable for Terraform
             tags,
+            resource_attributes: HashMap::new(),
         }
     }
 
@@ -241,6 +567,7 @@ impl TerraformParser {
         Some(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
             db_type: "dynamodb".to_string(),
             table_name: Some(table_name),
+            connection: None,
             operation: DatabaseOperation::Unknown, // Terraform defines the table, not operations
             detection_method: "terraform".to_string(),
             source_file: path.to_string_lossy().to_string(),
@@ -366,136 +693,1768 @@ impl TerraformParser {
         }))
     }
 
-    fn get_string_attribute(&self, body: &hcl::Body, key: &str) -> Option<String> {
-        body.attributes()
-            .find(|attr| attr.key() == key)
-            .and_then(|attr| match attr.expr() {
-                hcl::Expression::String(s) => Some(s.to_string()),
-                _ => None,
-            })
+    /// Deployment metadata for a resource, including its typed settings.
+    fn resource_metadata(
+        &self,
+        block: &hcl::Block,
+        backend_workspace: Option<&str>,
+        resource_attributes: HashMap<String, AttributeValue>,
+    ) -> DeploymentMetadata {
+        let tags = self.extract_tags(block.body());
+        let mut metadata = self.build_deployment_metadata(tags, backend_workspace);
+        metadata.resource_attributes = resource_attributes;
+        metadata
     }
-}
 
-impl Parser for TerraformParser {
-    fn as_any(&self) -> &dyn Any {
-        self
+    /// Literal settings of a resource as typed attributes.
+    ///
+    /// `keys` pairs each HCL attribute with the node attribute it is stored
+    /// as. Computed values (references, function calls) are skipped.
+    fn typed_attributes(
+        &self,
+        body: &hcl::Body,
+        keys: &[(&str, &str)],
+    ) -> HashMap<String, AttributeValue> {
+        keys.iter()
+            .filter_map(|(key, name)| {
+                attribute_expr(body, key)
+                    .and_then(literal_value)
+                    .map(|value| (name.to_string(), value))
+            })
+            .collect()
     }
 
-    fn supported_extensions(&self) -> &[&str] {
-        &["tf"]
-    }
+    fn parse_db_instance(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let instance_name = self
+            .get_string_attribute(body, "identifier")
+            .or_else(|| self.get_string_attribute(body, "db_name"))
+            .unwrap_or_else(|| tf_name.to_string());
+        let db_type = self
+            .get_string_attribute(body, "engine")
+            .map(|engine| db_type_from_engine(&engine))
+            .unwrap_or_else(|| "rds".to_string());
+
+        let attributes = self.typed_attributes(
+            body,
+            &[
+                ("engine", "engine"),
+                ("engine_version", "engine_version"),
+                ("instance_class", "instance_class"),
+                ("allocated_storage", "allocated_storage"),
+                ("multi_az", "multi_az"),
+                ("db_name", "database_name"),
+            ],
+        );
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
 
-    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
-        let body = self.parse_hcl(content)?;
-        Ok(self.extract_resources(&body, path))
+        Some(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+            db_type,
+            table_name: Some(instance_name),
+            connection: None,
+            operation: DatabaseOperation::Unknown,
+            detection_method: "terraform".to_string(),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_dynamodb_table() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
-resource "aws_dynamodb_table" "users" {
-  name           = "users-table"
-  billing_mode   = "PAY_PER_REQUEST"
-  hash_key       = "id"
-
-  attribute {
-    name = "id"
-    type = "S"
-  }
-}
-"#;
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+    /// Parse an Aurora cluster, folding its `aws_rds_cluster_instance`
+    /// resources into instance count and class attributes.
+    fn parse_rds_cluster(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+        resources: &[TerraformResource],
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let cluster_name = self
+            .get_string_attribute(body, "cluster_identifier")
+            .or_else(|| self.get_string_attribute(body, "database_name"))
+            .unwrap_or_else(|| tf_name.to_string());
+        // Terraform defaults to the MySQL-compatible "aurora" engine
+        let engine = self
+            .get_string_attribute(body, "engine")
+            .unwrap_or_else(|| "aurora".to_string());
+
+        let mut attributes = self.typed_attributes(
+            body,
+            &[
+                ("engine_version", "engine_version"),
+                ("engine_mode", "engine_mode"),
+                ("database_name", "database_name"),
+            ],
+        );
+        attributes.insert("engine".to_string(), AttributeValue::from(engine.as_str()));
 
-        let db_accesses: Vec<_> = discoveries
+        let address = format!("aws_rds_cluster.{}", tf_name);
+        let instances: Vec<&TerraformResource> = resources
             .iter()
-            .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
-                _ => None,
+            .filter(|r| r.resource_type == "aws_rds_cluster_instance")
+            .filter(|r| {
+                r.attribute("cluster_identifier")
+                    .is_some_and(|expr| refers_to(expr, &address, &cluster_name))
             })
             .collect();
+        if !instances.is_empty() {
+            let instance_count: i64 = instances
+                .iter()
+                .map(
+                    |instance| match instance.attribute("count").and_then(literal_value) {
+                        Some(AttributeValue::Integer(count)) => count,
+                        _ => 1,
+                    },
+                )
+                .sum();
+            attributes.insert(
+                "instance_count".to_string(),
+                AttributeValue::Integer(instance_count),
+            );
+            if let Some(instance_class) = instances.iter().find_map(|instance| {
+                self.get_string_attribute(instance.block.body(), "instance_class")
+            }) {
+                attributes.insert(
+                    "instance_class".to_string(),
+                    AttributeValue::String(instance_class),
+                );
+            }
+        }
 
-        assert_eq!(db_accesses.len(), 1);
-        assert_eq!(db_accesses[0].table_name, Some("users-table".to_string()));
-        assert_eq!(db_accesses[0].db_type, "dynamodb");
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
 
-        // Verify deployment metadata
-        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.deployment_method, "terraform");
+        Some(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+            db_type: db_type_from_engine(&engine),
+            table_name: Some(cluster_name),
+            connection: None,
+            operation: DatabaseOperation::Unknown,
+            detection_method: "terraform".to_string(),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
     }
 
-    #[test]
-    fn test_parse_sqs_queue() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
-resource "aws_sqs_queue" "orders" {
-  name = "orders-queue"
-  fifo_queue = false
-}
-"#;
+    fn parse_elasticache(
+        &self,
+        resource_type: &str,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let name_key = match resource_type {
+            "aws_elasticache_cluster" => "cluster_id",
+            "aws_elasticache_replication_group" => "replication_group_id",
+            _ => "name",
+        };
+        let cache_name = self
+            .get_string_attribute(body, name_key)
+            .unwrap_or_else(|| tf_name.to_string());
+        let engine = self
+            .get_string_attribute(body, "engine")
+            .unwrap_or_else(|| "redis".to_string())
+            .to_lowercase();
+        // Valkey is a Redis fork and speaks the same protocol
+        let db_type = match engine.as_str() {
+            "valkey" => "redis".to_string(),
+            _ => engine.clone(),
+        };
+
+        let mut attributes = self.typed_attributes(
+            body,
+            &[
+                ("engine_version", "engine_version"),
+                ("node_type", "node_type"),
+                ("num_cache_nodes", "num_cache_nodes"),
+                ("num_cache_clusters", "num_cache_clusters"),
+                ("automatic_failover_enabled", "automatic_failover_enabled"),
+            ],
+        );
+        attributes.insert("engine".to_string(), AttributeValue::String(engine));
+        if resource_type == "aws_elasticache_serverless_cache" {
+            attributes.insert("serverless".to_string(), AttributeValue::Boolean(true));
+        }
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        Some(Discovery::DatabaseAccess(DatabaseAccessDiscovery {
+            db_type,
+            table_name: Some(cache_name),
+            connection: None,
+            operation: DatabaseOperation::Unknown,
+            detection_method: "terraform".to_string(),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
 
-        let queues: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::QueueOperation(q) => Some(q),
-                _ => None,
-            })
-            .collect();
+    fn parse_kinesis_stream(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let stream_name = self
+            .get_string_attribute(body, "name")
+            .unwrap_or_else(|| tf_name.to_string());
 
-        assert_eq!(queues.len(), 1);
-        assert_eq!(queues[0].queue_name, Some("orders-queue".to_string()));
-        assert_eq!(queues[0].queue_type, "sqs");
+        let mut attributes = self.typed_attributes(
+            body,
+            &[
+                ("shard_count", "shard_count"),
+                ("retention_period", "retention_hours"),
+            ],
+        );
+        if let Some(mode) = body
+            .blocks()
+            .find(|b| b.identifier() == "stream_mode_details")
+            .and_then(|b| self.get_string_attribute(b.body(), "stream_mode"))
+        {
+            attributes.insert("stream_mode".to_string(), AttributeValue::String(mode));
+        }
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
 
-        // Verify deployment metadata
-        let metadata = queues[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.deployment_method, "terraform");
+        Some(Discovery::QueueOperation(QueueOperationDiscovery {
+            queue_type: "kinesis".to_string(),
+            queue_name: Some(stream_name),
+            operation: QueueOperationType::Unknown,
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
     }
 
-    #[test]
-    fn test_parse_lambda_function() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
-resource "aws_lambda_function" "processor" {
-  function_name = "order-processor"
-  runtime       = "python3.9"
-  handler       = "main.handler"
-}
-"#;
-
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-
-        let services: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::Service(s) => Some(s),
-                _ => None,
-            })
-            .collect();
+    fn parse_event_bus(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let bus_name = self
+            .get_string_attribute(block.body(), "name")
+            .unwrap_or_else(|| tf_name.to_string());
+        let metadata = self.resource_metadata(block, backend_workspace, HashMap::new());
 
-        assert_eq!(services.len(), 1);
-        assert_eq!(services[0].name, "order-processor");
-        assert_eq!(services[0].language, "python");
-        assert_eq!(services[0].framework, Some("aws-lambda".to_string()));
+        Some(Discovery::QueueOperation(QueueOperationDiscovery {
+            queue_type: "eventbridge".to_string(),
+            queue_name: Some(bus_name),
+            operation: QueueOperationType::Unknown,
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_msk_cluster(
+        &self,
+        resource_type: &str,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let cluster_name = self
+            .get_string_attribute(body, "cluster_name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        let mut attributes = self.typed_attributes(
+            body,
+            &[
+                ("kafka_version", "kafka_version"),
+                ("number_of_broker_nodes", "broker_count"),
+            ],
+        );
+        if let Some(instance_type) = body
+            .blocks()
+            .find(|b| b.identifier() == "broker_node_group_info")
+            .and_then(|b| self.get_string_attribute(b.body(), "instance_type"))
+        {
+            attributes.insert(
+                "instance_type".to_string(),
+                AttributeValue::String(instance_type),
+            );
+        }
+        if resource_type == "aws_msk_serverless_cluster" {
+            attributes.insert("serverless".to_string(), AttributeValue::Boolean(true));
+        }
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        Some(Discovery::QueueOperation(QueueOperationDiscovery {
+            queue_type: "msk".to_string(),
+            queue_name: Some(cluster_name),
+            operation: QueueOperationType::Unknown,
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_event_rule(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let rule_name = self
+            .get_string_attribute(body, "name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        let attributes = self.typed_attributes(
+            body,
+            &[
+                ("schedule_expression", "schedule_expression"),
+                ("event_pattern", "event_pattern"),
+                ("event_bus_name", "event_bus"),
+                ("state", "state"),
+            ],
+        );
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        Some(Discovery::CloudResourceUsage(CloudResourceDiscovery {
+            resource_type: "eventbridge-rule".to_string(),
+            resource_name: Some(rule_name),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_api_gateway(
+        &self,
+        resource_type: &str,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let api_name = self
+            .get_string_attribute(body, "name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        let mut attributes = self.typed_attributes(body, &[("protocol_type", "protocol_type")]);
+        if resource_type == "aws_api_gateway_rest_api" {
+            attributes.insert("protocol_type".to_string(), AttributeValue::from("REST"));
+        }
+        if let Some(types) = body
+            .blocks()
+            .find(|b| b.identifier() == "endpoint_configuration")
+            .and_then(|b| attribute_expr(b.body(), "types"))
+            .and_then(literal_value)
+        {
+            attributes.insert("endpoint_types".to_string(), types);
+        }
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        // APIs are represented as CloudResources with type "apigateway"
+        Some(Discovery::CloudResourceUsage(CloudResourceDiscovery {
+            resource_type: "apigateway".to_string(),
+            resource_name: Some(api_name),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_secret(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let secret_name = self
+            .get_string_attribute(body, "name")
+            .or_else(|| self.get_string_attribute(body, "name_prefix"))
+            .unwrap_or_else(|| tf_name.to_string());
+
+        let attributes = self.typed_attributes(
+            body,
+            &[("recovery_window_in_days", "recovery_window_in_days")],
+        );
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        Some(Discovery::CloudResourceUsage(CloudResourceDiscovery {
+            resource_type: "secretsmanager".to_string(),
+            resource_name: Some(secret_name),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_ssm_parameter(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let parameter_name = self
+            .get_string_attribute(body, "name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        // The parameter value is never recorded, it may be a secret
+        let attributes =
+            self.typed_attributes(body, &[("type", "parameter_type"), ("tier", "tier")]);
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        Some(Discovery::CloudResourceUsage(CloudResourceDiscovery {
+            resource_type: "ssm".to_string(),
+            resource_name: Some(parameter_name),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_state_machine(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+    ) -> Option<Discovery> {
+        let machine_name = self
+            .get_string_attribute(block.body(), "name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        let attributes = self.typed_attributes(block.body(), &[("type", "state_machine_type")]);
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        // State machines orchestrate other services, so they are services too
+        Some(Discovery::Service(ServiceDiscovery {
+            name: machine_name,
+            language: "unknown".to_string(),
+            framework: Some("step-functions".to_string()),
+            entry_point: format!("aws_sfn_state_machine.{}", tf_name),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    fn parse_ecs_service(
+        &self,
+        block: &hcl::Block,
+        tf_name: &str,
+        path: &Path,
+        backend_workspace: Option<&str>,
+        resources: &[TerraformResource],
+    ) -> Option<Discovery> {
+        let body = block.body();
+        let service_name = self
+            .get_string_attribute(body, "name")
+            .unwrap_or_else(|| tf_name.to_string());
+
+        // The task definition family identifies what the service runs
+        let task_definition = attribute_expr(body, "task_definition").and_then(|expr| {
+            match referenced_resource(resources, expr) {
+                Some(task) => self.get_string_attribute(task.block.body(), "family"),
+                None => self.get_string_attribute(body, "task_definition"),
+            }
+        });
+
+        let mut attributes = self.typed_attributes(
+            body,
+            &[
+                ("launch_type", "launch_type"),
+                ("desired_count", "desired_count"),
+                ("scheduling_strategy", "scheduling_strategy"),
+            ],
+        );
+        let cluster = attribute_expr(body, "cluster").and_then(|expr| {
+            match referenced_resource(resources, expr) {
+                Some(cluster) => self.get_string_attribute(cluster.block.body(), "name"),
+                None => self
+                    .get_string_attribute(body, "cluster")
+                    .map(|c| name_from_arn(&c)),
+            }
+        });
+        if let Some(cluster) = cluster {
+            attributes.insert("ecs_cluster".to_string(), AttributeValue::String(cluster));
+        }
+        let metadata = self.resource_metadata(block, backend_workspace, attributes);
+
+        Some(Discovery::Service(ServiceDiscovery {
+            name: service_name,
+            language: "unknown".to_string(),
+            framework: Some("ecs".to_string()),
+            entry_point: task_definition.unwrap_or_else(|| format!("aws_ecs_service.{}", tf_name)),
+            source_file: path.to_string_lossy().to_string(),
+            source_line: 1,
+            deployment_metadata: Some(metadata),
+        }))
+    }
+
+    /// A relationship from `source` to `target` declared by `resource`.
+    ///
+    /// Graph edges always start at a service, so relationships from any other
+    /// kind of component are dropped.
+    fn relationship(
+        &self,
+        resource: &TerraformResource,
+        source: &Component,
+        target: &Component,
+        relationship: RelationshipType,
+    ) -> Option<Discovery> {
+        if source.kind != ComponentKind::Service {
+            return None;
+        }
+        Some(Discovery::Relationship(RelationshipDiscovery {
+            source_service: source.name.clone(),
+            target: target.name.clone(),
+            target_kind: target.kind,
+            relationship,
+            detection_method: "terraform".to_string(),
+            source_file: resource.path.to_string_lossy().to_string(),
+            source_line: 1,
+        }))
+    }
+
+    /// `aws_sns_topic_subscription`: a Lambda endpoint subscribes to the topic.
+    fn parse_topic_subscription(
+        &self,
+        resource: &TerraformResource,
+        components: &HashMap<String, Component>,
+    ) -> Option<Discovery> {
+        let topic = resolve_component(
+            resource.attribute("topic_arn")?,
+            components,
+            Some(ComponentKind::Queue),
+        )?;
+        let endpoint_kind = match self
+            .get_string_attribute(resource.block.body(), "protocol")?
+            .as_str()
+        {
+            "lambda" => ComponentKind::Service,
+            "sqs" => ComponentKind::Queue,
+            // http(s), email and sms endpoints are outside the graph
+            _ => return None,
+        };
+        let endpoint = resolve_component(
+            resource.attribute("endpoint")?,
+            components,
+            Some(endpoint_kind),
+        )?;
+
+        self.relationship(resource, &endpoint, &topic, RelationshipType::Subscribes)
+    }
+
+    /// `aws_lambda_event_source_mapping`: the function consumes the queue or
+    /// stream, or reads the table whose stream it consumes.
+    fn parse_event_source_mapping(
+        &self,
+        resource: &TerraformResource,
+        components: &HashMap<String, Component>,
+    ) -> Option<Discovery> {
+        let function = resolve_component(
+            resource.attribute("function_name")?,
+            components,
+            Some(ComponentKind::Service),
+        )?;
+        let source = resolve_component(resource.attribute("event_source_arn")?, components, None)?;
+
+        let relationship = match source.kind {
+            ComponentKind::Database => RelationshipType::Reads,
+            _ => RelationshipType::Subscribes,
+        };
+        self.relationship(resource, &function, &source, relationship)
+    }
+
+    /// `aws_cloudwatch_event_target`: the target uses the rule and subscribes
+    /// to the event bus the rule listens on.
+    fn parse_event_target(
+        &self,
+        resource: &TerraformResource,
+        resources: &[TerraformResource],
+        components: &HashMap<String, Component>,
+    ) -> Vec<Discovery> {
+        let mut discoveries = Vec::new();
+        let (Some(rule_expr), Some(arn)) = (resource.attribute("rule"), resource.attribute("arn"))
+        else {
+            return discoveries;
+        };
+        let Some(target) = resolve_component(arn, components, None) else {
+            return discoveries;
+        };
+
+        if let Some(rule) =
+            resolve_component(rule_expr, components, Some(ComponentKind::CloudResource))
+        {
+            discoveries.extend(self.relationship(resource, &target, &rule, RelationshipType::Uses));
+        }
+
+        // Rules without a bus listen on the account's default bus, which is
+        // not declared anywhere
+        let bus = resource.attribute("event_bus_name").or_else(|| {
+            referenced_resource(resources, rule_expr)
+                .and_then(|rule| rule.attribute("event_bus_name"))
+        });
+        if let Some(bus) =
+            bus.and_then(|bus| resolve_component(bus, components, Some(ComponentKind::Queue)))
+        {
+            discoveries.extend(self.relationship(
+                resource,
+                &target,
+                &bus,
+                RelationshipType::Subscribes,
+            ));
+        }
+
+        discoveries
+    }
+
+    /// `aws_s3_bucket_notification`: notified Lambda functions use the bucket.
+    fn parse_bucket_notification(
+        &self,
+        resource: &TerraformResource,
+        components: &HashMap<String, Component>,
+    ) -> Vec<Discovery> {
+        let Some(bucket) = resource.attribute("bucket").and_then(|expr| {
+            resolve_component(expr, components, Some(ComponentKind::CloudResource))
+        }) else {
+            return Vec::new();
+        };
+
+        resource
+            .block
+            .body()
+            .blocks()
+            .filter(|b| b.identifier() == "lambda_function")
+            .filter_map(|b| attribute_expr(b.body(), "lambda_function_arn"))
+            .filter_map(|expr| resolve_component(expr, components, Some(ComponentKind::Service)))
+            .filter_map(|function| {
+                self.relationship(resource, &function, &bucket, RelationshipType::Uses)
+            })
+            .collect()
+    }
+
+    /// A state machine calls the functions its definition references and
+    /// depends on any other resource it references (SDK integrations).
+    fn parse_state_machine_references(
+        &self,
+        resource: &TerraformResource,
+        components: &HashMap<String, Component>,
+    ) -> Vec<Discovery> {
+        let (Some(machine), Some(definition)) = (
+            components.get(&resource.address()),
+            resource.attribute("definition"),
+        ) else {
+            return Vec::new();
+        };
+
+        referenced_addresses(definition)
+            .iter()
+            .filter_map(|address| components.get(address))
+            .filter(|target| target.name != machine.name)
+            .filter_map(|target| {
+                let relationship = match target.kind {
+                    ComponentKind::Service => RelationshipType::Calls,
+                    _ => RelationshipType::DependsOn,
+                };
+                self.relationship(resource, machine, target, relationship)
+            })
+            .collect()
+    }
+
+    /// `aws_apigatewayv2_route`: an HTTP API route, owned by the Lambda
+    /// function of its integration.
+    fn parse_apigatewayv2_route(
+        &self,
+        resource: &TerraformResource,
+        resources: &[TerraformResource],
+        components: &HashMap<String, Component>,
+    ) -> Option<Discovery> {
+        let route_key = self.get_string_attribute(resource.block.body(), "route_key")?;
+        // "$default" and WebSocket route keys have no method and path
+        let (method, route_path) = route_key.split_once(' ')?;
+
+        let owner = resource
+            .attribute("target")
+            .and_then(|target| referenced_resource(resources, target))
+            .filter(|integration| integration.resource_type == "aws_apigatewayv2_integration")
+            .and_then(|integration| integration.attribute("integration_uri"))
+            .and_then(|uri| resolve_component(uri, components, None))
+            .filter(|component| component.kind == ComponentKind::Service);
+
+        Some(self.api_endpoint(resource, method, route_path, owner))
+    }
+
+    /// `aws_api_gateway_method`: a REST API method, owned by the Lambda
+    /// function of its integration.
+    fn parse_api_gateway_method(
+        &self,
+        resource: &TerraformResource,
+        resources: &[TerraformResource],
+        components: &HashMap<String, Component>,
+    ) -> Option<Discovery> {
+        let http_method = self.get_string_attribute(resource.block.body(), "http_method")?;
+        let resource_id = resource.attribute("resource_id")?;
+        let route_path = self.rest_resource_path(resources, resource_id);
+
+        let address = resource.address();
+        let owner = resources
+            .iter()
+            .filter(|r| r.resource_type == "aws_api_gateway_integration")
+            .find(|integration| {
+                let same_method = match integration.attribute("http_method") {
+                    Some(hcl::Expression::String(m)) => m.eq_ignore_ascii_case(&http_method),
+                    Some(expr) => referenced_addresses(expr).contains(&address),
+                    None => false,
+                };
+                same_method && integration.attribute("resource_id") == Some(resource_id)
+            })
+            .and_then(|integration| integration.attribute("uri"))
+            .and_then(|uri| resolve_component(uri, components, None))
+            .filter(|component| component.kind == ComponentKind::Service);
+
+        Some(self.api_endpoint(resource, &http_method, &route_path, owner))
+    }
+
+    /// The path of an API Gateway REST resource, following `parent_id`
+    /// references up to the API's root resource.
+    fn rest_resource_path(
+        &self,
+        resources: &[TerraformResource],
+        resource_id: &hcl::Expression,
+    ) -> String {
+        let mut segments = Vec::new();
+        let mut current = referenced_resource(resources, resource_id);
+        while let Some(rest_resource) =
+            current.filter(|r| r.resource_type == "aws_api_gateway_resource")
+        {
+            // Guard against reference cycles
+            if segments.len() > 32 {
+                break;
+            }
+            let Some(part) = self.get_string_attribute(rest_resource.block.body(), "path_part")
+            else {
+                break;
+            };
+            segments.push(part);
+            current = rest_resource
+                .attribute("parent_id")
+                .and_then(|parent| referenced_resource(resources, parent));
+        }
+        segments.reverse();
+        format!("/{}", segments.join("/"))
+    }
+
+    fn api_endpoint(
+        &self,
+        resource: &TerraformResource,
+        method: &str,
+        path: &str,
+        owner: Option<Component>,
+    ) -> Discovery {
+        Discovery::ApiEndpoint(ApiEndpointDiscovery {
+            path: path.to_string(),
+            method: method.to_uppercase(),
+            service: owner.map(|component| component.name),
+            detection_method: "terraform".to_string(),
+            source_file: resource.path.to_string_lossy().to_string(),
+            source_line: 1,
+            ..Default::default()
+        })
+    }
+
+    fn get_string_attribute(&self, body: &hcl::Body, key: &str) -> Option<String> {
+        body.attributes()
+            .find(|attr| attr.key() == key)
+            .and_then(|attr| match attr.expr() {
+                hcl::Expression::String(s) => Some(s.to_string()),
+                _ => None,
+            })
+    }
+}
+
+impl Parser for TerraformParser {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "terraform"
+    }
+
+    fn supported_extensions(&self) -> &[&str] {
+        &["tf"]
+    }
+
+    fn parse_file(&self, path: &Path, content: &str) -> Result<Vec<Discovery>, ParserError> {
+        let body = self.parse_hcl(content)?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        // A single file is evaluated on its own, without following modules
+        let (discoveries, _) = self.evaluate_module(
+            dir,
+            &[(path.to_path_buf(), body)],
+            &hcl::Map::new(),
+            None,
+            &BTreeMap::new(),
+            MAX_MODULE_DEPTH,
+        );
+        Ok(discoveries)
+    }
+
+    /// Parse every directory of `.tf` files as one module, so references
+    /// between files resolve.
+    ///
+    /// Directories called as local modules are evaluated through their
+    /// callers; every other module is evaluated once per workspace. Files
+    /// are therefore not cached individually.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        _cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
+        let files = repo_files(repo_path, is_ignored_terraform_dir, |path| {
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("tf" | "tfvars")
+            )
+        });
+        let (bodies, failures) = parse_in_parallel(&files, |_, content| self.parse_hcl(content));
+
+        let mut modules: BTreeMap<PathBuf, ModuleFiles> = BTreeMap::new();
+        for (path, body) in bodies {
+            let is_tfvars = path.extension().and_then(|e| e.to_str()) == Some("tfvars");
+            let mut dir = path.parent().unwrap_or(repo_path);
+            if is_tfvars {
+                // env/prod.tfvars belongs to the module in the parent directory
+                let dir_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                if VAR_FILE_DIRS.contains(&dir_name) {
+                    dir = dir.parent().unwrap_or(repo_path);
+                }
+            }
+            let dir = dir.to_path_buf();
+            let module = modules.entry(dir).or_default();
+            if is_tfvars {
+                module.tfvars.push((path, body));
+            } else {
+                module.tf.push((path, body));
+            }
+        }
+
+        for module in modules.values_mut() {
+            module.tf.sort_by(|a, b| a.0.cmp(&b.0));
+            module.tfvars.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        let called: HashSet<PathBuf> = modules
+            .iter()
+            .flat_map(|(dir, module)| {
+                module
+                    .tf
+                    .iter()
+                    .flat_map(|(_, body)| body.blocks())
+                    .filter(|block| block.identifier() == "module")
+                    .filter_map(|block| self.get_string_attribute(block.body(), "source"))
+                    .filter_map(move |source| local_module_dir(dir, &source))
+            })
+            .collect();
+
+        let mut all_discoveries = Vec::new();
+        for (dir, module) in &modules {
+            if module.tf.is_empty() || called.contains(dir) {
+                continue;
+            }
+            for (workspace, inputs) in self.workspace_inputs(&module.tfvars) {
+                let (discoveries, _) = self.evaluate_module(
+                    dir,
+                    &module.tf,
+                    &inputs,
+                    workspace.as_deref(),
+                    &modules,
+                    0,
+                );
+                all_discoveries.extend(discoveries);
+            }
+        }
+        Ok(RepoParse {
+            discoveries: all_discoveries,
+            failures,
+        })
+    }
+}
+
+/// Directories to skip during Terraform scanning.
+fn is_ignored_terraform_dir(name: &str) -> bool {
+    matches!(
+        name,
+        ".terraform"
+            | ".git"
+            | "node_modules"
+            | "target"
+            | ".terragrunt-cache"
+            | ".idea"
+            | ".vscode"
+    )
+}
+
+/// The directory of a local module source (`./modules/orders`), relative to
+/// the calling module's directory. `None` for registry and remote sources.
+fn local_module_dir(dir: &Path, source: &str) -> Option<PathBuf> {
+    if !source.starts_with("./") && !source.starts_with("../") {
+        return None;
+    }
+    let mut resolved = PathBuf::new();
+    for component in dir.join(source).components() {
+        match component {
+            PathComponent::CurDir => {}
+            PathComponent::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    Some(resolved)
+}
+
+fn attribute_expr<'a>(body: &'a hcl::Body, key: &str) -> Option<&'a hcl::Expression> {
+    body.attributes()
+        .find(|attr| attr.key() == key)
+        .map(|attr| attr.expr())
+}
+
+/// A literal HCL value as a typed attribute; `None` for computed values.
+fn literal_value(expr: &hcl::Expression) -> Option<AttributeValue> {
+    match expr {
+        hcl::Expression::String(s) => Some(AttributeValue::String(s.clone())),
+        hcl::Expression::Bool(b) => Some(AttributeValue::Boolean(*b)),
+        hcl::Expression::Number(n) => n
+            .as_i64()
+            .map(AttributeValue::Integer)
+            .or_else(|| n.as_f64().map(AttributeValue::Float)),
+        hcl::Expression::Array(items) => items
+            .iter()
+            .map(literal_value)
+            .collect::<Option<Vec<_>>>()
+            .map(AttributeValue::List),
+        _ => None,
+    }
+}
+
+/// Resource addresses (`aws_sqs_queue.orders`) referenced anywhere in an
+/// expression, including inside `"${...}"` interpolations and function calls.
+fn referenced_addresses(expr: &hcl::Expression) -> Vec<String> {
+    let text = expr.to_string();
+    let mut addresses = Vec::new();
+    for token in text.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        let mut parts = token.split('.');
+        if let (Some(resource_type), Some(name)) = (parts.next(), parts.next()) {
+            if resource_type.starts_with("aws_") && !name.is_empty() {
+                let address = format!("{}.{}", resource_type, name);
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        }
+    }
+    addresses
+}
+
+/// The module resource an expression references, if any.
+fn referenced_resource<'r, 'a>(
+    resources: &'r [TerraformResource<'a>],
+    expr: &hcl::Expression,
+) -> Option<&'r TerraformResource<'a>> {
+    let addresses = referenced_addresses(expr);
+    resources.iter().find(|r| addresses.contains(&r.address()))
+}
+
+/// Whether an expression refers to the resource at `address`, by reference
+/// or by its literal `name`.
+fn refers_to(expr: &hcl::Expression, address: &str, name: &str) -> bool {
+    match expr {
+        hcl::Expression::String(value) => value == name,
+        _ => referenced_addresses(expr).iter().any(|a| a == address),
+    }
+}
+
+/// Resolve an expression to the component it refers to.
+///
+/// References resolve through the module's `components`. A literal ARN or
+/// name resolves by name, taking its kind from the ARN's service or, failing
+/// that, from `literal_kind`.
+fn resolve_component(
+    expr: &hcl::Expression,
+    components: &HashMap<String, Component>,
+    literal_kind: Option<ComponentKind>,
+) -> Option<Component> {
+    if let Some(component) = referenced_addresses(expr)
+        .iter()
+        .find_map(|address| components.get(address))
+    {
+        return Some(component.clone());
+    }
+    let hcl::Expression::String(value) = expr else {
+        return None;
+    };
+    let kind = kind_from_arn(value).or(literal_kind)?;
+    Some(Component {
+        name: name_from_arn(value),
+        kind,
+    })
+}
+
+/// The kind of component an ARN names, from its service.
+///
+/// `arn:aws:sqs:us-east-1:123:orders` → `Queue`.
+fn kind_from_arn(value: &str) -> Option<ComponentKind> {
+    let mut parts = value.strip_prefix("arn:")?.split(':');
+    let service = parts.nth(1)?;
+    match service {
+        "lambda" | "states" => Some(ComponentKind::Service),
+        "dynamodb" => Some(ComponentKind::Database),
+        "events" if value.contains(":rule/") => Some(ComponentKind::CloudResource),
+        "sqs" | "sns" | "kinesis" | "kafka" | "events" => Some(ComponentKind::Queue),
+        "s3" => Some(ComponentKind::CloudResource),
+        _ => None,
+    }
+}
+
+/// The graph component a resource discovery describes.
+fn component_of(discovery: &Discovery) -> Option<Component> {
+    let (name, kind) = match discovery {
+        Discovery::Service(s) => (Some(&s.name), ComponentKind::Service),
+        Discovery::DatabaseAccess(db) => (db.table_name.as_ref(), ComponentKind::Database),
+        Discovery::QueueOperation(q) => (q.queue_name.as_ref(), ComponentKind::Queue),
+        Discovery::CloudResourceUsage(r) => {
+            (r.resource_name.as_ref(), ComponentKind::CloudResource)
+        }
+        _ => return None,
+    };
+    Some(Component {
+        name: name?.clone(),
+        kind,
+    })
+}
+
+/// Database type for an RDS engine.
+///
+/// `aurora-postgresql` → `postgresql`, `aurora`/`aurora-mysql` → `mysql`,
+/// `sqlserver-ex` → `sqlserver`.
+fn db_type_from_engine(engine: &str) -> String {
+    let engine = engine.to_lowercase();
+    let engine = engine.strip_prefix("aurora-").unwrap_or(&engine);
+    match engine {
+        "aurora" | "mysql" => "mysql",
+        "postgres" | "postgresql" => "postgresql",
+        e if e.starts_with("oracle") => "oracle",
+        e if e.starts_with("sqlserver") => "sqlserver",
+        e => e,
+    }
+    .to_string()
+}
+
+/// The node type a resource type becomes, and the attributes holding its
+/// physical name in order of preference. `None` for resource types that do
+/// not become nodes. Terraform state import matches resources through the
+/// same table, so both agree on which node a resource is.
+pub(crate) fn resource_node(resource_type: &str) -> Option<(NodeType, &'static [&'static str])> {
+    let node: (NodeType, &[&str]) = match resource_type {
+        "aws_dynamodb_table" | "aws_elasticache_serverless_cache" => {
+            (NodeType::Database, &["name"])
+        }
+        "aws_db_instance" => (NodeType::Database, &["identifier", "db_name"]),
+        "aws_rds_cluster" => (NodeType::Database, &["cluster_identifier", "database_name"]),
+        "aws_elasticache_cluster" => (NodeType::Database, &["cluster_id"]),
+        "aws_elasticache_replication_group" => (NodeType::Database, &["replication_group_id"]),
+        "aws_sqs_queue" | "aws_sns_topic" | "aws_kinesis_stream" | "aws_cloudwatch_event_bus" => {
+            (NodeType::Queue, &["name"])
+        }
+        "aws_msk_cluster" | "aws_msk_serverless_cluster" => (NodeType::Queue, &["cluster_name"]),
+        "aws_s3_bucket" => (NodeType::CloudResource, &["bucket"]),
+        "aws_cloudwatch_event_rule"
+        | "aws_api_gateway_rest_api"
+        | "aws_apigatewayv2_api"
+        | "aws_secretsmanager_secret"
+        | "aws_ssm_parameter" => (NodeType::CloudResource, &["name"]),
+        "aws_lambda_function" => (NodeType::Service, &["function_name"]),
+        "aws_sfn_state_machine" | "aws_ecs_service" => (NodeType::Service, &["name"]),
+        _ => return None,
+    };
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dynamodb_table() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_dynamodb_table" "users" {
+  name           = "users-table"
+  billing_mode   = "PAY_PER_REQUEST"
+  hash_key       = "id"
+
+  attribute {
+    name = "id"
+    type = "S"
+  }
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let db_accesses: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(db_accesses.len(), 1);
+        assert_eq!(db_accesses[0].table_name, Some("users-table".to_string()));
+        assert_eq!(db_accesses[0].db_type, "dynamodb");
+
+        // Verify deployment metadata
+        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "terraform");
+    }
+
+    #[test]
+    fn test_parse_sqs_queue() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_sqs_queue" "orders" {
+  name = "orders-queue"
+  fifo_queue = false
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let queues: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(queues.len(), 1);
+        assert_eq!(queues[0].queue_name, Some("orders-queue".to_string()));
+        assert_eq!(queues[0].queue_type, "sqs");
+
+        // Verify deployment metadata
+        let metadata = queues[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "terraform");
+    }
+
+    #[test]
+    fn test_parse_lambda_function() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_lambda_function" "processor" {
+  function_name = "order-processor"
+  runtime       = "python3.9"
+  handler       = "main.handler"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let services: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].name, "order-processor");
+        assert_eq!(services[0].language, "python");
+        assert_eq!(services[0].framework, Some("aws-lambda".to_string()));
+
+        // Verify deployment metadata
+        let metadata = services[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "terraform");
+    }
+
+    #[test]
+    fn test_parse_s3_bucket() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_s3_bucket" "data" {
+  bucket = "my-data-bucket"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let resources: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::CloudResourceUsage(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(resources.len(), 1);
+        assert_eq!(
+            resources[0].resource_name,
+            Some("my-data-bucket".to_string())
+        );
+        assert_eq!(resources[0].resource_type, "s3");
 
         // Verify deployment metadata
+        let metadata = resources[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "terraform");
+    }
+
+    #[test]
+    fn test_parse_sns_topic() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_sns_topic" "notifications" {
+  name = "user-notifications"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let queues: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(queues.len(), 1);
+        assert_eq!(queues[0].queue_name, Some("user-notifications".to_string()));
+        assert_eq!(queues[0].queue_type, "sns");
+    }
+
+    #[test]
+    fn test_parse_resource_without_name() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_dynamodb_table" "default" {
+  billing_mode = "PAY_PER_REQUEST"
+  hash_key     = "id"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let db_accesses: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        // Should use the terraform resource name as fallback
+        assert_eq!(db_accesses.len(), 1);
+        assert_eq!(db_accesses[0].table_name, Some("default".to_string()));
+    }
+
+    // ==================== M8-T1 New Tests: Tag Extraction ====================
+
+    #[test]
+    fn test_parse_terraform_tags_managed_by() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_lambda_function" "api" {
+  function_name = "my-api"
+  runtime       = "nodejs18.x"
+  handler       = "index.handler"
+
+  tags = {
+    ManagedBy   = "Terraform"
+    Environment = "production"
+  }
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let services: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(services.len(), 1);
         let metadata = services[0].deployment_metadata.as_ref().unwrap();
         assert_eq!(metadata.deployment_method, "terraform");
+        assert_eq!(metadata.environment, Some("production".to_string()));
+        assert!(metadata.tags.contains_key("ManagedBy"));
+        assert!(metadata.tags.contains_key("Environment"));
+    }
+
+    #[test]
+    fn test_parse_terraform_backend_workspace() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+terraform {
+  backend "s3" {
+    bucket = "my-terraform-state"
+    key    = "production/terraform.tfstate"
+    region = "us-east-1"
+  }
+}
+
+resource "aws_dynamodb_table" "users" {
+  name = "users-table"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let db_accesses: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(db_accesses.len(), 1);
+        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.terraform_workspace, Some("production".to_string()));
+        assert_eq!(metadata.environment, Some("production".to_string()));
+    }
+
+    #[test]
+    fn test_parse_terraform_tags_variations() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_sqs_queue" "orders" {
+  name = "orders-queue"
+
+  tags = {
+    managed_by = "terraform"
+    env        = "staging"
+  }
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let queues: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(queues.len(), 1);
+        let metadata = queues[0].deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.deployment_method, "terraform");
+        assert_eq!(metadata.environment, Some("staging".to_string()));
+    }
+
+    #[test]
+    fn test_parse_terraform_resource_without_tags() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_dynamodb_table" "users" {
+  name = "users-table"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let db_accesses: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(db_accesses.len(), 1);
+        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
+        // Default to terraform since we're parsing Terraform files
+        assert_eq!(metadata.deployment_method, "terraform");
+        // No environment without tags
+        assert!(metadata.environment.is_none());
+    }
+
+    #[test]
+    fn test_parse_terraform_nested_key_path() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+terraform {
+  backend "s3" {
+    bucket = "my-state"
+    key    = "env/prod/myservice/terraform.tfstate"
+    region = "us-east-1"
+  }
+}
+
+resource "aws_s3_bucket" "data" {
+  bucket = "my-bucket"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let resources: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::CloudResourceUsage(r) => Some(r),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(resources.len(), 1);
+        let metadata = resources[0].deployment_metadata.as_ref().unwrap();
+        // Should extract "myservice" from the path (second-to-last segment)
+        assert_eq!(metadata.terraform_workspace, Some("myservice".to_string()));
     }
 
     #[test]
-    fn test_parse_s3_bucket() {
+    fn test_workspace_from_key_path_variations() {
+        let parser = TerraformParser::new().unwrap();
+
+        // Simple environment path
+        assert_eq!(
+            parser.workspace_from_key_path("production/terraform.tfstate"),
+            Some("production".to_string())
+        );
+
+        // Nested path
+        assert_eq!(
+            parser.workspace_from_key_path("env/staging/terraform.tfstate"),
+            Some("staging".to_string())
+        );
+
+        // Deep nested path - takes parent of tfstate
+        assert_eq!(
+            parser.workspace_from_key_path("org/team/dev/state.tfstate"),
+            Some("dev".to_string())
+        );
+
+        // No path - default workspace
+        assert_eq!(parser.workspace_from_key_path("terraform.tfstate"), None);
+    }
+
+    #[test]
+    fn test_extract_environment_from_tags_case_insensitive() {
+        let parser = TerraformParser::new().unwrap();
+
+        let mut tags1 = HashMap::new();
+        tags1.insert("Environment".to_string(), "prod".to_string());
+        assert_eq!(
+            parser.extract_environment_from_tags(&tags1),
+            Some("prod".to_string())
+        );
+
+        let mut tags2 = HashMap::new();
+        tags2.insert("environment".to_string(), "staging".to_string());
+        assert_eq!(
+            parser.extract_environment_from_tags(&tags2),
+            Some("staging".to_string())
+        );
+
+        let mut tags3 = HashMap::new();
+        tags3.insert("Env".to_string(), "dev".to_string());
+        assert_eq!(
+            parser.extract_environment_from_tags(&tags3),
+            Some("dev".to_string())
+        );
+
+        let mut tags4 = HashMap::new();
+        tags4.insert("env".to_string(), "test".to_string());
+        assert_eq!(
+            parser.extract_environment_from_tags(&tags4),
+            Some("test".to_string())
+        );
+
+        let tags5 = HashMap::new();
+        assert_eq!(parser.extract_environment_from_tags(&tags5), None);
+    }
+
+    // ==================== Broader Resource Coverage ====================
+
+    fn databases(discoveries: &[Discovery]) -> Vec<&DatabaseAccessDiscovery> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::DatabaseAccess(db) => Some(db),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn relationships(discoveries: &[Discovery]) -> Vec<&RelationshipDiscovery> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Relationship(r) => Some(r),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn resource_attribute<'a>(
+        metadata: &'a Option<DeploymentMetadata>,
+        key: &str,
+    ) -> Option<&'a AttributeValue> {
+        metadata.as_ref().unwrap().resource_attributes.get(key)
+    }
+
+    #[test]
+    fn test_parse_rds_instance_and_aurora_cluster() {
         let parser = TerraformParser::new().unwrap();
         let content = r#"
-resource "aws_s3_bucket" "data" {
-  bucket = "my-data-bucket"
+resource "aws_db_instance" "legacy" {
+  identifier        = "legacy-db"
+  engine            = "postgres"
+  engine_version    = "15.4"
+  instance_class    = "db.t3.medium"
+  allocated_storage = 100
+  multi_az          = true
+  password          = var.db_password
+}
+
+resource "aws_rds_cluster" "orders" {
+  cluster_identifier = "orders-cluster"
+  engine             = "aurora-postgresql"
+  database_name      = "orders"
+}
+
+resource "aws_rds_cluster_instance" "writer" {
+  cluster_identifier = aws_rds_cluster.orders.id
+  instance_class     = "db.r6g.large"
+}
+
+resource "aws_rds_cluster_instance" "readers" {
+  count              = 2
+  cluster_identifier = aws_rds_cluster.orders.id
+  instance_class     = "db.r6g.large"
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let dbs = databases(&discoveries);
+
+        // Cluster instances are folded into their cluster
+        assert_eq!(dbs.len(), 2);
+
+        let legacy = dbs
+            .iter()
+            .find(|d| d.table_name.as_deref() == Some("legacy-db"))
+            .unwrap();
+        assert_eq!(legacy.db_type, "postgresql");
+        assert_eq!(
+            resource_attribute(&legacy.deployment_metadata, "allocated_storage"),
+            Some(&AttributeValue::Integer(100))
+        );
+        assert_eq!(
+            resource_attribute(&legacy.deployment_metadata, "multi_az"),
+            Some(&AttributeValue::Boolean(true))
+        );
+        assert_eq!(
+            resource_attribute(&legacy.deployment_metadata, "instance_class"),
+            Some(&AttributeValue::from("db.t3.medium"))
+        );
+        // Computed values are not recorded
+        assert!(resource_attribute(&legacy.deployment_metadata, "password").is_none());
+
+        let cluster = dbs
+            .iter()
+            .find(|d| d.table_name.as_deref() == Some("orders-cluster"))
+            .unwrap();
+        assert_eq!(cluster.db_type, "postgresql");
+        assert_eq!(
+            resource_attribute(&cluster.deployment_metadata, "engine"),
+            Some(&AttributeValue::from("aurora-postgresql"))
+        );
+        assert_eq!(
+            resource_attribute(&cluster.deployment_metadata, "instance_count"),
+            Some(&AttributeValue::Integer(3))
+        );
+        assert_eq!(
+            resource_attribute(&cluster.deployment_metadata, "instance_class"),
+            Some(&AttributeValue::from("db.r6g.large"))
+        );
+    }
+
+    #[test]
+    fn test_parse_elasticache_kinesis_eventbridge_and_msk() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_elasticache_replication_group" "sessions" {
+  replication_group_id = "sessions"
+  engine               = "valkey"
+  node_type            = "cache.t4g.small"
+  num_cache_clusters   = 2
+}
+
+resource "aws_elasticache_cluster" "memcached" {
+  cluster_id      = "page-cache"
+  engine          = "memcached"
+  num_cache_nodes = 3
+}
+
+resource "aws_kinesis_stream" "clicks" {
+  name             = "clicks"
+  shard_count      = 4
+  retention_period = 48
+
+  stream_mode_details {
+    stream_mode = "PROVISIONED"
+  }
+}
+
+resource "aws_cloudwatch_event_bus" "orders" {
+  name = "orders-bus"
+}
+
+resource "aws_msk_cluster" "events" {
+  cluster_name           = "events-kafka"
+  kafka_version          = "3.5.1"
+  number_of_broker_nodes = 3
+
+  broker_node_group_info {
+    instance_type   = "kafka.m5.large"
+    client_subnets  = var.subnets
+    security_groups = [aws_security_group.kafka.id]
+  }
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+
+        let dbs = databases(&discoveries);
+        assert_eq!(dbs.len(), 2);
+        let sessions = dbs
+            .iter()
+            .find(|d| d.table_name.as_deref() == Some("sessions"))
+            .unwrap();
+        assert_eq!(sessions.db_type, "redis");
+        assert_eq!(
+            resource_attribute(&sessions.deployment_metadata, "engine"),
+            Some(&AttributeValue::from("valkey"))
+        );
+        assert_eq!(
+            resource_attribute(&sessions.deployment_metadata, "num_cache_clusters"),
+            Some(&AttributeValue::Integer(2))
+        );
+        assert!(
+            dbs.iter()
+                .any(|d| d.table_name.as_deref() == Some("page-cache") && d.db_type == "memcached")
+        );
+
+        let queues: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(queues.len(), 3);
+
+        let stream = queues.iter().find(|q| q.queue_type == "kinesis").unwrap();
+        assert_eq!(stream.queue_name, Some("clicks".to_string()));
+        assert_eq!(
+            resource_attribute(&stream.deployment_metadata, "shard_count"),
+            Some(&AttributeValue::Integer(4))
+        );
+        assert_eq!(
+            resource_attribute(&stream.deployment_metadata, "stream_mode"),
+            Some(&AttributeValue::from("PROVISIONED"))
+        );
+
+        let bus = queues
+            .iter()
+            .find(|q| q.queue_type == "eventbridge")
+            .unwrap();
+        assert_eq!(bus.queue_name, Some("orders-bus".to_string()));
+
+        let kafka = queues.iter().find(|q| q.queue_type == "msk").unwrap();
+        assert_eq!(kafka.queue_name, Some("events-kafka".to_string()));
+        assert_eq!(
+            resource_attribute(&kafka.deployment_metadata, "instance_type"),
+            Some(&AttributeValue::from("kafka.m5.large"))
+        );
+        assert_eq!(
+            resource_attribute(&kafka.deployment_metadata, "broker_count"),
+            Some(&AttributeValue::Integer(3))
+        );
+    }
+
+    #[test]
+    fn test_parse_cloud_resources_and_services() {
+        let parser = TerraformParser::new().unwrap();
+        let content = r#"
+resource "aws_cloudwatch_event_rule" "nightly" {
+  name                = "nightly-report"
+  schedule_expression = "cron(0 2 * * ? *)"
+}
+
+resource "aws_apigatewayv2_api" "http" {
+  name          = "public-api"
+  protocol_type = "HTTP"
+}
+
+resource "aws_api_gateway_rest_api" "legacy" {
+  name = "legacy-api"
+
+  endpoint_configuration {
+    types = ["REGIONAL"]
+  }
+}
+
+resource "aws_secretsmanager_secret" "db" {
+  name = "prod/db-credentials"
+}
+
+resource "aws_ssm_parameter" "flag" {
+  name  = "/app/feature-flag"
+  type  = "SecureString"
+  value = "super-secret"
+}
+
+resource "aws_sfn_state_machine" "checkout" {
+  name       = "checkout-flow"
+  type       = "EXPRESS"
+  role_arn   = aws_iam_role.sfn.arn
+  definition = "{}"
+}
+
+resource "aws_ecs_cluster" "main" {
+  name = "main-cluster"
+}
+
+resource "aws_ecs_task_definition" "api" {
+  family = "orders-api"
+}
+
+resource "aws_ecs_service" "api" {
+  name            = "orders-api"
+  cluster         = aws_ecs_cluster.main.id
+  task_definition = aws_ecs_task_definition.api.arn
+  launch_type     = "FARGATE"
+  desired_count   = 2
 }
 "#;
 
@@ -508,281 +2467,669 @@ resource "aws_s3_bucket" "data" {
                 _ => None,
             })
             .collect();
+        let find = |name: &str| {
+            resources
+                .iter()
+                .find(|r| r.resource_name.as_deref() == Some(name))
+                .unwrap()
+        };
+
+        let rule = find("nightly-report");
+        assert_eq!(rule.resource_type, "eventbridge-rule");
+        assert_eq!(
+            resource_attribute(&rule.deployment_metadata, "schedule_expression"),
+            Some(&AttributeValue::from("cron(0 2 * * ? *)"))
+        );
 
-        assert_eq!(resources.len(), 1);
+        let http_api = find("public-api");
+        assert_eq!(http_api.resource_type, "apigateway");
         assert_eq!(
-            resources[0].resource_name,
-            Some("my-data-bucket".to_string())
+            resource_attribute(&http_api.deployment_metadata, "protocol_type"),
+            Some(&AttributeValue::from("HTTP"))
+        );
+        let rest_api = find("legacy-api");
+        assert_eq!(
+            resource_attribute(&rest_api.deployment_metadata, "protocol_type"),
+            Some(&AttributeValue::from("REST"))
+        );
+        assert_eq!(
+            resource_attribute(&rest_api.deployment_metadata, "endpoint_types"),
+            Some(&AttributeValue::List(vec![AttributeValue::from(
+                "REGIONAL"
+            )]))
         );
-        assert_eq!(resources[0].resource_type, "s3");
 
-        // Verify deployment metadata
-        let metadata = resources[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.deployment_method, "terraform");
+        assert_eq!(find("prod/db-credentials").resource_type, "secretsmanager");
+        let parameter = find("/app/feature-flag");
+        assert_eq!(parameter.resource_type, "ssm");
+        assert_eq!(
+            resource_attribute(&parameter.deployment_metadata, "parameter_type"),
+            Some(&AttributeValue::from("SecureString"))
+        );
+        // Parameter values may be secrets and are never recorded
+        let metadata = parameter.deployment_metadata.as_ref().unwrap();
+        assert!(
+            !metadata
+                .resource_attributes
+                .values()
+                .any(|v| *v == AttributeValue::from("super-secret"))
+        );
+
+        let services: Vec<_> = discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::Service(s) => Some(s),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(services.len(), 2);
+
+        let machine = services.iter().find(|s| s.name == "checkout-flow").unwrap();
+        assert_eq!(machine.framework, Some("step-functions".to_string()));
+        assert_eq!(
+            resource_attribute(&machine.deployment_metadata, "state_machine_type"),
+            Some(&AttributeValue::from("EXPRESS"))
+        );
+
+        let ecs = services.iter().find(|s| s.name == "orders-api").unwrap();
+        assert_eq!(ecs.framework, Some("ecs".to_string()));
+        assert_eq!(ecs.entry_point, "orders-api");
+        assert_eq!(
+            resource_attribute(&ecs.deployment_metadata, "ecs_cluster"),
+            Some(&AttributeValue::from("main-cluster"))
+        );
+        assert_eq!(
+            resource_attribute(&ecs.deployment_metadata, "desired_count"),
+            Some(&AttributeValue::Integer(2))
+        );
     }
 
+    // ==================== Relationship Resources ====================
+
     #[test]
-    fn test_parse_sns_topic() {
+    fn test_subscription_and_event_source_mapping_relationships() {
         let parser = TerraformParser::new().unwrap();
         let content = r#"
-resource "aws_sns_topic" "notifications" {
-  name = "user-notifications"
+resource "aws_sns_topic" "orders" {
+  name = "order-events"
+}
+
+resource "aws_sqs_queue" "billing" {
+  name = "billing-queue"
+}
+
+resource "aws_dynamodb_table" "orders" {
+  name             = "orders"
+  stream_enabled   = true
+  stream_view_type = "NEW_IMAGE"
+}
+
+resource "aws_lambda_function" "notifier" {
+  function_name = "notifier"
+  runtime       = "nodejs20.x"
+}
+
+resource "aws_lambda_function" "billing" {
+  function_name = "billing-worker"
+  runtime       = "python3.12"
+}
+
+resource "aws_sns_topic_subscription" "notifier" {
+  topic_arn = aws_sns_topic.orders.arn
+  protocol  = "lambda"
+  endpoint  = aws_lambda_function.notifier.arn
+}
+
+resource "aws_sns_topic_subscription" "billing_queue" {
+  topic_arn = aws_sns_topic.orders.arn
+  protocol  = "sqs"
+  endpoint  = aws_sqs_queue.billing.arn
+}
+
+resource "aws_lambda_event_source_mapping" "billing" {
+  event_source_arn = aws_sqs_queue.billing.arn
+  function_name    = aws_lambda_function.billing.function_name
+}
+
+resource "aws_lambda_event_source_mapping" "audit" {
+  event_source_arn = aws_dynamodb_table.orders.stream_arn
+  function_name    = aws_lambda_function.notifier.arn
+}
+
+resource "aws_lambda_event_source_mapping" "clicks" {
+  event_source_arn = "arn:aws:kinesis:us-east-1:123456789012:stream/clicks"
+  function_name    = "notifier"
 }
 "#;
 
         let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-
-        let queues: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::QueueOperation(q) => Some(q),
-                _ => None,
+        let rels = relationships(&discoveries);
+
+        // The queue-to-topic subscription has no service end
+        assert_eq!(rels.len(), 4);
+        let has = |source: &str, target: &str, kind: ComponentKind, rel: RelationshipType| {
+            rels.iter().any(|r| {
+                r.source_service == source
+                    && r.target == target
+                    && r.target_kind == kind
+                    && r.relationship == rel
             })
-            .collect();
-
-        assert_eq!(queues.len(), 1);
-        assert_eq!(queues[0].queue_name, Some("user-notifications".to_string()));
-        assert_eq!(queues[0].queue_type, "sns");
+        };
+        assert!(has(
+            "notifier",
+            "order-events",
+            ComponentKind::Queue,
+            RelationshipType::Subscribes
+        ));
+        assert!(has(
+            "billing-worker",
+            "billing-queue",
+            ComponentKind::Queue,
+            RelationshipType::Subscribes
+        ));
+        assert!(has(
+            "notifier",
+            "orders",
+            ComponentKind::Database,
+            RelationshipType::Reads
+        ));
+        assert!(has(
+            "notifier",
+            "clicks",
+            ComponentKind::Queue,
+            RelationshipType::Subscribes
+        ));
+        assert!(rels.iter().all(|r| r.detection_method == "terraform"));
     }
 
     #[test]
-    fn test_parse_resource_without_name() {
+    fn test_event_target_bucket_notification_and_state_machine_relationships() {
         let parser = TerraformParser::new().unwrap();
         let content = r#"
-resource "aws_dynamodb_table" "default" {
-  billing_mode = "PAY_PER_REQUEST"
-  hash_key     = "id"
+resource "aws_cloudwatch_event_bus" "orders" {
+  name = "orders-bus"
 }
-"#;
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+resource "aws_cloudwatch_event_rule" "placed" {
+  name           = "order-placed"
+  event_bus_name = aws_cloudwatch_event_bus.orders.name
+}
 
-        let db_accesses: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
-                _ => None,
-            })
-            .collect();
+resource "aws_s3_bucket" "uploads" {
+  bucket = "user-uploads"
+}
 
-        // Should use the terraform resource name as fallback
-        assert_eq!(db_accesses.len(), 1);
-        assert_eq!(db_accesses[0].table_name, Some("default".to_string()));
+resource "aws_lambda_function" "fulfil" {
+  function_name = "fulfilment"
+  runtime       = "nodejs20.x"
+}
+
+resource "aws_lambda_function" "thumbnails" {
+  function_name = "thumbnailer"
+  runtime       = "python3.12"
+}
+
+resource "aws_sfn_state_machine" "checkout" {
+  name = "checkout-flow"
+  definition = jsonencode({
+    StartAt = "Fulfil"
+    States = {
+      Fulfil = {
+        Type     = "Task"
+        Resource = aws_lambda_function.fulfil.arn
+        End      = true
+      }
     }
+  })
+}
 
-    // ==================== M8-T1 New Tests: Tag Extraction ====================
+resource "aws_cloudwatch_event_target" "fulfil" {
+  rule           = aws_cloudwatch_event_rule.placed.name
+  event_bus_name = aws_cloudwatch_event_bus.orders.name
+  arn            = aws_lambda_function.fulfil.arn
+}
+
+resource "aws_cloudwatch_event_target" "checkout" {
+  rule = aws_cloudwatch_event_rule.placed.name
+  arn  = aws_sfn_state_machine.checkout.arn
+}
+
+resource "aws_s3_bucket_notification" "uploads" {
+  bucket = aws_s3_bucket.uploads.id
+
+  lambda_function {
+    lambda_function_arn = aws_lambda_function.thumbnails.arn
+    events              = ["s3:ObjectCreated:*"]
+  }
+}
+"#;
+
+        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
+        let rels = relationships(&discoveries);
+        let has = |source: &str, target: &str, rel: RelationshipType| {
+            rels.iter()
+                .any(|r| r.source_service == source && r.target == target && r.relationship == rel)
+        };
+
+        assert!(has("fulfilment", "order-placed", RelationshipType::Uses));
+        assert!(has(
+            "fulfilment",
+            "orders-bus",
+            RelationshipType::Subscribes
+        ));
+        // The bus is taken from the rule when the target does not name it
+        assert!(has("checkout-flow", "order-placed", RelationshipType::Uses));
+        assert!(has(
+            "checkout-flow",
+            "orders-bus",
+            RelationshipType::Subscribes
+        ));
+        assert!(has("thumbnailer", "user-uploads", RelationshipType::Uses));
+        assert!(has("checkout-flow", "fulfilment", RelationshipType::Calls));
+        assert_eq!(rels.len(), 6);
+    }
 
     #[test]
-    fn test_parse_terraform_tags_managed_by() {
+    fn test_api_gateway_routes_owned_by_lambda_integrations() {
         let parser = TerraformParser::new().unwrap();
         let content = r#"
-resource "aws_lambda_function" "api" {
-  function_name = "my-api"
-  runtime       = "nodejs18.x"
-  handler       = "index.handler"
+resource "aws_lambda_function" "users" {
+  function_name = "users-api"
+  runtime       = "nodejs20.x"
+}
 
-  tags = {
-    ManagedBy   = "Terraform"
-    Environment = "production"
-  }
+resource "aws_apigatewayv2_api" "http" {
+  name          = "public-api"
+  protocol_type = "HTTP"
+}
+
+resource "aws_apigatewayv2_integration" "users" {
+  api_id           = aws_apigatewayv2_api.http.id
+  integration_type = "AWS_PROXY"
+  integration_uri  = aws_lambda_function.users.invoke_arn
+}
+
+resource "aws_apigatewayv2_route" "get_user" {
+  api_id    = aws_apigatewayv2_api.http.id
+  route_key = "GET /users/{id}"
+  target    = "integrations/${aws_apigatewayv2_integration.users.id}"
+}
+
+resource "aws_apigatewayv2_route" "default" {
+  api_id    = aws_apigatewayv2_api.http.id
+  route_key = "$default"
+}
+
+resource "aws_api_gateway_rest_api" "legacy" {
+  name = "legacy-api"
+}
+
+resource "aws_api_gateway_resource" "orders" {
+  rest_api_id = aws_api_gateway_rest_api.legacy.id
+  parent_id   = aws_api_gateway_rest_api.legacy.root_resource_id
+  path_part   = "orders"
+}
+
+resource "aws_api_gateway_resource" "order" {
+  rest_api_id = aws_api_gateway_rest_api.legacy.id
+  parent_id   = aws_api_gateway_resource.orders.id
+  path_part   = "{orderId}"
+}
+
+resource "aws_api_gateway_method" "get_order" {
+  rest_api_id   = aws_api_gateway_rest_api.legacy.id
+  resource_id   = aws_api_gateway_resource.order.id
+  http_method   = "GET"
+  authorization = "NONE"
+}
+
+resource "aws_api_gateway_integration" "get_order" {
+  rest_api_id = aws_api_gateway_rest_api.legacy.id
+  resource_id = aws_api_gateway_resource.order.id
+  http_method = aws_api_gateway_method.get_order.http_method
+  type        = "AWS_PROXY"
+  uri         = aws_lambda_function.users.invoke_arn
 }
 "#;
 
         let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-        let services: Vec<_> = discoveries
+        let endpoints: Vec<_> = discoveries
             .iter()
             .filter_map(|d| match d {
-                Discovery::Service(s) => Some(s),
+                Discovery::ApiEndpoint(e) => Some(e),
                 _ => None,
             })
             .collect();
 
-        assert_eq!(services.len(), 1);
-        let metadata = services[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.deployment_method, "terraform");
-        assert_eq!(metadata.environment, Some("production".to_string()));
-        assert!(metadata.tags.contains_key("ManagedBy"));
-        assert!(metadata.tags.contains_key("Environment"));
+        assert_eq!(endpoints.len(), 2);
+        let route = endpoints.iter().find(|e| e.path == "/users/{id}").unwrap();
+        assert_eq!(route.method, "GET");
+        assert_eq!(route.service, Some("users-api".to_string()));
+        assert_eq!(route.detection_method, "terraform");
+
+        let method = endpoints
+            .iter()
+            .find(|e| e.path == "/orders/{orderId}")
+            .unwrap();
+        assert_eq!(method.method, "GET");
+        assert_eq!(method.service, Some("users-api".to_string()));
     }
 
     #[test]
-    fn test_parse_terraform_backend_workspace() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
+    fn test_parse_repo_resolves_references_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let infra = dir.path().join("infra");
+        std::fs::create_dir_all(&infra).unwrap();
+        std::fs::write(
+            infra.join("backend.tf"),
+            r#"
 terraform {
   backend "s3" {
-    bucket = "my-terraform-state"
-    key    = "production/terraform.tfstate"
-    region = "us-east-1"
+    key = "staging/terraform.tfstate"
   }
 }
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            infra.join("queues.tf"),
+            r#"
+resource "aws_sqs_queue" "jobs" {
+  name = "jobs"
+}
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            infra.join("functions.tf"),
+            r#"
+resource "aws_lambda_function" "worker" {
+  function_name = "job-worker"
+  runtime       = "python3.12"
+}
 
-resource "aws_dynamodb_table" "users" {
-  name = "users-table"
+resource "aws_lambda_event_source_mapping" "jobs" {
+  event_source_arn = aws_sqs_queue.jobs.arn
+  function_name    = aws_lambda_function.worker.arn
 }
-"#;
+"#,
+        )
+        .unwrap();
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-        let db_accesses: Vec<_> = discoveries
+        let parser = TerraformParser::new().unwrap();
+        let discoveries = parser.parse_repo(dir.path()).unwrap().discoveries;
+
+        let rels = relationships(&discoveries);
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].source_service, "job-worker");
+        assert_eq!(rels[0].target, "jobs");
+        assert!(rels[0].source_file.ends_with("functions.tf"));
+
+        // The backend file applies to the whole module
+        let queue = discoveries
             .iter()
-            .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
+            .find_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
                 _ => None,
             })
-            .collect();
+            .unwrap();
+        let metadata = queue.deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.terraform_workspace, Some("staging".to_string()));
+    }
 
-        assert_eq!(db_accesses.len(), 1);
-        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.terraform_workspace, Some("production".to_string()));
-        assert_eq!(metadata.environment, Some("production".to_string()));
+    // ==================== Variables, Locals and Modules ====================
+
+    fn queue_names(discoveries: &[Discovery]) -> Vec<(String, Option<String>)> {
+        discoveries
+            .iter()
+            .filter_map(|d| match d {
+                Discovery::QueueOperation(q) => Some((
+                    q.queue_name.clone().unwrap(),
+                    q.deployment_metadata
+                        .as_ref()
+                        .and_then(|m| m.terraform_workspace.clone()),
+                )),
+                _ => None,
+            })
+            .collect()
     }
 
     #[test]
-    fn test_parse_terraform_tags_variations() {
+    fn test_parse_interpolated_names() {
         let parser = TerraformParser::new().unwrap();
         let content = r#"
-resource "aws_sqs_queue" "orders" {
-  name = "orders-queue"
+variable "env" {
+  default = "dev"
+}
 
+locals {
+  prefix = "shop-${var.env}"
   tags = {
-    managed_by = "terraform"
-    env        = "staging"
+    Environment = var.env
+    ManagedBy   = "terraform"
   }
 }
+
+resource "aws_sqs_queue" "orders" {
+  name = "${local.prefix}-orders"
+  tags = local.tags
+}
+
+resource "aws_dynamodb_table" "carts" {
+  name = format("%s-%s", local.prefix, "carts")
+}
+
+resource "aws_lambda_function" "worker" {
+  function_name = join("-", [local.prefix, "worker"])
+  runtime       = "nodejs20.x"
+}
+
+resource "aws_lambda_event_source_mapping" "orders" {
+  event_source_arn = aws_sqs_queue.orders.arn
+  function_name    = aws_lambda_function.worker.arn
+}
 "#;
 
         let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-        let queues: Vec<_> = discoveries
+
+        assert_eq!(
+            queue_names(&discoveries),
+            vec![("shop-dev-orders".to_string(), None)]
+        );
+        let queue = discoveries
             .iter()
-            .filter_map(|d| match d {
+            .find_map(|d| match d {
                 Discovery::QueueOperation(q) => Some(q),
                 _ => None,
             })
-            .collect();
+            .unwrap();
+        let metadata = queue.deployment_metadata.as_ref().unwrap();
+        assert_eq!(metadata.environment, Some("dev".to_string()));
 
-        assert_eq!(queues.len(), 1);
-        let metadata = queues[0].deployment_metadata.as_ref().unwrap();
-        assert_eq!(metadata.deployment_method, "terraform");
-        assert_eq!(metadata.environment, Some("staging".to_string()));
+        assert_eq!(
+            databases(&discoveries)[0].table_name,
+            Some("shop-dev-carts".to_string())
+        );
+
+        let rels = relationships(&discoveries);
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].source_service, "shop-dev-worker");
+        assert_eq!(rels[0].target, "shop-dev-orders");
     }
 
     #[test]
-    fn test_parse_terraform_resource_without_tags() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
-resource "aws_dynamodb_table" "users" {
-  name = "users-table"
+    fn test_parse_repo_evaluates_tfvars_per_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let infra = dir.path().join("infra");
+        std::fs::create_dir_all(infra.join("envs")).unwrap();
+        std::fs::write(
+            infra.join("main.tf"),
+            r#"
+variable "env" {}
+
+variable "team" {
+  default = "core"
 }
-"#;
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-        let db_accesses: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::DatabaseAccess(db) => Some(db),
-                _ => None,
-            })
-            .collect();
+resource "aws_sqs_queue" "orders" {
+  name = "${var.team}-${var.env}-orders"
+}
 
-        assert_eq!(db_accesses.len(), 1);
-        let metadata = db_accesses[0].deployment_metadata.as_ref().unwrap();
-        // Default to terraform since we're parsing Terraform files
-        assert_eq!(metadata.deployment_method, "terraform");
-        // No environment without tags
-        assert!(metadata.environment.is_none());
+resource "aws_sns_topic" "events" {
+  name = "${var.team}-events-${terraform.workspace}"
+}
+"#,
+        )
+        .unwrap();
+        std::fs::write(infra.join("terraform.tfvars"), "team = \"shop\"\n").unwrap();
+        std::fs::write(infra.join("envs/prod.tfvars"), "env = \"prod\"\n").unwrap();
+        std::fs::write(infra.join("envs/staging.tfvars"), "env = \"staging\"\n").unwrap();
+
+        let parser = TerraformParser::new().unwrap();
+        let discoveries = parser.parse_repo(dir.path()).unwrap().discoveries;
+
+        let mut names = queue_names(&discoveries);
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("shop-events-prod".to_string(), Some("prod".to_string())),
+                (
+                    "shop-events-staging".to_string(),
+                    Some("staging".to_string())
+                ),
+                ("shop-prod-orders".to_string(), Some("prod".to_string())),
+                (
+                    "shop-staging-orders".to_string(),
+                    Some("staging".to_string())
+                ),
+            ]
+        );
     }
 
     #[test]
-    fn test_parse_terraform_nested_key_path() {
-        let parser = TerraformParser::new().unwrap();
-        let content = r#"
+    fn test_parse_repo_follows_local_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("infra");
+        let module = dir.path().join("modules/queue");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&module).unwrap();
+        std::fs::write(
+            module.join("main.tf"),
+            r#"
+variable "name" {}
+
+variable "retention" {
+  default = 345600
+}
+
+resource "aws_sqs_queue" "this" {
+  name                      = "${var.name}-queue"
+  message_retention_seconds = var.retention
+}
+
+output "queue_name" {
+  value = aws_sqs_queue.this.name
+}
+
+output "name" {
+  value = "${var.name}-queue"
+}
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("main.tf"),
+            r#"
 terraform {
   backend "s3" {
-    bucket = "my-state"
-    key    = "env/prod/myservice/terraform.tfstate"
-    region = "us-east-1"
+    key = "production/terraform.tfstate"
   }
 }
 
-resource "aws_s3_bucket" "data" {
-  bucket = "my-bucket"
+locals {
+  env = "prod"
 }
-"#;
 
-        let discoveries = parser.parse_file(Path::new("main.tf"), content).unwrap();
-        let resources: Vec<_> = discoveries
-            .iter()
-            .filter_map(|d| match d {
-                Discovery::CloudResourceUsage(r) => Some(r),
-                _ => None,
-            })
-            .collect();
+module "orders" {
+  source = "../modules/queue"
+  name   = "${local.env}-orders"
+}
 
-        assert_eq!(resources.len(), 1);
-        let metadata = resources[0].deployment_metadata.as_ref().unwrap();
-        // Should extract "myservice" from the path (second-to-last segment)
-        assert_eq!(metadata.terraform_workspace, Some("myservice".to_string()));
-    }
+module "registry" {
+  source  = "terraform-aws-modules/sqs/aws"
+  name    = "ignored"
+}
 
-    #[test]
-    fn test_workspace_from_key_path_variations() {
-        let parser = TerraformParser::new().unwrap();
+resource "aws_lambda_function" "worker" {
+  function_name = "${local.env}-worker"
+  runtime       = "python3.12"
+}
 
-        // Simple environment path
-        assert_eq!(
-            parser.workspace_from_key_path("production/terraform.tfstate"),
-            Some("production".to_string())
-        );
+resource "aws_lambda_event_source_mapping" "orders" {
+  event_source_arn = "arn:aws:sqs:us-east-1:123456789012:${module.orders.name}"
+  function_name    = aws_lambda_function.worker.function_name
+}
+"#,
+        )
+        .unwrap();
 
-        // Nested path
-        assert_eq!(
-            parser.workspace_from_key_path("env/staging/terraform.tfstate"),
-            Some("staging".to_string())
-        );
+        let parser = TerraformParser::new().unwrap();
+        let discoveries = parser.parse_repo(dir.path()).unwrap().discoveries;
 
-        // Deep nested path - takes parent of tfstate
+        // The module is only reported through its caller, with the caller's
+        // inputs and workspace
         assert_eq!(
-            parser.workspace_from_key_path("org/team/dev/state.tfstate"),
-            Some("dev".to_string())
+            queue_names(&discoveries),
+            vec![(
+                "prod-orders-queue".to_string(),
+                Some("production".to_string())
+            )]
         );
-
-        // No path - default workspace
-        assert_eq!(parser.workspace_from_key_path("terraform.tfstate"), None);
+        let queue = discoveries
+            .iter()
+            .find_map(|d| match d {
+                Discovery::QueueOperation(q) => Some(q),
+                _ => None,
+            })
+            .unwrap();
+        assert!(queue.source_file.ends_with("modules/queue/main.tf"));
+
+        // Module outputs are available to the caller
+        let rels = relationships(&discoveries);
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].source_service, "prod-worker");
+        assert_eq!(rels[0].target, "prod-orders-queue");
     }
 
     #[test]
-    fn test_extract_environment_from_tags_case_insensitive() {
-        let parser = TerraformParser::new().unwrap();
-
-        let mut tags1 = HashMap::new();
-        tags1.insert("Environment".to_string(), "prod".to_string());
+    fn test_local_module_dir() {
         assert_eq!(
-            parser.extract_environment_from_tags(&tags1),
-            Some("prod".to_string())
+            local_module_dir(Path::new("repo/infra"), "../modules/queue"),
+            Some(PathBuf::from("repo/modules/queue"))
         );
-
-        let mut tags2 = HashMap::new();
-        tags2.insert("environment".to_string(), "staging".to_string());
         assert_eq!(
-            parser.extract_environment_from_tags(&tags2),
-            Some("staging".to_string())
+            local_module_dir(Path::new("repo/infra"), "./modules/queue"),
+            Some(PathBuf::from("repo/infra/modules/queue"))
         );
-
-        let mut tags3 = HashMap::new();
-        tags3.insert("Env".to_string(), "dev".to_string());
         assert_eq!(
-            parser.extract_environment_from_tags(&tags3),
-            Some("dev".to_string())
+            local_module_dir(Path::new("repo"), "terraform-aws-modules/sqs/aws"),
+            None
         );
-
-        let mut tags4 = HashMap::new();
-        tags4.insert("env".to_string(), "test".to_string());
         assert_eq!(
-            parser.extract_environment_from_tags(&tags4),
-            Some("test".to_string())
+            local_module_dir(Path::new("repo"), "git::https://example.com/m.git"),
+            None
         );
+    }
 
-        let tags5 = HashMap::new();
-        assert_eq!(parser.extract_environment_from_tags(&tags5), None);
+    #[test]
+    fn test_db_type_from_engine() {
+        assert_eq!(db_type_from_engine("aurora"), "mysql");
+        assert_eq!(db_type_from_engine("aurora-mysql"), "mysql");
+        assert_eq!(db_type_from_engine("aurora-postgresql"), "postgresql");
+        assert_eq!(db_type_from_engine("postgres"), "postgresql");
+        assert_eq!(db_type_from_engine("mariadb"), "mariadb");
+        assert_eq!(db_type_from_engine("sqlserver-ex"), "sqlserver");
+        assert_eq!(db_type_from_engine("oracle-ee"), "oracle");
     }
 }
@@ -0,0 +1,572 @@
+//! Static evaluation of Terraform expressions.
+//!
+//! Resource names in Terraform are usually interpolated (`"${var.env}-orders"`,
+//! `local.table_name`). A [`ModuleScope`] holds everything about a module
+//! instance that is known without running Terraform:
+//!
+//! - `var.*` from variable defaults, overridden by `*.tfvars` values or the
+//!   arguments of the calling `module` block
+//! - `local.*`, in dependency order
+//! - `module.<name>.<output>` for local modules evaluated before the caller
+//! - `terraform.workspace` and `path.module`
+//! - the built-in functions `format`, `join`, `lower`, `upper`, `replace`,
+//!   `trimspace`, `trimprefix`, `trimsuffix`, `concat`, `merge`, `coalesce`,
+//!   `lookup` and `tostring`
+//!
+//! Evaluation is done in place and is best effort: expressions that depend
+//! on anything else (resource attributes, data sources, unset variables) are
+//! left as they are, so resource references still resolve afterwards.
+
+use hcl::Value;
+use hcl::eval::{Context, Evaluate, FuncArgs, FuncDef, ParamType};
+use hcl::{Expression, Map};
+
+/// `module` block attributes that are Terraform meta-arguments rather than
+/// module inputs.
+const MODULE_META_ARGUMENTS: &[&str] = &[
+    "source",
+    "version",
+    "count",
+    "for_each",
+    "providers",
+    "depends_on",
+];
+
+/// Known values of one module instance.
+pub(crate) struct ModuleScope {
+    variables: Map<String, Value>,
+    locals: Map<String, Value>,
+    modules: Map<String, Value>,
+    workspace: String,
+    module_path: String,
+    /// `locals` entries, re-evaluated whenever new module outputs are known
+    local_exprs: Vec<(String, Expression)>,
+}
+
+impl ModuleScope {
+    /// Build the scope of a module from its files.
+    ///
+    /// `inputs` (tfvars values or module arguments) override the defaults of
+    /// the variables the module declares.
+    pub(crate) fn new(
+        bodies: &[&hcl::Body],
+        inputs: &Map<String, Value>,
+        workspace: &str,
+        module_path: &str,
+    ) -> Self {
+        let mut scope = Self {
+            variables: Map::new(),
+            locals: Map::new(),
+            modules: Map::new(),
+            workspace: workspace.to_string(),
+            module_path: module_path.to_string(),
+            local_exprs: Vec::new(),
+        };
+
+        let defaults_context = scope.context();
+        for body in bodies {
+            for block in body.blocks() {
+                match block.identifier() {
+                    "variable" => {
+                        let Some(name) = block.labels().first().map(|l| l.as_str()) else {
+                            continue;
+                        };
+                        let value = inputs.get(name).cloned().or_else(|| {
+                            block
+                                .body()
+                                .attributes()
+                                .find(|attr| attr.key() == "default")
+                                .and_then(|attr| attr.expr().evaluate(&defaults_context).ok())
+                        });
+                        if let Some(value) = value {
+                            scope.variables.insert(name.to_string(), value);
+                        }
+                    }
+                    "locals" => {
+                        for attr in block.body().attributes() {
+                            scope
+                                .local_exprs
+                                .push((attr.key().to_string(), attr.expr().clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        scope.resolve_locals();
+        scope
+    }
+
+    /// Make the outputs of a called module available as `module.<name>.*`.
+    pub(crate) fn add_module_outputs(&mut self, name: &str, outputs: Map<String, Value>) {
+        self.modules
+            .insert(name.to_string(), Value::Object(outputs));
+        // Locals may refer to module outputs
+        self.resolve_locals();
+    }
+
+    /// Evaluate every expression of `body` that can be evaluated.
+    pub(crate) fn evaluate_in_place(&self, body: &mut hcl::Body) {
+        if let Err(errors) = body.evaluate_in_place(&self.context()) {
+            tracing::trace!(
+                "{} Terraform expressions left unevaluated in {}",
+                errors.len(),
+                self.module_path
+            );
+        }
+    }
+
+    /// The input values a `module` block passes to the called module.
+    pub(crate) fn module_arguments(&self, block: &hcl::Block) -> Map<String, Value> {
+        let ctx = self.context();
+        block
+            .body()
+            .attributes()
+            .filter(|attr| !MODULE_META_ARGUMENTS.contains(&attr.key()))
+            .filter_map(|attr| {
+                attr.expr()
+                    .evaluate(&ctx)
+                    .ok()
+                    .map(|value| (attr.key().to_string(), value))
+            })
+            .collect()
+    }
+
+    /// The module's `output` values that can be evaluated.
+    pub(crate) fn outputs(&self, bodies: &[&hcl::Body]) -> Map<String, Value> {
+        let ctx = self.context();
+        bodies
+            .iter()
+            .flat_map(|body| body.blocks())
+            .filter(|block| block.identifier() == "output")
+            .filter_map(|block| {
+                let name = block.labels().first()?.as_str().to_string();
+                let value = block
+                    .body()
+                    .attributes()
+                    .find(|attr| attr.key() == "value")?
+                    .expr()
+                    .evaluate(&ctx)
+                    .ok()?;
+                Some((name, value))
+            })
+            .collect()
+    }
+
+    /// Evaluate locals until no more can be resolved; a local may refer to
+    /// other locals declared anywhere in the module.
+    fn resolve_locals(&mut self) {
+        let mut pending: Vec<&(String, Expression)> = self.local_exprs.iter().collect();
+        let mut locals = Map::new();
+
+        loop {
+            let ctx = context_with(
+                &self.variables,
+                &locals,
+                &self.modules,
+                &self.workspace,
+                &self.module_path,
+            );
+            let mut resolved = Vec::new();
+            pending.retain(|(name, expr)| match expr.evaluate(&ctx) {
+                Ok(value) => {
+                    resolved.push((name.clone(), value));
+                    false
+                }
+                Err(_) => true,
+            });
+            if resolved.is_empty() {
+                break;
+            }
+            locals.extend(resolved);
+        }
+
+        self.locals = locals;
+    }
+
+    fn context(&self) -> Context<'static> {
+        context_with(
+            &self.variables,
+            &self.locals,
+            &self.modules,
+            &self.workspace,
+            &self.module_path,
+        )
+    }
+}
+
+/// Values assigned in a `.tfvars` file.
+pub(crate) fn tfvars_values(body: &hcl::Body) -> Map<String, Value> {
+    let ctx = context_with(&Map::new(), &Map::new(), &Map::new(), "default", ".");
+    body.attributes()
+        .filter_map(|attr| {
+            attr.expr()
+                .evaluate(&ctx)
+                .ok()
+                .map(|value| (attr.key().to_string(), value))
+        })
+        .collect()
+}
+
+fn context_with(
+    variables: &Map<String, Value>,
+    locals: &Map<String, Value>,
+    modules: &Map<String, Value>,
+    workspace: &str,
+    module_path: &str,
+) -> Context<'static> {
+    let mut ctx = Context::new();
+    declare_functions(&mut ctx);
+    ctx.declare_var("var", Value::Object(variables.clone()));
+    ctx.declare_var("local", Value::Object(locals.clone()));
+    ctx.declare_var("module", Value::Object(modules.clone()));
+    ctx.declare_var(
+        "terraform",
+        Value::from_iter([("workspace", Value::from(workspace))]),
+    );
+    ctx.declare_var(
+        "path",
+        Value::from_iter([("module", Value::from(module_path))]),
+    );
+    ctx
+}
+
+fn declare_functions(ctx: &mut Context) {
+    let string = || ParamType::String;
+    let any_array = || ParamType::array_of(ParamType::Any);
+
+    ctx.declare_func(
+        "format",
+        FuncDef::builder()
+            .param(string())
+            .variadic_param(ParamType::Any)
+            .build(format),
+    );
+    ctx.declare_func(
+        "join",
+        FuncDef::builder()
+            .param(string())
+            .variadic_param(any_array())
+            .build(join),
+    );
+    ctx.declare_func("lower", FuncDef::builder().param(string()).build(lower));
+    ctx.declare_func("upper", FuncDef::builder().param(string()).build(upper));
+    ctx.declare_func(
+        "trimspace",
+        FuncDef::builder().param(string()).build(trimspace),
+    );
+    ctx.declare_func(
+        "replace",
+        FuncDef::builder()
+            .params([string(), string(), string()])
+            .build(replace),
+    );
+    ctx.declare_func(
+        "trimprefix",
+        FuncDef::builder()
+            .params([string(), string()])
+            .build(trimprefix),
+    );
+    ctx.declare_func(
+        "trimsuffix",
+        FuncDef::builder()
+            .params([string(), string()])
+            .build(trimsuffix),
+    );
+    ctx.declare_func(
+        "concat",
+        FuncDef::builder().variadic_param(any_array()).build(concat),
+    );
+    ctx.declare_func(
+        "merge",
+        FuncDef::builder()
+            .variadic_param(ParamType::nullable(ParamType::object_of(ParamType::Any)))
+            .build(merge),
+    );
+    ctx.declare_func(
+        "coalesce",
+        FuncDef::builder()
+            .variadic_param(ParamType::Any)
+            .build(coalesce),
+    );
+    ctx.declare_func(
+        "lookup",
+        FuncDef::builder()
+            .params([ParamType::object_of(ParamType::Any), string()])
+            .variadic_param(ParamType::Any)
+            .build(lookup),
+    );
+    ctx.declare_func(
+        "tostring",
+        FuncDef::builder().param(ParamType::Any).build(tostring),
+    );
+}
+
+/// The string form of a primitive value, as Terraform converts it.
+fn primitive_string(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(format!("expected a primitive value, got {}", other)),
+    }
+}
+
+fn str_arg(args: &FuncArgs, index: usize) -> &str {
+    // Parameter types are validated before the function is called
+    args[index].as_str().unwrap_or_default()
+}
+
+/// `format(spec, args...)` with the `%s`, `%d`, `%v` and `%%` verbs.
+fn format(args: FuncArgs) -> Result<Value, String> {
+    let spec = str_arg(&args, 0);
+    let mut values = args.variadic_args();
+    let mut result = String::new();
+    let mut chars = spec.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => result.push('%'),
+            Some('s' | 'd' | 'v') => {
+                let value = values.next().ok_or("not enough arguments for format")?;
+                result.push_str(&primitive_string(value)?);
+            }
+            Some(verb) => return Err(format!("unsupported format verb %{}", verb)),
+            None => return Err("format string ends with %".to_string()),
+        }
+    }
+
+    Ok(Value::String(result))
+}
+
+fn join(args: FuncArgs) -> Result<Value, String> {
+    let separator = str_arg(&args, 0);
+    let mut parts = Vec::new();
+    for list in args.variadic_args() {
+        for value in list.as_array().into_iter().flatten() {
+            parts.push(primitive_string(value)?);
+        }
+    }
+    Ok(Value::String(parts.join(separator)))
+}
+
+fn lower(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(str_arg(&args, 0).to_lowercase()))
+}
+
+fn upper(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(str_arg(&args, 0).to_uppercase()))
+}
+
+fn trimspace(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::from(str_arg(&args, 0).trim()))
+}
+
+/// `replace(string, substring, replacement)`; regex patterns (`/.../`) are
+/// not supported.
+fn replace(args: FuncArgs) -> Result<Value, String> {
+    let substring = str_arg(&args, 1);
+    if substring.len() > 1 && substring.starts_with('/') && substring.ends_with('/') {
+        return Err("regular expressions are not supported".to_string());
+    }
+    Ok(Value::from(
+        str_arg(&args, 0).replace(substring, str_arg(&args, 2)),
+    ))
+}
+
+fn trimprefix(args: FuncArgs) -> Result<Value, String> {
+    let value = str_arg(&args, 0);
+    Ok(Value::from(
+        value.strip_prefix(str_arg(&args, 1)).unwrap_or(value),
+    ))
+}
+
+fn trimsuffix(args: FuncArgs) -> Result<Value, String> {
+    let value = str_arg(&args, 0);
+    Ok(Value::from(
+        value.strip_suffix(str_arg(&args, 1)).unwrap_or(value),
+    ))
+}
+
+fn concat(args: FuncArgs) -> Result<Value, String> {
+    Ok(Value::Array(
+        args.variadic_args()
+            .filter_map(|list| list.as_array())
+            .flatten()
+            .cloned()
+            .collect(),
+    ))
+}
+
+fn merge(args: FuncArgs) -> Result<Value, String> {
+    let mut merged = Map::new();
+    for object in args.variadic_args().filter_map(|o| o.as_object()) {
+        merged.extend(object.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    Ok(Value::Object(merged))
+}
+
+fn coalesce(args: FuncArgs) -> Result<Value, String> {
+    args.variadic_args()
+        .find(|value| !value.is_null() && value.as_str() != Some(""))
+        .cloned()
+        .ok_or_else(|| "no non-null, non-empty argument".to_string())
+}
+
+fn lookup(args: FuncArgs) -> Result<Value, String> {
+    let key = str_arg(&args, 1);
+    args[0]
+        .as_object()
+        .and_then(|object| object.get(key))
+        .or_else(|| args.variadic_args().next())
+        .cloned()
+        .ok_or_else(|| format!("key {:?} not found", key))
+}
+
+fn tostring(args: FuncArgs) -> Result<Value, String> {
+    primitive_string(&args[0]).map(Value::String)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(content: &str, inputs: &[(&str, &str)]) -> (hcl::Body, ModuleScope) {
+        let body: hcl::Body = hcl::from_str(content).unwrap();
+        let inputs: Map<String, Value> = inputs
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::from(*v)))
+            .collect();
+        let scope = ModuleScope::new(&[&body], &inputs, "staging", "infra");
+        (body, scope)
+    }
+
+    fn attribute(body: &hcl::Body, block: &str, key: &str) -> Expression {
+        body.blocks()
+            .find(|b| b.labels().get(1).map(|l| l.as_str()) == Some(block))
+            .unwrap()
+            .body()
+            .attributes()
+            .find(|a| a.key() == key)
+            .unwrap()
+            .expr()
+            .clone()
+    }
+
+    #[test]
+    fn test_variables_locals_and_functions() {
+        let content = r#"
+variable "env" {
+  default = "dev"
+}
+
+variable "team" {
+  default = "payments"
+}
+
+locals {
+  # Declared before the local it depends on
+  table_name = "${local.prefix}-orders"
+  prefix     = format("%s-%s", var.team, var.env)
+  tags       = merge({ Team = var.team }, { Env = upper(var.env) })
+}
+
+resource "aws_dynamodb_table" "orders" {
+  name = local.table_name
+  tags = local.tags
+}
+
+resource "aws_sqs_queue" "jobs" {
+  name = join("-", [var.env, "jobs", terraform.workspace])
+  policy = aws_iam_policy.jobs.arn
+}
+"#;
+        let (mut body, scope) = scope(content, &[("env", "prod")]);
+        scope.evaluate_in_place(&mut body);
+
+        assert_eq!(
+            attribute(&body, "orders", "name"),
+            Expression::String("payments-prod-orders".to_string())
+        );
+        assert_eq!(
+            attribute(&body, "jobs", "name"),
+            Expression::String("prod-jobs-staging".to_string())
+        );
+        let tags = attribute(&body, "orders", "tags").to_string();
+        assert!(tags.contains("PROD"));
+        // References to resources are left for later resolution
+        assert_eq!(
+            attribute(&body, "jobs", "policy").to_string(),
+            "aws_iam_policy.jobs.arn"
+        );
+    }
+
+    #[test]
+    fn test_module_arguments_and_outputs() {
+        let caller: hcl::Body = hcl::from_str(
+            r#"
+variable "env" {
+  default = "dev"
+}
+
+module "orders" {
+  source     = "./modules/orders"
+  name       = "${var.env}-orders"
+  depends_on = [aws_iam_role.x]
+}
+"#,
+        )
+        .unwrap();
+        let scope = ModuleScope::new(&[&caller], &Map::new(), "default", ".");
+        let block = caller
+            .blocks()
+            .find(|b| b.identifier() == "module")
+            .unwrap();
+        let arguments = scope.module_arguments(block);
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments.get("name"), Some(&Value::from("dev-orders")));
+
+        let child: hcl::Body = hcl::from_str(
+            r#"
+variable "name" {}
+
+output "queue_name" {
+  value = "${var.name}-queue"
+}
+
+output "queue_arn" {
+  value = aws_sqs_queue.this.arn
+}
+"#,
+        )
+        .unwrap();
+        let child_scope = ModuleScope::new(&[&child], &arguments, "default", "modules/orders");
+        let outputs = child_scope.outputs(&[&child]);
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(
+            outputs.get("queue_name"),
+            Some(&Value::from("dev-orders-queue"))
+        );
+    }
+
+    #[test]
+    fn test_tfvars_values() {
+        let body: hcl::Body = hcl::from_str(
+            r#"
+env    = "production"
+shards = 4
+name   = format("%s-%d", "orders", 2)
+"#,
+        )
+        .unwrap();
+        let values = tfvars_values(&body);
+        assert_eq!(values.get("env"), Some(&Value::from("production")));
+        assert_eq!(values.get("shards"), Some(&Value::from(4)));
+        assert_eq!(values.get("name"), Some(&Value::from("orders-2")));
+    }
+}
@@ -9,9 +9,14 @@
 //! - **Deterministic**: Uses tree-sitter AST parsing only, no LLM calls
 //! - **Extensible**: New languages can be added by implementing the Parser trait
 //! - **Resilient**: Parser failures don't crash the entire survey
+//! - **Parallel**: Files are parsed on a worker pool, with results kept in
+//!   file order so the output does not depend on scheduling
 
+use crate::cache::DiscoveryCache;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors that can occur during parsing.
@@ -35,13 +40,75 @@ pub enum ParserError {
     /// An error occurred in the tree-sitter parsing library.
     #[error("Tree-sitter error: {0}")]
     TreeSitterError(String),
+
+    /// A parser plugin could not be run or returned an invalid response.
+    #[error("Plugin {plugin} failed: {reason}")]
+    PluginFailed {
+        /// The plugin's name.
+        plugin: String,
+        /// What went wrong.
+        reason: String,
+    },
+
+    /// A user-defined detection rule is invalid.
+    #[error("Invalid detection rule {rule}: {reason}")]
+    InvalidRule {
+        /// The rule's name.
+        rule: String,
+        /// What is wrong with it.
+        reason: String,
+    },
+
+    /// A parser plugin did not respond in time and was stopped.
+    #[error("Plugin {plugin} timed out after {seconds}s")]
+    PluginTimedOut {
+        /// The plugin's name.
+        plugin: String,
+        /// The timeout that was exceeded.
+        seconds: u64,
+    },
+}
+
+/// A file that could not be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    /// The file, or the repository when a whole parser failed.
+    pub path: PathBuf,
+
+    /// Why parsing failed.
+    pub error: String,
+}
+
+/// What parsing a repository produced.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoParse {
+    /// Discoveries, ordered by the file they were made in.
+    pub discoveries: Vec<Discovery>,
+
+    /// Files that failed to parse. They are skipped; the rest of the
+    /// repository is still parsed.
+    pub failures: Vec<ParseFailure>,
+}
+
+impl RepoParse {
+    /// Parse files in parallel (see [`parse_in_parallel`]).
+    pub(crate) fn parse_files<F>(files: &[PathBuf], parse: F) -> Self
+    where
+        F: Fn(&Path, &str) -> Result<Vec<Discovery>, ParserError> + Sync,
+    {
+        let (parsed, failures) = parse_in_parallel(files, parse);
+        Self {
+            discoveries: parsed.into_iter().flat_map(|(_, d)| d).collect(),
+            failures,
+        }
+    }
 }
 
 /// A discovery made by a parser during code analysis.
 ///
 /// Each variant represents a different type of code pattern that Forge
 /// tracks to build the knowledge graph.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Discovery {
     /// A service entry point was found (e.g., from package.json or main.py).
     Service(ServiceDiscovery),
@@ -52,6 +119,9 @@ pub enum Discovery {
     /// An HTTP API call was detected (e.g., axios, fetch, requests).
     ApiCall(ApiCallDiscovery),
 
+    /// An API endpoint exposed by a service was found (e.g., an HTTP route).
+    ApiEndpoint(ApiEndpointDiscovery),
+
     /// A database access was detected (e.g., DynamoDB, PostgreSQL).
     DatabaseAccess(DatabaseAccessDiscovery),
 
@@ -60,10 +130,84 @@ pub enum Discovery {
 
     /// A cloud resource usage was detected (e.g., S3, Lambda).
     CloudResourceUsage(CloudResourceDiscovery),
+
+    /// A relationship between a named service and another component was
+    /// declared in configuration (e.g., docker-compose `depends_on`).
+    Relationship(RelationshipDiscovery),
+
+    /// A generated gRPC client or server implementation was used.
+    GrpcUsage(GrpcUsageDiscovery),
+
+    /// A CloudFormation stack exported a resource for other stacks to import.
+    StackExport(StackExportDiscovery),
+
+    /// A CloudFormation stack imported a value exported by another stack.
+    StackImport(StackImportDiscovery),
+
+    /// An environment variable was set for a service in IaC (e.g., Lambda
+    /// `Environment.Variables`).
+    EnvironmentVariable(EnvironmentVariableDiscovery),
+}
+
+impl Discovery {
+    /// The file the discovery was made in.
+    pub fn source_file(&self) -> &str {
+        match self {
+            Self::Service(d) => &d.source_file,
+            Self::Import(d) => &d.source_file,
+            Self::ApiCall(d) => &d.source_file,
+            Self::ApiEndpoint(d) => &d.source_file,
+            Self::DatabaseAccess(d) => &d.source_file,
+            Self::QueueOperation(d) => &d.source_file,
+            Self::CloudResourceUsage(d) => &d.source_file,
+            Self::Relationship(d) => &d.source_file,
+            Self::GrpcUsage(d) => &d.source_file,
+            Self::StackExport(d) => &d.source_file,
+            Self::StackImport(d) => &d.source_file,
+            Self::EnvironmentVariable(d) => &d.source_file,
+        }
+    }
+
+    /// The line of the source file the discovery was made at.
+    pub fn source_line(&self) -> u32 {
+        match self {
+            Self::Service(d) => d.source_line,
+            Self::Import(d) => d.source_line,
+            Self::ApiCall(d) => d.source_line,
+            Self::ApiEndpoint(d) => d.source_line,
+            Self::DatabaseAccess(d) => d.source_line,
+            Self::QueueOperation(d) => d.source_line,
+            Self::CloudResourceUsage(d) => d.source_line,
+            Self::Relationship(d) => d.source_line,
+            Self::GrpcUsage(d) => d.source_line,
+            Self::StackExport(d) => d.source_line,
+            Self::StackImport(d) => d.source_line,
+            Self::EnvironmentVariable(d) => d.source_line,
+        }
+    }
+
+    /// The file the discovery was made in, for re-pointing a discovery at
+    /// another copy of the file.
+    pub fn source_file_mut(&mut self) -> &mut String {
+        match self {
+            Self::Service(d) => &mut d.source_file,
+            Self::Import(d) => &mut d.source_file,
+            Self::ApiCall(d) => &mut d.source_file,
+            Self::ApiEndpoint(d) => &mut d.source_file,
+            Self::DatabaseAccess(d) => &mut d.source_file,
+            Self::QueueOperation(d) => &mut d.source_file,
+            Self::CloudResourceUsage(d) => &mut d.source_file,
+            Self::Relationship(d) => &mut d.source_file,
+            Self::GrpcUsage(d) => &mut d.source_file,
+            Self::StackExport(d) => &mut d.source_file,
+            Self::StackImport(d) => &mut d.source_file,
+            Self::EnvironmentVariable(d) => &mut d.source_file,
+        }
+    }
 }
 
 /// Details about a discovered service entry point.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct ServiceDiscovery {
     /// Service name (usually from package.json name field or directory name).
     pub name: String,
@@ -88,7 +232,7 @@ pub struct ServiceDiscovery {
 }
 
 /// Details about an import/require statement.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImportDiscovery {
     /// The module being imported (e.g., "express", "@aws-sdk/client-dynamodb").
     pub module: String,
@@ -107,7 +251,7 @@ pub struct ImportDiscovery {
 }
 
 /// Details about an HTTP API call.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ApiCallDiscovery {
     /// The target URL, service name, or endpoint pattern.
     pub target: String,
@@ -125,8 +269,74 @@ pub struct ApiCallDiscovery {
     pub source_line: u32,
 }
 
+/// Details about an API endpoint exposed by a service.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ApiEndpointDiscovery {
+    /// Route path with `{param}` placeholders (e.g., "/users/{id}").
+    pub path: String,
+
+    /// HTTP method in upper case ("GET", "POST", ...), or "ANY".
+    pub method: String,
+
+    /// Name of the service exposing the endpoint. `None` attributes the
+    /// endpoint to the service whose repository is being parsed.
+    pub service: Option<String>,
+
+    /// Operation identifier from an API contract (e.g., OpenAPI `operationId`).
+    pub operation_id: Option<String>,
+
+    /// Schema names referenced by the request body.
+    pub request_schemas: Vec<String>,
+
+    /// Schema names referenced by the responses.
+    pub response_schemas: Vec<String>,
+
+    /// How the endpoint was detected (e.g., "serverless", "openapi").
+    pub detection_method: String,
+
+    /// Source file declaring the endpoint.
+    pub source_file: String,
+
+    /// Line number of the declaration.
+    pub source_line: u32,
+}
+
+/// Details about usage of a generated gRPC client or server.
+///
+/// gRPC services are declared in `.proto` files (see the protobuf parser);
+/// this discovery ties code to one of those services by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrpcUsageDiscovery {
+    /// gRPC service name as declared in the `.proto` file (e.g., "UserService").
+    pub service: String,
+
+    /// Whether the code calls the service or implements it.
+    pub role: GrpcRole,
+
+    /// RPC methods invoked on the client, if they could be determined.
+    pub methods: Vec<String>,
+
+    /// How the usage was detected (e.g., "grpc-js", "grpcio", "grpc-go").
+    pub detection_method: String,
+
+    /// Source file containing the usage.
+    pub source_file: String,
+
+    /// Line number of the usage.
+    pub source_line: u32,
+}
+
+/// Which side of a gRPC service a piece of code is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GrpcRole {
+    /// The code constructs a client or stub for the service.
+    Client,
+    /// The code registers an implementation of the service.
+    Server,
+}
+
 /// Details about a database access pattern.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DatabaseAccessDiscovery {
     /// Database type (e.g., "dynamodb", "postgresql", "mongodb").
     pub db_type: String,
@@ -134,6 +344,11 @@ pub struct DatabaseAccessDiscovery {
     /// Table or collection name if detected.
     pub table_name: Option<String>,
 
+    /// Connection the access goes through, for databases holding many
+    /// tables (e.g., the `DATABASE_URL` environment variable). Services
+    /// using the same connection share one `Database` node.
+    pub connection: Option<String>,
+
     /// The type of database operation.
     pub operation: DatabaseOperation,
 
@@ -151,7 +366,7 @@ pub struct DatabaseAccessDiscovery {
 }
 
 /// Types of database operations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DatabaseOperation {
     /// Read-only operation (SELECT, get, query, scan).
     Read,
@@ -164,9 +379,10 @@ pub enum DatabaseOperation {
 }
 
 /// Details about a queue/message operation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QueueOperationDiscovery {
-    /// Queue type (e.g., "sqs", "sns", "eventbridge", "rabbitmq").
+    /// Queue type (e.g., "sqs", "sns", "eventbridge", "kafka", "rabbitmq",
+    /// "redis-stream", "celery", "bullmq").
     pub queue_type: String,
 
     /// Queue or topic name/ARN if detected.
@@ -186,7 +402,7 @@ pub struct QueueOperationDiscovery {
 }
 
 /// Types of queue/message operations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QueueOperationType {
     /// Publishing/sending a message.
     Publish,
@@ -197,7 +413,7 @@ pub enum QueueOperationType {
 }
 
 /// Details about cloud resource usage.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CloudResourceDiscovery {
     /// Resource type (e.g., "s3", "lambda", "secretsmanager").
     pub resource_type: String,
@@ -215,13 +431,146 @@ pub struct CloudResourceDiscovery {
     pub deployment_metadata: Option<DeploymentMetadata>,
 }
 
+/// Details about a relationship declared between two components.
+///
+/// Unlike the other discoveries, which are attributed to the service whose
+/// repository is being parsed, a relationship names its source service
+/// explicitly. This lets configuration files that describe several services
+/// (docker-compose, IaC templates) record who talks to whom.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationshipDiscovery {
+    /// Name of the service the relationship originates from.
+    pub source_service: String,
+
+    /// Name of the target component (service name, table, queue, etc.).
+    pub target: String,
+
+    /// What kind of component the target is.
+    pub target_kind: ComponentKind,
+
+    /// The nature of the relationship.
+    pub relationship: RelationshipType,
+
+    /// How the relationship was detected (e.g., "docker-compose").
+    pub detection_method: String,
+
+    /// Source file declaring the relationship.
+    pub source_file: String,
+
+    /// Line number of the declaration.
+    pub source_line: u32,
+}
+
+/// Details about a resource exported by a CloudFormation stack
+/// (`Outputs.*.Export.Name`).
+///
+/// Exports are matched to [`StackImportDiscovery`] consumers by name when the
+/// graph is built, so stacks in different repositories can be linked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackExportDiscovery {
+    /// Export name, with intrinsic functions resolved (e.g., "prod-OrdersTable").
+    pub export_name: String,
+
+    /// Name of the exported resource.
+    pub target: String,
+
+    /// What kind of component the exported resource is.
+    pub target_kind: ComponentKind,
+
+    /// Services of the exporting stack that use the resource. They own it;
+    /// importing services share it.
+    pub owners: Vec<String>,
+
+    /// Template declaring the export.
+    pub source_file: String,
+
+    /// Line number of the declaration.
+    pub source_line: u32,
+}
+
+/// Details about a value a service imports from another CloudFormation stack
+/// (`Fn::ImportValue`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StackImportDiscovery {
+    /// Name of the service using the imported value.
+    pub source_service: String,
+
+    /// Export name, with intrinsic functions resolved.
+    pub export_name: String,
+
+    /// How the service uses the exported resource.
+    pub relationship: RelationshipType,
+
+    /// Template containing the import.
+    pub source_file: String,
+
+    /// Line number of the import.
+    pub source_line: u32,
+}
+
+/// Details about an environment variable set for a service in IaC.
+///
+/// Resource names the code reads from environment variables are replaced by
+/// the value set in the same repository when the graph is built.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentVariableDiscovery {
+    /// Name of the service the variable is set for.
+    pub service: String,
+
+    /// Variable name (e.g., "USERS_TABLE").
+    pub name: String,
+
+    /// Value, with references to declared resources replaced by the
+    /// resource name.
+    pub value: String,
+
+    /// File setting the variable.
+    pub source_file: String,
+
+    /// Line number of the definition.
+    pub source_line: u32,
+}
+
+/// Kinds of components that can be the target of a relationship.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComponentKind {
+    /// Another service.
+    Service,
+    /// A database or table.
+    Database,
+    /// A queue or topic.
+    Queue,
+    /// Any other cloud resource.
+    CloudResource,
+}
+
+/// Types of declared relationships.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelationshipType {
+    /// A generic dependency; the edge type is inferred from the target kind.
+    DependsOn,
+    /// The source calls the target service.
+    Calls,
+    /// The source reads from the target.
+    Reads,
+    /// The source writes to the target.
+    Writes,
+    /// The source publishes to the target queue or topic.
+    Publishes,
+    /// The source consumes from the target queue or topic.
+    Subscribes,
+    /// The source uses the target cloud resource.
+    Uses,
+}
+
 /// Deployment metadata extracted from Infrastructure as Code files.
 ///
 /// This metadata helps LLM coding agents understand HOW resources are deployed,
 /// enabling generation of correct deployment commands and operational context.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct DeploymentMetadata {
-    /// How this resource is deployed: "terraform", "sam", "cloudformation", or "unknown".
+    /// How this resource is deployed: "terraform", "sam", "cloudformation",
+    /// "docker-compose", or "unknown".
     pub deployment_method: String,
 
     /// Terraform workspace (typically maps to environment).
@@ -235,6 +584,10 @@ pub struct DeploymentMetadata {
 
     /// Additional tags extracted from resource definitions.
     pub tags: std::collections::HashMap<String, String>,
+
+    /// Typed resource settings (engine, instance class, shard count, ...)
+    /// copied onto the resulting node as attributes.
+    pub resource_attributes: std::collections::HashMap<String, forge_graph::AttributeValue>,
 }
 
 /// Trait for language-specific parsers.
@@ -253,6 +606,10 @@ pub struct DeploymentMetadata {
 /// }
 ///
 /// impl Parser for MyLangParser {
+///     fn name(&self) -> &str {
+///         "mylang"
+///     }
+///
 ///     fn supported_extensions(&self) -> &[&str] {
 ///         &["ml", "mli"]
 ///     }
@@ -270,6 +627,19 @@ pub trait Parser: Send + Sync {
     /// `JavaScriptParser::parse_package_json` or `PythonParser::parse_project_config`.
     fn as_any(&self) -> &dyn Any;
 
+    /// A short, stable name for the parser (e.g. `"javascript"`), used to
+    /// key cached discoveries.
+    fn name(&self) -> &str;
+
+    /// Version of the discoveries the parser makes.
+    ///
+    /// Cached discoveries are only reused by the same version of the same
+    /// parser and of Forge. Bump it when a change makes the parser find
+    /// different discoveries in the same file.
+    fn version(&self) -> u32 {
+        1
+    }
+
     /// Returns the file extensions this parser handles (without the dot).
     ///
     /// For example: `&["js", "jsx", "ts", "tsx"]` for JavaScript/TypeScript.
@@ -287,71 +657,126 @@ pub trait Parser: Send + Sync {
 
     /// Parse an entire repository and return all discoveries.
     ///
+    /// Same as [`parse_repo_cached`](Self::parse_repo_cached) without a
+    /// cache.
+    fn parse_repo(&self, repo_path: &Path) -> Result<RepoParse, ParserError> {
+        self.parse_repo_cached(repo_path, &DiscoveryCache::disabled())
+    }
+
+    /// Parse an entire repository, reusing the discoveries cached for files
+    /// parsed before.
+    ///
     /// The default implementation walks the directory tree, filters by supported
-    /// extensions, and calls `parse_file` for each matching file. It skips
-    /// common directories like `node_modules`, `.git`, `target`, etc.
+    /// extensions, and calls `parse_file` for each matching file not in the
+    /// cache. It skips common directories like `node_modules`, `.git`,
+    /// `target`, etc.
+    ///
+    /// Files are parsed in parallel on the worker pool the call runs on (see
+    /// [`WorkerPool`](crate::WorkerPool)); discoveries keep file order.
     ///
-    /// Override this method if you need custom repository traversal logic.
+    /// Override this method (not `parse_repo`) if you need custom repository
+    /// traversal logic. Only cache a file's discoveries if they depend on
+    /// nothing but the file's name and content, or the names of the
+    /// directories holding it when the cache is
+    /// [keyed by them](crate::cache::ParserCache::keyed_by_directories).
     ///
     /// # Arguments
     /// * `repo_path` - Path to the root of the repository
+    /// * `cache` - Discoveries of previously parsed files
     ///
     /// # Returns
-    /// A vector of all discoveries found in the repository.
-    fn parse_repo(&self, repo_path: &Path) -> Result<Vec<Discovery>, ParserError> {
-        let mut all_discoveries = Vec::new();
+    /// All discoveries found in the repository, and the files that failed
+    /// to parse.
+    fn parse_repo_cached(
+        &self,
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Result<RepoParse, ParserError> {
         let extensions = self.supported_extensions();
-
-        for entry in walkdir::WalkDir::new(repo_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_entry(|e| !is_ignored_dir(e.file_name().to_str().unwrap_or("")))
-        {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(e) => {
-                    tracing::debug!("Failed to read directory entry: {}", e);
-                    continue;
-                }
-            };
-
-            if !entry.file_type().is_file() {
-                continue;
-            }
-
-            let path = entry.path();
+        let files = repo_files(repo_path, is_ignored_dir, |path| {
             let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            extensions.contains(&ext)
+        });
+        let cache = cache.for_parser(self.name(), self.version());
+        Ok(RepoParse::parse_files(&files, |path, content| {
+            cache.get_or_parse(path, content, || self.parse_file(path, content))
+        }))
+    }
+}
 
-            if !extensions.contains(&ext) {
+/// The files below `repo_path` that `include` accepts, sorted by path.
+///
+/// Directories whose name `skip_dir` accepts are not entered.
+pub(crate) fn repo_files(
+    repo_path: &Path,
+    skip_dir: fn(&str) -> bool,
+    include: impl Fn(&Path) -> bool,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(repo_path)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !skip_dir(e.file_name().to_str().unwrap_or("")))
+    {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                tracing::debug!("Failed to read directory entry: {}", e);
                 continue;
             }
+        };
+        if entry.file_type().is_file() && include(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+    files
+}
 
-            let content = match std::fs::read_to_string(path) {
-                Ok(c) => c,
-                Err(e) => {
-                    tracing::debug!("Failed to read file {}: {}", path.display(), e);
-                    continue; // Skip unreadable files (binary, permissions, etc.)
-                }
-            };
-
-            match self.parse_file(path, &content) {
-                Ok(discoveries) => all_discoveries.extend(discoveries),
-                Err(e) => {
-                    // Log but continue - don't fail entire survey for one file
-                    tracing::warn!("Failed to parse {}: {}", path.display(), e);
-                }
+/// Read and parse files in parallel on the current worker pool.
+///
+/// Results keep the order of `files`, whichever worker finishes first.
+/// Unreadable files (binary, permissions, etc.) are skipped; files `parse`
+/// rejects are returned as failures.
+pub(crate) fn parse_in_parallel<T, F>(
+    files: &[PathBuf],
+    parse: F,
+) -> (Vec<(PathBuf, T)>, Vec<ParseFailure>)
+where
+    T: Send,
+    F: Fn(&Path, &str) -> Result<T, ParserError> + Sync,
+{
+    let results: Vec<Option<Result<T, ParserError>>> = files
+        .par_iter()
+        .map(|path| match std::fs::read_to_string(path) {
+            Ok(content) => Some(parse(path, &content)),
+            Err(e) => {
+                tracing::debug!("Failed to read file {}: {}", path.display(), e);
+                None
             }
+        })
+        .collect();
+
+    let mut parsed = Vec::new();
+    let mut failures = Vec::new();
+    for (path, result) in files.iter().zip(results) {
+        match result {
+            Some(Ok(value)) => parsed.push((path.clone(), value)),
+            Some(Err(e)) => failures.push(ParseFailure {
+                path: path.clone(),
+                error: e.to_string(),
+            }),
+            None => {}
         }
-
-        Ok(all_discoveries)
     }
+    (parsed, failures)
 }
 
 /// Directories to skip during repository traversal.
 ///
 /// These are common directories that don't contain source code we want to analyze,
 /// or that would significantly slow down parsing.
-fn is_ignored_dir(name: &str) -> bool {
+pub(crate) fn is_ignored_dir(name: &str) -> bool {
     matches!(
         name,
         // JavaScript/Node.js
@@ -410,6 +835,64 @@ mod tests {
         assert!(!is_ignored_dir("services"));
     }
 
+    #[test]
+    fn test_parse_in_parallel_collects_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut files = Vec::new();
+        for i in 0..20 {
+            let path = dir.path().join(format!("file{:02}.txt", i));
+            let content = if i % 7 == 3 { "bad" } else { "good" };
+            std::fs::write(&path, content).unwrap();
+            files.push(path);
+        }
+        files.push(dir.path().join("missing.txt"));
+
+        let (parsed, failures) = parse_in_parallel(&files, |path, content| {
+            if content == "bad" {
+                return Err(ParserError::ParseFailed {
+                    path: path.display().to_string(),
+                });
+            }
+            Ok(path.file_name().unwrap().to_string_lossy().into_owned())
+        });
+
+        // Results keep file order; unreadable files are skipped
+        let names: Vec<_> = parsed.into_iter().map(|(_, name)| name).collect();
+        let expected: Vec<_> = (0..20)
+            .filter(|i| i % 7 != 3)
+            .map(|i| format!("file{:02}.txt", i))
+            .collect();
+        assert_eq!(names, expected);
+        let failed: Vec<_> = failures.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            failed,
+            vec![
+                dir.path().join("file03.txt"),
+                dir.path().join("file10.txt"),
+                dir.path().join("file17.txt"),
+            ]
+        );
+        assert!(failures[0].error.contains("file03.txt"));
+    }
+
+    #[test]
+    fn test_repo_files_sorted_and_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["b/x.js", "a.js", "node_modules/m.js", "c.py", "b/a.js"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "").unwrap();
+        }
+        let files = repo_files(dir.path(), is_ignored_dir, |path| {
+            path.extension().and_then(|e| e.to_str()) == Some("js")
+        });
+        let expected: Vec<_> = ["a.js", "b/a.js", "b/x.js"]
+            .iter()
+            .map(|f| dir.path().join(f))
+            .collect();
+        assert_eq!(files, expected);
+    }
+
     #[test]
     fn test_discovery_equality() {
         let d1 = Discovery::Import(ImportDiscovery {
@@ -478,6 +961,10 @@ mod tests {
             self
         }
 
+        fn name(&self) -> &str {
+            "mock"
+        }
+
         fn supported_extensions(&self) -> &[&str] {
             &["mock"]
         }
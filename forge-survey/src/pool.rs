@@ -0,0 +1,97 @@
+//! Bounded worker pool for parsing.
+//!
+//! Surveys parse many repositories, each with many files. All of that work
+//! runs on one [`WorkerPool`] so the number of threads stays bounded no
+//! matter how many repositories are surveyed at once: parsers started
+//! inside [`WorkerPool::install`] (including the per-file parallelism of
+//! [`Parser::parse_repo`](crate::Parser::parse_repo)) share its threads.
+//!
+//! Parallelism never changes results. Work is split across threads but
+//! results are collected in input order, and the graph is built from them
+//! sequentially.
+
+use crate::cache::DiscoveryCache;
+use crate::parser::{Parser, ParserError, RepoParse};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A fixed-size pool of parser threads.
+pub struct WorkerPool {
+    pool: ThreadPool,
+}
+
+impl WorkerPool {
+    /// Create a pool with `jobs` threads, or one per CPU when `jobs` is 0.
+    pub fn new(jobs: usize) -> Result<Self, ThreadPoolBuildError> {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .thread_name(|i| format!("forge-parse-{}", i))
+            .build()?;
+        Ok(Self { pool })
+    }
+
+    /// The number of threads in the pool.
+    pub fn jobs(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Run `f` on the pool, blocking until it returns.
+    ///
+    /// Parallel iterators used inside `f` run on the pool's threads.
+    pub fn install<R: Send>(&self, f: impl FnOnce() -> R + Send) -> R {
+        self.pool.install(f)
+    }
+
+    /// Run each parser over a repository, all in parallel on the pool.
+    ///
+    /// Results are in the order of `parsers`.
+    pub fn parse_repo(
+        &self,
+        parsers: &[Arc<dyn Parser>],
+        repo_path: &Path,
+        cache: &DiscoveryCache,
+    ) -> Vec<Result<RepoParse, ParserError>> {
+        self.install(|| {
+            parsers
+                .par_iter()
+                .map(|parser| parser.parse_repo_cached(repo_path, cache))
+                .collect()
+        })
+    }
+}
+
+impl std::fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("jobs", &self.jobs())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_pool_size() {
+        assert_eq!(WorkerPool::new(3).unwrap().jobs(), 3);
+        assert!(WorkerPool::new(0).unwrap().jobs() >= 1);
+    }
+
+    #[test]
+    fn test_worker_pool_keeps_order() {
+        let pool = WorkerPool::new(4).unwrap();
+        let squares: Vec<u64> =
+            pool.install(|| (0..1000u64).into_par_iter().map(|n| n * n).collect());
+        assert_eq!(squares, (0..1000u64).map(|n| n * n).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_worker_pool_runs_on_pool_threads() {
+        let pool = WorkerPool::new(2).unwrap();
+        let threads = pool.install(rayon::current_num_threads);
+        assert_eq!(threads, 2);
+    }
+}
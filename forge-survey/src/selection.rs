@@ -0,0 +1,397 @@
+//! Selection of the repositories a survey covers.
+//!
+//! Repositories discovered from a hosting service are matched against
+//! include and exclude criteria on what the service says about them: topics,
+//! archived and fork status, primary language, last push, visibility and
+//! name. A repository is surveyed when it meets every include criterion and
+//! no exclude criterion.
+//!
+//! Every decision carries its reason, so users can see why a repository is
+//! or isn't surveyed.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use forge_survey::selection::{RepoCriteria, RepoFilter};
+//!
+//! let filter = RepoFilter {
+//!     exclude: RepoCriteria { archived: Some(true), ..Default::default() },
+//!     ..Default::default()
+//! };
+//! let selection = filter.select(&repo, Utc::now());
+//! println!("{}: {}", repo.full_name, selection.reason);
+//! ```
+
+use crate::source::{RepoInfo, Visibility};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+
+/// Criteria on repository metadata. Unset criteria are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct RepoCriteria {
+    /// Has any of these topics (case-insensitive)
+    pub topics: Vec<String>,
+    /// Has one of these primary languages (case-insensitive)
+    pub languages: Vec<String>,
+    /// Has one of these visibilities
+    pub visibility: Vec<Visibility>,
+    /// Is (or isn't) archived
+    pub archived: Option<bool>,
+    /// Is (or isn't) a fork
+    pub fork: Option<bool>,
+    /// Was pushed to in the last N days
+    pub pushed_within_days: Option<u32>,
+    /// Has a name matching this pattern
+    pub name_regex: Option<Regex>,
+}
+
+/// One criterion checked against a repository.
+struct Check {
+    matched: bool,
+    /// What the repository is, with respect to the criterion
+    reason: String,
+}
+
+impl Check {
+    fn new(matched: bool, reason: String) -> Self {
+        Self { matched, reason }
+    }
+}
+
+impl RepoCriteria {
+    /// Check each set criterion against a repository.
+    fn check(&self, repo: &RepoInfo, now: DateTime<Utc>) -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        if let Some(archived) = self.archived {
+            let reason = if repo.archived {
+                "archived"
+            } else {
+                "not archived"
+            };
+            checks.push(Check::new(repo.archived == archived, reason.to_string()));
+        }
+
+        if let Some(fork) = self.fork {
+            let reason = if repo.fork { "fork" } else { "not a fork" };
+            checks.push(Check::new(repo.fork == fork, reason.to_string()));
+        }
+
+        if !self.visibility.is_empty() {
+            checks.push(match repo.visibility {
+                Some(visibility) if self.visibility.contains(&visibility) => {
+                    Check::new(true, visibility.to_string())
+                }
+                Some(visibility) => Check::new(
+                    false,
+                    format!(
+                        "{} isn't one of {}",
+                        visibility,
+                        join(self.visibility.iter().map(Visibility::as_str))
+                    ),
+                ),
+                None => Check::new(false, "unknown visibility".to_string()),
+            });
+        }
+
+        if !self.languages.is_empty() {
+            checks.push(match &repo.language {
+                Some(language) if contains_ignore_case(&self.languages, language) => {
+                    Check::new(true, format!("language {}", language))
+                }
+                Some(language) => Check::new(
+                    false,
+                    format!(
+                        "language {} isn't one of {}",
+                        language,
+                        join(self.languages.iter().map(String::as_str))
+                    ),
+                ),
+                None => Check::new(false, "no known language".to_string()),
+            });
+        }
+
+        if !self.topics.is_empty() {
+            let topic = repo
+                .topics
+                .iter()
+                .find(|topic| contains_ignore_case(&self.topics, topic));
+            checks.push(match topic {
+                Some(topic) => Check::new(true, format!("topic '{}'", topic)),
+                None => Check::new(
+                    false,
+                    format!(
+                        "no topic of {}",
+                        join(self.topics.iter().map(String::as_str))
+                    ),
+                ),
+            });
+        }
+
+        if let Some(days) = self.pushed_within_days {
+            let since = now - Duration::days(i64::from(days));
+            checks.push(match repo.pushed_at {
+                Some(pushed_at) if pushed_at >= since => {
+                    Check::new(true, format!("pushed in the last {} days", days))
+                }
+                Some(pushed_at) => Check::new(
+                    false,
+                    format!("last pushed {}", pushed_at.format("%Y-%m-%d")),
+                ),
+                None => Check::new(false, "no push date".to_string()),
+            });
+        }
+
+        checks.extend(self.check_name(&repo.name));
+        checks
+    }
+
+    /// Check the name criterion, which needs no metadata.
+    fn check_name(&self, name: &str) -> Option<Check> {
+        let pattern = self.name_regex.as_ref()?;
+        let matched = pattern.is_match(name);
+        let reason = if matched {
+            format!("name matches /{}/", pattern)
+        } else {
+            format!("name doesn't match /{}/", pattern)
+        };
+        Some(Check::new(matched, reason))
+    }
+}
+
+/// Include and exclude criteria for the repositories of an org survey.
+#[derive(Debug, Clone, Default)]
+pub struct RepoFilter {
+    /// Criteria a repository must all meet
+    pub include: RepoCriteria,
+    /// Criteria any of which excludes a repository
+    pub exclude: RepoCriteria,
+}
+
+/// Whether a repository is surveyed, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub included: bool,
+    pub reason: String,
+}
+
+impl Selection {
+    /// A repository that is surveyed.
+    pub fn included(reason: impl Into<String>) -> Self {
+        Self {
+            included: true,
+            reason: reason.into(),
+        }
+    }
+
+    /// A repository that isn't surveyed.
+    pub fn excluded(reason: impl Into<String>) -> Self {
+        Self {
+            included: false,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl RepoFilter {
+    /// Decide by name alone whether a repository is excluded, so that
+    /// repositories named explicitly need not be fetched to be excluded.
+    ///
+    /// `None` when the repository's metadata decides.
+    pub fn exclude_by_name(&self, name: &str) -> Option<Selection> {
+        if let Some(missed) = self.include.check_name(name).filter(|check| !check.matched) {
+            return Some(Selection::excluded(missed.reason));
+        }
+        self.exclude
+            .check_name(name)
+            .filter(|check| check.matched)
+            .map(|met| Selection::excluded(met.reason))
+    }
+
+    /// Decide whether a repository is surveyed, as of `now`.
+    ///
+    /// The reason is the first include criterion the repository misses or
+    /// the first exclude criterion it meets; for a surveyed repository it
+    /// lists the include criteria it meets.
+    pub fn select(&self, repo: &RepoInfo, now: DateTime<Utc>) -> Selection {
+        let include = self.include.check(repo, now);
+        if let Some(missed) = include.iter().find(|check| !check.matched) {
+            return Selection::excluded(missed.reason.clone());
+        }
+
+        let exclude = self.exclude.check(repo, now);
+        if let Some(met) = exclude.into_iter().find(|check| check.matched) {
+            return Selection::excluded(met.reason);
+        }
+
+        if include.is_empty() {
+            Selection::included("no filter excludes it")
+        } else {
+            Selection::included(join(include.iter().map(|check| check.reason.as_str())))
+        }
+    }
+}
+
+fn contains_ignore_case(values: &[String], value: &str) -> bool {
+    values.iter().any(|v| v.eq_ignore_ascii_case(value))
+}
+
+fn join<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    values.collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::SourceKind;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap()
+    }
+
+    fn repo(name: &str) -> RepoInfo {
+        RepoInfo {
+            full_name: format!("acme/{}", name),
+            name: name.to_string(),
+            owner: "acme".to_string(),
+            clone_url: String::new(),
+            default_branch: "main".to_string(),
+            language: Some("TypeScript".to_string()),
+            archived: false,
+            fork: false,
+            topics: vec!["payments".to_string()],
+            pushed_at: Some(Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap()),
+            visibility: Some(Visibility::Private),
+            source: SourceKind::GitHub,
+        }
+    }
+
+    #[test]
+    fn test_select_without_filters() {
+        let selection = RepoFilter::default().select(&repo("billing"), now());
+        assert_eq!(selection, Selection::included("no filter excludes it"));
+    }
+
+    #[test]
+    fn test_include_criteria_must_all_match() {
+        let filter = RepoFilter {
+            include: RepoCriteria {
+                topics: vec!["Payments".to_string(), "billing".to_string()],
+                languages: vec!["typescript".to_string()],
+                pushed_within_days: Some(90),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            filter.select(&repo("billing"), now()),
+            Selection::included(
+                "language TypeScript, topic 'payments', pushed in the last 90 days"
+            )
+        );
+
+        let mut stale = repo("ledger");
+        stale.pushed_at = Some(Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap());
+        assert_eq!(
+            filter.select(&stale, now()),
+            Selection::excluded("last pushed 2025-01-02")
+        );
+
+        let mut python = repo("reports");
+        python.language = Some("Python".to_string());
+        assert_eq!(
+            filter.select(&python, now()),
+            Selection::excluded("language Python isn't one of typescript")
+        );
+
+        let mut untagged = repo("gateway");
+        untagged.topics.clear();
+        assert_eq!(
+            filter.select(&untagged, now()),
+            Selection::excluded("no topic of Payments, billing")
+        );
+    }
+
+    #[test]
+    fn test_any_exclude_criterion_excludes() {
+        let filter = RepoFilter {
+            exclude: RepoCriteria {
+                archived: Some(true),
+                fork: Some(true),
+                name_regex: Some(Regex::new("-legacy$").unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(filter.select(&repo("billing"), now()).included);
+
+        let mut archived = repo("billing");
+        archived.archived = true;
+        assert_eq!(
+            filter.select(&archived, now()),
+            Selection::excluded("archived")
+        );
+
+        assert_eq!(
+            filter.select(&repo("billing-legacy"), now()),
+            Selection::excluded("name matches /-legacy$/")
+        );
+    }
+
+    #[test]
+    fn test_exclude_by_name() {
+        let filter = RepoFilter {
+            include: RepoCriteria {
+                archived: Some(false),
+                name_regex: Some(Regex::new("^svc-").unwrap()),
+                ..Default::default()
+            },
+            exclude: RepoCriteria {
+                name_regex: Some(Regex::new("-legacy$").unwrap()),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            filter.exclude_by_name("billing"),
+            Some(Selection::excluded("name doesn't match /^svc-/"))
+        );
+        assert_eq!(
+            filter.exclude_by_name("svc-billing-legacy"),
+            Some(Selection::excluded("name matches /-legacy$/"))
+        );
+        // Whether it is archived needs its metadata
+        assert_eq!(filter.exclude_by_name("svc-billing"), None);
+        assert_eq!(RepoFilter::default().exclude_by_name("billing"), None);
+    }
+
+    #[test]
+    fn test_unknown_metadata_misses_include_criteria() {
+        let filter = RepoFilter {
+            include: RepoCriteria {
+                visibility: vec![Visibility::Public, Visibility::Internal],
+                ..Default::default()
+            },
+            exclude: RepoCriteria {
+                pushed_within_days: Some(30),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            filter.select(&repo("billing"), now()),
+            Selection::excluded("private isn't one of public, internal")
+        );
+
+        let mut unknown = repo("billing");
+        unknown.visibility = None;
+        unknown.pushed_at = None;
+        assert_eq!(
+            filter.select(&unknown, now()),
+            Selection::excluded("unknown visibility")
+        );
+
+        // An unknown push date doesn't meet an exclude criterion either
+        unknown.visibility = Some(Visibility::Public);
+        assert!(filter.select(&unknown, now()).included);
+    }
+}
@@ -0,0 +1,698 @@
+//! Terraform state ingestion.
+//!
+//! Source code only gives the logical names of resources. Their real
+//! identities (ARN, region, account) live in Terraform state. This module
+//! reads state offline, from either:
+//!
+//! - a local `terraform.tfstate` file (state format version 4), or
+//! - the output of `terraform show -json`
+//!
+//! and enriches the matching Database, Queue, CloudResource and Service nodes
+//! of a graph with `arn`, `aws_region`, `aws_account_id` and
+//! `terraform_address` attributes. Resources in state that no code accesses
+//! are flagged with `unused: true`, and resources with no node at all are
+//! reported.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use forge_survey::terraform_state::TerraformState;
+//!
+//! let state = TerraformState::load(Path::new("terraform.tfstate"))?;
+//! let result = state.apply_to_graph(&mut graph, &HashMap::new());
+//! println!("{} nodes enriched", result.enriched.len());
+//! ```
+
+use crate::parser::terraform::resource_node;
+use forge_graph::{AttributeValue, EdgeType, ForgeGraph, NodeId, NodeType};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors that can occur while reading Terraform state.
+#[derive(Debug, Error)]
+pub enum TerraformStateError {
+    /// IO error reading the state file
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file is not valid JSON
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The JSON is neither version 4 state nor `terraform show -json` output
+    #[error("Unsupported Terraform state format: {0}")]
+    UnsupportedFormat(String),
+}
+
+/// A managed resource instance recorded in Terraform state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateResource {
+    /// Resource address (e.g., "module.orders.aws_sqs_queue.jobs[0]").
+    pub address: String,
+
+    /// Resource type (e.g., "aws_sqs_queue").
+    pub resource_type: String,
+
+    /// Physical name, from the attribute the Terraform parser names nodes by.
+    pub name: Option<String>,
+
+    /// ARN, if the resource has one.
+    pub arn: Option<String>,
+
+    /// AWS region, from the ARN or the `region` attribute.
+    pub region: Option<String>,
+
+    /// AWS account ID, from the ARN.
+    pub account_id: Option<String>,
+}
+
+/// Outcome of applying Terraform state to a graph.
+#[derive(Debug, Default)]
+pub struct StateImportResult {
+    /// Nodes that received attributes from state
+    pub enriched: Vec<NodeId>,
+
+    /// Nodes of resources in state that no code accesses
+    pub unused: Vec<NodeId>,
+
+    /// Addresses of resources in state that match no node
+    pub unmatched: Vec<String>,
+}
+
+/// Resources read from one or more Terraform state files.
+#[derive(Debug, Clone, Default)]
+pub struct TerraformState {
+    resources: Vec<StateResource>,
+}
+
+impl TerraformState {
+    /// Load state from a `terraform.tfstate` or `terraform show -json` file.
+    pub fn load(path: &Path) -> Result<Self, TerraformStateError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_json(&content)
+    }
+
+    /// Parse state from JSON in either supported format.
+    pub fn from_json(content: &str) -> Result<Self, TerraformStateError> {
+        let json: Value = serde_json::from_str(content)?;
+        let mut state = Self::default();
+
+        if let Some(root) = json.get("values").and_then(|v| v.get("root_module")) {
+            // `terraform show -json`
+            state.read_module(root);
+        } else if json.get("version").and_then(|v| v.as_u64()) == Some(4) {
+            state.read_v4_resources(&json);
+        } else if json.get("format_version").is_some() {
+            // `terraform show -json` of an empty state has no values
+        } else {
+            let version = json
+                .get("version")
+                .map(|v| format!("state version {}", v))
+                .unwrap_or_else(|| "no state version".to_string());
+            return Err(TerraformStateError::UnsupportedFormat(version));
+        }
+
+        Ok(state)
+    }
+
+    /// Add the resources of another state (e.g., another workspace).
+    pub fn merge(&mut self, other: TerraformState) {
+        self.resources.extend(other.resources);
+    }
+
+    /// The managed AWS resources in state that become graph nodes.
+    pub fn resources(&self) -> &[StateResource] {
+        &self.resources
+    }
+
+    /// Enrich matching nodes of `graph` with their identities from state.
+    ///
+    /// A node matches a state resource when it has the same `arn` attribute
+    /// or, failing that, the same name. `account_environments` maps AWS
+    /// account IDs to environment names (from `forge.yaml`), so nodes
+    /// without an environment get the one their account belongs to.
+    pub fn apply_to_graph(
+        &self,
+        graph: &mut ForgeGraph,
+        account_environments: &HashMap<String, String>,
+    ) -> StateImportResult {
+        let mut result = StateImportResult::default();
+
+        for resource in &self.resources {
+            let Some(node_id) = find_node(graph, resource) else {
+                result.unmatched.push(resource.address.clone());
+                continue;
+            };
+            // Services run on their own; only resources wait to be accessed.
+            let unused =
+                node_id.node_type() != NodeType::Service && !is_accessed_by_code(graph, &node_id);
+
+            let Some(node) = graph.get_node_mut(&node_id) else {
+                continue;
+            };
+            let mut set = |key: &str, value: &Option<String>| {
+                if let Some(value) = value {
+                    node.attributes
+                        .insert(key.to_string(), AttributeValue::String(value.clone()));
+                }
+            };
+            set("arn", &resource.arn);
+            set("aws_region", &resource.region);
+            set("aws_account_id", &resource.account_id);
+            set("terraform_address", &Some(resource.address.clone()));
+
+            if !node.attributes.contains_key("environment") {
+                if let Some(environment) = resource
+                    .account_id
+                    .as_ref()
+                    .and_then(|account| account_environments.get(account))
+                {
+                    node.attributes.insert(
+                        "environment".to_string(),
+                        AttributeValue::String(environment.clone()),
+                    );
+                }
+            }
+
+            if unused {
+                node.attributes
+                    .insert("unused".to_string(), AttributeValue::Boolean(true));
+            } else {
+                node.attributes.remove("unused");
+            }
+            node.metadata.updated_at = chrono::Utc::now();
+
+            if !result.enriched.contains(&node_id) {
+                if unused {
+                    result.unused.push(node_id.clone());
+                }
+                result.enriched.push(node_id);
+            }
+        }
+
+        result
+    }
+
+    /// Read the resources of a `terraform show -json` module and its children.
+    fn read_module(&mut self, module: &Value) {
+        for resource in array(module, "resources") {
+            if resource.get("mode").and_then(|m| m.as_str()) != Some("managed") {
+                continue;
+            }
+            let (Some(address), Some(resource_type), Some(values)) = (
+                resource.get("address").and_then(|a| a.as_str()),
+                resource.get("type").and_then(|t| t.as_str()),
+                resource.get("values"),
+            ) else {
+                continue;
+            };
+            self.add(address.to_string(), resource_type, values);
+        }
+
+        for child in array(module, "child_modules") {
+            self.read_module(child);
+        }
+    }
+
+    /// Read the resources of version 4 state.
+    fn read_v4_resources(&mut self, state: &Value) {
+        for resource in array(state, "resources") {
+            if resource.get("mode").and_then(|m| m.as_str()) != Some("managed") {
+                continue;
+            }
+            let (Some(resource_type), Some(name)) = (
+                resource.get("type").and_then(|t| t.as_str()),
+                resource.get("name").and_then(|n| n.as_str()),
+            ) else {
+                continue;
+            };
+            let base = match resource.get("module").and_then(|m| m.as_str()) {
+                Some(module) => format!("{}.{}.{}", module, resource_type, name),
+                None => format!("{}.{}", resource_type, name),
+            };
+
+            for instance in array(resource, "instances") {
+                let address = match instance.get("index_key") {
+                    Some(Value::String(key)) => format!("{}[\"{}\"]", base, key),
+                    Some(Value::Number(index)) => format!("{}[{}]", base, index),
+                    _ => base.clone(),
+                };
+                if let Some(attributes) = instance.get("attributes") {
+                    self.add(address, resource_type, attributes);
+                }
+            }
+        }
+    }
+
+    fn add(&mut self, address: String, resource_type: &str, attributes: &Value) {
+        let Some((_, name_keys)) = resource_node(resource_type) else {
+            return;
+        };
+        let string = |key: &str| {
+            attributes
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+
+        let arn = string("arn");
+        let (arn_region, arn_account) = arn.as_deref().map(arn_location).unwrap_or_default();
+        self.resources.push(StateResource {
+            address,
+            resource_type: resource_type.to_string(),
+            name: name_keys.iter().find_map(|key| string(key)),
+            region: arn_region.or_else(|| string("region")),
+            account_id: arn_account,
+            arn,
+        });
+    }
+}
+
+/// The region and account of an ARN (`arn:aws:sqs:us-east-1:123456789012:jobs`).
+///
+/// Global resources such as S3 buckets leave both empty.
+fn arn_location(arn: &str) -> (Option<String>, Option<String>) {
+    let mut parts = arn.splitn(6, ':').skip(3);
+    let non_empty = |part: Option<&str>| part.filter(|p| !p.is_empty()).map(|p| p.to_string());
+    (non_empty(parts.next()), non_empty(parts.next()))
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> impl Iterator<Item = &'v Value> {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+}
+
+/// The resource node a state resource corresponds to: by ARN first, then
+/// by name. Only nodes of the type the resource type becomes are matched.
+fn find_node(graph: &ForgeGraph, resource: &StateResource) -> Option<NodeId> {
+    let (node_type, _) = resource_node(&resource.resource_type)?;
+    let candidates = || graph.nodes_by_type(node_type);
+
+    if let Some(arn) = &resource.arn {
+        let by_arn = candidates().find(|node| {
+            matches!(node.attributes.get("arn"), Some(AttributeValue::String(a)) if a == arn)
+        });
+        if let Some(node) = by_arn {
+            return Some(node.id.clone());
+        }
+    }
+
+    let name = resource.name.as_deref()?;
+    candidates()
+        .find(|node| node.display_name == name || node.id.name() == name)
+        .map(|node| node.id.clone())
+}
+
+/// Whether any service accesses a resource in code.
+///
+/// Parsing an IaC definition also links the repository's service to the
+/// resource. Such an edge has no declared reason and its only evidence is
+/// the file defining the resource or another IaC file, so it does not count.
+fn is_accessed_by_code(graph: &ForgeGraph, resource_id: &NodeId) -> bool {
+    let declared_in = graph
+        .get_node(resource_id)
+        .and_then(|node| node.metadata.source_file.clone());
+
+    graph.edges_to(resource_id).into_iter().any(|edge| {
+        let is_access = matches!(
+            edge.edge_type,
+            EdgeType::Calls
+                | EdgeType::Reads
+                | EdgeType::Writes
+                | EdgeType::Publishes
+                | EdgeType::Subscribes
+                | EdgeType::Uses
+                | EdgeType::ReadsShared
+                | EdgeType::WritesShared
+        );
+        let is_declaration = edge.metadata.reason.is_none()
+            && edge.metadata.evidence.iter().all(|evidence| {
+                let file = evidence
+                    .rsplit_once(':')
+                    .map(|(file, _)| file)
+                    .unwrap_or(evidence);
+                declared_in.as_deref() == Some(file) || is_iac_file(file)
+            });
+        is_access && !is_declaration
+    })
+}
+
+fn is_iac_file(file: &str) -> bool {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    matches!(extension, "tf" | "tfvars" | "yaml" | "yml" | "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_graph::{DiscoverySource, Edge, NodeBuilder};
+
+    const STATE_V4: &str = r#"{
+  "version": 4,
+  "terraform_version": "1.7.5",
+  "resources": [
+    {
+      "mode": "managed",
+      "type": "aws_sqs_queue",
+      "name": "jobs",
+      "provider": "provider[\"registry.terraform.io/hashicorp/aws\"]",
+      "instances": [
+        {
+          "attributes": {
+            "arn": "arn:aws:sqs:eu-west-1:123456789012:prod-jobs",
+            "name": "prod-jobs",
+            "id": "https://sqs.eu-west-1.amazonaws.com/123456789012/prod-jobs"
+          }
+        }
+      ]
+    },
+    {
+      "module": "module.storage",
+      "mode": "managed",
+      "type": "aws_s3_bucket",
+      "name": "assets",
+      "instances": [
+        {
+          "index_key": "eu",
+          "attributes": { "arn": "arn:aws:s3:::acme-assets", "bucket": "acme-assets", "region": "eu-west-1" }
+        }
+      ]
+    },
+    {
+      "mode": "data",
+      "type": "aws_sqs_queue",
+      "name": "external",
+      "instances": [{ "attributes": { "name": "external" } }]
+    },
+    {
+      "mode": "managed",
+      "type": "aws_iam_role",
+      "name": "worker",
+      "instances": [{ "attributes": { "name": "worker" } }]
+    }
+  ]
+}"#;
+
+    const SHOW_JSON: &str = r#"{
+  "format_version": "1.0",
+  "values": {
+    "root_module": {
+      "resources": [
+        {
+          "address": "aws_dynamodb_table.orders",
+          "mode": "managed",
+          "type": "aws_dynamodb_table",
+          "name": "orders",
+          "values": { "arn": "arn:aws:dynamodb:us-east-1:210987654321:table/orders", "name": "orders" }
+        }
+      ],
+      "child_modules": [
+        {
+          "address": "module.cache",
+          "resources": [
+            {
+              "address": "module.cache.aws_elasticache_replication_group.main",
+              "mode": "managed",
+              "type": "aws_elasticache_replication_group",
+              "name": "main",
+              "values": { "arn": "arn:aws:elasticache:us-east-1:210987654321:replicationgroup:sessions", "replication_group_id": "sessions" }
+            }
+          ]
+        }
+      ]
+    }
+  }
+}"#;
+
+    fn add_node(graph: &mut ForgeGraph, node_type: NodeType, name: &str, file: &str) -> NodeId {
+        let id = NodeId::new(node_type, "test-org/infra", name).unwrap();
+        let node = NodeBuilder::new()
+            .id(id.clone())
+            .node_type(node_type)
+            .display_name(name)
+            .source(DiscoverySource::TerraformParser)
+            .source_file(file)
+            .build()
+            .unwrap();
+        graph.upsert_node(node);
+        id
+    }
+
+    fn add_edge(
+        graph: &mut ForgeGraph,
+        from: &NodeId,
+        to: &NodeId,
+        edge_type: EdgeType,
+        evidence: &str,
+    ) {
+        let mut edge = Edge::new(from.clone(), to.clone(), edge_type).unwrap();
+        edge.metadata.evidence.push(evidence.to_string());
+        graph.upsert_edge(edge).unwrap();
+    }
+
+    #[test]
+    fn test_parse_state_v4() {
+        let state = TerraformState::from_json(STATE_V4).unwrap();
+        assert_eq!(
+            state.resources(),
+            &[
+                StateResource {
+                    address: "aws_sqs_queue.jobs".to_string(),
+                    resource_type: "aws_sqs_queue".to_string(),
+                    name: Some("prod-jobs".to_string()),
+                    arn: Some("arn:aws:sqs:eu-west-1:123456789012:prod-jobs".to_string()),
+                    region: Some("eu-west-1".to_string()),
+                    account_id: Some("123456789012".to_string()),
+                },
+                StateResource {
+                    address: "module.storage.aws_s3_bucket.assets[\"eu\"]".to_string(),
+                    resource_type: "aws_s3_bucket".to_string(),
+                    name: Some("acme-assets".to_string()),
+                    arn: Some("arn:aws:s3:::acme-assets".to_string()),
+                    region: Some("eu-west-1".to_string()),
+                    account_id: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_show_json() {
+        let state = TerraformState::from_json(SHOW_JSON).unwrap();
+        let names: Vec<_> = state
+            .resources()
+            .iter()
+            .map(|r| (r.address.as_str(), r.name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("aws_dynamodb_table.orders", Some("orders")),
+                (
+                    "module.cache.aws_elasticache_replication_group.main",
+                    Some("sessions")
+                ),
+            ]
+        );
+        assert!(matches!(
+            TerraformState::from_json(r#"{"version": 3, "modules": []}"#),
+            Err(TerraformStateError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_to_graph() {
+        let mut graph = ForgeGraph::new();
+        let service = NodeId::new(NodeType::Service, "test-org/infra", "worker").unwrap();
+        graph.upsert_node(
+            NodeBuilder::new()
+                .id(service.clone())
+                .node_type(NodeType::Service)
+                .display_name("worker")
+                .source(DiscoverySource::TerraformParser)
+                .build()
+                .unwrap(),
+        );
+        let jobs = add_node(&mut graph, NodeType::Queue, "prod-jobs", "infra/main.tf");
+        let assets = add_node(
+            &mut graph,
+            NodeType::CloudResource,
+            "acme-assets",
+            "infra/main.tf",
+        );
+
+        // Declared in Terraform, and published to from code
+        add_edge(
+            &mut graph,
+            &service,
+            &jobs,
+            EdgeType::Publishes,
+            "infra/main.tf:3",
+        );
+        add_edge(
+            &mut graph,
+            &service,
+            &jobs,
+            EdgeType::Publishes,
+            "src/worker.py:12",
+        );
+        // Only declared
+        add_edge(
+            &mut graph,
+            &service,
+            &assets,
+            EdgeType::Uses,
+            "infra/main.tf:9",
+        );
+
+        let mut state = TerraformState::from_json(STATE_V4).unwrap();
+        state.merge(TerraformState::from_json(SHOW_JSON).unwrap());
+        let environments = HashMap::from([("123456789012".to_string(), "production".to_string())]);
+        let result = state.apply_to_graph(&mut graph, &environments);
+
+        assert_eq!(result.enriched, vec![jobs.clone(), assets.clone()]);
+        assert_eq!(result.unused, vec![assets.clone()]);
+        assert_eq!(
+            result.unmatched,
+            vec![
+                "aws_dynamodb_table.orders",
+                "module.cache.aws_elasticache_replication_group.main"
+            ]
+        );
+
+        let jobs_node = graph.get_node(&jobs).unwrap();
+        assert_eq!(
+            jobs_node.attributes.get("arn"),
+            Some(&AttributeValue::from(
+                "arn:aws:sqs:eu-west-1:123456789012:prod-jobs"
+            ))
+        );
+        assert_eq!(
+            jobs_node.attributes.get("aws_region"),
+            Some(&AttributeValue::from("eu-west-1"))
+        );
+        assert_eq!(
+            jobs_node.attributes.get("aws_account_id"),
+            Some(&AttributeValue::from("123456789012"))
+        );
+        assert_eq!(
+            jobs_node.attributes.get("environment"),
+            Some(&AttributeValue::from("production"))
+        );
+        assert!(!jobs_node.attributes.contains_key("unused"));
+
+        let assets_node = graph.get_node(&assets).unwrap();
+        assert_eq!(
+            assets_node.attributes.get("unused"),
+            Some(&AttributeValue::Boolean(true))
+        );
+        assert!(!assets_node.attributes.contains_key("aws_account_id"));
+    }
+
+    #[test]
+    fn test_same_name_matches_node_of_resource_type() {
+        let mut graph = ForgeGraph::new();
+        let table = add_node(&mut graph, NodeType::Database, "orders", "infra/main.tf");
+        let queue = add_node(&mut graph, NodeType::Queue, "orders", "infra/main.tf");
+
+        let state = TerraformState::from_json(
+            r#"{
+  "version": 4,
+  "resources": [
+    {
+      "mode": "managed",
+      "type": "aws_sqs_queue",
+      "name": "orders",
+      "instances": [
+        { "attributes": { "arn": "arn:aws:sqs:us-east-1:123456789012:orders", "name": "orders" } }
+      ]
+    },
+    {
+      "mode": "managed",
+      "type": "aws_dynamodb_table",
+      "name": "orders",
+      "instances": [
+        { "attributes": { "arn": "arn:aws:dynamodb:us-east-1:123456789012:table/orders", "name": "orders" } }
+      ]
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+        let result = state.apply_to_graph(&mut graph, &HashMap::new());
+
+        assert_eq!(result.enriched, vec![queue.clone(), table.clone()]);
+        assert_eq!(
+            graph.get_node(&queue).unwrap().attributes.get("arn"),
+            Some(&AttributeValue::from(
+                "arn:aws:sqs:us-east-1:123456789012:orders"
+            ))
+        );
+        assert_eq!(
+            graph.get_node(&table).unwrap().attributes.get("arn"),
+            Some(&AttributeValue::from(
+                "arn:aws:dynamodb:us-east-1:123456789012:table/orders"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_services_enriched_but_never_unused() {
+        let mut graph = ForgeGraph::new();
+        let api = add_node(&mut graph, NodeType::Service, "orders-api", "infra/ecs.tf");
+        let flow = add_node(&mut graph, NodeType::Service, "checkout", "infra/sfn.tf");
+
+        let state = TerraformState::from_json(
+            r#"{
+  "version": 4,
+  "resources": [
+    {
+      "mode": "managed",
+      "type": "aws_ecs_service",
+      "name": "api",
+      "instances": [
+        { "attributes": { "id": "arn:aws:ecs:us-east-1:123456789012:service/main/orders-api", "name": "orders-api" } }
+      ]
+    },
+    {
+      "mode": "managed",
+      "type": "aws_sfn_state_machine",
+      "name": "checkout",
+      "instances": [
+        { "attributes": { "arn": "arn:aws:states:us-east-1:123456789012:stateMachine:checkout", "name": "checkout" } }
+      ]
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+        let result = state.apply_to_graph(&mut graph, &HashMap::new());
+
+        assert_eq!(result.enriched, vec![api.clone(), flow.clone()]);
+        assert!(result.unused.is_empty());
+        assert_eq!(
+            graph
+                .get_node(&flow)
+                .unwrap()
+                .attributes
+                .get("aws_account_id"),
+            Some(&AttributeValue::from("123456789012"))
+        );
+        assert!(
+            !graph
+                .get_node(&api)
+                .unwrap()
+                .attributes
+                .contains_key("unused")
+        );
+    }
+}
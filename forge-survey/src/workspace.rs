@@ -0,0 +1,759 @@
+//! Workspace (monorepo) package detection.
+//!
+//! A repository may hold many deployables. Each package found below the
+//! repository root becomes a service of its own, and discoveries are
+//! attributed to the package whose directory contains their source file
+//! (see [`GraphBuilder::process_package_discoveries`]).
+//!
+//! | Layout | Declared by | Service name |
+//! |--------|-------------|--------------|
+//! | SAM functions | `CodeUri` of an `AWS::Serverless::Function` | `FunctionName`, or the logical ID |
+//! | npm / yarn workspaces | `workspaces` in the root `package.json` | `name` in the package's `package.json` |
+//! | pnpm workspaces | `packages` in `pnpm-workspace.yaml` | `name` in the package's `package.json` |
+//! | Nx projects | `project.json` | `name`, or the directory name |
+//! | Cargo workspaces | `members` of `[workspace]` in the root `Cargo.toml` | `name` in the member's `[package]` |
+//! | Python projects | `pyproject.toml` below the root | the project name |
+//!
+//! Turborepo builds on the package manager's workspaces, so its packages are
+//! found through those. A directory claimed by several layouts keeps the
+//! first one in the table: a SAM function is deployed under its function
+//! name, whatever its package is called. A directory shared by several SAM
+//! functions is not a package, since its code cannot be attributed to one.
+//!
+//! [`PackageOverride`]s (from `forge.yaml`) rename packages, declare
+//! packages no layout finds, and exclude directories.
+//!
+//! [`GraphBuilder::process_package_discoveries`]: crate::GraphBuilder::process_package_discoveries
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use walkdir::WalkDir;
+
+use crate::parser::{
+    CloudFormationParser, JavaScriptParser, ParserRegistry, PythonParser, ServiceDiscovery,
+    is_ignored_dir,
+};
+
+/// How a package was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceLayout {
+    /// The code directory of a SAM function.
+    Sam,
+    /// An npm or yarn workspace.
+    NpmWorkspaces,
+    /// A pnpm workspace.
+    PnpmWorkspaces,
+    /// An Nx project.
+    Nx,
+    /// A Cargo workspace member.
+    Cargo,
+    /// A Python project with its own `pyproject.toml`.
+    Python,
+    /// Declared in configuration.
+    Override,
+}
+
+impl fmt::Display for WorkspaceLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Sam => "SAM function",
+            Self::NpmWorkspaces => "npm workspace",
+            Self::PnpmWorkspaces => "pnpm workspace",
+            Self::Nx => "Nx project",
+            Self::Cargo => "Cargo workspace",
+            Self::Python => "Python project",
+            Self::Override => "configured",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A package below the repository root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspacePackage {
+    /// Package directory, relative to the repository root.
+    pub path: PathBuf,
+
+    /// How the package was found.
+    pub layout: WorkspaceLayout,
+
+    /// The service the package is surveyed as.
+    pub service: ServiceDiscovery,
+}
+
+/// A per-directory override of package detection.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageOverride {
+    /// Directory, relative to the repository root.
+    pub path: PathBuf,
+
+    /// Service name of the package at `path`. A directory no layout
+    /// detects becomes a package when it is given a name.
+    pub name: Option<String>,
+
+    /// No directory at or below `path` is a package; its files belong to
+    /// the enclosing package.
+    pub exclude: bool,
+}
+
+/// Detect the packages of a repository, sorted by path.
+///
+/// The repository root itself is never a package: it is the repository's
+/// own service, which keeps the discoveries outside every package.
+pub fn detect_packages(
+    registry: &ParserRegistry,
+    repo_path: &Path,
+    overrides: &[PackageOverride],
+) -> Vec<WorkspacePackage> {
+    let tree = RepoTree::scan(repo_path);
+    let js = registry.get("javascript");
+    let js = js
+        .as_ref()
+        .and_then(|p| p.as_any().downcast_ref::<JavaScriptParser>());
+    let python = registry.get("python");
+    let python = python
+        .as_ref()
+        .and_then(|p| p.as_any().downcast_ref::<PythonParser>());
+    let cloudformation = registry.get("cloudformation");
+    let cloudformation = cloudformation
+        .as_ref()
+        .and_then(|p| p.as_any().downcast_ref::<CloudFormationParser>());
+
+    let mut found = Vec::new();
+    if let Some(cloudformation) = cloudformation {
+        found.extend(sam_packages(&tree, cloudformation));
+    }
+    if let Some(js) = js {
+        found.extend(npm_packages(&tree, js));
+        found.extend(nx_packages(&tree, js));
+    }
+    found.extend(cargo_packages(&tree));
+    if let Some(python) = python {
+        found.extend(python_packages(&tree, python));
+    }
+
+    let mut packages: BTreeMap<PathBuf, WorkspacePackage> = BTreeMap::new();
+    for package in found {
+        if package.path.as_os_str().is_empty() {
+            continue;
+        }
+        packages.entry(package.path.clone()).or_insert(package);
+    }
+
+    for package_override in overrides {
+        let path = normalize(&package_override.path);
+        if package_override.exclude {
+            packages.retain(|package_path, _| !package_path.starts_with(&path));
+            continue;
+        }
+        let Some(name) = &package_override.name else {
+            continue;
+        };
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let package = packages.entry(path.clone()).or_insert_with(|| {
+            let dir = repo_path.join(&path);
+            let service = js
+                .and_then(|js| js.parse_package_json(&dir))
+                .or_else(|| python.and_then(|py| py.parse_project_config(&dir)))
+                .unwrap_or_else(|| ServiceDiscovery {
+                    language: "unknown".to_string(),
+                    entry_point: "unknown".to_string(),
+                    source_file: dir.to_string_lossy().to_string(),
+                    ..Default::default()
+                });
+            WorkspacePackage {
+                path: path.clone(),
+                layout: WorkspaceLayout::Override,
+                service,
+            }
+        });
+        package.service.name = name.clone();
+    }
+
+    packages.into_values().collect()
+}
+
+/// The directories and files of a repository, outside ignored directories.
+struct RepoTree<'a> {
+    root: &'a Path,
+    /// Directories, relative to the root
+    dirs: Vec<PathBuf>,
+    /// Files, relative to the root
+    files: Vec<PathBuf>,
+}
+
+impl<'a> RepoTree<'a> {
+    fn scan(root: &'a Path) -> Self {
+        let mut tree = Self {
+            root,
+            dirs: Vec::new(),
+            files: Vec::new(),
+        };
+        let entries = WalkDir::new(root)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| {
+                e.depth() == 0 || !is_ignored_dir(e.file_name().to_str().unwrap_or(""))
+            })
+            .filter_map(|e| e.ok());
+        for entry in entries {
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                continue;
+            };
+            if entry.file_type().is_dir() {
+                tree.dirs.push(relative.to_path_buf());
+            } else {
+                tree.files.push(relative.to_path_buf());
+            }
+        }
+        tree
+    }
+
+    /// Files with the given name, in any directory.
+    fn files_named<'t>(&'t self, name: &'t str) -> impl Iterator<Item = &'t PathBuf> {
+        self.files
+            .iter()
+            .filter(move |f| f.file_name().and_then(|n| n.to_str()) == Some(name))
+    }
+
+    /// Directories matching workspace globs (`packages/*`, `apps/**`).
+    /// Patterns starting with `!` exclude directories.
+    fn matching_dirs(&self, patterns: &[String]) -> Vec<&PathBuf> {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        let compile = |pattern: &str| {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            Pattern::new(pattern).ok()
+        };
+        let (excluded, included): (Vec<_>, Vec<_>) =
+            patterns.iter().partition(|p| p.starts_with('!'));
+        let included: Vec<Pattern> = included.iter().filter_map(|p| compile(p)).collect();
+        let excluded: Vec<Pattern> = excluded.iter().filter_map(|p| compile(&p[1..])).collect();
+
+        self.dirs
+            .iter()
+            .filter(|dir| {
+                let Some(dir) = dir.to_str() else {
+                    return false;
+                };
+                included.iter().any(|p| p.matches_with(dir, options))
+                    && !excluded.iter().any(|p| p.matches_with(dir, options))
+            })
+            .collect()
+    }
+}
+
+/// The code directories of SAM functions.
+fn sam_packages(tree: &RepoTree, cloudformation: &CloudFormationParser) -> Vec<WorkspacePackage> {
+    let mut functions: BTreeMap<PathBuf, Vec<ServiceDiscovery>> = BTreeMap::new();
+    for file in &tree.files {
+        let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if !matches!(extension, "yaml" | "yml" | "json") {
+            continue;
+        }
+        let path = tree.root.join(file);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for (service, dir) in cloudformation.function_code_dirs(&path, &content) {
+            let Ok(relative) = dir.strip_prefix(normalize(tree.root)) else {
+                continue;
+            };
+            if tree.dirs.iter().any(|d| d == relative) {
+                functions
+                    .entry(relative.to_path_buf())
+                    .or_default()
+                    .push(service);
+            }
+        }
+    }
+
+    functions
+        .into_iter()
+        .filter_map(|(path, mut services)| {
+            if services.len() > 1 {
+                tracing::debug!(
+                    "{} is shared by {} SAM functions; not a package",
+                    path.display(),
+                    services.len()
+                );
+                return None;
+            }
+            Some(WorkspacePackage {
+                path,
+                layout: WorkspaceLayout::Sam,
+                service: services.pop()?,
+            })
+        })
+        .collect()
+}
+
+/// npm, yarn and pnpm workspace packages.
+fn npm_packages(tree: &RepoTree, js: &JavaScriptParser) -> Vec<WorkspacePackage> {
+    let mut packages = Vec::new();
+
+    let npm_patterns = std::fs::read_to_string(tree.root.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|package| {
+            let workspaces = package.get("workspaces");
+            // Yarn also accepts `{ "packages": [...] }`
+            let list = workspaces.and_then(|w| w.get("packages")).or(workspaces);
+            string_list(list.and_then(|l| l.as_array()))
+        })
+        .unwrap_or_default();
+    let pnpm_patterns = std::fs::read_to_string(tree.root.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str::<serde_yaml::Value>(&content).ok())
+        .map(|workspace| {
+            workspace
+                .get("packages")
+                .and_then(|p| p.as_sequence())
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.as_str().map(|s| s.to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    for (patterns, layout) in [
+        (npm_patterns, WorkspaceLayout::NpmWorkspaces),
+        (pnpm_patterns, WorkspaceLayout::PnpmWorkspaces),
+    ] {
+        for dir in tree.matching_dirs(&patterns) {
+            if let Some(service) = js.parse_package_json(&tree.root.join(dir)) {
+                packages.push(WorkspacePackage {
+                    path: dir.clone(),
+                    layout,
+                    service,
+                });
+            }
+        }
+    }
+
+    packages
+}
+
+/// Nx projects, declared by `project.json`.
+fn nx_packages(tree: &RepoTree, js: &JavaScriptParser) -> Vec<WorkspacePackage> {
+    tree.files_named("project.json")
+        .filter_map(|file| {
+            let path = file.parent()?.to_path_buf();
+            let dir = tree.root.join(&path);
+            let project_path = tree.root.join(file);
+            let project: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&project_path).ok()?).ok()?;
+            let name = project
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(|n| n.to_string())
+                .or_else(|| Some(path.file_name()?.to_str()?.to_string()))?;
+
+            let mut service = js.parse_package_json(&dir).unwrap_or_else(|| {
+                let language = if dir.join("tsconfig.json").exists() {
+                    "typescript"
+                } else {
+                    "javascript"
+                };
+                ServiceDiscovery {
+                    language: language.to_string(),
+                    entry_point: "index.js".to_string(),
+                    source_file: project_path.to_string_lossy().to_string(),
+                    source_line: 1,
+                    ..Default::default()
+                }
+            });
+            service.name = name;
+            Some(WorkspacePackage {
+                path,
+                layout: WorkspaceLayout::Nx,
+                service,
+            })
+        })
+        .collect()
+}
+
+/// Members of a Cargo workspace.
+fn cargo_packages(tree: &RepoTree) -> Vec<WorkspacePackage> {
+    let Ok(manifest) = std::fs::read_to_string(tree.root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = toml_section(&manifest, "workspace") else {
+        return Vec::new();
+    };
+    let mut patterns = toml_string_array(workspace, "members");
+    patterns.extend(
+        toml_string_array(workspace, "exclude")
+            .into_iter()
+            .map(|p| format!("!{}", p)),
+    );
+
+    tree.matching_dirs(&patterns)
+        .into_iter()
+        .filter_map(|path| {
+            let dir = tree.root.join(path);
+            let manifest_path = dir.join("Cargo.toml");
+            let manifest = std::fs::read_to_string(&manifest_path).ok()?;
+            let name = toml_string(toml_section(&manifest, "package")?, "name")?;
+            let entry_point = if dir.join("src/main.rs").exists() {
+                "src/main.rs"
+            } else {
+                "src/lib.rs"
+            };
+            Some(WorkspacePackage {
+                path: path.clone(),
+                layout: WorkspaceLayout::Cargo,
+                service: ServiceDiscovery {
+                    name,
+                    language: "rust".to_string(),
+                    entry_point: entry_point.to_string(),
+                    source_file: manifest_path.to_string_lossy().to_string(),
+                    source_line: 1,
+                    ..Default::default()
+                },
+            })
+        })
+        .collect()
+}
+
+/// Python projects with their own `pyproject.toml`.
+fn python_packages(tree: &RepoTree, python: &PythonParser) -> Vec<WorkspacePackage> {
+    tree.files_named("pyproject.toml")
+        .filter_map(|file| {
+            let path = file.parent()?.to_path_buf();
+            let service = python.parse_project_config(&tree.root.join(&path))?;
+            Some(WorkspacePackage {
+                path,
+                layout: WorkspaceLayout::Python,
+                service,
+            })
+        })
+        .collect()
+}
+
+/// The strings of a JSON array.
+fn string_list(values: Option<&Vec<serde_json::Value>>) -> Vec<String> {
+    values
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+/// The body of a `[section]` of a TOML file.
+fn toml_section<'c>(content: &'c str, section: &str) -> Option<&'c str> {
+    let header = format!("[{}]", section);
+    let start = content
+        .match_indices(&header)
+        .map(|(i, _)| i)
+        .find(|&i| i == 0 || content[..i].ends_with('\n'))?
+        + header.len();
+    let body = &content[start..];
+    let end = body.find("\n[").map(|i| i + 1).unwrap_or(body.len());
+    Some(&body[..end])
+}
+
+/// A string value (`key = "value"`) of a TOML section.
+fn toml_string(section: &str, key: &str) -> Option<String> {
+    section.lines().find_map(|line| {
+        let (name, value) = line.split_once('=')?;
+        if name.trim() != key {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))?;
+        Some(value.to_string())
+    })
+}
+
+/// The strings of an array value (`key = ["a", "b"]`, possibly spanning
+/// several lines) of a TOML section.
+fn toml_string_array(section: &str, key: &str) -> Vec<String> {
+    let Some(start) = section.lines().position(|line| {
+        line.split_once('=')
+            .is_some_and(|(name, value)| name.trim() == key && value.trim_start().starts_with('['))
+    }) else {
+        return Vec::new();
+    };
+
+    let mut array = String::new();
+    for line in section.lines().skip(start) {
+        let line = line.split('#').next().unwrap_or("");
+        array.push_str(line);
+        if line.contains(']') {
+            break;
+        }
+    }
+    let Some((_, items)) = array.split_once('[') else {
+        return Vec::new();
+    };
+    let items = items.split(']').next().unwrap_or("");
+    items
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\''))
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_string())
+        .collect()
+}
+
+/// Remove `.` components and a trailing separator from a configured path.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_file(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn summary(packages: &[WorkspacePackage]) -> Vec<(String, WorkspaceLayout, String)> {
+        packages
+            .iter()
+            .map(|p| {
+                (
+                    p.path.to_string_lossy().to_string(),
+                    p.layout,
+                    p.service.name.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_npm_pnpm_and_nx_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(
+            root,
+            "package.json",
+            r#"{ "name": "platform", "private": true, "workspaces": { "packages": ["services/*", "!services/legacy"] } }"#,
+        );
+        write_file(
+            root,
+            "services/orders/package.json",
+            r#"{ "name": "@shop/orders" }"#,
+        );
+        write_file(
+            root,
+            "services/legacy/package.json",
+            r#"{ "name": "legacy" }"#,
+        );
+        write_file(
+            root,
+            "services/orders/src/deep/package.json",
+            r#"{ "name": "nested" }"#,
+        );
+        write_file(root, "pnpm-workspace.yaml", "packages:\n  - 'tools/**'\n");
+        write_file(root, "tools/cli/package.json", r#"{ "name": "shop-cli" }"#);
+        write_file(root, "apps/web/project.json", r#"{ "name": "storefront" }"#);
+        write_file(root, "apps/admin/project.json", "{}");
+        write_file(root, "apps/admin/tsconfig.json", "{}");
+        write_file(
+            root,
+            "node_modules/dep/project.json",
+            r#"{ "name": "dep" }"#,
+        );
+
+        let registry = ParserRegistry::new().unwrap();
+        let packages = detect_packages(&registry, root, &[]);
+        assert_eq!(
+            summary(&packages),
+            vec![
+                (
+                    "apps/admin".to_string(),
+                    WorkspaceLayout::Nx,
+                    "admin".to_string()
+                ),
+                (
+                    "apps/web".to_string(),
+                    WorkspaceLayout::Nx,
+                    "storefront".to_string()
+                ),
+                (
+                    "services/orders".to_string(),
+                    WorkspaceLayout::NpmWorkspaces,
+                    "@shop/orders".to_string()
+                ),
+                (
+                    "tools/cli".to_string(),
+                    WorkspaceLayout::PnpmWorkspaces,
+                    "shop-cli".to_string()
+                ),
+            ]
+        );
+        assert_eq!(packages[0].service.language, "typescript");
+    }
+
+    #[test]
+    fn test_cargo_python_and_sam_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\n    \"crates/*\", # services\n]\nexclude = [\"crates/scratch\"]\n\n[workspace.dependencies]\nserde = \"1\"\n",
+        );
+        write_file(
+            root,
+            "crates/ingest/Cargo.toml",
+            "[package]\nname = \"ingest\"\n",
+        );
+        write_file(root, "crates/ingest/src/main.rs", "fn main() {}");
+        write_file(
+            root,
+            "crates/scratch/Cargo.toml",
+            "[package]\nname = \"scratch\"\n",
+        );
+        write_file(
+            root,
+            "ml/ranker/pyproject.toml",
+            "[project]\nname = \"ranker\"\n",
+        );
+        write_file(
+            root,
+            "template.yaml",
+            r#"
+Transform: AWS::Serverless-2016-10-31
+Resources:
+  Payments:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: payments
+      CodeUri: ml/ranker/
+      Runtime: python3.12
+  Reports:
+    Type: AWS::Serverless::Function
+    Properties:
+      CodeUri: lambdas/shared
+  Exports:
+    Type: AWS::Serverless::Function
+    Properties:
+      CodeUri: lambdas/shared
+"#,
+        );
+        write_file(root, "lambdas/shared/app.py", "");
+
+        let registry = ParserRegistry::new().unwrap();
+        let packages = detect_packages(&registry, root, &[]);
+        assert_eq!(
+            summary(&packages),
+            vec![
+                (
+                    "crates/ingest".to_string(),
+                    WorkspaceLayout::Cargo,
+                    "ingest".to_string()
+                ),
+                (
+                    "ml/ranker".to_string(),
+                    WorkspaceLayout::Sam,
+                    "payments".to_string()
+                ),
+            ]
+        );
+        assert_eq!(packages[0].service.language, "rust");
+        assert_eq!(packages[0].service.entry_point, "src/main.rs");
+        assert_eq!(
+            packages[1]
+                .service
+                .deployment_metadata
+                .as_ref()
+                .map(|m| m.deployment_method.as_str()),
+            Some("sam")
+        );
+    }
+
+    #[test]
+    fn test_package_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_file(
+            root,
+            "services/orders/pyproject.toml",
+            "[project]\nname = \"orders\"\n",
+        );
+        write_file(
+            root,
+            "services/billing/pyproject.toml",
+            "[project]\nname = \"billing\"\n",
+        );
+        write_file(
+            root,
+            "tools/lint/pyproject.toml",
+            "[project]\nname = \"lint\"\n",
+        );
+        write_file(root, "jobs/nightly/run.sh", "");
+
+        let overrides = vec![
+            PackageOverride {
+                path: PathBuf::from("./services/billing/"),
+                name: Some("billing-api".to_string()),
+                exclude: false,
+            },
+            PackageOverride {
+                path: PathBuf::from("jobs/nightly"),
+                name: Some("nightly".to_string()),
+                exclude: false,
+            },
+            PackageOverride {
+                path: PathBuf::from("tools"),
+                name: None,
+                exclude: true,
+            },
+        ];
+        let registry = ParserRegistry::new().unwrap();
+        let packages = detect_packages(&registry, root, &overrides);
+        assert_eq!(
+            summary(&packages),
+            vec![
+                (
+                    "jobs/nightly".to_string(),
+                    WorkspaceLayout::Override,
+                    "nightly".to_string()
+                ),
+                (
+                    "services/billing".to_string(),
+                    WorkspaceLayout::Python,
+                    "billing-api".to_string()
+                ),
+                (
+                    "services/orders".to_string(),
+                    WorkspaceLayout::Python,
+                    "orders".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toml_helpers() {
+        let manifest = "[package]\nname = 'app'\n\n[workspace]\nmembers = [\"a\", 'b/*']\n";
+        assert_eq!(
+            toml_string(toml_section(manifest, "package").unwrap(), "name"),
+            Some("app".to_string())
+        );
+        assert_eq!(
+            toml_string_array(toml_section(manifest, "workspace").unwrap(), "members"),
+            vec!["a".to_string(), "b/*".to_string()]
+        );
+        assert_eq!(toml_section(manifest, "dependencies"), None);
+    }
+}
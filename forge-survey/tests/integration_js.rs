@@ -46,8 +46,8 @@ fn test_js_and_tf_integration() {
     let js_parser = JavaScriptParser::new().unwrap();
     let tf_parser = TerraformParser::new().unwrap();
 
-    let js_discoveries = js_parser.parse_repo(repo_path).unwrap();
-    let tf_discoveries = tf_parser.parse_repo(repo_path).unwrap();
+    let js_discoveries = js_parser.parse_repo(repo_path).unwrap().discoveries;
+    let tf_discoveries = tf_parser.parse_repo(repo_path).unwrap().discoveries;
 
     // Build the graph
     let mut builder = GraphBuilder::new();
@@ -175,7 +175,7 @@ app.listen(3000, () => {
         let service_id = builder.add_service(service);
 
         // Parse repository and process discoveries
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -202,7 +202,7 @@ app.listen(3000, () => {
 
     // Should have detected DynamoDB access (from AWS SDK imports)
     let databases: Vec<_> = graph.nodes_by_type(NodeType::Database).collect();
-    let has_db = databases.len() > 0;
+    let has_db = !databases.is_empty();
 
     // Alternative: Check for database edges
     let has_db_edges = graph
@@ -227,7 +227,7 @@ app.listen(3000, () => {
     // Should have at least one READS edge from service to database
     let read_edges: Vec<_> = graph.edges_by_type(EdgeType::Reads).collect();
     assert!(
-        read_edges.len() > 0,
+        !read_edges.is_empty(),
         "Should have at least one READS edge for DynamoDB GetCommand"
     );
 }
@@ -274,7 +274,7 @@ async function createOrder(orderData) {
 
     if let Some(service) = parser.parse_package_json(&repo_path) {
         let service_id = builder.add_service(service);
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -360,7 +360,7 @@ async function processData(bucket, key) {
 
     if let Some(service) = parser.parse_package_json(&repo_path) {
         let service_id = builder.add_service(service);
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -457,6 +457,55 @@ fn test_survey_typescript_repo_with_framework() {
     );
 }
 
+/// Test that Express routes become Api nodes owned by the service.
+#[test]
+fn test_survey_js_repo_with_routes() {
+    let dir = tempdir().unwrap();
+    let repo_path = dir.path().join("users-api");
+    fs::create_dir_all(repo_path.join("src")).unwrap();
+
+    fs::write(
+        repo_path.join("package.json"),
+        r#"{"name": "users-api", "dependencies": {"express": "^4.18.0"}}"#,
+    )
+    .unwrap();
+    fs::write(
+        repo_path.join("src/server.js"),
+        r#"
+const express = require('express');
+const app = express();
+
+app.get('/users/:id', (req, res) => res.json({ id: req.params.id }));
+app.post('/users', (req, res) => res.status(201).end());
+app.listen(3000);
+"#,
+    )
+    .unwrap();
+
+    let parser = JavaScriptParser::new().unwrap();
+    let mut builder = GraphBuilder::new();
+    let service = parser.parse_package_json(&repo_path).unwrap();
+    let service_id = builder.add_service(service);
+    builder.process_discoveries(
+        parser.parse_repo(&repo_path).unwrap().discoveries,
+        &service_id,
+    );
+    let graph = builder.build();
+
+    let mut routes: Vec<_> = graph
+        .nodes_by_type(NodeType::Api)
+        .map(|api| api.display_name.clone())
+        .collect();
+    routes.sort();
+    assert_eq!(routes, vec!["GET /users/{id}", "POST /users"]);
+
+    for api in graph.nodes_by_type(NodeType::Api) {
+        let owners = graph.edges_to_by_type(&api.id, EdgeType::Owns);
+        assert_eq!(owners.len(), 1);
+        assert_eq!(owners[0].source, service_id);
+    }
+}
+
 /// Test that parser handles empty directories gracefully.
 #[test]
 fn test_survey_empty_js_repo() {
@@ -468,7 +517,7 @@ fn test_survey_empty_js_repo() {
     let builder = GraphBuilder::new();
 
     // Should not panic on empty repo
-    let discoveries = parser.parse_repo(&repo_path).unwrap();
+    let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
     assert_eq!(
         discoveries.len(),
         0,
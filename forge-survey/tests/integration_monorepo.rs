@@ -0,0 +1,179 @@
+//! Integration test for surveying a monorepo with several deployables.
+
+use forge_graph::{EdgeType, NodeType};
+use forge_survey::{PackageOverride, SurveyConfig, survey};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// Inlined from common.rs to avoid module issues in integration tests
+fn write_file(path: &Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+/// Test that each workspace package becomes a service and discoveries are
+/// attributed to the package containing them.
+///
+/// This test verifies:
+/// - npm workspace packages and SAM function code directories become services
+/// - Languages used only inside packages are detected
+/// - Code in a package is attributed to that package's service
+/// - Code outside every package stays with the repository's service
+/// - A configured override renames a package
+#[tokio::test]
+async fn test_survey_monorepo_packages() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().join("platform");
+
+    write_file(
+        &root.join("package.json"),
+        r#"{ "name": "platform", "private": true, "workspaces": ["services/*"] }"#,
+    );
+    write_file(
+        &root.join("scripts/seed.js"),
+        r#"
+const { DynamoDBClient, PutItemCommand } = require('@aws-sdk/client-dynamodb');
+const client = new DynamoDBClient({});
+client.send(new PutItemCommand({ TableName: 'fixtures', Item: {} }));
+"#,
+    );
+
+    // Writes orders
+    write_file(
+        &root.join("services/orders/package.json"),
+        r#"{ "name": "orders", "dependencies": { "@aws-sdk/client-dynamodb": "latest" } }"#,
+    );
+    write_file(
+        &root.join("services/orders/src/handler.js"),
+        r#"
+const { DynamoDBClient, PutItemCommand } = require('@aws-sdk/client-dynamodb');
+const client = new DynamoDBClient({});
+
+async function createOrder(order) {
+    await client.send(new PutItemCommand({ TableName: 'orders', Item: order }));
+}
+
+module.exports = { createOrder };
+"#,
+    );
+
+    // Reads orders; renamed by an override
+    write_file(
+        &root.join("services/billing/package.json"),
+        r#"{ "name": "billing", "dependencies": { "@aws-sdk/client-dynamodb": "latest" } }"#,
+    );
+    write_file(
+        &root.join("services/billing/src/invoice.js"),
+        r#"
+const { DynamoDBClient, GetItemCommand } = require('@aws-sdk/client-dynamodb');
+const client = new DynamoDBClient({});
+
+async function invoice(id) {
+    return client.send(new GetItemCommand({ TableName: 'orders', Key: { id: { S: id } } }));
+}
+
+module.exports = { invoice };
+"#,
+    );
+
+    // A Python Lambda function whose table comes from its SAM environment
+    write_file(
+        &root.join("template.yaml"),
+        r#"
+Transform: AWS::Serverless-2016-10-31
+Resources:
+  Ranker:
+    Type: AWS::Serverless::Function
+    Properties:
+      FunctionName: ranker
+      CodeUri: lambdas/ranker/
+      Runtime: python3.12
+      Handler: app.handler
+      Environment:
+        Variables:
+          RANKINGS_TABLE: rankings
+"#,
+    );
+    for (file, content) in [
+        (
+            "app.py",
+            r#"
+import os
+import boto3
+
+dynamodb = boto3.resource('dynamodb')
+table = dynamodb.Table(os.environ['RANKINGS_TABLE'])
+
+def handler(event, context):
+    return table.get_item(Key={'id': event['id']})
+"#,
+        ),
+        ("model.py", "def score(item): return 1"),
+        ("features.py", "def features(item): return []"),
+    ] {
+        write_file(&root.join("lambdas/ranker").join(file), content);
+    }
+
+    let config = SurveyConfig {
+        sources: vec![root.clone()],
+        packages: vec![PackageOverride {
+            path: PathBuf::from("services/billing"),
+            name: Some("billing-api".to_string()),
+            exclude: false,
+        }],
+        ..Default::default()
+    };
+    let graph = survey(config).await.unwrap();
+
+    let service = |name: &str| {
+        graph
+            .nodes_by_type(NodeType::Service)
+            .find(|s| s.display_name == name)
+            .unwrap_or_else(|| panic!("service {} not found", name))
+            .id
+            .clone()
+    };
+    let database = |name: &str| {
+        graph
+            .nodes_by_type(NodeType::Database)
+            .find(|d| d.display_name == name)
+            .unwrap_or_else(|| panic!("database {} not found", name))
+            .id
+            .clone()
+    };
+    let edge_targets = |service_id, edge_type| -> Vec<_> {
+        graph
+            .edges_from_by_type(&service_id, edge_type)
+            .into_iter()
+            .map(|e| e.target.clone())
+            .collect()
+    };
+
+    assert!(
+        graph
+            .nodes_by_type(NodeType::Service)
+            .all(|s| s.display_name != "billing"),
+        "the override should rename the billing package"
+    );
+
+    assert_eq!(
+        edge_targets(service("orders"), EdgeType::Writes),
+        vec![database("orders")]
+    );
+    assert_eq!(
+        edge_targets(service("billing-api"), EdgeType::Reads),
+        vec![database("orders")]
+    );
+    assert_eq!(
+        edge_targets(service("ranker"), EdgeType::Reads),
+        vec![database("rankings")]
+    );
+    assert_eq!(
+        edge_targets(service("platform"), EdgeType::Writes),
+        vec![database("fixtures")]
+    );
+    assert!(edge_targets(service("platform"), EdgeType::Reads).is_empty());
+}
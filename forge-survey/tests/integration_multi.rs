@@ -187,7 +187,7 @@ output "table_arn" {
     // These should be deduplicated into a single node
     let databases: Vec<_> = graph.nodes_by_type(NodeType::Database).collect();
     assert!(
-        databases.len() >= 1,
+        !databases.is_empty(),
         "Should detect at least one database, found {}",
         databases.len()
     );
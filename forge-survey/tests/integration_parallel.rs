@@ -0,0 +1,129 @@
+//! Integration test for parallel surveys.
+
+use forge_graph::ForgeGraph;
+use forge_survey::{SurveyConfig, survey_with_failures};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// Inlined from common.rs to avoid module issues in integration tests
+fn write_file(path: &Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+/// Nodes and edges in graph order. Attributes are compared as JSON, whose
+/// maps are sorted by key.
+fn snapshot(graph: &ForgeGraph) -> (Vec<String>, Vec<String>) {
+    let nodes = graph
+        .nodes()
+        .map(|node| format!("{} {}", node.id, serde_json::json!(node.attributes)))
+        .collect();
+    let edges = graph
+        .edges()
+        .map(|edge| format!("{} -{:?}-> {}", edge.source, edge.edge_type, edge.target))
+        .collect();
+    (nodes, edges)
+}
+
+/// Test that surveying in parallel produces the same graph as surveying
+/// sequentially, and reports files that failed to parse.
+///
+/// This test verifies:
+/// - Node and edge order don't depend on the number of worker threads
+/// - Repeated parallel surveys produce the same graph
+/// - A file that fails to parse is reported and the rest of its repository
+///   is still surveyed
+#[tokio::test]
+async fn test_parallel_survey_is_deterministic() {
+    let dir = tempdir().unwrap();
+    let mut sources: Vec<PathBuf> = Vec::new();
+
+    for i in 0..6 {
+        let root = dir.path().join(format!("service-{}", i));
+        write_file(
+            &root.join("package.json"),
+            &format!(
+                r#"{{ "name": "service-{}", "dependencies": {{ "@aws-sdk/client-dynamodb": "latest", "axios": "latest" }} }}"#,
+                i
+            ),
+        );
+        for j in 0..4 {
+            write_file(
+                &root.join(format!("src/handler{}.js", j)),
+                &format!(
+                    r#"
+const axios = require('axios');
+const {{ DynamoDBClient, PutItemCommand, GetItemCommand }} = require('@aws-sdk/client-dynamodb');
+const client = new DynamoDBClient({{}});
+
+async function handle{j}(item) {{
+    await client.send(new PutItemCommand({{ TableName: 'table-{i}-{j}', Item: item }}));
+    await client.send(new GetItemCommand({{ TableName: 'shared', Key: item }}));
+    return axios.get('http://service-{next}/items');
+}}
+
+module.exports = {{ handle{j} }};
+"#,
+                    i = i,
+                    j = j,
+                    next = (i + 1) % 6
+                ),
+            );
+        }
+        // Three files, enough for Terraform to be detected
+        write_file(
+            &root.join("infra/main.tf"),
+            &format!(
+                r#"
+resource "aws_sqs_queue" "events" {{
+  name = "events-{}"
+}}
+"#,
+                i
+            ),
+        );
+        write_file(&root.join("infra/variables.tf"), "variable \"region\" {}\n");
+        write_file(&root.join("infra/outputs.tf"), "");
+        sources.push(root);
+    }
+    write_file(
+        &sources[2].join("infra/broken.tf"),
+        "resource \"aws_sqs_queue\" {{{",
+    );
+
+    let run = |jobs: usize| {
+        let sources = sources.clone();
+        async move {
+            survey_with_failures(SurveyConfig {
+                sources,
+                jobs,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+        }
+    };
+
+    let sequential = run(1).await;
+    let parallel = run(4).await;
+    let again = run(4).await;
+
+    assert!(sequential.graph.node_count() > 0);
+    assert_eq!(snapshot(&sequential.graph), snapshot(&parallel.graph));
+    assert_eq!(snapshot(&parallel.graph), snapshot(&again.graph));
+
+    for outcome in [&sequential, &parallel] {
+        let failed: Vec<_> = outcome.failures.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(failed, vec![sources[2].join("infra/broken.tf")]);
+    }
+    assert!(
+        parallel
+            .graph
+            .nodes()
+            .any(|node| node.display_name == "events-2"),
+        "the rest of a repository with a broken file should still be surveyed"
+    );
+}
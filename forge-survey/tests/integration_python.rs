@@ -102,7 +102,7 @@ async def create_user(user_data: dict):
         let service_id = builder.add_service(service);
 
         // Parse repository and process discoveries
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -137,7 +137,7 @@ async def create_user(user_data: dict):
 
     // Should have detected DynamoDB database access
     let databases: Vec<_> = graph.nodes_by_type(NodeType::Database).collect();
-    let has_db = databases.len() > 0;
+    let has_db = !databases.is_empty();
 
     // Alternative: Check for database edges
     let has_db_edges = graph
@@ -205,7 +205,7 @@ def delete_order(order_id):
         let service_id = builder.add_service(service);
 
         // Parse repository and process discoveries
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -319,7 +319,7 @@ def process_data(data_id):
         let service_id = builder.add_service(service);
 
         // Parse repository and process discoveries
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -327,18 +327,18 @@ def process_data(data_id):
 
     // Should detect DynamoDB (Database node)
     let databases: Vec<_> = graph.nodes_by_type(NodeType::Database).collect();
-    assert!(databases.len() > 0, "Should detect DynamoDB database node");
+    assert!(!databases.is_empty(), "Should detect DynamoDB database node");
 
     // Should detect S3 (CloudResource node)
     let cloud_resources: Vec<_> = graph.nodes_by_type(NodeType::CloudResource).collect();
     assert!(
-        cloud_resources.len() > 0,
+        !cloud_resources.is_empty(),
         "Should detect S3 cloud resource node"
     );
 
     // Should detect SQS (Queue node)
     let queues: Vec<_> = graph.nodes_by_type(NodeType::Queue).collect();
-    assert!(queues.len() > 0, "Should detect SQS queue node");
+    assert!(!queues.is_empty(), "Should detect SQS queue node");
 
     // Verify we have a good mix of node types
     assert!(
@@ -417,7 +417,7 @@ if __name__ == '__main__':
         let service_id = builder.add_service(service);
 
         // Parse repository and process discoveries
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 
@@ -462,7 +462,7 @@ fn test_survey_empty_python_repo() {
     // Should succeed even with no Python files
     assert!(result.is_ok(), "Should handle empty repo gracefully");
 
-    let discoveries = result.unwrap();
+    let discoveries = result.unwrap().discoveries;
     // No source files means no discoveries (except possibly service config)
     assert!(
         discoveries.is_empty() || discoveries.len() < 5,
@@ -504,7 +504,7 @@ django = ">=4.2.0"
         let service_id = builder.add_service(service);
 
         // Parse repository (should handle missing source gracefully)
-        let discoveries = parser.parse_repo(&repo_path).unwrap();
+        let discoveries = parser.parse_repo(&repo_path).unwrap().discoveries;
         builder.process_discoveries(discoveries, &service_id);
     }
 